@@ -0,0 +1,97 @@
+// Parsing and hierarchy-building benchmarks
+//
+// Run with `cargo bench --bench parse`. Criterion keeps the results of the
+// previous run under target/criterion and reports the change against them, so
+// runs can be compared before and after a change.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use org_x::orgmode::{parse_org_document, OrgDocumentRepository};
+use std::path::Path;
+
+// Many top-level tasks with planning lines and property drawers
+fn flat_tasks(count: usize) -> String {
+    let mut content = String::from("#+TITLE: Flat tasks\n#+FILETAGS: :bench:\n\n");
+    for i in 0..count {
+        content.push_str(&format!(
+            "* TODO [#B] Task number {} :work:\nSCHEDULED: <2024-03-{:02} Mon>\n:PROPERTIES:\n:EFFORT: 0:30\n:CATEGORY: bench\n:END:\nSome notes about task {}.\n\n",
+            i,
+            i % 28 + 1,
+            i
+        ));
+    }
+    content
+}
+
+// A deep outline that exercises hierarchy building
+fn deep_outline(depth: usize, breadth: usize) -> String {
+    fn push_level(content: &mut String, level: usize, depth: usize, breadth: usize) {
+        if level > depth {
+            return;
+        }
+        for i in 0..breadth {
+            content.push_str(&format!(
+                "{} Section {}.{}\nBody text for level {}.\n",
+                "*".repeat(level),
+                level,
+                i,
+                level
+            ));
+            push_level(content, level + 1, depth, breadth);
+        }
+    }
+
+    let mut content = String::from("#+TITLE: Deep outline\n\n");
+    push_level(&mut content, 1, depth, breadth);
+    content
+}
+
+// Notes with long bodies, lists, and links
+fn long_notes(count: usize) -> String {
+    let paragraph =
+        "Lorem ipsum dolor sit amet, [[https://orgmode.org][org]] consectetur adipiscing elit.\n";
+    let mut content = String::from("#+TITLE: Long notes\n\n");
+    for i in 0..count {
+        content.push_str(&format!("* Note {}\n", i));
+        for _ in 0..20 {
+            content.push_str(paragraph);
+        }
+        content.push_str("- [ ] first item\n- [X] second item\n\n");
+    }
+    content
+}
+
+fn corpora() -> [(&'static str, String); 3] {
+    [
+        ("flat_tasks/500", flat_tasks(500)),
+        ("deep_outline/6x3", deep_outline(6, 3)),
+        ("long_notes/200", long_notes(200)),
+    ]
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, content) in &corpora() {
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), content, |b, content| {
+            b.iter(|| parse_org_document(black_box(content), Some("bench.org")).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn bench_repository(c: &mut Criterion) {
+    let corpora = corpora();
+    c.bench_function("repository/parse_content x3", |b| {
+        b.iter(|| {
+            let mut repository = OrgDocumentRepository::new();
+            for (name, content) in &corpora {
+                let path = format!("/bench/{}.org", name.replace('/', "_"));
+                repository.parse_content(Path::new(&path), content).unwrap();
+            }
+            repository.perf_stats()
+        });
+    });
+}
+
+criterion_group!(benches, bench_parse, bench_repository);
+criterion_main!(benches);
@@ -2,26 +2,174 @@
 // This file will contain the API functions that can be called from the frontend
 // and will be exported using tauri-specta
 
+use crate::jobs::{JobInfo, JobKind, JobRegistry, JOB_PROGRESS_EVENT};
+use crate::orgmode::document::is_archive_path;
+use crate::orgmode::drill::{card_for_headline, write_review, DrillState};
+use crate::orgmode::file_keywords::set_file_keyword;
+use crate::orgmode::images::{
+    image_mime_type, is_image_allowed, percent_decode, thumbnail, MAX_THUMBNAIL_SIZE,
+};
+use crate::orgmode::journal::{ensure_entry, find_entry_line};
+use crate::orgmode::recent::{load_recent, record_access, save_recent, RECENT_FILE_NAME};
+use crate::orgmode::reminders::{
+    load_reminders, parse_remind_at, save_reminders, take_due, upcoming, REMINDERS_FILE_NAME,
+};
+use crate::orgmode::repository::parse_into;
+use crate::orgmode::snapshot::{
+    create_snapshot as save_snapshot, delete_snapshot as remove_snapshot,
+    list_snapshots as load_snapshots, snapshot_files, Snapshot, SNAPSHOT_DIR_NAME,
+};
+use crate::orgmode::source::{flatten_headlines, headline_line_numbers};
+use crate::orgmode::subtree::{insert_subtree, subtree_lines, take_lines};
+use crate::orgmode::time_zone::{self, parse_time_zone, set_assumed_time_zone};
+use crate::orgmode::trash::{load_trash, save_trash, TrashEntry, TRASH_FILE_NAME};
 use crate::orgmode::{
-    parse_org_document_with_settings, parse_sample_org, FileMonitor, OrgDocument,
-    OrgDocumentRepository, StateType, TodoStatus,
+    action_item_entries, activity_heatmap, add_checklist_item as insert_checklist_item,
+    append_to_archive, apply_section_action, apply_todo_keyword, archive_path, archive_properties,
+    bookmark_entry, cache_issues, cached_issues, carry_overdue,
+    check_links as check_document_links, collect_agenda, collect_cited_keys,
+    collect_completed_tasks, collect_contacts, collect_due_cards, collect_issue_links,
+    collect_next_actions, collect_overdue, collect_projects, collect_tangle_targets,
+    collect_tickler, collect_urgent_tasks, column_values, conflict_original, deferred_value,
+    diff_headlines, document_citations, document_timeline, effort_presets,
+    export_agenda as render_agenda, extract_action_items as find_action_items, fetch_issues,
+    file_entry, file_highlights, find_category_occurrences, find_duplicates, find_image_links,
+    find_in_document, find_keyword_occurrences, find_latex_spans, find_source_blocks,
+    format_document, group_tasks, headline_children, is_contact_link, is_valid_effort,
+    is_valid_property_key, is_valid_tag, keyword_color, keyword_style, load_issues,
+    matches_filters, meeting_link, merge_conflict, merge_edits, merge_edits_preferring,
+    merge_headlines as merge_sibling_headlines, month_calendar, narrow_to_subtree,
+    parse_highlights, parse_org_document_with_settings, parse_paragraphs, parse_sample_org,
+    parse_text_runs, plan_date_shifts, property_columns, quick_find as find_titles, read_bundle,
+    reconcile_into, remove_checklist_item as delete_checklist_item, remove_headline_property,
+    render_content_html, render_digest, replace_headline_keyword, retain_unignored, retry_delay,
+    rewrite_category, rewrite_tags, save_issues, set_headline_properties, set_planning_date,
+    set_style_overrides, shared_page, shift_planning_date, sort_by_property,
+    sort_children as sort_headline_children, spawn_hook, split_date_terms,
+    split_headline as split_headline_at, suggest_schedule, sum_values,
+    sync_github_issues as sync_issue_file, task_urgency, uses_category, verify_documents,
+    write_bundle, ActionItem, ActivityHeatmap, AgendaFormat, AppStateBundle, AppStateSummary,
+    Bookmark, BookmarkConfig, BookmarkServer, BrokenLink, CalendarMonth, CaptureTarget, Card,
+    CardKind, CategoryOccurrence, ChangeFeed, CitedKey, CompletedDay, ConflictResolution, Contact,
+    CycleDirection, DateDelta, DateLocale, DateRange, DateShift, DateTerm, DependencyGraph,
+    DigestConfig, DigestPeriod, DocumentCitations, DocumentStats, DocumentSummary, DriftKind,
+    DuplicateEntry, EntryPlacement, Favorite, FavoriteHeadline, FileMonitor, FileReplacePreview,
+    FormatConventions, GitHubSyncConfig, GitHubSyncSummary, GroupingMode, HeadlineAction,
+    HeadlineChange, HeadlineDiff, HeadlineGroup, HeadlineMatches, HeadlinePage, HeadlineRef,
+    HighlightImportSummary, HookConfig, HookEvent, HtmlSanitizeConfig, IgnoreView, IgnoredTags,
+    ImageLink, IssueConfig, IssueInfo, JournalConfig, JournalEntry, KeywordOccurrence, LatexSpan,
+    MemoryStats, MergeOutcome, MergeSide, OrgDocument, OrgDocumentRepository, OrgHeadline,
+    ParseDiagnostic, ParsedFile, PerfStats, PlannerConfig, PlanningField, PluginDocument,
+    PluginHeadline, PluginHost, PluginInfo, Project, ProjectStatus, PropertyFilter, PropertyType,
+    PropertyValue, QuickFindResult, RecentAccess, RecentItem, ReconcileSummary, Reminder,
+    ReplaceQuery, ReplaceSummary, RichText, ScheduleSuggestion, SearchHit, SearchResults,
+    ShareServer, ShareSession, ShiftFilter, SortKey, SortRule, SourceBlock, SplitMode, StateType,
+    StatusStyles, StoredDocument, SyncConflict, TagColor, TagRewriteSummary, TangleTarget,
+    TicklerItem, TimelineDay, TodoConfiguration, TodoStatus, UrgencyWeights, UrgentTask,
+    VerificationReport, WorkSchedule, WriteConflict, APP_STATE_VERSION, DEFERRED_PROPERTY,
+    DIGEST_READY_EVENT, EFFORT_PROPERTY, ISSUES_FILE_NAME, ISSUES_UPDATED_EVENT, MAX_AGENDA_DAYS,
+    PLUGIN_DIR_NAME, REMINDER_DUE_EVENT, SEARCH_RESULTS_EVENT, WRITE_CONFLICT_EVENT,
+};
+use crate::settings::{
+    FileTemplate, MonitoredPath, PathType, SettingsManager, TodoKeywords, UserSettings,
 };
-use crate::settings::{MonitoredPath, PathType, SettingsManager, TodoKeywords, UserSettings};
 #[cfg(debug_assertions)]
 use crate::test_datetime;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
 
 // Global monitor instance accessible via thread-safe lazy initialization
 static FILE_MONITOR: Lazy<Mutex<Option<FileMonitor>>> = Lazy::new(|| Mutex::new(None));
 
+// Whether the startup scan has loaded every monitored file; until then a missing
+// document may just not be loaded yet
+static INITIAL_SCAN_COMPLETE: AtomicBool = AtomicBool::new(false);
+
+// Running long jobs (scans, index rebuilds, searches) that can be cancelled
+static JOBS: Lazy<JobRegistry> = Lazy::new(JobRegistry::new);
+
+// Serializes updates to the persisted recent items list
+static RECENT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// Serializes updates to the reminder store
+static REMINDERS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// How often the reminder scheduler looks for due reminders
+const REMINDER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+// The running read-only share server, if any
+static SHARE_SERVER: Lazy<Mutex<Option<ShareServer>>> = Lazy::new(|| Mutex::new(None));
+
+// The running bookmark endpoint, if it is turned on
+// An async lock, so restarts wait for the previous endpoint to free its port
+static BOOKMARK_SERVER: Lazy<tokio::sync::Mutex<Option<BookmarkServer>>> =
+    Lazy::new(|| tokio::sync::Mutex::new(None));
+
+// Loaded WebAssembly plugins, swapped out whole when they are reloaded
+static PLUGINS: Lazy<Mutex<Arc<PluginHost>>> =
+    Lazy::new(|| Mutex::new(Arc::new(PluginHost::new())));
+
+// How often the digest scheduler checks whether the morning digest is due
+const DIGEST_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+// Day the morning digest was last emitted, so it goes out once a day
+static LAST_DIGEST_DATE: Lazy<Mutex<Option<NaiveDate>>> = Lazy::new(|| Mutex::new(None));
+
+// How often the issue refresher checks whether a refresh is due
+const ISSUE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+// An edit that conflicted with changes on disk, kept until the conflicts are resolved or
+// discarded
+#[derive(Clone)]
+struct PendingMerge {
+    base: String,  // The content the edit was made to
+    local: String, // The edited content
+    disk: String,  // The file the conflicts were found against
+    keywords: (Vec<String>, Vec<String>),
+    conflict: WriteConflict,
+}
+
+// Conflicting edits by document id
+static PENDING_MERGES: Lazy<Mutex<HashMap<String, PendingMerge>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 // Global settings manager instance
 static SETTINGS_MANAGER: Lazy<SettingsManager> = Lazy::new(|| SettingsManager::new());
 
+/// Run a closure against the monitored repository
+/// Returns None when file monitoring has not been started
+fn with_repository<T>(
+    f: impl FnOnce(&mut OrgDocumentRepository) -> T,
+) -> Result<Option<T>, String> {
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+
+    if let Some(monitor) = monitor_lock.as_ref() {
+        let repository = monitor.get_repository();
+        let mut repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+
+        Ok(Some(f(&mut repository_lock)))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Helper function to scan directory for org files
-fn scan_directory_for_org_files(dir_path: &str, recursive: bool) -> Result<Vec<String>, String> {
+fn scan_directory_for_org_files(
+    dir_path: &str,
+    recursive: bool,
+    include_archives: bool,
+) -> Result<Vec<String>, String> {
     let mut org_files = Vec::new();
     let path = Path::new(dir_path);
 
@@ -33,7 +181,7 @@ fn scan_directory_for_org_files(dir_path: &str, recursive: bool) -> Result<Vec<S
         return Err(format!("Path is not a directory: {}", dir_path));
     }
 
-    scan_directory_recursive(path, recursive, &mut org_files)?;
+    scan_directory_recursive(path, recursive, include_archives, &mut org_files)?;
     Ok(org_files)
 }
 
@@ -41,6 +189,7 @@ fn scan_directory_for_org_files(dir_path: &str, recursive: bool) -> Result<Vec<S
 fn scan_directory_recursive(
     dir_path: &Path,
     recursive: bool,
+    include_archives: bool,
     org_files: &mut Vec<String>,
 ) -> Result<(), String> {
     let entries = fs::read_dir(dir_path)
@@ -52,9 +201,9 @@ fn scan_directory_recursive(
         let path = entry.path();
 
         if path.is_file() {
-            // Check if it's an org file
+            // Check if it's an org file (or an archive file when enabled)
             if let Some(extension) = path.extension() {
-                if extension == "org" {
+                if extension == "org" || (include_archives && is_archive_path(&path)) {
                     // Skip hidden files
                     if let Some(file_name) = path.file_name() {
                         if let Some(file_name_str) = file_name.to_str() {
@@ -72,7 +221,7 @@ fn scan_directory_recursive(
             if let Some(dir_name) = path.file_name() {
                 if let Some(dir_name_str) = dir_name.to_str() {
                     if !dir_name_str.starts_with('.') {
-                        scan_directory_recursive(&path, recursive, org_files)?;
+                        scan_directory_recursive(&path, recursive, include_archives, org_files)?;
                     }
                 }
             }
@@ -114,6 +263,8 @@ pub fn run_datetime_test() -> String {
 #[tauri::command]
 #[specta::specta]
 pub async fn start_file_monitoring(app_handle: tauri::AppHandle) -> Result<String, String> {
+    INITIAL_SCAN_COMPLETE.store(false, Ordering::SeqCst);
+
     // Load user settings
     let settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
@@ -140,6 +291,7 @@ pub async fn start_file_monitoring(app_handle: tauri::AppHandle) -> Result<Strin
         // If monitor exists, update its app_handle
         if let Some(monitor) = monitor_lock.as_mut() {
             monitor.set_app_handle(app_handle.clone());
+            monitor.set_include_archives(settings.parse_archive_files);
         }
 
         if let Some(monitor) = monitor_lock.as_mut() {
@@ -169,7 +321,11 @@ pub async fn start_file_monitoring(app_handle: tauri::AppHandle) -> Result<Strin
             }
             PathType::Directory => {
                 // Scan directory for org files (always recursive now)
-                match scan_directory_for_org_files(&monitored_path.path, true) {
+                match scan_directory_for_org_files(
+                    &monitored_path.path,
+                    true,
+                    settings.parse_archive_files,
+                ) {
                     Ok(org_files) => {
                         all_file_paths.extend(org_files);
                     }
@@ -203,22 +359,47 @@ pub async fn start_file_monitoring(app_handle: tauri::AppHandle) -> Result<Strin
         user_todo_keywords.0, user_todo_keywords.1
     );
 
-    // Now parse all files one by one using user TODO keywords
-    for file_path in all_file_paths {
-        let mut repo_lock = repository
-            .lock()
-            .map_err(|e| format!("Failed to lock repository: {}", e))?;
-        match repo_lock
-            .parse_file_with_keywords(std::path::Path::new(&file_path), user_todo_keywords.clone())
-        {
-            Ok(doc_id) => println!("Successfully parsed file: {} -> {}", file_path, doc_id),
-            Err(e) => {
-                eprintln!("Failed to parse file {}: {}", file_path, e)
-            }
+    // Reconcile files one by one using user TODO keywords: documents already in the
+    // repository with unchanged content are reused, everything else is (re)parsed
+    // Each parsed document is indexed for search as it is added
+    // The scan is a job: cancelling it skips the remaining files but still starts monitoring
+    let (job_id, token) = JOBS.start(JobKind::Scan);
+    let mut summary = ReconcileSummary::default();
+    let total = all_file_paths.len() as u32;
+    let mut scanned = 0;
+    for (index, file_path) in all_file_paths.into_iter().enumerate() {
+        if token.is_cancelled() {
+            break;
         }
-        drop(repo_lock);
+        // Read and parse without the repository lock so it stays available for other commands
+        let path = std::path::Path::new(&file_path);
+        let outcome = match tokio::fs::read_to_string(path).await {
+            Ok(content) => reconcile_into(&repository, path, &content, user_todo_keywords.clone()),
+            Err(e) => Err(format!("Failed to read file {}: {}", file_path, e)),
+        };
+        if let Err(e) = &outcome {
+            eprintln!("Failed to parse file {}: {}", file_path, e)
+        }
+        summary.record(outcome);
+        scanned = index as u32 + 1;
+        emit_job(&app_handle, JOBS.set_progress(job_id, scanned, total));
     }
 
+    // Documents whose files were deleted while not monitoring
+    summary.missing = repository
+        .lock()
+        .map_err(|e| format!("Failed to lock repository: {}", e))?
+        .mark_deleted_files_missing()
+        .len() as u32;
+
+    let scan_result = format!(
+        "{} reused, {} reparsed, {} missing, {} failed",
+        summary.reused, summary.reparsed, summary.missing, summary.failed
+    );
+    println!("Startup reconciliation: {}", scan_result);
+    emit_job(&app_handle, JOBS.complete(job_id, Some(scan_result)));
+    INITIAL_SCAN_COMPLETE.store(scanned == total, Ordering::SeqCst);
+
     // Start monitoring (need to re-acquire monitor lock)
     {
         let mut monitor_lock = FILE_MONITOR
@@ -231,10 +412,17 @@ pub async fn start_file_monitoring(app_handle: tauri::AppHandle) -> Result<Strin
     }
 
     let monitored_count = settings.get_parse_enabled_paths().len();
-    Ok(format!(
-        "File monitoring started with {} monitored paths from settings",
-        monitored_count
-    ))
+    let mut message = format!(
+        "File monitoring started with {} monitored paths from settings ({} documents reused, {} reparsed)",
+        monitored_count, summary.reused, summary.reparsed
+    );
+    if scanned < total {
+        message.push_str(&format!(
+            "; initial scan cancelled after {} of {} files",
+            scanned, total
+        ));
+    }
+    Ok(message)
 }
 
 /// Stop file monitoring
@@ -246,6 +434,7 @@ pub async fn stop_file_monitoring() -> Result<String, String> {
         .lock()
         .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
 
+    INITIAL_SCAN_COMPLETE.store(false, Ordering::SeqCst);
     if let Some(monitor) = monitor_lock.as_mut() {
         monitor.stop_monitoring();
         Ok("File monitoring stopped".to_string())
@@ -282,28 +471,386 @@ pub async fn get_all_documents() -> Result<Vec<OrgDocument>, String> {
 }
 
 /// Get document by ID
+/// Opening a document reloads its body content if it was evicted, and evicts
+/// the least recently opened documents when a limit is configured
 #[tauri::command]
 #[specta::specta]
-pub async fn get_org_document_by_id(document_id: String) -> Result<Option<OrgDocument>, String> {
-    // Get a lock on the monitor
-    let monitor_lock = FILE_MONITOR
+pub async fn get_org_document_by_id(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<Option<OrgDocument>, String> {
+    open_document(&app_handle, &document_id).await?;
+    track_recent(&app_handle, &document_id, None, RecentAccess::Opened);
+    let document = with_repository(|repository| repository.get(&document_id).cloned())?;
+    Ok(document.flatten())
+}
+
+/// Get a headline's subtree as a virtual document, like narrowing in Emacs, for views
+/// and exports of part of a file
+/// The subtree is promoted to level 1 and inherits the headline's tags and category. Its
+/// headlines keep their ids, so edits to them apply to the real document.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_subtree_as_document(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+) -> Result<Option<OrgDocument>, String> {
+    open_document(&app_handle, &document_id).await?;
+    let document = with_repository(|repository| {
+        repository
+            .get(&document_id)
+            .and_then(|document| narrow_to_subtree(document, &headline_id))
+    })?;
+    Ok(document.flatten())
+}
+
+/// Get a document's metadata and headline counts without its content or outline
+/// Use `get_headline_children` to load the outline level by level
+#[tauri::command]
+#[specta::specta]
+pub async fn get_document_summary(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<Option<DocumentSummary>, String> {
+    open_document(&app_handle, &document_id).await?;
+    track_recent(&app_handle, &document_id, None, RecentAccess::Opened);
+    let summary = with_repository(|repository| {
+        repository
+            .get(&document_id)
+            .map(DocumentSummary::from_document)
+    })?;
+    Ok(summary.flatten())
+}
+
+/// Get word and character counts and reading time for a document and each of its subtrees
+#[tauri::command]
+#[specta::specta]
+pub async fn get_document_stats(document_id: String) -> Result<Option<DocumentStats>, String> {
+    let stats = with_repository(|repository| repository.document_stats(&document_id).cloned())?;
+    Ok(stats.flatten())
+}
+
+/// Get a document's scheduled, deadline, closed and timestamped entries grouped by day,
+/// oldest first, for a per-project timeline
+/// Days are marked as working days or holidays from the work schedule
+#[tauri::command]
+#[specta::specta]
+pub async fn get_document_timeline(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<Option<Vec<TimelineDay>>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let schedule = settings.work_schedule.with_feed_holidays();
+
+    open_document(&app_handle, &document_id).await?;
+    let timeline = with_repository(|repository| {
+        repository
+            .get(&document_id)
+            .map(|document| document_timeline(document, &schedule))
+    })?;
+    Ok(timeline.flatten())
+}
+
+/// Lay out a month as calendar weeks starting on the configured first day of the week,
+/// with day names in the configured language
+#[tauri::command]
+#[specta::specta]
+pub async fn get_calendar_month(
+    app_handle: tauri::AppHandle,
+    year: i32,
+    month: u32,
+) -> Result<CalendarMonth, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    month_calendar(year, month, &settings.date_locale)
+        .ok_or_else(|| format!("Invalid month: {}-{}", year, month))
+}
+
+/// Get a page of a headline's children, or of the top-level headlines if `parent_id` is null
+/// Children are returned without content or nested headlines, with the values of the
+/// visible `property:` table columns. They are ordered by `sort`, or by the default sort
+/// from settings if it is null; an empty order keeps file order.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_headline_children(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    parent_id: Option<String>,
+    offset: u32,
+    limit: u32,
+    sort: Option<Vec<SortRule>>,
+) -> Result<Option<HeadlinePage>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let columns = property_columns(
+        settings
+            .table_columns
+            .iter()
+            .filter(|column| column.visible)
+            .map(|column| column.id.as_str()),
+    );
+    let sort = sort.unwrap_or_else(|| settings.default_sort.clone());
+    let page = with_repository(|repository| {
+        repository.get(&document_id).and_then(|document| {
+            headline_children(
+                document,
+                parent_id.as_deref(),
+                offset as usize,
+                limit as usize,
+                &columns,
+                &sort,
+                |property| settings.get_custom_property_type(property),
+                &settings.todo_keywords.to_todo_configuration(),
+            )
+        })
+    })?;
+    Ok(page.flatten())
+}
+
+/// Get the values of the visible `property:` table columns for each headline, in the order
+/// given
+/// Values are inherited the way the outline's are; missing headlines get no values.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_column_values(
+    app_handle: tauri::AppHandle,
+    headlines: Vec<HeadlineRef>,
+) -> Result<Vec<BTreeMap<String, String>>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let columns = property_columns(
+        settings
+            .table_columns
+            .iter()
+            .filter(|column| column.visible)
+            .map(|column| column.id.as_str()),
+    );
+    let values = with_repository(|repository| {
+        headlines
+            .iter()
+            .map(|headline| {
+                repository
+                    .get(&headline.document_id)
+                    .and_then(|document| {
+                        let found = document.find_headline(&headline.headline_id)?;
+                        Some(column_values(document, found, &columns))
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    })?;
+    Ok(values.unwrap_or_else(|| vec![BTreeMap::new(); headlines.len()]))
+}
+
+/// Get the body text of a single headline
+#[tauri::command]
+#[specta::specta]
+pub async fn get_headline_content(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+) -> Result<Option<String>, String> {
+    open_document(&app_handle, &document_id).await?;
+    track_recent(
+        &app_handle,
+        &document_id,
+        Some(&headline_id),
+        RecentAccess::Opened,
+    );
+    let content = with_repository(|repository| {
+        repository
+            .get(&document_id)
+            .and_then(|document| document.find_headline(&headline_id))
+            .map(|headline| headline.content.clone())
+    })?;
+    Ok(content.flatten())
+}
+
+// Mark a document as opened, reloading its body content if it was evicted
+// and evicting the least recently opened documents when a limit is configured
+async fn open_document(app_handle: &tauri::AppHandle, document_id: &str) -> Result<(), String> {
+    // Read evicted content from disk before taking the repository lock
+    let evicted_path = with_repository(|repository| {
+        repository
+            .is_evicted(document_id)
+            .then(|| repository.get_path_by_id(document_id))
+            .flatten()
+    })?
+    .flatten();
+    let content = match evicted_path {
+        Some(path) => Some(
+            tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|e| format!("Failed to read file {}: {}", path, e))?,
+        ),
+        None => None,
+    };
+
+    let max_loaded = SETTINGS_MANAGER
+        .load_settings(app_handle)
+        .await
+        .map(|settings| settings.max_loaded_documents)
+        .unwrap_or(None);
+
+    with_repository(|repository| {
+        if let Some(content) = &content {
+            repository.rehydrate_content(document_id, content)?;
+        }
+        repository.mark_opened(document_id);
+        if let Some(limit) = max_loaded {
+            repository.evict_least_recent(limit as usize);
+        }
+        Ok::<_, String>(())
+    })?
+    .transpose()?;
+    Ok(())
+}
+
+// Location of the recent items file in the app data directory
+fn recent_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(RECENT_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+// Record an access to a document or headline in the recent items list
+fn update_recent(
+    app_handle: &tauri::AppHandle,
+    document_id: &str,
+    headline_id: Option<&str>,
+    access: RecentAccess,
+) -> Result<(), String> {
+    // Headlines are also kept by :ID: and outline path, which survive edits above them
+    let (title, target) = with_repository(|repository| match headline_id {
+        Some(headline_id) => repository
+            .get(document_id)
+            .and_then(|document| Favorite::for_headline(document, headline_id))
+            .and_then(|target| Some((target.outline_path.last()?.clone(), Some(target)))),
+        None => repository
+            .get_title_by_id(document_id)
+            .map(|title| (title, None)),
+    })?
+    .flatten()
+    .unwrap_or_else(|| {
+        let title = Path::new(document_id)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("Untitled")
+            .to_string();
+        (title, None)
+    });
+
+    let path = recent_path(app_handle)?;
+    let _guard = RECENT_LOCK
         .lock()
-        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+        .map_err(|e| format!("Failed to lock recent items: {}", e))?;
+    let mut items = load_recent(&path)?;
+    let (org_id, outline_path) = target
+        .map(|target| (target.org_id, target.outline_path))
+        .unwrap_or_default();
+    record_access(
+        &mut items,
+        RecentItem {
+            document_id: document_id.to_string(),
+            headline_id: headline_id.map(str::to_string),
+            title,
+            access,
+            access_count: 1,
+            accessed_at: time_zone::now_rfc3339(),
+            org_id,
+            outline_path,
+        },
+    );
+    save_recent(&path, &items)
+}
 
-    if let Some(monitor) = monitor_lock.as_ref() {
-        // Access the repository from the monitor
-        let repository = monitor.get_repository();
-        let repository_lock = repository
-            .lock()
-            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+// Tracking recent items never fails the command that triggered it
+fn track_recent(
+    app_handle: &tauri::AppHandle,
+    document_id: &str,
+    headline_id: Option<&str>,
+    access: RecentAccess,
+) {
+    if let Err(e) = update_recent(app_handle, document_id, headline_id, access) {
+        eprintln!("Failed to update recent items: {}", e);
+    }
+}
 
-        // Get document by ID
-        Ok(repository_lock.get(&document_id).cloned())
-    } else {
-        Ok(None)
+// Record each file rewritten by a bulk edit as edited
+fn track_rewrites(app_handle: &tauri::AppHandle, rewrites: &[(String, String)]) {
+    for (file_path, _) in rewrites {
+        track_recent(app_handle, file_path, None, RecentAccess::Edited);
     }
 }
 
+/// Get recently opened or edited documents and headlines, most recent first
+/// Renamed documents are reported under their current ID and moved headlines under their
+/// current one. Forgotten documents and deleted headlines are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_recent_items(
+    app_handle: tauri::AppHandle,
+    limit: u32,
+) -> Result<Vec<RecentItem>, String> {
+    let items = {
+        let _guard = RECENT_LOCK
+            .lock()
+            .map_err(|e| format!("Failed to lock recent items: {}", e))?;
+        load_recent(&recent_path(&app_handle)?)?
+    };
+
+    let resolved = with_repository(|repository| {
+        items
+            .iter()
+            .filter_map(|item| {
+                let document = repository
+                    .resolve_id(&item.document_id)
+                    .and_then(|document_id| repository.get(&document_id))?;
+                let mut item = RecentItem {
+                    document_id: document.id.clone(),
+                    ..item.clone()
+                };
+                item.relocate(document).then_some(item)
+            })
+            .collect::<Vec<_>>()
+    })?;
+
+    // Without a loaded repository the stored items are returned as they are
+    let mut items = resolved.unwrap_or(items);
+    items.truncate(limit as usize);
+    Ok(items)
+}
+
+/// Get the etag of every headline in a document, keyed by headline ID
+/// Lets the frontend re-render only the rows whose etag changed
+#[tauri::command]
+#[specta::specta]
+pub async fn get_document_etags(
+    document_id: String,
+) -> Result<Option<HashMap<String, String>>, String> {
+    let etags = with_repository(|repository| {
+        repository.get(&document_id).map(|document| {
+            flatten_headlines(&document.headlines)
+                .into_iter()
+                .map(|headline| (headline.id.clone(), headline.etag.clone()))
+                .collect()
+        })
+    })?;
+    Ok(etags.flatten())
+}
+
 /// Get document display title by ID
 #[tauri::command]
 #[specta::specta]
@@ -358,234 +905,762 @@ pub async fn get_org_document_path_by_id(document_id: String) -> Result<String,
     }
 }
 
-/// Load user settings
+/// Resolve a document ID that may have changed because its file was renamed
 #[tauri::command]
 #[specta::specta]
-pub async fn load_user_settings(app_handle: tauri::AppHandle) -> Result<UserSettings, String> {
-    SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn resolve_document_id(document_id: String) -> Result<Option<String>, String> {
+    let resolved = with_repository(|repository| repository.resolve_id(&document_id))?;
+    Ok(resolved.flatten())
 }
 
-/// Get the external editor command from user settings
+/// Drop a document whose file has gone missing from the repository
+/// Returns false if the document is unknown or its file still exists
 #[tauri::command]
 #[specta::specta]
-pub async fn get_external_editor_command(app_handle: tauri::AppHandle) -> Result<String, String> {
-    let settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(settings.external_editor_command)
+pub async fn forget_missing_document(document_id: String) -> Result<bool, String> {
+    let forgotten = with_repository(|repository| repository.forget_missing(&document_id))?;
+    Ok(forgotten.flatten().is_some())
 }
 
-/// Set the external editor command in user settings
+/// Compare stored documents with the files on disk, e.g. after the machine slept
+/// With `repair`, changed files are reparsed and vanished files marked missing
 #[tauri::command]
 #[specta::specta]
-pub async fn set_external_editor_command(
-    app_handle: tauri::AppHandle,
-    command: String,
-) -> Result<(), String> {
-    let mut settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())?;
-    settings.external_editor_command = command;
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &settings)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn verify_repository(repair: bool) -> Result<VerificationReport, String> {
+    // Files are read without the locks, so verification does not stall the watcher
+    let stored = with_repository(|repository| {
+        repository
+            .list()
+            .into_iter()
+            .map(StoredDocument::from)
+            .collect::<Vec<_>>()
+    })?;
+    let Some(stored) = stored else {
+        return Ok(VerificationReport::default());
+    };
+    let mut report = verify_documents(stored);
+    if !repair {
+        return Ok(report);
+    }
+
+    // Files are reparsed without the monitor lock too, as the watcher does
+    let monitor = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?
+        .as_ref()
+        .map(|monitor| (monitor.get_repository(), monitor.app_handle()));
+    let Some((repository, app_handle)) = monitor else {
+        return Ok(report);
+    };
+    let todo_keywords = match app_handle.as_ref() {
+        Some(handle) => Some(FileMonitor::load_user_todo_keywords(handle).await),
+        None => None,
+    };
+
+    for drifted in &mut report.drifted {
+        drifted.repaired = match drifted.kind {
+            DriftKind::Changed | DriftKind::Reappeared => {
+                let path = Path::new(&drifted.file_path);
+                let reparsed = match tokio::fs::read_to_string(path).await {
+                    Ok(content) => parse_into(&repository, path, &content, todo_keywords.clone()),
+                    Err(e) => Err(format!("Failed to read file {}: {}", drifted.file_path, e)),
+                };
+                match reparsed {
+                    Ok(_) => true,
+                    Err(e) => {
+                        eprintln!("Failed to reparse {}: {}", drifted.file_path, e);
+                        false
+                    }
+                }
+            }
+            DriftKind::Missing => repository
+                .lock()
+                .map_err(|e| format!("Failed to lock repository: {}", e))?
+                .mark_missing(&drifted.document_id),
+            DriftKind::Unreadable => false,
+        };
+    }
+
+    Ok(report)
 }
 
-/// Reset the external editor command to default in user settings
+// Read a file, treating a missing one as empty
+fn read_or_empty(path: &str) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read file {}: {}", path, e)),
+    }
+}
+
+/// List the conflict copies Dropbox and Syncthing left among the monitored files, each with
+/// a headline diff against the file it conflicts with
 #[tauri::command]
 #[specta::specta]
-pub async fn reset_external_editor_command(app_handle: tauri::AppHandle) -> Result<(), String> {
-    let mut settings = SETTINGS_MANAGER
+pub async fn get_sync_conflicts(app_handle: tauri::AppHandle) -> Result<Vec<SyncConflict>, String> {
+    let keywords = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
-        .map_err(|e| e.to_string())?;
-    settings.external_editor_command = UserSettings::default().external_editor_command;
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &settings)
-        .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?
+        .todo_keywords
+        .to_todo_configuration()
+        .keyword_lists();
+    let mut paths: Vec<(String, PathBuf)> = with_repository(|repository| {
+        repository
+            .list()
+            .into_iter()
+            .filter(|document| !document.missing)
+            .filter_map(|document| {
+                let original = conflict_original(Path::new(&document.file_path))?;
+                Some((document.file_path.clone(), original))
+            })
+            .collect()
+    })?
+    .unwrap_or_default();
+    paths.sort();
+
+    let mut conflicts = Vec::new();
+    for (conflict_path, original) in paths {
+        let original_path = original.to_string_lossy().to_string();
+        let conflict = read_or_empty(&conflict_path)?;
+        let content = read_or_empty(&original_path)?;
+        conflicts.push(SyncConflict {
+            diff: diff_headlines(&content, &conflict, &keywords)?,
+            conflict_path,
+            original_exists: original.exists(),
+            original_path,
+        });
+    }
+    Ok(conflicts)
 }
 
-/// Open a file in external editor using the configured command
+/// Resolve a sync conflict copy by merging its extra headlines into the original file, or
+/// by discarding it
+/// Returns the headlines that differ between both files after a merge. While there are
+/// any, the copy is kept so its edits are not lost; discarding always deletes it.
 #[tauri::command]
 #[specta::specta]
-pub async fn open_file_in_external_editor(
+pub async fn resolve_sync_conflict(
     app_handle: tauri::AppHandle,
-    file_path: String,
-    line: Option<u32>,
-    column: Option<u32>,
-) -> Result<(), String> {
-    let settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let mut command = settings.external_editor_command.clone();
-    command = command.replace("{file}", &file_path);
-    command = command.replace("{line}", &line.unwrap_or(1).to_string());
-    command = command.replace("{column}", &column.unwrap_or(1).to_string());
-
-    let parts: Vec<&str> = command.split_whitespace().collect();
-    if parts.is_empty() {
-        return Err("External editor command is empty".to_string());
+    conflict_path: String,
+    resolution: ConflictResolution,
+) -> Result<Vec<HeadlineDiff>, String> {
+    // Only files named as conflict copies of a monitored document are ever deleted
+    let original = conflict_original(Path::new(&conflict_path))
+        .ok_or_else(|| format!("{} is not a sync conflict copy", conflict_path))?;
+    let monitored = with_repository(|repository| repository.get(&conflict_path).is_some())?;
+    if monitored != Some(true) {
+        return Err(format!("Document not found: {}", conflict_path));
     }
 
-    use std::process::Command;
-    let program = parts[0];
-    let args = &parts[1..];
-
-    let mut cmd = Command::new(program);
-    cmd.args(args);
+    if resolution == ConflictResolution::Merge {
+        let keywords = SETTINGS_MANAGER
+            .load_settings(&app_handle)
+            .await
+            .map_err(|e| e.to_string())?
+            .todo_keywords
+            .to_todo_configuration()
+            .keyword_lists();
+        let original_path = original.to_string_lossy().to_string();
+        let content = read_or_empty(&original_path)?;
+        let conflict = read_or_empty(&conflict_path)?;
+        let merged = merge_conflict(&content, &conflict, &keywords)?;
+        if merged != content {
+            write_monitored_file(&original_path, &merged)?;
+            track_recent(&app_handle, &original_path, None, RecentAccess::Edited);
+        }
 
-    match cmd.spawn() {
-        Ok(_) => {
-            println!(
-                "Successfully launched external editor: {} with args: {:?}",
-                program, args
-            );
-            Ok(())
+        // Headlines edited in both files can't be merged without losing one side
+        let changed: Vec<HeadlineDiff> = diff_headlines(&merged, &conflict, &keywords)?
+            .into_iter()
+            .filter(|entry| entry.change == HeadlineChange::Changed)
+            .collect();
+        if !changed.is_empty() {
+            return Ok(changed);
         }
-        Err(e) => Err(format!(
-            "Failed to open file in external editor '{}': {}",
-            program, e
-        )),
     }
+
+    fs::remove_file(&conflict_path)
+        .map_err(|e| format!("Failed to delete file {}: {}", conflict_path, e))?;
+    with_repository(|repository| repository.remove(&conflict_path))?;
+    Ok(Vec::new())
 }
 
-/// Save user settings
+/// Get the TODO keywords valid for a document: its own `#+TODO:` sequences
+/// merged with the keywords from settings
 #[tauri::command]
 #[specta::specta]
-pub async fn save_user_settings(
+pub async fn get_document_todo_config(
     app_handle: tauri::AppHandle,
-    settings: UserSettings,
-) -> Result<(), String> {
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &settings)
+    document_id: String,
+) -> Result<Option<TodoConfiguration>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    let settings_config = settings.todo_keywords.to_todo_configuration();
+
+    let config = with_repository(|repository| {
+        repository
+            .get(&document_id)
+            .map(|document| document.effective_todo_config(&settings_config))
+    })?;
+    Ok(config.flatten())
 }
 
-/// Helper function to restart file monitoring with current settings
-async fn restart_file_monitoring_with_settings(
-    app_handle: &tauri::AppHandle,
-) -> Result<(), String> {
-    // Load current settings to check what files should be covered
-    let settings = SETTINGS_MANAGER
+/// Get document and headline changes since a cursor from a previous call
+/// Start with cursor 0; if `complete` is false, refetch all documents
+#[tauri::command]
+#[specta::specta]
+pub async fn poll_changes(since_cursor: u32) -> Result<ChangeFeed, String> {
+    let feed = with_repository(|repository| repository.changes_since(since_cursor))?;
+    Ok(feed.unwrap_or(ChangeFeed {
+        cursor: 0,
+        changes: Vec::new(),
+        complete: since_cursor == 0,
+    }))
+}
+
+/// Fuzzy-match document and headline titles for a jump-to dialog
+#[tauri::command]
+#[specta::specta]
+pub async fn quick_find(query: String, limit: u32) -> Result<Vec<QuickFindResult>, String> {
+    let results =
+        with_repository(|repository| find_titles(&repository.list(), &query, limit as usize))?;
+    Ok(results.unwrap_or_default())
+}
+
+// Tags ignored in a view, or none if the settings can't be read
+async fn ignored_tags(app_handle: &tauri::AppHandle, view: IgnoreView) -> Vec<String> {
+    SETTINGS_MANAGER
         .load_settings(app_handle)
         .await
-        .map_err(|e| e.to_string())?;
+        .map(|settings| settings.ignored_tags.for_view(view).to_vec())
+        .unwrap_or_default()
+}
 
-    // Stop current monitoring
-    let _ = stop_file_monitoring().await;
+// Run a search for up to `limit` hits without those on headlines with ignored tags
+// Hits are dropped after searching, so all hits are searched for when tags are ignored
+fn search_unignored(
+    repository: &OrgDocumentRepository,
+    search: impl FnOnce(usize) -> Option<Vec<SearchHit>>,
+    limit: u32,
+    ignored: &[String],
+) -> Option<Vec<SearchHit>> {
+    let mut hits = search(if ignored.is_empty() {
+        limit as usize
+    } else {
+        usize::MAX
+    })?;
+    retain_unignored(
+        &mut hits,
+        ignored,
+        |id| repository.get(id),
+        |hit| (hit.document_id.as_str(), hit.headline_id.as_str()),
+    );
+    hits.truncate(limit as usize);
+    Some(hits)
+}
 
-    // Prune the repository to remove documents that are no longer covered
-    {
-        let monitor_lock = FILE_MONITOR
-            .lock()
-            .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+// Split the `deadline:`, `scheduled:` and `due:` range terms off a search query, with
+// relative weeks starting on the configured first day of the week
+async fn split_query(
+    app_handle: &tauri::AppHandle,
+    query: &str,
+) -> Result<(String, Vec<DateTerm>), String> {
+    let week_start = SETTINGS_MANAGER
+        .load_settings(app_handle)
+        .await
+        .map(|settings| settings.date_locale.first_weekday())
+        .unwrap_or(Weekday::Mon);
+    split_date_terms(query, time_zone::today(), week_start)
+}
 
-        if let Some(monitor) = monitor_lock.as_ref() {
-            let repository = monitor.get_repository();
-            let mut repository_lock = repository
-                .lock()
-                .map_err(|e| format!("Failed to lock repository: {}", e))?;
+/// Full-text search over headline titles and content, narrowed by property filters
+/// Terms such as `due:this-quarter`, `deadline:W23` or `scheduled:2024-06` keep headlines
+/// with that planning date in the range, and on their own list every such headline.
+/// Headlines with ignored tags are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn search_headlines(
+    app_handle: tauri::AppHandle,
+    query: String,
+    limit: u32,
+    filters: Vec<PropertyFilter>,
+) -> Result<Vec<SearchHit>, String> {
+    let (query, dates) = split_query(&app_handle, &query).await?;
+    let ignored = ignored_tags(&app_handle, IgnoreView::Search).await;
+    let hits = with_repository(|repository| {
+        search_unignored(
+            repository,
+            |limit| Some(repository.search(&query, limit, &filters, &dates)),
+            limit,
+            &ignored,
+        )
+    })?;
+    Ok(hits.flatten().unwrap_or_default())
+}
 
-            // Prune documents not covered by current settings
-            let removed_ids = repository_lock
-                .prune_uncovered_documents(|file_path| settings.is_file_covered(file_path));
+/// Find a phrase within one document, returning match offsets per headline for highlighting
+/// Returns None if the document doesn't exist
+#[tauri::command]
+#[specta::specta]
+pub async fn search_in_document(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    query: String,
+) -> Result<Option<Vec<HeadlineMatches>>, String> {
+    open_document(&app_handle, &document_id).await?;
+    let matches = with_repository(|repository| {
+        repository
+            .get(&document_id)
+            .map(|document| find_in_document(document, &query))
+    })?;
+    Ok(matches.flatten())
+}
 
-            if !removed_ids.is_empty() {
-                println!(
-                    "Pruned {} documents from repository: {:?}",
-                    removed_ids.len(),
-                    removed_ids
-                );
+/// Search in the background, emitting the hits as a search-results event
+/// Returns the job ID; a cancelled search emits nothing. Queries take the same date terms
+/// as `search_headlines`. Headlines with ignored tags are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_search(
+    app_handle: tauri::AppHandle,
+    query: String,
+    limit: u32,
+    filters: Vec<PropertyFilter>,
+) -> Result<u32, String> {
+    let (query, dates) = split_query(&app_handle, &query).await?;
+    let ignored = ignored_tags(&app_handle, IgnoreView::Search).await;
+    let (job_id, token) = JOBS.start(JobKind::Search);
+    tauri::async_runtime::spawn_blocking(move || {
+        let hits = match with_repository(|repository| {
+            search_unignored(
+                repository,
+                |limit| repository.search_cancellable(&query, limit, &filters, &dates, &token),
+                limit,
+                &ignored,
+            )
+        }) {
+            Ok(hits) => hits.unwrap_or(Some(Vec::new())),
+            Err(e) => {
+                emit_job(&app_handle, JOBS.fail(job_id, e));
+                return;
+            }
+        };
+
+        let summary = hits.as_ref().map(|hits| format!("{} hits", hits.len()));
+        emit_job(&app_handle, JOBS.complete(job_id, summary));
+        if let Some(hits) = hits {
+            let results = SearchResults { job_id, hits };
+            if let Err(e) = app_handle.emit(SEARCH_RESULTS_EVENT, results) {
+                eprintln!("Failed to emit search results: {}", e);
             }
         }
+    });
+    Ok(job_id)
+}
+
+/// Rebuild the search index from all loaded documents in the background
+/// Returns the job ID; progress is reported through job-progress events
+/// A cancelled rebuild keeps the previous index
+#[tauri::command]
+#[specta::specta]
+pub async fn rebuild_search_index(app_handle: tauri::AppHandle) -> Result<u32, String> {
+    let (job_id, token) = JOBS.start(JobKind::IndexBuild);
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = with_repository(|repository| {
+            repository.rebuild_search_index(&token, |indexed, total| {
+                emit_job(
+                    &app_handle,
+                    JOBS.set_progress(job_id, indexed as u32, total as u32),
+                )
+            })
+        });
+
+        let info = match result {
+            Ok(indexed) => JOBS.complete(
+                job_id,
+                indexed
+                    .flatten()
+                    .map(|count| format!("{} documents indexed", count)),
+            ),
+            Err(e) => JOBS.fail(job_id, e),
+        };
+        emit_job(&app_handle, info);
+    });
+    Ok(job_id)
+}
+
+/// Get recent parse durations per file and repository sizes
+#[tauri::command]
+#[specta::specta]
+pub async fn get_perf_stats() -> Result<PerfStats, String> {
+    let stats = with_repository(|repository| repository.perf_stats())?;
+    Ok(stats.unwrap_or_default())
+}
+
+/// Get the files that failed to parse, including files that crashed the parser
+#[tauri::command]
+#[specta::specta]
+pub async fn get_parse_diagnostics() -> Result<Vec<ParseDiagnostic>, String> {
+    let diagnostics = with_repository(|repository| repository.parse_diagnostics())?;
+    Ok(diagnostics.unwrap_or_default())
+}
+
+/// Get document and headline counts with an estimate of the memory they use
+#[tauri::command]
+#[specta::specta]
+pub async fn get_memory_stats() -> Result<MemoryStats, String> {
+    let stats = with_repository(|repository| repository.memory_stats())?;
+    Ok(stats.unwrap_or_default())
+}
+
+/// List running and recently finished background jobs, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_jobs() -> Result<Vec<JobInfo>, String> {
+    Ok(JOBS.list())
+}
+
+/// Get the current state of a background job
+#[tauri::command]
+#[specta::specta]
+pub async fn get_job(job_id: u32) -> Result<Option<JobInfo>, String> {
+    Ok(JOBS.get(job_id))
+}
+
+/// Cancel a running job started by a scan, search, or index rebuild
+/// Returns false if the job is unknown or has already finished
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_job(job_id: u32) -> Result<bool, String> {
+    Ok(JOBS.cancel(job_id))
+}
+
+// Tell the frontend about a job's new state
+fn emit_job(app_handle: &tauri::AppHandle, info: Option<JobInfo>) {
+    let Some(info) = info else {
+        return;
+    };
+    if let Err(e) = app_handle.emit(JOB_PROGRESS_EVENT, info) {
+        eprintln!("Failed to emit job progress: {}", e);
     }
+}
 
-    // Start monitoring with updated settings
-    let _ = start_file_monitoring(app_handle.clone()).await?;
+/// Get all tasks bucketed by category or agenda-group property
+/// Only tasks matching every property filter are included
+#[tauri::command]
+#[specta::specta]
+pub async fn get_grouped_tasks(
+    mode: GroupingMode,
+    filters: Vec<PropertyFilter>,
+) -> Result<Vec<HeadlineGroup>, String> {
+    let groups = with_repository(|repository| group_tasks(&repository.list(), mode, &filters))?;
+    Ok(groups.unwrap_or_default())
+}
 
-    Ok(())
+/// Get all distinct values of a headline property, e.g. for a column filter dropdown
+#[tauri::command]
+#[specta::specta]
+pub async fn get_property_values(property: String) -> Result<Vec<PropertyValue>, String> {
+    let values = with_repository(|repository| repository.get_property_values(&property))?;
+    Ok(values.unwrap_or_default())
 }
 
-/// Add a monitored path to settings
+/// Get tasks closed within a date range, grouped by day and category
 #[tauri::command]
 #[specta::specta]
-pub async fn add_monitored_path(
+pub async fn get_completed_tasks(range: DateRange) -> Result<Vec<CompletedDay>, String> {
+    if range.to_naive_dates().is_none() {
+        return Err(format!(
+            "Invalid date range: {} to {}",
+            range.start, range.end
+        ));
+    }
+
+    // Logbooks of evicted documents are read back from disk
+    let reloaded = read_evicted_documents().await?;
+    let days = with_full_documents(reloaded, |documents| {
+        collect_completed_tasks(documents, &range)
+    })?;
+    Ok(days.unwrap_or_default())
+}
+
+/// Turn a range expression such as `2024-W23`, `2024-06`, `Q3` or `this-quarter` into
+/// dates, for the range arguments of other commands
+/// Relative weeks start on the configured first day of the week
+#[tauri::command]
+#[specta::specta]
+pub async fn resolve_date_range(
     app_handle: tauri::AppHandle,
-    path: MonitoredPath,
-) -> Result<UserSettings, String> {
-    let mut settings = SETTINGS_MANAGER
+    expression: String,
+) -> Result<DateRange, String> {
+    let settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
+    DateRange::parse(
+        &expression,
+        time_zone::today(),
+        settings.date_locale.first_weekday(),
+    )
+}
 
-    settings
-        .add_monitored_path(path)
-        .map_err(|e| e.to_string())?;
+// Read evicted documents back from disk and parse them, outside the repository lock
+async fn read_evicted_documents() -> Result<HashMap<String, OrgDocument>, String> {
+    let evicted = with_repository(|repository| {
+        repository
+            .list()
+            .into_iter()
+            .filter(|document| repository.is_evicted(&document.id))
+            .map(|document| {
+                let keywords = repository.parsed_keywords(&document.id).cloned();
+                (document.id.clone(), document.file_path.clone(), keywords)
+            })
+            .collect::<Vec<_>>()
+    })?
+    .unwrap_or_default();
+    let mut documents = HashMap::new();
+    for (id, path, keywords) in evicted {
+        let parsed = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => ParsedFile::parse(Path::new(&path), &content, keywords),
+            Err(e) => Err(format!("Failed to read file {}: {}", path, e)),
+        };
+        match parsed {
+            Ok(parsed) => {
+                documents.insert(id, parsed.into_document());
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    Ok(documents)
+}
 
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &settings)
+// Run `f` over every document with its full content, using the documents read by
+// `read_evicted_documents` in place of evicted ones
+fn with_full_documents<T>(
+    mut reloaded: HashMap<String, OrgDocument>,
+    f: impl FnOnce(&[&OrgDocument]) -> T,
+) -> Result<Option<T>, String> {
+    with_repository(|repository| {
+        let reloaded: Vec<OrgDocument> = repository
+            .list()
+            .into_iter()
+            .filter_map(|document| {
+                let parsed = reloaded.remove(&document.id)?;
+                Some(OrgDocument {
+                    content: parsed.content,
+                    headlines: parsed.headlines,
+                    ..document.clone()
+                })
+            })
+            .collect();
+        let documents: Vec<&OrgDocument> = repository
+            .list()
+            .into_iter()
+            .filter(|document| !repository.is_evicted(&document.id))
+            .chain(reloaded.iter())
+            .collect();
+        f(&documents)
+    })
+}
+
+/// Get per-day counts of completed tasks and clocked minutes for a year, for an activity heatmap
+#[tauri::command]
+#[specta::specta]
+pub async fn get_activity_heatmap(year: i32) -> Result<ActivityHeatmap, String> {
+    // Logbooks of evicted documents are read back from disk
+    let reloaded = read_evicted_documents().await?;
+    let heatmap = with_full_documents(reloaded, |documents| activity_heatmap(documents, year))?;
+    Ok(heatmap.unwrap_or_else(|| activity_heatmap(&[], year)))
+}
+
+/// Export the agenda for a date range as org-agenda style plain text or a printable HTML
+/// page, listing each day's scheduled, deadline and timestamped entries
+/// The range is an expression such as `2024-06-01..2024-06-15`, `W23`, `2024-06` or
+/// `this-quarter`, with relative weeks starting on the configured first day of the week.
+/// Repeating timestamps show on every day they repeat on, open tasks past their date are
+/// carried forward to today, and days and months are named in the date locale's language.
+/// Ranges longer than a year are rejected. Headlines with ignored tags are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_agenda(
+    app_handle: tauri::AppHandle,
+    range: String,
+    format: AgendaFormat,
+) -> Result<String, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
+    let today = time_zone::today();
+    let range = DateRange::parse(&range, today, settings.date_locale.first_weekday())?;
+    let (start, end) = range
+        .to_naive_dates()
+        .ok_or_else(|| format!("Invalid date range: {} to {}", range.start, range.end))?;
+    if (end - start).num_days() >= MAX_AGENDA_DAYS {
+        return Err(format!(
+            "An agenda can cover at most {} days",
+            MAX_AGENDA_DAYS
+        ));
+    }
+    let ignored = settings.ignored_tags.for_view(IgnoreView::Agenda).to_vec();
+    let keywords = settings.todo_keywords.to_todo_configuration();
+    let schedule = settings.work_schedule.with_feed_holidays();
+
+    // Body timestamps of evicted documents are read back from disk
+    let reloaded = read_evicted_documents().await?;
+    let days = with_full_documents(reloaded, |documents| {
+        let mut days = collect_agenda(documents, start, end, &schedule);
+        carry_overdue(
+            &mut days,
+            collect_overdue(documents, today, &keywords),
+            today,
+        );
+        let by_id = documents_by_id(documents);
+        for day in &mut days {
+            retain_unignored(
+                &mut day.entries,
+                &ignored,
+                |id| by_id.get(id).copied(),
+                |entry| (entry.document_id.as_str(), entry.headline_id.as_str()),
+            );
+        }
+        days
+    })?
+    .unwrap_or_else(|| collect_agenda(&[], start, end, &schedule));
+    Ok(render_agenda(&days, format, &settings.date_locale.language))
+}
 
-    // Restart monitoring to reflect changes
-    restart_file_monitoring_with_settings(&app_handle).await?;
+// Documents by id, for looking up the documents of agenda entries
+fn documents_by_id<'a>(documents: &[&'a OrgDocument]) -> HashMap<&'a str, &'a OrgDocument> {
+    documents
+        .iter()
+        .map(|document| (document.id.as_str(), *document))
+        .collect()
+}
 
-    Ok(settings)
+// First and last day of a digest's agenda
+fn digest_range(
+    period: DigestPeriod,
+    today: NaiveDate,
+    locale: &DateLocale,
+) -> (NaiveDate, NaiveDate) {
+    match period {
+        DigestPeriod::Daily => (today, today),
+        DigestPeriod::Weekly => locale
+            .week_of(today)
+            .to_naive_dates()
+            .unwrap_or((today, today)),
+    }
 }
 
-/// Remove a monitored path from settings
+// Render the digest of today's or this week's agenda plus overdue tasks
+async fn build_digest(
+    settings: &UserSettings,
+    period: DigestPeriod,
+    format: AgendaFormat,
+) -> Result<String, String> {
+    let today = time_zone::today();
+    let (start, end) = digest_range(period, today, &settings.date_locale);
+    let schedule = settings.work_schedule.clone().with_feed_holidays();
+
+    // Body timestamps of evicted documents are read back from disk
+    let reloaded = read_evicted_documents().await?;
+    let ignored = settings.ignored_tags.for_view(IgnoreView::Agenda);
+    let keywords = settings.todo_keywords.to_todo_configuration();
+    let (days, overdue) = with_full_documents(reloaded, |documents| {
+        let (mut days, mut overdue) = (
+            collect_agenda(documents, start, end, &schedule),
+            collect_overdue(documents, today, &keywords),
+        );
+        let by_id = documents_by_id(documents);
+        for day in &mut days {
+            retain_unignored(
+                &mut day.entries,
+                ignored,
+                |id| by_id.get(id).copied(),
+                |entry| (entry.document_id.as_str(), entry.headline_id.as_str()),
+            );
+        }
+        retain_unignored(
+            &mut overdue,
+            ignored,
+            |id| by_id.get(id).copied(),
+            |task| {
+                (
+                    task.entry.document_id.as_str(),
+                    task.entry.headline_id.as_str(),
+                )
+            },
+        );
+        (days, overdue)
+    })?
+    .unwrap_or_else(|| (collect_agenda(&[], start, end, &schedule), Vec::new()));
+    Ok(render_digest(
+        &days,
+        &overdue,
+        today,
+        format,
+        &settings.date_locale.language,
+    ))
+}
+
+/// Render a digest of today's or this week's agenda with overdue tasks above it, as plain
+/// text or HTML to paste into a mail
 #[tauri::command]
 #[specta::specta]
-pub async fn remove_monitored_path(
+pub async fn get_digest(
     app_handle: tauri::AppHandle,
-    path: String,
+    period: DigestPeriod,
+    format: AgendaFormat,
+) -> Result<String, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    build_digest(&settings, period, format).await
+}
+
+/// Update the digest period, format and morning auto-copy toggle
+#[tauri::command]
+#[specta::specta]
+pub async fn set_digest_config(
+    app_handle: tauri::AppHandle,
+    config: DigestConfig,
 ) -> Result<UserSettings, String> {
     let mut settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
-    if !settings.remove_monitored_path(&path) {
-        return Err(format!("Path not found: {}", path));
-    }
+    settings.set_digest_config(config);
 
     SETTINGS_MANAGER
         .save_settings(&app_handle, &settings)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Restart monitoring to reflect changes
-    restart_file_monitoring_with_settings(&app_handle).await?;
-
     Ok(settings)
 }
 
-/// Update a monitored path in settings
+/// Update the shell commands run on task completion, capture and file changes, and how
+/// long they may run
 #[tauri::command]
 #[specta::specta]
-pub async fn update_monitored_path(
+pub async fn set_hook_config(
     app_handle: tauri::AppHandle,
-    old_path: String,
-    new_path: MonitoredPath,
+    config: HookConfig,
 ) -> Result<UserSettings, String> {
+    config.validate()?;
+
     let mut settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
-    settings
-        .update_monitored_path(&old_path, new_path)
-        .map_err(|e| e.to_string())?;
+    settings.set_hook_config(config);
 
     SETTINGS_MANAGER
         .save_settings(&app_handle, &settings)
@@ -595,114 +1670,3510 @@ pub async fn update_monitored_path(
     Ok(settings)
 }
 
-/// Set whether parsing is enabled for a monitored path
+/// Update the issue tracker tokens and refresh interval
 #[tauri::command]
 #[specta::specta]
-pub async fn set_path_parse_enabled(
+pub async fn set_issue_config(
     app_handle: tauri::AppHandle,
-    path: String,
-    parse_enabled: bool,
+    config: IssueConfig,
 ) -> Result<UserSettings, String> {
+    config.validate()?;
+
     let mut settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
-    settings
-        .set_path_parse_enabled(&path, parse_enabled)
-        .map_err(|e| e.to_string())?;
+    settings.set_issue_config(config);
 
     SETTINGS_MANAGER
         .save_settings(&app_handle, &settings)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Restart monitoring to reflect changes
-    restart_file_monitoring_with_settings(&app_handle).await?;
-
     Ok(settings)
 }
 
-/// Clear user settings
+/// Fetch the title and status of every GitHub and Jira issue linked from a headline
+/// They show in the ISSUE_STATUS and ISSUE_TITLE properties and are emitted as an
+/// issues-updated event
 #[tauri::command]
 #[specta::specta]
-pub async fn clear_user_settings(app_handle: tauri::AppHandle) -> Result<(), String> {
-    SETTINGS_MANAGER
-        .clear_settings(&app_handle)
+pub async fn refresh_issues(app_handle: tauri::AppHandle) -> Result<Vec<IssueInfo>, String> {
+    let config = SETTINGS_MANAGER
+        .load_settings(&app_handle)
         .await
-        .map_err(|e| e.to_string())
-}
+        .map_err(|e| e.to_string())?
+        .issues;
+    if !config.enabled {
+        return Err("Issue enrichment is turned off".to_string());
+    }
 
-/// Get current TODO keywords configuration from user settings
-#[tauri::command]
-#[specta::specta]
-pub async fn get_user_todo_keywords(app_handle: tauri::AppHandle) -> Result<TodoKeywords, String> {
-    let current_settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
+    let reloaded = read_evicted_documents().await?;
+    let links = with_full_documents(reloaded, collect_issue_links)?.unwrap_or_default();
+    let issues = tauri::async_runtime::spawn_blocking(move || fetch_issues(&links, &config))
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| format!("Failed to fetch issues: {}", e))??;
 
-    Ok(current_settings.get_todo_keywords().clone())
+    cache_issues(&issues);
+    save_issues(&issues_path(&app_handle)?, &cached_issues())?;
+    if let Err(e) = app_handle.emit(ISSUES_UPDATED_EVENT, &issues) {
+        eprintln!("Failed to emit issues: {}", e);
+    }
+    Ok(issues)
 }
 
-/// Get current custom headline properties from user settings
-#[tauri::command]
-#[specta::specta]
-pub async fn get_custom_properties(app_handle: tauri::AppHandle) -> Result<Vec<String>, String> {
-    let current_settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(current_settings.get_custom_properties().clone())
+// Location of the saved issue states in the app data directory
+fn issues_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(ISSUES_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
 }
 
-/// Add a custom headline property
+/// Refresh linked issues in the background every `refresh_minutes` while enrichment is on
+/// The states saved by the last run show until the first refresh. Failing refreshes are
+/// retried with a growing delay rather than every check.
+pub fn start_issue_refresher(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        match issues_path(&app_handle).and_then(|path| load_issues(&path)) {
+            Ok(issues) => cache_issues(&issues),
+            Err(e) => eprintln!("Failed to load saved issues: {}", e),
+        }
+        let mut last_refresh: Option<std::time::Instant> = None;
+        let mut failures = 0;
+        let mut retry_at: Option<std::time::Instant> = None;
+        loop {
+            if let Ok(settings) = SETTINGS_MANAGER.load_settings(&app_handle).await {
+                let interval =
+                    std::time::Duration::from_secs(u64::from(settings.issues.refresh_minutes) * 60);
+                let due = settings.issues.enabled
+                    && last_refresh.is_none_or(|last| last.elapsed() >= interval)
+                    && retry_at.is_none_or(|at| std::time::Instant::now() >= at);
+                if due {
+                    match refresh_issues(app_handle.clone()).await {
+                        // Documents may still be loading, so keep trying until issues turn up
+                        Ok(issues) if !issues.is_empty() => {
+                            last_refresh = Some(std::time::Instant::now());
+                            failures = 0;
+                            retry_at = None;
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            failures += 1;
+                            retry_at = Some(std::time::Instant::now() + retry_delay(failures));
+                            eprintln!("Failed to refresh issues: {}", e);
+                        }
+                    }
+                }
+            }
+            tokio::time::sleep(ISSUE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Update the GitHub issue sync file, repositories and interval
+/// The file must be inside the monitored paths, since syncs write to it without asking.
 #[tauri::command]
 #[specta::specta]
-pub async fn add_custom_property(
+pub async fn set_github_sync_config(
     app_handle: tauri::AppHandle,
-    property: String,
-) -> Result<Vec<String>, String> {
-    let mut current_settings = SETTINGS_MANAGER
+    config: GitHubSyncConfig,
+) -> Result<UserSettings, String> {
+    config.validate()?;
+
+    let mut settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
+    if let Some(file) = config
+        .file
+        .as_deref()
+        .filter(|file| !file.trim().is_empty())
+    {
+        check_sync_file(&settings, file)?;
+    }
 
-    current_settings
-        .add_custom_property(property)
-        .map_err(|e| e.to_string())?;
+    settings.set_github_sync_config(config);
 
     SETTINGS_MANAGER
-        .save_settings(&app_handle, &current_settings)
+        .save_settings(&app_handle, &settings)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Trigger re-parsing of all documents with updated settings
-    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
-        eprintln!(
-            "Warning: Failed to reload documents after custom property change: {}",
-            e
-        );
-    }
+    Ok(settings)
+}
 
-    Ok(current_settings.get_custom_properties().clone())
+// The sync file is written without asking, so it must be one of the monitored files
+fn check_sync_file(settings: &UserSettings, file: &str) -> Result<(), String> {
+    if settings.is_file_covered(file) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} is outside the monitored paths; add it or its directory first",
+            file
+        ))
+    }
+}
+
+/// Sync the GitHub issue file: close issues whose headlines were given the closed
+/// keyword, then mirror the open issues assigned to the token's user
+#[tauri::command]
+#[specta::specta]
+pub async fn sync_github_issues(app_handle: tauri::AppHandle) -> Result<GitHubSyncSummary, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !settings.github_sync.enabled {
+        return Err("GitHub issue sync is turned off".to_string());
+    }
+    let path = settings
+        .github_sync
+        .file
+        .clone()
+        .filter(|file| !file.trim().is_empty())
+        .ok_or_else(|| "Choose a file for GitHub issues".to_string())?;
+    check_sync_file(&settings, &path)?;
+    let token = settings.issues.github_token().map(str::to_string);
+    let config = settings.github_sync;
+
+    let read = |path: &str| match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read file {}: {}", path, e)),
+    };
+    let content = read(&path)?;
+    let original = content.clone();
+    let (synced, summary) = tauri::async_runtime::spawn_blocking(move || {
+        sync_issue_file(&content, &config, token.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Failed to sync GitHub issues: {}", e))??;
+
+    if synced != original {
+        // Edits made while the requests ran win; the next sync picks them up
+        if read(&path)? != original {
+            return Err(format!("{} changed during the sync", path));
+        }
+        write_monitored_file(&path, &synced)?;
+        track_recent(&app_handle, &path, None, RecentAccess::Edited);
+    }
+    Ok(summary)
+}
+
+/// Sync GitHub issues in the background every `interval_minutes` while the sync is on
+pub fn start_github_sync(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_sync: Option<std::time::Instant> = None;
+        loop {
+            if let Ok(settings) = SETTINGS_MANAGER.load_settings(&app_handle).await {
+                let interval = std::time::Duration::from_secs(
+                    u64::from(settings.github_sync.interval_minutes) * 60,
+                );
+                let due = settings.github_sync.enabled
+                    && last_sync.is_none_or(|last| last.elapsed() >= interval);
+                if due {
+                    // Failed syncs also wait a full interval, to stay within rate limits
+                    last_sync = Some(std::time::Instant::now());
+                    if let Err(e) = sync_github_issues(app_handle.clone()).await {
+                        eprintln!("Failed to sync GitHub issues: {}", e);
+                    }
+                }
+            }
+            tokio::time::sleep(ISSUE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Update how edited headlines lay out planning lines, drawers and tags
+#[tauri::command]
+#[specta::specta]
+pub async fn set_format_conventions(
+    app_handle: tauri::AppHandle,
+    conventions: FormatConventions,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_format_conventions(conventions);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Set the tags whose headlines are left out of the agenda, next actions and searches,
+/// with optional lists for each of those views
+#[tauri::command]
+#[specta::specta]
+pub async fn set_ignored_tags(
+    app_handle: tauri::AppHandle,
+    ignored_tags: IgnoredTags,
+) -> Result<UserSettings, String> {
+    ignored_tags.validate()?;
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_ignored_tags(ignored_tags);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Set the default order of listed headlines, used when a listing gives no order of its
+/// own; an empty list keeps file order
+#[tauri::command]
+#[specta::specta]
+pub async fn set_default_sort(
+    app_handle: tauri::AppHandle,
+    sort: Vec<SortRule>,
+) -> Result<UserSettings, String> {
+    for rule in &sort {
+        if let SortKey::Property(property) = &rule.key {
+            if !is_valid_property_key(property) {
+                return Err(format!("Invalid property name: '{}'", property));
+            }
+        }
+    }
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_default_sort(sort);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Report broken file links, unresolved id links and dangling internal targets
+#[tauri::command]
+#[specta::specta]
+pub async fn check_links() -> Result<Vec<BrokenLink>, String> {
+    // Links in the bodies of evicted documents are read back from disk
+    let reloaded = read_evicted_documents().await?;
+    let broken = with_full_documents(reloaded, check_document_links)?;
+    Ok(broken.unwrap_or_default())
+}
+
+/// Audit the repository for duplicated :ID: properties and same-file duplicate titles
+#[tauri::command]
+#[specta::specta]
+pub async fn audit_duplicates() -> Result<Vec<DuplicateEntry>, String> {
+    let duplicates = with_repository(|repository| find_duplicates(&repository.list()))?;
+    Ok(duplicates.unwrap_or_default())
+}
+
+/// List org-drill and org-fc cards due today or earlier, most overdue first
+/// Cards that were never reviewed come last
+#[tauri::command]
+#[specta::specta]
+pub async fn get_due_cards() -> Result<Vec<Card>, String> {
+    let today = time_zone::today();
+    // org-fc review data lives in drawers, which evicted documents drop
+    let reloaded = read_evicted_documents().await?;
+    let cards = with_full_documents(reloaded, |documents| collect_due_cards(documents, today))?;
+    Ok(cards.unwrap_or_default())
+}
+
+/// Record a review of an org-drill card, rated 0 (blackout) to 5 (perfect)
+/// The card's DRILL_* properties and SCHEDULED date are updated as org-drill would
+/// Returns the card with its new due date
+#[tauri::command]
+#[specta::specta]
+pub async fn record_card_review(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    quality: u8,
+) -> Result<Card, String> {
+    if quality > 5 {
+        return Err(format!("Review quality must be 0 to 5, got {}", quality));
+    }
+    let content = read_document_file(&document_id)?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+
+    let (file_path, updated, card) = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+        let document = repository_lock
+            .get(&document_id)
+            .ok_or_else(|| format!("Document not found: {}", document_id))?;
+        let headline = document
+            .find_headline(&headline_id)
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+        let card = card_for_headline(document, headline)
+            .ok_or_else(|| format!("Not a flashcard: {}", headline.title.raw))?;
+        if card.kind != CardKind::Drill {
+            return Err("Reviews can only be recorded for org-drill cards".to_string());
+        }
+
+        // Refuse if the file changed since it was parsed, since headline ids may have moved
+        if !repository_lock.matches_content(&document_id, &content) {
+            return Err(format!(
+                "{} changed on disk since it was loaded",
+                document.file_path
+            ));
+        }
+        let mut current = document.clone();
+        current.content = content.clone();
+        let line = headline_line_numbers(&current)
+            .get(&headline_id)
+            .copied()
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+
+        let now = time_zone::now();
+        let (state, days) = DrillState::from_headline(headline).review(quality);
+        let due = now.date() + chrono::Duration::days(days);
+        let updated = write_review(&content, line, due, &state.properties(quality, now))
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+
+        let card = Card {
+            due: Some(due.format("%Y-%m-%d").to_string()),
+            total_repeats: state.total_repeats,
+            failure_count: state.failure_count,
+            ..card
+        };
+        (document.file_path.clone(), updated, card)
+    };
+
+    monitor.write_file(Path::new(&file_path), &updated)?;
+    drop(monitor_lock);
+    track_recent(
+        &app_handle,
+        &document_id,
+        Some(&headline_id),
+        RecentAccess::Edited,
+    );
+    Ok(card)
+}
+
+/// Change a headline's TODO keyword, or clear it with None
+/// Closing a task that is still blocked by open subtasks, open BLOCKER/DEPENDS targets,
+/// earlier siblings in an ordered subtree or unchecked checkboxes is refused unless `force`
+/// Closing is logged as the file's `#+STARTUP:` options ask, with `note` as the closing
+/// note under `lognotedone`; reopening removes the CLOSED timestamp
+/// Keywords are those of the file's `#+TODO:` lines together with the ones from settings.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_todo_keyword(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    keyword: Option<String>,
+    force: bool,
+    note: Option<String>,
+) -> Result<(), String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let settings_config = settings.todo_keywords.to_todo_configuration();
+    let content = read_document_file(&document_id)?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+
+    let (file_path, updated, done_hook) = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+        let document = repository_lock
+            .get(&document_id)
+            .ok_or_else(|| format!("Document not found: {}", document_id))?;
+        if document.archived {
+            return Err(format!("{} is a read-only archive", document.file_path));
+        }
+        let headline = document
+            .find_headline(&headline_id)
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+
+        // Refuse if the file changed since it was parsed, since headline ids may have moved
+        if !repository_lock.matches_content(&document_id, &content) {
+            return Err(format!(
+                "{} changed on disk since it was loaded",
+                document.file_path
+            ));
+        }
+        let mut current = document.clone();
+        current.content = content;
+
+        let config = document.effective_todo_config(&settings_config);
+        let graph = DependencyGraph::build(&repository_lock.list());
+        let (updated, closed) = apply_todo_keyword(
+            &current,
+            &graph,
+            &config,
+            &headline_id,
+            keyword.as_deref(),
+            force,
+            note.as_deref(),
+            time_zone::now(),
+        )?;
+
+        let done_hook = closed.then(|| {
+            vec![
+                ("ORGX_FILE".to_string(), document.file_path.clone()),
+                ("ORGX_DOCUMENT_ID".to_string(), document_id.clone()),
+                ("ORGX_HEADLINE_ID".to_string(), headline_id.clone()),
+                ("ORGX_TITLE".to_string(), headline.title.raw.clone()),
+                (
+                    "ORGX_KEYWORD".to_string(),
+                    keyword.clone().unwrap_or_default(),
+                ),
+                (
+                    "ORGX_PREVIOUS_KEYWORD".to_string(),
+                    headline
+                        .title
+                        .todo_keyword
+                        .as_deref()
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+            ]
+        });
+        (document.file_path.clone(), updated, done_hook)
+    };
+
+    monitor.write_file(Path::new(&file_path), &updated)?;
+    drop(monitor_lock);
+    if let Some(variables) = done_hook {
+        spawn_hook(&settings.hooks, HookEvent::TaskDone, variables);
+    }
+    track_recent(
+        &app_handle,
+        &document_id,
+        Some(&headline_id),
+        RecentAccess::Edited,
+    );
+    Ok(())
+}
+
+/// Move a headline's TODO keyword to the next or previous one in its sequence, wrapping
+/// through no keyword, like Shift-right and Shift-left in Emacs
+/// Closing is checked and logged as by `set_todo_keyword`. Returns the new keyword.
+#[tauri::command]
+#[specta::specta]
+pub async fn cycle_todo_state(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    direction: CycleDirection,
+) -> Result<Option<String>, String> {
+    let settings_config = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map(|settings| settings.todo_keywords.to_todo_configuration())
+        .unwrap_or_else(|_| TodoConfiguration::default());
+    let keyword = with_repository(|repository| {
+        let document = repository.get(&document_id)?;
+        let headline = document.find_headline(&headline_id)?;
+        let config = document.effective_todo_config(&settings_config);
+        Some(config.cycle(headline.title.todo_keyword.as_deref(), direction))
+    })?
+    .flatten()
+    .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+
+    set_todo_keyword(
+        app_handle,
+        document_id,
+        headline_id,
+        keyword.clone(),
+        false,
+        None,
+    )
+    .await?;
+    Ok(keyword)
+}
+
+// Move a headline's subtree to the end of its file's archive, recording where it came
+// from in ARCHIVE_ properties as org-mode does
+async fn archive_subtree(
+    app_handle: &tauri::AppHandle,
+    document_id: &str,
+    headline_id: &str,
+) -> Result<(), String> {
+    let properties = with_repository(|repository| {
+        let document = repository
+            .get(document_id)
+            .ok_or_else(|| format!("Document not found: {}", document_id))?;
+        if document.archived {
+            return Err(format!("{} is a read-only archive", document.file_path));
+        }
+        archive_properties(document, headline_id, time_zone::now())
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))
+    })?
+    .ok_or_else(|| "File monitoring is not running".to_string())??;
+    let content = read_document_file(document_id)?;
+
+    // The archive is read and both files are written under the monitor lock, so neither
+    // changes between
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+    let cut = cut_subtree(monitor, document_id, headline_id, content)?;
+
+    // The archive is created with the first subtree archived to it
+    let path = archive_path(&cut.file_path);
+    let archive = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read file {}: {}", path, e)),
+    };
+    let archive = append_to_archive(&archive, &cut.removed, &properties)
+        .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+
+    monitor.write_file(Path::new(&cut.file_path), &cut.remaining)?;
+    if let Err(e) = monitor.write_file(Path::new(&path), &archive) {
+        monitor.restore_file(Path::new(&cut.file_path), &cut.content)?;
+        return Err(e);
+    }
+    drop(monitor_lock);
+    track_recent(app_handle, document_id, None, RecentAccess::Edited);
+    Ok(())
+}
+
+/// Run a single-key action on a headline, like org-mode's speed commands: cycle its TODO
+/// state, set its priority, schedule it for today or tomorrow, archive it or clock in
+/// Priorities must be within the file's `#+PRIORITIES:` range, A to C by default. Archived
+/// subtrees go to the end of `<file>_archive`. Clocking in is refused while the headline's
+/// clock is already running.
+#[tauri::command]
+#[specta::specta]
+pub async fn execute_headline_action(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    action: HeadlineAction,
+) -> Result<(), String> {
+    match action {
+        HeadlineAction::CycleState(direction) => {
+            cycle_todo_state(app_handle, document_id, headline_id, direction).await?;
+        }
+        HeadlineAction::Archive => {
+            archive_subtree(&app_handle, &document_id, &headline_id).await?;
+        }
+        _ => {
+            let language = SETTINGS_MANAGER
+                .load_settings(&app_handle)
+                .await
+                .map_err(|e| e.to_string())?
+                .date_locale
+                .language;
+            let now = time_zone::now();
+            edit_document_source(&app_handle, &document_id, Some(&headline_id), |document| {
+                apply_section_action(document, &headline_id, action, now, &language)
+            })
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+// Rewrite the source of a document with `edit`, which gets the document with its current
+// content, refusing archives and files changed since they were parsed
+async fn edit_document_source(
+    app_handle: &tauri::AppHandle,
+    document_id: &str,
+    headline_id: Option<&str>,
+    edit: impl FnOnce(&OrgDocument) -> Result<String, String>,
+) -> Result<(), String> {
+    let settings_config = SETTINGS_MANAGER
+        .load_settings(app_handle)
+        .await
+        .map(|settings| settings.todo_keywords.to_todo_configuration())
+        .unwrap_or_else(|_| TodoConfiguration::default());
+    let content = read_document_file(document_id)?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+
+    let (file_path, updated) = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+        let document = repository_lock
+            .get(document_id)
+            .ok_or_else(|| format!("Document not found: {}", document_id))?;
+        if document.archived {
+            return Err(format!("{} is a read-only archive", document.file_path));
+        }
+
+        if repository_lock.matches_content(document_id, &content) {
+            let mut current = document.clone();
+            current.content = content;
+            (document.file_path.clone(), edit(&current)?)
+        } else {
+            // Headline ids may have moved in the changed file, so the edit is made to the
+            // version that was loaded and merged with the file headline by headline
+            if repository_lock.is_evicted(document_id) {
+                return Err(format!(
+                    "{} changed on disk since it was loaded",
+                    document.file_path
+                ));
+            }
+            let local = edit(document)?;
+            let keywords = document
+                .effective_todo_config(&settings_config)
+                .keyword_lists();
+            match merge_edits(&document.content, &local, &content, &keywords)? {
+                MergeOutcome::Merged(merged) => (document.file_path.clone(), merged),
+                MergeOutcome::Conflicts(conflicts) => {
+                    let conflict = WriteConflict {
+                        document_id: document_id.to_string(),
+                        file_path: document.file_path.clone(),
+                        conflicts,
+                    };
+                    PENDING_MERGES
+                        .lock()
+                        .map_err(|e| format!("Failed to lock pending merges: {}", e))?
+                        .insert(
+                            document_id.to_string(),
+                            PendingMerge {
+                                base: document.content.clone(),
+                                local,
+                                disk: content,
+                                keywords,
+                                conflict: conflict.clone(),
+                            },
+                        );
+                    let _ = app_handle.emit(WRITE_CONFLICT_EVENT, &conflict);
+                    return Err(format!(
+                        "{} changed on disk and the edit conflicts with it",
+                        document.file_path
+                    ));
+                }
+            }
+        }
+    };
+
+    monitor.write_file(Path::new(&file_path), &updated)?;
+    drop(monitor_lock);
+    track_recent(app_handle, document_id, headline_id, RecentAccess::Edited);
+    Ok(())
+}
+
+// Rewrite the source of a headline's file with `edit`, which gets the current content and
+// the headline's 1-based line
+async fn edit_headline_source(
+    app_handle: &tauri::AppHandle,
+    document_id: &str,
+    headline_id: &str,
+    edit: impl FnOnce(&str, usize) -> Result<String, String>,
+) -> Result<(), String> {
+    edit_document_source(app_handle, document_id, Some(headline_id), |document| {
+        let line = headline_line_numbers(document)
+            .get(headline_id)
+            .copied()
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+        edit(&document.content, line)
+    })
+    .await
+}
+
+/// Get the headlines an edit of a document conflicts with, if one is waiting to be resolved
+#[tauri::command]
+#[specta::specta]
+pub async fn get_write_conflict(document_id: String) -> Result<Option<WriteConflict>, String> {
+    let pending_merges = PENDING_MERGES
+        .lock()
+        .map_err(|e| format!("Failed to lock pending merges: {}", e))?;
+    Ok(pending_merges
+        .get(&document_id)
+        .map(|pending| pending.conflict.clone()))
+}
+
+/// Drop an edit that conflicted with changes on disk, keeping the file as it is
+#[tauri::command]
+#[specta::specta]
+pub async fn discard_write_conflict(document_id: String) -> Result<(), String> {
+    PENDING_MERGES
+        .lock()
+        .map_err(|e| format!("Failed to lock pending merges: {}", e))?
+        .remove(&document_id);
+    Ok(())
+}
+
+/// Write an edit that conflicted with changes on disk, merging it with the file as it is
+/// now and settling headlines both changed in favor of `side`
+/// If the file changed again in a way that conflicts with the edit, the new conflicts are
+/// reported instead and the edit stays pending.
+#[tauri::command]
+#[specta::specta]
+pub async fn resolve_write_conflict(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    side: MergeSide,
+) -> Result<(), String> {
+    // The file is read and written under the monitor lock, like other edits, so it cannot
+    // change in between; pending merges are locked after it, as edits do
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+    let mut pending_merges = PENDING_MERGES
+        .lock()
+        .map_err(|e| format!("Failed to lock pending merges: {}", e))?;
+    let pending = pending_merges
+        .get(&document_id)
+        .cloned()
+        .ok_or_else(|| format!("No conflicting edit for {}", document_id))?;
+    let (file_path, disk) = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+        let document = repository_lock
+            .get(&document_id)
+            .ok_or_else(|| format!("Document not found: {}", document_id))?;
+        if document.archived {
+            return Err(format!("{} is a read-only archive", document.file_path));
+        }
+        (
+            document.file_path.clone(),
+            read_or_empty(&document.file_path)?,
+        )
+    };
+
+    if disk != pending.disk {
+        // Changed again since the conflicts were shown, so the choice may not cover it
+        if let MergeOutcome::Conflicts(conflicts) =
+            merge_edits(&pending.base, &pending.local, &disk, &pending.keywords)?
+        {
+            let conflict = WriteConflict {
+                document_id: document_id.clone(),
+                file_path: file_path.clone(),
+                conflicts,
+            };
+            pending_merges.insert(
+                document_id.clone(),
+                PendingMerge {
+                    disk,
+                    conflict: conflict.clone(),
+                    ..pending
+                },
+            );
+            let _ = app_handle.emit(WRITE_CONFLICT_EVENT, &conflict);
+            return Err(format!(
+                "{} changed on disk again and the edit conflicts with it",
+                file_path
+            ));
+        }
+    }
+
+    let merged = merge_edits_preferring(
+        &pending.base,
+        &pending.local,
+        &disk,
+        &pending.keywords,
+        side,
+    )?;
+    if merged != disk {
+        monitor.write_file(Path::new(&file_path), &merged)?;
+    }
+    pending_merges.remove(&document_id);
+    drop(pending_merges);
+    drop(monitor_lock);
+    if merged != disk {
+        track_recent(&app_handle, &document_id, None, RecentAccess::Edited);
+    }
+    Ok(())
+}
+
+/// Write a headline's Effort property, creating its drawer if needed
+#[tauri::command]
+#[specta::specta]
+pub async fn set_effort(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    effort: String,
+) -> Result<(), String> {
+    let effort = effort.trim().to_string();
+    if !is_valid_effort(&effort) {
+        return Err(format!("Invalid effort: '{}'", effort));
+    }
+
+    edit_headline_source(&app_handle, &document_id, &headline_id, |content, line| {
+        set_headline_properties(content, line, &[(EFFORT_PROPERTY, effort)])
+            .ok_or_else(|| "Malformed property drawer".to_string())
+    })
+    .await
+}
+
+/// Set a property on a headline, editing its `:PROPERTIES:` drawer in place or creating one
+/// The key is matched case-insensitively against existing properties; values of typed
+/// custom properties must parse as their type
+#[tauri::command]
+#[specta::specta]
+pub async fn set_headline_property(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    let key = key.trim().to_string();
+    if !is_valid_property_key(&key) {
+        return Err(format!("Invalid property name: '{}'", key));
+    }
+    if value.contains('\n') {
+        return Err("Property values must fit on one line".to_string());
+    }
+    let value = value.trim().to_string();
+    SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?
+        .validate_property_value(&key, &value)
+        .map_err(|e| e.to_string())?;
+
+    edit_headline_source(&app_handle, &document_id, &headline_id, |content, line| {
+        set_headline_properties(content, line, &[(key.as_str(), value)])
+            .ok_or_else(|| "Malformed property drawer".to_string())
+    })
+    .await
+}
+
+/// Remove a property from a headline, dropping its `:PROPERTIES:` drawer if it becomes empty
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_headline_property(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    key: String,
+) -> Result<(), String> {
+    let key = key.trim().to_string();
+    edit_headline_source(&app_handle, &document_id, &headline_id, |content, line| {
+        remove_headline_property(content, line, &key)
+            .ok_or_else(|| format!("Property not found: {}", key))
+    })
+    .await
+}
+
+/// Write a document back from an edited copy of its headline tree
+/// Parts of headlines that are unchanged are kept byte for byte and only the parts that
+/// changed are rewritten. Headlines left out are removed, and headlines with an empty id
+/// are written as new ones.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_document_headlines(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headlines: Vec<OrgHeadline>,
+) -> Result<(), String> {
+    open_document(&app_handle, &document_id).await?;
+    edit_document_source(&app_handle, &document_id, None, |document| {
+        let mut edited = document.clone();
+        edited.headlines = headlines;
+        Ok(format_document(&edited))
+    })
+    .await
+}
+
+/// Defer a task until a day after today, hiding it and its subtasks from the agenda, next
+/// actions and urgent tasks until then through its DEFERRED property
+/// Without a day, the task comes back now.
+#[tauri::command]
+#[specta::specta]
+pub async fn defer_task(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    until_date: Option<String>,
+) -> Result<(), String> {
+    let Some(until_date) = until_date else {
+        return edit_headline_source(&app_handle, &document_id, &headline_id, |content, line| {
+            remove_headline_property(content, line, DEFERRED_PROPERTY)
+                .ok_or_else(|| "Task is not deferred".to_string())
+        })
+        .await;
+    };
+    let until = NaiveDate::parse_from_str(&until_date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date: '{}'", until_date))?;
+    if until <= time_zone::today() {
+        return Err("Tasks can only be deferred to a day after today".to_string());
+    }
+    let language = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?
+        .date_locale
+        .language;
+
+    edit_headline_source(&app_handle, &document_id, &headline_id, |content, line| {
+        let value = deferred_value(until, &language);
+        set_headline_properties(content, line, &[(DEFERRED_PROPERTY, value)])
+            .ok_or_else(|| "Malformed property drawer".to_string())
+    })
+    .await
+}
+
+/// Add an unchecked item to a headline's checklist, before the item at `position` or at
+/// the end, creating the list if needed and updating statistics cookies
+#[tauri::command]
+#[specta::specta]
+pub async fn add_checklist_item(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    text: String,
+    position: Option<u32>,
+) -> Result<(), String> {
+    if text.trim().is_empty() || text.contains('\n') {
+        return Err("Checklist items must be a single non-empty line".to_string());
+    }
+
+    edit_headline_source(&app_handle, &document_id, &headline_id, |content, line| {
+        insert_checklist_item(
+            content,
+            line,
+            &text,
+            position.map(|position| position as usize),
+        )
+        .ok_or_else(|| format!("Headline not found: {}", headline_id))
+    })
+    .await
+}
+
+/// Remove a top-level item, with anything nested under it, from a headline's checklist
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_checklist_item(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    index: u32,
+) -> Result<(), String> {
+    edit_headline_source(&app_handle, &document_id, &headline_id, |content, line| {
+        delete_checklist_item(content, line, index as usize)
+            .ok_or_else(|| format!("Checklist item not found: {}", index))
+    })
+    .await
+}
+
+/// Reorder a headline's children by title, TODO state, priority, deadline or a property,
+/// moving each child's whole subtree
+#[tauri::command]
+#[specta::specta]
+pub async fn sort_children(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    key: SortKey,
+) -> Result<(), String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let settings_config = settings.todo_keywords.to_todo_configuration();
+    let property_type = match &key {
+        SortKey::Property(property) => settings.get_custom_property_type(property),
+        _ => PropertyType::String,
+    };
+
+    edit_document_source(&app_handle, &document_id, Some(&headline_id), |document| {
+        let config = document.effective_todo_config(&settings_config);
+        sort_headline_children(document, &headline_id, &key, &config, property_type)
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))
+    })
+    .await
+}
+
+/// Split a headline into siblings at the items of its first plain list or at its
+/// paragraphs, each new headline titled by the first line of its text
+#[tauri::command]
+#[specta::specta]
+pub async fn split_headline(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    mode: SplitMode,
+) -> Result<(), String> {
+    edit_document_source(&app_handle, &document_id, Some(&headline_id), |document| {
+        split_headline_at(document, &headline_id, mode)
+    })
+    .await
+}
+
+/// Merge sibling headlines into the first of them in file order, keeping the others'
+/// titles and bodies as its text and their children as its own
+#[tauri::command]
+#[specta::specta]
+pub async fn merge_headlines(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_ids: Vec<String>,
+) -> Result<(), String> {
+    let first = headline_ids.first().cloned();
+    edit_document_source(&app_handle, &document_id, first.as_deref(), |document| {
+        merge_sibling_headlines(document, &headline_ids)
+    })
+    .await
+}
+
+// Check a single-line keyword value, returning it trimmed or None if it is empty
+fn keyword_value(name: &str, value: &str) -> Result<Option<String>, String> {
+    if value.contains('\n') {
+        return Err(format!("The {} must fit on one line", name));
+    }
+    let value = value.trim();
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+/// Set a document's `#+TITLE:` line, or remove it if the title is empty
+#[tauri::command]
+#[specta::specta]
+pub async fn set_document_title(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    title: String,
+) -> Result<(), String> {
+    let title = keyword_value("title", &title)?;
+    edit_document_source(&app_handle, &document_id, None, |document| {
+        Ok(set_file_keyword(
+            &document.content,
+            "TITLE",
+            title.as_deref(),
+        ))
+    })
+    .await
+}
+
+/// Replace a document's `#+FILETAGS:` with the given tags, or remove it if there are none
+#[tauri::command]
+#[specta::specta]
+pub async fn set_filetags(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let mut filetags: Vec<&str> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()) {
+        if !is_valid_tag(tag) {
+            return Err(format!("Invalid tag: '{}'", tag));
+        }
+        if !filetags.contains(&tag) {
+            filetags.push(tag);
+        }
+    }
+    let value = (!filetags.is_empty()).then(|| format!(":{}:", filetags.join(":")));
+
+    edit_document_source(&app_handle, &document_id, None, |document| {
+        Ok(set_file_keyword(
+            &document.content,
+            "FILETAGS",
+            value.as_deref(),
+        ))
+    })
+    .await
+}
+
+/// Set a document's `#+CATEGORY:` line, or remove it if the category is empty
+#[tauri::command]
+#[specta::specta]
+pub async fn set_document_category(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    category: String,
+) -> Result<(), String> {
+    let category = keyword_value("category", &category)?;
+    edit_document_source(&app_handle, &document_id, None, |document| {
+        Ok(set_file_keyword(
+            &document.content,
+            "CATEGORY",
+            category.as_deref(),
+        ))
+    })
+    .await
+}
+
+/// Load user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn load_user_settings(app_handle: tauri::AppHandle) -> Result<UserSettings, String> {
+    SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the external editor command from user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_external_editor_command(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(settings.external_editor_command)
+}
+
+/// Set the external editor command in user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn set_external_editor_command(
+    app_handle: tauri::AppHandle,
+    command: String,
+) -> Result<(), String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    settings.external_editor_command = command;
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Limit how many recently opened documents keep their body content loaded
+/// Other documents keep their outline and reload content from disk when opened
+#[tauri::command]
+#[specta::specta]
+pub async fn set_max_loaded_documents(
+    app_handle: tauri::AppHandle,
+    limit: Option<u32>,
+) -> Result<UserSettings, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings.set_max_loaded_documents(limit);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(limit) = limit {
+        with_repository(|repository| repository.evict_least_recent(limit as usize))?;
+    }
+
+    Ok(current_settings)
+}
+
+/// Enable or disable parsing of *.org_archive files and reload documents
+#[tauri::command]
+#[specta::specta]
+pub async fn set_parse_archive_files(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+) -> Result<UserSettings, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings.set_parse_archive_files(enabled);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Re-scan so archive documents are added or pruned
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after settings change: {}",
+            e
+        );
+    }
+
+    Ok(current_settings)
+}
+
+/// Reset the external editor command to default in user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn reset_external_editor_command(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    settings.external_editor_command = UserSettings::default().external_editor_command;
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Open a file in external editor using the configured command
+#[tauri::command]
+#[specta::specta]
+pub async fn open_file_in_external_editor(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> Result<(), String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut command = settings.external_editor_command.clone();
+    command = command.replace("{file}", &file_path);
+    command = command.replace("{line}", &line.unwrap_or(1).to_string());
+    command = command.replace("{column}", &column.unwrap_or(1).to_string());
+
+    let parts: Vec<&str> = command.split_whitespace().collect();
+    if parts.is_empty() {
+        return Err("External editor command is empty".to_string());
+    }
+
+    use std::process::Command;
+    let program = parts[0];
+    let args = &parts[1..];
+
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+
+    match cmd.spawn() {
+        Ok(_) => {
+            println!(
+                "Successfully launched external editor: {} with args: {:?}",
+                program, args
+            );
+            Ok(())
+        }
+        Err(e) => Err(format!(
+            "Failed to open file in external editor '{}': {}",
+            program, e
+        )),
+    }
+}
+
+// File path of a loaded document, so only known documents can be opened
+fn document_file_path(document_id: &str) -> Result<String, String> {
+    with_repository(|repository| {
+        repository
+            .get(document_id)
+            .map(|document| document.file_path.clone())
+    })?
+    .flatten()
+    .ok_or_else(|| format!("Document not found: {}", document_id))
+}
+
+// Read a loaded document's file without holding any lock; writers compare it with the
+// repository under the lock, so a change in between is caught there
+fn read_document_file(document_id: &str) -> Result<String, String> {
+    let file_path = document_file_path(document_id)?;
+    fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file {}: {}", file_path, e))
+}
+
+// Read the files of the documents `select` picks without holding any lock, by document id,
+// for commands that rewrite many files; they are compared with the repository under the lock
+fn read_document_files(
+    select: impl Fn(&OrgDocument) -> bool,
+) -> Result<HashMap<String, String>, String> {
+    let paths = with_repository(|repository| {
+        repository
+            .list()
+            .into_iter()
+            .filter(|document| select(document))
+            .map(|document| (document.id.clone(), document.file_path.clone()))
+            .collect::<Vec<_>>()
+    })?
+    .unwrap_or_default();
+    paths
+        .into_iter()
+        .map(|(id, file_path)| {
+            fs::read_to_string(&file_path)
+                .map(|content| (id, content))
+                .map_err(|e| format!("Failed to read file {}: {}", file_path, e))
+        })
+        .collect()
+}
+
+// Take a file read by `read_document_files`; a document missing from them was changed
+// after they were read
+fn take_document_file(
+    contents: &mut HashMap<String, String>,
+    document: &OrgDocument,
+) -> Result<String, String> {
+    contents
+        .remove(&document.id)
+        .ok_or_else(|| format!("{} changed on disk since it was loaded", document.file_path))
+}
+
+/// Show a document's file in Finder, Explorer, or the platform file manager
+#[tauri::command]
+#[specta::specta]
+pub async fn reveal_document_in_file_manager(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<(), String> {
+    let file_path = document_file_path(&document_id)?;
+    app_handle
+        .opener()
+        .reveal_item_in_dir(&file_path)
+        .map_err(|e| format!("Failed to reveal {}: {}", file_path, e))
+}
+
+/// Open a document's file with the application the OS associates with org files
+#[tauri::command]
+#[specta::specta]
+pub async fn open_with_system_default(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<(), String> {
+    let file_path = document_file_path(&document_id)?;
+    app_handle
+        .opener()
+        .open_path(&file_path, None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {}", file_path, e))
+}
+
+/// Save user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn save_user_settings(
+    app_handle: tauri::AppHandle,
+    settings: UserSettings,
+) -> Result<(), String> {
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Helper function to restart file monitoring with current settings
+async fn restart_file_monitoring_with_settings(
+    app_handle: &tauri::AppHandle,
+) -> Result<(), String> {
+    // Load current settings to check what files should be covered
+    let settings = SETTINGS_MANAGER
+        .load_settings(app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Stop current monitoring
+    let _ = stop_file_monitoring().await;
+
+    // Prune the repository to remove documents that are no longer covered
+    {
+        let monitor_lock = FILE_MONITOR
+            .lock()
+            .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+
+        if let Some(monitor) = monitor_lock.as_ref() {
+            let repository = monitor.get_repository();
+            let mut repository_lock = repository
+                .lock()
+                .map_err(|e| format!("Failed to lock repository: {}", e))?;
+
+            // Prune documents not covered by current settings
+            let removed_ids = repository_lock
+                .prune_uncovered_documents(|file_path| settings.is_file_covered(file_path));
+
+            if let Ok(mut pending_merges) = PENDING_MERGES.lock() {
+                for id in &removed_ids {
+                    pending_merges.remove(id);
+                }
+            }
+            if !removed_ids.is_empty() {
+                println!(
+                    "Pruned {} documents from repository: {:?}",
+                    removed_ids.len(),
+                    removed_ids
+                );
+            }
+        }
+    }
+
+    // Start monitoring with updated settings
+    let _ = start_file_monitoring(app_handle.clone()).await?;
+
+    Ok(())
+}
+
+/// Create a new org file from a file template and start monitoring it
+/// Without a template name the file only gets a #+TITLE from its file name
+/// Returns the id of the new document
+#[tauri::command]
+#[specta::specta]
+pub async fn create_org_file(
+    app_handle: tauri::AppHandle,
+    path: String,
+    template_name: Option<String>,
+) -> Result<String, String> {
+    let file_path = Path::new(&path);
+    if file_path.extension().and_then(|ext| ext.to_str()) != Some("org") {
+        return Err(format!("Not an org file: {}", path));
+    }
+    if file_path.exists() {
+        return Err(format!("File already exists: {}", path));
+    }
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let default_title = file_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let content = match &template_name {
+        Some(name) => settings
+            .get_file_template(name)
+            .ok_or_else(|| format!("Template not found: {}", name))?
+            .render(default_title),
+        None => format!("#+TITLE: {}\n", default_title),
+    };
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    write_monitored_file(&path, &content)?;
+
+    // Files outside the monitored paths are added as single monitored files
+    ensure_monitored(
+        &app_handle,
+        &mut settings,
+        &path,
+        MonitoredPath::file(path.clone()),
+    )
+    .await?;
+    reparse_new_file(file_path)?;
+    track_recent(&app_handle, &path, None, RecentAccess::Edited);
+
+    Ok(path)
+}
+
+// Add `monitored_path` to settings and restart monitoring if `file_path` is not covered yet
+async fn ensure_monitored(
+    app_handle: &tauri::AppHandle,
+    settings: &mut UserSettings,
+    file_path: &str,
+    monitored_path: MonitoredPath,
+) -> Result<(), String> {
+    if settings.is_file_covered(file_path) {
+        return Ok(());
+    }
+
+    settings
+        .add_monitored_path(monitored_path)
+        .map_err(|e| e.to_string())?;
+    SETTINGS_MANAGER
+        .save_settings(app_handle, settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    restart_file_monitoring_with_settings(app_handle).await
+}
+
+// Parse a file the app just wrote, so it can be opened before the watcher reports it
+fn reparse_new_file(path: &Path) -> Result<(), String> {
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    match monitor_lock.as_ref() {
+        Some(monitor) => monitor.reparse_file(path),
+        None => Ok(()),
+    }
+}
+
+// Locate the journal entry for a date, creating its file and headline as needed
+async fn journal_entry(
+    app_handle: &tauri::AppHandle,
+    date: NaiveDate,
+) -> Result<JournalEntry, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let config = settings.journal.clone();
+    config.validate()?;
+    let directory = config
+        .directory
+        .clone()
+        .ok_or_else(|| "Journal directory is not configured".to_string())?;
+
+    let week_start = settings.date_locale.first_weekday();
+    let path = Path::new(&directory).join(config.file_name(date, week_start));
+    let file_path = path.to_string_lossy().to_string();
+    let heading = config.heading(date);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read file {}: {}", file_path, e)),
+    };
+
+    let new_content = ensure_entry(&content, &heading);
+    if let Some(new_content) = &new_content {
+        fs::create_dir_all(&directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory, e))?;
+        write_monitored_file(&file_path, new_content)?;
+    }
+    ensure_monitored(
+        app_handle,
+        &mut settings,
+        &file_path,
+        MonitoredPath::directory(directory.clone()),
+    )
+    .await?;
+    reparse_new_file(&path)?;
+
+    // Map the entry line back to the headline id of the parsed document
+    let entry_line = find_entry_line(new_content.as_deref().unwrap_or(&content), &heading)
+        .map(|index| index + 1);
+    let headline_id = with_repository(|repository| {
+        let document = repository.get(&file_path)?;
+        headline_line_numbers(document)
+            .into_iter()
+            .find(|(_, line)| Some(*line) == entry_line)
+            .map(|(id, _)| id)
+    })?
+    .flatten()
+    .ok_or_else(|| format!("Journal entry not found in {}", file_path))?;
+    if new_content.is_some() {
+        track_recent(
+            app_handle,
+            &file_path,
+            Some(&headline_id),
+            RecentAccess::Edited,
+        );
+    }
+
+    Ok(JournalEntry {
+        document_id: file_path.clone(),
+        headline_id,
+        file_path,
+        created: new_content.is_some(),
+    })
+}
+
+/// Find the journal entry for a date (YYYY-MM-DD), creating its file and headline if needed
+#[tauri::command]
+#[specta::specta]
+pub async fn open_or_create_journal_entry(
+    app_handle: tauri::AppHandle,
+    date: String,
+) -> Result<JournalEntry, String> {
+    let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{}': {}", date, e))?;
+    journal_entry(&app_handle, date).await
+}
+
+/// List the source blocks of a document with their header arguments and results
+/// Blocks are only read, never executed
+#[tauri::command]
+#[specta::specta]
+pub async fn get_source_blocks(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<Option<Vec<SourceBlock>>, String> {
+    open_document(&app_handle, &document_id).await?;
+    let blocks =
+        with_repository(|repository| repository.get(&document_id).map(find_source_blocks))?;
+    Ok(blocks.flatten())
+}
+
+/// List every file that source blocks are tangled into, across documents
+#[tauri::command]
+#[specta::specta]
+pub async fn list_tangle_targets() -> Result<Vec<TangleTarget>, String> {
+    let reloaded = read_evicted_documents().await?;
+    let targets = with_full_documents(reloaded, collect_tangle_targets)?;
+    Ok(targets.unwrap_or_default())
+}
+
+/// List links to image files in a document, to show them inline via the org-image protocol
+#[tauri::command]
+#[specta::specta]
+pub async fn get_image_links(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<Option<Vec<ImageLink>>, String> {
+    open_document(&app_handle, &document_id).await?;
+    let images = with_repository(|repository| repository.get(&document_id).map(find_image_links))?;
+    Ok(images.flatten())
+}
+
+/// Serve an image for the org-image protocol, from a percent-encoded absolute path
+/// Only images inside the directories of loaded documents are served. A `size=<pixels>`
+/// query asks for a PNG thumbnail that fits in a square of that size; SVG is served with
+/// scripts disabled.
+pub fn serve_image(uri_path: &str, query: Option<&str>) -> tauri::http::Response<Vec<u8>> {
+    let respond = |status: u16, mime_type: &str, body: Vec<u8>| {
+        let mut response = tauri::http::Response::builder()
+            .status(status)
+            .header("Content-Type", mime_type)
+            .header("X-Content-Type-Options", "nosniff");
+        if mime_type == "image/svg+xml" {
+            response = response.header("Content-Security-Policy", "script-src 'none'");
+        }
+        response.body(body).unwrap_or_default()
+    };
+
+    let Some(path) = percent_decode(uri_path.trim_start_matches('/')) else {
+        return respond(400, "text/plain", b"Invalid path".to_vec());
+    };
+    let path = PathBuf::from(path);
+
+    let roots: Vec<PathBuf> = with_repository(|repository| {
+        repository
+            .list()
+            .iter()
+            .filter_map(|document| Path::new(&document.file_path).parent())
+            .map(Path::to_path_buf)
+            .collect()
+    })
+    .ok()
+    .flatten()
+    .unwrap_or_default();
+    if !is_image_allowed(&path, &roots) {
+        return respond(403, "text/plain", b"Forbidden".to_vec());
+    }
+
+    let size = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|pair| pair.strip_prefix("size="));
+    let size = match size.map(str::parse::<u32>) {
+        Some(Ok(size)) if size > 0 => Some(size.min(MAX_THUMBNAIL_SIZE)),
+        Some(_) => return respond(400, "text/plain", b"Invalid size".to_vec()),
+        None => None,
+    };
+
+    match (fs::read(&path), image_mime_type(&path)) {
+        (Ok(bytes), Some(mime_type)) => match size.and_then(|size| thumbnail(&bytes, size)) {
+            Some(png) => respond(200, "image/png", png),
+            None => respond(200, mime_type, bytes),
+        },
+        _ => respond(404, "text/plain", b"Not found".to_vec()),
+    }
+}
+
+/// Get a headline's title and body as styled text runs, so markup is rendered instead of shown raw
+#[tauri::command]
+#[specta::specta]
+pub async fn get_headline_rich_text(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+) -> Result<Option<RichText>, String> {
+    open_document(&app_handle, &document_id).await?;
+    let rich_text = with_repository(|repository| {
+        let headline = repository.get(&document_id)?.find_headline(&headline_id)?;
+        Some(RichText {
+            title: parse_text_runs(&headline.title.raw),
+            paragraphs: parse_paragraphs(&headline.content),
+        })
+    })?;
+    Ok(rich_text.flatten())
+}
+
+/// Get a headline's body as sanitized HTML, filtered through the configured allowlist
+#[tauri::command]
+#[specta::specta]
+pub async fn get_headline_html(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+) -> Result<Option<String>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    open_document(&app_handle, &document_id).await?;
+    let html = with_repository(|repository| {
+        let headline = repository.get(&document_id)?.find_headline(&headline_id)?;
+        Some(render_content_html(
+            &headline.content,
+            &settings.html_sanitize,
+        ))
+    })?;
+    Ok(html.flatten())
+}
+
+/// Share a read-only view of a document on a temporary localhost server, for showing
+/// meeting notes during a call; the page reloads itself when the document changes
+/// Replaces any document already being shared
+#[tauri::command]
+#[specta::specta]
+pub async fn start_sharing(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<ShareSession, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    open_document(&app_handle, &document_id).await?;
+    if with_repository(|repository| repository.get(&document_id).is_none())?.unwrap_or(true) {
+        return Err(format!("Document not found: {}", document_id));
+    }
+
+    let config = settings.html_sanitize;
+    let shared_id = document_id.clone();
+    let server = ShareServer::start(&document_id, move || {
+        with_repository(|repository| {
+            // Keep the shared document recently opened so its body is not evicted
+            repository.mark_opened(&shared_id);
+            repository
+                .get(&shared_id)
+                .map(|document| shared_page(document, &config))
+        })
+        .ok()
+        .flatten()
+        .flatten()
+    })
+    .await
+    .map_err(|e| format!("Failed to start share server: {}", e))?;
+
+    let session = server.session.clone();
+    let previous = SHARE_SERVER
+        .lock()
+        .map_err(|e| format!("Failed to lock share server: {}", e))?
+        .replace(server);
+    if let Some(previous) = previous {
+        previous.stop();
+    }
+    Ok(session)
+}
+
+/// Stop sharing the shared document, if any
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_sharing() -> Result<(), String> {
+    let server = SHARE_SERVER
+        .lock()
+        .map_err(|e| format!("Failed to lock share server: {}", e))?
+        .take();
+    if let Some(server) = server {
+        server.stop();
+    }
+    Ok(())
+}
+
+// Location of the plugin directory in the app data directory
+fn plugin_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(PLUGIN_DIR_NAME))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+// Run `f` against the loaded plugins on the blocking thread pool, since plugin calls
+// can take as long as their fuel allows
+async fn with_plugins<T: Send + 'static>(
+    f: impl FnOnce(&PluginHost) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let host = PLUGINS
+        .lock()
+        .map_err(|e| format!("Failed to lock plugins: {}", e))?
+        .clone();
+    tauri::async_runtime::spawn_blocking(move || f(&host))
+        .await
+        .map_err(|e| format!("Plugin call failed: {}", e))?
+}
+
+/// Load the plugins in the plugin directory, replacing the ones loaded before
+/// Returns every plugin found, with the error for any that failed to load
+#[tauri::command]
+#[specta::specta]
+pub async fn reload_plugins(app_handle: tauri::AppHandle) -> Result<Vec<PluginInfo>, String> {
+    let dir = plugin_dir(&app_handle)?;
+    let host = tauri::async_runtime::spawn_blocking(move || PluginHost::load_dir(&dir))
+        .await
+        .map_err(|e| format!("Failed to load plugins: {}", e))?;
+    let plugins = host.plugins();
+    // Calls already running keep the host they started with
+    *PLUGINS
+        .lock()
+        .map_err(|e| format!("Failed to lock plugins: {}", e))? = Arc::new(host);
+    Ok(plugins)
+}
+
+/// Load plugins in the background at startup
+pub fn load_plugins(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = reload_plugins(app_handle).await {
+            eprintln!("Failed to load plugins: {}", e);
+        }
+    });
+}
+
+/// List the loaded plugins and the functions they provide
+#[tauri::command]
+#[specta::specta]
+pub async fn list_plugins() -> Result<Vec<PluginInfo>, String> {
+    with_plugins(|host| Ok(host.plugins())).await
+}
+
+/// Find the headlines of all documents that a plugin query function matches
+#[tauri::command]
+#[specta::specta]
+pub async fn run_plugin_query(
+    plugin_id: String,
+    function_name: String,
+    argument: String,
+) -> Result<Vec<HeadlineRef>, String> {
+    let reloaded = read_evicted_documents().await?;
+    let headlines = with_full_documents(reloaded, |documents| {
+        documents
+            .iter()
+            .filter(|document| !document.missing)
+            .flat_map(|document| {
+                flatten_headlines(&document.headlines)
+                    .into_iter()
+                    .map(move |headline| PluginHeadline::new(document, headline))
+            })
+            .collect::<Vec<_>>()
+    })?
+    .unwrap_or_default();
+
+    with_plugins(move |host| {
+        let matches = host.query(&plugin_id, &function_name, &argument, &headlines)?;
+        Ok(headlines
+            .into_iter()
+            .zip(matches)
+            .filter(|(_, matched)| *matched)
+            .map(|(headline, _)| HeadlineRef {
+                document_id: headline.document_id,
+                headline_id: headline.headline_id,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Export a document with a plugin exporter function
+#[tauri::command]
+#[specta::specta]
+pub async fn export_with_plugin(
+    app_handle: tauri::AppHandle,
+    plugin_id: String,
+    function_name: String,
+    document_id: String,
+) -> Result<String, String> {
+    open_document(&app_handle, &document_id).await?;
+    let document =
+        with_repository(|repository| repository.get(&document_id).map(PluginDocument::new))?
+            .flatten()
+            .ok_or_else(|| format!("Document not found: {}", document_id))?;
+
+    with_plugins(move |host| host.export(&plugin_id, &function_name, &document)).await
+}
+
+/// Update the allowlist used when rendering org content as HTML
+#[tauri::command]
+#[specta::specta]
+pub async fn set_html_sanitize_config(
+    app_handle: tauri::AppHandle,
+    config: HtmlSanitizeConfig,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_html_sanitize_config(config);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Find LaTeX fragments and entities in the body of a headline, or of the whole document
+/// Span offsets are relative to that content
+#[tauri::command]
+#[specta::specta]
+pub async fn get_latex_spans(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: Option<String>,
+) -> Result<Option<Vec<LatexSpan>>, String> {
+    open_document(&app_handle, &document_id).await?;
+    let spans = with_repository(|repository| {
+        let document = repository.get(&document_id)?;
+        let content = match &headline_id {
+            Some(headline_id) => &document.find_headline(headline_id)?.content,
+            None => &document.content,
+        };
+        Some(find_latex_spans(content))
+    })?;
+    Ok(spans.flatten())
+}
+
+/// Get the bibliographies of a document and its citations, located by headline
+#[tauri::command]
+#[specta::specta]
+pub async fn get_document_citations(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+) -> Result<Option<DocumentCitations>, String> {
+    open_document(&app_handle, &document_id).await?;
+    let citations =
+        with_repository(|repository| repository.get(&document_id).map(document_citations))?;
+    Ok(citations.flatten())
+}
+
+/// List every cited key across documents, with how often and where it is cited
+#[tauri::command]
+#[specta::specta]
+pub async fn list_cited_keys() -> Result<Vec<CitedKey>, String> {
+    let reloaded = read_evicted_documents().await?;
+    let keys = with_full_documents(reloaded, collect_cited_keys)?;
+    Ok(keys.unwrap_or_default())
+}
+
+/// List contacts from the contacts files, filtered by an optional search query
+#[tauri::command]
+#[specta::specta]
+pub async fn list_contacts(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+) -> Result<Vec<Contact>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let contacts = with_repository(|repository| {
+        collect_contacts(&repository.list(), &settings.contacts_files)
+    })?
+    .unwrap_or_default();
+
+    Ok(match query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => contacts
+            .into_iter()
+            .filter(|contact| contact.matches(query))
+            .collect(),
+        _ => contacts,
+    })
+}
+
+/// Set which files are searched for contacts
+#[tauri::command]
+#[specta::specta]
+pub async fn set_contacts_files(
+    app_handle: tauri::AppHandle,
+    files: Vec<String>,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_contacts_files(files);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Open a mailto: or tel: link with the system handler
+#[tauri::command]
+#[specta::specta]
+pub async fn open_contact_link(app_handle: tauri::AppHandle, url: String) -> Result<(), String> {
+    if !is_contact_link(&url) {
+        return Err(format!("Not a mailto: or tel: link: {}", url));
+    }
+
+    app_handle
+        .opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| format!("Failed to open link: {}", e))
+}
+
+/// Set where journal files are kept and how they are named
+#[tauri::command]
+#[specta::specta]
+pub async fn set_journal_config(
+    app_handle: tauri::AppHandle,
+    config: JournalConfig,
+) -> Result<UserSettings, String> {
+    config.validate()?;
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_journal_config(config);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+// Parse an optional YYYY-MM-DD date, defaulting to today
+fn parse_date_or_today(date: Option<String>) -> Result<NaiveDate, String> {
+    match date {
+        Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date '{}': {}", date, e)),
+        None => Ok(time_zone::today()),
+    }
+}
+
+// Compute the new content of the target file with captured text filed in it
+// Returns the target file path and its new content
+async fn file_at_target(
+    app_handle: &tauri::AppHandle,
+    target: CaptureTarget,
+    text: &str,
+) -> Result<(String, String), String> {
+    match target {
+        CaptureTarget::File { path } => {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+            let content = file_entry(&content, EntryPlacement::End, text)
+                .ok_or_else(|| format!("Failed to file the entry in {}", path))?;
+            Ok((path, content))
+        }
+        CaptureTarget::Journal { date } => {
+            let date = parse_date_or_today(date)?;
+            let entry = journal_entry(app_handle, date).await?;
+            let heading = SETTINGS_MANAGER
+                .load_settings(app_handle)
+                .await
+                .map_err(|e| e.to_string())?
+                .journal
+                .heading(date);
+            let content = fs::read_to_string(&entry.file_path)
+                .map_err(|e| format!("Failed to read file {}: {}", entry.file_path, e))?;
+            let content = file_entry(&content, EntryPlacement::Under(&heading), text)
+                .ok_or_else(|| format!("Journal entry not found in {}", entry.file_path))?;
+            Ok((entry.file_path, content))
+        }
+        CaptureTarget::Datetree { path, date } => {
+            let date = parse_date_or_today(date)?;
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+            let content = file_entry(&content, EntryPlacement::Datetree(date), text)
+                .ok_or_else(|| format!("Failed to file the entry in {}", path))?;
+            Ok((path, content))
+        }
+    }
+}
+
+// Write a file through the monitor so the change is not reported back as external
+fn write_monitored_file(path: &str, content: &str) -> Result<(), String> {
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+    monitor.write_file(Path::new(path), content)
+}
+
+// Write a file through the monitor exactly as given, leaving headline layout alone
+fn restore_monitored_file(path: &str, content: &str) -> Result<(), String> {
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+    monitor.restore_file(Path::new(path), content)
+}
+
+/// File captured text at a capture target
+/// Returns the id of the document the text was added to
+#[tauri::command]
+#[specta::specta]
+pub async fn capture(
+    app_handle: tauri::AppHandle,
+    target: CaptureTarget,
+    text: String,
+) -> Result<String, String> {
+    let text = with_plugins(move |host| host.process_capture(&text)).await?;
+    let (path, content) = file_at_target(&app_handle, target, &text).await?;
+    write_monitored_file(&path, &content)?;
+    if let Ok(settings) = SETTINGS_MANAGER.load_settings(&app_handle).await {
+        let variables = vec![
+            ("ORGX_FILE".to_string(), path.clone()),
+            ("ORGX_DOCUMENT_ID".to_string(), path.clone()),
+            ("ORGX_TEXT".to_string(), text),
+        ];
+        spawn_hook(&settings.hooks, HookEvent::Capture, variables);
+    }
+    track_recent(&app_handle, &path, None, RecentAccess::Edited);
+    Ok(path)
+}
+
+/// Import a Readwise JSON or CSV highlights export into a notes file
+/// Each highlight is filed under a headline for its book or article; highlights already
+/// in the file are skipped by id. The notes file is created if it does not exist.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_highlights(
+    app_handle: tauri::AppHandle,
+    export_path: String,
+    notes_path: String,
+) -> Result<HighlightImportSummary, String> {
+    let export = fs::read_to_string(&export_path)
+        .map_err(|e| format!("Failed to read file {}: {}", export_path, e))?;
+    let highlights = parse_highlights(&export)?;
+    let content = match fs::read_to_string(&notes_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read file {}: {}", notes_path, e)),
+    };
+
+    let (content, summary) = file_highlights(&content, &highlights);
+    if summary.imported > 0 {
+        write_monitored_file(&notes_path, &content)?;
+        track_recent(&app_handle, &notes_path, None, RecentAccess::Edited);
+    }
+    Ok(summary)
+}
+
+/// File a browser bookmark in the bookmark inbox as a `TODO Read:` entry tagged `reading`
+/// Returns the id of the inbox document
+#[tauri::command]
+#[specta::specta]
+pub async fn capture_bookmark(
+    app_handle: tauri::AppHandle,
+    bookmark: Bookmark,
+) -> Result<String, String> {
+    let inbox = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?
+        .bookmarks
+        .inbox
+        .filter(|inbox| !inbox.trim().is_empty())
+        .ok_or_else(|| "Choose an inbox file for bookmarks".to_string())?;
+    capture(
+        app_handle,
+        CaptureTarget::File { path: inbox },
+        bookmark_entry(&bookmark),
+    )
+    .await
+}
+
+/// Propose tasks from the notes under a meeting headline: its unchecked checkboxes and
+/// lines like `TODO: ...` or `Action: ...`
+#[tauri::command]
+#[specta::specta]
+pub async fn extract_action_items(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+) -> Result<Vec<ActionItem>, String> {
+    // The notes are needed in full, so bring back an evicted document's content
+    open_document(&app_handle, &document_id).await?;
+    with_repository(|repository| {
+        let document = repository
+            .get(&document_id)
+            .ok_or_else(|| format!("Document not found: {}", document_id))?;
+        find_action_items(document, &headline_id)
+            .ok_or_else(|| format!("Headline not found: {}", headline_id))
+    })?
+    .ok_or_else(|| "File monitoring is not running".to_string())?
+}
+
+/// File accepted action items as TODO headlines at a capture target, each linking back to
+/// the meeting headline they came from
+/// Returns the id of the document the tasks were added to
+#[tauri::command]
+#[specta::specta]
+pub async fn accept_action_items(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    items: Vec<String>,
+    target: CaptureTarget,
+) -> Result<String, String> {
+    if items.is_empty() {
+        return Err("No action items to accept".to_string());
+    }
+    if items
+        .iter()
+        .any(|item| item.trim().is_empty() || item.contains('\n'))
+    {
+        return Err("Action items must be a single non-empty line".to_string());
+    }
+
+    let link = with_repository(|repository| {
+        let document = repository.get(&document_id)?;
+        document
+            .find_headline(&headline_id)
+            .map(|headline| meeting_link(document, headline))
+    })?
+    .flatten()
+    .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+    capture(app_handle, target, action_item_entries(&items, &link)).await
+}
+
+// Stop the bookmark endpoint and start it again with the saved settings, if enabled
+async fn restart_bookmark_server(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let config = SETTINGS_MANAGER
+        .load_settings(app_handle)
+        .await
+        .map_err(|e| e.to_string())?
+        .bookmarks;
+    let mut server = BOOKMARK_SERVER.lock().await;
+    if let Some(running) = server.take() {
+        running.stop().await;
+    }
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let handle = app_handle.clone();
+    let started = BookmarkServer::start(&config, move |bookmark| {
+        let handle = handle.clone();
+        Box::pin(async move { capture_bookmark(handle, bookmark).await.map(|_| ()) })
+    })
+    .await
+    .map_err(|e| {
+        format!(
+            "Failed to start the bookmark endpoint on port {}: {}",
+            config.port, e
+        )
+    })?;
+    *server = Some(started);
+    Ok(())
+}
+
+/// Start the bookmark endpoint in the background, if it is turned on
+pub fn start_bookmark_server(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = restart_bookmark_server(&app_handle).await {
+            eprintln!("{}", e);
+        }
+    });
+}
+
+/// Update the bookmark endpoint and restart it
+/// A token is generated the first time the endpoint is turned on
+#[tauri::command]
+#[specta::specta]
+pub async fn set_bookmark_config(
+    app_handle: tauri::AppHandle,
+    mut config: BookmarkConfig,
+) -> Result<UserSettings, String> {
+    if config.enabled && config.token.trim().is_empty() {
+        config.token = uuid::Uuid::new_v4().simple().to_string();
+    }
+    config.validate()?;
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_bookmark_config(config);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    restart_bookmark_server(&app_handle).await?;
+    Ok(settings)
+}
+
+/// Get the `javascript:` bookmarklet that sends the current browser page to the bookmark
+/// endpoint
+#[tauri::command]
+#[specta::specta]
+pub async fn get_bookmarklet(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let config = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?
+        .bookmarks;
+    if !config.enabled {
+        return Err("The bookmark endpoint is turned off".to_string());
+    }
+    Ok(config.bookmarklet())
+}
+
+/// Move a headline and its subtree to a capture target, such as a date tree
+/// Returns the id of the document the headline was moved to
+#[tauri::command]
+#[specta::specta]
+pub async fn refile_headline(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+    target: CaptureTarget,
+) -> Result<String, String> {
+    let content = read_document_file(&document_id)?;
+    let cut = {
+        let monitor_lock = FILE_MONITOR
+            .lock()
+            .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+        let monitor = monitor_lock
+            .as_ref()
+            .ok_or_else(|| "File monitoring is not running".to_string())?;
+        cut_subtree(monitor, &document_id, &headline_id, content)?
+    };
+
+    // Remove the subtree first, so refiling within the same file sees the cut content
+    write_monitored_file(&cut.file_path, &cut.remaining)?;
+    let filed = match file_at_target(&app_handle, target, &cut.removed).await {
+        Ok(filed) => filed,
+        Err(e) => {
+            write_monitored_file(&cut.file_path, &cut.content)?;
+            return Err(e);
+        }
+    };
+    if let Err(e) = write_monitored_file(&filed.0, &filed.1) {
+        write_monitored_file(&cut.file_path, &cut.content)?;
+        return Err(e);
+    }
+
+    track_recent(&app_handle, &filed.0, None, RecentAccess::Edited);
+    Ok(filed.0)
+}
+
+// Resolve favorites to their current headlines, dropping favorites whose document
+// or headline is gone and following renamed documents
+// Favorites are only dropped once the startup scan has loaded every file.
+async fn resolve_favorites(
+    app_handle: &tauri::AppHandle,
+    mut settings: UserSettings,
+) -> Result<Vec<FavoriteHeadline>, String> {
+    let resolved = with_repository(|repository| {
+        settings
+            .favorites
+            .iter()
+            .map(|favorite| {
+                repository
+                    .resolve_id(&favorite.document_id)
+                    .and_then(|document_id| repository.get(&document_id))
+                    .filter(|document| !document.missing)
+                    .and_then(|document| favorite.resolve(document))
+            })
+            .collect::<Vec<_>>()
+    })?;
+    // Favorites cannot be resolved, or cleaned up, before documents are loaded
+    let Some(resolved) = resolved else {
+        return Ok(Vec::new());
+    };
+
+    let current: Vec<Favorite> = settings
+        .favorites
+        .iter()
+        .zip(&resolved)
+        .filter_map(|(favorite, headline)| {
+            headline.as_ref().map(|headline| Favorite {
+                document_id: headline.document_id.clone(),
+                org_id: favorite.org_id.clone(),
+                outline_path: headline.outline_path.clone(),
+            })
+        })
+        .collect();
+    if current != settings.favorites && INITIAL_SCAN_COMPLETE.load(Ordering::SeqCst) {
+        settings.favorites = current;
+        SETTINGS_MANAGER
+            .save_settings(app_handle, &settings)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(resolved.into_iter().flatten().collect())
+}
+
+// Build a favorite for a headline of a loaded document
+fn favorite_for_headline(document_id: &str, headline_id: &str) -> Result<Favorite, String> {
+    with_repository(|repository| {
+        repository
+            .get(document_id)
+            .and_then(|document| Favorite::for_headline(document, headline_id))
+    })?
+    .flatten()
+    .ok_or_else(|| format!("Headline not found: {}", headline_id))
+}
+
+/// List pinned headlines in the order they were added
+/// Favorites whose headline no longer exists are removed from the settings once every
+/// monitored file has been loaded
+#[tauri::command]
+#[specta::specta]
+pub async fn list_favorites(app_handle: tauri::AppHandle) -> Result<Vec<FavoriteHeadline>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    resolve_favorites(&app_handle, settings).await
+}
+
+/// Pin a headline, returning the updated favorites
+#[tauri::command]
+#[specta::specta]
+pub async fn add_favorite(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+) -> Result<Vec<FavoriteHeadline>, String> {
+    let favorite = favorite_for_headline(&document_id, &headline_id)?;
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if settings.add_favorite(favorite) {
+        SETTINGS_MANAGER
+            .save_settings(&app_handle, &settings)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    resolve_favorites(&app_handle, settings).await
+}
+
+/// Unpin a headline, returning the updated favorites
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_favorite(
+    app_handle: tauri::AppHandle,
+    document_id: String,
+    headline_id: String,
+) -> Result<Vec<FavoriteHeadline>, String> {
+    let favorite = favorite_for_headline(&document_id, &headline_id)?;
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !settings.remove_favorite(&favorite) {
+        return Err(format!("Headline is not a favorite: {}", headline_id));
+    }
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    resolve_favorites(&app_handle, settings).await
+}
+
+/// Add a file template, replacing any template with the same name
+#[tauri::command]
+#[specta::specta]
+pub async fn save_file_template(
+    app_handle: tauri::AppHandle,
+    template: FileTemplate,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings
+        .save_file_template(template)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Remove a file template by name
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_file_template(
+    app_handle: tauri::AppHandle,
+    name: String,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !settings.remove_file_template(&name) {
+        return Err(format!("Template not found: {}", name));
+    }
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Add a monitored path to settings
+#[tauri::command]
+#[specta::specta]
+pub async fn add_monitored_path(
+    app_handle: tauri::AppHandle,
+    path: MonitoredPath,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings
+        .add_monitored_path(path)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Restart monitoring to reflect changes
+    restart_file_monitoring_with_settings(&app_handle).await?;
+
+    Ok(settings)
+}
+
+/// Remove a monitored path from settings
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_monitored_path(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !settings.remove_monitored_path(&path) {
+        return Err(format!("Path not found: {}", path));
+    }
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Restart monitoring to reflect changes
+    restart_file_monitoring_with_settings(&app_handle).await?;
+
+    Ok(settings)
+}
+
+/// Update a monitored path in settings
+#[tauri::command]
+#[specta::specta]
+pub async fn update_monitored_path(
+    app_handle: tauri::AppHandle,
+    old_path: String,
+    new_path: MonitoredPath,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings
+        .update_monitored_path(&old_path, new_path)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Set whether parsing is enabled for a monitored path
+#[tauri::command]
+#[specta::specta]
+pub async fn set_path_parse_enabled(
+    app_handle: tauri::AppHandle,
+    path: String,
+    parse_enabled: bool,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings
+        .set_path_parse_enabled(&path, parse_enabled)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Restart monitoring to reflect changes
+    restart_file_monitoring_with_settings(&app_handle).await?;
+
+    Ok(settings)
+}
+
+/// Clear user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_user_settings(app_handle: tauri::AppHandle) -> Result<(), String> {
+    SETTINGS_MANAGER
+        .clear_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write settings, favorites, reminders, recent items and the trash to one file, for
+/// moving to another machine
+///
+/// The issue tracker and bookmark tokens are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_app_state(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<AppStateSummary, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let bundle = AppStateBundle {
+        version: APP_STATE_VERSION,
+        exported_at: time_zone::now_rfc3339(),
+        settings: serde_json::to_value(settings.without_secrets()).map_err(|e| e.to_string())?,
+        reminders: load_reminders(&reminders_path(&app_handle)?)?,
+        recent: load_recent(&recent_path(&app_handle)?)?,
+        trash: load_trash(&trash_path(&app_handle)?)?,
+    };
+    write_bundle(Path::new(&path), &bundle)?;
+    Ok(bundle.summary())
+}
+
+/// Replace settings, favorites, reminders, recent items and the trash with those in a file
+/// written by `export_app_state`, then restart monitoring with the imported paths
+///
+/// Each section is checked as its setter would; tokens missing from the file are kept.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_app_state(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<AppStateSummary, String> {
+    let bundle = read_bundle(Path::new(&path))?;
+    let mut settings = SETTINGS_MANAGER
+        .settings_from_value(bundle.settings.clone())
+        .map_err(|e| e.to_string())?;
+    let current = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    settings.keep_secrets(&current);
+    if settings.bookmarks.enabled && settings.bookmarks.token.trim().is_empty() {
+        settings.bookmarks.token = uuid::Uuid::new_v4().simple().to_string();
+    }
+    let zone = settings
+        .time_zone
+        .as_deref()
+        .map(parse_time_zone)
+        .transpose()?;
+    settings.validate_sections()?;
+
+    {
+        let _guard = REMINDERS_LOCK
+            .lock()
+            .map_err(|e| format!("Failed to lock reminders: {}", e))?;
+        save_reminders(&reminders_path(&app_handle)?, &bundle.reminders)?;
+    }
+    {
+        let _guard = RECENT_LOCK
+            .lock()
+            .map_err(|e| format!("Failed to lock recent items: {}", e))?;
+        save_recent(&recent_path(&app_handle)?, &bundle.recent)?;
+    }
+    save_trash(&trash_path(&app_handle)?, &bundle.trash)?;
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    set_assumed_time_zone(zone);
+    set_style_overrides(settings.status_styles);
+    restart_file_monitoring_with_settings(&app_handle).await?;
+    Ok(bundle.summary())
+}
+
+/// Get current TODO keywords configuration from user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_user_todo_keywords(app_handle: tauri::AppHandle) -> Result<TodoKeywords, String> {
+    let current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(current_settings.get_todo_keywords().clone())
+}
+
+/// Get current custom headline properties from user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_custom_properties(app_handle: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(current_settings.get_custom_properties().clone())
+}
+
+/// Add a custom headline property
+#[tauri::command]
+#[specta::specta]
+pub async fn add_custom_property(
+    app_handle: tauri::AppHandle,
+    property: String,
+) -> Result<Vec<String>, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings
+        .add_custom_property(property)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Trigger re-parsing of all documents with updated settings
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after custom property change: {}",
+            e
+        );
+    }
+
+    Ok(current_settings.get_custom_properties().clone())
 }
 
 /// Edit a custom headline property by index
 #[tauri::command]
 #[specta::specta]
-pub async fn edit_custom_property(
+pub async fn edit_custom_property(
+    app_handle: tauri::AppHandle,
+    index: u32,
+    new_property: String,
+) -> Result<Vec<String>, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings
+        .edit_custom_property(index as usize, new_property)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Trigger re-parsing of all documents with updated settings
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after custom property change: {}",
+            e
+        );
+    }
+
+    Ok(current_settings.get_custom_properties().clone())
+}
+
+/// Remove a custom headline property by index
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_custom_property(
+    app_handle: tauri::AppHandle,
+    index: u32,
+) -> Result<Vec<String>, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings
+        .remove_custom_property(index as usize)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Trigger re-parsing of all documents with updated settings
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after custom property change: {}",
+            e
+        );
+    }
+
+    Ok(current_settings.get_custom_properties().clone())
+}
+
+/// Declare the type of a custom property (string, number, duration or date)
+#[tauri::command]
+#[specta::specta]
+pub async fn set_custom_property_type(
+    app_handle: tauri::AppHandle,
+    property: String,
+    property_type: PropertyType,
+) -> Result<UserSettings, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings
+        .set_custom_property_type(&property, property_type)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(current_settings)
+}
+
+/// Sort headlines by a property according to its declared type
+/// Headlines without a valid value are placed last
+#[tauri::command]
+#[specta::specta]
+pub async fn sort_headlines_by_property(
+    app_handle: tauri::AppHandle,
+    headlines: Vec<HeadlineRef>,
+    property: String,
+    descending: bool,
+) -> Result<Vec<HeadlineRef>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let property_type = settings.get_custom_property_type(&property);
+
+    let sorted = with_repository(|repository| {
+        sort_by_property(headlines.clone(), property_type, descending, |headline| {
+            headline_property(repository, headline, &property)
+        })
+    })?;
+    Ok(sorted.unwrap_or(headlines))
+}
+
+/// Total a number or duration property over headlines, formatted for display
+/// Returns None for string and date properties
+#[tauri::command]
+#[specta::specta]
+pub async fn sum_property(
+    app_handle: tauri::AppHandle,
+    headlines: Vec<HeadlineRef>,
+    property: String,
+) -> Result<Option<String>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let property_type = settings.get_custom_property_type(&property);
+
+    let total = with_repository(|repository| {
+        let values: Vec<String> = headlines
+            .iter()
+            .filter_map(|headline| headline_property(repository, headline, &property))
+            .collect();
+        sum_values(property_type, values.iter().map(String::as_str)).map(|total| total.display())
+    })?;
+    Ok(total.flatten())
+}
+
+fn headline_property(
+    repository: &OrgDocumentRepository,
+    headline: &HeadlineRef,
+    property: &str,
+) -> Option<String> {
+    repository
+        .get(&headline.document_id)?
+        .find_headline(&headline.headline_id)?
+        .title
+        .get_property_ignore_case(property)
+        .map(|value| value.to_string())
+}
+
+fn headline_urgency(
+    repository: &OrgDocumentRepository,
+    headline: &HeadlineRef,
+    weights: &UrgencyWeights,
+    today: NaiveDate,
+) -> Option<f64> {
+    let document = repository.get(&headline.document_id)?;
+    let found = document.find_headline(&headline.headline_id)?;
+    task_urgency(document, found, weights, today)
+}
+
+/// Get the urgency of each headline, in the order given
+/// Headlines that are not open tasks have no urgency
+#[tauri::command]
+#[specta::specta]
+pub async fn get_urgency_scores(
+    app_handle: tauri::AppHandle,
+    headlines: Vec<HeadlineRef>,
+) -> Result<Vec<Option<f64>>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let today = time_zone::today();
+
+    let scores = with_repository(|repository| {
+        headlines
+            .iter()
+            .map(|headline| {
+                headline_urgency(repository, headline, &settings.urgency_weights, today)
+            })
+            .collect()
+    })?;
+    Ok(scores.unwrap_or_else(|| vec![None; headlines.len()]))
+}
+
+/// Sort headlines by urgency, for the urgency table column
+/// Headlines that are not open tasks are placed last
+#[tauri::command]
+#[specta::specta]
+pub async fn sort_headlines_by_urgency(
+    app_handle: tauri::AppHandle,
+    headlines: Vec<HeadlineRef>,
+    descending: bool,
+) -> Result<Vec<HeadlineRef>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let today = time_zone::today();
+
+    let sorted = with_repository(|repository| {
+        let mut scored: Vec<(Option<f64>, HeadlineRef)> = headlines
+            .iter()
+            .map(|headline| {
+                let score =
+                    headline_urgency(repository, headline, &settings.urgency_weights, today);
+                (score, headline.clone())
+            })
+            .collect();
+        scored.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) if descending => b.total_cmp(a),
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        scored.into_iter().map(|(_, headline)| headline).collect()
+    })?;
+    Ok(sorted.unwrap_or(headlines))
+}
+
+/// Get the most urgent open tasks across all documents
+#[tauri::command]
+#[specta::specta]
+pub async fn get_most_urgent_tasks(
+    app_handle: tauri::AppHandle,
+    limit: u32,
+) -> Result<Vec<UrgentTask>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let today = time_zone::today();
+
+    let mut tasks = with_repository(|repository| {
+        collect_urgent_tasks(&repository.list(), &settings.urgency_weights, today)
+    })?
+    .unwrap_or_default();
+    tasks.truncate(limit as usize);
+    Ok(tasks)
+}
+
+/// Suggest next actions for a GTD panel: the most urgent open tasks that are not
+/// waiting or blocked, limited to the given `@context` tags (all contexts when empty)
+/// Headlines with ignored tags are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_next_actions(
+    app_handle: tauri::AppHandle,
+    context_tags: Vec<String>,
+    limit: u32,
+) -> Result<Vec<UrgentTask>, String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let today = time_zone::today();
+
+    let ignored = settings.ignored_tags.for_view(IgnoreView::NextActions);
+    let mut actions = with_repository(|repository| {
+        let mut actions = collect_next_actions(
+            &repository.list(),
+            &context_tags,
+            &settings.urgency_weights,
+            today,
+        );
+        retain_unignored(
+            &mut actions,
+            ignored,
+            |id| repository.get(id),
+            |action| (action.document_id.as_str(), action.headline_id.as_str()),
+        );
+        actions
+    })?
+    .unwrap_or_default();
+    actions.truncate(limit as usize);
+    Ok(actions)
+}
+
+/// List the tickler: tasks deferred past today, soonest to come back first, then
+/// headlines tagged `:someday:`
+#[tauri::command]
+#[specta::specta]
+pub async fn get_tickler() -> Result<Vec<TicklerItem>, String> {
+    let today = time_zone::today();
+    Ok(
+        with_repository(|repository| collect_tickler(&repository.list(), today))?
+            .unwrap_or_default(),
+    )
+}
+
+/// Get task dependencies from BLOCKER/DEPENDS properties across all documents
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dependency_graph() -> Result<DependencyGraph, String> {
+    Ok(
+        with_repository(|repository| DependencyGraph::build(&repository.list()))?
+            .unwrap_or_default(),
+    )
+}
+
+/// Get all projects (headlines with child tasks) with their status, for a projects review
+#[tauri::command]
+#[specta::specta]
+pub async fn get_projects() -> Result<Vec<Project>, String> {
+    Ok(with_repository(|repository| collect_projects(&repository.list()))?.unwrap_or_default())
+}
+
+/// Get open projects that have no next action
+#[tauri::command]
+#[specta::specta]
+pub async fn get_stuck_projects() -> Result<Vec<Project>, String> {
+    let projects = get_projects().await?;
+    Ok(projects
+        .into_iter()
+        .filter(|project| project.status == ProjectStatus::Stuck)
+        .collect())
+}
+
+/// Set how deadline, priority, age and effort add up to a task's urgency
+#[tauri::command]
+#[specta::specta]
+pub async fn set_urgency_weights(
+    app_handle: tauri::AppHandle,
+    weights: UrgencyWeights,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_urgency_weights(weights);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Get the effort values to offer for a headline: its file's or an ancestor's
+/// `Effort_ALL`, falling back to the presets in settings
+#[tauri::command]
+#[specta::specta]
+pub async fn get_effort_presets(
+    app_handle: tauri::AppHandle,
+    document_id: Option<String>,
+    headline_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    if let Some(document_id) = &document_id {
+        let presets = with_repository(|repository| {
+            repository
+                .get(document_id)
+                .and_then(|document| effort_presets(document, headline_id.as_deref()))
+        })?;
+        if let Some(presets) = presets.flatten() {
+            return Ok(presets);
+        }
+    }
+
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(settings.effort_presets)
+}
+
+/// Set the effort presets offered when a file doesn't define `Effort_ALL`
+#[tauri::command]
+#[specta::specta]
+pub async fn set_effort_presets(
+    app_handle: tauri::AppHandle,
+    presets: Vec<String>,
+) -> Result<UserSettings, String> {
+    let presets: Vec<String> = presets
+        .iter()
+        .map(|preset| preset.trim().to_string())
+        .collect();
+    if let Some(invalid) = presets.iter().find(|preset| !is_valid_effort(preset)) {
+        return Err(format!("Invalid effort: '{}'", invalid));
+    }
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_effort_presets(presets);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Set how much the auto-scheduling planner plans per day and how far ahead, or turn it
+/// on or off
+#[tauri::command]
+#[specta::specta]
+pub async fn set_planner_config(
+    app_handle: tauri::AppHandle,
+    config: PlannerConfig,
+) -> Result<UserSettings, String> {
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_planner_config(config);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// List the IANA time zone names the time zone can be set to
+#[tauri::command]
+#[specta::specta]
+pub async fn get_time_zones() -> Result<Vec<String>, String> {
+    Ok(chrono_tz::TZ_VARIANTS
+        .iter()
+        .map(|zone| zone.name().to_string())
+        .collect())
+}
+
+/// Set the IANA time zone org timestamps are assumed to be in, e.g. `America/New_York`,
+/// or null to use the system time zone
+/// This decides which day is today for overdue and due-today checks
+#[tauri::command]
+#[specta::specta]
+pub async fn set_time_zone(
+    app_handle: tauri::AppHandle,
+    time_zone: Option<String>,
+) -> Result<UserSettings, String> {
+    let zone = time_zone.as_deref().map(parse_time_zone).transpose()?;
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_time_zone(zone.map(|zone| zone.name().to_string()));
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    set_assumed_time_zone(zone);
+    Ok(settings)
+}
+
+/// Use the time zone from the settings, keeping the system's if it is unset or unknown
+pub fn apply_time_zone_setting(app_handle: &tauri::AppHandle) {
+    let settings = tauri::async_runtime::block_on(SETTINGS_MANAGER.load_settings(app_handle));
+    let time_zone = match settings {
+        Ok(settings) => settings.time_zone,
+        Err(e) => {
+            eprintln!("Failed to load settings: {}", e);
+            return;
+        }
+    };
+    match time_zone.as_deref().map(parse_time_zone).transpose() {
+        Ok(zone) => set_assumed_time_zone(zone),
+        Err(e) => eprintln!("{}, using the system time zone", e),
+    }
+}
+
+/// Set the first day of the week and the language day names are written in, e.g. `de`
+/// for `<2024-05-15 Mi>`
+#[tauri::command]
+#[specta::specta]
+pub async fn set_date_locale(
+    app_handle: tauri::AppHandle,
+    locale: DateLocale,
+) -> Result<UserSettings, String> {
+    locale.validate()?;
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_date_locale(locale);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Set the workdays, working hours and holidays used by the planner and the timeline
+/// `holiday_feed` is the path of an .ics calendar whose events count as holidays
+#[tauri::command]
+#[specta::specta]
+pub async fn set_work_schedule(
+    app_handle: tauri::AppHandle,
+    schedule: WorkSchedule,
+) -> Result<UserSettings, String> {
+    schedule.validate()?;
+    if let Some(feed) = &schedule.holiday_feed {
+        if !Path::new(feed).is_file() {
+            return Err(format!("Holiday feed not found: {}", feed));
+        }
+    }
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_work_schedule(schedule);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+/// Move a custom headline property up/down in the list
+#[tauri::command]
+#[specta::specta]
+pub async fn move_custom_property(
     app_handle: tauri::AppHandle,
     index: u32,
-    new_property: String,
+    direction: i32,
+) -> Result<Vec<String>, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings
+        .move_custom_property(index as usize, direction)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Trigger re-parsing of all documents with updated settings
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after custom property change: {}",
+            e
+        );
+    }
+
+    Ok(current_settings.get_custom_properties().clone())
+}
+
+/// Reset custom headline properties to empty
+#[tauri::command]
+#[specta::specta]
+pub async fn reset_custom_properties_to_defaults(
+    app_handle: tauri::AppHandle,
 ) -> Result<Vec<String>, String> {
     let mut current_settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
+    current_settings.reset_custom_properties_to_defaults();
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Trigger re-parsing of all documents with updated settings
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after custom property reset: {}",
+            e
+        );
+    }
+
+    Ok(current_settings.get_custom_properties().clone())
+}
+
+/// Update TODO keywords in user settings
+#[tauri::command]
+#[specta::specta]
+pub async fn update_todo_keywords(
+    app_handle: tauri::AppHandle,
+    todo_keywords: TodoKeywords,
+) -> Result<UserSettings, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings.update_todo_keywords(todo_keywords);
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Trigger re-parsing of all documents with updated settings
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after settings change: {}",
+            e
+        );
+    }
+
+    Ok(current_settings)
+}
+
+/// Add active TODO keyword
+#[tauri::command]
+#[specta::specta]
+pub async fn add_active_todo_keyword(
+    app_handle: tauri::AppHandle,
+    keyword: String,
+) -> Result<UserSettings, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    current_settings
+        .get_todo_keywords_mut()
+        .add_active_keyword(keyword)
+        .map_err(|e| e.to_string())?;
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &current_settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Trigger re-parsing of all documents with updated settings
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after settings change: {}",
+            e
+        );
+    }
+
+    Ok(current_settings)
+}
+
+/// Add closed TODO keyword
+#[tauri::command]
+#[specta::specta]
+pub async fn add_closed_todo_keyword(
+    app_handle: tauri::AppHandle,
+    keyword: String,
+) -> Result<UserSettings, String> {
+    let mut current_settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
     current_settings
-        .edit_custom_property(index as usize, new_property)
+        .get_todo_keywords_mut()
+        .add_closed_keyword(keyword)
         .map_err(|e| e.to_string())?;
 
     SETTINGS_MANAGER
@@ -713,28 +5184,29 @@ pub async fn edit_custom_property(
     // Trigger re-parsing of all documents with updated settings
     if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
         eprintln!(
-            "Warning: Failed to reload documents after custom property change: {}",
+            "Warning: Failed to reload documents after settings change: {}",
             e
         );
     }
 
-    Ok(current_settings.get_custom_properties().clone())
+    Ok(current_settings)
 }
 
-/// Remove a custom headline property by index
+/// Remove active TODO keyword by index
 #[tauri::command]
 #[specta::specta]
-pub async fn remove_custom_property(
+pub async fn remove_active_todo_keyword(
     app_handle: tauri::AppHandle,
     index: u32,
-) -> Result<Vec<String>, String> {
+) -> Result<UserSettings, String> {
     let mut current_settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
     current_settings
-        .remove_custom_property(index as usize)
+        .get_todo_keywords_mut()
+        .remove_active_keyword(index as usize)
         .map_err(|e| e.to_string())?;
 
     SETTINGS_MANAGER
@@ -742,32 +5214,24 @@ pub async fn remove_custom_property(
         .await
         .map_err(|e| e.to_string())?;
 
-    // Trigger re-parsing of all documents with updated settings
-    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
-        eprintln!(
-            "Warning: Failed to reload documents after custom property change: {}",
-            e
-        );
-    }
-
-    Ok(current_settings.get_custom_properties().clone())
+    Ok(current_settings)
 }
 
-/// Move a custom headline property up/down in the list
+/// Remove closed TODO keyword by index
 #[tauri::command]
 #[specta::specta]
-pub async fn move_custom_property(
+pub async fn remove_closed_todo_keyword(
     app_handle: tauri::AppHandle,
     index: u32,
-    direction: i32,
-) -> Result<Vec<String>, String> {
+) -> Result<UserSettings, String> {
     let mut current_settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
     current_settings
-        .move_custom_property(index as usize, direction)
+        .get_todo_keywords_mut()
+        .remove_closed_keyword(index as usize)
         .map_err(|e| e.to_string())?;
 
     SETTINGS_MANAGER
@@ -775,216 +5239,1191 @@ pub async fn move_custom_property(
         .await
         .map_err(|e| e.to_string())?;
 
-    // Trigger re-parsing of all documents with updated settings
-    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
-        eprintln!(
-            "Warning: Failed to reload documents after custom property change: {}",
-            e
-        );
-    }
-
-    Ok(current_settings.get_custom_properties().clone())
+    Ok(current_settings)
 }
 
-/// Reset custom headline properties to empty
+/// Edit active TODO keyword by index
 #[tauri::command]
 #[specta::specta]
-pub async fn reset_custom_properties_to_defaults(
+pub async fn edit_active_todo_keyword(
     app_handle: tauri::AppHandle,
-) -> Result<Vec<String>, String> {
+    index: u32,
+    new_keyword: String,
+) -> Result<UserSettings, String> {
     let mut current_settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
 
-    current_settings.reset_custom_properties_to_defaults();
+    current_settings
+        .get_todo_keywords_mut()
+        .edit_active_keyword(index as usize, new_keyword)
+        .map_err(|e| e.to_string())?;
 
     SETTINGS_MANAGER
         .save_settings(&app_handle, &current_settings)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Trigger re-parsing of all documents with updated settings
-    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
-        eprintln!(
-            "Warning: Failed to reload documents after custom property reset: {}",
-            e
-        );
+    Ok(current_settings)
+}
+
+/// List headlines in monitored files that use a TODO keyword, before renaming it
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_todo_keyword_rename(
+    old_keyword: String,
+) -> Result<Vec<KeywordOccurrence>, String> {
+    let reloaded = read_evicted_documents().await?;
+    let occurrences = with_full_documents(reloaded, |documents| {
+        find_keyword_occurrences(documents, &old_keyword)
+    })?;
+    Ok(occurrences.unwrap_or_default())
+}
+
+/// Rewrite a renamed TODO keyword in all monitored files, on headlines and in the files'
+/// `#+TODO:` lines
+/// Returns the number of files that were changed
+#[tauri::command]
+#[specta::specta]
+pub async fn apply_todo_keyword_rename(
+    app_handle: tauri::AppHandle,
+    old_keyword: String,
+    new_keyword: String,
+) -> Result<u32, String> {
+    if new_keyword.is_empty() || new_keyword.contains(char::is_whitespace) {
+        return Err(format!("Invalid TODO keyword: '{}'", new_keyword));
+    }
+
+    // Find the files to change from the full text of evicted documents, then rewrite each
+    // through the drift check
+    let reloaded = read_evicted_documents().await?;
+    let document_ids: Vec<String> = with_full_documents(reloaded, |documents| {
+        documents
+            .iter()
+            .filter(|document| !document.missing && !document.archived && !document.flags.read_only)
+            .filter(|document| {
+                replace_headline_keyword(&document.content, &old_keyword, &new_keyword).is_some()
+            })
+            .map(|document| document.id.clone())
+            .collect()
+    })?
+    .unwrap_or_default();
+
+    for document_id in &document_ids {
+        edit_document_source(&app_handle, document_id, None, |document| {
+            Ok(
+                replace_headline_keyword(&document.content, &old_keyword, &new_keyword)
+                    .unwrap_or_else(|| document.content.clone()),
+            )
+        })
+        .await?;
+    }
+
+    Ok(document_ids.len() as u32)
+}
+
+/// Rename a tag on every headline and FILETAGS line in monitored files
+#[tauri::command]
+#[specta::specta]
+pub async fn rename_tag(
+    app_handle: tauri::AppHandle,
+    old_tag: String,
+    new_tag: String,
+) -> Result<TagRewriteSummary, String> {
+    merge_tags(app_handle, vec![old_tag], new_tag).await
+}
+
+/// Replace several tags with one on every headline and FILETAGS line in monitored files
+/// Headlines that had more than one of the tags keep a single copy
+#[tauri::command]
+#[specta::specta]
+pub async fn merge_tags(
+    app_handle: tauri::AppHandle,
+    tags: Vec<String>,
+    into: String,
+) -> Result<TagRewriteSummary, String> {
+    if !is_valid_tag(&into) {
+        return Err(format!("Invalid tag: '{}'", into));
+    }
+    let is_merged = |tag: &str| tags.iter().any(|old| old == tag);
+    let is_affected = |document: &OrgDocument| {
+        let uses_tags = document.filetags.iter().any(|tag| is_merged(tag))
+            || flatten_headlines(&document.headlines)
+                .iter()
+                .any(|headline| headline.title.tags.iter().any(|tag| is_merged(tag)));
+        !document.missing && !document.archived && !document.flags.read_only && uses_tags
+    };
+    // Read from disk, since evicted documents don't keep their content
+    let mut contents = read_document_files(is_affected)?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let Some(monitor) = monitor_lock.as_ref() else {
+        return Ok(TagRewriteSummary::default());
+    };
+
+    // Compute all rewrites first so the repository lock is released before writing, and
+    // nothing is written if any affected file changed on disk
+    let mut summary = TagRewriteSummary::default();
+    let rewrites: Vec<(String, String)> = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+
+        let mut rewrites = Vec::new();
+        for document in repository_lock.list() {
+            if !is_affected(document) {
+                continue;
+            }
+            let content = take_document_file(&mut contents, document)?;
+            if !repository_lock.matches_content(&document.id, &content) {
+                return Err(format!(
+                    "{} changed on disk since it was loaded",
+                    document.file_path
+                ));
+            }
+            if let Some((updated, headlines)) = rewrite_tags(&content, &tags, &into) {
+                summary.files_changed += 1;
+                summary.headlines_changed += headlines;
+                rewrites.push((document.file_path.clone(), updated));
+            }
+        }
+        rewrites
+    };
+
+    for (file_path, content) in &rewrites {
+        monitor.write_file(Path::new(file_path), content)?;
+    }
+    drop(monitor_lock);
+    track_rewrites(&app_handle, &rewrites);
+
+    Ok(summary)
+}
+
+/// List the category keywords and properties in monitored files that set a category,
+/// before renaming it
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_category_rename(
+    old_category: String,
+) -> Result<Vec<CategoryOccurrence>, String> {
+    let found = with_repository(|repository| {
+        let mut occurrences = Vec::new();
+        let mut evicted = Vec::new();
+        for document in repository.list() {
+            if document.missing
+                || document.archived
+                || document.flags.read_only
+                || !uses_category(document, &old_category)
+            {
+                continue;
+            }
+            if repository.is_evicted(&document.id) {
+                evicted.push(document.clone());
+            } else {
+                occurrences.extend(find_category_occurrences(document, &old_category));
+            }
+        }
+        (occurrences, evicted)
+    })?;
+    let Some((mut occurrences, evicted)) = found else {
+        return Ok(Vec::new());
+    };
+
+    // Evicted documents only keep their keyword lines, so read them back from disk once
+    // the locks are released
+    for mut document in evicted {
+        match fs::read_to_string(&document.file_path) {
+            Ok(content) => {
+                document.content = content;
+                occurrences.extend(find_category_occurrences(&document, &old_category));
+            }
+            Err(e) => eprintln!("Failed to read file {}: {}", document.file_path, e),
+        }
+    }
+    Ok(occurrences)
+}
+
+/// Rewrite a category in the `#+CATEGORY:` keywords and `:CATEGORY:` properties of all
+/// monitored files
+/// Returns the number of files that were changed
+#[tauri::command]
+#[specta::specta]
+pub async fn rename_category(
+    app_handle: tauri::AppHandle,
+    old_category: String,
+    new_category: String,
+) -> Result<u32, String> {
+    let new_category = new_category.trim().to_string();
+    if new_category.is_empty() || new_category.contains('\n') {
+        return Err(format!("Invalid category: '{}'", new_category));
+    }
+    let is_affected = |document: &OrgDocument| {
+        !document.missing
+            && !document.archived
+            && !document.flags.read_only
+            && uses_category(document, &old_category)
+    };
+    let mut contents = read_document_files(is_affected)?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let Some(monitor) = monitor_lock.as_ref() else {
+        return Ok(0);
+    };
+
+    // Compute all rewrites first so the repository lock is released before writing, and
+    // nothing is written if any affected file changed on disk
+    let rewrites: Vec<(String, String)> = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+
+        let mut rewrites = Vec::new();
+        for document in repository_lock.list() {
+            if !is_affected(document) {
+                continue;
+            }
+            let content = take_document_file(&mut contents, document)?;
+            if !repository_lock.matches_content(&document.id, &content) {
+                return Err(format!(
+                    "{} changed on disk since it was loaded",
+                    document.file_path
+                ));
+            }
+            if let Some(updated) = rewrite_category(&content, &old_category, &new_category) {
+                rewrites.push((document.file_path.clone(), updated));
+            }
+        }
+        rewrites
+    };
+
+    for (file_path, content) in &rewrites {
+        monitor.write_file(Path::new(file_path), content)?;
+    }
+    drop(monitor_lock);
+    track_rewrites(&app_handle, &rewrites);
+
+    Ok(rewrites.len() as u32)
+}
+
+/// Preview a find-and-replace across monitored files, listing every match per file
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_find_replace(query: ReplaceQuery) -> Result<Vec<FileReplacePreview>, String> {
+    let regex = query.compile()?;
+    // Evicted documents only keep their keyword lines, so they are read back from disk
+    // once the locks are released
+    let documents = with_repository(|repository| {
+        let mut documents: Vec<(String, String, Option<String>)> = repository
+            .list()
+            .into_iter()
+            .filter(|document| !document.missing && !document.archived)
+            .map(|document| {
+                let content =
+                    (!repository.is_evicted(&document.id)).then(|| document.content.clone());
+                (document.id.clone(), document.file_path.clone(), content)
+            })
+            .collect();
+        documents.sort_by(|a, b| a.1.cmp(&b.1));
+        documents
+    })?;
+
+    let mut previews = Vec::new();
+    for (document_id, file_path, content) in documents.unwrap_or_default() {
+        let content = match content {
+            Some(content) => content,
+            None => match fs::read_to_string(&file_path) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Failed to read file {}: {}", file_path, e);
+                    continue;
+                }
+            },
+        };
+        let matches = query.preview(&regex, &content);
+        if !matches.is_empty() {
+            previews.push(FileReplacePreview {
+                document_id,
+                file_path,
+                matches,
+            });
+        }
+    }
+    Ok(previews)
+}
+
+/// Replace every match of a find-and-replace in monitored files, except in the excluded
+/// documents, and reparse the changed files
+#[tauri::command]
+#[specta::specta]
+pub async fn apply_find_replace(
+    app_handle: tauri::AppHandle,
+    query: ReplaceQuery,
+    excluded_document_ids: Vec<String>,
+) -> Result<ReplaceSummary, String> {
+    let regex = query.compile()?;
+    let is_included = |document: &OrgDocument| {
+        !document.missing && !document.archived && !excluded_document_ids.contains(&document.id)
+    };
+    let mut contents = read_document_files(is_included)?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let Some(monitor) = monitor_lock.as_ref() else {
+        return Ok(ReplaceSummary::default());
+    };
+
+    // Compute all rewrites first so the repository lock is released before writing, and
+    // nothing is written if any affected file changed on disk
+    let mut summary = ReplaceSummary::default();
+    let rewrites: Vec<(String, String)> = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+
+        let mut rewrites = Vec::new();
+        for document in repository_lock.list() {
+            if !is_included(document) {
+                continue;
+            }
+            let content = take_document_file(&mut contents, document)?;
+            let Some((updated, count)) = query.replace_all(&regex, &content) else {
+                continue;
+            };
+            if !repository_lock.matches_content(&document.id, &content) {
+                return Err(format!(
+                    "{} changed on disk since it was loaded",
+                    document.file_path
+                ));
+            }
+            summary.files_changed += 1;
+            summary.replacements += count;
+            rewrites.push((document.file_path.clone(), updated));
+        }
+        rewrites
+    };
+
+    for (file_path, content) in &rewrites {
+        monitor.write_file(Path::new(file_path), content)?;
+    }
+    drop(monitor_lock);
+    track_rewrites(&app_handle, &rewrites);
+
+    Ok(summary)
+}
+
+/// Set a property on every headline matching all filters, optionally only within one
+/// headline's subtree, e.g. to set `agenda-group` on a whole project
+/// Drawers are created where missing, and values of typed custom properties must parse as
+/// their type. Returns the number of headlines changed
+#[tauri::command]
+#[specta::specta]
+pub async fn bulk_set_property(
+    app_handle: tauri::AppHandle,
+    filters: Vec<PropertyFilter>,
+    root: Option<HeadlineRef>,
+    key: String,
+    value: String,
+) -> Result<u32, String> {
+    let value = value.trim().to_string();
+    if !is_valid_property_key(&key) {
+        return Err(format!("Invalid property key: '{}'", key));
+    }
+    if value.contains('\n') {
+        return Err("Property values cannot span lines".to_string());
+    }
+    if filters.is_empty() && root.is_none() {
+        return Err("Refusing to set a property on every headline".to_string());
+    }
+    SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?
+        .validate_property_value(&key, &value)
+        .map_err(|e| e.to_string())?;
+
+    // Headlines of a document to set the property on
+    let targets = |document: &OrgDocument| -> Result<Vec<String>, String> {
+        if document.missing
+            || document.archived
+            || root
+                .as_ref()
+                .is_some_and(|root| root.document_id != document.id)
+        {
+            return Ok(Vec::new());
+        }
+        let candidates = match &root {
+            Some(root) => {
+                let headline = document
+                    .find_headline(&root.headline_id)
+                    .ok_or_else(|| format!("Headline not found: {}", root.headline_id))?;
+                std::iter::once(headline)
+                    .chain(flatten_headlines(&headline.children))
+                    .collect()
+            }
+            None => flatten_headlines(&document.headlines),
+        };
+        Ok(candidates
+            .into_iter()
+            .filter(|headline| matches_filters(document, headline, &filters))
+            .filter(|headline| {
+                headline.title.get_property_ignore_case(&key) != Some(value.as_str())
+            })
+            .map(|headline| headline.id.clone())
+            .collect())
+    };
+    let mut contents =
+        read_document_files(|document| targets(document).is_ok_and(|ids| !ids.is_empty()))?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let Some(monitor) = monitor_lock.as_ref() else {
+        return Ok(0);
+    };
+
+    // Compute all rewrites first so the repository lock is released before writing, and
+    // nothing is written if any affected file changed on disk
+    let mut changed = 0;
+    let rewrites: Vec<(String, String)> = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+
+        let mut rewrites = Vec::new();
+        for document in repository_lock.list() {
+            let targets = targets(document)?;
+            if targets.is_empty() {
+                continue;
+            }
+
+            let mut content = take_document_file(&mut contents, document)?;
+            if !repository_lock.matches_content(&document.id, &content) {
+                return Err(format!(
+                    "{} changed on disk since it was loaded",
+                    document.file_path
+                ));
+            }
+            let mut current = document.clone();
+            current.content = content.clone();
+            let line_numbers = headline_line_numbers(&current);
+
+            // Edit from the bottom up so inserted drawers don't shift the lines still to edit
+            let mut lines: Vec<usize> = targets
+                .iter()
+                .filter_map(|id| line_numbers.get(id).copied())
+                .collect();
+            lines.sort_unstable_by(|a, b| b.cmp(a));
+            let property = [(key.as_str(), value.clone())];
+            for line in lines {
+                content = set_headline_properties(&content, line, &property).ok_or_else(|| {
+                    format!("Malformed property drawer in {}", document.file_path)
+                })?;
+                changed += 1;
+            }
+            rewrites.push((document.file_path.clone(), content));
+        }
+        rewrites
+    };
+
+    for (file_path, content) in &rewrites {
+        monitor.write_file(Path::new(file_path), content)?;
+    }
+    drop(monitor_lock);
+    track_rewrites(&app_handle, &rewrites);
+
+    Ok(changed)
+}
+
+/// List the SCHEDULED and DEADLINE dates of open tasks that a bulk shift by `delta`
+/// (e.g. `+1d`) would move
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_shift_dates(
+    app_handle: tauri::AppHandle,
+    filter: ShiftFilter,
+    delta: String,
+) -> Result<Vec<DateShift>, String> {
+    let delta = DateDelta::parse(&delta)?;
+    let today = time_zone::today();
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let language = settings.date_locale.language;
+    let settings_config = settings.todo_keywords.to_todo_configuration();
+
+    let shifts = with_repository(|repository| {
+        plan_date_shifts(
+            &repository.list(),
+            &filter,
+            &delta,
+            today,
+            &language,
+            &settings_config,
+        )
+    })?;
+    Ok(shifts.unwrap_or_default())
+}
+
+/// Move the SCHEDULED and DEADLINE dates of matching open tasks by `delta`, except on the
+/// excluded headlines, e.g. `+1d` for everything overdue
+/// Returns the number of dates moved
+#[tauri::command]
+#[specta::specta]
+pub async fn shift_dates(
+    app_handle: tauri::AppHandle,
+    filter: ShiftFilter,
+    delta: String,
+    excluded: Vec<HeadlineRef>,
+) -> Result<u32, String> {
+    let delta = DateDelta::parse(&delta)?;
+    let today = time_zone::today();
+    let settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+    let language = settings.date_locale.language;
+    let settings_config = settings.todo_keywords.to_todo_configuration();
+
+    // Dates of a document to shift
+    let shifts = |document: &OrgDocument| -> Vec<DateShift> {
+        plan_date_shifts(
+            &[document],
+            &filter,
+            &delta,
+            today,
+            &language,
+            &settings_config,
+        )
+        .into_iter()
+        .filter(|shift| {
+            !excluded.iter().any(|headline| {
+                headline.document_id == shift.document_id
+                    && headline.headline_id == shift.headline_id
+            })
+        })
+        .collect()
+    };
+    let mut contents = read_document_files(|document| !shifts(document).is_empty())?;
+
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let Some(monitor) = monitor_lock.as_ref() else {
+        return Ok(0);
+    };
+
+    // Compute all rewrites first so the repository lock is released before writing, and
+    // nothing is written if any affected file changed on disk
+    let mut shifted = 0;
+    let rewrites: Vec<(String, String)> = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
+
+        let mut rewrites = Vec::new();
+        for document in repository_lock.list() {
+            let shifts = shifts(document);
+            if shifts.is_empty() {
+                continue;
+            }
+
+            let mut content = take_document_file(&mut contents, document)?;
+            if !repository_lock.matches_content(&document.id, &content) {
+                return Err(format!(
+                    "{} changed on disk since it was loaded",
+                    document.file_path
+                ));
+            }
+            let mut current = document.clone();
+            current.content = content.clone();
+            let line_numbers = headline_line_numbers(&current);
+
+            // Shifting a date never adds or removes lines, so the line numbers stay valid
+            for shift in shifts {
+                let Some(line) = line_numbers.get(&shift.headline_id).copied() else {
+                    continue;
+                };
+                let updated = shift_planning_date(&content, line, shift.field, &delta, &language);
+                if let Some(updated) = updated {
+                    content = updated;
+                    shifted += 1;
+                }
+            }
+            rewrites.push((document.file_path.clone(), content));
+        }
+        rewrites
+    };
+
+    for (file_path, content) in &rewrites {
+        monitor.write_file(Path::new(file_path), content)?;
     }
+    drop(monitor_lock);
+    track_rewrites(&app_handle, &rewrites);
 
-    Ok(current_settings.get_custom_properties().clone())
+    Ok(shifted)
 }
 
-/// Update TODO keywords in user settings
+/// Propose SCHEDULED dates for unscheduled open tasks from their effort, the load already
+/// scheduled on each working day of the work schedule
+/// Returns nothing while the planner is turned off
 #[tauri::command]
 #[specta::specta]
-pub async fn update_todo_keywords(
+pub async fn get_schedule_suggestions(
     app_handle: tauri::AppHandle,
-    todo_keywords: TodoKeywords,
-) -> Result<UserSettings, String> {
-    let mut current_settings = SETTINGS_MANAGER
+) -> Result<Vec<ScheduleSuggestion>, String> {
+    let settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
-
-    current_settings.update_todo_keywords(todo_keywords);
-
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &current_settings)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    // Trigger re-parsing of all documents with updated settings
-    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
-        eprintln!(
-            "Warning: Failed to reload documents after settings change: {}",
-            e
-        );
+    if !settings.planner.enabled {
+        return Ok(Vec::new());
     }
-
-    Ok(current_settings)
+    let today = time_zone::today();
+    let schedule = settings.work_schedule.clone().with_feed_holidays();
+
+    let suggestions = with_repository(|repository| {
+        suggest_schedule(
+            &repository.list(),
+            &settings.planner,
+            &schedule,
+            &settings.urgency_weights,
+            today,
+            &settings.todo_keywords.to_todo_configuration(),
+        )
+    })?;
+    Ok(suggestions.unwrap_or_default())
 }
 
-/// Add active TODO keyword
+/// Write the accepted schedule suggestions as SCHEDULED dates
+/// Returns the number of headlines scheduled
 #[tauri::command]
 #[specta::specta]
-pub async fn add_active_todo_keyword(
+pub async fn accept_schedule_suggestions(
     app_handle: tauri::AppHandle,
-    keyword: String,
-) -> Result<UserSettings, String> {
-    let mut current_settings = SETTINGS_MANAGER
+    suggestions: Vec<ScheduleSuggestion>,
+) -> Result<u32, String> {
+    let settings = SETTINGS_MANAGER
         .load_settings(&app_handle)
         .await
         .map_err(|e| e.to_string())?;
+    let language = settings.date_locale.language;
+
+    let mut dates: HashMap<&str, Vec<(&str, NaiveDate)>> = HashMap::new();
+    for suggestion in &suggestions {
+        let date = NaiveDate::parse_from_str(&suggestion.date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date: '{}'", suggestion.date))?;
+        dates
+            .entry(suggestion.document_id.as_str())
+            .or_default()
+            .push((suggestion.headline_id.as_str(), date));
+    }
+    let mut contents = read_document_files(|document| dates.contains_key(document.id.as_str()))?;
 
-    current_settings
-        .get_todo_keywords_mut()
-        .add_active_keyword(keyword)
-        .map_err(|e| e.to_string())?;
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let Some(monitor) = monitor_lock.as_ref() else {
+        return Ok(0);
+    };
 
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &current_settings)
-        .await
-        .map_err(|e| e.to_string())?;
+    // Compute all rewrites first so the repository lock is released before writing, and
+    // nothing is written if any affected file changed on disk
+    let mut scheduled = 0;
+    let rewrites: Vec<(String, String)> = {
+        let repository = monitor.get_repository();
+        let repository_lock = repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?;
 
-    // Trigger re-parsing of all documents with updated settings
-    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
-        eprintln!(
-            "Warning: Failed to reload documents after settings change: {}",
-            e
-        );
+        let mut rewrites = Vec::new();
+        for (document_id, headlines) in dates {
+            let document = repository_lock
+                .get(document_id)
+                .ok_or_else(|| format!("Document not found: {}", document_id))?;
+            if document.archived {
+                return Err(format!("{} is a read-only archive", document.file_path));
+            }
+            let mut content = take_document_file(&mut contents, document)?;
+            if !repository_lock.matches_content(document_id, &content) {
+                return Err(format!(
+                    "{} changed on disk since it was loaded",
+                    document.file_path
+                ));
+            }
+            let mut current = document.clone();
+            current.content = content.clone();
+            let line_numbers = headline_line_numbers(&current);
+
+            // Edit from the bottom up so inserted planning lines don't shift the lines
+            // still to edit
+            let mut lines: Vec<(usize, NaiveDate)> = headlines
+                .into_iter()
+                .map(|(headline_id, date)| {
+                    line_numbers
+                        .get(headline_id)
+                        .map(|line| (*line, date))
+                        .ok_or_else(|| format!("Headline not found: {}", headline_id))
+                })
+                .collect::<Result<_, _>>()?;
+            lines.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+            for (line, date) in lines {
+                content =
+                    set_planning_date(&content, line, PlanningField::Scheduled, date, &language)
+                        .ok_or_else(|| format!("Malformed headline in {}", document.file_path))?;
+                scheduled += 1;
+            }
+            rewrites.push((document.file_path.clone(), content));
+        }
+        rewrites
+    };
+
+    for (file_path, content) in &rewrites {
+        monitor.write_file(Path::new(file_path), content)?;
     }
+    drop(monitor_lock);
+    track_rewrites(&app_handle, &rewrites);
 
-    Ok(current_settings)
+    Ok(scheduled)
 }
 
-/// Add closed TODO keyword
+// Location of the trash file in the app data directory
+fn trash_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(TRASH_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+// A headline subtree cut out of its file, before the file is written
+struct CutSubtree {
+    file_path: String,
+    content: String,   // File content before the cut
+    remaining: String, // File content without the subtree
+    removed: String,
+    title: String,
+    line: u32, // 1-based line where the subtree started
+}
+
+// Cut a headline and its descendants out of `content`, the file as read from disk
+// Refuses if the file changed since it was parsed, since headline ids may have moved
+fn cut_subtree(
+    monitor: &FileMonitor,
+    document_id: &str,
+    headline_id: &str,
+    content: String,
+) -> Result<CutSubtree, String> {
+    let repository = monitor.get_repository();
+    let repository_lock = repository
+        .lock()
+        .map_err(|e| format!("Failed to lock repository: {}", e))?;
+    let document = repository_lock
+        .get(document_id)
+        .ok_or_else(|| format!("Document not found: {}", document_id))?;
+    let title = document
+        .find_headline(headline_id)
+        .map(|headline| headline.title.raw.clone())
+        .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+
+    if !repository_lock.matches_content(document_id, &content) {
+        return Err(format!(
+            "{} changed on disk since it was loaded",
+            document.file_path
+        ));
+    }
+
+    let mut current = document.clone();
+    current.content = content.clone();
+    let lines = subtree_lines(&current, headline_id)
+        .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+    let line = lines.start as u32 + 1;
+    let (remaining, removed) = take_lines(&content, lines);
+
+    Ok(CutSubtree {
+        file_path: document.file_path.clone(),
+        content,
+        remaining,
+        removed,
+        title,
+        line,
+    })
+}
+
+/// Delete a headline and its subtree from its file, keeping the text in the trash
 #[tauri::command]
 #[specta::specta]
-pub async fn add_closed_todo_keyword(
+pub async fn delete_headline(
     app_handle: tauri::AppHandle,
-    keyword: String,
-) -> Result<UserSettings, String> {
-    let mut current_settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())?;
+    document_id: String,
+    headline_id: String,
+) -> Result<TrashEntry, String> {
+    let trash_path = trash_path(&app_handle)?;
+    let content = read_document_file(&document_id)?;
+    let monitor_lock = FILE_MONITOR
+        .lock()
+        .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+    let monitor = monitor_lock
+        .as_ref()
+        .ok_or_else(|| "File monitoring is not running".to_string())?;
+
+    let cut = cut_subtree(monitor, &document_id, &headline_id, content)?;
+    let entry = TrashEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        file_path: cut.file_path.clone(),
+        title: cut.title,
+        line: cut.line,
+        text: cut.removed,
+        deleted_at: time_zone::now_rfc3339(),
+    };
 
-    current_settings
-        .get_todo_keywords_mut()
-        .add_closed_keyword(keyword)
-        .map_err(|e| e.to_string())?;
+    // Save the trash before touching the file, so the text is never lost
+    let mut entries = load_trash(&trash_path)?;
+    entries.push(entry.clone());
+    save_trash(&trash_path, &entries)?;
 
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &current_settings)
-        .await
-        .map_err(|e| e.to_string())?;
+    monitor.write_file(Path::new(&cut.file_path), &cut.remaining)?;
+    drop(monitor_lock);
+    track_recent(&app_handle, &cut.file_path, None, RecentAccess::Edited);
+    Ok(entry)
+}
 
-    // Trigger re-parsing of all documents with updated settings
-    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
-        eprintln!(
-            "Warning: Failed to reload documents after settings change: {}",
-            e
-        );
+/// List deleted headlines, most recent first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_trash(app_handle: tauri::AppHandle) -> Result<Vec<TrashEntry>, String> {
+    let mut entries = load_trash(&trash_path(&app_handle)?)?;
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Put a deleted headline back into its original file and remove it from the trash
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_from_trash(
+    app_handle: tauri::AppHandle,
+    entry_id: String,
+) -> Result<TrashEntry, String> {
+    let trash_path = trash_path(&app_handle)?;
+    let mut entries = load_trash(&trash_path)?;
+    let index = entries
+        .iter()
+        .position(|entry| entry.id == entry_id)
+        .ok_or_else(|| format!("Trash entry not found: {}", entry_id))?;
+    let entry = entries[index].clone();
+
+    // A file that was removed since is recreated with just the restored text
+    let path = Path::new(&entry.file_path);
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read file {}: {}", entry.file_path, e)),
+    };
+    let restored = insert_subtree(&content, entry.line.saturating_sub(1) as usize, &entry.text);
+
+    {
+        let monitor_lock = FILE_MONITOR
+            .lock()
+            .map_err(|e| format!("Failed to lock file monitor: {}", e))?;
+        let monitor = monitor_lock
+            .as_ref()
+            .ok_or_else(|| "File monitoring is not running".to_string())?;
+        monitor.write_file(path, &restored)?;
     }
 
-    Ok(current_settings)
+    entries.remove(index);
+    save_trash(&trash_path, &entries)?;
+    track_recent(&app_handle, &entry.file_path, None, RecentAccess::Edited);
+    Ok(entry)
 }
 
-/// Remove active TODO keyword by index
+// Location of the snapshots in the app data directory
+fn snapshot_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(SNAPSHOT_DIR_NAME))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+// Copy every monitored file that exists into a new snapshot
+fn snapshot_monitored_files(dir: &Path, label: &str) -> Result<Snapshot, String> {
+    let mut paths: Vec<String> = with_repository(|repository| {
+        repository
+            .list()
+            .into_iter()
+            .filter(|document| !document.missing)
+            .map(|document| document.file_path.clone())
+            .collect()
+    })?
+    .unwrap_or_default();
+    paths.sort();
+    save_snapshot(dir, label, &paths, chrono::Local::now())
+}
+
+/// Copy all monitored files into a new snapshot, as a safety net before bulk changes
 #[tauri::command]
 #[specta::specta]
-pub async fn remove_active_todo_keyword(
+pub async fn create_snapshot(
     app_handle: tauri::AppHandle,
-    index: u32,
-) -> Result<UserSettings, String> {
-    let mut current_settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
+    label: String,
+) -> Result<Snapshot, String> {
+    let dir = snapshot_dir(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || snapshot_monitored_files(&dir, &label))
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+}
 
-    current_settings
-        .get_todo_keywords_mut()
-        .remove_active_keyword(index as usize)
-        .map_err(|e| e.to_string())?;
+/// List snapshots, most recent first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_snapshots(app_handle: tauri::AppHandle) -> Result<Vec<Snapshot>, String> {
+    load_snapshots(&snapshot_dir(&app_handle)?)
+}
 
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &current_settings)
-        .await
-        .map_err(|e| e.to_string())?;
+/// Write the files in a snapshot back as they were when it was taken
+/// The current files are snapshotted first so the restore can be undone. Files created
+/// since the snapshot are left alone. Returns the paths of the files that were rewritten.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_snapshot(
+    app_handle: tauri::AppHandle,
+    snapshot_id: String,
+) -> Result<Vec<String>, String> {
+    let dir = snapshot_dir(&app_handle)?;
+    let restored = tauri::async_runtime::spawn_blocking(move || {
+        let files = snapshot_files(&dir, &snapshot_id)?;
+        snapshot_monitored_files(&dir, &format!("Before restoring {}", snapshot_id))?;
+
+        let mut restored = Vec::new();
+        for (path, content) in files {
+            if read_or_empty(&path)? != content {
+                restore_monitored_file(&path, &content)?;
+                restored.push(path);
+            }
+        }
+        Ok::<_, String>(restored)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
 
-    Ok(current_settings)
+    for path in &restored {
+        track_recent(&app_handle, path, None, RecentAccess::Edited);
+    }
+    Ok(restored)
 }
 
-/// Remove closed TODO keyword by index
+/// Delete a snapshot and its file copies
 #[tauri::command]
 #[specta::specta]
-pub async fn remove_closed_todo_keyword(
+pub async fn delete_snapshot(
     app_handle: tauri::AppHandle,
-    index: u32,
-) -> Result<UserSettings, String> {
-    let mut current_settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())?;
+    snapshot_id: String,
+) -> Result<(), String> {
+    remove_snapshot(&snapshot_dir(&app_handle)?, &snapshot_id)
+}
 
-    current_settings
-        .get_todo_keywords_mut()
-        .remove_closed_keyword(index as usize)
-        .map_err(|e| e.to_string())?;
+// Location of the reminder store in the app data directory
+fn reminders_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(REMINDERS_FILE_NAME))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
 
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &current_settings)
+// Load the reminder store, apply a change, and save it if the change succeeded
+fn update_reminders<T>(
+    app_handle: &tauri::AppHandle,
+    f: impl FnOnce(&mut Vec<Reminder>) -> Result<T, String>,
+) -> Result<T, String> {
+    let path = reminders_path(app_handle)?;
+    let _guard = REMINDERS_LOCK
+        .lock()
+        .map_err(|e| format!("Failed to lock reminders: {}", e))?;
+    let mut reminders = load_reminders(&path)?;
+    let result = f(&mut reminders)?;
+    save_reminders(&path, &reminders)?;
+    Ok(result)
+}
+
+// Point reminders at where their headlines are now, following renamed files
+// Reminders in documents that are not loaded are left as they are
+fn relocate_reminders(reminders: &mut [Reminder]) -> Result<(), String> {
+    with_repository(|repository| {
+        for reminder in reminders.iter_mut() {
+            let Some(document) = repository
+                .resolve_id(&reminder.document_id)
+                .and_then(|document_id| repository.get(&document_id))
+                .filter(|document| !document.missing)
+            else {
+                continue;
+            };
+            reminder.document_id = document.id.clone();
+            reminder.relocate(document);
+        }
+    })?;
+    Ok(())
+}
+
+// Emit an event for every reminder that became due, marking it as fired
+fn fire_due_reminders(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let now = chrono::Local::now().fixed_offset();
+    let due = update_reminders(app_handle, |reminders| {
+        relocate_reminders(reminders)?;
+        Ok(take_due(reminders, now))
+    })?;
+    for reminder in due {
+        if let Err(e) = app_handle.emit(REMINDER_DUE_EVENT, &reminder) {
+            eprintln!("Failed to emit reminder {}: {}", reminder.id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Start the background task that fires due reminders
+/// Reminders that came due while the app was closed fire on the first check
+pub fn start_reminder_scheduler(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = fire_due_reminders(&app_handle) {
+                eprintln!("Failed to check reminders: {}", e);
+            }
+            tokio::time::sleep(REMINDER_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// Whether the morning digest is due: auto-copy is on, it is past the start of a working
+// day, and for weekly digests, no earlier day this week was a working day
+fn digest_due(settings: &UserSettings, schedule: &WorkSchedule, now: NaiveDateTime) -> bool {
+    let today = now.date();
+    let day_start =
+        NaiveTime::parse_from_str(&schedule.day_start, "%H:%M").unwrap_or(NaiveTime::MIN);
+    if !settings.digest.auto_copy || !schedule.is_working_day(today) || now.time() < day_start {
+        return false;
+    }
+    match settings.digest.period {
+        DigestPeriod::Daily => true,
+        DigestPeriod::Weekly => {
+            let (week_start, _) = digest_range(DigestPeriod::Weekly, today, &settings.date_locale);
+            !week_start
+                .iter_days()
+                .take_while(|day| *day < today)
+                .any(|day| schedule.is_working_day(day))
+        }
+    }
+}
+
+async fn emit_morning_digest(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let settings = SETTINGS_MANAGER
+        .load_settings(app_handle)
         .await
         .map_err(|e| e.to_string())?;
+    let now = time_zone::now();
+    let already_sent = *LAST_DIGEST_DATE
+        .lock()
+        .map_err(|e| format!("Failed to lock digest date: {}", e))?
+        == Some(now.date());
+    let schedule = settings.work_schedule.clone().with_feed_holidays();
+    if already_sent || !digest_due(&settings, &schedule, now) {
+        return Ok(());
+    }
 
-    Ok(current_settings)
+    let digest = build_digest(&settings, settings.digest.period, settings.digest.format).await?;
+    app_handle
+        .emit(DIGEST_READY_EVENT, &digest)
+        .map_err(|e| format!("Failed to emit digest: {}", e))?;
+    *LAST_DIGEST_DATE
+        .lock()
+        .map_err(|e| format!("Failed to lock digest date: {}", e))? = Some(now.date());
+    Ok(())
 }
 
-/// Edit active TODO keyword by index
+/// Start the background task that emits the digest each morning when auto-copy is on,
+/// for the frontend to put on the clipboard
+/// The digest goes out once the working day has started, at most once a day
+pub fn start_digest_scheduler(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = emit_morning_digest(&app_handle).await {
+                eprintln!("Failed to prepare digest: {}", e);
+            }
+            tokio::time::sleep(DIGEST_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Attach a reminder to a headline without changing its org file
+/// `remind_at` is an RFC 3339 date and time, or one without an offset in the assumed time zone
 #[tauri::command]
 #[specta::specta]
-pub async fn edit_active_todo_keyword(
+pub async fn set_reminder(
     app_handle: tauri::AppHandle,
-    index: u32,
-    new_keyword: String,
-) -> Result<UserSettings, String> {
-    let mut current_settings = SETTINGS_MANAGER
-        .load_settings(&app_handle)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    current_settings
-        .get_todo_keywords_mut()
-        .edit_active_keyword(index as usize, new_keyword)
-        .map_err(|e| e.to_string())?;
+    document_id: String,
+    headline_id: String,
+    remind_at: String,
+    message: String,
+) -> Result<Reminder, String> {
+    let remind_at = parse_remind_at(&remind_at)?;
+    let reminder = with_repository(|repository| {
+        repository.get(&document_id).and_then(|document| {
+            Reminder::for_headline(
+                uuid::Uuid::new_v4().to_string(),
+                document,
+                &headline_id,
+                remind_at,
+                message,
+            )
+        })
+    })?
+    .flatten()
+    .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+
+    update_reminders(&app_handle, |reminders| {
+        reminders.push(reminder.clone());
+        Ok(())
+    })?;
+    Ok(reminder)
+}
 
-    SETTINGS_MANAGER
-        .save_settings(&app_handle, &current_settings)
-        .await
-        .map_err(|e| e.to_string())?;
+/// Remove a reminder, whether or not it has fired
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_reminder(
+    app_handle: tauri::AppHandle,
+    reminder_id: String,
+) -> Result<Reminder, String> {
+    update_reminders(&app_handle, |reminders| {
+        let index = reminders
+            .iter()
+            .position(|reminder| reminder.id == reminder_id)
+            .ok_or_else(|| format!("Reminder not found: {}", reminder_id))?;
+        Ok(reminders.remove(index))
+    })
+}
 
-    Ok(current_settings)
+/// List reminders that have not fired yet, soonest first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_upcoming_reminders(app_handle: tauri::AppHandle) -> Result<Vec<Reminder>, String> {
+    let _guard = REMINDERS_LOCK
+        .lock()
+        .map_err(|e| format!("Failed to lock reminders: {}", e))?;
+    let mut reminders = load_reminders(&reminders_path(&app_handle)?)?;
+    relocate_reminders(&mut reminders)?;
+    Ok(upcoming(&reminders))
 }
 
 /// Edit closed TODO keyword by index
@@ -1141,12 +6580,8 @@ pub async fn get_todo_keywords(app_handle: tauri::AppHandle) -> Result<Vec<TodoS
             keyword: keyword.clone(),
             state_type: StateType::Active,
             order: order as u32,
-            color: Some(match keyword.as_str() {
-                "TODO" => "#ff0000".to_string(),        // Red
-                "IN-PROGRESS" => "#ff9900".to_string(), // Orange
-                "WAITING" => "#ffff00".to_string(),     // Yellow
-                _ => "#0066cc".to_string(),             // Blue for custom keywords
-            }),
+            color: Some(keyword_color(keyword, &StateType::Active)),
+            style: keyword_style(keyword, &StateType::Active),
         });
     }
 
@@ -1156,17 +6591,69 @@ pub async fn get_todo_keywords(app_handle: tauri::AppHandle) -> Result<Vec<TodoS
             keyword: keyword.clone(),
             state_type: StateType::Closed,
             order: (100 + order) as u32, // Start closed keywords at 100
-            color: Some(match keyword.as_str() {
-                "DONE" => "#00ff00".to_string(),      // Green
-                "CANCELLED" => "#999999".to_string(), // Gray
-                _ => "#666666".to_string(),           // Dark gray for custom closed keywords
-            }),
+            color: Some(keyword_color(keyword, &StateType::Closed)),
+            style: keyword_style(keyword, &StateType::Closed),
         });
     }
 
     Ok(keywords)
 }
 
+/// Get the color and style of each of the given tags, keyed by tag
+/// Colors come from the same palette as TODO keywords, so they are the same on every run;
+/// the light and dark colors and icons chosen in the settings take precedence in the style.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_tag_colors(tags: Vec<String>) -> Result<HashMap<String, TagColor>, String> {
+    Ok(tags
+        .into_iter()
+        .map(|tag| {
+            let color = TagColor::of(&tag);
+            (tag, color)
+        })
+        .collect())
+}
+
+/// Choose the light and dark colors and icons of TODO keywords and tags, replacing the
+/// generated ones; documents are re-parsed so their statuses carry the new styles
+#[tauri::command]
+#[specta::specta]
+pub async fn set_status_styles(
+    app_handle: tauri::AppHandle,
+    styles: StatusStyles,
+) -> Result<UserSettings, String> {
+    styles.validate()?;
+
+    let mut settings = SETTINGS_MANAGER
+        .load_settings(&app_handle)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    settings.set_status_styles(styles.clone());
+
+    SETTINGS_MANAGER
+        .save_settings(&app_handle, &settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    set_style_overrides(styles);
+    if let Err(e) = reload_documents_with_settings(app_handle.clone()).await {
+        eprintln!(
+            "Warning: Failed to reload documents after settings change: {}",
+            e
+        );
+    }
+    Ok(settings)
+}
+
+/// Use the keyword and tag styles chosen in the settings
+pub fn apply_status_style_setting(app_handle: &tauri::AppHandle) {
+    match tauri::async_runtime::block_on(SETTINGS_MANAGER.load_settings(app_handle)) {
+        Ok(settings) => set_style_overrides(settings.status_styles),
+        Err(e) => eprintln!("Failed to load settings: {}", e),
+    }
+}
+
 // ============================================================================
 // Table Columns Configuration Commands
 // ============================================================================
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Event emitted with `JobInfo` whenever a job makes progress or ends
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+
+// Finished jobs kept around for `list_jobs`/`get_job`
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Shared flag that long-running work checks to stop early
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the work holding this token to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// What a background job is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum JobKind {
+    Scan,
+    IndexBuild,
+    Search,
+    Sync,
+    Export,
+}
+
+/// Lifecycle state of a background job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Snapshot of a background job, as reported to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct JobInfo {
+    pub id: u32,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub done: u32,
+    pub total: u32,
+    pub percent: u32,
+    pub result: Option<String>, // Short summary once completed
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+struct Job {
+    info: JobInfo,
+    token: CancellationToken,
+}
+
+/// Registry of background jobs, so the frontend can follow and cancel them by ID
+#[derive(Debug)]
+pub struct JobRegistry {
+    next_id: AtomicU32,
+    jobs: Mutex<HashMap<u32, Job>>,
+}
+
+impl Default for JobRegistry {
+    fn default() -> Self {
+        Self {
+            next_id: AtomicU32::new(1),
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new running job and get its ID and cancellation token
+    pub fn start(&self, kind: JobKind) -> (u32, CancellationToken) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let token = CancellationToken::new();
+        let info = JobInfo {
+            id,
+            kind,
+            status: JobStatus::Running,
+            done: 0,
+            total: 0,
+            percent: 0,
+            result: None,
+            error: None,
+        };
+
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.insert(
+                id,
+                Job {
+                    info,
+                    token: token.clone(),
+                },
+            );
+        }
+        (id, token)
+    }
+
+    /// Record that `done` of `total` steps are finished
+    pub fn set_progress(&self, id: u32, done: u32, total: u32) -> Option<JobInfo> {
+        self.update(id, |info| {
+            info.done = done;
+            info.total = total;
+            info.percent = percent(done, total);
+        })
+    }
+
+    /// Mark a job as ended successfully, or as cancelled if cancellation was requested
+    pub fn complete(&self, id: u32, result: Option<String>) -> Option<JobInfo> {
+        let cancelled = self.token(id).is_some_and(|token| token.is_cancelled());
+        let info = self.update(id, |info| {
+            if cancelled {
+                info.status = JobStatus::Cancelled;
+            } else {
+                info.status = JobStatus::Completed;
+                info.percent = 100;
+            }
+            info.result = result;
+        });
+        self.prune();
+        info
+    }
+
+    /// Mark a job as failed
+    pub fn fail(&self, id: u32, error: String) -> Option<JobInfo> {
+        let info = self.update(id, |info| {
+            info.status = JobStatus::Failed;
+            info.error = Some(error);
+        });
+        self.prune();
+        info
+    }
+
+    /// Cancel a running job, returning false if it is unknown or already finished
+    pub fn cancel(&self, id: u32) -> bool {
+        let Ok(jobs) = self.jobs.lock() else {
+            return false;
+        };
+        match jobs.get(&id) {
+            Some(job) if job.info.status == JobStatus::Running => {
+                job.token.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<JobInfo> {
+        let jobs = self.jobs.lock().ok()?;
+        jobs.get(&id).map(|job| job.info.clone())
+    }
+
+    /// All known jobs, newest first
+    pub fn list(&self) -> Vec<JobInfo> {
+        let Ok(jobs) = self.jobs.lock() else {
+            return Vec::new();
+        };
+        let mut infos: Vec<JobInfo> = jobs.values().map(|job| job.info.clone()).collect();
+        infos.sort_by_key(|info| std::cmp::Reverse(info.id));
+        infos
+    }
+
+    fn token(&self, id: u32) -> Option<CancellationToken> {
+        let jobs = self.jobs.lock().ok()?;
+        jobs.get(&id).map(|job| job.token.clone())
+    }
+
+    fn update(&self, id: u32, f: impl FnOnce(&mut JobInfo)) -> Option<JobInfo> {
+        let mut jobs = self.jobs.lock().ok()?;
+        let job = jobs.get_mut(&id)?;
+        f(&mut job.info);
+        Some(job.info.clone())
+    }
+
+    // Drop the oldest finished jobs beyond MAX_FINISHED_JOBS
+    fn prune(&self) {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return;
+        };
+        let mut finished: Vec<u32> = jobs
+            .values()
+            .filter(|job| job.info.status != JobStatus::Running)
+            .map(|job| job.info.id)
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort_unstable();
+        for id in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            jobs.remove(id);
+        }
+    }
+}
+
+fn percent(done: u32, total: u32) -> u32 {
+    if total == 0 {
+        return 0;
+    }
+    (u64::from(done.min(total)) * 100 / u64::from(total)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_job() {
+        let jobs = JobRegistry::new();
+        let (first, token) = jobs.start(JobKind::Scan);
+        let (second, _) = jobs.start(JobKind::Search);
+        assert_ne!(first, second);
+
+        assert!(!token.is_cancelled());
+        assert!(jobs.cancel(first));
+        assert!(token.is_cancelled());
+        assert_eq!(
+            jobs.complete(first, None).unwrap().status,
+            JobStatus::Cancelled
+        );
+
+        jobs.complete(second, None);
+        assert!(!jobs.cancel(second));
+        assert!(!jobs.cancel(999));
+    }
+
+    #[test]
+    fn test_job_progress() {
+        let jobs = JobRegistry::new();
+        let (id, _) = jobs.start(JobKind::IndexBuild);
+
+        let info = jobs.set_progress(id, 1, 3).unwrap();
+        assert_eq!((info.done, info.total, info.percent), (1, 3, 33));
+
+        let info = jobs.complete(id, Some("3 documents".to_string())).unwrap();
+        assert_eq!(info.status, JobStatus::Completed);
+        assert_eq!(info.percent, 100);
+        assert_eq!(jobs.get(id), Some(info));
+
+        let (failed, _) = jobs.start(JobKind::Export);
+        jobs.fail(failed, "disk full".to_string());
+        let ids: Vec<u32> = jobs.list().iter().map(|job| job.id).collect();
+        assert_eq!(ids, vec![failed, id]);
+        assert_eq!(jobs.get(failed).unwrap().status, JobStatus::Failed);
+    }
+}
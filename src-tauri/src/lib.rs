@@ -1,5 +1,6 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 pub mod api;
+pub mod jobs;
 pub mod orgmode;
 pub mod settings;
 #[cfg(debug_assertions)]
@@ -19,17 +20,110 @@ fn generate_ts_bindings() {
         api::stop_file_monitoring,
         api::get_all_documents,
         api::get_org_document_by_id,
+        api::get_subtree_as_document,
+        api::get_document_summary,
+        api::get_document_stats,
+        api::get_document_timeline,
+        api::get_calendar_month,
+        api::get_headline_children,
+        api::get_column_values,
+        api::get_headline_content,
+        api::get_recent_items,
+        api::get_document_etags,
         api::get_org_document_display_title_by_id,
         api::get_org_document_path_by_id,
+        api::resolve_document_id,
+        api::forget_missing_document,
+        api::verify_repository,
+        api::get_sync_conflicts,
+        api::resolve_sync_conflict,
+        api::get_write_conflict,
+        api::discard_write_conflict,
+        api::resolve_write_conflict,
+        api::get_document_todo_config,
+        api::poll_changes,
+        api::search_headlines,
+        api::search_in_document,
+        api::rebuild_search_index,
+        api::cancel_job,
+        api::get_job,
+        api::get_perf_stats,
+        api::get_parse_diagnostics,
+        api::set_max_loaded_documents,
+        api::get_memory_stats,
+        api::list_jobs,
+        api::start_search,
+        api::quick_find,
+        api::get_grouped_tasks,
+        api::get_property_values,
+        api::get_completed_tasks,
+        api::resolve_date_range,
+        api::export_agenda,
+        api::get_digest,
+        api::set_digest_config,
+        api::set_hook_config,
+        api::set_issue_config,
+        api::refresh_issues,
+        api::set_github_sync_config,
+        api::sync_github_issues,
+        api::set_format_conventions,
+        api::set_ignored_tags,
+        api::set_default_sort,
+        api::get_activity_heatmap,
+        api::check_links,
+        api::audit_duplicates,
+        api::get_due_cards,
+        api::record_card_review,
+        api::set_todo_keyword,
+        api::cycle_todo_state,
+        api::execute_headline_action,
         api::load_user_settings,
         api::save_user_settings,
+        api::create_org_file,
+        api::list_favorites,
+        api::add_favorite,
+        api::remove_favorite,
+        api::save_file_template,
+        api::remove_file_template,
+        api::open_or_create_journal_entry,
+        api::set_journal_config,
+        api::get_source_blocks,
+        api::list_tangle_targets,
+        api::get_image_links,
+        api::get_headline_rich_text,
+        api::get_headline_html,
+        api::start_sharing,
+        api::stop_sharing,
+        api::list_plugins,
+        api::reload_plugins,
+        api::run_plugin_query,
+        api::export_with_plugin,
+        api::get_latex_spans,
+        api::get_document_citations,
+        api::list_cited_keys,
+        api::list_contacts,
+        api::set_contacts_files,
+        api::set_html_sanitize_config,
+        api::open_contact_link,
+        api::capture,
+        api::import_highlights,
+        api::capture_bookmark,
+        api::extract_action_items,
+        api::accept_action_items,
+        api::set_bookmark_config,
+        api::get_bookmarklet,
+        api::refile_headline,
         api::add_monitored_path,
         api::remove_monitored_path,
         api::update_monitored_path,
         api::set_path_parse_enabled,
         api::clear_user_settings,
+        api::export_app_state,
+        api::import_app_state,
         api::check_path_monitoring_status,
         api::get_todo_keywords,
+        api::get_tag_colors,
+        api::set_status_styles,
         api::get_user_todo_keywords,
         api::update_todo_keywords,
         api::add_active_todo_keyword,
@@ -37,6 +131,29 @@ fn generate_ts_bindings() {
         api::remove_active_todo_keyword,
         api::remove_closed_todo_keyword,
         api::edit_active_todo_keyword,
+        api::preview_todo_keyword_rename,
+        api::apply_todo_keyword_rename,
+        api::rename_tag,
+        api::merge_tags,
+        api::preview_category_rename,
+        api::rename_category,
+        api::preview_find_replace,
+        api::apply_find_replace,
+        api::bulk_set_property,
+        api::preview_shift_dates,
+        api::shift_dates,
+        api::get_schedule_suggestions,
+        api::accept_schedule_suggestions,
+        api::delete_headline,
+        api::list_trash,
+        api::restore_from_trash,
+        api::create_snapshot,
+        api::list_snapshots,
+        api::restore_snapshot,
+        api::delete_snapshot,
+        api::set_reminder,
+        api::remove_reminder,
+        api::get_upcoming_reminders,
         api::edit_closed_todo_keyword,
         api::move_active_todo_keyword,
         api::move_closed_todo_keyword,
@@ -47,11 +164,46 @@ fn generate_ts_bindings() {
         api::edit_custom_property,
         api::remove_custom_property,
         api::move_custom_property,
+        api::set_custom_property_type,
+        api::sort_headlines_by_property,
+        api::sum_property,
+        api::get_urgency_scores,
+        api::sort_headlines_by_urgency,
+        api::get_most_urgent_tasks,
+        api::get_next_actions,
+        api::get_tickler,
+        api::get_dependency_graph,
+        api::get_projects,
+        api::get_stuck_projects,
+        api::set_urgency_weights,
+        api::get_effort_presets,
+        api::set_effort_presets,
+        api::set_planner_config,
+        api::set_work_schedule,
+        api::set_date_locale,
+        api::get_time_zones,
+        api::set_time_zone,
+        api::set_effort,
+        api::set_headline_property,
+        api::delete_headline_property,
+        api::save_document_headlines,
+        api::defer_task,
+        api::add_checklist_item,
+        api::remove_checklist_item,
+        api::sort_children,
+        api::split_headline,
+        api::merge_headlines,
+        api::set_document_title,
+        api::set_filetags,
+        api::set_document_category,
         api::reset_custom_properties_to_defaults,
         api::get_external_editor_command,
         api::set_external_editor_command,
+        api::set_parse_archive_files,
         api::reset_external_editor_command,
         api::open_file_in_external_editor,
+        api::reveal_document_in_file_manager,
+        api::open_with_system_default,
         api::get_table_columns,
         api::get_available_table_columns,
         api::update_table_columns,
@@ -92,17 +244,110 @@ pub fn run() {
         api::stop_file_monitoring,
         api::get_all_documents,
         api::get_org_document_by_id,
+        api::get_subtree_as_document,
+        api::get_document_summary,
+        api::get_document_stats,
+        api::get_document_timeline,
+        api::get_calendar_month,
+        api::get_headline_children,
+        api::get_column_values,
+        api::get_headline_content,
+        api::get_recent_items,
+        api::get_document_etags,
         api::get_org_document_display_title_by_id,
         api::get_org_document_path_by_id,
+        api::resolve_document_id,
+        api::forget_missing_document,
+        api::verify_repository,
+        api::get_sync_conflicts,
+        api::resolve_sync_conflict,
+        api::get_write_conflict,
+        api::discard_write_conflict,
+        api::resolve_write_conflict,
+        api::get_document_todo_config,
+        api::poll_changes,
+        api::search_headlines,
+        api::search_in_document,
+        api::rebuild_search_index,
+        api::cancel_job,
+        api::get_job,
+        api::get_perf_stats,
+        api::get_parse_diagnostics,
+        api::set_max_loaded_documents,
+        api::get_memory_stats,
+        api::list_jobs,
+        api::start_search,
+        api::quick_find,
+        api::get_grouped_tasks,
+        api::get_property_values,
+        api::get_completed_tasks,
+        api::resolve_date_range,
+        api::export_agenda,
+        api::get_digest,
+        api::set_digest_config,
+        api::set_hook_config,
+        api::set_issue_config,
+        api::refresh_issues,
+        api::set_github_sync_config,
+        api::sync_github_issues,
+        api::set_format_conventions,
+        api::set_ignored_tags,
+        api::set_default_sort,
+        api::get_activity_heatmap,
+        api::check_links,
+        api::audit_duplicates,
+        api::get_due_cards,
+        api::record_card_review,
+        api::set_todo_keyword,
+        api::cycle_todo_state,
+        api::execute_headline_action,
         api::load_user_settings,
         api::save_user_settings,
+        api::create_org_file,
+        api::list_favorites,
+        api::add_favorite,
+        api::remove_favorite,
+        api::save_file_template,
+        api::remove_file_template,
+        api::open_or_create_journal_entry,
+        api::set_journal_config,
+        api::get_source_blocks,
+        api::list_tangle_targets,
+        api::get_image_links,
+        api::get_headline_rich_text,
+        api::get_headline_html,
+        api::start_sharing,
+        api::stop_sharing,
+        api::list_plugins,
+        api::reload_plugins,
+        api::run_plugin_query,
+        api::export_with_plugin,
+        api::get_latex_spans,
+        api::get_document_citations,
+        api::list_cited_keys,
+        api::list_contacts,
+        api::set_contacts_files,
+        api::set_html_sanitize_config,
+        api::open_contact_link,
+        api::capture,
+        api::import_highlights,
+        api::capture_bookmark,
+        api::extract_action_items,
+        api::accept_action_items,
+        api::set_bookmark_config,
+        api::get_bookmarklet,
+        api::refile_headline,
         api::add_monitored_path,
         api::remove_monitored_path,
         api::update_monitored_path,
         api::set_path_parse_enabled,
         api::clear_user_settings,
+        api::export_app_state,
+        api::import_app_state,
         api::check_path_monitoring_status,
         api::get_todo_keywords,
+        api::get_tag_colors,
+        api::set_status_styles,
         api::get_user_todo_keywords,
         api::update_todo_keywords,
         api::add_active_todo_keyword,
@@ -110,6 +355,29 @@ pub fn run() {
         api::remove_active_todo_keyword,
         api::remove_closed_todo_keyword,
         api::edit_active_todo_keyword,
+        api::preview_todo_keyword_rename,
+        api::apply_todo_keyword_rename,
+        api::rename_tag,
+        api::merge_tags,
+        api::preview_category_rename,
+        api::rename_category,
+        api::preview_find_replace,
+        api::apply_find_replace,
+        api::bulk_set_property,
+        api::preview_shift_dates,
+        api::shift_dates,
+        api::get_schedule_suggestions,
+        api::accept_schedule_suggestions,
+        api::delete_headline,
+        api::list_trash,
+        api::restore_from_trash,
+        api::create_snapshot,
+        api::list_snapshots,
+        api::restore_snapshot,
+        api::delete_snapshot,
+        api::set_reminder,
+        api::remove_reminder,
+        api::get_upcoming_reminders,
         api::edit_closed_todo_keyword,
         api::move_active_todo_keyword,
         api::move_closed_todo_keyword,
@@ -120,11 +388,46 @@ pub fn run() {
         api::edit_custom_property,
         api::remove_custom_property,
         api::move_custom_property,
+        api::set_custom_property_type,
+        api::sort_headlines_by_property,
+        api::sum_property,
+        api::get_urgency_scores,
+        api::sort_headlines_by_urgency,
+        api::get_most_urgent_tasks,
+        api::get_next_actions,
+        api::get_tickler,
+        api::get_dependency_graph,
+        api::get_projects,
+        api::get_stuck_projects,
+        api::set_urgency_weights,
+        api::get_effort_presets,
+        api::set_effort_presets,
+        api::set_planner_config,
+        api::set_work_schedule,
+        api::set_date_locale,
+        api::get_time_zones,
+        api::set_time_zone,
+        api::set_effort,
+        api::set_headline_property,
+        api::delete_headline_property,
+        api::save_document_headlines,
+        api::defer_task,
+        api::add_checklist_item,
+        api::remove_checklist_item,
+        api::sort_children,
+        api::split_headline,
+        api::merge_headlines,
+        api::set_document_title,
+        api::set_filetags,
+        api::set_document_category,
         api::reset_custom_properties_to_defaults,
         api::get_external_editor_command,
         api::set_external_editor_command,
+        api::set_parse_archive_files,
         api::reset_external_editor_command,
         api::open_file_in_external_editor,
+        api::reveal_document_in_file_manager,
+        api::open_with_system_default,
         api::get_table_columns,
         api::get_available_table_columns,
         api::update_table_columns,
@@ -142,17 +445,110 @@ pub fn run() {
         api::stop_file_monitoring,
         api::get_all_documents,
         api::get_org_document_by_id,
+        api::get_subtree_as_document,
+        api::get_document_summary,
+        api::get_document_stats,
+        api::get_document_timeline,
+        api::get_calendar_month,
+        api::get_headline_children,
+        api::get_column_values,
+        api::get_headline_content,
+        api::get_recent_items,
+        api::get_document_etags,
         api::get_org_document_display_title_by_id,
         api::get_org_document_path_by_id,
+        api::resolve_document_id,
+        api::forget_missing_document,
+        api::verify_repository,
+        api::get_sync_conflicts,
+        api::resolve_sync_conflict,
+        api::get_write_conflict,
+        api::discard_write_conflict,
+        api::resolve_write_conflict,
+        api::get_document_todo_config,
+        api::poll_changes,
+        api::search_headlines,
+        api::search_in_document,
+        api::rebuild_search_index,
+        api::cancel_job,
+        api::get_job,
+        api::get_perf_stats,
+        api::get_parse_diagnostics,
+        api::set_max_loaded_documents,
+        api::get_memory_stats,
+        api::list_jobs,
+        api::start_search,
+        api::quick_find,
+        api::get_grouped_tasks,
+        api::get_property_values,
+        api::get_completed_tasks,
+        api::resolve_date_range,
+        api::export_agenda,
+        api::get_digest,
+        api::set_digest_config,
+        api::set_hook_config,
+        api::set_issue_config,
+        api::refresh_issues,
+        api::set_github_sync_config,
+        api::sync_github_issues,
+        api::set_format_conventions,
+        api::set_ignored_tags,
+        api::set_default_sort,
+        api::get_activity_heatmap,
+        api::check_links,
+        api::audit_duplicates,
+        api::get_due_cards,
+        api::record_card_review,
+        api::set_todo_keyword,
+        api::cycle_todo_state,
+        api::execute_headline_action,
         api::load_user_settings,
         api::save_user_settings,
+        api::create_org_file,
+        api::list_favorites,
+        api::add_favorite,
+        api::remove_favorite,
+        api::save_file_template,
+        api::remove_file_template,
+        api::open_or_create_journal_entry,
+        api::set_journal_config,
+        api::get_source_blocks,
+        api::list_tangle_targets,
+        api::get_image_links,
+        api::get_headline_rich_text,
+        api::get_headline_html,
+        api::start_sharing,
+        api::stop_sharing,
+        api::list_plugins,
+        api::reload_plugins,
+        api::run_plugin_query,
+        api::export_with_plugin,
+        api::get_latex_spans,
+        api::get_document_citations,
+        api::list_cited_keys,
+        api::list_contacts,
+        api::set_contacts_files,
+        api::set_html_sanitize_config,
+        api::open_contact_link,
+        api::capture,
+        api::import_highlights,
+        api::capture_bookmark,
+        api::extract_action_items,
+        api::accept_action_items,
+        api::set_bookmark_config,
+        api::get_bookmarklet,
+        api::refile_headline,
         api::add_monitored_path,
         api::remove_monitored_path,
         api::update_monitored_path,
         api::set_path_parse_enabled,
         api::clear_user_settings,
+        api::export_app_state,
+        api::import_app_state,
         api::check_path_monitoring_status,
         api::get_todo_keywords,
+        api::get_tag_colors,
+        api::set_status_styles,
         api::get_user_todo_keywords,
         api::update_todo_keywords,
         api::add_active_todo_keyword,
@@ -160,6 +556,29 @@ pub fn run() {
         api::remove_active_todo_keyword,
         api::remove_closed_todo_keyword,
         api::edit_active_todo_keyword,
+        api::preview_todo_keyword_rename,
+        api::apply_todo_keyword_rename,
+        api::rename_tag,
+        api::merge_tags,
+        api::preview_category_rename,
+        api::rename_category,
+        api::preview_find_replace,
+        api::apply_find_replace,
+        api::bulk_set_property,
+        api::preview_shift_dates,
+        api::shift_dates,
+        api::get_schedule_suggestions,
+        api::accept_schedule_suggestions,
+        api::delete_headline,
+        api::list_trash,
+        api::restore_from_trash,
+        api::create_snapshot,
+        api::list_snapshots,
+        api::restore_snapshot,
+        api::delete_snapshot,
+        api::set_reminder,
+        api::remove_reminder,
+        api::get_upcoming_reminders,
         api::edit_closed_todo_keyword,
         api::move_active_todo_keyword,
         api::move_closed_todo_keyword,
@@ -170,11 +589,46 @@ pub fn run() {
         api::edit_custom_property,
         api::remove_custom_property,
         api::move_custom_property,
+        api::set_custom_property_type,
+        api::sort_headlines_by_property,
+        api::sum_property,
+        api::get_urgency_scores,
+        api::sort_headlines_by_urgency,
+        api::get_most_urgent_tasks,
+        api::get_next_actions,
+        api::get_tickler,
+        api::get_dependency_graph,
+        api::get_projects,
+        api::get_stuck_projects,
+        api::set_urgency_weights,
+        api::get_effort_presets,
+        api::set_effort_presets,
+        api::set_planner_config,
+        api::set_work_schedule,
+        api::set_date_locale,
+        api::get_time_zones,
+        api::set_time_zone,
+        api::set_effort,
+        api::set_headline_property,
+        api::delete_headline_property,
+        api::save_document_headlines,
+        api::defer_task,
+        api::add_checklist_item,
+        api::remove_checklist_item,
+        api::sort_children,
+        api::split_headline,
+        api::merge_headlines,
+        api::set_document_title,
+        api::set_filetags,
+        api::set_document_category,
         api::reset_custom_properties_to_defaults,
         api::get_external_editor_command,
         api::set_external_editor_command,
+        api::set_parse_archive_files,
         api::reset_external_editor_command,
         api::open_file_in_external_editor,
+        api::reveal_document_in_file_manager,
+        api::open_with_system_default,
         api::get_table_columns,
         api::get_available_table_columns,
         api::update_table_columns,
@@ -188,6 +642,27 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
+        .setup(|app| {
+            api::apply_time_zone_setting(app.handle());
+            api::apply_status_style_setting(app.handle());
+            api::start_reminder_scheduler(app.handle().clone());
+            api::start_digest_scheduler(app.handle().clone());
+            api::load_plugins(app.handle().clone());
+            api::start_bookmark_server(app.handle().clone());
+            api::start_issue_refresher(app.handle().clone());
+            api::start_github_sync(app.handle().clone());
+            Ok(())
+        })
+        .register_asynchronous_uri_scheme_protocol(
+            orgmode::IMAGE_PROTOCOL,
+            |_context, request, responder| {
+                // Reading and scaling images must not block the webview's thread
+                let uri = request.uri().clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    responder.respond(api::serve_image(uri.path(), uri.query()))
+                });
+            },
+        )
         .invoke_handler(builder.invoke_handler())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
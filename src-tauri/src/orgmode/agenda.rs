@@ -0,0 +1,490 @@
+use crate::orgmode::columns::property_value;
+use crate::orgmode::digest::OverdueTask;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::html::escape_html;
+use crate::orgmode::locale::{long_day_name, month_name};
+use crate::orgmode::tickler::deferrals;
+use crate::orgmode::timeline::{stamped_entries, TimelineKind};
+use crate::orgmode::work_schedule::WorkSchedule;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+
+/// Longest span an agenda may cover, in days
+pub const MAX_AGENDA_DAYS: i64 = 366;
+
+/// How an exported agenda is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum AgendaFormat {
+    Text, // The classic org-agenda layout
+    Html, // A standalone page for printing
+}
+
+/// A headline on one agenda day
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgendaEntry {
+    pub document_id: String,
+    pub headline_id: String,
+    pub category: String,
+    pub title: String,
+    pub todo_keyword: Option<String>,
+    pub kind: TimelineKind,
+    pub time: Option<String>,             // HH:MM, None for all-day entries
+    pub overdue_since: Option<NaiveDate>, // Past date of an open task carried forward
+}
+
+/// The agenda entries of one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgendaDay {
+    pub date: NaiveDate,
+    pub entries: Vec<AgendaEntry>,
+    pub holiday: Option<String>,
+}
+
+// Step of a repeater like `+1w`, `++2d` or `.+1m` as (count, unit); hourly repeaters are
+// not expanded
+fn repeat_step(repeater: &str) -> Option<(u32, char)> {
+    let rest = repeater.trim_start_matches(['.', '+']);
+    let unit = rest.chars().last()?;
+    let count: u32 = rest[..rest.len() - unit.len_utf8()].parse().ok()?;
+    (count > 0 && matches!(unit, 'd' | 'w' | 'm' | 'y')).then_some((count, unit))
+}
+
+// The `n`th repetition of `date`
+fn repeat_date(date: NaiveDate, (count, unit): (u32, char), n: u32) -> Option<NaiveDate> {
+    let steps = count.checked_mul(n)?;
+    match unit {
+        'd' => date.checked_add_days(Days::new(steps.into())),
+        'w' => date.checked_add_days(Days::new(u64::from(steps) * 7)),
+        'm' => date.checked_add_months(Months::new(steps)),
+        _ => date.checked_add_months(Months::new(steps.checked_mul(12)?)),
+    }
+}
+
+// Days from `start` to `end` that a timestamp on `date` falls on, with its repetitions if
+// it has a repeater
+fn occurrences(
+    date: NaiveDate,
+    repeater: Option<&str>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<NaiveDate> {
+    let Some(step) = repeater.and_then(repeat_step) else {
+        return (start..=end)
+            .contains(&date)
+            .then_some(date)
+            .into_iter()
+            .collect();
+    };
+    // Skip the repetitions that end before `start`, one short for months of uneven length
+    let (count, unit) = step;
+    let mut n = if start <= date {
+        0
+    } else {
+        let months = (start.year() - date.year()) * 12 + start.month() as i32 - date.month() as i32;
+        let skipped = match unit {
+            'd' => (start - date).num_days() / i64::from(count),
+            'w' => (start - date).num_days() / (7 * i64::from(count)),
+            'm' => i64::from(months) / i64::from(count) - 1,
+            _ => i64::from(months) / (12 * i64::from(count)) - 1,
+        };
+        u32::try_from(skipped).unwrap_or(0)
+    };
+
+    let mut dates = Vec::new();
+    while let Some(next) = repeat_date(date, step, n).filter(|next| *next <= end) {
+        if next >= start {
+            dates.push(next);
+        }
+        n += 1;
+    }
+    dates
+}
+
+/// The CATEGORY of a headline, or the file name without its extension if none is set
+pub(crate) fn entry_category(document: &OrgDocument, headline_id: &str) -> String {
+    document
+        .find_headline(headline_id)
+        .and_then(|headline| property_value(document, headline, "CATEGORY"))
+        .filter(|category| !category.trim().is_empty())
+        .or_else(|| {
+            Path::new(&document.file_path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default()
+}
+
+/// Collect the scheduled, deadline and active timestamp entries of every day from `start`
+/// to `end`, including days without any
+///
+/// Timestamps with a repeater show on each day they repeat on. Timed entries come first in
+/// time order, then all-day ones, as in org-agenda. Closed entries, tasks deferred past the
+/// day, missing documents, read-only archives and files marked `no-agenda` are left out.
+/// Body timestamps need the documents' content, so load evicted ones first.
+pub fn collect_agenda(
+    documents: &[&OrgDocument],
+    start: NaiveDate,
+    end: NaiveDate,
+    schedule: &WorkSchedule,
+) -> Vec<AgendaDay> {
+    let mut days: Vec<AgendaDay> = start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| AgendaDay {
+            date,
+            entries: Vec::new(),
+            holiday: schedule.holiday(date).map(str::to_string),
+        })
+        .collect();
+
+    for document in documents {
+        if document.missing || document.archived || document.flags.no_agenda {
+            continue;
+        }
+        let deferrals = deferrals(document);
+        for (datetime, repeater, entry) in stamped_entries(document) {
+            if entry.kind == TimelineKind::Closed {
+                continue;
+            }
+            let dates = occurrences(datetime.to_naive_date(), repeater.as_deref(), start, end);
+            if dates.is_empty() {
+                continue;
+            }
+            let category = entry_category(document, &entry.headline_id);
+            for date in dates {
+                let deferred = deferrals
+                    .get(&entry.headline_id)
+                    .is_some_and(|until| *until > date);
+                let index = (date - start).num_days() as usize;
+                let (false, Some(day)) = (deferred, days.get_mut(index)) else {
+                    continue;
+                };
+                day.entries.push(AgendaEntry {
+                    document_id: document.id.clone(),
+                    headline_id: entry.headline_id.clone(),
+                    category: category.clone(),
+                    title: entry.title.clone(),
+                    todo_keyword: entry.todo_keyword.clone(),
+                    kind: entry.kind,
+                    time: entry.time.clone(),
+                    overdue_since: None,
+                });
+            }
+        }
+    }
+
+    for day in &mut days {
+        day.entries
+            .sort_by(|a, b| (a.time.is_none(), &a.time).cmp(&(b.time.is_none(), &b.time)));
+    }
+    days
+}
+
+/// Carry open tasks whose deadline or scheduled date has passed forward to `today`, after
+/// its own entries, as org-agenda does; nothing is added if today is not on the agenda
+pub fn carry_overdue(days: &mut [AgendaDay], overdue: Vec<OverdueTask>, today: NaiveDate) {
+    if let Some(day) = days.iter_mut().find(|day| day.date == today) {
+        day.entries
+            .extend(overdue.into_iter().map(|task| task.entry));
+    }
+}
+
+// Title line like org-agenda's: "Week-agenda (W23):"
+pub(crate) fn agenda_title(days: &[AgendaDay]) -> String {
+    let (Some(first), Some(last)) = (days.first(), days.last()) else {
+        return "Agenda".to_string();
+    };
+    let (first_week, last_week) = (first.date.iso_week().week(), last.date.iso_week().week());
+    match days.len() {
+        1 => format!("Day-agenda (W{:02}):", first_week),
+        7 if first_week == last_week => format!("Week-agenda (W{:02}):", first_week),
+        _ => format!("Span-agenda (W{:02}-W{:02}):", first_week, last_week),
+    }
+}
+
+// Day heading like org-agenda-format-date-aligned, with the week on Mondays and the
+// first day, naming the day and month in `language`
+fn day_heading(day: &AgendaDay, first: bool, language: &str) -> String {
+    let week = if first || day.date.weekday() == chrono::Weekday::Mon {
+        format!(" W{:02}", day.date.iso_week().week())
+    } else {
+        String::new()
+    };
+    format!(
+        "{:<10} {:>2} {} {}{}",
+        long_day_name(day.date.weekday(), language),
+        day.date.day(),
+        month_name(day.date.month(), language),
+        day.date.year(),
+        week
+    )
+}
+
+fn kind_label(kind: TimelineKind) -> &'static str {
+    match kind {
+        TimelineKind::Scheduled => "Scheduled:",
+        TimelineKind::Deadline => "Deadline:",
+        TimelineKind::Closed => "Closed:",
+        TimelineKind::Timestamp => "",
+    }
+}
+
+// org-agenda's label for an entry on `date`: its kind, or for a task carried forward,
+// "3 d. ago:" for deadlines and "Sched. 3x:" for scheduled tasks
+pub(crate) fn entry_label(entry: &AgendaEntry, date: NaiveDate) -> String {
+    let Some(since) = entry.overdue_since else {
+        return kind_label(entry.kind).to_string();
+    };
+    let days = (date - since).num_days();
+    match entry.kind {
+        TimelineKind::Deadline => format!("{} d. ago:", days),
+        _ => format!("Sched.{:>2}x:", days),
+    }
+}
+
+fn entry_text(entry: &AgendaEntry) -> String {
+    match &entry.todo_keyword {
+        Some(keyword) => format!("{} {}", keyword, entry.title),
+        None => entry.title.clone(),
+    }
+}
+
+// One entry line with org-agenda's category, time and label columns
+pub(crate) fn entry_line_text(entry: &AgendaEntry, label: &str) -> String {
+    let time = entry.time.as_ref().map_or(String::new(), |time| {
+        format!("{:<12}", format!("{}......", time))
+    });
+    let label = if label.is_empty() {
+        String::new()
+    } else {
+        format!("{:<12}", label)
+    };
+    format!(
+        "  {:<12}{}{}{}\n",
+        format!("{}:", entry.category),
+        time,
+        label,
+        entry_text(entry)
+    )
+}
+
+// Day headings and entry lines, without the title line
+pub(crate) fn days_text(days: &[AgendaDay], language: &str) -> String {
+    let mut output = String::new();
+    for (index, day) in days.iter().enumerate() {
+        output.push_str(&day_heading(day, index == 0, language));
+        if let Some(holiday) = &day.holiday {
+            output.push_str(&format!("  ({})", holiday));
+        }
+        output.push('\n');
+
+        for entry in &day.entries {
+            output.push_str(&entry_line_text(entry, &entry_label(entry, day.date)));
+        }
+    }
+    output
+}
+
+/// Lay out agenda days as org-agenda prints them, naming days and months in `language`
+pub fn agenda_text(days: &[AgendaDay], language: &str) -> String {
+    format!("{}\n{}", agenda_title(days), days_text(days, language))
+}
+
+const AGENDA_STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+h1{font-size:1.4em}\
+h2{font-size:1.05em;margin:1.2em 0 .3em;border-bottom:1px solid #ccc}\
+.holiday{color:#888;font-weight:normal}\
+table{border-collapse:collapse;width:100%}\
+td{padding:.15em .6em .15em 0;vertical-align:top}\
+.category,.time,.kind{white-space:nowrap;color:#555}\
+.keyword{font-weight:bold}\
+@media print{body{margin:0}h2{break-after:avoid}tr{break-inside:avoid}}";
+
+// Wrap sections in a standalone page with the agenda style
+pub(crate) fn html_page(title: &str, body: &str) -> String {
+    let title = escape_html(title);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n{}</body>\n</html>\n",
+        title, AGENDA_STYLE, title, body
+    )
+}
+
+// One entry as a table row; `label` goes in the kind column
+pub(crate) fn entry_row_html(entry: &AgendaEntry, label: &str) -> String {
+    let keyword = entry
+        .todo_keyword
+        .as_ref()
+        .map_or(String::new(), |keyword| {
+            format!("<span class=\"keyword\">{}</span> ", escape_html(keyword))
+        });
+    format!(
+        "<tr><td class=\"category\">{}</td><td class=\"time\">{}</td><td class=\"kind\">{}</td><td>{}{}</td></tr>\n",
+        escape_html(&entry.category),
+        entry.time.as_deref().unwrap_or(""),
+        escape_html(label),
+        keyword,
+        escape_html(&entry.title)
+    )
+}
+
+// A section per day with its heading and entry table
+pub(crate) fn days_html(days: &[AgendaDay], language: &str) -> String {
+    let mut html = String::new();
+    for day in days {
+        let holiday = day.holiday.as_ref().map_or(String::new(), |holiday| {
+            format!(" <span class=\"holiday\">{}</span>", escape_html(holiday))
+        });
+        let heading = format!(
+            "{} {} {} {}",
+            long_day_name(day.date.weekday(), language),
+            day.date.day(),
+            month_name(day.date.month(), language),
+            day.date.year()
+        );
+        html.push_str(&format!(
+            "<section>\n<h2>{}{}</h2>\n",
+            escape_html(&heading),
+            holiday
+        ));
+        if !day.entries.is_empty() {
+            html.push_str("<table>\n");
+            for entry in &day.entries {
+                html.push_str(&entry_row_html(
+                    entry,
+                    entry_label(entry, day.date).trim_end_matches(':'),
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</section>\n");
+    }
+    html
+}
+
+/// Render agenda days as a standalone, printable HTML page
+pub fn agenda_html(days: &[AgendaDay], language: &str) -> String {
+    html_page(
+        agenda_title(days).trim_end_matches(':'),
+        &days_html(days, language),
+    )
+}
+
+/// Export agenda days in the given format, naming days and months in `language`
+pub fn export_agenda(days: &[AgendaDay], format: AgendaFormat, language: &str) -> String {
+    match format {
+        AgendaFormat::Text => agenda_text(days, language),
+        AgendaFormat::Html => agenda_html(days, language),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::digest::collect_overdue;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::todo::TodoConfiguration;
+
+    fn agenda() -> Vec<AgendaDay> {
+        let doc = parse_org_document(
+            "#+CATEGORY: work\n* TODO Report\nDEADLINE: <2024-06-04 Tue>\n* TODO Call <Bob>\nSCHEDULED: <2024-06-04 Tue 10:00>\n* DONE Filed\nCLOSED: [2024-06-03 Mon 09:00]\n* Lunch <2024-06-05 Wed 12:30>\n",
+            Some("work.org"),
+        )
+        .unwrap();
+        let start = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 6, 9).unwrap();
+        collect_agenda(&[&doc], start, end, &WorkSchedule::default())
+    }
+
+    #[test]
+    fn test_collect_agenda() {
+        let days = agenda();
+        assert_eq!(days.len(), 7);
+        // Closed entries are not on the agenda
+        assert!(days[0].entries.is_empty());
+        // The timed entry comes before the all-day deadline
+        let tuesday: Vec<(&str, TimelineKind)> = days[1]
+            .entries
+            .iter()
+            .map(|entry| (entry.title.as_str(), entry.kind))
+            .collect();
+        assert_eq!(
+            tuesday,
+            vec![
+                ("Call <Bob>", TimelineKind::Scheduled),
+                ("Report", TimelineKind::Deadline)
+            ]
+        );
+        assert_eq!(days[1].entries[0].category, "work");
+    }
+
+    #[test]
+    fn test_export_agenda() {
+        let days = agenda();
+        let text = export_agenda(&days, AgendaFormat::Text, "en");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Week-agenda (W23):");
+        assert_eq!(lines[1], "Monday      3 June 2024 W23");
+        assert_eq!(lines[2], "Tuesday     4 June 2024");
+        assert_eq!(
+            lines[3],
+            "  work:       10:00...... Scheduled:  TODO Call <Bob>"
+        );
+        assert_eq!(lines[4], "  work:       Deadline:   TODO Report");
+        assert_eq!(
+            lines[6],
+            "  work:       12:30...... Lunch <2024-06-05 Wed 12:30>"
+        );
+
+        let html = export_agenda(&days, AgendaFormat::Html, "en");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h2>Tuesday 4 June 2024</h2>"));
+        assert!(html.contains("<span class=\"keyword\">TODO</span> Call &lt;Bob&gt;"));
+
+        let german = export_agenda(&days, AgendaFormat::Text, "de");
+        assert!(german.contains("\nMontag      3 Juni 2024 W23\n"));
+    }
+
+    #[test]
+    fn test_repeats_and_overdue() {
+        let doc = parse_org_document(
+            "* TODO Standup\nSCHEDULED: <2024-05-27 Mon 09:00 +1w>\n* Rent <2024-01-04 Thu ++1m>\n* TODO Report\nDEADLINE: <2024-05-31 Fri>\n",
+            Some("/notes/chores.org"),
+        )
+        .unwrap();
+        let start = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 6, 16).unwrap();
+        let mut days = collect_agenda(&[&doc], start, end, &WorkSchedule::default());
+
+        // Repeaters put an entry on every day it repeats on within the range
+        let standups: Vec<u32> = days
+            .iter()
+            .filter(|day| day.entries.iter().any(|entry| entry.title == "Standup"))
+            .map(|day| day.date.day())
+            .collect();
+        assert_eq!(standups, vec![3, 10]);
+        assert_eq!(days[1].entries[0].title, "Rent <2024-01-04 Thu ++1m>");
+        // Without a CATEGORY the file name is used
+        assert_eq!(days[0].entries[0].category, "chores");
+
+        // Open tasks past their date are carried forward to today
+        let today = NaiveDate::from_ymd_opt(2024, 6, 4).unwrap();
+        let overdue = collect_overdue(&[&doc], today, &TodoConfiguration::default());
+        carry_overdue(&mut days, overdue, today);
+        let text = export_agenda(&days, AgendaFormat::Text, "en");
+        assert!(text.contains("  chores:     Sched. 8x:  TODO Standup\n"));
+        assert!(text.contains("  chores:     4 d. ago:   TODO Report\n"));
+
+        assert_eq!(repeat_step(".+2d"), Some((2, 'd')));
+        assert_eq!(repeat_step("+1h"), None);
+        let jan31 = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let feb = occurrences(
+            jan31,
+            Some("+1m"),
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+        );
+        assert_eq!(feb.len(), 2);
+    }
+}
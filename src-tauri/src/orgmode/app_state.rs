@@ -0,0 +1,116 @@
+use crate::orgmode::recent::RecentItem;
+use crate::orgmode::reminders::Reminder;
+use crate::orgmode::trash::TrashEntry;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// Version of the app state bundle format, raised when it changes incompatibly
+pub const APP_STATE_VERSION: u32 = 1;
+
+/// Settings and the state kept in the app data directory, in one file for moving to
+/// another machine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppStateBundle {
+    pub version: u32,
+    pub exported_at: String,         // RFC 3339
+    pub settings: serde_json::Value, // As stored, so bundles from older versions migrate on import
+    #[serde(default)]
+    pub reminders: Vec<Reminder>,
+    #[serde(default)]
+    pub recent: Vec<RecentItem>,
+    #[serde(default)]
+    pub trash: Vec<TrashEntry>,
+}
+
+/// What an app state bundle held
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct AppStateSummary {
+    pub exported_at: String,
+    pub reminders: u32,
+    pub recent_items: u32,
+    pub trash_entries: u32,
+}
+
+impl AppStateBundle {
+    pub fn summary(&self) -> AppStateSummary {
+        AppStateSummary {
+            exported_at: self.exported_at.clone(),
+            reminders: self.reminders.len() as u32,
+            recent_items: self.recent.len() as u32,
+            trash_entries: self.trash.len() as u32,
+        }
+    }
+}
+
+/// Write a bundle, creating the parent directory if needed
+pub fn write_bundle(path: &Path, bundle: &AppStateBundle) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(bundle)
+        .map_err(|e| format!("Failed to serialize app state: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Read a bundle, refusing ones written by a newer version of the app
+pub fn read_bundle(path: &Path) -> Result<AppStateBundle, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let bundle: AppStateBundle = serde_json::from_str(&json)
+        .map_err(|e| format!("{} is not an app state export: {}", path.display(), e))?;
+    if bundle.version > APP_STATE_VERSION {
+        return Err(format!(
+            "{} was exported by a newer version of the app",
+            path.display()
+        ));
+    }
+    if !bundle.settings.is_object() {
+        return Err(format!("{} has no settings", path.display()));
+    }
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("export").join("org-x-state.json");
+        let bundle = AppStateBundle {
+            version: APP_STATE_VERSION,
+            exported_at: "2024-03-01T12:00:00+00:00".to_string(),
+            settings: serde_json::json!({ "monitored_paths": [] }),
+            reminders: vec![Reminder {
+                id: "r1".to_string(),
+                document_id: "/notes/todo.org".to_string(),
+                headline_id: Some("1".to_string()),
+                org_id: None,
+                outline_path: vec!["Pay rent".to_string()],
+                title: "Pay rent".to_string(),
+                remind_at: "2024-03-04T09:00:00+00:00".to_string(),
+                message: String::new(),
+                fired: false,
+            }],
+            recent: Vec::new(),
+            trash: Vec::new(),
+        };
+        write_bundle(&path, &bundle).unwrap();
+        let read = read_bundle(&path).unwrap();
+        assert_eq!(read, bundle);
+        assert_eq!(read.summary().reminders, 1);
+
+        // Lists missing from a bundle are empty; bundles from newer versions are refused
+        fs::write(&path, r#"{"version":1,"exported_at":"","settings":{}}"#).unwrap();
+        assert!(read_bundle(&path).unwrap().trash.is_empty());
+        fs::write(&path, r#"{"version":2,"exported_at":"","settings":{}}"#).unwrap();
+        assert!(read_bundle(&path).is_err());
+        fs::write(&path, "* TODO Not a bundle\n").unwrap();
+        assert!(read_bundle(&path).is_err());
+    }
+}
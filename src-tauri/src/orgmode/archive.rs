@@ -0,0 +1,97 @@
+use crate::orgmode::capture::capture_entry;
+use crate::orgmode::document::{OrgDocument, ARCHIVE_EXTENSION};
+use crate::orgmode::properties::set_headline_properties;
+use chrono::NaiveDateTime;
+
+/// The archive of an org file, `notes.org_archive` for `notes.org`, as org-mode names it
+/// by default
+pub fn archive_path(file_path: &str) -> String {
+    match file_path.strip_suffix(".org") {
+        Some(stem) => format!("{}.{}", stem, ARCHIVE_EXTENSION),
+        None => format!("{}_archive", file_path),
+    }
+}
+
+/// The ARCHIVE_ properties org-mode records on an archived headline: when it was archived,
+/// its file, outline path, category and TODO keyword
+/// Returns None if the headline is not found.
+pub fn archive_properties(
+    document: &OrgDocument,
+    headline_id: &str,
+    now: NaiveDateTime,
+) -> Option<Vec<(&'static str, String)>> {
+    let path = document.headline_path(headline_id)?;
+    let (headline, ancestors) = path.split_last()?;
+    let outline_path: Vec<&str> = ancestors
+        .iter()
+        .map(|ancestor| ancestor.title.raw.as_str())
+        .collect();
+    let category = document
+        .get_inherited_property(headline_id, "CATEGORY")
+        .map_or_else(|| document.category.clone(), str::to_string);
+
+    let mut properties = vec![
+        ("ARCHIVE_TIME", now.format("%Y-%m-%d %a %H:%M").to_string()),
+        ("ARCHIVE_FILE", document.file_path.clone()),
+    ];
+    if !outline_path.is_empty() {
+        properties.push(("ARCHIVE_OLPATH", outline_path.join("/")));
+    }
+    if !category.is_empty() {
+        properties.push(("ARCHIVE_CATEGORY", category));
+    }
+    if let Some(keyword) = &headline.title.todo_keyword {
+        properties.push(("ARCHIVE_TODO", keyword.to_string()));
+    }
+    Some(properties)
+}
+
+/// Append a subtree cut from its file to the end of an archive, as a top-level headline
+/// carrying `properties`
+/// Returns None if the subtree does not start with a headline.
+pub fn append_to_archive(
+    archive: &str,
+    subtree: &str,
+    properties: &[(&str, String)],
+) -> Option<String> {
+    let subtree = set_headline_properties(subtree, 1, properties)?;
+    let mut content = archive.to_string();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&capture_entry(&subtree, 1));
+    Some(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_archive_subtree() {
+        let doc = parse_org_document(
+            "#+CATEGORY: home\n* Projects\n** DONE Paint the shed\nUsed green\n*** DONE Buy paint\n",
+            Some("/notes/todo.org"),
+        )
+        .unwrap();
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 0)
+            .unwrap();
+        assert_eq!(archive_path(&doc.file_path), "/notes/todo.org_archive");
+
+        let properties = archive_properties(&doc, "1.1", now).unwrap();
+        let archived = append_to_archive(
+            "#+TITLE: Archive",
+            "** DONE Paint the shed\nUsed green\n*** DONE Buy paint\n",
+            &properties,
+        )
+        .unwrap();
+        assert_eq!(
+            archived,
+            "#+TITLE: Archive\n* DONE Paint the shed\n:PROPERTIES:\n:ARCHIVE_TIME: 2024-01-15 Mon 10:30\n:ARCHIVE_FILE: /notes/todo.org\n:ARCHIVE_OLPATH: Projects\n:ARCHIVE_CATEGORY: home\n:ARCHIVE_TODO: DONE\n:END:\nUsed green\n** DONE Buy paint\n"
+        );
+        assert!(archive_properties(&doc, "9", now).is_none());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::{flatten_headlines, headline_line_numbers};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+
+/// Kind of duplication found by the audit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DuplicateKind {
+    Id,    // Same :ID: property on several headlines, across all documents
+    Title, // Identical headline titles within one document
+}
+
+/// Where a duplicated headline lives
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HeadlineLocation {
+    pub document_id: String,
+    pub file_path: String,
+    pub headline_id: String,
+    pub line: u32, // 1-based line number, 0 if unknown
+}
+
+/// A value shared by more than one headline
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DuplicateEntry {
+    pub kind: DuplicateKind,
+    pub value: String,
+    pub locations: Vec<HeadlineLocation>,
+}
+
+/// Report duplicated `:ID:` properties and identical titles within the same file
+pub fn find_duplicates(documents: &[&OrgDocument]) -> Vec<DuplicateEntry> {
+    let mut documents = documents.to_vec();
+    documents.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    let mut ids: BTreeMap<String, Vec<HeadlineLocation>> = BTreeMap::new();
+    let mut duplicates = Vec::new();
+
+    for document in documents {
+        let lines = headline_line_numbers(document);
+        let mut titles: BTreeMap<&str, Vec<HeadlineLocation>> = BTreeMap::new();
+
+        for headline in flatten_headlines(&document.headlines) {
+            let location = HeadlineLocation {
+                document_id: document.id.clone(),
+                file_path: document.file_path.clone(),
+                headline_id: headline.id.clone(),
+                line: lines.get(&headline.id).copied().unwrap_or(0) as u32,
+            };
+
+            if let Some(id) = headline.title.get_property_ignore_case("ID") {
+                let id = id.trim();
+                if !id.is_empty() {
+                    ids.entry(id.to_string())
+                        .or_default()
+                        .push(location.clone());
+                }
+            }
+
+            let title = headline.title.raw.trim();
+            if !title.is_empty() {
+                titles.entry(title).or_default().push(location);
+            }
+        }
+
+        duplicates.extend(
+            titles
+                .into_iter()
+                .filter(|(_, locations)| locations.len() > 1)
+                .map(|(title, locations)| DuplicateEntry {
+                    kind: DuplicateKind::Title,
+                    value: title.to_string(),
+                    locations,
+                }),
+        );
+    }
+
+    let mut id_duplicates: Vec<DuplicateEntry> = ids
+        .into_iter()
+        .filter(|(_, locations)| locations.len() > 1)
+        .map(|(id, locations)| DuplicateEntry {
+            kind: DuplicateKind::Id,
+            value: id,
+            locations,
+        })
+        .collect();
+
+    // ID collisions break navigation outright, so list them first
+    id_duplicates.extend(duplicates);
+    id_duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_find_duplicates() {
+        let first = parse_org_document(
+            "* Meeting\n:PROPERTIES:\n:ID: shared\n:END:\n* Notes\n* Meeting\n",
+            Some("a.org"),
+        )
+        .unwrap();
+        let second = parse_org_document(
+            "* Meeting\n* Other\n:PROPERTIES:\n:ID: shared\n:END:\n",
+            Some("b.org"),
+        )
+        .unwrap();
+
+        let duplicates = find_duplicates(&[&second, &first]);
+        assert_eq!(duplicates.len(), 2);
+
+        assert_eq!(duplicates[0].kind, DuplicateKind::Id);
+        assert_eq!(duplicates[0].value, "shared");
+        let files: Vec<(&str, u32)> = duplicates[0]
+            .locations
+            .iter()
+            .map(|l| (l.file_path.as_str(), l.line))
+            .collect();
+        assert_eq!(files, vec![("a.org", 1), ("b.org", 2)]);
+
+        // "Meeting" in b.org appears once there, so only a.org is reported
+        assert_eq!(duplicates[1].kind, DuplicateKind::Title);
+        assert_eq!(duplicates[1].value, "Meeting");
+        let lines: Vec<u32> = duplicates[1].locations.iter().map(|l| l.line).collect();
+        assert_eq!(lines, vec![1, 6]);
+    }
+}
@@ -0,0 +1,367 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::links::resolve_file;
+use crate::orgmode::source::{source_lines, SourceLine};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Output stored under a `#+RESULTS:` line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct BlockResults {
+    pub line: u32, // 1-based line of `#+RESULTS:`
+    pub text: String,
+}
+
+/// A `#+BEGIN_SRC` block with its header arguments, never executed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SourceBlock {
+    pub document_id: String,
+    pub headline_id: Option<String>,
+    pub line: u32, // 1-based line of `#+BEGIN_SRC`
+    pub name: Option<String>,
+    pub language: Option<String>,
+    pub header_args: BTreeMap<String, String>, // File-level `header-args` merged with the block's own
+    pub body: String,
+    pub tangle: Option<String>, // Resolved tangle path, None unless the block is tangled
+    pub results: Option<BlockResults>,
+}
+
+/// A file that source blocks are tangled into
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct TangleTarget {
+    pub path: String,
+    pub document_ids: Vec<String>,
+    pub block_count: u32,
+    pub languages: Vec<String>,
+}
+
+/// Parse header arguments such as `:tangle yes :results output` into key/value pairs
+/// Keys keep their leading colon stripped; flags without a value map to an empty string
+pub fn parse_header_args(text: &str) -> BTreeMap<String, String> {
+    let mut args = BTreeMap::new();
+    let mut key: Option<&str> = None;
+    let mut value: Vec<&str> = Vec::new();
+
+    for token in text.split_whitespace() {
+        if let Some(name) = token.strip_prefix(':').filter(|name| !name.is_empty()) {
+            if let Some(key) = key {
+                args.insert(key.to_string(), value.join(" "));
+            }
+            key = Some(name);
+            value.clear();
+        } else if key.is_some() {
+            value.push(token);
+        }
+    }
+    if let Some(key) = key {
+        args.insert(key.to_string(), value.join(" "));
+    }
+
+    args
+}
+
+// Value after a `#+KEYWORD:` prefix, matched case-insensitively
+fn keyword_value<'a>(line: &'a str, keyword: &str) -> Option<&'a str> {
+    let line = line.trim_start();
+    let prefix = line.get(..keyword.len())?;
+    prefix
+        .eq_ignore_ascii_case(keyword)
+        .then(|| line[keyword.len()..].trim())
+}
+
+// File-level header arguments from `#+PROPERTY: header-args ...` lines
+fn file_header_args(content: &str, language: Option<&str>) -> BTreeMap<String, String> {
+    let mut args = BTreeMap::new();
+    for line in content.lines() {
+        let Some(property) = keyword_value(line, "#+PROPERTY:") else {
+            continue;
+        };
+        let (name, value) = property
+            .split_once(char::is_whitespace)
+            .unwrap_or((property, ""));
+        let applies = match name.strip_prefix("header-args") {
+            Some("") => true,
+            Some(specific) => specific.strip_prefix(':') == language,
+            None => false,
+        };
+        if applies {
+            args.extend(parse_header_args(value));
+        }
+    }
+    args
+}
+
+// File extension for tangling `:tangle yes` blocks of a language
+fn tangle_extension(language: &str) -> &str {
+    match language {
+        "python" => "py",
+        "emacs-lisp" | "elisp" => "el",
+        "shell" | "bash" | "sh" => "sh",
+        "rust" => "rs",
+        "javascript" | "js" => "js",
+        "typescript" => "ts",
+        "ruby" => "rb",
+        "haskell" => "hs",
+        other => other,
+    }
+}
+
+// Resolve the `:tangle` argument of a block to a path
+fn tangle_path(document: &OrgDocument, language: Option<&str>, tangle: &str) -> Option<String> {
+    match tangle {
+        "" | "no" => None,
+        "yes" => {
+            let stem = Path::new(&document.file_path).with_extension("");
+            let extension = tangle_extension(language?);
+            Some(format!("{}.{}", stem.to_string_lossy(), extension))
+        }
+        path => Some(
+            resolve_file(document, path.trim_matches('"'))
+                .to_string_lossy()
+                .to_string(),
+        ),
+    }
+}
+
+// Collect the output following a `#+RESULTS:` line: fixed-width lines, a table,
+// a list, a drawer, or a block. Returns the text and the number of lines used
+fn results_text(lines: &[SourceLine]) -> (String, usize) {
+    let Some(first) = lines.first().map(|line| line.text.trim_start()) else {
+        return (String::new(), 0);
+    };
+
+    let closing = if first.eq_ignore_ascii_case(":results:") {
+        Some(":end:".to_string())
+    } else {
+        keyword_value(first, "#+BEGIN_").map(|kind| {
+            let kind = kind.split_whitespace().next().unwrap_or_default();
+            format!("#+end_{}", kind.to_lowercase())
+        })
+    };
+
+    let count = match closing {
+        Some(closing) => lines
+            .iter()
+            .position(|line| line.text.trim().eq_ignore_ascii_case(&closing))
+            .map_or(lines.len(), |end| end + 1),
+        None => lines
+            .iter()
+            .take_while(|line| {
+                let text = line.text.trim_start();
+                text == ":"
+                    || text.starts_with(": ")
+                    || text.starts_with('|')
+                    || text.starts_with("- ")
+            })
+            .count(),
+    };
+
+    let text = lines[..count]
+        .iter()
+        .map(|line| line.text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    (text, count)
+}
+
+/// Find the source blocks of a document with their header arguments and results
+pub fn find_source_blocks(document: &OrgDocument) -> Vec<SourceBlock> {
+    let lines = source_lines(document);
+    let mut blocks: Vec<SourceBlock> = Vec::new();
+    let mut named_results: Vec<(String, BlockResults)> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = &lines[index];
+
+        if let Some(header) = keyword_value(line.text, "#+BEGIN_SRC") {
+            let Some(end) = lines[index + 1..]
+                .iter()
+                .position(|line| keyword_value(line.text, "#+END_SRC").is_some())
+                .map(|offset| index + 1 + offset)
+            else {
+                break;
+            };
+
+            let language = header
+                .split_whitespace()
+                .next()
+                .filter(|word| !word.starts_with(':'));
+            let mut header_args = file_header_args(&document.content, language);
+            header_args.extend(parse_header_args(header));
+            let name = index
+                .checked_sub(1)
+                .and_then(|previous| keyword_value(lines[previous].text, "#+NAME:"))
+                .map(str::to_string);
+            let tangle = header_args
+                .get("tangle")
+                .and_then(|tangle| tangle_path(document, language, tangle));
+            let body = lines[index + 1..end]
+                .iter()
+                .map(|line| line.text)
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            // Unnamed results directly follow their block, after blank lines
+            let mut next = end + 1;
+            while next < lines.len() && lines[next].text.trim().is_empty() {
+                next += 1;
+            }
+            let mut results = None;
+            if let Some(label) = lines.get(next).and_then(|line| results_label(line.text)) {
+                if label.is_empty() || Some(label) == name.as_deref() {
+                    let (text, count) = results_text(&lines[next + 1..]);
+                    results = Some(BlockResults {
+                        line: lines[next].number as u32,
+                        text,
+                    });
+                    next += 1 + count;
+                }
+            }
+
+            blocks.push(SourceBlock {
+                document_id: document.id.clone(),
+                headline_id: line.headline_id.map(|id| id.to_string()),
+                line: line.number as u32,
+                name,
+                language: language.map(str::to_string),
+                header_args,
+                body,
+                tangle,
+                results,
+            });
+            index = next;
+            continue;
+        }
+
+        // Named results may live elsewhere in the file
+        if let Some(label) = results_label(line.text).filter(|label| !label.is_empty()) {
+            let (text, count) = results_text(&lines[index + 1..]);
+            named_results.push((
+                label.to_string(),
+                BlockResults {
+                    line: line.number as u32,
+                    text,
+                },
+            ));
+            index += 1 + count;
+            continue;
+        }
+
+        index += 1;
+    }
+
+    for (label, results) in named_results {
+        if let Some(block) = blocks
+            .iter_mut()
+            .find(|block| block.results.is_none() && block.name.as_deref() == Some(&label))
+        {
+            block.results = Some(results);
+        }
+    }
+
+    blocks
+}
+
+// Label of a `#+RESULTS:` or `#+RESULTS[hash]:` line, empty when unnamed
+fn results_label(line: &str) -> Option<&str> {
+    let rest = keyword_value(line, "#+RESULTS")?;
+    let rest = match rest.strip_prefix('[') {
+        Some(hashed) => &hashed[hashed.find(']')? + 1..],
+        None => rest,
+    };
+    Some(rest.strip_prefix(':')?.trim())
+}
+
+/// Group tangled source blocks by target file, sorted by path
+pub fn collect_tangle_targets(documents: &[&OrgDocument]) -> Vec<TangleTarget> {
+    let mut targets: BTreeMap<String, TangleTarget> = BTreeMap::new();
+
+    for document in documents {
+        for block in find_source_blocks(document) {
+            let Some(path) = block.tangle else {
+                continue;
+            };
+            let target = targets.entry(path.clone()).or_insert_with(|| TangleTarget {
+                path,
+                document_ids: Vec::new(),
+                block_count: 0,
+                languages: Vec::new(),
+            });
+            target.block_count += 1;
+            if !target.document_ids.contains(&document.id) {
+                target.document_ids.push(document.id.clone());
+            }
+            if let Some(language) = block.language {
+                if !target.languages.contains(&language) {
+                    target.languages.push(language);
+                }
+            }
+        }
+    }
+
+    targets.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const CONTENT: &str = "#+PROPERTY: header-args:python :results output
+* Setup
+#+NAME: greet
+#+BEGIN_SRC python :tangle yes :exports both
+print(\"hi\")
+#+END_SRC
+
+#+RESULTS: greet
+: hi
+
+* Config
+#+begin_src emacs-lisp :tangle init.el
+(setq x 1)
+#+end_src
+#+begin_src sh
+ls
+#+end_src
+#+RESULTS:
+| a | b |
+";
+
+    #[test]
+    fn test_parse_header_args() {
+        let args = parse_header_args(":tangle ~/x.py :results output silent :noweb");
+        assert_eq!(args["tangle"], "~/x.py");
+        assert_eq!(args["results"], "output silent");
+        assert_eq!(args["noweb"], "");
+    }
+
+    #[test]
+    fn test_find_source_blocks() {
+        let doc = parse_org_document(CONTENT, Some("/notes/setup.org")).unwrap();
+        let blocks = find_source_blocks(&doc);
+        assert_eq!(blocks.len(), 3);
+
+        let python = &blocks[0];
+        assert_eq!(python.name.as_deref(), Some("greet"));
+        assert_eq!(python.header_args["results"], "output");
+        assert_eq!(python.tangle.as_deref(), Some("/notes/setup.py"));
+        assert_eq!(python.body, "print(\"hi\")");
+        assert_eq!(python.results.as_ref().unwrap().text, ": hi");
+        assert_eq!(python.headline_id.as_deref(), Some("1"));
+
+        assert_eq!(blocks[1].tangle.as_deref(), Some("/notes/init.el"));
+        assert!(blocks[1].results.is_none());
+        assert!(!blocks[1].header_args.contains_key("results"));
+
+        assert_eq!(blocks[2].tangle, None);
+        assert_eq!(blocks[2].results.as_ref().unwrap().text, "| a | b |");
+
+        let targets = collect_tangle_targets(&[&doc]);
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].path, "/notes/init.el");
+        assert_eq!(targets[1].languages, vec!["python"]);
+    }
+}
@@ -0,0 +1,328 @@
+use crate::orgmode::highlights::quote_block;
+use crate::orgmode::html::escape_html;
+use crate::orgmode::images::percent_decode;
+use crate::orgmode::share::{response, serve, BoxFuture, Request};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Tag of read-later entries
+pub const READING_TAG: &str = "reading";
+
+/// A page sent from the browser to read later
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct Bookmark {
+    pub url: String,
+    pub title: String,
+    pub selection: Option<String>, // Text selected on the page, quoted in the entry
+}
+
+// Value of a form-encoded query parameter
+fn query_value(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .and_then(|(_, value)| percent_decode(&value.replace('+', " ")))
+}
+
+impl Bookmark {
+    /// Read a bookmark from a query string with `url`, `title` and `selection`
+    pub fn from_query(query: &str) -> Result<Self, String> {
+        let url = query_value(query, "url")
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| "The bookmark has no URL".to_string())?;
+        Ok(Self {
+            url,
+            title: query_value(query, "title").unwrap_or_default(),
+            selection: query_value(query, "selection").filter(|text| !text.trim().is_empty()),
+        })
+    }
+}
+
+// Percent-encode the brackets, whitespace and control characters of a URL
+fn encode_url(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len());
+    for c in url.chars() {
+        if c == '[' || c == ']' || c.is_whitespace() || c.is_control() {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+    encoded
+}
+
+/// The inbox entry for a bookmark, `* TODO Read: [[url][title]] :reading:`, with the
+/// selection quoted below it
+pub fn bookmark_entry(bookmark: &Bookmark) -> String {
+    // Brackets would end the link early and line breaks the headline, so they are encoded
+    // in the URL and replaced in the title
+    let url = encode_url(bookmark.url.trim());
+    let title = bookmark
+        .title
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('[', "(")
+        .replace(']', ")");
+    let link = if title.is_empty() {
+        format!("[[{}]]", url)
+    } else {
+        format!("[[{}][{}]]", url, title)
+    };
+
+    let mut entry = format!("* TODO Read: {} :{}:\n", link, READING_TAG);
+    if let Some(selection) = &bookmark.selection {
+        entry.push_str(&quote_block(selection));
+    }
+    entry
+}
+
+/// The local endpoint that a bookmarklet sends pages to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct BookmarkConfig {
+    pub enabled: bool,
+    pub port: u16,     // Port on 127.0.0.1; bookmarklets need it to stay the same
+    pub token: String, // Secret the bookmarklet sends, so other pages cannot capture
+    pub inbox: Option<String>, // File that bookmarks are filed in
+}
+
+impl Default for BookmarkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 17421,
+            token: String::new(),
+            inbox: None,
+        }
+    }
+}
+
+impl BookmarkConfig {
+    /// Check that an enabled endpoint has an unprivileged port, a token and an inbox
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.port < 1024 {
+            return Err("The bookmark port must be 1024 or higher".to_string());
+        }
+        if self.token.trim().is_empty() {
+            return Err("The bookmark endpoint needs a token".to_string());
+        }
+        if self
+            .inbox
+            .as_deref()
+            .is_none_or(|inbox| inbox.trim().is_empty())
+        {
+            return Err("Choose an inbox file for bookmarks".to_string());
+        }
+        Ok(())
+    }
+
+    /// A `javascript:` bookmarklet that sends the current page, its title and the
+    /// selected text to the endpoint in a small window
+    pub fn bookmarklet(&self) -> String {
+        format!(
+            "javascript:(()=>{{const p=new URLSearchParams({{token:{},url:location.href,title:document.title,selection:String(getSelection())}});window.open('http://127.0.0.1:{}/capture?'+p,'orgx','width=360,height=140');}})();",
+            serde_json::Value::String(self.token.clone()),
+            self.port
+        )
+    }
+}
+
+// A short page for the bookmarklet's window, which closes itself after success
+fn result_page(status: &str, message: &str, close: bool) -> String {
+    let script = if close {
+        "<script>setTimeout(() => window.close(), 1200);</script>"
+    } else {
+        ""
+    };
+    response(
+        status,
+        "text/html; charset=utf-8",
+        &format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>org-x</title>\n</head>\n<body style=\"font-family:sans-serif\">\n<p>{}</p>\n{}\n</body>\n</html>\n",
+            escape_html(message),
+            script
+        ),
+    )
+}
+
+/// A running bookmark endpoint
+pub struct BookmarkServer {
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl BookmarkServer {
+    /// Accept `GET /capture?token=…&url=…&title=…&selection=…` on the configured port
+    /// of 127.0.0.1, handing bookmarks with the right token to `capture`
+    pub async fn start(
+        config: &BookmarkConfig,
+        capture: impl Fn(Bookmark) -> BoxFuture<Result<(), String>> + Send + Sync + 'static,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", config.port)).await?;
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        let token = config.token.clone();
+        let capture = Arc::new(capture);
+
+        let task = serve(
+            listener,
+            stopped,
+            Arc::new(move |request: Request| -> BoxFuture<String> {
+                let (token, capture) = (token.clone(), capture.clone());
+                Box::pin(async move {
+                    let target = request.target.as_str();
+                    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+                    if request.method != "GET" || path != "/capture" {
+                        return response("404 Not Found", "text/plain", "Not found");
+                    }
+                    if query_value(query, "token").as_deref() != Some(token.as_str()) {
+                        return result_page("403 Forbidden", "The bookmark token is wrong", false);
+                    }
+                    match Bookmark::from_query(query) {
+                        Ok(bookmark) => match capture(bookmark).await {
+                            Ok(()) => result_page("200 OK", "Saved to read later", true),
+                            Err(e) => result_page("500 Internal Server Error", &e, false),
+                        },
+                        Err(e) => result_page("400 Bad Request", &e, false),
+                    }
+                })
+            }),
+        );
+        Ok(Self { shutdown, task })
+    }
+
+    /// Stop accepting bookmarks, waiting until the port is free again
+    pub async fn stop(self) {
+        // The server task may already be gone, in which case there is nothing to stop
+        let _ = self.shutdown.send(());
+        let _ = self.task.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    #[test]
+    fn test_bookmark_entry() {
+        let bookmark = Bookmark::from_query(
+            "url=https%3A%2F%2Fexample.com%2Fa%5B1%5D&title=Rust+%5Bblog%5D%0A+post&selection=Quoted+text",
+        )
+        .unwrap();
+        assert_eq!(
+            bookmark_entry(&bookmark),
+            "* TODO Read: [[https://example.com/a%5B1%5D][Rust (blog) post]] :reading:\n#+begin_quote\nQuoted text\n#+end_quote\n"
+        );
+        let untitled = Bookmark {
+            url: "https://example.com".to_string(),
+            title: " ".to_string(),
+            selection: None,
+        };
+        assert_eq!(
+            bookmark_entry(&untitled),
+            "* TODO Read: [[https://example.com]] :reading:\n"
+        );
+        let injected = Bookmark {
+            url: "https://example.com/a\r\n* TODO Injected".to_string(),
+            title: "Title\r\n* DONE\u{b}Injected".to_string(),
+            selection: None,
+        };
+        assert_eq!(
+            bookmark_entry(&injected),
+            "* TODO Read: [[https://example.com/a%0D%0A*%20TODO%20Injected][Title * DONE Injected]] :reading:\n"
+        );
+        assert!(Bookmark::from_query("title=No+link").is_err());
+    }
+
+    async fn get(port: u16, target: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        stream
+            .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).as_bytes())
+            .await
+            .unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).await.unwrap();
+        reply
+    }
+
+    #[tokio::test]
+    async fn test_bookmark_server() {
+        // Take a free port to configure, as the endpoint listens on a fixed one
+        let port = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = BookmarkConfig {
+            enabled: true,
+            port,
+            token: "secret".to_string(),
+            inbox: Some("inbox.org".to_string()),
+        };
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let sink = captured.clone();
+        let server = BookmarkServer::start(&config, move |bookmark| {
+            sink.lock().unwrap().push(bookmark);
+            Box::pin(async { Ok(()) })
+        })
+        .await
+        .unwrap();
+
+        let reply = get(port, "/capture?token=guess&url=https%3A%2F%2Fexample.com").await;
+        assert!(reply.starts_with("HTTP/1.1 403"));
+        let reply = get(
+            port,
+            "/capture?token=secret&url=https%3A%2F%2Fexample.com&title=Example",
+        )
+        .await;
+        assert!(reply.starts_with("HTTP/1.1 200 OK"));
+        assert_eq!(captured.lock().unwrap()[0].title, "Example");
+
+        // Long selections span several reads and arrive whole
+        let selection = "word+".repeat(2000);
+        let reply = get(
+            port,
+            &format!(
+                "/capture?token=secret&url=https%3A%2F%2Fexample.com&selection={}end",
+                selection
+            ),
+        )
+        .await;
+        assert!(reply.starts_with("HTTP/1.1 200 OK"));
+        let long = captured.lock().unwrap()[1].selection.clone().unwrap();
+        assert_eq!(long.len(), 10_003);
+        assert!(long.ends_with("word end"));
+
+        // A line over the 64 KiB limit is refused rather than cut; it is sent without a line
+        // break so the server reads every byte before answering
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let start = "GET /capture?selection=";
+        let line = format!("{}{}", start, "x".repeat(64 * 1024 + 1 - start.len()));
+        stream.write_all(line.as_bytes()).await.unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).await.unwrap();
+        assert!(reply.starts_with("HTTP/1.1 414"));
+
+        server.stop().await;
+
+        // The port is free again once stop returns, so the endpoint can restart at once
+        let restarted = BookmarkServer::start(&config, |_| Box::pin(async { Ok(()) })).await;
+        restarted.unwrap().stop().await;
+    }
+}
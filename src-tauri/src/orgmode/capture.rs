@@ -0,0 +1,147 @@
+use crate::orgmode::datetree::file_under_datetree;
+use crate::orgmode::journal::append_to_entry;
+use crate::orgmode::source::headline_level;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Where captured text is filed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub enum CaptureTarget {
+    /// Appended as a top-level headline at the end of a file
+    File { path: String },
+    /// Filed under the journal entry of a day (YYYY-MM-DD), today if None
+    Journal { date: Option<String> },
+    /// Filed under the Year → Month → Day headlines of a date tree, today if None
+    Datetree { path: String, date: Option<String> },
+}
+
+/// Turn captured text into a subtree whose first headline is at `level`
+/// Text that does not start with a headline becomes the title of one,
+/// and deeper headlines keep their depth relative to the first
+pub fn capture_entry(text: &str, level: usize) -> String {
+    let text = text.trim_matches('\n');
+    let first_level = text.lines().next().and_then(headline_level);
+
+    let mut output = String::with_capacity(text.len() + level + 2);
+    if first_level.is_none() {
+        output.push_str(&"*".repeat(level));
+        output.push(' ');
+    }
+
+    let base = first_level.unwrap_or(0);
+    for line in text.lines() {
+        match headline_level(line) {
+            Some(stars) if first_level.is_some() => {
+                // Keep headlines shallower than the first one at least at `level`
+                let depth = (stars + level).saturating_sub(base).max(level);
+                output.push_str(&"*".repeat(depth));
+                output.push_str(&line[stars..]);
+            }
+            Some(stars) => {
+                output.push_str(&"*".repeat(stars + level));
+                output.push_str(&line[stars..]);
+            }
+            None => output.push_str(line),
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Where a captured or refiled subtree goes within its target file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryPlacement<'a> {
+    End,                 // A top-level headline at the end of the file
+    Under(&'a str),      // The last child of the journal entry with this heading
+    Datetree(NaiveDate), // The last child of the date tree's day headline
+}
+
+/// File captured text into a target file's content as a subtree at `placement`
+/// The file keeps its line endings, so a CRLF file gets the subtree, and any date tree
+/// headlines created for it, with CRLF whatever the text uses. Returns None if the
+/// journal entry is missing.
+pub fn file_entry(content: &str, placement: EntryPlacement, text: &str) -> Option<String> {
+    let crlf = content.contains("\r\n");
+    let mut content = content.replace("\r\n", "\n");
+    let text = &text.replace("\r\n", "\n");
+    let mut output = match placement {
+        EntryPlacement::End => {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&capture_entry(text, 1));
+            content
+        }
+        EntryPlacement::Under(heading) => {
+            append_to_entry(&content, heading, &capture_entry(text, 2))?
+        }
+        EntryPlacement::Datetree(date) => {
+            file_under_datetree(&content, date, &capture_entry(text, 4))
+        }
+    };
+    if crlf {
+        output = output.replace('\n', "\r\n");
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::subtree::{subtree_lines, take_lines};
+
+    #[test]
+    fn test_capture_entry() {
+        assert_eq!(capture_entry("Call Alice", 1), "* Call Alice\n");
+        assert_eq!(
+            capture_entry("Meeting notes\nAgenda\n* Decision\n", 2),
+            "** Meeting notes\nAgenda\n*** Decision\n"
+        );
+        assert_eq!(
+            capture_entry("*** Idea\nBody\n**** Detail", 2),
+            "** Idea\nBody\n*** Detail\n"
+        );
+    }
+
+    #[test]
+    fn test_refile_into_crlf_file() {
+        // Refiling cuts the subtree out of its file and files it at the target
+        let source = parse_org_document(
+            "* Inbox\n** TODO Call Alice\nAbout the invoice\n* Later\n",
+            Some("inbox.org"),
+        )
+        .unwrap();
+        let lines = subtree_lines(&source, &source.headlines[0].children[0].id).unwrap();
+        let (remaining, removed) = take_lines(&source.content, lines);
+        assert_eq!(remaining, "* Inbox\n* Later\n");
+
+        let date = NaiveDate::from_ymd_opt(2024, 5, 16).unwrap();
+        let target = "#+TITLE: Log\r\n* 2024\r\n";
+        assert_eq!(
+            file_entry(target, EntryPlacement::Datetree(date), &removed).unwrap(),
+            "#+TITLE: Log\r\n* 2024\r\n** 2024-05 May\r\n*** 2024-05-16 Thursday\r\n**** TODO Call Alice\r\nAbout the invoice\r\n"
+        );
+
+        // A subtree cut from a CRLF file does not bring its line endings into an LF file
+        let journal = "* Thursday, 2024-05-16\n* Friday, 2024-05-17\n";
+        let filed = file_entry(
+            journal,
+            EntryPlacement::Under("Thursday, 2024-05-16"),
+            &removed.replace('\n', "\r\n"),
+        );
+        assert_eq!(
+            filed.as_deref(),
+            Some("* Thursday, 2024-05-16\n** TODO Call Alice\nAbout the invoice\n* Friday, 2024-05-17\n")
+        );
+        assert_eq!(
+            file_entry(journal, EntryPlacement::Under("Saturday"), &removed),
+            None
+        );
+        assert_eq!(
+            file_entry("* Notes", EntryPlacement::End, &removed).unwrap(),
+            "* Notes\n* TODO Call Alice\nAbout the invoice\n"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::{flatten_headlines, source_lines};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A `#+CATEGORY:` keyword or `:CATEGORY:` property line setting a given category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct CategoryOccurrence {
+    pub document_id: String,
+    pub file_path: String,
+    pub headline_id: Option<String>, // None for the file-level keyword
+    pub line: u32,                   // 1-based line number
+    pub text: String,
+}
+
+// Byte range of the value on a category keyword or property line
+fn category_value_span(line: &str) -> Option<(usize, usize)> {
+    let indent = line.len() - line.trim_start().len();
+    let key_end = ["#+CATEGORY:", ":CATEGORY:"].iter().find_map(|key| {
+        line.get(indent..indent + key.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(key))
+            .map(|_| indent + key.len())
+    })?;
+    let rest = &line[key_end..];
+    let start = key_end + (rest.len() - rest.trim_start().len());
+    Some((start, start + rest.trim().len()))
+}
+
+/// Whether a document sets `category` anywhere, judged from its parsed keywords and
+/// properties, so it also works for documents whose content was evicted
+pub fn uses_category(document: &OrgDocument, category: &str) -> bool {
+    document.category == category
+        || flatten_headlines(&document.headlines)
+            .iter()
+            .any(|headline| headline.title.get_property_ignore_case("CATEGORY") == Some(category))
+}
+
+/// Find the lines of a document that set `category`
+pub fn find_category_occurrences(
+    document: &OrgDocument,
+    category: &str,
+) -> Vec<CategoryOccurrence> {
+    source_lines(document)
+        .into_iter()
+        .filter(|line| {
+            category_value_span(line.text)
+                .is_some_and(|(start, end)| &line.text[start..end] == category)
+        })
+        .map(|line| CategoryOccurrence {
+            document_id: document.id.clone(),
+            file_path: document.file_path.clone(),
+            headline_id: line.headline_id.map(|id| id.to_string()),
+            line: line.number as u32,
+            text: line.text.to_string(),
+        })
+        .collect()
+}
+
+/// Replace `old` with `new` on every category keyword and property line
+/// Returns None if nothing set the category
+pub fn rewrite_category(content: &str, old: &str, new: &str) -> Option<String> {
+    let mut output = String::with_capacity(content.len());
+    let mut replaced = false;
+
+    for line in content.split_inclusive('\n') {
+        match category_value_span(line) {
+            Some((start, end)) if &line[start..end] == old => {
+                output.push_str(&line[..start]);
+                output.push_str(new);
+                output.push_str(&line[end..]);
+                replaced = true;
+            }
+            _ => output.push_str(line),
+        }
+    }
+
+    replaced.then_some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_category_rename() {
+        let content = "#+CATEGORY: work\n* Meeting\n:PROPERTIES:\n:CATEGORY:  work\n:END:\n* Chores\n:PROPERTIES:\n:category: home\n:END:\nMy work notes\n";
+        let doc = parse_org_document(content, Some("tasks.org")).unwrap();
+        assert!(uses_category(&doc, "work"));
+        assert!(!uses_category(&doc, "garden"));
+
+        let occurrences = find_category_occurrences(&doc, "work");
+        let lines: Vec<u32> = occurrences.iter().map(|o| o.line).collect();
+        assert_eq!(lines, vec![1, 4]);
+        assert_eq!(occurrences[0].headline_id, None);
+        assert_eq!(
+            occurrences[1].headline_id.as_deref(),
+            Some(doc.headlines[0].id.as_str())
+        );
+
+        let updated = rewrite_category(content, "work", "office").unwrap();
+        assert!(updated.starts_with("#+CATEGORY: office\n"));
+        assert!(updated.contains(":CATEGORY:  office\n"));
+        assert!(updated.contains(":category: home\n"));
+        assert!(updated.contains("My work notes\n"));
+        assert!(rewrite_category(content, "garden", "yard").is_none());
+    }
+}
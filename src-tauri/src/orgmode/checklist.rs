@@ -0,0 +1,294 @@
+use crate::orgmode::logbook::{headline_lines, join_lines};
+use crate::orgmode::source::headline_level;
+
+/// A plain list item line, split into its parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem<'a> {
+    pub indent: usize,
+    pub bullet: &'a str,        // `-`, `+`, `1.` or `1)`
+    pub checkbox: Option<char>, // ' ', 'X' or '-' inside `[ ]`
+    pub text: &'a str,
+}
+
+impl ListItem<'_> {
+    pub fn is_checked(&self) -> bool {
+        self.checkbox
+            .is_some_and(|state| state.eq_ignore_ascii_case(&'X'))
+    }
+}
+
+/// Parse a line as a plain list item with a `-`, `+` or numbered bullet
+pub fn parse_list_item(line: &str) -> Option<ListItem<'_>> {
+    let item = line.trim_start();
+    let indent = line.len() - item.len();
+    let after_bullet = match item.strip_prefix(['-', '+']) {
+        Some(rest) => rest,
+        None => {
+            let number = item.trim_start_matches(|c: char| c.is_ascii_digit());
+            if number.len() == item.len() {
+                return None;
+            }
+            number.strip_prefix(['.', ')'])?
+        }
+    };
+    let bullet = &item[..item.len() - after_bullet.len()];
+    let rest = after_bullet.trim_start_matches([' ', '\t']);
+    if rest.len() == after_bullet.len() && !rest.is_empty() {
+        return None;
+    }
+
+    let checkbox = rest
+        .strip_prefix('[')
+        .and_then(|inner| inner.chars().next())
+        .filter(|state| matches!(state, ' ' | 'X' | 'x' | '-') && rest[2..].starts_with(']'));
+    let text = match checkbox {
+        Some(_) => rest[3..].trim_start(),
+        None => rest,
+    };
+    Some(ListItem {
+        indent,
+        bullet,
+        checkbox,
+        text,
+    })
+}
+
+// Line ranges (0-based, end exclusive) of the top-level items of the first plain list in
+// the section after a headline, skipping drawers and blocks
+pub(crate) fn checklist_items(lines: &[String], headline: usize) -> Vec<(usize, usize)> {
+    let end = lines[headline + 1..]
+        .iter()
+        .position(|line| headline_level(line).is_some())
+        .map_or(lines.len(), |offset| headline + 1 + offset);
+
+    let mut items: Vec<(usize, usize)> = Vec::new();
+    let mut list_indent = None;
+    let mut in_drawer = false;
+    let mut in_block = false;
+    for (index, line) in lines.iter().enumerate().take(end).skip(headline + 1) {
+        let trimmed = line.trim();
+        if in_drawer || in_block {
+            in_drawer &= !trimmed.eq_ignore_ascii_case(":END:");
+            in_block &= !trimmed.to_ascii_uppercase().starts_with("#+END");
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        match (parse_list_item(line), list_indent) {
+            (Some(item), None) => {
+                list_indent = Some(item.indent);
+                items.push((index, index + 1));
+            }
+            (Some(item), Some(top)) if item.indent == top => items.push((index, index + 1)),
+            // Nested items and continuation lines belong to the item above
+            (_, Some(top)) if indent > top => {}
+            (_, Some(_)) => break,
+            (None, None) => {
+                in_drawer = trimmed.len() > 2
+                    && trimmed.starts_with(':')
+                    && trimmed.ends_with(':')
+                    && !trimmed[1..trimmed.len() - 1].contains([':', ' ']);
+                in_block = trimmed.to_ascii_uppercase().starts_with("#+BEGIN");
+            }
+        }
+        if let Some(last) = items.last_mut() {
+            last.1 = index + 1;
+        }
+    }
+    items
+}
+
+// Index of the line after the last non-blank line of a headline's section, where a new
+// list goes
+fn new_list_line(lines: &[String], headline: usize) -> usize {
+    let end = lines[headline + 1..]
+        .iter()
+        .position(|line| headline_level(line).is_some())
+        .map_or(lines.len(), |offset| headline + 1 + offset);
+    (headline + 1..end)
+        .rev()
+        .find(|index| !lines[*index].trim().is_empty())
+        .map_or(headline + 1, |index| index + 1)
+}
+
+/// Rewrite the `[n/m]` and `[n%]` statistics cookies on a headline line
+pub fn update_cookies(line: &str, checked: usize, total: usize) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let inner = &rest[1..end];
+        let is_fraction = inner
+            .split_once('/')
+            .is_some_and(|(a, b)| [a, b].iter().all(|n| n.chars().all(|c| c.is_ascii_digit())));
+        let is_percent = inner
+            .strip_suffix('%')
+            .is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()));
+
+        if is_fraction {
+            output.push_str(&format!("[{}/{}]", checked, total));
+        } else if is_percent {
+            let percent = (checked * 100).checked_div(total).unwrap_or(0);
+            output.push_str(&format!("[{}%]", percent));
+        } else {
+            output.push_str(&rest[..=end]);
+        }
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+// Renumber ordered items and refresh the headline's cookies from the checkboxes
+// Cookies are left alone when no checkboxes are left to count
+fn refresh(lines: &mut [String], headline: usize) {
+    let items = checklist_items(lines, headline);
+    let mut number = 0;
+    let (mut checked, mut total) = (0, 0);
+    for (start, _) in items {
+        let line = &lines[start];
+        let Some(item) = parse_list_item(line) else {
+            continue;
+        };
+        if item.checkbox.is_some() {
+            total += 1;
+            checked += usize::from(item.is_checked());
+        }
+        if let Some(delimiter) = item.bullet.strip_prefix(|c: char| c.is_ascii_digit()) {
+            let delimiter = delimiter.trim_start_matches(|c: char| c.is_ascii_digit());
+            number += 1;
+            let bullet_start = item.indent;
+            let bullet_end = bullet_start + item.bullet.len();
+            let renumbered = format!(
+                "{}{}{}{}",
+                &line[..bullet_start],
+                number,
+                delimiter,
+                &line[bullet_end..]
+            );
+            lines[start] = renumbered;
+        }
+    }
+    if total > 0 {
+        lines[headline] = update_cookies(&lines[headline], checked, total);
+    }
+}
+
+/// Add an unchecked item to the checklist of the headline on a 1-based line
+///
+/// The item goes before the top-level item at `position`, or at the end of the list if
+/// `position` is None or past the end, and copies the indentation and bullet of the
+/// existing items. A headline without a list gets one at the end of its body. Ordered
+/// lists are renumbered and statistics cookies updated. Returns None if the line is not a
+/// headline
+pub fn add_checklist_item(
+    content: &str,
+    headline_line: usize,
+    text: &str,
+    position: Option<usize>,
+) -> Option<String> {
+    let (mut lines, headline) = headline_lines(content, headline_line)?;
+    let items = checklist_items(&lines, headline);
+
+    let (at, prefix) = match items.first() {
+        Some((first, _)) => {
+            let item = parse_list_item(&lines[*first])?;
+            let at = position
+                .and_then(|position| items.get(position))
+                .map_or(items[items.len() - 1].1, |(start, _)| *start);
+            (
+                at,
+                format!("{}{}", &lines[*first][..item.indent], item.bullet),
+            )
+        }
+        None => (new_list_line(&lines, headline), "-".to_string()),
+    };
+    lines.insert(at, format!("{} [ ] {}", prefix, text.trim()));
+
+    refresh(&mut lines, headline);
+    Some(join_lines(lines, content))
+}
+
+/// Remove a top-level item, with its nested items and continuation lines, from the
+/// checklist of the headline on a 1-based line
+/// Returns None if the line is not a headline or the item does not exist
+pub fn remove_checklist_item(content: &str, headline_line: usize, index: usize) -> Option<String> {
+    let (mut lines, headline) = headline_lines(content, headline_line)?;
+    let (start, end) = *checklist_items(&lines, headline).get(index)?;
+    lines.drain(start..end);
+
+    refresh(&mut lines, headline);
+    Some(join_lines(lines, content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "* TODO Pack [1/2] [50%]\n:LOGBOOK:\n- Note taken\n:END:\n  - [X] Tent\n    - poles\n  - [ ] Stove\n\n* Other\n";
+
+    #[test]
+    fn test_parse_list_item() {
+        let item = parse_list_item("  3) [X] Done").unwrap();
+        assert_eq!((item.indent, item.bullet, item.text), (2, "3)", "Done"));
+        assert!(item.is_checked());
+        assert_eq!(parse_list_item("- plain").unwrap().checkbox, None);
+        assert!(parse_list_item("-5 degrees").is_none());
+        assert!(parse_list_item("2024 was a year").is_none());
+    }
+
+    #[test]
+    fn test_add_and_remove_items() {
+        let added = add_checklist_item(CONTENT, 1, "Matches", Some(1)).unwrap();
+        assert_eq!(
+            added,
+            "* TODO Pack [1/3] [33%]\n:LOGBOOK:\n- Note taken\n:END:\n  - [X] Tent\n    - poles\n  - [ ] Matches\n  - [ ] Stove\n\n* Other\n"
+        );
+        let appended = add_checklist_item(CONTENT, 1, "Map", None).unwrap();
+        assert!(appended.contains("  - [ ] Stove\n  - [ ] Map\n\n* Other"));
+
+        // The nested item goes with its parent
+        let removed = remove_checklist_item(CONTENT, 1, 0).unwrap();
+        assert_eq!(
+            removed,
+            "* TODO Pack [0/1] [0%]\n:LOGBOOK:\n- Note taken\n:END:\n  - [ ] Stove\n\n* Other\n"
+        );
+        assert_eq!(remove_checklist_item(CONTENT, 1, 2), None);
+    }
+
+    #[test]
+    fn test_new_and_ordered_lists() {
+        let created = add_checklist_item("* Trip [/]\nSome notes\n\n* Next\n", 1, "Book", None);
+        assert_eq!(
+            created.unwrap(),
+            "* Trip [0/1]\nSome notes\n- [ ] Book\n\n* Next\n"
+        );
+
+        let ordered = "* Steps\n1. [ ] First\n2. [ ] Second\n";
+        assert_eq!(
+            add_checklist_item(ordered, 1, "Zeroth", Some(0)).unwrap(),
+            "* Steps\n1. [ ] Zeroth\n2. [ ] First\n3. [ ] Second\n"
+        );
+        assert_eq!(
+            remove_checklist_item(ordered, 1, 0).unwrap(),
+            "* Steps\n1. [ ] Second\n"
+        );
+
+        // Without checkboxes there is nothing to count, so the cookie is kept
+        assert_eq!(
+            remove_checklist_item("* Trip [1/1]\n- [X] Book\n", 1, 0).unwrap(),
+            "* Trip [1/1]\n"
+        );
+        assert_eq!(
+            add_checklist_item("* Steps [2/3]\n1. Plain\n", 1, "Zeroth", None).unwrap(),
+            "* Steps [0/1]\n1. Plain\n2. [ ] Zeroth\n"
+        );
+    }
+}
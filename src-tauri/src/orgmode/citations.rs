@@ -0,0 +1,177 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::source_lines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+
+/// An org-cite citation such as `[cite/t:@doe2020; @roe2021]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Citation {
+    pub style: Option<String>, // `t` in `[cite/t:...]`
+    pub keys: Vec<String>,     // Cited keys without the `@`
+}
+
+/// A citation with its location
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct CitationRef {
+    pub document_id: String,
+    pub headline_id: Option<String>, // None for the preamble before the first headline
+    pub line: u32,                   // 1-based line number
+    pub citation: Citation,
+}
+
+/// Bibliography files and citations of one document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DocumentCitations {
+    pub bibliographies: Vec<String>, // Values of `#+BIBLIOGRAPHY:` keywords
+    pub citations: Vec<CitationRef>,
+}
+
+/// A cited key with the documents citing it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct CitedKey {
+    pub key: String,
+    pub count: u32,
+    pub document_ids: Vec<String>,
+}
+
+/// Extract all `[cite:...]` citations from text
+pub fn extract_citations(text: &str) -> Vec<Citation> {
+    let mut citations = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("[cite") {
+        let after = &rest[start + "[cite".len()..];
+        let Some(end) = after.find(']') else {
+            break;
+        };
+        let inner = &after[..end];
+        rest = &after[end + 1..];
+
+        let Some((style, references)) = inner.split_once(':') else {
+            continue;
+        };
+        let style = match style.strip_prefix('/') {
+            Some(style) if !style.is_empty() => Some(style.to_string()),
+            _ if style.is_empty() => None,
+            _ => continue, // Not a citation, e.g. `[citation needed]`
+        };
+
+        let keys: Vec<String> = references
+            .split(';')
+            .filter_map(|reference| {
+                let key = reference.split_once('@')?.1;
+                let key: String = key
+                    .chars()
+                    .take_while(|c| !c.is_whitespace() && *c != ';')
+                    .collect();
+                (!key.is_empty()).then_some(key)
+            })
+            .collect();
+        if !keys.is_empty() {
+            citations.push(Citation { style, keys });
+        }
+    }
+
+    citations
+}
+
+/// Values of `#+BIBLIOGRAPHY:` keywords in a document
+pub fn bibliographies(document: &OrgDocument) -> Vec<String> {
+    document
+        .content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let keyword = line.get(.."#+BIBLIOGRAPHY:".len())?;
+            keyword
+                .eq_ignore_ascii_case("#+BIBLIOGRAPHY:")
+                .then(|| line[keyword.len()..].trim().to_string())
+        })
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Bibliographies and located citations of a document
+pub fn document_citations(document: &OrgDocument) -> DocumentCitations {
+    let citations = source_lines(document)
+        .into_iter()
+        .flat_map(|line| {
+            extract_citations(line.text)
+                .into_iter()
+                .map(move |citation| CitationRef {
+                    document_id: document.id.clone(),
+                    headline_id: line.headline_id.map(|id| id.to_string()),
+                    line: line.number as u32,
+                    citation,
+                })
+        })
+        .collect();
+
+    DocumentCitations {
+        bibliographies: bibliographies(document),
+        citations,
+    }
+}
+
+/// All cited keys across documents, sorted by key
+pub fn collect_cited_keys(documents: &[&OrgDocument]) -> Vec<CitedKey> {
+    let mut keys: BTreeMap<String, CitedKey> = BTreeMap::new();
+
+    for document in documents {
+        for citation in extract_citations(&document.content) {
+            for key in citation.keys {
+                let entry = keys.entry(key.clone()).or_insert_with(|| CitedKey {
+                    key,
+                    count: 0,
+                    document_ids: Vec::new(),
+                });
+                entry.count += 1;
+                if !entry.document_ids.contains(&document.id) {
+                    entry.document_ids.push(document.id.clone());
+                }
+            }
+        }
+    }
+
+    keys.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_extract_citations() {
+        let citations =
+            extract_citations("See [cite/t:see @doe2020 p. 4; @roe:2021] and [cite:@doe2020].");
+        assert_eq!(citations.len(), 2);
+        assert_eq!(citations[0].style.as_deref(), Some("t"));
+        assert_eq!(citations[0].keys, vec!["doe2020", "roe:2021"]);
+        assert_eq!(citations[1].style, None);
+
+        assert!(extract_citations("[citation needed] [cite:no keys]").is_empty());
+    }
+
+    #[test]
+    fn test_document_citations() {
+        let doc = parse_org_document(
+            "#+BIBLIOGRAPHY: refs.bib\n#+bibliography: more.bib\nIntro [cite:@a]\n* Notes\nAs shown [cite:@b;@a]\n",
+            Some("paper.org"),
+        )
+        .unwrap();
+
+        let found = document_citations(&doc);
+        assert_eq!(found.bibliographies, vec!["refs.bib", "more.bib"]);
+        assert_eq!(found.citations.len(), 2);
+        assert_eq!(found.citations[0].headline_id, None);
+        assert_eq!(found.citations[1].headline_id.as_deref(), Some("1"));
+        assert_eq!(found.citations[1].line, 5);
+
+        let keys = collect_cited_keys(&[&doc]);
+        assert_eq!(keys.len(), 2);
+        assert_eq!((keys[0].key.as_str(), keys[0].count), ("a", 2));
+        assert_eq!(keys[1].document_ids, vec!["paper.org"]);
+    }
+}
@@ -0,0 +1,400 @@
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::tags::is_valid_tag;
+use crate::orgmode::todo::StateType;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+// Hues of common keywords, so they keep their familiar colors
+const KNOWN_HUES: &[(&str, f64)] = &[
+    ("TODO", 0.0),
+    ("NEXT", 210.0),
+    ("IN-PROGRESS", 30.0),
+    ("WAITING", 50.0),
+    ("DONE", 120.0),
+];
+
+// Lucide icons of common keywords; others get a plain or checked circle by state
+const KNOWN_ICONS: &[(&str, &str)] = &[
+    ("IN-PROGRESS", "circle-dot"),
+    ("WAITING", "circle-pause"),
+    ("CANCELLED", "circle-x"),
+    ("CANCELED", "circle-x"),
+];
+
+// Relative luminance with equal contrast against white and black, about 4.6:1, so colors
+// read as well in the light theme as in the dark one
+const TARGET_LUMINANCE: f64 = 0.179;
+
+// Relative luminances of the theme variants: about 6:1 against white for the light theme
+// and 9:1 against black for the dark one
+const LIGHT_LUMINANCE: f64 = 0.12;
+const DARK_LUMINANCE: f64 = 0.4;
+
+const TAG_SATURATION: f64 = 0.6;
+
+// Styles chosen in the settings, applied wherever statuses and tags are styled
+static STYLE_OVERRIDES: Lazy<RwLock<StatusStyles>> =
+    Lazy::new(|| RwLock::new(StatusStyles::default()));
+
+/// How a TODO keyword or tag is shown in the light and the dark theme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct StatusStyle {
+    pub light: String,        // `#rrggbb`, readable on light backgrounds
+    pub dark: String,         // `#rrggbb`, readable on dark backgrounds
+    pub icon: Option<String>, // Name of a Lucide icon, such as "circle-check"
+}
+
+/// The colors of a tag, like the `color` and `style` of a TODO keyword's status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct TagColor {
+    pub color: String,      // One color readable in either theme
+    pub style: StatusStyle, // Light and dark theme colors and an icon hint
+}
+
+impl TagColor {
+    /// The colors of a tag, with the styles chosen in the settings
+    pub fn of(tag: &str) -> Self {
+        Self {
+            color: tag_color(tag),
+            style: tag_style(tag),
+        }
+    }
+}
+
+/// A style chosen for a TODO keyword or tag; fields left None keep the generated ones
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct StyleOverride {
+    pub name: String,
+    pub light: Option<String>,
+    pub dark: Option<String>,
+    pub icon: Option<String>,
+}
+
+impl StyleOverride {
+    fn apply(&self, mut style: StatusStyle) -> StatusStyle {
+        if let Some(light) = &self.light {
+            style.light = light.to_ascii_lowercase();
+        }
+        if let Some(dark) = &self.dark {
+            style.dark = dark.to_ascii_lowercase();
+        }
+        if let Some(icon) = &self.icon {
+            style.icon = Some(icon.clone());
+        }
+        style
+    }
+}
+
+/// Styles chosen for TODO keywords and tags, replacing the generated ones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct StatusStyles {
+    pub keywords: Vec<StyleOverride>,
+    pub tags: Vec<StyleOverride>,
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl StatusStyles {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(style) = self
+            .keywords
+            .iter()
+            .find(|style| style.name.is_empty() || style.name.contains(char::is_whitespace))
+        {
+            return Err(format!("Invalid TODO keyword: '{}'", style.name));
+        }
+        if let Some(style) = self.tags.iter().find(|style| !is_valid_tag(&style.name)) {
+            return Err(format!("Invalid tag: '{}'", style.name));
+        }
+        for style in self.keywords.iter().chain(&self.tags) {
+            let name = style.name.as_str();
+            if let Some(color) = [&style.light, &style.dark]
+                .into_iter()
+                .flatten()
+                .find(|color| !is_hex_color(color))
+            {
+                return Err(format!("Invalid color for {}: '{}'", name, color));
+            }
+            if style.icon.as_deref().is_some_and(|icon| {
+                icon.is_empty()
+                    || !icon
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            }) {
+                return Err(format!("Invalid icon for {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// The style of a TODO keyword, generated from its name and state unless chosen here
+    pub fn keyword_style(&self, keyword: &str, state_type: &StateType) -> StatusStyle {
+        let icon = KNOWN_ICONS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(keyword))
+            .map(|(_, icon)| *icon)
+            .unwrap_or(match state_type {
+                StateType::Active => "circle",
+                StateType::Closed => "circle-check",
+            });
+        let (hue, saturation) = keyword_hue(keyword, state_type);
+        let style = StatusStyle {
+            light: palette_color(hue, saturation, LIGHT_LUMINANCE),
+            dark: palette_color(hue, saturation, DARK_LUMINANCE),
+            icon: Some(icon.to_string()),
+        };
+        match self.keywords.iter().find(|chosen| chosen.name == keyword) {
+            Some(chosen) => chosen.apply(style),
+            None => style,
+        }
+    }
+
+    /// The style of a tag, generated from its name unless chosen here
+    pub fn tag_style(&self, tag: &str) -> StatusStyle {
+        let hue = hashed_hue(tag);
+        let style = StatusStyle {
+            light: palette_color(hue, TAG_SATURATION, LIGHT_LUMINANCE),
+            dark: palette_color(hue, TAG_SATURATION, DARK_LUMINANCE),
+            icon: Some("tag".to_string()),
+        };
+        match self.tags.iter().find(|chosen| chosen.name == tag) {
+            Some(chosen) => chosen.apply(style),
+            None => style,
+        }
+    }
+}
+
+/// Use the styles chosen in the settings for statuses and tags styled from now on
+pub fn set_style_overrides(styles: StatusStyles) {
+    match STYLE_OVERRIDES.write() {
+        Ok(mut overrides) => *overrides = styles,
+        Err(poisoned) => *poisoned.into_inner() = styles,
+    }
+}
+
+/// The style of a TODO keyword, with the styles chosen in the settings
+pub fn keyword_style(keyword: &str, state_type: &StateType) -> StatusStyle {
+    match STYLE_OVERRIDES.read() {
+        Ok(overrides) => overrides.keyword_style(keyword, state_type),
+        Err(poisoned) => poisoned.into_inner().keyword_style(keyword, state_type),
+    }
+}
+
+/// The style of a tag, with the styles chosen in the settings
+pub fn tag_style(tag: &str) -> StatusStyle {
+    match STYLE_OVERRIDES.read() {
+        Ok(overrides) => overrides.tag_style(tag),
+        Err(poisoned) => poisoned.into_inner().tag_style(tag),
+    }
+}
+
+/// The style of each tag of a document, from its file tags and headline tags
+pub fn document_tag_styles(
+    filetags: &[String],
+    headlines: &[OrgHeadline],
+) -> HashMap<String, StatusStyle> {
+    let headline_tags = flatten_headlines(headlines)
+        .into_iter()
+        .flat_map(|headline| headline.title.tags.iter().map(|tag| tag.to_string()));
+    filetags
+        .iter()
+        .cloned()
+        .chain(headline_tags)
+        .map(|tag| {
+            let style = tag_style(&tag);
+            (tag, style)
+        })
+        .collect()
+}
+
+// A stable hash (FNV-1a), so a name gets the same color on every run and platform
+fn hash(name: &str) -> u32 {
+    name.bytes().fold(0x811c_9dc5, |hash: u32, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+// Spread hashes over the color wheel by the golden angle, so close hashes get distant hues
+fn hashed_hue(name: &str) -> f64 {
+    (hash(name) as f64 * 137.508) % 360.0
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> [f64; 3] {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    [r + m, g + m, b + m]
+}
+
+// WCAG relative luminance of an sRGB color
+fn luminance(rgb: [f64; 3]) -> f64 {
+    let linear = rgb.map(|channel| {
+        if channel <= 0.03928 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    0.2126 * linear[0] + 0.7152 * linear[1] + 0.0722 * linear[2]
+}
+
+// A `#rrggbb` color of a hue and saturation, with the lightness that meets a relative
+// luminance; luminance grows with lightness, so a binary search finds it
+fn palette_color(hue: f64, saturation: f64, target: f64) -> String {
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..24 {
+        let middle = (low + high) / 2.0;
+        if luminance(hsl_to_rgb(hue, saturation, middle)) < target {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    let [r, g, b] = hsl_to_rgb(hue, saturation, (low + high) / 2.0)
+        .map(|channel| (channel * 255.0).round().clamp(0.0, 255.0) as u8);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+// Hue and saturation of a keyword's colors
+fn keyword_hue(keyword: &str, state_type: &StateType) -> (f64, f64) {
+    let known = KNOWN_HUES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(keyword))
+        .map(|(_, hue)| *hue);
+    let saturation = match (state_type, known) {
+        (StateType::Closed, None) => 0.15,
+        _ => 0.8,
+    };
+    (known.unwrap_or_else(|| hashed_hue(keyword)), saturation)
+}
+
+/// A color for a TODO keyword, the same for the same keyword wherever it is defined
+/// Common keywords keep their familiar hues and other keywords get one from their name.
+/// Closed keywords other than DONE are muted.
+pub fn keyword_color(keyword: &str, state_type: &StateType) -> String {
+    let (hue, saturation) = keyword_hue(keyword, state_type);
+    palette_color(hue, saturation, TARGET_LUMINANCE)
+}
+
+/// A color for a tag, with a hue from its name
+pub fn tag_color(tag: &str) -> String {
+    palette_color(hashed_hue(tag), TAG_SATURATION, TARGET_LUMINANCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channels(color: &str) -> [f64; 3] {
+        [1, 3, 5]
+            .map(|start| u8::from_str_radix(&color[start..start + 2], 16).unwrap() as f64 / 255.0)
+    }
+
+    fn contrast(color: &str, background: f64) -> f64 {
+        let (a, b) = (luminance(channels(color)) + 0.05, background + 0.05);
+        a.max(b) / a.min(b)
+    }
+
+    #[test]
+    fn test_palette_colors() {
+        let todo = keyword_color("TODO", &StateType::Active);
+        assert_eq!(todo, keyword_color("todo", &StateType::Active));
+        let [r, g, b] = channels(&todo);
+        assert!(r > g && r > b);
+
+        // Custom keywords get distinct colors rather than a shared fallback
+        let custom: Vec<String> = ["REVIEW", "BLOCKED", "DRAFT", "IDEA"]
+            .iter()
+            .map(|keyword| keyword_color(keyword, &StateType::Active))
+            .collect();
+        for (index, color) in custom.iter().enumerate() {
+            assert!(!custom[index + 1..].contains(color));
+        }
+        assert_eq!(tag_color("work"), tag_color("work"));
+        assert_ne!(tag_color("work"), tag_color("home"));
+
+        // Every color reads on both a white and a black background
+        let mut colors = custom;
+        colors.extend([
+            todo,
+            tag_color("work"),
+            keyword_color("WONTFIX", &StateType::Closed),
+        ]);
+        for color in &colors {
+            assert!(contrast(color, 1.0) >= 4.5, "{} on white", color);
+            assert!(contrast(color, 0.0) >= 4.5, "{} on black", color);
+        }
+    }
+
+    #[test]
+    fn test_status_styles() {
+        let mut styles = StatusStyles::default();
+        let done = styles.keyword_style("DONE", &StateType::Closed);
+        assert_eq!(done.icon.as_deref(), Some("circle-check"));
+        assert!(contrast(&done.light, 1.0) >= 6.0, "{} on white", done.light);
+        assert!(contrast(&done.dark, 0.0) >= 8.0, "{} on black", done.dark);
+        let waiting = styles.keyword_style("WAITING", &StateType::Active);
+        assert_eq!(waiting.icon.as_deref(), Some("circle-pause"));
+        assert_eq!(styles.tag_style("work").icon.as_deref(), Some("tag"));
+
+        // Chosen styles replace only the fields they set
+        styles.keywords.push(StyleOverride {
+            name: "REVIEW".to_string(),
+            light: Some("#AA00AA".to_string()),
+            dark: None,
+            icon: Some("eye".to_string()),
+        });
+        let review = styles.keyword_style("REVIEW", &StateType::Active);
+        let generated = StatusStyles::default().keyword_style("REVIEW", &StateType::Active);
+        assert_eq!(
+            (review.light.as_str(), review.dark, review.icon.as_deref()),
+            ("#aa00aa", generated.dark, Some("eye"))
+        );
+        assert!(styles.validate().is_ok());
+
+        styles.tags.push(StyleOverride {
+            name: "work".to_string(),
+            light: None,
+            dark: Some("red".to_string()),
+            icon: None,
+        });
+        assert!(styles.validate().is_err());
+        styles.tags[0].dark = None;
+        styles.tags[0].icon = Some("Briefcase".to_string());
+        assert!(styles.validate().is_err());
+        styles.tags[0].icon = None;
+        styles.tags[0].name = "two words".to_string();
+        assert!(styles.validate().is_err());
+    }
+
+    #[test]
+    fn test_document_tag_styles() {
+        let doc = crate::orgmode::parser::parse_org_document(
+            "#+FILETAGS: :project:
+* Task :work:
+** Sub :home:work:
+",
+            Some("tags.org"),
+        )
+        .unwrap();
+        let mut tags: Vec<&str> = doc.tag_styles.keys().map(String::as_str).collect();
+        tags.sort();
+        assert_eq!(tags, vec!["home", "project", "work"]);
+        assert_eq!(doc.tag_styles["work"], tag_style("work"));
+    }
+}
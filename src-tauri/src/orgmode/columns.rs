@@ -0,0 +1,129 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::issues::issue_property;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Prefix of table column ids that show a headline property
+pub const PROPERTY_COLUMN_PREFIX: &str = "property:";
+
+/// Property names of the `property:X` columns among table column ids
+pub fn property_columns<'a>(column_ids: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    column_ids
+        .into_iter()
+        .filter_map(|id| id.strip_prefix(PROPERTY_COLUMN_PREFIX))
+        .map(str::to_string)
+        .collect()
+}
+
+// File tags, then the tags of each ancestor and the headline itself, without duplicates
+fn all_tags(document: &OrgDocument, headline: &OrgHeadline) -> Vec<String> {
+    let path = document
+        .headline_path(&headline.id)
+        .unwrap_or_else(|| vec![headline]);
+    let mut tags: Vec<String> = Vec::new();
+    let inherited = path
+        .iter()
+        .flat_map(|headline| headline.title.tags.iter().map(|tag| tag.to_string()));
+    for tag in document.filetags.iter().cloned().chain(inherited) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn format_tags(tags: &[String]) -> Option<String> {
+    (!tags.is_empty()).then(|| format!(":{}:", tags.join(":")))
+}
+
+/// Compute a property column value the way org's column view does
+/// The special properties ITEM, TODO, PRIORITY, TAGS, ALLTAGS and CATEGORY are derived
+/// from the headline, and ISSUE, ISSUE_STATUS and ISSUE_TITLE from its first issue link;
+/// others are inherited from ancestors and file-level properties.
+pub fn property_value(
+    document: &OrgDocument,
+    headline: &OrgHeadline,
+    property: &str,
+) -> Option<String> {
+    match property.to_ascii_uppercase().as_str() {
+        "ITEM" => Some(headline.title.raw.clone()),
+        "TODO" => headline.title.todo_keyword.as_deref().map(str::to_string),
+        "PRIORITY" => headline.title.priority.map(|priority| priority.to_string()),
+        "TAGS" => {
+            let tags: Vec<String> = headline
+                .title
+                .tags
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect();
+            format_tags(&tags)
+        }
+        "ALLTAGS" => format_tags(&all_tags(document, headline)),
+        "CATEGORY" => document
+            .get_inherited_property(&headline.id, "CATEGORY")
+            .map(str::to_string)
+            .or_else(|| (!document.category.is_empty()).then(|| document.category.clone()))
+            .or_else(|| {
+                Path::new(&document.file_path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            }),
+        issue @ ("ISSUE" | "ISSUE_STATUS" | "ISSUE_TITLE") => issue_property(headline, issue),
+        _ => document
+            .get_inherited_property(&headline.id, property)
+            .map(str::to_string),
+    }
+}
+
+/// Values of the given properties for a headline, keyed by property name
+/// Properties without a value are left out
+pub fn column_values(
+    document: &OrgDocument,
+    headline: &OrgHeadline,
+    properties: &[String],
+) -> BTreeMap<String, String> {
+    properties
+        .iter()
+        .filter_map(|property| {
+            property_value(document, headline, property).map(|value| (property.clone(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_property_values() {
+        let doc = parse_org_document(
+            "#+FILETAGS: :home:\n#+PROPERTY: Owner alice\n* Garden :outdoor:\n:PROPERTIES:\n:agenda-group: Weekend\n:END:\n** TODO [#A] Mow lawn :home:chore:\n:PROPERTIES:\n:Effort: 1:00\n:END:\n",
+            Some("/notes/chores.org"),
+        )
+        .unwrap();
+        let garden = &doc.headlines[0];
+        let mow = &garden.children[0];
+
+        let columns = property_columns(["title", "property:ALLTAGS", "property:agenda-group"]);
+        assert_eq!(columns, vec!["ALLTAGS", "agenda-group"]);
+
+        let values = column_values(&doc, mow, &columns);
+        assert_eq!(values["ALLTAGS"], ":home:outdoor:chore:");
+        assert_eq!(values["agenda-group"], "Weekend");
+
+        assert_eq!(
+            property_value(&doc, mow, "TAGS").as_deref(),
+            Some(":home:chore:")
+        );
+        assert_eq!(property_value(&doc, mow, "PRIORITY").as_deref(), Some("A"));
+        assert_eq!(property_value(&doc, mow, "Owner").as_deref(), Some("alice"));
+        assert_eq!(
+            property_value(&doc, mow, "CATEGORY").as_deref(),
+            Some("chores")
+        );
+        // Missing values are left out rather than sent as empty strings
+        assert!(column_values(&doc, garden, &["Effort".to_string()]).is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::orgmode::datetime::{DateRange, OrgDatetime};
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::logbook::parse_state_changes;
+use crate::orgmode::todo::TodoConfiguration;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+
+/// A task that was closed, with the time it was closed
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompletedTask {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub keyword: String,
+    pub category: String,
+    pub closed: OrgDatetime,
+}
+
+/// Completed tasks sharing a category on a given day
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompletedCategory {
+    pub category: String,
+    pub tasks: Vec<CompletedTask>,
+}
+
+/// All tasks completed on a single day (YYYY-MM-DD)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CompletedDay {
+    pub date: String,
+    pub categories: Vec<CompletedCategory>,
+}
+
+/// Collect tasks closed within the range, grouped by day and category
+///
+/// The closing time comes from the CLOSED planning timestamp, falling back to
+/// the latest logbook state change into the current keyword. Days are ordered
+/// most recent first; categories alphabetically.
+pub fn collect_completed_tasks(documents: &[&OrgDocument], range: &DateRange) -> Vec<CompletedDay> {
+    let mut tasks = Vec::new();
+
+    for document in documents {
+        let default_config;
+        let config = match &document.todo_config {
+            Some(config) => config,
+            None => {
+                default_config = TodoConfiguration::default();
+                &default_config
+            }
+        };
+
+        collect_from_headlines(
+            document,
+            &document.headlines,
+            config,
+            &document.category,
+            range,
+            &mut tasks,
+        );
+    }
+
+    // Most recent first, stable across documents
+    tasks.sort_by(|a, b| {
+        b.closed
+            .to_naive_datetime()
+            .cmp(&a.closed.to_naive_datetime())
+            .then_with(|| a.document_id.cmp(&b.document_id))
+            .then_with(|| a.headline_id.cmp(&b.headline_id))
+    });
+
+    let mut days: BTreeMap<String, BTreeMap<String, Vec<CompletedTask>>> = BTreeMap::new();
+    for task in tasks {
+        let date = task.closed.to_naive_date().format("%Y-%m-%d").to_string();
+        days.entry(date)
+            .or_default()
+            .entry(task.category.clone())
+            .or_default()
+            .push(task);
+    }
+
+    days.into_iter()
+        .rev()
+        .map(|(date, categories)| CompletedDay {
+            date,
+            categories: categories
+                .into_iter()
+                .map(|(category, tasks)| CompletedCategory { category, tasks })
+                .collect(),
+        })
+        .collect()
+}
+
+// Walk the tree carrying the inherited category down to children
+fn collect_from_headlines(
+    document: &OrgDocument,
+    headlines: &[OrgHeadline],
+    config: &TodoConfiguration,
+    inherited_category: &str,
+    range: &DateRange,
+    tasks: &mut Vec<CompletedTask>,
+) {
+    for headline in headlines {
+        let category = headline
+            .title
+            .get_property_ignore_case("CATEGORY")
+            .unwrap_or(inherited_category);
+
+        if let Some(closed) = completion_time(headline, config) {
+            if range.contains(&closed) {
+                tasks.push(CompletedTask {
+                    document_id: document.id.clone(),
+                    headline_id: headline.id.clone(),
+                    title: headline.title.raw.clone(),
+                    keyword: headline
+                        .title
+                        .todo_keyword
+                        .as_deref()
+                        .unwrap_or_default()
+                        .to_string(),
+                    category: category.to_string(),
+                    closed,
+                });
+            }
+        }
+
+        collect_from_headlines(document, &headline.children, config, category, range, tasks);
+    }
+}
+
+/// When a headline was closed, if it is currently in a closed state
+pub fn completion_time(headline: &OrgHeadline, config: &TodoConfiguration) -> Option<OrgDatetime> {
+    completion_time_in(headline, config, &headline.content)
+}
+
+/// Like `completion_time`, reading logbook state changes from `section` instead of the
+/// headline's content, for documents whose content was evicted
+pub fn completion_time_in(
+    headline: &OrgHeadline,
+    config: &TodoConfiguration,
+    section: &str,
+) -> Option<OrgDatetime> {
+    let status = headline.get_todo_status(config)?;
+    if !status.is_closed() {
+        return None;
+    }
+
+    if let Some(closed) = headline
+        .title
+        .planning
+        .as_ref()
+        .and_then(|planning| planning.closed.as_ref())
+        .and_then(|closed| closed.start_date())
+    {
+        return Some(closed.clone());
+    }
+
+    parse_state_changes(section)
+        .into_iter()
+        .filter(|change| change.to == status.keyword)
+        .map(|change| change.timestamp)
+        .max_by_key(|timestamp| timestamp.to_naive_datetime())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const SAMPLE: &str = r#"#+TITLE: Done list
+#+CATEGORY: Work
+
+* DONE Ship release
+CLOSED: [2024-03-05 Tue 16:00]
+* DONE Update docs
+:LOGBOOK:
+- State "DONE"       from "TODO"       [2024-03-05 Tue 09:15]
+:END:
+* Personal
+:PROPERTIES:
+:CATEGORY: Home
+:END:
+** DONE Fix bike
+CLOSED: [2024-03-04 Mon]
+** DONE Old chore
+CLOSED: [2024-02-01 Thu]
+* TODO Still open
+:LOGBOOK:
+- State "DONE"       from "TODO"       [2024-03-04 Mon 10:00]
+- State "TODO"       from "DONE"       [2024-03-05 Tue 10:00]
+:END:
+"#;
+
+    #[test]
+    fn test_collect_completed_tasks() {
+        let doc = parse_org_document(SAMPLE, Some("done.org")).unwrap();
+        let range = DateRange::new("2024-03-01", "2024-03-31");
+        let days = collect_completed_tasks(&[&doc], &range);
+
+        let dates: Vec<&str> = days.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-03-05", "2024-03-04"]);
+
+        let work = &days[0].categories[0];
+        assert_eq!(work.category, "Work");
+        let titles: Vec<&str> = work.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Ship release", "Update docs"]);
+
+        assert_eq!(days[1].categories.len(), 1);
+        assert_eq!(days[1].categories[0].category, "Home");
+        assert_eq!(days[1].categories[0].tasks[0].title, "Fix bike");
+    }
+}
@@ -0,0 +1,296 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::parser::parse_org_document_with_keywords;
+use crate::orgmode::source::headline_sections;
+use crate::orgmode::subtree::{insert_subtree, subtree_lines, take_lines};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// Dropbox: `notes (conflicted copy).org`, `notes (Ann's conflicted copy 2024-03-01).org`
+static DROPBOX_CONFLICT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+?) \([^()]*conflicted copy[^()]*\)(\.[^.]+)$").unwrap());
+
+// Syncthing: `notes.sync-conflict-20240301-120000-ABCDEFG.org`
+static SYNCTHING_CONFLICT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+)\.sync-conflict-\d{8}-\d{6}(?:-[A-Z0-9]+)?(\.[^.]+)$").unwrap());
+
+/// How a headline differs between a file and its conflict copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum HeadlineChange {
+    Added,   // Only in the conflict copy
+    Removed, // Only in the original
+    Changed, // In both, with different text
+}
+
+/// A headline whose section differs, with both sides for a side-by-side view
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HeadlineDiff {
+    pub outline: Vec<String>, // Titles from the top-level headline down to this one
+    pub change: HeadlineChange,
+    pub original: Option<String>, // The headline's own section in the original
+    pub conflict: Option<String>, // The headline's own section in the conflict copy
+}
+
+/// A conflict copy left by a file sync service next to the file it conflicts with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct SyncConflict {
+    pub conflict_path: String,
+    pub original_path: String,
+    pub original_exists: bool,
+    pub diff: Vec<HeadlineDiff>,
+}
+
+/// What to do with a conflict copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ConflictResolution {
+    Merge,   // Add the headlines only in the copy, then delete it unless others differ
+    Discard, // Delete the copy
+}
+
+/// The file a Dropbox or Syncthing conflict copy belongs to, or None for other files
+pub fn conflict_original(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let captures = DROPBOX_CONFLICT
+        .captures(name)
+        .or_else(|| SYNCTHING_CONFLICT.captures(name))?;
+    Some(path.with_file_name(format!("{}{}", &captures[1], &captures[2])))
+}
+
+// A headline keyed by its ID property, or else by its outline path
+pub(crate) struct Entry<'a> {
+    pub(crate) key: String,
+    pub(crate) parent: Option<String>,
+    pub(crate) outline: Vec<String>,
+    pub(crate) headline: &'a OrgHeadline,
+}
+
+// Headlines in file order, keyed so that the same headline matches across versions
+pub(crate) fn entries(document: &OrgDocument) -> Vec<Entry<'_>> {
+    fn visit<'a>(
+        headlines: &'a [OrgHeadline],
+        parent: Option<&str>,
+        outline: &[String],
+        seen: &mut HashMap<String, usize>,
+        out: &mut Vec<Entry<'a>>,
+    ) {
+        for headline in headlines {
+            let mut path = outline.to_vec();
+            path.push(headline.title.raw.clone());
+            let key = match headline.get_property("ID") {
+                Some(id) => format!("id:{}", id),
+                None => {
+                    // Repeated titles under one parent are told apart by their position
+                    let joined = path.join("\u{1f}");
+                    let count = seen.entry(joined.clone()).or_insert(0);
+                    *count += 1;
+                    format!("{}\u{1f}{}", joined, count)
+                }
+            };
+            out.push(Entry {
+                key: key.clone(),
+                parent: parent.map(str::to_string),
+                outline: path.clone(),
+                headline,
+            });
+            visit(&headline.children, Some(&key), &path, seen, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(
+        &document.headlines,
+        None,
+        &[],
+        &mut HashMap::new(),
+        &mut out,
+    );
+    out
+}
+
+pub(crate) fn parse(
+    content: &str,
+    keywords: &(Vec<String>, Vec<String>),
+) -> Result<OrgDocument, String> {
+    parse_org_document_with_keywords(content, None, keywords.clone()).map_err(|e| e.to_string())
+}
+
+/// Headlines that were added, removed or changed in a conflict copy, in original order
+/// followed by the added ones
+pub fn diff_headlines(
+    original: &str,
+    conflict: &str,
+    keywords: &(Vec<String>, Vec<String>),
+) -> Result<Vec<HeadlineDiff>, String> {
+    let (original, conflict) = (parse(original, keywords)?, parse(conflict, keywords)?);
+    let (original_sections, conflict_sections) =
+        (headline_sections(&original), headline_sections(&conflict));
+    let conflict_entries = entries(&conflict);
+    let by_key: HashMap<&str, &Entry> = conflict_entries
+        .iter()
+        .map(|entry| (entry.key.as_str(), entry))
+        .collect();
+
+    let mut diff = Vec::new();
+    let mut matched = HashSet::new();
+    for entry in entries(&original) {
+        let section = original_sections.get(&entry.headline.id).cloned();
+        match by_key.get(entry.key.as_str()) {
+            Some(other) => {
+                matched.insert(other.key.as_str());
+                let other_section = conflict_sections.get(&other.headline.id).cloned();
+                if section != other_section {
+                    diff.push(HeadlineDiff {
+                        outline: entry.outline,
+                        change: HeadlineChange::Changed,
+                        original: section,
+                        conflict: other_section,
+                    });
+                }
+            }
+            None => diff.push(HeadlineDiff {
+                outline: entry.outline,
+                change: HeadlineChange::Removed,
+                original: section,
+                conflict: None,
+            }),
+        }
+    }
+    for entry in &conflict_entries {
+        if !matched.contains(entry.key.as_str()) {
+            diff.push(HeadlineDiff {
+                outline: entry.outline.clone(),
+                change: HeadlineChange::Added,
+                original: None,
+                conflict: conflict_sections.get(&entry.headline.id).cloned(),
+            });
+        }
+    }
+    Ok(diff)
+}
+
+/// Merge a conflict copy into the original by adding the subtrees that only the copy
+/// has, each at the end of its parent's subtree, or of the file for top-level ones
+/// Headlines in both files keep the original's text.
+pub fn merge_conflict(
+    original: &str,
+    conflict: &str,
+    keywords: &(Vec<String>, Vec<String>),
+) -> Result<String, String> {
+    let conflict_document = parse(conflict, keywords)?;
+    let original_keys: HashSet<String> = entries(&parse(original, keywords)?)
+        .into_iter()
+        .map(|entry| entry.key)
+        .collect();
+
+    let mut merged = original.to_string();
+    let mut added: HashSet<&str> = HashSet::new();
+    let conflict_entries = entries(&conflict_document);
+    for entry in &conflict_entries {
+        if original_keys.contains(&entry.key) {
+            continue;
+        }
+        added.insert(entry.key.as_str());
+        // Descendants of an added headline come along with its subtree
+        if entry
+            .parent
+            .as_deref()
+            .is_some_and(|parent| added.contains(parent))
+        {
+            continue;
+        }
+        let Some(range) = subtree_lines(&conflict_document, &entry.headline.id) else {
+            continue;
+        };
+        let (_, subtree) = take_lines(conflict, range);
+
+        let document = parse(&merged, keywords)?;
+        let end = entry
+            .parent
+            .as_deref()
+            .and_then(|parent| {
+                let parent = entries(&document)
+                    .into_iter()
+                    .find(|candidate| candidate.key == parent)?;
+                subtree_lines(&document, &parent.headline.id)
+            })
+            .map_or(merged.lines().count(), |range| range.end);
+        merged = insert_subtree(&merged, end, &subtree);
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords() -> (Vec<String>, Vec<String>) {
+        (vec!["TODO".to_string()], vec!["DONE".to_string()])
+    }
+
+    #[test]
+    fn test_conflict_original() {
+        let original = |name: &str| {
+            conflict_original(&Path::new("/notes").join(name))
+                .map(|path| path.to_string_lossy().to_string())
+        };
+        assert_eq!(
+            original("todo (conflicted copy).org").as_deref(),
+            Some("/notes/todo.org")
+        );
+        assert_eq!(
+            original("todo (Ann's conflicted copy 2024-03-01).org").as_deref(),
+            Some("/notes/todo.org")
+        );
+        assert_eq!(
+            original("todo.sync-conflict-20240301-120000-ABCDEFG.org").as_deref(),
+            Some("/notes/todo.org")
+        );
+        assert_eq!(original("todo (draft).org"), None);
+        assert_eq!(original("todo.org"), None);
+    }
+
+    const ORIGINAL: &str = "* Work\n** TODO Report\n** TODO Review\n* Home\n";
+    const CONFLICT: &str =
+        "* Work\n** DONE Report\n** TODO Budget\n*** Numbers\n* Home\n* Garden\nWater plants\n";
+
+    #[test]
+    fn test_diff_headlines() {
+        let diff = diff_headlines(ORIGINAL, CONFLICT, &keywords()).unwrap();
+        let summary: Vec<(HeadlineChange, String)> = diff
+            .iter()
+            .map(|entry| (entry.change, entry.outline.join(" / ")))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (HeadlineChange::Changed, "Work / Report".to_string()),
+                (HeadlineChange::Removed, "Work / Review".to_string()),
+                (HeadlineChange::Added, "Work / Budget".to_string()),
+                (HeadlineChange::Added, "Work / Budget / Numbers".to_string()),
+                (HeadlineChange::Added, "Garden".to_string()),
+            ]
+        );
+        assert_eq!(diff[0].original.as_deref(), Some("** TODO Report\n"));
+        assert_eq!(diff[0].conflict.as_deref(), Some("** DONE Report\n"));
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        assert_eq!(
+            merge_conflict(ORIGINAL, CONFLICT, &keywords()).unwrap(),
+            "* Work\n** TODO Report\n** TODO Review\n** TODO Budget\n*** Numbers\n* Home\n* Garden\nWater plants\n"
+        );
+        // Headlines with an ID match wherever they moved
+        let moved = merge_conflict(
+            "* Work\n** Plan\n:PROPERTIES:\n:ID: plan\n:END:\n",
+            "* Plan\n:PROPERTIES:\n:ID: plan\n:END:\n",
+            &keywords(),
+        )
+        .unwrap();
+        assert_eq!(moved, "* Work\n** Plan\n:PROPERTIES:\n:ID: plan\n:END:\n");
+    }
+}
@@ -0,0 +1,150 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::flatten_headlines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A headline with contact properties, in the style of org-contacts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Contact {
+    pub document_id: String,
+    pub headline_id: String,
+    pub name: String,
+    pub emails: Vec<String>, // From EMAIL and EMAIL_* properties
+    pub phones: Vec<String>, // From PHONE and PHONE_* properties
+    pub address: Option<String>,
+    pub birthday: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl Contact {
+    /// Case-insensitive match against the name, emails, phones, and tags
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        std::iter::once(&self.name)
+            .chain(&self.emails)
+            .chain(&self.phones)
+            .chain(&self.tags)
+            .any(|value| value.to_lowercase().contains(&query))
+    }
+}
+
+// Whether a property key is `name` or a numbered/suffixed variant such as EMAIL_WORK
+fn is_contact_key(key: &str, name: &str) -> bool {
+    key.get(..name.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(name))
+        && matches!(key.as_bytes().get(name.len()), None | Some(b'_'))
+}
+
+/// Collect contacts from headlines with EMAIL or PHONE properties
+/// Only `contacts_files` are searched, or all documents if the list is empty
+pub fn collect_contacts(documents: &[&OrgDocument], contacts_files: &[String]) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+
+    for document in documents {
+        if !contacts_files.is_empty() && !contacts_files.contains(&document.file_path) {
+            continue;
+        }
+
+        for headline in flatten_headlines(&document.headlines) {
+            let mut properties: Vec<(&str, &str)> = headline
+                .title
+                .properties
+                .iter()
+                .map(|(key, value)| (key.as_ref(), value.as_str()))
+                .collect();
+            properties.sort();
+
+            let emails: Vec<String> = properties
+                .iter()
+                .filter(|(key, _)| is_contact_key(key, "EMAIL"))
+                .flat_map(|(_, value)| value.split(|c: char| c.is_whitespace() || c == ','))
+                .filter(|email| !email.is_empty())
+                .map(str::to_string)
+                .collect();
+            let phones: Vec<String> = properties
+                .iter()
+                .filter(|(key, _)| is_contact_key(key, "PHONE"))
+                .map(|(_, value)| value.trim())
+                .filter(|phone| !phone.is_empty())
+                .map(str::to_string)
+                .collect();
+            if emails.is_empty() && phones.is_empty() {
+                continue;
+            }
+
+            let title = &headline.title;
+            contacts.push(Contact {
+                document_id: document.id.clone(),
+                headline_id: headline.id.clone(),
+                name: title.raw.clone(),
+                emails,
+                phones,
+                address: title
+                    .get_property_ignore_case("ADDRESS")
+                    .map(str::to_string),
+                birthday: title
+                    .get_property_ignore_case("BIRTHDAY")
+                    .map(str::to_string),
+                tags: title.tags.iter().map(|tag| tag.to_string()).collect(),
+            });
+        }
+    }
+
+    contacts.sort_by_key(|contact| contact.name.to_lowercase());
+    contacts
+}
+
+/// Whether a link target can be opened as a contact action (mailto: or tel:)
+pub fn is_contact_link(target: &str) -> bool {
+    target
+        .split_once(':')
+        .is_some_and(|(scheme, rest)| matches!(scheme, "mailto" | "tel") && !rest.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_collect_contacts() {
+        let doc = parse_org_document(
+            "* Friends\n\
+             ** Bob Smith :work:\n\
+             :PROPERTIES:\n\
+             :EMAIL: bob@example.com, bob@home.example\n\
+             :PHONE_MOBILE: +1 555 0100\n\
+             :BIRTHDAY: 1990-04-01\n\
+             :END:\n\
+             ** Alice\n\
+             :PROPERTIES:\n\
+             :EMAIL_WORK: alice@example.com\n\
+             :END:\n\
+             ** Not a contact\n",
+            Some("contacts.org"),
+        )
+        .unwrap();
+
+        let contacts = collect_contacts(&[&doc], &[]);
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].name, "Alice");
+        assert_eq!(
+            contacts[1].emails,
+            vec!["bob@example.com", "bob@home.example"]
+        );
+        assert_eq!(contacts[1].phones, vec!["+1 555 0100"]);
+        assert_eq!(contacts[1].birthday.as_deref(), Some("1990-04-01"));
+        assert!(contacts[1].matches("WORK"));
+        assert!(!contacts[0].matches("bob"));
+
+        assert!(collect_contacts(&[&doc], &["other.org".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_is_contact_link() {
+        assert!(is_contact_link("mailto:bob@example.com"));
+        assert!(is_contact_link("tel:+15550100"));
+        assert!(!is_contact_link("https://example.com"));
+        assert!(!is_contact_link("mailto:"));
+    }
+}
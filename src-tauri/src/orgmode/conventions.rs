@@ -0,0 +1,361 @@
+use crate::orgmode::properties::is_planning_line;
+use crate::orgmode::source::headline_level;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+
+// Planning keywords in the order org-mode writes them
+const PLANNING_ORDER: [&str; 3] = ["CLOSED:", "DEADLINE:", "SCHEDULED:"];
+
+/// Where a headline's planning line and drawers start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum MetadataIndent {
+    Keep,     // Leave them as they are in the file
+    Flush,    // At the start of the line, as Orgzly and beorg write them by default
+    Headline, // Under the headline text, like org-mode's org-adapt-indentation
+}
+
+/// Layout rules for headlines written back to files, so that files also edited in
+/// mobile apps such as Orgzly and beorg don't churn in diffs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct FormatConventions {
+    pub metadata_indent: MetadataIndent,
+    pub tags_column: u32, // Column that tags end at, like org-tags-column; 0 leaves one space
+}
+
+impl Default for FormatConventions {
+    fn default() -> Self {
+        Self {
+            metadata_indent: MetadataIndent::Keep,
+            tags_column: 0,
+        }
+    }
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+// Whether a line opens a drawer such as `:PROPERTIES:` or `:LOGBOOK:`
+fn is_drawer_start(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() > 2
+        && trimmed.starts_with(':')
+        && trimmed.ends_with(':')
+        && !trimmed.eq_ignore_ascii_case(":END:")
+        && trimmed[1..trimmed.len() - 1]
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+// Planning items in org-mode's order, separated by single spaces
+fn ordered_planning(line: &str) -> String {
+    let trimmed = line.trim();
+    let mut starts: Vec<(usize, &str)> = PLANNING_ORDER
+        .iter()
+        .filter_map(|keyword| Some((trimmed.find(keyword)?, *keyword)))
+        .collect();
+    starts.sort_unstable();
+    let mut items: Vec<(usize, String)> = starts
+        .iter()
+        .enumerate()
+        .map(|(index, (start, keyword))| {
+            let end = starts.get(index + 1).map_or(trimmed.len(), |next| next.0);
+            let timestamp = trimmed[start + keyword.len()..end].trim();
+            let rank = PLANNING_ORDER
+                .iter()
+                .position(|k| k == keyword)
+                .unwrap_or(0);
+            (rank, format!("{} {}", keyword, timestamp))
+        })
+        .collect();
+    if items.is_empty() {
+        return trimmed.to_string();
+    }
+    items.sort_by_key(|(rank, _)| *rank);
+    items
+        .into_iter()
+        .map(|(_, item)| item)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// A headline line with its tags moved to end at `column`, or one space after the title
+// if it is too long
+fn align_tags(line: &str, column: u32) -> String {
+    let trimmed = line.trim_end();
+    let Some(space) = trimmed.rfind(char::is_whitespace) else {
+        return line.to_string();
+    };
+    let tags = &trimmed[space + 1..];
+    if tags.len() <= 2 || !tags.starts_with(':') || !tags.ends_with(':') {
+        return line.to_string();
+    }
+    let head = trimmed[..space].trim_end();
+    let width = head.chars().count() + tags.chars().count();
+    let padding = (column as usize).saturating_sub(width).max(1);
+    format!("{}{}{}", head, " ".repeat(padding), tags)
+}
+
+/// Lay out the headline on a 1-based line of `content` by `conventions`
+///
+/// Unless the indent is `Keep`, the planning line is written in org-mode's CLOSED,
+/// DEADLINE, SCHEDULED order and it and the drawers right after it are re-indented,
+/// keeping the indentation of lines inside drawers relative to the drawer. With a tags
+/// column, the headline's tags are aligned to it. Lines are never added or removed, and
+/// content is returned unchanged if the line is not a headline.
+pub fn conform_headline(
+    content: &str,
+    headline_line: usize,
+    conventions: &FormatConventions,
+) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let Some(headline) = headline_line.checked_sub(1) else {
+        return content.to_string();
+    };
+    let Some(level) = lines.get(headline).and_then(|line| headline_level(line)) else {
+        return content.to_string();
+    };
+
+    if conventions.tags_column > 0 {
+        lines[headline] = align_tags(&lines[headline], conventions.tags_column);
+    }
+
+    let indent = match conventions.metadata_indent {
+        MetadataIndent::Keep => None,
+        MetadataIndent::Flush => Some(String::new()),
+        MetadataIndent::Headline => Some(" ".repeat(level + 1)),
+    };
+    if let Some(indent) = indent {
+        let mut index = headline + 1;
+        if lines.get(index).is_some_and(|line| is_planning_line(line)) {
+            lines[index] = format!("{}{}", indent, ordered_planning(&lines[index]));
+            index += 1;
+        }
+        while lines.get(index).is_some_and(|line| is_drawer_start(line)) {
+            let Some(length) = lines[index..]
+                .iter()
+                .position(|line| line.trim().eq_ignore_ascii_case(":END:"))
+            else {
+                break;
+            };
+            let base = leading_whitespace(&lines[index]).to_string();
+            for line in &mut lines[index..=index + length] {
+                let rest = line
+                    .strip_prefix(base.as_str())
+                    .unwrap_or(line.trim_start());
+                *line = format!("{}{}", indent, rest);
+            }
+            index += length + 1;
+        }
+    }
+
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+// Each headline's own lines up to the next headline, with the headline's 1-based line
+fn headline_blocks(content: &str) -> Vec<(usize, String)> {
+    let mut blocks: Vec<(usize, String)> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if headline_level(line).is_some() {
+            blocks.push((index + 1, String::new()));
+        }
+        if let Some((_, block)) = blocks.last_mut() {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    blocks
+}
+
+/// Lay out by `conventions` every headline of `after` whose own lines are not in `before`
+///
+/// This is applied to every write, so headlines an edit touches follow the conventions
+/// whichever command made it, and the rest of the file is left as it was. CRLF line
+/// endings are kept.
+pub fn conform_edited(before: &str, after: &str, conventions: &FormatConventions) -> String {
+    if *conventions == FormatConventions::default() {
+        return after.to_string();
+    }
+    let crlf = after.contains("\r\n");
+    let before = before.replace("\r\n", "\n");
+    let mut content = after.replace("\r\n", "\n");
+
+    let unchanged: HashSet<String> = headline_blocks(&before)
+        .into_iter()
+        .map(|(_, block)| block)
+        .collect();
+    for (line, block) in headline_blocks(&content.clone()) {
+        if !unchanged.contains(&block) {
+            content = conform_headline(&content, line, conventions);
+        }
+    }
+    if crlf {
+        content = content.replace('\n', "\r\n");
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::logbook::{add_log_note, set_closed};
+    use crate::orgmode::properties::set_headline_properties;
+    use crate::orgmode::reschedule::{set_planning_date, PlanningField};
+    use chrono::NaiveDate;
+
+    // A file as Orgzly writes it: flush planning lines and drawers, one space before tags
+    const ORGZLY: &str = "* TODO Pay rent :home:\nDEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>\n:PROPERTIES:\n:ID: rent\n:END:\nTransfer from savings\n* Notes\n";
+
+    fn mobile() -> FormatConventions {
+        FormatConventions {
+            metadata_indent: MetadataIndent::Flush,
+            tags_column: 0,
+        }
+    }
+
+    #[test]
+    fn test_mobile_round_trip() {
+        // Files already laid out as mobile apps write them are left byte for byte
+        assert_eq!(conform_headline(ORGZLY, 1, &mobile()), ORGZLY);
+
+        let edited = set_headline_properties(ORGZLY, 1, &[("Effort", "1:00".to_string())])
+            .map(|content| conform_headline(&content, 1, &mobile()))
+            .unwrap();
+        assert_eq!(
+            edited,
+            "* TODO Pay rent :home:\nDEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>\n:PROPERTIES:\n:ID: rent\n:Effort: 1:00\n:END:\nTransfer from savings\n* Notes\n"
+        );
+    }
+
+    #[test]
+    fn test_planning_order() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let content = "* TODO Pay rent\nSCHEDULED: <2024-03-04 Mon>\n";
+        assert_eq!(
+            set_planning_date(content, 1, PlanningField::Deadline, date, "en").unwrap(),
+            "* TODO Pay rent\nDEADLINE: <2024-03-09 Sat> SCHEDULED: <2024-03-04 Mon>\n"
+        );
+
+        let closed = NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_opt(18, 0, 0)
+            .unwrap();
+        assert_eq!(
+            set_closed(ORGZLY, 1, Some(closed)).unwrap().lines().nth(1),
+            Some("CLOSED: [2024-03-05 Tue 18:00] DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>")
+        );
+
+        // Lines written in another order are put in org-mode's
+        let reordered = conform_headline(
+            "* TODO Pay rent\n  SCHEDULED: <2024-03-04 Mon>   DEADLINE: <2024-03-08 Fri>\n",
+            1,
+            &mobile(),
+        );
+        assert_eq!(
+            reordered,
+            "* TODO Pay rent\nDEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>\n"
+        );
+    }
+
+    #[test]
+    fn test_drawer_placement() {
+        // New drawers go after the planning line, and notes after the property drawer
+        let content = "* TODO Pay rent\nSCHEDULED: <2024-03-04 Mon>\nTransfer from savings\n";
+        let content = set_headline_properties(content, 1, &[("ID", "rent".to_string())]).unwrap();
+        let content = add_log_note(
+            &content,
+            1,
+            "Note taken on [2024-03-04 Mon 09:00]",
+            "Paid",
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            content,
+            "* TODO Pay rent\nSCHEDULED: <2024-03-04 Mon>\n:PROPERTIES:\n:ID: rent\n:END:\n:LOGBOOK:\n- Note taken on [2024-03-04 Mon 09:00] \\\\\n  Paid\n:END:\nTransfer from savings\n"
+        );
+
+        // A planning line added to a headline with an indented drawer is indented with it
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(
+            set_planning_date(
+                "** TODO Pay rent\n   :PROPERTIES:\n   :ID: rent\n   :END:\n",
+                1,
+                PlanningField::Scheduled,
+                date,
+                "en"
+            )
+            .unwrap(),
+            "** TODO Pay rent\n   SCHEDULED: <2024-03-04 Mon>\n   :PROPERTIES:\n   :ID: rent\n   :END:\n"
+        );
+    }
+
+    #[test]
+    fn test_indent_conventions() {
+        let indented = conform_headline(
+            ORGZLY,
+            1,
+            &FormatConventions {
+                metadata_indent: MetadataIndent::Headline,
+                tags_column: 0,
+            },
+        );
+        assert_eq!(
+            indented,
+            "* TODO Pay rent :home:\n  DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>\n  :PROPERTIES:\n  :ID: rent\n  :END:\nTransfer from savings\n* Notes\n"
+        );
+        // Flushing keeps the indentation of logbook notes relative to their drawer
+        assert_eq!(
+            conform_headline(
+                "** DONE Call\n   :LOGBOOK:\n   - Note taken on [2024-03-04 Mon 09:00] \\\\\n     Left a message\n   :END:\n",
+                1,
+                &mobile()
+            ),
+            "** DONE Call\n:LOGBOOK:\n- Note taken on [2024-03-04 Mon 09:00] \\\\\n  Left a message\n:END:\n"
+        );
+        // Keep leaves everything alone
+        let kept = "* TODO Pay rent\n SCHEDULED: <2024-03-04 Mon>\n";
+        assert_eq!(
+            conform_headline(kept, 1, &FormatConventions::default()),
+            kept
+        );
+    }
+
+    #[test]
+    fn test_tags_column() {
+        let aligned = FormatConventions {
+            metadata_indent: MetadataIndent::Keep,
+            tags_column: 30,
+        };
+        assert_eq!(
+            conform_headline("* TODO Pay rent :home:\n", 1, &aligned),
+            "* TODO Pay rent         :home:\n"
+        );
+        assert_eq!(
+            conform_headline("* TODO Pay the rent before Friday :home:\n", 1, &aligned),
+            "* TODO Pay the rent before Friday :home:\n"
+        );
+        assert_eq!(conform_headline("* Notes\n", 1, &aligned), "* Notes\n");
+    }
+
+    #[test]
+    fn test_conform_edited() {
+        let before = "* TODO Pay rent\r\n  SCHEDULED: <2024-03-04 Mon>\r\n* TODO Call\r\n  DEADLINE: <2024-03-08 Fri>\r\n";
+        let after = before.replace("Call", "Call back");
+        // Only the edited headline is laid out, and CRLF endings are kept
+        assert_eq!(
+            conform_edited(before, &after, &mobile()),
+            "* TODO Pay rent\r\n  SCHEDULED: <2024-03-04 Mon>\r\n* TODO Call back\r\nDEADLINE: <2024-03-08 Fri>\r\n"
+        );
+        assert_eq!(conform_edited(before, before, &mobile()), before);
+        assert_eq!(
+            conform_edited(before, &after, &FormatConventions::default()),
+            after
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use crate::orgmode::locale::{day_name, DEFAULT_LANGUAGE};
+use crate::orgmode::time_zone;
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc,
+    Weekday,
+};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::hash::{Hash, Hasher};
@@ -40,64 +45,48 @@ impl OrgDatetime {
         }
     }
 
+    /// Create from a date, naming the day in `language` (see `locale::day_name`)
+    pub fn from_naive_date(date: NaiveDate, language: &str) -> Self {
+        Self {
+            year: date.year() as u16,
+            month: date.month() as u8,
+            day: date.day() as u8,
+            dayname: day_name(date.weekday(), language).to_string(),
+            hour: None,
+            minute: None,
+        }
+    }
+
     /// Create from ISO8601 date string (YYYY-MM-DD)
     pub fn from_date_string(date_str: &str) -> Option<Self> {
         // Try to parse the date string
-        if let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-            let dayname = match date.weekday() {
-                chrono::Weekday::Mon => "Mon",
-                chrono::Weekday::Tue => "Tue",
-                chrono::Weekday::Wed => "Wed",
-                chrono::Weekday::Thu => "Thu",
-                chrono::Weekday::Fri => "Fri",
-                chrono::Weekday::Sat => "Sat",
-                chrono::Weekday::Sun => "Sun",
-            };
-
-            return Some(Self {
-                year: date.year() as u16,
-                month: date.month() as u8,
-                day: date.day() as u8,
-                dayname: dayname.to_string(),
-                hour: None,
-                minute: None,
-            });
-        }
-
-        None
+        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()?;
+        Some(Self::from_naive_date(date, DEFAULT_LANGUAGE))
     }
 
     /// Create from ISO8601 datetime string (YYYY-MM-DDThh:mm:ss)
     pub fn from_datetime_string(datetime_str: &str) -> Option<Self> {
         // Try to parse the datetime string
-        if let Ok(dt) = NaiveDateTime::parse_from_str(datetime_str, "%Y-%m-%dT%H:%M:%S") {
-            let date = dt.date();
-            let time = dt.time();
-
-            let dayname = match date.weekday() {
-                chrono::Weekday::Mon => "Mon",
-                chrono::Weekday::Tue => "Tue",
-                chrono::Weekday::Wed => "Wed",
-                chrono::Weekday::Thu => "Thu",
-                chrono::Weekday::Fri => "Fri",
-                chrono::Weekday::Sat => "Sat",
-                chrono::Weekday::Sun => "Sun",
-            };
+        let dt = NaiveDateTime::parse_from_str(datetime_str, "%Y-%m-%dT%H:%M:%S").ok()?;
+        let time = dt.time();
+
+        Some(Self {
+            hour: Some(time.hour() as u8),
+            minute: Some(time.minute() as u8),
+            ..Self::from_naive_date(dt.date(), DEFAULT_LANGUAGE)
+        })
+    }
 
-            return Some(Self {
-                year: date.year() as u16,
-                month: date.month() as u8,
-                day: date.day() as u8,
-                dayname: dayname.to_string(),
-                hour: Some(time.hour() as u8),
-                minute: Some(time.minute() as u8),
-            });
+    /// Create from an instant, at its wall-clock time in the assumed time zone
+    pub fn from_utc(instant: DateTime<Utc>) -> Self {
+        let local = time_zone::to_local(instant, time_zone::assumed_time_zone());
+        Self {
+            hour: Some(local.hour() as u8),
+            minute: Some(local.minute() as u8),
+            ..Self::from_naive_date(local.date(), DEFAULT_LANGUAGE)
         }
-
-        None
     }
 
-    /// Convert to a NaiveDate
     pub fn to_naive_date(&self) -> NaiveDate {
         NaiveDate::from_ymd_opt(self.year as i32, self.month as u32, self.day as u32)
             .unwrap_or_else(|| NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
@@ -118,6 +107,11 @@ impl OrgDatetime {
         }
     }
 
+    /// The instant this date and time denotes in the assumed time zone, midnight for dates
+    pub fn to_utc(&self) -> DateTime<Utc> {
+        time_zone::to_utc(self.to_naive_datetime(), time_zone::assumed_time_zone())
+    }
+
     /// Format as org-date string (YYYY-MM-DD day)
     pub fn format_org_date(&self) -> String {
         format!(
@@ -140,14 +134,14 @@ impl OrgDatetime {
 
     /// Check if date is today
     pub fn is_today(&self) -> bool {
-        let today = chrono::Local::now().date_naive();
+        let today = time_zone::today();
         let date = self.to_naive_date();
         date == today
     }
 
     /// Check if date is this week (next 7 days including today)
     pub fn is_this_week(&self) -> bool {
-        let today = chrono::Local::now().date_naive();
+        let today = time_zone::today();
         let date = self.to_naive_date();
         let days_diff = date.signed_duration_since(today).num_days();
         days_diff >= 0 && days_diff < 7
@@ -155,12 +149,167 @@ impl OrgDatetime {
 
     /// Check if date is overdue (before today)
     pub fn is_overdue(&self) -> bool {
-        let today = chrono::Local::now().date_naive();
+        let today = time_zone::today();
         let date = self.to_naive_date();
         date < today
     }
 }
 
+/// Inclusive date range expressed as ISO8601 dates (YYYY-MM-DD)
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+pub struct DateRange {
+    pub start: String,
+    pub end: String,
+}
+
+impl DateRange {
+    /// Create a range from ISO8601 date strings
+    pub fn new(start: &str, end: &str) -> Self {
+        Self {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    /// Parse the bounds, returning None if either date is invalid
+    pub fn to_naive_dates(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let start = NaiveDate::parse_from_str(&self.start, "%Y-%m-%d").ok()?;
+        let end = NaiveDate::parse_from_str(&self.end, "%Y-%m-%d").ok()?;
+        Some((start, end))
+    }
+
+    /// Check whether a date falls within the range (bounds included)
+    pub fn contains(&self, date: &OrgDatetime) -> bool {
+        self.to_naive_dates().is_some_and(|(start, end)| {
+            let date = date.to_naive_date();
+            date >= start && date <= end
+        })
+    }
+
+    fn from_naive_dates(start: NaiveDate, end: NaiveDate) -> Self {
+        Self::new(
+            &start.format("%Y-%m-%d").to_string(),
+            &end.format("%Y-%m-%d").to_string(),
+        )
+    }
+
+    /// The Monday to Sunday ISO week `week` of an ISO week-numbering year
+    pub fn iso_week(year: i32, week: u32) -> Option<Self> {
+        let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+        let sunday = NaiveDate::from_isoywd_opt(year, week, Weekday::Sun)?;
+        Some(Self::from_naive_dates(monday, sunday))
+    }
+
+    /// A calendar month, 1 for January
+    pub fn month(year: i32, month: u32) -> Option<Self> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+        Some(Self::from_naive_dates(first, last))
+    }
+
+    /// A calendar quarter, 1 for January to March
+    pub fn quarter(year: i32, quarter: u32) -> Option<Self> {
+        if !(1..=4).contains(&quarter) {
+            return None;
+        }
+        let first = NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1)?;
+        let last = first.checked_add_months(Months::new(3))?.pred_opt()?;
+        Some(Self::from_naive_dates(first, last))
+    }
+
+    /// A calendar year
+    pub fn year(year: i32) -> Option<Self> {
+        let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+        let last = NaiveDate::from_ymd_opt(year, 12, 31)?;
+        Some(Self::from_naive_dates(first, last))
+    }
+
+    /// Parse a range expression, relative to `today` where needed
+    ///
+    /// Accepts a date (`2024-06-05`), two dates (`2024-06-01..2024-06-15`), a year
+    /// (`2024`), a month (`2024-06`), an ISO week (`2024-W23`, or `W23` in the current
+    /// ISO year), a quarter (`2024-Q2`, or `Q2` in the current year), and `this-`,
+    /// `next-` or `last-` followed by `week`, `month`, `quarter` or `year`. Relative weeks
+    /// start on `week_start`.
+    pub fn parse(expression: &str, today: NaiveDate, week_start: Weekday) -> Result<Self, String> {
+        let invalid = || format!("Invalid date range: '{}'", expression);
+        let expression = expression.trim();
+        let upper = expression.to_ascii_uppercase();
+        let number = |text: &str| text.parse::<u32>().map_err(|_| invalid());
+        let year = |text: &str| text.parse::<i32>().map_err(|_| invalid());
+
+        if let Some((start, end)) = expression.split_once("..") {
+            let start =
+                NaiveDate::parse_from_str(start.trim(), "%Y-%m-%d").map_err(|_| invalid())?;
+            let end = NaiveDate::parse_from_str(end.trim(), "%Y-%m-%d").map_err(|_| invalid())?;
+            if end < start {
+                return Err(invalid());
+            }
+            return Ok(Self::from_naive_dates(start, end));
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(expression, "%Y-%m-%d") {
+            return Ok(Self::from_naive_dates(date, date));
+        }
+        let lower = expression.to_ascii_lowercase();
+        let relative = lower.split_once('-').and_then(|(relative, unit)| {
+            let offset = match relative {
+                "last" => -1,
+                "this" => 0,
+                "next" => 1,
+                _ => return None,
+            };
+            Some((unit, offset))
+        });
+        if let Some((unit, offset)) = relative {
+            return Self::relative(unit, offset, today, week_start).ok_or_else(invalid);
+        }
+
+        let range = match upper.split_once('-') {
+            Some((year_part, rest)) => {
+                let year = year(year_part)?;
+                if let Some(week) = rest.strip_prefix('W') {
+                    Self::iso_week(year, number(week)?)
+                } else if let Some(quarter) = rest.strip_prefix('Q') {
+                    Self::quarter(year, number(quarter)?)
+                } else {
+                    Self::month(year, number(rest)?)
+                }
+            }
+            None => {
+                if let Some(week) = upper.strip_prefix('W') {
+                    Self::iso_week(today.iso_week().year(), number(week)?)
+                } else if let Some(quarter) = upper.strip_prefix('Q') {
+                    Self::quarter(today.year(), number(quarter)?)
+                } else {
+                    Self::year(year(&upper)?)
+                }
+            }
+        };
+        range.ok_or_else(invalid)
+    }
+
+    // The week, month, quarter or year `offset` periods from the one containing `today`
+    fn relative(unit: &str, offset: i32, today: NaiveDate, week_start: Weekday) -> Option<Self> {
+        match unit {
+            "week" => {
+                let start = today.week(week_start).first_day();
+                let start = start.checked_add_signed(Duration::weeks(offset.into()))?;
+                Some(Self::from_naive_dates(start, start + Duration::days(6)))
+            }
+            "month" => {
+                let months = today.year() * 12 + today.month0() as i32 + offset;
+                Self::month(months.div_euclid(12), months.rem_euclid(12) as u32 + 1)
+            }
+            "quarter" => {
+                let quarters = today.year() * 4 + (today.month0() / 3) as i32 + offset;
+                Self::quarter(quarters.div_euclid(4), quarters.rem_euclid(4) as u32 + 1)
+            }
+            "year" => Self::year(today.year() + offset),
+            _ => None,
+        }
+    }
+}
+
 // Implement Hash for OrgDatetime
 impl Hash for OrgDatetime {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -238,6 +387,23 @@ mod tests {
         assert_eq!(datetime.minute, Some(30));
     }
 
+    #[test]
+    fn test_from_naive_date() {
+        let date = NaiveDate::from_ymd_opt(2023, 5, 10).unwrap();
+        assert_eq!(OrgDatetime::from_naive_date(date, "de").dayname, "Mi");
+        assert_eq!(
+            OrgDatetime::from_naive_date(date, "en").format_org_date(),
+            "2023-05-10 Wed"
+        );
+    }
+
+    #[test]
+    fn test_utc_conversion() {
+        // Without an assumed zone the system's is used, so only the round trip is fixed
+        let datetime = OrgDatetime::with_time(2023, 5, 10, "Wed", 14, 30);
+        assert_eq!(OrgDatetime::from_utc(datetime.to_utc()), datetime);
+    }
+
     #[test]
     fn test_format_org_date() {
         let date = OrgDatetime::new(2023, 5, 10, "Wed");
@@ -253,4 +419,82 @@ mod tests {
         let date = OrgDatetime::new(2023, 5, 10, "Wed");
         assert_eq!(date.format_org_datetime(), "2023-05-10 Wed");
     }
+
+    #[test]
+    fn test_date_range_contains() {
+        let range = DateRange::new("2024-01-01", "2024-01-31");
+
+        assert!(range.contains(&OrgDatetime::new(2024, 1, 1, "Mon")));
+        assert!(range.contains(&OrgDatetime::with_time(2024, 1, 31, "Wed", 23, 59)));
+        assert!(!range.contains(&OrgDatetime::new(2024, 2, 1, "Thu")));
+
+        let invalid = DateRange::new("2024-13-01", "2024-01-31");
+        assert!(invalid.to_naive_dates().is_none());
+        assert!(!invalid.contains(&OrgDatetime::new(2024, 1, 10, "Wed")));
+    }
+
+    #[test]
+    fn test_date_range_expressions() {
+        let today = NaiveDate::from_ymd_opt(2024, 11, 20).unwrap(); // Wednesday
+        let parse = |expression: &str| DateRange::parse(expression, today, Weekday::Mon);
+
+        assert_eq!(
+            parse("2024-W23"),
+            Ok(DateRange::new("2024-06-03", "2024-06-09"))
+        );
+        // ISO week 1 of 2025 starts in 2024
+        assert_eq!(
+            parse("2025-w01"),
+            Ok(DateRange::new("2024-12-30", "2025-01-05"))
+        );
+        assert_eq!(parse("W47"), Ok(DateRange::new("2024-11-18", "2024-11-24")));
+        assert_eq!(
+            parse("2024-02"),
+            Ok(DateRange::new("2024-02-01", "2024-02-29"))
+        );
+        assert_eq!(parse("Q2"), Ok(DateRange::new("2024-04-01", "2024-06-30")));
+        assert_eq!(
+            parse("2023-Q4"),
+            Ok(DateRange::new("2023-10-01", "2023-12-31"))
+        );
+        assert_eq!(
+            parse("2024"),
+            Ok(DateRange::new("2024-01-01", "2024-12-31"))
+        );
+        assert_eq!(
+            parse("2024-06-01..2024-06-15"),
+            Ok(DateRange::new("2024-06-01", "2024-06-15"))
+        );
+        assert_eq!(
+            parse("2024-06-05"),
+            Ok(DateRange::new("2024-06-05", "2024-06-05"))
+        );
+
+        assert_eq!(
+            parse("this-quarter"),
+            Ok(DateRange::new("2024-10-01", "2024-12-31"))
+        );
+        assert_eq!(
+            parse("next-quarter"),
+            Ok(DateRange::new("2025-01-01", "2025-03-31"))
+        );
+        assert_eq!(
+            parse("last-month"),
+            Ok(DateRange::new("2024-10-01", "2024-10-31"))
+        );
+        assert_eq!(
+            DateRange::parse("next-week", today, Weekday::Sun),
+            Ok(DateRange::new("2024-11-24", "2024-11-30"))
+        );
+
+        for invalid in [
+            "2024-W54",
+            "Q5",
+            "2024-13",
+            "soon",
+            "2024-06-15..2024-06-01",
+        ] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
 }
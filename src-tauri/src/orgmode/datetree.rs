@@ -0,0 +1,172 @@
+use crate::orgmode::source::headline_level;
+use crate::orgmode::subtree::insert_subtree;
+use chrono::NaiveDate;
+use std::ops::Range;
+
+/// Headline titles of a date tree, in the format of org-capture's file+datetree
+/// Returns (year, month, day), e.g. ("2024", "2024-05 May", "2024-05-16 Thursday")
+pub fn datetree_headings(date: NaiveDate) -> [String; 3] {
+    [
+        date.format("%Y").to_string(),
+        date.format("%Y-%m %B").to_string(),
+        date.format("%Y-%m-%d %A").to_string(),
+    ]
+}
+
+// Sort keys matched against the first word of each date tree headline
+fn datetree_keys(date: NaiveDate) -> [String; 3] {
+    [
+        date.format("%Y").to_string(),
+        date.format("%Y-%m").to_string(),
+        date.format("%Y-%m-%d").to_string(),
+    ]
+}
+
+// First word of a date tree headline, ignoring the stars
+// Other headlines (e.g. "* Inbox") have no key and are left in place
+fn headline_key(line: &str) -> Option<&str> {
+    line.trim_start_matches('*')
+        .split_whitespace()
+        .next()
+        .filter(|word| word.chars().all(|c| c.is_ascii_digit() || c == '-'))
+}
+
+// End of the subtree starting at `start` (exclusive), bounded by `limit`
+fn subtree_end(lines: &[String], start: usize, level: usize, limit: usize) -> usize {
+    (start + 1..limit)
+        .find(|&index| headline_level(&lines[index]).is_some_and(|other| other <= level))
+        .unwrap_or(limit)
+}
+
+// Find the child headline with `key` inside `parent`, inserting it in date order if
+// `heading` is given. Returns the line index and whether the headline was inserted
+fn find_child(
+    lines: &mut Vec<String>,
+    parent: Range<usize>,
+    level: usize,
+    key: &str,
+    heading: Option<&str>,
+) -> Option<(usize, bool)> {
+    let mut insert_at = parent.end;
+    for index in parent {
+        if headline_level(&lines[index]) != Some(level) {
+            continue;
+        }
+        match headline_key(&lines[index]) {
+            Some(existing) if existing == key => return Some((index, false)),
+            Some(existing) if existing > key => {
+                insert_at = index;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let heading = heading?;
+    lines.insert(insert_at, format!("{} {}", "*".repeat(level), heading));
+    Some((insert_at, true))
+}
+
+// Walk the year, month and day headlines, optionally creating missing ones
+// Returns the line index of the day headline
+fn walk_datetree(lines: &mut Vec<String>, date: NaiveDate, create: bool) -> Option<usize> {
+    let headings = datetree_headings(date);
+    let keys = datetree_keys(date);
+
+    let mut parent = 0..lines.len();
+    let mut day = None;
+    for (depth, (key, heading)) in keys.iter().zip(&headings).enumerate() {
+        let level = depth + 1;
+        let heading = create.then_some(heading.as_str());
+        let (index, inserted) = find_child(lines, parent.clone(), level, key, heading)?;
+        let limit = parent.end + usize::from(inserted);
+        parent = index + 1..subtree_end(lines, index, level, limit);
+        day = Some(index);
+    }
+    day
+}
+
+fn split_lines(content: &str) -> Vec<String> {
+    content.lines().map(str::to_string).collect()
+}
+
+fn join_lines(lines: &[String]) -> String {
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content
+}
+
+/// Line index (0-based) of the day headline for `date`, if the date tree has one
+pub fn find_datetree_day(content: &str, date: NaiveDate) -> Option<usize> {
+    walk_datetree(&mut split_lines(content), date, false)
+}
+
+/// Create the year, month and day headlines for `date` where missing
+/// Returns the new content and the line index (0-based) of the day headline
+pub fn ensure_datetree_day(content: &str, date: NaiveDate) -> (String, usize) {
+    let mut lines = split_lines(content);
+    let day = walk_datetree(&mut lines, date, true).unwrap_or_default();
+    (join_lines(&lines), day)
+}
+
+/// File a subtree as the last child of the day headline for `date`
+/// The subtree should already be at day level + 1 (level 4)
+pub fn file_under_datetree(content: &str, date: NaiveDate, subtree: &str) -> String {
+    let (content, day) = ensure_datetree_day(content, date);
+    let lines = split_lines(&content);
+    let end = subtree_end(&lines, day, 3, lines.len());
+    insert_subtree(&content, end, subtree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap()
+    }
+
+    #[test]
+    fn test_ensure_datetree_day() {
+        let (content, day) = ensure_datetree_day("#+TITLE: Log\n", date(16));
+        assert_eq!(
+            content,
+            "#+TITLE: Log\n* 2024\n** 2024-05 May\n*** 2024-05-16 Thursday\n"
+        );
+        assert_eq!(day, 3);
+
+        // Lookup is idempotent and new days are inserted in date order
+        assert_eq!(
+            ensure_datetree_day(&content, date(16)),
+            (content.clone(), 3)
+        );
+        let (content, day) = ensure_datetree_day(&content, date(2));
+        assert_eq!(day, 3);
+        assert_eq!(find_datetree_day(&content, date(16)), Some(4));
+        assert_eq!(find_datetree_day(&content, date(20)), None);
+
+        let new_year = NaiveDate::from_ymd_opt(2023, 12, 31).unwrap();
+        let (content, _) = ensure_datetree_day(&content, new_year);
+        assert!(content.starts_with("#+TITLE: Log\n* 2023\n** 2023-12 December\n"));
+
+        // Headlines outside the date tree don't affect the order
+        let (content, _) = ensure_datetree_day("* Inbox\n", date(16));
+        assert!(content.starts_with("* Inbox\n* 2024\n"));
+    }
+
+    #[test]
+    fn test_file_under_datetree() {
+        let content =
+            "* 2024\n** 2024-05 May\n*** 2024-05-16 Thursday\n**** First\nNotes\n* Other\n";
+        assert_eq!(
+            file_under_datetree(content, date(16), "**** Second\n"),
+            "* 2024\n** 2024-05 May\n*** 2024-05-16 Thursday\n**** First\nNotes\n**** Second\n* Other\n"
+        );
+        assert_eq!(
+            file_under_datetree(content, date(17), "**** Next day\n"),
+            "* 2024\n** 2024-05 May\n*** 2024-05-16 Thursday\n**** First\nNotes\n*** 2024-05-17 Friday\n**** Next day\n* Other\n"
+        );
+    }
+}
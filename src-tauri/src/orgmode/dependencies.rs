@@ -0,0 +1,448 @@
+use crate::orgmode::checklist::parse_list_item;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::grouping::HeadlineRef;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::todo::TodoConfiguration;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+
+/// Property listing the headlines a task waits on (org-depend and org-edna)
+pub const BLOCKER_PROPERTY: &str = "BLOCKER";
+
+/// Alternative spelling of `BLOCKER` used by some setups
+pub const DEPENDS_PROPERTY: &str = "DEPENDS";
+
+/// Property making a headline's child tasks a sequence, each blocked by the one before
+pub const ORDERED_PROPERTY: &str = "ORDERED";
+
+/// One entry of a BLOCKER/DEPENDS property
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyRef {
+    Id(String),      // `:ID:` of another headline, bare or inside `ids(...)`
+    PreviousSibling, // `previous-sibling`
+}
+
+/// Parse a BLOCKER/DEPENDS value such as `ids(a "b") previous-sibling`
+/// Other org-edna finders and conditions are not supported and are skipped
+pub fn parse_dependency_refs(value: &str) -> Vec<DependencyRef> {
+    let mut refs = Vec::new();
+    let mut rest = value.trim();
+
+    while !rest.is_empty() {
+        if let Some(args) = rest.strip_prefix("ids(") {
+            let (inside, after) = args.split_once(')').unwrap_or((args, ""));
+            refs.extend(
+                inside
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .map(|id| id.trim_matches('"'))
+                    .filter(|id| !id.is_empty())
+                    .map(|id| DependencyRef::Id(id.to_string())),
+            );
+            rest = after.trim_start();
+            continue;
+        }
+
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let token = &rest[..end];
+        rest = rest[end..].trim_start();
+        if token == "previous-sibling" {
+            refs.push(DependencyRef::PreviousSibling);
+        } else if let Some(id) = token.strip_prefix("id:") {
+            refs.push(DependencyRef::Id(id.to_string()));
+        } else if !token.contains('(') && !token.contains('!') {
+            refs.push(DependencyRef::Id(token.trim_matches('"').to_string()));
+        }
+    }
+
+    refs
+}
+
+// Any value but `nil` turns ORDERED on, as in org-mode
+fn is_ordered(headline: &OrgHeadline) -> bool {
+    headline
+        .title
+        .get_property_ignore_case(ORDERED_PROPERTY)
+        .map(str::trim)
+        .is_some_and(|value| !value.is_empty() && !value.eq_ignore_ascii_case("nil"))
+}
+
+/// Count unchecked (`[ ]`) and partially checked (`[-]`) list items in a section
+pub fn unchecked_checkboxes(section: &str) -> usize {
+    section
+        .lines()
+        .filter_map(parse_list_item)
+        .filter(|item| matches!(item.checkbox, Some(' ' | '-')))
+        .count()
+}
+
+/// A headline taking part in a dependency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DependencyNode {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub todo_keyword: Option<String>,
+    pub open: bool,    // In an active TODO state, so it still blocks its dependents
+    pub blocked: bool, // Waits on at least one open headline
+}
+
+/// Where a dependency comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DependencyKind {
+    Property, // BLOCKER/DEPENDS
+    Ordered,  // Earlier sibling under ORDERED or `#+STARTUP: ordered`
+}
+
+/// `blocked` cannot be done before `blocker`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DependencyEdge {
+    pub blocker: HeadlineRef,
+    pub blocked: HeadlineRef,
+    pub kind: DependencyKind,
+}
+
+/// A reference that does not match any `:ID:` in the loaded documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct UnresolvedDependency {
+    pub document_id: String,
+    pub headline_id: String,
+    pub id: String,
+}
+
+/// Dependencies between headlines across all documents, for visualization
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    pub unresolved: Vec<UnresolvedDependency>,
+}
+
+// Identifies a headline across documents
+type Key = (String, String);
+
+fn key(document: &OrgDocument, headline: &OrgHeadline) -> Key {
+    (document.id.clone(), headline.id.clone())
+}
+
+fn headline_ref((document_id, headline_id): &Key) -> HeadlineRef {
+    HeadlineRef {
+        document_id: document_id.clone(),
+        headline_id: headline_id.clone(),
+    }
+}
+
+fn dependency_refs(headline: &OrgHeadline) -> Vec<DependencyRef> {
+    [BLOCKER_PROPERTY, DEPENDS_PROPERTY]
+        .iter()
+        .filter_map(|property| headline.title.get_property_ignore_case(property))
+        .flat_map(parse_dependency_refs)
+        .collect()
+}
+
+fn is_open(headline: &OrgHeadline, config: &TodoConfiguration) -> bool {
+    headline
+        .get_todo_status(config)
+        .is_some_and(|status| status.is_active())
+}
+
+impl DependencyGraph {
+    /// Build the graph from BLOCKER/DEPENDS properties in the given documents
+    /// Missing and read-only archived documents are skipped
+    pub fn build(documents: &[&OrgDocument]) -> Self {
+        let documents: Vec<&OrgDocument> = documents
+            .iter()
+            .copied()
+            .filter(|document| !document.missing && !document.archived)
+            .collect();
+
+        // Every headline with an :ID:, and whether it is still open
+        let mut by_org_id: HashMap<&str, Key> = HashMap::new();
+        let mut headlines: HashMap<Key, (&OrgHeadline, bool)> = HashMap::new();
+        let mut edges: Vec<(Key, Key, DependencyKind)> = Vec::new();
+        let mut unresolved = Vec::new();
+
+        for document in &documents {
+            let config = document
+                .todo_config
+                .clone()
+                .unwrap_or_else(TodoConfiguration::default);
+            walk(&document.headlines, &mut |headline| {
+                headlines.insert(
+                    key(document, headline),
+                    (headline, is_open(headline, &config)),
+                );
+                if let Some(org_id) = headline.title.get_property_ignore_case("ID") {
+                    by_org_id.insert(org_id.trim(), key(document, headline));
+                }
+            });
+        }
+
+        for document in &documents {
+            walk_siblings(&document.headlines, &mut |headline, previous| {
+                for dependency in dependency_refs(headline) {
+                    let blocker = match dependency {
+                        DependencyRef::PreviousSibling => {
+                            previous.map(|previous| key(document, previous))
+                        }
+                        DependencyRef::Id(id) => {
+                            let found = by_org_id.get(id.as_str()).cloned();
+                            if found.is_none() {
+                                unresolved.push(UnresolvedDependency {
+                                    document_id: document.id.clone(),
+                                    headline_id: headline.id.clone(),
+                                    id,
+                                });
+                            }
+                            found
+                        }
+                    };
+                    if let Some(blocker) = blocker {
+                        edges.push((blocker, key(document, headline), DependencyKind::Property));
+                    }
+                }
+            });
+            // `#+STARTUP: ordered` makes every set of sibling tasks in the file a sequence
+            let file_ordered = document.startup.ordered;
+            ordered_edges(
+                document,
+                &document.headlines,
+                file_ordered,
+                file_ordered,
+                &mut edges,
+            );
+        }
+
+        let blocked: HashSet<&Key> = edges
+            .iter()
+            .filter(|(blocker, _, _)| headlines.get(blocker).is_some_and(|(_, open)| *open))
+            .map(|(_, blocked, _)| blocked)
+            .collect();
+
+        // Nodes in the order they first appear in an edge
+        let mut seen = HashSet::new();
+        let mut nodes = Vec::new();
+        for node_key in edges
+            .iter()
+            .flat_map(|(blocker, blocked, _)| [blocker, blocked])
+        {
+            if !seen.insert(node_key) {
+                continue;
+            }
+            if let Some((headline, open)) = headlines.get(node_key) {
+                nodes.push(DependencyNode {
+                    document_id: node_key.0.clone(),
+                    headline_id: node_key.1.clone(),
+                    title: headline.title.raw.clone(),
+                    todo_keyword: headline.title.todo_keyword.as_deref().map(str::to_string),
+                    open: *open,
+                    blocked: blocked.contains(node_key),
+                });
+            }
+        }
+
+        Self {
+            nodes,
+            edges: edges
+                .iter()
+                .map(|(blocker, blocked, kind)| DependencyEdge {
+                    blocker: headline_ref(blocker),
+                    blocked: headline_ref(blocked),
+                    kind: *kind,
+                })
+                .collect(),
+            unresolved,
+        }
+    }
+
+    /// Whether a headline waits on at least one open headline
+    pub fn is_blocked(&self, document_id: &str, headline_id: &str) -> bool {
+        self.nodes.iter().any(|node| {
+            node.blocked && node.document_id == document_id && node.headline_id == headline_id
+        })
+    }
+
+    /// Open headlines the given headline waits on
+    pub fn open_blockers(&self, document_id: &str, headline_id: &str) -> Vec<&DependencyNode> {
+        self.edges
+            .iter()
+            .filter(|edge| {
+                edge.blocked.document_id == document_id && edge.blocked.headline_id == headline_id
+            })
+            .filter_map(|edge| {
+                self.nodes.iter().find(|node| {
+                    node.open
+                        && node.document_id == edge.blocker.document_id
+                        && node.headline_id == edge.blocker.headline_id
+                })
+            })
+            .collect()
+    }
+}
+
+/// Reasons a task cannot be closed yet, empty if it can
+/// Follows org-mode's TODO and checkbox dependencies: open child tasks, open blockers
+/// (including earlier siblings in an ordered subtree) and unchecked checkboxes in the
+/// task's own section all keep it open. Subtasks are checked against `config`, the
+/// keywords valid for the document.
+pub fn completion_blockers(
+    graph: &DependencyGraph,
+    document: &OrgDocument,
+    headline: &OrgHeadline,
+    section: &str,
+    config: &TodoConfiguration,
+) -> Vec<String> {
+    let mut reasons: Vec<String> = headline
+        .children
+        .iter()
+        .filter(|child| is_open(child, config))
+        .map(|child| format!("Subtask '{}' is not done", child.title.raw))
+        .collect();
+
+    reasons.extend(
+        graph
+            .open_blockers(&document.id, &headline.id)
+            .into_iter()
+            .map(|blocker| format!("Blocked by '{}'", blocker.title)),
+    );
+
+    match unchecked_checkboxes(section) {
+        0 => {}
+        1 => reasons.push("1 checkbox is unchecked".to_string()),
+        count => reasons.push(format!("{} checkboxes are unchecked", count)),
+    }
+    reasons
+}
+
+// Chain sibling tasks under ordered parents, each blocked by the previous task
+fn ordered_edges(
+    document: &OrgDocument,
+    headlines: &[OrgHeadline],
+    ordered: bool,
+    file_ordered: bool,
+    edges: &mut Vec<(Key, Key, DependencyKind)>,
+) {
+    let mut previous_task: Option<&OrgHeadline> = None;
+    for headline in headlines {
+        if ordered && headline.is_task() {
+            if let Some(previous) = previous_task {
+                edges.push((
+                    key(document, previous),
+                    key(document, headline),
+                    DependencyKind::Ordered,
+                ));
+            }
+            previous_task = Some(headline);
+        }
+        ordered_edges(
+            document,
+            &headline.children,
+            file_ordered || is_ordered(headline),
+            file_ordered,
+            edges,
+        );
+    }
+}
+
+// Visit every headline in document order
+fn walk<'a>(headlines: &'a [OrgHeadline], visit: &mut impl FnMut(&'a OrgHeadline)) {
+    for headline in headlines {
+        visit(headline);
+        walk(&headline.children, visit);
+    }
+}
+
+// Visit every headline along with its previous sibling
+fn walk_siblings<'a>(
+    headlines: &'a [OrgHeadline],
+    visit: &mut impl FnMut(&'a OrgHeadline, Option<&'a OrgHeadline>),
+) {
+    let mut previous = None;
+    for headline in headlines {
+        visit(headline, previous);
+        walk_siblings(&headline.children, visit);
+        previous = Some(headline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::source::headline_sections;
+
+    #[test]
+    fn test_parse_dependency_refs() {
+        assert_eq!(
+            parse_dependency_refs("ids(a \"b-2\") previous-sibling id:c d"),
+            vec![
+                DependencyRef::Id("a".to_string()),
+                DependencyRef::Id("b-2".to_string()),
+                DependencyRef::PreviousSibling,
+                DependencyRef::Id("c".to_string()),
+                DependencyRef::Id("d".to_string()),
+            ]
+        );
+        assert!(parse_dependency_refs("match(\"@home\") todo?(DONE)").is_empty());
+    }
+
+    #[test]
+    fn test_dependency_graph() {
+        let tasks = parse_org_document(
+            "* TODO Order parts\n:PROPERTIES:\n:ID: parts\n:END:\n* TODO Assemble\n:PROPERTIES:\n:BLOCKER: previous-sibling\n:END:\n* DONE Measure\n:PROPERTIES:\n:ID: measure\n:END:\n",
+            Some("/notes/tasks.org"),
+        )
+        .unwrap();
+        let other = parse_org_document(
+            "* TODO Cut board\n:PROPERTIES:\n:DEPENDS: ids(measure) missing\n:END:\n* TODO Paint\n:PROPERTIES:\n:BLOCKER: ids(parts)\n:END:\n",
+            Some("/notes/other.org"),
+        )
+        .unwrap();
+
+        let graph = DependencyGraph::build(&[&tasks, &other]);
+        assert_eq!(graph.edges.len(), 3);
+        assert!(graph.is_blocked("/notes/tasks.org", "2"));
+        assert!(graph.is_blocked("/notes/other.org", "2"));
+        // Measure is done, so cutting can start
+        assert!(!graph.is_blocked("/notes/other.org", "1"));
+        assert!(!graph.is_blocked("/notes/tasks.org", "1"));
+        assert_eq!(graph.unresolved.len(), 1);
+        assert_eq!(graph.unresolved[0].id, "missing");
+        assert_eq!(graph.nodes.len(), 5);
+    }
+
+    #[test]
+    fn test_ordered_and_checkbox_blocking() {
+        let doc = parse_org_document(
+            "* Move house\n:PROPERTIES:\n:ORDERED: t\n:END:\n** DONE Find flat\n** TODO Pack boxes\n- [X] Books\n- [ ] Kitchen\n1. [-] Clothes\n** Notes\n** TODO Hand back keys\n* TODO Unordered\n",
+            Some("/notes/move.org"),
+        )
+        .unwrap();
+        let graph = DependencyGraph::build(&[&doc]);
+
+        assert!(!graph.is_blocked("/notes/move.org", "1.2"));
+        assert!(graph.is_blocked("/notes/move.org", "1.4"));
+        assert!(!graph.is_blocked("/notes/move.org", "2"));
+        assert_eq!(graph.edges[0].kind, DependencyKind::Ordered);
+
+        let sections = headline_sections(&doc);
+        let config = TodoConfiguration::default();
+        let pack = doc.find_headline("1.2").unwrap();
+        assert_eq!(
+            completion_blockers(&graph, &doc, pack, &sections["1.2"], &config),
+            vec!["2 checkboxes are unchecked"]
+        );
+        let keys = doc.find_headline("1.4").unwrap();
+        assert_eq!(
+            completion_blockers(&graph, &doc, keys, &sections["1.4"], &config),
+            vec!["Blocked by 'Pack boxes'"]
+        );
+
+        let startup = parse_org_document(
+            "#+STARTUP: overview ordered\n* TODO First\n* TODO Second\n",
+            Some("/notes/seq.org"),
+        )
+        .unwrap();
+        assert!(DependencyGraph::build(&[&startup]).is_blocked("/notes/seq.org", "2"));
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const PANIC_PREFIX: &str = "Parser panicked";
+
+/// A file that could not be parsed during the last attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ParseDiagnostic {
+    pub file_path: String,
+    pub message: String,
+    pub panicked: bool, // The parser crashed rather than returning an error
+}
+
+/// Run a parse, turning a panic into an error so one bad file can't take down a scan
+/// The error is prefixed with "Parser panicked" so callers can tell crashes apart
+pub fn catch_parse_panic<T>(parse: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    match catch_unwind(AssertUnwindSafe(parse)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|reason| reason.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown cause".to_string());
+            Err(format!("{}: {}", PANIC_PREFIX, reason))
+        }
+    }
+}
+
+impl ParseDiagnostic {
+    pub fn new(file_path: &str, message: &str) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            message: message.to_string(),
+            panicked: message.starts_with(PANIC_PREFIX),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_parse_panic() {
+        assert_eq!(catch_parse_panic(|| Ok::<_, String>(1)), Ok(1));
+
+        let error = catch_parse_panic::<()>(|| panic!("bad drawer")).unwrap_err();
+        assert_eq!(error, "Parser panicked: bad drawer");
+        assert!(ParseDiagnostic::new("x.org", &error).panicked);
+        assert!(!ParseDiagnostic::new("x.org", "Failed to parse document").panicked);
+    }
+}
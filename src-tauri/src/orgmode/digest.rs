@@ -0,0 +1,198 @@
+use crate::orgmode::agenda::{
+    agenda_title, days_html, days_text, entry_category, entry_label, entry_line_text,
+    entry_row_html, html_page, AgendaDay, AgendaEntry, AgendaFormat,
+};
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::tickler::deferrals;
+use crate::orgmode::timeline::TimelineKind;
+use crate::orgmode::timestamp::OrgTimestamp;
+use crate::orgmode::todo::TodoConfiguration;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Event emitted with the digest text when it is ready to be copied each morning
+pub const DIGEST_READY_EVENT: &str = "digest-ready";
+
+/// How many days a digest's agenda covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DigestPeriod {
+    Daily,  // Today
+    Weekly, // The week containing today
+}
+
+/// The morning deadline digest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct DigestConfig {
+    pub auto_copy: bool, // Emit the digest at the start of each working day, for the clipboard
+    pub period: DigestPeriod,
+    pub format: AgendaFormat,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            auto_copy: false,
+            period: DigestPeriod::Daily,
+            format: AgendaFormat::Text,
+        }
+    }
+}
+
+/// An open task whose deadline or scheduled date has passed
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverdueTask {
+    pub entry: AgendaEntry,
+    pub date: NaiveDate,
+}
+
+impl OverdueTask {
+    // org-agenda's label for a past date: "3 d. ago:" for deadlines, "Sched. 3x:" for
+    // scheduled tasks
+    fn label(&self, today: NaiveDate) -> String {
+        entry_label(&self.entry, today)
+    }
+}
+
+/// Collect open tasks with a deadline, or else a scheduled date, before `today`, oldest
+/// first
+/// Tasks deferred past `today`, missing documents, read-only archives and files marked
+/// `no-agenda` are left out. Files' own `#+TODO:` keywords come before `settings_config`.
+pub fn collect_overdue(
+    documents: &[&OrgDocument],
+    today: NaiveDate,
+    settings_config: &TodoConfiguration,
+) -> Vec<OverdueTask> {
+    let mut overdue = Vec::new();
+    for document in documents {
+        if document.missing || document.archived || document.flags.no_agenda {
+            continue;
+        }
+        let config = document.effective_todo_config(settings_config);
+        let deferrals = deferrals(document);
+
+        for headline in flatten_headlines(&document.headlines) {
+            let is_open = headline
+                .get_todo_status(&config)
+                .is_some_and(|status| status.is_active())
+                && deferrals
+                    .get(&headline.id)
+                    .is_none_or(|until| *until <= today);
+            let Some(planning) = headline.title.planning.as_deref().filter(|_| is_open) else {
+                continue;
+            };
+            let past = |timestamp: Option<&OrgTimestamp>| {
+                timestamp
+                    .and_then(|timestamp| timestamp.start_date())
+                    .map(|date| date.to_naive_date())
+                    .filter(|date| *date < today)
+            };
+            let (kind, date) = match (
+                past(planning.deadline.as_ref()),
+                past(planning.scheduled.as_ref()),
+            ) {
+                (Some(date), _) => (TimelineKind::Deadline, date),
+                (None, Some(date)) => (TimelineKind::Scheduled, date),
+                (None, None) => continue,
+            };
+            overdue.push(OverdueTask {
+                entry: AgendaEntry {
+                    document_id: document.id.clone(),
+                    headline_id: headline.id.clone(),
+                    category: entry_category(document, &headline.id),
+                    title: headline.title.raw.clone(),
+                    todo_keyword: headline.title.todo_keyword.as_deref().map(str::to_string),
+                    kind,
+                    time: None,
+                    overdue_since: Some(date),
+                },
+                date,
+            });
+        }
+    }
+    overdue.sort_by_key(|task| task.date);
+    overdue
+}
+
+/// Render the agenda days with the overdue tasks above them, as text to paste into a
+/// mail or as an HTML page, naming days and months in `language`
+pub fn render_digest(
+    days: &[AgendaDay],
+    overdue: &[OverdueTask],
+    today: NaiveDate,
+    format: AgendaFormat,
+    language: &str,
+) -> String {
+    match format {
+        AgendaFormat::Text => {
+            let mut output = agenda_title(days);
+            output.push('\n');
+            if !overdue.is_empty() {
+                output.push_str("Overdue\n");
+                for task in overdue {
+                    output.push_str(&entry_line_text(&task.entry, &task.label(today)));
+                }
+            }
+            output.push_str(&days_text(days, language));
+            output
+        }
+        AgendaFormat::Html => {
+            let mut body = String::new();
+            if !overdue.is_empty() {
+                body.push_str("<section>\n<h2>Overdue</h2>\n<table>\n");
+                for task in overdue {
+                    let label = task.label(today);
+                    body.push_str(&entry_row_html(&task.entry, label.trim_end_matches(':')));
+                }
+                body.push_str("</table>\n</section>\n");
+            }
+            body.push_str(&days_html(days, language));
+            html_page(agenda_title(days).trim_end_matches(':'), &body)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::agenda::collect_agenda;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::work_schedule::WorkSchedule;
+
+    #[test]
+    fn test_render_digest() {
+        let doc = parse_org_document(
+            "#+CATEGORY: work\n* TODO Report\nDEADLINE: <2024-06-01 Sat>\n* TODO Old plan\nSCHEDULED: <2024-05-31 Fri>\n* DONE Filed\nDEADLINE: <2024-05-30 Thu>\n* TODO Call\nSCHEDULED: <2024-06-03 Mon 10:00>\n",
+            Some("work.org"),
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let overdue = collect_overdue(&[&doc], today, &TodoConfiguration::default());
+        let titles: Vec<&str> = overdue
+            .iter()
+            .map(|task| task.entry.title.as_str())
+            .collect();
+        // Closed tasks are not overdue
+        assert_eq!(titles, vec!["Old plan", "Report"]);
+
+        let days = collect_agenda(&[&doc], today, today, &WorkSchedule::default());
+        let text = render_digest(&days, &overdue, today, AgendaFormat::Text, "en");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Day-agenda (W23):",
+                "Overdue",
+                "  work:       Sched. 3x:  TODO Old plan",
+                "  work:       2 d. ago:   TODO Report",
+                "Monday      3 June 2024 W23",
+                "  work:       10:00...... Scheduled:  TODO Call",
+            ]
+        );
+
+        let html = render_digest(&days, &overdue, today, AgendaFormat::Html, "en");
+        assert!(html.contains("<h2>Overdue</h2>"));
+        assert!(html.contains("<td class=\"kind\">2 d. ago</td>"));
+    }
+}
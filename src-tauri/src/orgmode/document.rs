@@ -1,16 +1,24 @@
+use crate::orgmode::colors::StatusStyle;
+use crate::orgmode::flags::DocumentFlags;
 use crate::orgmode::headline::OrgHeadline;
-use crate::orgmode::todo::TodoConfiguration;
+use crate::orgmode::startup::StartupOptions;
+use crate::orgmode::time_zone;
+use crate::orgmode::todo::{file_todo_lines, TodoConfiguration};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::HashMap;
+use std::path::Path;
 
-// Serialize DateTime to RFC3339 format
+// Serialize DateTime to RFC3339 format, with the assumed time zone's offset
 pub(crate) fn serialize_datetime<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
-    serializer.serialize_str(&date.to_rfc3339())
+    serializer.serialize_str(&time_zone::to_rfc3339(
+        *date,
+        time_zone::assumed_time_zone(),
+    ))
 }
 
 /// Basic org-mode document structure
@@ -25,10 +33,90 @@ pub struct OrgDocument {
     #[specta(skip)]
     pub parsed_at: DateTime<Utc>,
     pub file_path: String,
-    pub properties: HashMap<String, String>, // Content from :PROPERTIES: drawer
+    pub properties: HashMap<String, String>, // #+PROPERTY: lines and the top :PROPERTIES: drawer
+    pub keywords: HashMap<String, String>,   // Other #+KEY: lines, without TITLE, FILETAGS etc.
+    pub startup: StartupOptions,             // Parsed from #+STARTUP: lines
+    pub flags: DocumentFlags,                // Parsed from #+ORG_X: lines
+    pub constants: HashMap<String, String>,  // #+CONSTANTS: definitions for table formulas
     pub category: String,                    // Category from #+CATEGORY: line
     pub etag: String,                        // Entity tag for change detection
     pub todo_config: Option<TodoConfiguration>, // Extracted from file
+    #[serde(default)]
+    pub tag_styles: HashMap<String, StatusStyle>, // Styles of the file and headline tags
+    pub archived: bool,                      // Parsed from an .org_archive file
+    pub missing: bool,                       // File no longer exists on disk
+}
+
+/// File extension used by org-mode for archived subtrees
+pub const ARCHIVE_EXTENSION: &str = "org_archive";
+
+/// Check whether a path points to an org archive file
+pub fn is_archive_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION)
+}
+
+impl OrgDocument {
+    /// Find a headline anywhere in the document by its ID
+    pub fn find_headline(&self, headline_id: &str) -> Option<&OrgHeadline> {
+        self.headline_path(headline_id)
+            .and_then(|path| path.last().copied())
+    }
+
+    /// Get the chain of headlines from the top level down to the given headline
+    pub fn headline_path(&self, headline_id: &str) -> Option<Vec<&OrgHeadline>> {
+        fn find_path<'a>(
+            headlines: &'a [OrgHeadline],
+            headline_id: &str,
+            path: &mut Vec<&'a OrgHeadline>,
+        ) -> bool {
+            for headline in headlines {
+                path.push(headline);
+                if headline.id == headline_id || find_path(&headline.children, headline_id, path) {
+                    return true;
+                }
+                path.pop();
+            }
+            false
+        }
+
+        let mut path = Vec::new();
+        if find_path(&self.headlines, headline_id, &mut path) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Get a file-level property, from `#+PROPERTY:` lines or the top property drawer
+    /// Property keys are matched case-insensitively, as in org-mode
+    pub fn get_file_property(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get a property value for a headline, inheriting from its ancestors and then the
+    /// file-level properties
+    /// Property keys are matched case-insensitively, as in org-mode
+    pub fn get_inherited_property(&self, headline_id: &str, key: &str) -> Option<&str> {
+        self.headline_path(headline_id)?
+            .into_iter()
+            .rev()
+            .find_map(|headline| headline.title.get_property_ignore_case(key))
+            .or_else(|| self.get_file_property(key))
+    }
+
+    /// Get the TODO keywords valid in this file
+    /// File-local `#+TODO:` sequences come first, then settings keywords they don't redefine
+    pub fn effective_todo_config(&self, settings: &TodoConfiguration) -> TodoConfiguration {
+        let lines = file_todo_lines(&self.content);
+        if lines.is_empty() {
+            settings.clone()
+        } else {
+            TodoConfiguration::from_org_config(&lines).merged_with(settings)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -47,9 +135,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "test.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: Some(TodoConfiguration::default()),
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         assert_eq!(doc.id, "doc1");
@@ -58,4 +153,34 @@ mod tests {
         assert_eq!(doc.category, "Test");
         assert_eq!(doc.file_path, "test.org");
     }
+
+    #[test]
+    fn test_is_archive_path() {
+        assert!(is_archive_path(Path::new("/notes/todo.org_archive")));
+        assert!(!is_archive_path(Path::new("/notes/todo.org")));
+        assert!(!is_archive_path(Path::new("/notes/org_archive")));
+    }
+
+    #[test]
+    fn test_effective_todo_config() {
+        use crate::orgmode::parser::parse_org_document;
+
+        let settings =
+            TodoConfiguration::from_keywords(&["TODO".to_string()], &["DONE".to_string()]);
+
+        let plain = parse_org_document("* TODO Task\n", Some("plain.org")).unwrap();
+        assert_eq!(
+            plain.effective_todo_config(&settings).keywords(),
+            vec!["TODO", "DONE"]
+        );
+
+        let local = parse_org_document(
+            "#+TODO: NEXT WAITING | DONE\n* NEXT Task\n",
+            Some("local.org"),
+        )
+        .unwrap();
+        let config = local.effective_todo_config(&settings);
+        assert_eq!(config.keywords(), vec!["NEXT", "WAITING", "DONE", "TODO"]);
+        assert_eq!(config.default_sequence, "file-1");
+    }
 }
@@ -0,0 +1,360 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::properties::set_drawer_properties;
+use crate::orgmode::source::{flatten_headlines, headline_level};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Tag marking an org-drill card
+pub const DRILL_TAG: &str = "drill";
+
+/// Tag marking an org-fc card
+pub const FC_TAG: &str = "fc";
+
+/// Reviews rated at or below this quality count as failures, as in org-drill
+const FAILURE_QUALITY: u8 = 2;
+
+/// Lowest ease factor SM2 allows
+const MIN_EASE: f64 = 1.3;
+
+/// Which flashcard package a card belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum CardKind {
+    Drill,
+    Fc,
+}
+
+/// A flashcard headline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Card {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub kind: CardKind,
+    pub due: Option<String>, // YYYY-MM-DD; None for cards never reviewed
+    pub total_repeats: u32,
+    pub failure_count: u32,
+}
+
+/// org-drill's SM2 scheduling state, read from DRILL_* properties
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrillState {
+    pub last_interval: f64, // Days
+    pub repeats_since_fail: u32,
+    pub total_repeats: u32,
+    pub failure_count: u32,
+    pub average_quality: Option<f64>,
+    pub ease: f64,
+}
+
+impl Default for DrillState {
+    fn default() -> Self {
+        Self {
+            last_interval: 0.0,
+            repeats_since_fail: 0,
+            total_repeats: 0,
+            failure_count: 0,
+            average_quality: None,
+            ease: 2.5,
+        }
+    }
+}
+
+impl DrillState {
+    /// Read the state from a headline's properties, defaulting missing values
+    pub fn from_headline(headline: &OrgHeadline) -> Self {
+        let property = |key: &str| headline.title.get_property_ignore_case(key);
+        let number = |key: &str| property(key).and_then(|value| value.trim().parse::<f64>().ok());
+        let count = |key: &str| number(key).map_or(0, |value| value.max(0.0) as u32);
+        let default = Self::default();
+
+        Self {
+            last_interval: number("DRILL_LAST_INTERVAL").unwrap_or(default.last_interval),
+            repeats_since_fail: count("DRILL_REPEATS_SINCE_FAIL"),
+            total_repeats: count("DRILL_TOTAL_REPEATS"),
+            failure_count: count("DRILL_FAILURE_COUNT"),
+            average_quality: number("DRILL_AVERAGE_QUALITY"),
+            ease: number("DRILL_EASE").unwrap_or(default.ease),
+        }
+    }
+
+    /// Apply a review rated 0 (blackout) to 5 (perfect) using org-drill's SM2 variant
+    /// Returns the new state and the days until the next review
+    pub fn review(&self, quality: u8) -> (DrillState, i64) {
+        let quality = quality.min(5);
+        let repeats = self.repeats_since_fail.max(1);
+        let average_quality = Some(match self.average_quality {
+            Some(average) => {
+                (quality as f64 + average * self.total_repeats as f64)
+                    / (self.total_repeats + 1) as f64
+            }
+            None => quality as f64,
+        });
+
+        // A failed card starts over and is due again today; its ease is kept
+        if quality <= FAILURE_QUALITY {
+            let state = DrillState {
+                last_interval: 0.0,
+                repeats_since_fail: 1,
+                total_repeats: self.total_repeats + 1,
+                failure_count: self.failure_count + 1,
+                average_quality,
+                ease: self.ease,
+            };
+            return (state, 0);
+        }
+
+        let ease = next_ease(self.ease, quality);
+        let interval = match repeats {
+            1 => 1.0,
+            2 => 6.0,
+            _ => self.last_interval * ease,
+        };
+        let state = DrillState {
+            last_interval: interval,
+            repeats_since_fail: repeats + 1,
+            total_repeats: self.total_repeats + 1,
+            failure_count: self.failure_count,
+            average_quality,
+            ease,
+        };
+        (state, interval.round() as i64)
+    }
+
+    /// The DRILL_* properties org-drill writes after a review
+    pub fn properties(&self, quality: u8, reviewed: NaiveDateTime) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "DRILL_LAST_INTERVAL",
+                round(self.last_interval, 4).to_string(),
+            ),
+            (
+                "DRILL_REPEATS_SINCE_FAIL",
+                self.repeats_since_fail.to_string(),
+            ),
+            ("DRILL_TOTAL_REPEATS", self.total_repeats.to_string()),
+            ("DRILL_FAILURE_COUNT", self.failure_count.to_string()),
+            (
+                "DRILL_AVERAGE_QUALITY",
+                round(self.average_quality.unwrap_or(0.0), 3).to_string(),
+            ),
+            ("DRILL_EASE", round(self.ease, 3).to_string()),
+            ("DRILL_LAST_QUALITY", quality.min(5).to_string()),
+            (
+                "DRILL_LAST_REVIEWED",
+                reviewed.format("[%Y-%m-%d %a %H:%M]").to_string(),
+            ),
+        ]
+    }
+}
+
+// SM2 ease update; ease never drops below the minimum
+fn next_ease(ease: f64, quality: u8) -> f64 {
+    if ease < MIN_EASE {
+        return MIN_EASE;
+    }
+    let miss = (5 - quality) as f64;
+    (ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE)
+}
+
+fn round(value: f64, places: i32) -> f64 {
+    let factor = 10f64.powi(places);
+    (value * factor).round() / factor
+}
+
+/// Which kind of card a headline is, if any
+pub fn card_kind(headline: &OrgHeadline) -> Option<CardKind> {
+    let has_tag = |tag: &str| headline.title.tags.iter().any(|t| t.as_ref() == tag);
+    if has_tag(DRILL_TAG) {
+        Some(CardKind::Drill)
+    } else if has_tag(FC_TAG) {
+        Some(CardKind::Fc)
+    } else {
+        None
+    }
+}
+
+// Earliest due date in an org-fc REVIEW_DATA table, one row per card side
+fn fc_due_date(content: &str) -> Option<NaiveDate> {
+    content
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.eq_ignore_ascii_case(":REVIEW_DATA:"))
+        .skip(1)
+        .take_while(|line| !line.eq_ignore_ascii_case(":END:"))
+        .filter(|line| line.starts_with('|') && !line.starts_with("|-"))
+        .filter_map(|row| {
+            let due = row.trim_matches('|').rsplit('|').next()?.trim();
+            NaiveDate::parse_from_str(due.get(..10)?, "%Y-%m-%d").ok()
+        })
+        .min()
+}
+
+/// Build a card for a flashcard headline
+pub fn card_for_headline(document: &OrgDocument, headline: &OrgHeadline) -> Option<Card> {
+    let kind = card_kind(headline)?;
+    let (due, total_repeats, failure_count) = match kind {
+        CardKind::Drill => {
+            let state = DrillState::from_headline(headline);
+            let due = headline
+                .scheduled_timestamp()
+                .and_then(|scheduled| scheduled.start_date())
+                .map(|date| date.to_naive_date());
+            (due, state.total_repeats, state.failure_count)
+        }
+        CardKind::Fc => (fc_due_date(&headline.content), 0, 0),
+    };
+
+    Some(Card {
+        document_id: document.id.clone(),
+        headline_id: headline.id.clone(),
+        title: headline.title.raw.clone(),
+        kind,
+        due: due.map(|date| date.format("%Y-%m-%d").to_string()),
+        total_repeats,
+        failure_count,
+    })
+}
+
+/// Collect cards due on or before `today`, most overdue first and new cards last
+/// Missing and read-only archived documents are skipped
+pub fn collect_due_cards(documents: &[&OrgDocument], today: NaiveDate) -> Vec<Card> {
+    let today = today.format("%Y-%m-%d").to_string();
+    let mut cards: Vec<Card> = documents
+        .iter()
+        .filter(|document| !document.missing && !document.archived)
+        .flat_map(|document| {
+            flatten_headlines(&document.headlines)
+                .into_iter()
+                .filter_map(move |headline| card_for_headline(document, headline))
+        })
+        .filter(|card| match &card.due {
+            Some(due) => *due <= today,
+            None => true,
+        })
+        .collect();
+
+    cards.sort_by(|a, b| {
+        (a.due.is_none(), &a.due, &a.document_id).cmp(&(b.due.is_none(), &b.due, &b.document_id))
+    });
+    cards
+}
+
+/// Write a review into the headline starting at `headline_line` (1-based)
+/// The SCHEDULED date is replaced or added and properties are set in the drawer,
+/// creating the planning line and drawer if the headline has none
+/// Returns None if `headline_line` is not a headline line
+pub fn write_review(
+    content: &str,
+    headline_line: usize,
+    scheduled: NaiveDate,
+    properties: &[(&str, String)],
+) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let headline = headline_line.checked_sub(1)?;
+    headline_level(lines.get(headline)?)?;
+    let scheduled = scheduled.format("SCHEDULED: <%Y-%m-%d %a>").to_string();
+
+    let planning = headline + 1;
+    if lines
+        .get(planning)
+        .is_some_and(|line| is_planning_line(line))
+    {
+        let updated = with_scheduled(&lines[planning], &scheduled);
+        lines[planning] = updated;
+    } else {
+        lines.insert(planning, scheduled);
+    }
+
+    set_drawer_properties(&mut lines, headline, properties)?;
+
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    Some(output)
+}
+
+fn is_planning_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+        .iter()
+        .any(|keyword| trimmed.starts_with(keyword))
+}
+
+// Replace the SCHEDULED timestamp of a planning line, or append one
+fn with_scheduled(line: &str, scheduled: &str) -> String {
+    match line.find("SCHEDULED:") {
+        Some(start) => {
+            let end = line[start..]
+                .find('>')
+                .map_or(line.len(), |offset| start + offset + 1);
+            format!("{}{}{}", &line[..start], scheduled, &line[end..])
+        }
+        None => format!("{} {}", line.trim_end(), scheduled),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const DECK: &str = "* Capital of France :drill:\nSCHEDULED: <2024-01-05 Fri>\n:PROPERTIES:\n:ID: card-1\n:DRILL_REPEATS_SINCE_FAIL: 3\n:DRILL_TOTAL_REPEATS: 2\n:DRILL_LAST_INTERVAL: 6.0\n:DRILL_EASE: 2.5\n:END:\nParis\n* New card :drill:\nBerlin\n* Later :drill:\nSCHEDULED: <2024-02-01 Thu>\n* Front :fc:\n:REVIEW_DATA:\n| position | ease | box | interval | due                  |\n|----------+------+-----+----------+----------------------|\n| front    | 2.50 |   0 |     0.00 | 2024-01-03T10:00:00Z |\n:END:\n* Not a card\n";
+
+    #[test]
+    fn test_collect_due_cards() {
+        let doc = parse_org_document(DECK, Some("deck.org")).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let cards = collect_due_cards(&[&doc], today);
+
+        let titles: Vec<&str> = cards.iter().map(|card| card.title.as_str()).collect();
+        assert_eq!(titles, vec!["Front", "Capital of France", "New card"]);
+        assert_eq!(cards[0].kind, CardKind::Fc);
+        assert_eq!(cards[1].due.as_deref(), Some("2024-01-05"));
+        assert_eq!(cards[1].total_repeats, 2);
+        assert_eq!(cards[2].due, None);
+    }
+
+    #[test]
+    fn test_sm2_review() {
+        let state = DrillState::default();
+        let (state, days) = state.review(4);
+        assert_eq!((days, state.repeats_since_fail), (1, 2));
+        let (state, days) = state.review(5);
+        assert_eq!(days, 6);
+        assert!((state.ease - 2.6).abs() < 1e-9);
+        let (state, days) = state.review(4);
+        assert_eq!(days, 16); // 6 * 2.6
+        assert_eq!(state.total_repeats, 3);
+
+        let (failed, days) = state.review(1);
+        assert_eq!(days, 0);
+        assert_eq!(failed.repeats_since_fail, 1);
+        assert_eq!(failed.failure_count, 1);
+        assert_eq!(failed.ease, state.ease);
+    }
+
+    #[test]
+    fn test_write_review() {
+        let scheduled = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+        let properties = vec![
+            ("DRILL_TOTAL_REPEATS", "3".to_string()),
+            ("DRILL_LAST_QUALITY", "4".to_string()),
+        ];
+
+        let updated = write_review(DECK, 1, scheduled, &properties).unwrap();
+        assert!(updated.starts_with(
+            "* Capital of France :drill:\nSCHEDULED: <2024-01-20 Sat>\n:PROPERTIES:\n:ID: card-1\n"
+        ));
+        assert!(updated.contains(":DRILL_TOTAL_REPEATS: 3\n"));
+        assert!(updated.contains(":DRILL_LAST_QUALITY: 4\n:END:\nParis\n"));
+
+        let updated = write_review(DECK, 11, scheduled, &properties).unwrap();
+        assert!(updated.contains(
+            "* New card :drill:\nSCHEDULED: <2024-01-20 Sat>\n:PROPERTIES:\n:DRILL_TOTAL_REPEATS: 3\n:DRILL_LAST_QUALITY: 4\n:END:\nBerlin\n"
+        ));
+        assert_eq!(write_review(DECK, 2, scheduled, &properties), None);
+    }
+}
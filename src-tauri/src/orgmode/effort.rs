@@ -0,0 +1,72 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::properties::parse_duration_minutes;
+
+/// Property holding a headline's effort estimate
+pub const EFFORT_PROPERTY: &str = "Effort";
+
+/// Property listing the allowed effort values, as org's column view uses it
+pub const EFFORT_ALL_PROPERTY: &str = "Effort_ALL";
+
+/// Effort presets offered when neither the file nor the settings define any
+pub const DEFAULT_EFFORT_PRESETS: [&str; 6] = ["0:15", "0:30", "1:00", "2:00", "4:00", "8:00"];
+
+/// Whether a value can be used as an effort estimate
+pub fn is_valid_effort(value: &str) -> bool {
+    parse_duration_minutes(value).is_some()
+}
+
+// Split an Effort_ALL value into its valid entries
+fn parse_presets(value: &str) -> Option<Vec<String>> {
+    let presets: Vec<String> = value
+        .split_whitespace()
+        .filter(|preset| is_valid_effort(preset))
+        .map(str::to_string)
+        .collect();
+    (!presets.is_empty()).then_some(presets)
+}
+
+/// Effort presets for a headline: the nearest `:Effort_ALL:` property on it or an
+/// ancestor, then the file's `#+PROPERTY: Effort_ALL` line
+/// Returns None if neither is set, so the settings presets apply
+pub fn effort_presets(document: &OrgDocument, headline_id: Option<&str>) -> Option<Vec<String>> {
+    headline_id
+        .and_then(|id| document.get_inherited_property(id, EFFORT_ALL_PROPERTY))
+        .and_then(parse_presets)
+        .or_else(|| {
+            document
+                .get_file_property(EFFORT_ALL_PROPERTY)
+                .and_then(parse_presets)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_effort_presets() {
+        let doc = parse_org_document(
+            "#+PROPERTY: Effort_ALL 0 0:10 0:30 1:00\n* Project\n:PROPERTIES:\n:Effort_ALL: 1:00 2:00 later\n:END:\n** TODO Task\n* TODO Other\n",
+            Some("plan.org"),
+        )
+        .unwrap();
+        let task = doc.headlines[0].children[0].id.as_str();
+        let other = doc.headlines[1].id.as_str();
+
+        // Inherited from the project, skipping values that aren't durations
+        assert_eq!(
+            effort_presets(&doc, Some(task)),
+            Some(vec!["1:00".to_string(), "2:00".to_string()])
+        );
+        assert_eq!(
+            effort_presets(&doc, Some(other)),
+            Some(["0", "0:10", "0:30", "1:00"].map(String::from).to_vec())
+        );
+        assert_eq!(
+            effort_presets(&doc, None).map(|presets| presets.len()),
+            Some(4)
+        );
+        assert!(is_valid_effort("1:30") && !is_valid_effort("soon"));
+    }
+}
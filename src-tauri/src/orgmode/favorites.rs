@@ -0,0 +1,137 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::flatten_headlines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A pinned headline, stored in settings
+/// Positional headline IDs shift when headlines are added above, so a favorite is
+/// identified by the org `:ID:` property when set, otherwise by its outline path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Favorite {
+    pub document_id: String,
+    pub org_id: Option<String>,    // Value of the headline's :ID: property
+    pub outline_path: Vec<String>, // Titles from the top-level headline down
+}
+
+/// A favorite resolved against the current document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct FavoriteHeadline {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub todo_keyword: Option<String>,
+    pub outline_path: Vec<String>,
+}
+
+impl Favorite {
+    /// Build a favorite for a headline, or None if the headline is not in the document
+    pub fn for_headline(document: &OrgDocument, headline_id: &str) -> Option<Self> {
+        let path = document.headline_path(headline_id)?;
+        let headline = path.last()?;
+        Some(Self {
+            document_id: document.id.clone(),
+            org_id: headline
+                .title
+                .get_property_ignore_case("ID")
+                .map(str::to_string),
+            outline_path: path
+                .iter()
+                .map(|headline| headline.title.raw.clone())
+                .collect(),
+        })
+    }
+
+    /// Whether two favorites point at the same headline
+    pub fn same_target(&self, other: &Favorite) -> bool {
+        self.document_id == other.document_id
+            && match (&self.org_id, &other.org_id) {
+                (Some(id), Some(other_id)) => id == other_id,
+                _ => self.outline_path == other.outline_path,
+            }
+    }
+
+    /// Find the favorite's headline in the current version of its document
+    pub fn find<'a>(&self, document: &'a OrgDocument) -> Option<&'a OrgHeadline> {
+        locate_headline(document, self.org_id.as_deref(), &self.outline_path)
+    }
+
+    /// Resolve the favorite to its current headline
+    pub fn resolve(&self, document: &OrgDocument) -> Option<FavoriteHeadline> {
+        let headline = self.find(document)?;
+        let outline_path = document
+            .headline_path(&headline.id)?
+            .iter()
+            .map(|headline| headline.title.raw.clone())
+            .collect();
+        Some(FavoriteHeadline {
+            document_id: document.id.clone(),
+            headline_id: headline.id.clone(),
+            title: headline.title.raw.clone(),
+            todo_keyword: headline.title.todo_keyword.as_deref().map(str::to_string),
+            outline_path,
+        })
+    }
+}
+
+/// Find a headline by its `:ID:` property, or else by the titles from the top-level
+/// headline down, so it is found again after headlines are added above it
+pub fn locate_headline<'a>(
+    document: &'a OrgDocument,
+    org_id: Option<&str>,
+    outline_path: &[String],
+) -> Option<&'a OrgHeadline> {
+    if let Some(org_id) = org_id {
+        let by_id = flatten_headlines(&document.headlines)
+            .into_iter()
+            .find(|headline| headline.title.get_property_ignore_case("ID") == Some(org_id));
+        if by_id.is_some() {
+            return by_id;
+        }
+    }
+
+    let mut headlines = &document.headlines;
+    let mut found = None;
+    for title in outline_path {
+        let headline = headlines
+            .iter()
+            .find(|headline| &headline.title.raw == title)?;
+        headlines = &headline.children;
+        found = Some(headline);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_favorite_survives_moved_headlines() {
+        let document = parse_org_document(
+            "* Projects\n** TODO Website\n* Areas\n** Health\n:PROPERTIES:\n:ID: health-1\n:END:\n",
+            Some("/notes/a.org"),
+        )
+        .unwrap();
+        let website = Favorite::for_headline(&document, "1.1").unwrap();
+        let health = Favorite::for_headline(&document, "2.1").unwrap();
+        assert_eq!(website.outline_path, vec!["Projects", "Website"]);
+        assert_eq!(health.org_id.as_deref(), Some("health-1"));
+
+        // A new headline shifts positional IDs; Health is also renamed
+        let edited = parse_org_document(
+            "* Inbox\n* Projects\n** DONE Website\n* Areas\n** Fitness\n:PROPERTIES:\n:ID: health-1\n:END:\n",
+            Some("/notes/a.org"),
+        )
+        .unwrap();
+        let resolved = website.resolve(&edited).unwrap();
+        assert_eq!(resolved.headline_id, "2.1");
+        assert_eq!(resolved.todo_keyword.as_deref(), Some("DONE"));
+        assert_eq!(health.resolve(&edited).unwrap().title, "Fitness");
+
+        let removed = parse_org_document("* Projects\n", Some("/notes/a.org")).unwrap();
+        assert!(website.resolve(&removed).is_none());
+        assert!(website.same_target(&Favorite::for_headline(&edited, "2.1").unwrap()));
+    }
+}
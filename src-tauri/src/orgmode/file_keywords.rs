@@ -0,0 +1,140 @@
+use crate::orgmode::source::headline_level;
+
+// Byte offset of the value on a `#+KEY:` line, if the line sets `key`
+fn keyword_value_start(line: &str, key: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = line[indent..].strip_prefix("#+")?;
+    let name = rest.get(..key.len())?;
+    (name.eq_ignore_ascii_case(key) && rest[key.len()..].starts_with(':'))
+        .then_some(indent + 2 + key.len() + 1)
+}
+
+// Whether a line opens or closes a block like `#+BEGIN_SRC`, as (opens, closes)
+fn block_delimiter(line: &str) -> (bool, bool) {
+    let upper = line.trim_start().to_ascii_uppercase();
+    (upper.starts_with("#+BEGIN_"), upper.starts_with("#+END_"))
+}
+
+// Index of the line where a new keyword goes: after a leading property drawer and, unless
+// `first`, after the keyword and comment lines at the top of the file, stopping at a block
+fn insertion_line(lines: &[&str], first: bool) -> usize {
+    let mut index = 0;
+    if lines
+        .first()
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case(":PROPERTIES:"))
+    {
+        if let Some(end) = lines
+            .iter()
+            .position(|line| line.trim().eq_ignore_ascii_case(":END:"))
+        {
+            index = end + 1;
+        }
+    }
+    if first {
+        return index;
+    }
+    while lines.get(index).is_some_and(|line| {
+        line.starts_with('#') && headline_level(line).is_none() && !block_delimiter(line).0
+    }) {
+        index += 1;
+    }
+    index
+}
+
+/// Set a file keyword such as `#+TITLE:`, or remove it with None
+///
+/// The first line setting the keyword is rewritten in place and any others are removed,
+/// so repeated FILETAGS or CATEGORY lines collapse into one. Lines inside blocks such as
+/// `#+BEGIN_SRC` or `#+BEGIN_EXAMPLE` are left alone. A missing keyword is added
+/// at the top of the file, after a leading property drawer: TITLE before the other
+/// keywords, anything else after them.
+pub fn set_file_keyword(content: &str, key: &str, value: Option<&str>) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut found = false;
+    let mut index = 0;
+    let mut in_block = false;
+
+    while index < lines.len() {
+        match block_delimiter(&lines[index]) {
+            (true, _) => in_block = true,
+            (_, true) => in_block = false,
+            _ => {}
+        }
+        if in_block {
+            index += 1;
+            continue;
+        }
+        match keyword_value_start(&lines[index], key) {
+            Some(start) if !found => {
+                found = true;
+                match value {
+                    Some(value) => {
+                        let prefix = lines[index][..start].trim_end().to_string();
+                        lines[index] = format!("{} {}", prefix, value);
+                        index += 1;
+                    }
+                    None => {
+                        lines.remove(index);
+                    }
+                }
+            }
+            Some(_) => {
+                lines.remove(index);
+            }
+            None => index += 1,
+        }
+    }
+
+    if let (false, Some(value)) = (found, value) {
+        let line_refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let at = insertion_line(&line_refs, key.eq_ignore_ascii_case("TITLE"));
+        lines.insert(at, format!("#+{}: {}", key, value));
+    }
+
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') || (content.is_empty() && !output.is_empty()) {
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_file_keyword() {
+        let content = ":PROPERTIES:\n:ID: note\n:END:\n#+title: Draft\n#+FILETAGS: :a:\n\n* Heading\n#+FILETAGS: :b:\n";
+
+        // Existing lines keep their spelling of the key
+        let titled = set_file_keyword(content, "TITLE", Some("Final"));
+        assert!(titled.contains("\n#+title: Final\n"));
+
+        // Repeated lines collapse into the first
+        let tagged = set_file_keyword(content, "FILETAGS", Some(":c:d:"));
+        assert_eq!(
+            tagged,
+            ":PROPERTIES:\n:ID: note\n:END:\n#+title: Draft\n#+FILETAGS: :c:d:\n\n* Heading\n"
+        );
+
+        // New keywords go after the drawer and the existing keywords, TITLE first
+        let categorized = set_file_keyword(content, "CATEGORY", Some("notes"));
+        assert!(categorized.contains("#+FILETAGS: :a:\n#+CATEGORY: notes\n\n* Heading"));
+        let plain = set_file_keyword("#+AUTHOR: Me\n* Task\n", "TITLE", Some("Plan"));
+        assert_eq!(plain, "#+TITLE: Plan\n#+AUTHOR: Me\n* Task\n");
+
+        let untagged = set_file_keyword(content, "FILETAGS", None);
+        assert!(!untagged.contains("FILETAGS"));
+        assert_eq!(set_file_keyword("", "TITLE", Some("New")), "#+TITLE: New\n");
+
+        // Keywords quoted in blocks are not the file's own
+        let quoted = "#+TITLE: Notes\n* Org\n#+BEGIN_EXAMPLE\n#+TITLE: Example\n#+END_EXAMPLE\n";
+        let renamed = set_file_keyword(quoted, "TITLE", Some("Org notes"));
+        assert_eq!(renamed, quoted.replace("Notes", "Org notes"));
+        let block = "#+BEGIN_SRC org\n#+CATEGORY: demo\n#+END_SRC\n";
+        assert_eq!(
+            set_file_keyword(block, "CATEGORY", Some("work")),
+            format!("#+CATEGORY: work\n{}", block)
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// In-file keyword holding a document's flags, like `#+ORG_X: no-index readonly`
+pub const FLAGS_KEYWORD: &str = "ORG_X";
+
+/// Per-file opt-outs from a document's `#+ORG_X:` lines
+/// Unknown flags are ignored, so files stay readable by older versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct DocumentFlags {
+    pub no_index: bool,  // `no-index`: left out of the full-text search index
+    pub no_agenda: bool, // `no-agenda`: left out of the agenda and the views built on it
+    pub read_only: bool, // `readonly`: never written back to
+}
+
+impl DocumentFlags {
+    /// Collect the flags of every `#+ORG_X:` line in a file
+    pub fn from_content(content: &str) -> Self {
+        let mut flags = Self::default();
+        let values = content.lines().filter_map(|line| {
+            let rest = line.trim_start().strip_prefix("#+")?;
+            let (key, value) = rest.split_once(':')?;
+            key.eq_ignore_ascii_case(FLAGS_KEYWORD).then_some(value)
+        });
+
+        for flag in values.flat_map(str::split_whitespace) {
+            match flag.to_ascii_lowercase().as_str() {
+                "no-index" => flags.no_index = true,
+                "no-agenda" => flags.no_agenda = true,
+                "readonly" | "read-only" => flags.read_only = true,
+                _ => {}
+            }
+        }
+        flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_document_flags() {
+        let content =
+            "#+TITLE: Inbox\n#+ORG_X: no-index NO-AGENDA\n#+org_x: readonly sync\n* Task\n";
+        let doc = parse_org_document(content, Some("inbox.org")).unwrap();
+        assert_eq!(
+            doc.flags,
+            DocumentFlags {
+                no_index: true,
+                no_agenda: true,
+                read_only: true,
+            }
+        );
+        assert!(DocumentFlags::from_content("* Task\n#+ORG_X: readonly\n").read_only);
+        assert_eq!(
+            DocumentFlags::from_content("#+TITLE: Notes\n"),
+            DocumentFlags::default()
+        );
+    }
+}
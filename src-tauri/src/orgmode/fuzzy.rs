@@ -0,0 +1,204 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::normalize::normalize_char;
+use crate::orgmode::source::flatten_headlines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+const SCORE_MATCH: u32 = 16;
+const BONUS_FIRST_CHAR: u32 = 8;
+const BONUS_WORD_START: u32 = 10;
+const BONUS_CONSECUTIVE: u32 = 6;
+const BONUS_CASE_MATCH: u32 = 1;
+const PENALTY_GAP_START: u32 = 3;
+const PENALTY_GAP_EXTENSION: u32 = 1;
+
+/// A fuzzy match of a document or headline title
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct QuickFindResult {
+    pub document_id: String,
+    pub headline_id: Option<String>, // None when the document title matched
+    pub title: String,
+    pub score: u32,
+    pub positions: Vec<u32>, // Matched character indices in the title, for highlighting
+}
+
+/// Score `candidate` against `query` as an in-order subsequence match
+///
+/// Matching ignores case, diacritics and character width, and favours matches at word
+/// starts and runs of consecutive characters. Returns None if not every query character
+/// is found.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<(u32, Vec<u32>)> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return None;
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+    if query.len() > chars.len() {
+        return None;
+    }
+
+    // best[i][j]: best score matching query[..=i] with query[i] at chars[j]
+    let mut best: Vec<Vec<Option<u32>>> = vec![vec![None; chars.len()]; query.len()];
+    let mut from: Vec<Vec<usize>> = vec![vec![0; chars.len()]; query.len()];
+
+    for (i, &q) in query.iter().enumerate() {
+        for (j, &c) in chars.iter().enumerate() {
+            if !chars_match(q, c) {
+                continue;
+            }
+
+            let mut bonus = SCORE_MATCH + char_bonus(&chars, j);
+            if q == c {
+                bonus += BONUS_CASE_MATCH;
+            }
+
+            if i == 0 {
+                let gap = (j as u32).min(PENALTY_GAP_START * 3);
+                best[0][j] =
+                    Some((bonus + BONUS_FIRST_CHAR * u32::from(j == 0)).saturating_sub(gap));
+                continue;
+            }
+
+            // Pick the best previous position, paying for the gap in between
+            for k in 0..j {
+                let Some(previous) = best[i - 1][k] else {
+                    continue;
+                };
+                let score = if k + 1 == j {
+                    previous + bonus + BONUS_CONSECUTIVE
+                } else {
+                    let gap = (j - k - 1) as u32;
+                    (previous + bonus)
+                        .saturating_sub(PENALTY_GAP_START + PENALTY_GAP_EXTENSION * (gap - 1))
+                };
+                if best[i][j].is_none_or(|current| score > current) {
+                    best[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut position, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))?;
+
+    let mut positions = vec![position as u32];
+    for i in (1..=last).rev() {
+        position = from[i][position];
+        positions.push(position as u32);
+    }
+    positions.reverse();
+
+    Some((score, positions))
+}
+
+fn chars_match(query: char, candidate: char) -> bool {
+    if query.is_ascii() && candidate.is_ascii() {
+        return query.eq_ignore_ascii_case(&candidate);
+    }
+    normalize_char(query) == normalize_char(candidate)
+}
+
+// Bonus for matching at the start of a word or a camelCase hump
+fn char_bonus(chars: &[char], index: usize) -> u32 {
+    let Some(&previous) = index.checked_sub(1).and_then(|i| chars.get(i)) else {
+        return BONUS_WORD_START;
+    };
+    let current = chars[index];
+
+    if !previous.is_alphanumeric() && current.is_alphanumeric()
+        || previous.is_lowercase() && current.is_uppercase()
+    {
+        BONUS_WORD_START
+    } else {
+        0
+    }
+}
+
+/// Fuzzy-find document and headline titles, best matches first
+pub fn quick_find(documents: &[&OrgDocument], query: &str, limit: usize) -> Vec<QuickFindResult> {
+    let mut results = Vec::new();
+
+    for document in documents {
+        if let Some((score, positions)) = fuzzy_match(query, &document.title) {
+            results.push(QuickFindResult {
+                document_id: document.id.clone(),
+                headline_id: None,
+                title: document.title.clone(),
+                score,
+                positions,
+            });
+        }
+
+        for headline in flatten_headlines(&document.headlines) {
+            if let Some((score, positions)) = fuzzy_match(query, &headline.title.raw) {
+                results.push(QuickFindResult {
+                    document_id: document.id.clone(),
+                    headline_id: Some(headline.id.clone()),
+                    title: headline.title.raw.clone(),
+                    score,
+                    positions,
+                });
+            }
+        }
+    }
+
+    // Higher score first, then shorter titles, then a stable order
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.title.chars().count().cmp(&b.title.chars().count()))
+            .then_with(|| a.document_id.cmp(&b.document_id))
+            .then_with(|| a.headline_id.cmp(&b.headline_id))
+    });
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("xyz", "Weekly review"), None);
+        assert_eq!(fuzzy_match("", "Weekly review"), None);
+
+        let (_, positions) = fuzzy_match("wr", "Weekly review").unwrap();
+        assert_eq!(positions, vec![0, 7]);
+
+        // Word starts beat scattered matches
+        let (start, _) = fuzzy_match("wr", "Weekly review").unwrap();
+        let (scattered, _) = fuzzy_match("wr", "Dowry").unwrap();
+        assert!(start > scattered);
+
+        let (_, positions) = fuzzy_match("cafe", "Le Café").unwrap();
+        assert_eq!(positions, vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_quick_find() {
+        let doc = parse_org_document(
+            "#+TITLE: Project plan\n* Planning meeting\n* Plant watering\n** Pay plumber\n",
+            Some("plan.org"),
+        )
+        .unwrap();
+
+        let results = quick_find(&[&doc], "plan", 10);
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        // Equal prefix matches rank shorter titles first
+        assert_eq!(titles[..2], ["Plant watering", "Planning meeting"]);
+        assert!(titles.contains(&"Project plan"));
+        assert!(!titles.contains(&"Pay plumber"));
+
+        let document_hit = results.iter().find(|r| r.title == "Project plan").unwrap();
+        assert_eq!(document_hit.headline_id, None);
+
+        assert_eq!(quick_find(&[&doc], "plan", 1).len(), 1);
+    }
+}
@@ -0,0 +1,614 @@
+use crate::orgmode::issues::{api_agent, github_request};
+use crate::orgmode::parser::extract_todo_keywords_from_content;
+use crate::orgmode::properties::{property_key, set_drawer_properties};
+use crate::orgmode::source::headline_level;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+
+/// Property with the `owner/repo#number` of a synced issue
+pub const GITHUB_ISSUE_PROPERTY: &str = "GITHUB_ISSUE";
+
+/// Property with the state of a synced issue at the last sync, `open` or `closed`
+pub const GITHUB_STATE_PROPERTY: &str = "GITHUB_STATE";
+
+// Shortest time between automatic syncs, to stay within API rate limits
+const MIN_SYNC_MINUTES: u32 = 5;
+
+// Most open assigned issues fetched per repository
+const PAGE_SIZE: u32 = 100;
+
+/// A repository whose assigned issues are synced, and the keywords issue states map to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct GitHubSyncRepository {
+    pub repository: String,     // `owner/repo`
+    pub open_keyword: String,   // Keyword of open issues, e.g. TODO
+    pub closed_keyword: String, // Keyword of closed issues, e.g. DONE
+    pub close_on_github: bool,  // Close the issue when its headline is given closed_keyword
+}
+
+impl GitHubSyncRepository {
+    // Keyword for an issue state
+    fn keyword(&self, state: &str) -> &str {
+        if state == "open" {
+            &self.open_keyword
+        } else {
+            &self.closed_keyword
+        }
+    }
+}
+
+/// Two-way sync of the issues assigned to the GitHub user with an org file
+/// Requests use the GitHub token of the issue settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct GitHubSyncConfig {
+    pub enabled: bool,
+    pub file: Option<String>, // Org file with one headline per issue
+    pub repositories: Vec<GitHubSyncRepository>,
+    pub interval_minutes: u32,
+}
+
+impl Default for GitHubSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: None,
+            repositories: Vec::new(),
+            interval_minutes: 15,
+        }
+    }
+}
+
+// Whether a repository name has the `owner/repo` form
+fn is_repository_name(name: &str) -> bool {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    name.split_once('/')
+        .is_some_and(|(owner, repo)| valid(owner) && valid(repo))
+}
+
+impl GitHubSyncConfig {
+    /// Check the interval, the file and each repository's name and keywords
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_minutes < MIN_SYNC_MINUTES {
+            return Err(format!(
+                "GitHub issues can be synced at most every {} minutes",
+                MIN_SYNC_MINUTES
+            ));
+        }
+        if self.enabled
+            && self
+                .file
+                .as_deref()
+                .is_none_or(|file| file.trim().is_empty())
+        {
+            return Err("Choose a file for GitHub issues".to_string());
+        }
+        let mut names = HashSet::new();
+        for repository in &self.repositories {
+            if !is_repository_name(&repository.repository) {
+                return Err(format!(
+                    "Invalid repository: {} (expected owner/repo)",
+                    repository.repository
+                ));
+            }
+            if !names.insert(repository.repository.to_lowercase()) {
+                return Err(format!("{} is listed twice", repository.repository));
+            }
+            for keyword in [&repository.open_keyword, &repository.closed_keyword] {
+                if keyword.is_empty() || keyword.contains(char::is_whitespace) {
+                    return Err(format!("Invalid keyword: '{}'", keyword));
+                }
+            }
+            if repository.open_keyword == repository.closed_keyword {
+                return Err(format!(
+                    "Open and closed issues of {} need different keywords",
+                    repository.repository
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Settings of a synced repository
+    fn repository(&self, name: &str) -> Option<&GitHubSyncRepository> {
+        self.repositories
+            .iter()
+            .find(|repository| repository.repository.eq_ignore_ascii_case(name))
+    }
+}
+
+/// An issue as reported by GitHub
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteIssue {
+    pub repository: String,
+    pub number: u64,
+    pub title: String,
+    pub state: String, // `open` or `closed`
+    pub url: String,
+}
+
+impl RemoteIssue {
+    /// The `owner/repo#number` key stored in the GITHUB_ISSUE property
+    pub fn key(&self) -> String {
+        format!("{}#{}", self.repository, self.number)
+    }
+}
+
+/// What a sync changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct GitHubSyncSummary {
+    pub added: u32,            // Newly assigned issues filed as headlines
+    pub updated: u32,          // Headlines whose title or state changed
+    pub closed_on_github: u32, // Issues closed because their headline was closed
+}
+
+// A headline mirroring an issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SyncedIssue {
+    line: usize, // 0-based index of the headline line
+    key: String,
+    state: Option<String>,
+}
+
+// Headlines with a GITHUB_ISSUE property, read from their property drawers
+fn synced_issues(lines: &[String]) -> Vec<SyncedIssue> {
+    let mut synced = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if headline_level(line).is_none() {
+            continue;
+        }
+        let mut key = None;
+        let mut state = None;
+        for body in lines[index + 1..]
+            .iter()
+            .take_while(|body| headline_level(body).is_none())
+        {
+            let Some(property) = property_key(body) else {
+                continue;
+            };
+            let value = body.trim_start()[property.len() + 2..].trim().to_string();
+            if property.eq_ignore_ascii_case(GITHUB_ISSUE_PROPERTY) {
+                key = Some(value);
+            } else if property.eq_ignore_ascii_case(GITHUB_STATE_PROPERTY) {
+                state = Some(value);
+            }
+        }
+        if let Some(key) = key {
+            synced.push(SyncedIssue {
+                line: index,
+                key,
+                state,
+            });
+        }
+    }
+    synced
+}
+
+// Split an `owner/repo#number` key
+fn split_key(key: &str) -> Option<(&str, u64)> {
+    let (repository, number) = key.split_once('#')?;
+    Some((repository, number.parse().ok()?))
+}
+
+// A headline line split into stars, keyword, priority cookie, title and tags
+struct HeadlineParts<'a> {
+    stars: &'a str,
+    keyword: Option<&'a str>,
+    priority: Option<&'a str>,
+    title: String,
+    tags: Option<&'a str>,
+}
+
+fn headline_parts<'a>(line: &'a str, keywords: &HashSet<String>) -> HeadlineParts<'a> {
+    let stars_end = line.find(|c: char| c != '*').unwrap_or(line.len());
+    let mut words: Vec<&str> = line[stars_end..].split_whitespace().collect();
+    let tags = words
+        .last()
+        .copied()
+        .filter(|word| word.len() > 2 && word.starts_with(':') && word.ends_with(':'));
+    if tags.is_some() {
+        words.pop();
+    }
+    let keyword = words
+        .first()
+        .copied()
+        .filter(|word| keywords.contains(*word));
+    if keyword.is_some() {
+        words.remove(0);
+    }
+    let priority = words
+        .first()
+        .copied()
+        .filter(|word| word.len() == 4 && word.starts_with("[#") && word.ends_with(']'));
+    if priority.is_some() {
+        words.remove(0);
+    }
+    HeadlineParts {
+        stars: &line[..stars_end],
+        keyword,
+        priority,
+        title: words.join(" "),
+        tags,
+    }
+}
+
+fn headline_line(parts: &HeadlineParts) -> String {
+    let mut line = parts.stars.to_string();
+    for part in [
+        parts.keyword,
+        parts.priority,
+        Some(parts.title.as_str()),
+        parts.tags,
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|part| !part.is_empty())
+    {
+        line.push(' ');
+        line.push_str(part);
+    }
+    line
+}
+
+// Keywords recognized on synced headlines: the file's and the configured ones
+fn known_keywords(content: &str, config: &GitHubSyncConfig) -> HashSet<String> {
+    let (active, closed) = extract_todo_keywords_from_content(content);
+    active
+        .into_iter()
+        .chain(closed)
+        .chain(config.repositories.iter().flat_map(|repository| {
+            [
+                repository.open_keyword.clone(),
+                repository.closed_keyword.clone(),
+            ]
+        }))
+        .collect()
+}
+
+/// Keys of synced issues to close on GitHub: those of repositories that allow it whose
+/// headline has the closed keyword while the issue was open at the last sync
+pub fn issues_to_close(content: &str, config: &GitHubSyncConfig) -> Vec<String> {
+    let keywords = known_keywords(content, config);
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    synced_issues(&lines)
+        .into_iter()
+        .filter(|synced| synced.state.as_deref() == Some("open"))
+        .filter(|synced| {
+            let Some((name, _)) = split_key(&synced.key) else {
+                return false;
+            };
+            config.repository(name).is_some_and(|repository| {
+                repository.close_on_github
+                    && headline_parts(&lines[synced.line], &keywords).keyword
+                        == Some(repository.closed_keyword.as_str())
+            })
+        })
+        .map(|synced| synced.key)
+        .collect()
+}
+
+/// A new headline for an open issue, linking to it in the body
+pub fn issue_entry(issue: &RemoteIssue, repository: &GitHubSyncRepository) -> String {
+    format!(
+        "* {} {}\n:PROPERTIES:\n:{}: {}\n:{}: {}\n:END:\n{}\n",
+        repository.keyword(&issue.state),
+        issue.title,
+        GITHUB_ISSUE_PROPERTY,
+        issue.key(),
+        GITHUB_STATE_PROPERTY,
+        issue.state,
+        issue.url
+    )
+}
+
+/// Mirror fetched issues into the content of the sync file
+/// Synced headlines get the issue's title, and its state's keyword when the state
+/// changed since the last sync, so local keywords like NEXT are kept otherwise.
+/// Open issues without a headline are appended; issues of unlisted repositories are
+/// left out.
+pub fn apply_remote_issues(
+    content: &str,
+    issues: &[RemoteIssue],
+    config: &GitHubSyncConfig,
+) -> (String, GitHubSyncSummary) {
+    let keywords = known_keywords(content, config);
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut summary = GitHubSyncSummary::default();
+    let mut entries = String::new();
+
+    // Synced headlines by key, ignoring case, keeping the first headline of each issue
+    let mut index: HashMap<String, SyncedIssue> = HashMap::new();
+    for synced in synced_issues(&lines) {
+        index
+            .entry(synced.key.to_ascii_lowercase())
+            .or_insert(synced);
+    }
+
+    for issue in issues {
+        let Some(repository) = config.repository(&issue.repository) else {
+            continue;
+        };
+        let Some(synced) = index.get_mut(&issue.key().to_ascii_lowercase()) else {
+            if issue.state == "open" {
+                entries.push_str(&issue_entry(issue, repository));
+                summary.added += 1;
+            }
+            continue;
+        };
+
+        let state_changed = synced.state.as_deref() != Some(issue.state.as_str());
+        let mut parts = headline_parts(&lines[synced.line], &keywords);
+        if state_changed {
+            parts.keyword = Some(repository.keyword(&issue.state));
+        }
+        parts.title = issue.title.clone();
+        let headline = headline_line(&parts);
+
+        let title_changed = headline != lines[synced.line];
+        lines[synced.line] = headline;
+        if state_changed {
+            synced.state = Some(issue.state.clone());
+            let line = synced.line;
+            let before = lines.len();
+            set_drawer_properties(
+                &mut lines,
+                line,
+                &[(GITHUB_STATE_PROPERTY, issue.state.clone())],
+            );
+            // A new drawer line moves the headlines below it
+            let inserted = lines.len() - before;
+            if inserted > 0 {
+                for synced in index.values_mut().filter(|synced| synced.line > line) {
+                    synced.line += inserted;
+                }
+            }
+        }
+        if title_changed || state_changed {
+            summary.updated += 1;
+        }
+    }
+
+    let mut output = lines.join("\n");
+    if !output.is_empty() && (content.ends_with('\n') || !entries.is_empty()) {
+        output.push('\n');
+    }
+    output.push_str(&entries);
+    (output, summary)
+}
+
+// An issue from the REST API, or None for pull requests and unexpected responses
+fn parse_remote_issue(repository: &str, body: &serde_json::Value) -> Option<RemoteIssue> {
+    if !body["pull_request"].is_null() {
+        return None;
+    }
+    Some(RemoteIssue {
+        repository: repository.to_string(),
+        number: body["number"].as_u64()?,
+        title: body["title"]
+            .as_str()?
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+        state: body["state"].as_str()?.to_string(),
+        url: body["html_url"].as_str()?.to_string(),
+    })
+}
+
+fn json(request: ureq::Request) -> Result<serde_json::Value, String> {
+    request
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())
+}
+
+/// Close issues whose headlines were closed, fetch the open issues assigned to the
+/// token's user in each repository and mirror them into `content`
+/// Blocks the calling thread. Synced issues that were open and are no longer listed are
+/// fetched one by one, so closing them on GitHub closes their headline. Failed requests
+/// are logged and skipped.
+pub fn sync_github_issues(
+    content: &str,
+    config: &GitHubSyncConfig,
+    token: Option<&str>,
+) -> Result<(String, GitHubSyncSummary), String> {
+    let token = token.ok_or_else(|| "Syncing GitHub issues needs a GitHub token".to_string())?;
+    let agent = api_agent();
+    let user = json(github_request(&agent, "GET", "/user", Some(token)))?;
+    let login = user["login"]
+        .as_str()
+        .ok_or_else(|| "Unexpected response for the GitHub user".to_string())?;
+
+    let mut issues: Vec<RemoteIssue> = Vec::new();
+    let mut closed_on_github = 0;
+    for key in issues_to_close(content, config) {
+        let Some((repository, number)) = split_key(&key) else {
+            continue;
+        };
+        let closed = github_request(
+            &agent,
+            "PATCH",
+            &format!("/repos/{}/issues/{}", repository, number),
+            Some(token),
+        )
+        .send_json(serde_json::json!({ "state": "closed" }))
+        .map_err(|e| e.to_string())
+        .and_then(|response| {
+            response
+                .into_json::<serde_json::Value>()
+                .map_err(|e| e.to_string())
+        });
+        match closed.map(|body| parse_remote_issue(repository, &body)) {
+            Ok(Some(issue)) => {
+                issues.push(issue);
+                closed_on_github += 1;
+            }
+            Ok(None) => eprintln!("Unexpected response for issue {}", key),
+            Err(e) => eprintln!("Failed to close issue {}: {}", key, e),
+        }
+    }
+
+    for repository in &config.repositories {
+        let path = format!(
+            "/repos/{}/issues?assignee={}&state=open&per_page={}",
+            repository.repository, login, PAGE_SIZE
+        );
+        match json(github_request(&agent, "GET", &path, Some(token))) {
+            Ok(body) => issues.extend(
+                body.as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|issue| parse_remote_issue(&repository.repository, issue)),
+            ),
+            Err(e) => eprintln!("Failed to list issues of {}: {}", repository.repository, e),
+        }
+    }
+
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let listed: HashSet<String> = issues.iter().map(RemoteIssue::key).collect();
+    for synced in synced_issues(&lines) {
+        if synced.state.as_deref() != Some("open") || listed.contains(&synced.key) {
+            continue;
+        }
+        let Some((repository, number)) = split_key(&synced.key) else {
+            continue;
+        };
+        let Some(repository) = config.repository(repository) else {
+            continue;
+        };
+        let path = format!("/repos/{}/issues/{}", repository.repository, number);
+        match json(github_request(&agent, "GET", &path, Some(token))) {
+            Ok(body) => issues.extend(parse_remote_issue(&repository.repository, &body)),
+            Err(e) => eprintln!("Failed to fetch issue {}: {}", synced.key, e),
+        }
+    }
+
+    let (content, mut summary) = apply_remote_issues(content, &issues, config);
+    summary.closed_on_github = closed_on_github;
+    Ok((content, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GitHubSyncConfig {
+        GitHubSyncConfig {
+            enabled: true,
+            file: Some("github.org".to_string()),
+            repositories: vec![GitHubSyncRepository {
+                repository: "acme/app".to_string(),
+                open_keyword: "TODO".to_string(),
+                closed_keyword: "DONE".to_string(),
+                close_on_github: true,
+            }],
+            interval_minutes: 15,
+        }
+    }
+
+    fn issue(number: u64, title: &str, state: &str) -> RemoteIssue {
+        RemoteIssue {
+            repository: "acme/app".to_string(),
+            number,
+            title: title.to_string(),
+            state: state.to_string(),
+            url: format!("https://github.com/acme/app/issues/{}", number),
+        }
+    }
+
+    const SYNCED: &str = "#+TODO: TODO NEXT | DONE\n* NEXT [#A] Fix crash :work:\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#1\n:GITHUB_STATE: open\n:END:\nNotes kept here\n* DONE Update docs\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#2\n:GITHUB_STATE: open\n:END:\n";
+
+    #[test]
+    fn test_apply_remote_issues() {
+        let issues = vec![
+            issue(1, "Fix crash on start", "open"),
+            issue(2, "Update docs", "closed"),
+            issue(3, "Add export", "open"),
+            RemoteIssue {
+                repository: "acme/other".to_string(),
+                ..issue(4, "Unlisted", "open")
+            },
+        ];
+        let (content, summary) = apply_remote_issues(SYNCED, &issues, &config());
+        assert_eq!(
+            content,
+            "#+TODO: TODO NEXT | DONE\n* NEXT [#A] Fix crash on start :work:\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#1\n:GITHUB_STATE: open\n:END:\nNotes kept here\n* DONE Update docs\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#2\n:GITHUB_STATE: closed\n:END:\n* TODO Add export\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#3\n:GITHUB_STATE: open\n:END:\nhttps://github.com/acme/app/issues/3\n"
+        );
+        assert_eq!(
+            summary,
+            GitHubSyncSummary {
+                added: 1,
+                updated: 2,
+                closed_on_github: 0
+            }
+        );
+
+        // Reopening on GitHub brings the open keyword back
+        let (content, _) =
+            apply_remote_issues(&content, &[issue(2, "Update docs", "open")], &config());
+        assert!(content.contains("* TODO Update docs\n"));
+
+        // A state added to the first drawer leaves the second headline's line in place
+        let without_state = "* TODO One\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#1\n:END:\n* TODO Two\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#2\n:END:\n";
+        let (content, summary) = apply_remote_issues(
+            without_state,
+            &[issue(1, "One", "open"), issue(2, "Two", "closed")],
+            &config(),
+        );
+        assert_eq!(
+            content,
+            "* TODO One\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#1\n:GITHUB_STATE: open\n:END:\n* DONE Two\n:PROPERTIES:\n:GITHUB_ISSUE: acme/app#2\n:GITHUB_STATE: closed\n:END:\n"
+        );
+        assert_eq!(summary.updated, 2);
+    }
+
+    #[test]
+    fn test_issues_to_close() {
+        assert_eq!(issues_to_close(SYNCED, &config()), vec!["acme/app#2"]);
+
+        let mut manual = config();
+        manual.repositories[0].close_on_github = false;
+        assert!(issues_to_close(SYNCED, &manual).is_empty());
+    }
+
+    #[test]
+    fn test_parse_remote_issue() {
+        let body = serde_json::json!({
+            "number": 7,
+            "title": "Crash\nwhen saving",
+            "state": "open",
+            "html_url": "https://github.com/acme/app/issues/7"
+        });
+        assert_eq!(
+            parse_remote_issue("acme/app", &body),
+            Some(issue(7, "Crash when saving", "open"))
+        );
+        let pull = serde_json::json!({
+            "number": 8,
+            "title": "Fix",
+            "state": "open",
+            "html_url": "https://github.com/acme/app/pull/8",
+            "pull_request": { "merged_at": null }
+        });
+        assert_eq!(parse_remote_issue("acme/app", &pull), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+        let mut invalid = config();
+        invalid.repositories[0].repository = "acme".to_string();
+        assert!(invalid.validate().is_err());
+        let mut invalid = config();
+        invalid.repositories[0].closed_keyword = "TODO".to_string();
+        assert!(invalid.validate().is_err());
+        let mut invalid = config();
+        invalid.file = None;
+        assert!(invalid.validate().is_err());
+    }
+}
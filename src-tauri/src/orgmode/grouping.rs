@@ -0,0 +1,229 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::properties::{matches_filters, PropertyFilter};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+
+/// Property used by org-super-agenda style setups to bucket tasks
+pub const AGENDA_GROUP_PROPERTY: &str = "agenda-group";
+
+/// How tasks are bucketed in a grouped listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum GroupingMode {
+    /// Group by effective category (inherited CATEGORY property or document category)
+    Category,
+    /// Group by the inherited `agenda-group` property
+    AgendaGroup,
+}
+
+/// Reference to a headline within a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HeadlineRef {
+    pub document_id: String,
+    pub headline_id: String,
+}
+
+/// A named bucket of headlines
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HeadlineGroup {
+    /// Group name, or None for tasks that have no value for the grouping key
+    pub name: Option<String>,
+    pub headlines: Vec<HeadlineRef>,
+}
+
+/// Bucket all tasks in the given documents according to the grouping mode
+///
+/// Groups are sorted by name with the ungrouped bucket last. Within a group,
+/// tasks keep document order (documents sorted by file path). Only tasks
+/// matching all property filters are included. Documents whose files are
+/// missing or archived are skipped.
+pub fn group_tasks(
+    documents: &[&OrgDocument],
+    mode: GroupingMode,
+    filters: &[PropertyFilter],
+) -> Vec<HeadlineGroup> {
+    let mut documents: Vec<&OrgDocument> = documents
+        .iter()
+        .copied()
+        .filter(|document| !document.missing && !document.archived)
+        .collect();
+    documents.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    let mut named: BTreeMap<String, Vec<HeadlineRef>> = BTreeMap::new();
+    let mut ungrouped = Vec::new();
+
+    for document in documents {
+        let fallback = match mode {
+            GroupingMode::Category if !document.category.is_empty() => {
+                Some(document.category.as_str())
+            }
+            _ => None,
+        };
+        collect_tasks(
+            document,
+            &document.headlines,
+            mode,
+            filters,
+            fallback,
+            &mut named,
+            &mut ungrouped,
+        );
+    }
+
+    let mut groups: Vec<HeadlineGroup> = named
+        .into_iter()
+        .map(|(name, headlines)| HeadlineGroup {
+            name: Some(name),
+            headlines,
+        })
+        .collect();
+
+    if !ungrouped.is_empty() {
+        groups.push(HeadlineGroup {
+            name: None,
+            headlines: ungrouped,
+        });
+    }
+
+    groups
+}
+
+// Walk the tree carrying the inherited group value down to children
+fn collect_tasks(
+    document: &OrgDocument,
+    headlines: &[OrgHeadline],
+    mode: GroupingMode,
+    filters: &[PropertyFilter],
+    inherited: Option<&str>,
+    named: &mut BTreeMap<String, Vec<HeadlineRef>>,
+    ungrouped: &mut Vec<HeadlineRef>,
+) {
+    let key = match mode {
+        GroupingMode::Category => "CATEGORY",
+        GroupingMode::AgendaGroup => AGENDA_GROUP_PROPERTY,
+    };
+
+    for headline in headlines {
+        let value = headline
+            .title
+            .get_property_ignore_case(key)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .or(inherited);
+
+        if headline.is_task() && matches_filters(document, headline, filters) {
+            let headline_ref = HeadlineRef {
+                document_id: document.id.clone(),
+                headline_id: headline.id.clone(),
+            };
+            match value {
+                Some(name) => named
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(headline_ref),
+                None => ungrouped.push(headline_ref),
+            }
+        }
+
+        collect_tasks(
+            document,
+            &headline.children,
+            mode,
+            filters,
+            value,
+            named,
+            ungrouped,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const SAMPLE: &str = r#"#+TITLE: Grouping
+#+CATEGORY: Home
+
+* Work
+:PROPERTIES:
+:agenda-group: Office
+:END:
+** TODO Write report
+** TODO Call client
+:PROPERTIES:
+:AGENDA-GROUP: Phone
+:END:
+* TODO Water plants
+* Errands
+:PROPERTIES:
+:CATEGORY: Shopping
+:END:
+** TODO Buy milk
+"#;
+
+    fn names(groups: &[HeadlineGroup]) -> Vec<Option<&str>> {
+        groups.iter().map(|g| g.name.as_deref()).collect()
+    }
+
+    #[test]
+    fn test_agenda_group_inheritance() {
+        let doc = parse_org_document(SAMPLE, Some("grouping.org")).unwrap();
+
+        let report = doc.find_headline("1.1").unwrap();
+        assert_eq!(report.get_agenda_group(&doc), Some("Office"));
+
+        let call = doc.find_headline("1.2").unwrap();
+        assert_eq!(call.get_agenda_group(&doc), Some("Phone"));
+
+        let plants = doc.find_headline("2").unwrap();
+        assert_eq!(plants.get_agenda_group(&doc), None);
+
+        let doc = parse_org_document(
+            "* TODO Padded\n:PROPERTIES:\n:AGENDA-GROUP:  Work \n:END:\n",
+            Some("padded.org"),
+        )
+        .unwrap();
+        assert_eq!(doc.headlines[0].get_agenda_group(&doc), Some("Work"));
+    }
+
+    #[test]
+    fn test_group_tasks_by_agenda_group() {
+        let doc = parse_org_document(SAMPLE, Some("grouping.org")).unwrap();
+        let groups = group_tasks(&[&doc], GroupingMode::AgendaGroup, &[]);
+
+        assert_eq!(names(&groups), vec![Some("Office"), Some("Phone"), None]);
+        assert_eq!(groups[0].headlines[0].headline_id, "1.1");
+        assert_eq!(groups[1].headlines[0].headline_id, "1.2");
+        assert_eq!(groups[2].headlines.len(), 2);
+    }
+
+    #[test]
+    fn test_group_tasks_by_category() {
+        let doc = parse_org_document(SAMPLE, Some("grouping.org")).unwrap();
+        let groups = group_tasks(&[&doc], GroupingMode::Category, &[]);
+
+        assert_eq!(names(&groups), vec![Some("Home"), Some("Shopping")]);
+        assert_eq!(groups[0].headlines.len(), 3);
+        assert_eq!(groups[1].headlines[0].headline_id, "3.1");
+
+        let mut missing = doc.clone();
+        missing.missing = true;
+        let archive = parse_org_document(SAMPLE, Some("grouping.org_archive")).unwrap();
+        assert!(group_tasks(&[&missing, &archive], GroupingMode::Category, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_group_tasks_with_property_filter() {
+        let doc = parse_org_document(SAMPLE, Some("grouping.org")).unwrap();
+        let filter = PropertyFilter {
+            property: "category".to_string(),
+            values: vec!["Shopping".to_string()],
+        };
+        let groups = group_tasks(&[&doc], GroupingMode::AgendaGroup, &[filter]);
+
+        assert_eq!(names(&groups), vec![None]);
+        assert_eq!(groups[0].headlines[0].headline_id, "3.1");
+    }
+}
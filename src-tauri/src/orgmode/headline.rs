@@ -1,4 +1,5 @@
 use crate::orgmode::document::OrgDocument;
+use crate::orgmode::grouping::AGENDA_GROUP_PROPERTY;
 use crate::orgmode::timestamp::OrgTimestamp;
 use crate::orgmode::title::OrgTitle;
 use crate::orgmode::todo::TodoConfiguration;
@@ -133,6 +134,14 @@ impl OrgHeadline {
         document.category.clone()
     }
 
+    // Get effective agenda group (from this headline or the nearest ancestor)
+    pub fn get_agenda_group<'a>(&self, document: &'a OrgDocument) -> Option<&'a str> {
+        document
+            .get_inherited_property(&self.id, AGENDA_GROUP_PROPERTY)
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+    }
+
     // Get resolved TODO status with color and state information
     pub fn get_todo_status(&self, config: &TodoConfiguration) -> Option<TodoStatus> {
         if let Some(keyword) = &self.title.todo_keyword {
@@ -272,6 +281,8 @@ impl OrgHeadline {
 mod tests {
     use super::*;
     use crate::orgmode::document::OrgDocument;
+    use crate::orgmode::flags::DocumentFlags;
+    use crate::orgmode::startup::StartupOptions;
     use crate::orgmode::title::OrgTitle;
     use chrono::Utc;
     use std::collections::HashMap;
@@ -329,9 +340,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "test.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "DocumentCategory".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Create headline with no category property
@@ -419,9 +437,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "test.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Create parent headline
@@ -491,9 +516,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "test.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Create top-level headlines
@@ -0,0 +1,112 @@
+use crate::orgmode::completed::completion_time_in;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::logbook::parse_clock_entries;
+use crate::orgmode::source::{flatten_headlines, headline_sections};
+use crate::orgmode::todo::TodoConfiguration;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Activity on one day
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct HeatmapDay {
+    pub date: String, // YYYY-MM-DD
+    pub completed: u32,
+    pub clocked_minutes: u32,
+}
+
+/// Daily activity over a year, for a contribution-graph style heatmap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ActivityHeatmap {
+    pub year: i32,
+    pub days: Vec<HeatmapDay>, // Every day of the year, January 1st first
+    pub max_completed: u32,    // Busiest day, for scaling colors
+    pub max_clocked_minutes: u32,
+    pub total_completed: u32,
+    pub total_clocked_minutes: u32,
+}
+
+// The entry for a date, if it falls in the year
+fn day_mut(days: &mut [HeatmapDay], year: i32, date: NaiveDate) -> Option<&mut HeatmapDay> {
+    if date.year() != year {
+        return None;
+    }
+    days.get_mut(date.ordinal0() as usize)
+}
+
+/// Count tasks closed and minutes clocked on each day of `year`
+///
+/// Archived documents count, since finished work usually ends up there. Clock time goes
+/// to the day the clock started. Documents need their full content for logbook data,
+/// so reload evicted ones first.
+pub fn activity_heatmap(documents: &[&OrgDocument], year: i32) -> ActivityHeatmap {
+    let mut days: Vec<HeatmapDay> = (1..=366)
+        .filter_map(|ordinal| NaiveDate::from_yo_opt(year, ordinal))
+        .map(|date| HeatmapDay {
+            date: date.format("%Y-%m-%d").to_string(),
+            ..HeatmapDay::default()
+        })
+        .collect();
+
+    for document in documents.iter().filter(|document| !document.missing) {
+        let config = document
+            .todo_config
+            .clone()
+            .unwrap_or_else(TodoConfiguration::default);
+        let sections = headline_sections(document);
+
+        for headline in flatten_headlines(&document.headlines) {
+            let section = sections.get(&headline.id).map_or("", String::as_str);
+            if let Some(closed) = completion_time_in(headline, &config, section) {
+                if let Some(day) = day_mut(&mut days, year, closed.to_naive_date()) {
+                    day.completed += 1;
+                }
+            }
+        }
+        for clock in parse_clock_entries(&document.content) {
+            if let Some(day) = day_mut(&mut days, year, clock.start.to_naive_date()) {
+                day.clocked_minutes += clock.minutes();
+            }
+        }
+    }
+
+    ActivityHeatmap {
+        year,
+        max_completed: days.iter().map(|day| day.completed).max().unwrap_or(0),
+        max_clocked_minutes: days
+            .iter()
+            .map(|day| day.clocked_minutes)
+            .max()
+            .unwrap_or(0),
+        total_completed: days.iter().map(|day| day.completed).sum(),
+        total_clocked_minutes: days.iter().map(|day| day.clocked_minutes).sum(),
+        days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_activity_heatmap() {
+        let doc = parse_org_document(
+            "* DONE Write report\nCLOSED: [2024-03-01 Fri 17:00]\n:LOGBOOK:\nCLOCK: [2024-03-01 Fri 09:00]--[2024-03-01 Fri 11:00] =>  2:00\nCLOCK: [2024-02-29 Thu 14:00]--[2024-02-29 Thu 14:30] =>  0:30\n:END:\n* DONE Old task\n:LOGBOOK:\n- State \"DONE\"       from \"TODO\"       [2023-12-31 Sun 10:00]\n:END:\n* DONE Review\n:LOGBOOK:\n- State \"DONE\"       from \"TODO\"       [2024-03-01 Fri 18:00]\n:END:\n",
+            Some("work.org"),
+        )
+        .unwrap();
+        let heatmap = activity_heatmap(&[&doc], 2024);
+
+        // 2024 is a leap year
+        assert_eq!(heatmap.days.len(), 366);
+        let march_first = &heatmap.days[60];
+        assert_eq!(march_first.date, "2024-03-01");
+        assert_eq!(march_first.completed, 2);
+        assert_eq!(march_first.clocked_minutes, 120);
+        assert_eq!(heatmap.days[59].clocked_minutes, 30);
+        assert_eq!(heatmap.total_completed, 2);
+        assert_eq!(heatmap.max_clocked_minutes, 120);
+        assert_eq!(heatmap.total_clocked_minutes, 150);
+    }
+}
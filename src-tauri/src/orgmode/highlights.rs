@@ -0,0 +1,458 @@
+use crate::orgmode::source::headline_level;
+use crate::orgmode::subtree::insert_subtree;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+
+/// Property holding a highlight's id, so highlights already filed are not imported again
+pub const HIGHLIGHT_ID_PROPERTY: &str = "HIGHLIGHT_ID";
+
+// Longest highlight headline title, in characters; the full text is in the body
+const TITLE_CHARS: usize = 60;
+
+/// A highlight read from an export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    pub id: String,
+    pub text: String,
+    pub note: Option<String>,
+    pub source: String, // Title of the book or article
+    pub author: Option<String>,
+    pub location: Option<String>,
+    pub highlighted_at: Option<String>,
+    pub url: Option<String>,
+}
+
+/// What an import filed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HighlightImportSummary {
+    pub imported: u32,
+    pub duplicates: u32, // Highlights skipped because their id was already filed
+    pub sources: u32,    // Books and articles that got new highlights
+}
+
+#[derive(Deserialize)]
+struct ReadwiseExport {
+    results: Vec<ReadwiseBook>,
+}
+
+#[derive(Deserialize)]
+struct ReadwiseBook {
+    #[serde(default)]
+    title: String,
+    author: Option<String>,
+    #[serde(default)]
+    highlights: Vec<ReadwiseHighlight>,
+}
+
+#[derive(Deserialize)]
+struct ReadwiseHighlight {
+    id: serde_json::Value, // A number in the API, kept as written
+    text: String,
+    note: Option<String>,
+    location: Option<serde_json::Value>,
+    highlighted_at: Option<String>,
+    url: Option<String>,
+}
+
+// Non-empty, trimmed text
+fn present(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Stable id for highlights exported without one (FNV-1a of the source and text)
+fn derived_id(source: &str, text: &str) -> String {
+    let hash = source
+        .bytes()
+        .chain([0])
+        .chain(text.bytes())
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+fn parse_readwise_json(export: &str) -> Result<Vec<Highlight>, String> {
+    // The API export wraps the books in `results`; a saved page may be just the list
+    let books = match serde_json::from_str::<ReadwiseExport>(export) {
+        Ok(export) => export.results,
+        Err(_) => serde_json::from_str::<Vec<ReadwiseBook>>(export)
+            .map_err(|e| format!("Invalid Readwise export: {}", e))?,
+    };
+    let mut highlights = Vec::new();
+    for book in books {
+        for highlight in book.highlights {
+            highlights.push(Highlight {
+                id: json_text(&highlight.id),
+                text: highlight.text,
+                note: present(highlight.note),
+                source: book.title.clone(),
+                author: present(book.author.clone()),
+                location: highlight.location.as_ref().map(json_text),
+                highlighted_at: present(highlight.highlighted_at),
+                url: present(highlight.url),
+            });
+        }
+    }
+    Ok(highlights)
+}
+
+/// Split CSV text into records of fields, as RFC 4180 quotes them
+/// Quoted fields may contain commas, doubled quotes and line breaks
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let (mut record, mut field) = (Vec::new(), String::new());
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+        .into_iter()
+        .filter(|record| record.iter().any(|field| !field.trim().is_empty()))
+        .collect()
+}
+
+// Column names accepted for each field: Readwise's CSV export first, then generic ones
+const TEXT_COLUMNS: &[&str] = &["highlight", "text", "quote"];
+const SOURCE_COLUMNS: &[&str] = &["book title", "title", "source", "book"];
+const AUTHOR_COLUMNS: &[&str] = &["book author", "author"];
+const NOTE_COLUMNS: &[&str] = &["note", "notes", "comment"];
+const LOCATION_COLUMNS: &[&str] = &["location", "page"];
+const DATE_COLUMNS: &[&str] = &["highlighted at", "date", "created"];
+const ID_COLUMNS: &[&str] = &["id", "highlight id"];
+const URL_COLUMNS: &[&str] = &["url", "link"];
+
+fn parse_highlights_csv(export: &str) -> Result<Vec<Highlight>, String> {
+    let mut records = parse_csv(export).into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or_else(|| "The highlights export is empty".to_string())?
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|column| column == name))
+    };
+    let text_column = column(TEXT_COLUMNS)
+        .ok_or_else(|| "The highlights export has no Highlight or Text column".to_string())?;
+    let columns = [
+        column(SOURCE_COLUMNS),
+        column(AUTHOR_COLUMNS),
+        column(NOTE_COLUMNS),
+        column(LOCATION_COLUMNS),
+        column(DATE_COLUMNS),
+        column(ID_COLUMNS),
+        column(URL_COLUMNS),
+    ];
+
+    let mut highlights = Vec::new();
+    for record in records {
+        let field = |index: Option<usize>| present(index.and_then(|i| record.get(i)).cloned());
+        let [source, author, note, location, date, id, url] = columns.map(&field);
+        let Some(text) = field(Some(text_column)) else {
+            continue;
+        };
+        let source = source.unwrap_or_default();
+        highlights.push(Highlight {
+            id: id.unwrap_or_else(|| derived_id(&source, &text)),
+            text,
+            note,
+            source,
+            author,
+            location,
+            highlighted_at: date,
+            url,
+        });
+    }
+    Ok(highlights)
+}
+
+/// Read highlights from a Readwise JSON export or a CSV export with a header row
+/// CSV columns are matched by name, so Readwise's CSV export works as is. Highlights
+/// without an id get one derived from their source and text.
+pub fn parse_highlights(export: &str) -> Result<Vec<Highlight>, String> {
+    let trimmed = export.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        parse_readwise_json(trimmed)
+    } else {
+        parse_highlights_csv(export)
+    }
+}
+
+// Ids of the highlights already filed in a notes file
+fn filed_ids(content: &str) -> HashSet<String> {
+    let key = format!(":{}:", HIGHLIGHT_ID_PROPERTY);
+    content
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix(key.as_str()))
+        .filter_map(|value| value.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+// First line of the text, shortened to fit a headline
+fn highlight_title(text: &str) -> String {
+    let first = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let first = first.trim();
+    if first.chars().count() > TITLE_CHARS {
+        let short: String = first.chars().take(TITLE_CHARS).collect();
+        format!("{}…", short.trim_end())
+    } else {
+        first.to_string()
+    }
+}
+
+// Lines that would read as headlines are indented so they stay in the body
+fn body_line(line: &str) -> String {
+    if headline_level(line).is_some() {
+        format!(" {}", line)
+    } else {
+        line.to_string()
+    }
+}
+
+// Text on one line, with runs of whitespace and line breaks as single spaces
+fn one_line(text: &str) -> String {
+    text.split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// A highlight id as filed, without whitespace so it reads back as one word
+fn filed_id(id: &str) -> String {
+    id.split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Quoted text as a quote block, kept out of the outline
+// Lines starting with `#+` are escaped with a comma, as org does in blocks, so a quoted
+// `#+end_quote` cannot end the block early.
+pub(crate) fn quote_block(text: &str) -> String {
+    let mut block = "#+begin_quote\n".to_string();
+    for line in text.trim().lines() {
+        let escaped = line.trim_start().trim_start_matches(',').starts_with("#+");
+        if escaped {
+            block.push(',');
+        }
+        block.push_str(&body_line(line));
+        block.push('\n');
+    }
+    block.push_str("#+end_quote\n");
+    block
+}
+
+/// A highlight as a level 2 headline with its id and details in a property drawer and
+/// its text in a quote block
+pub fn highlight_entry(highlight: &Highlight) -> String {
+    let mut entry = format!("** {}\n:PROPERTIES:\n", highlight_title(&highlight.text));
+    entry.push_str(&format!(
+        ":{}: {}\n",
+        HIGHLIGHT_ID_PROPERTY,
+        filed_id(&highlight.id)
+    ));
+    for (key, value) in [
+        ("LOCATION", &highlight.location),
+        ("HIGHLIGHTED_AT", &highlight.highlighted_at),
+        ("URL", &highlight.url),
+    ] {
+        if let Some(value) = value {
+            entry.push_str(&format!(":{}: {}\n", key, one_line(value)));
+        }
+    }
+    entry.push_str(":END:\n");
+    entry.push_str(&quote_block(&highlight.text));
+    if let Some(note) = &highlight.note {
+        for line in note.lines() {
+            entry.push_str(&body_line(line));
+            entry.push('\n');
+        }
+    }
+    entry
+}
+
+// Line index of the top-level headline for a source, and the end of its subtree
+fn source_section(lines: &[&str], source: &str) -> Option<(usize, usize)> {
+    let start = lines
+        .iter()
+        .position(|line| headline_level(line) == Some(1) && line[1..].trim() == source)?;
+    let end = (start + 1..lines.len())
+        .find(|&index| headline_level(lines[index]) == Some(1))
+        .unwrap_or(lines.len());
+    Some((start, end))
+}
+
+/// File highlights under a top-level headline per book or article, creating the ones
+/// that are missing
+/// Highlights whose id is already in the file, or earlier in the import, are skipped
+pub fn file_highlights(
+    content: &str,
+    highlights: &[Highlight],
+) -> (String, HighlightImportSummary) {
+    let mut filed = filed_ids(content);
+    let mut content = content.to_string();
+    let mut summary = HighlightImportSummary {
+        imported: 0,
+        duplicates: 0,
+        sources: 0,
+    };
+    let mut sources: HashSet<String> = HashSet::new();
+
+    for highlight in highlights {
+        if !filed.insert(filed_id(&highlight.id)) {
+            summary.duplicates += 1;
+            continue;
+        }
+        let source = match one_line(&highlight.source) {
+            source if source.is_empty() => "Untitled".to_string(),
+            source => source,
+        };
+        let entry = highlight_entry(highlight);
+        let lines: Vec<&str> = content.lines().collect();
+        content = match source_section(&lines, &source) {
+            Some((_, end)) => insert_subtree(&content, end, &entry),
+            None => {
+                let mut section = format!("* {}\n", source);
+                if let Some(author) = &highlight.author {
+                    section.push_str(&format!(
+                        ":PROPERTIES:\n:AUTHOR: {}\n:END:\n",
+                        one_line(author)
+                    ));
+                }
+                section.push_str(&entry);
+                insert_subtree(&content, lines.len(), &section)
+            }
+        };
+        summary.imported += 1;
+        sources.insert(source);
+    }
+    summary.sources = sources.len() as u32;
+    (content, summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let records = parse_csv("a,b\r\n\"x, \"\"y\"\"\",\"two\nlines\"\n\n");
+        assert_eq!(
+            records,
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["x, \"y\"".to_string(), "two\nlines".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_highlights() {
+        let csv = "Highlight,Book Title,Book Author,Amazon Book ID,Note,Color,Tags,Location Type,Location,Highlighted at\n\"Stay hungry, stay foolish\",Speeches,Jobs,,,yellow,,location,12,2024-05-01 10:00:00+00:00\n";
+        let highlights = parse_highlights(csv).unwrap();
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(highlights[0].text, "Stay hungry, stay foolish");
+        assert_eq!(highlights[0].source, "Speeches");
+        assert_eq!(highlights[0].location.as_deref(), Some("12"));
+        // Readwise's CSV has no highlight id, so one is derived from the content
+        assert_eq!(
+            highlights[0].id,
+            derived_id("Speeches", "Stay hungry, stay foolish")
+        );
+
+        let json = r#"{"count":1,"results":[{"user_book_id":7,"title":"Walden","author":"Thoreau","highlights":[{"id":42,"text":"Simplify, simplify.","note":"","location":88,"highlighted_at":null,"url":null}]}]}"#;
+        let highlights = parse_highlights(json).unwrap();
+        assert_eq!(highlights[0].id, "42");
+        assert_eq!(highlights[0].author.as_deref(), Some("Thoreau"));
+        assert_eq!(highlights[0].note, None);
+
+        assert!(parse_highlights("Title,Author\nWalden,Thoreau\n").is_err());
+    }
+
+    fn highlight(id: &str, source: &str, text: &str) -> Highlight {
+        Highlight {
+            id: id.to_string(),
+            text: text.to_string(),
+            note: None,
+            source: source.to_string(),
+            author: None,
+            location: None,
+            highlighted_at: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_file_highlights() {
+        let content = "#+TITLE: Reading\n* Walden\n** First\n:PROPERTIES:\n:HIGHLIGHT_ID: 1\n:END:\n* Other\n";
+        let highlights = vec![
+            highlight("1", "Walden", "Already filed"),
+            highlight("2", "Walden", "Simplify.\n* not a headline"),
+            highlight("3", "Essays", "Trust thyself."),
+            highlight("3", "Essays", "Trust thyself."),
+        ];
+        let (content, summary) = file_highlights(content, &highlights);
+        assert_eq!(
+            summary,
+            HighlightImportSummary {
+                imported: 2,
+                duplicates: 2,
+                sources: 2
+            }
+        );
+        assert_eq!(
+            content,
+            "#+TITLE: Reading\n* Walden\n** First\n:PROPERTIES:\n:HIGHLIGHT_ID: 1\n:END:\n** Simplify.\n:PROPERTIES:\n:HIGHLIGHT_ID: 2\n:END:\n#+begin_quote\nSimplify.\n * not a headline\n#+end_quote\n* Other\n* Essays\n** Trust thyself.\n:PROPERTIES:\n:HIGHLIGHT_ID: 3\n:END:\n#+begin_quote\nTrust thyself.\n#+end_quote\n"
+        );
+
+        // Importing the same export again adds nothing
+        let (again, summary) = file_highlights(&content, &highlights);
+        assert_eq!(again, content);
+        assert_eq!(summary.imported, 0);
+
+        // Line breaks stay out of titles and properties, and quoted block lines are escaped
+        let mut injected = highlight("4 5", "Essays\n* Injected", "#+end_quote\n* Also\n,#+x");
+        injected.author = Some("Emerson\r\n:END:".to_string());
+        let (content, summary) = file_highlights("", &[injected.clone()]);
+        assert_eq!(
+            content,
+            "* Essays * Injected\n:PROPERTIES:\n:AUTHOR: Emerson :END:\n:END:\n** #+end_quote\n:PROPERTIES:\n:HIGHLIGHT_ID: 4-5\n:END:\n#+begin_quote\n,#+end_quote\n * Also\n,,#+x\n#+end_quote\n"
+        );
+        assert_eq!(summary.imported, 1);
+        assert_eq!(file_highlights(&content, &[injected]).1.duplicates, 1);
+    }
+}
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Longest a hook may be allowed to run, in seconds
+pub const MAX_HOOK_TIMEOUT_SECONDS: u32 = 300;
+
+// How often a running hook is checked for having finished
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Something that happened in the app that a hook can run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum HookEvent {
+    TaskDone,    // A task was switched to a closed keyword
+    Capture,     // Text was captured to a file
+    FileChanged, // A monitored file was changed outside the app
+}
+
+impl HookEvent {
+    /// Name passed to hooks in `ORGX_EVENT`
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::TaskDone => "task_done",
+            HookEvent::Capture => "capture",
+            HookEvent::FileChanged => "file_changed",
+        }
+    }
+}
+
+/// Shell commands to run on app events
+/// Commands get the event's details as `ORGX_*` environment variables, never spliced
+/// into the command line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HookConfig {
+    pub on_task_done: Option<String>,
+    pub on_capture: Option<String>,
+    pub on_file_changed: Option<String>,
+    pub timeout_seconds: u32, // Hooks still running after this are killed
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            on_task_done: None,
+            on_capture: None,
+            on_file_changed: None,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+impl HookConfig {
+    /// Check the timeout
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_HOOK_TIMEOUT_SECONDS).contains(&self.timeout_seconds) {
+            return Err(format!(
+                "Hook timeout must be between 1 and {} seconds",
+                MAX_HOOK_TIMEOUT_SECONDS
+            ));
+        }
+        Ok(())
+    }
+
+    /// The command to run on an event, if one is set
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        let command = match event {
+            HookEvent::TaskDone => &self.on_task_done,
+            HookEvent::Capture => &self.on_capture,
+            HookEvent::FileChanged => &self.on_file_changed,
+        };
+        command
+            .as_deref()
+            .map(str::trim)
+            .filter(|command| !command.is_empty())
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// Run a hook command through the shell with `ORGX_EVENT` and the given variables set,
+/// waiting for it to finish
+/// Blocks the calling thread, so run it off the async runtime. A hook that outlives
+/// `timeout` is killed; it and hooks that exit with an error are reported as errors.
+pub fn run_hook(
+    command: &str,
+    event: HookEvent,
+    variables: &[(String, String)],
+    timeout: Duration,
+) -> Result<(), String> {
+    let mut child = shell_command(command)
+        .env("ORGX_EVENT", event.name())
+        .envs(variables.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {} hook: {}", event.name(), e))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                return Err(format!("{} hook failed: {}", event.name(), status));
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                // Reap the killed hook so it does not linger as a zombie
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} hook timed out after {} seconds",
+                    event.name(),
+                    timeout.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(HOOK_POLL_INTERVAL),
+            Err(e) => {
+                return Err(format!("Failed to wait for {} hook: {}", event.name(), e));
+            }
+        }
+    }
+}
+
+/// Run the hook for an event, if one is set, on the blocking thread pool
+/// Failures are logged; the event itself is never held up by its hook
+pub fn spawn_hook(config: &HookConfig, event: HookEvent, variables: Vec<(String, String)>) {
+    let Some(command) = config.command(event).map(str::to_string) else {
+        return;
+    };
+    let timeout = Duration::from_secs(u64::from(config.timeout_seconds));
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = run_hook(&command, event, &variables, timeout) {
+            eprintln!("{}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("event.txt");
+        let variables = vec![
+            ("ORGX_TITLE".to_string(), "Pay rent; rm -rf /".to_string()),
+            ("OUTPUT".to_string(), output.display().to_string()),
+        ];
+        run_hook(
+            "printf '%s %s' \"$ORGX_EVENT\" \"$ORGX_TITLE\" > \"$OUTPUT\"",
+            HookEvent::TaskDone,
+            &variables,
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        // Variables reach the hook as plain values
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "task_done Pay rent; rm -rf /"
+        );
+
+        assert!(run_hook("exit 3", HookEvent::Capture, &[], Duration::from_secs(5)).is_err());
+
+        let started = Instant::now();
+        let timed_out = run_hook(
+            "sleep 5",
+            HookEvent::FileChanged,
+            &[],
+            Duration::from_millis(200),
+        );
+        assert!(timed_out.unwrap_err().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_hook_config() {
+        let config = HookConfig {
+            on_capture: Some("  ".to_string()),
+            on_task_done: Some("notify-send done".to_string()),
+            ..HookConfig::default()
+        };
+        assert_eq!(config.command(HookEvent::Capture), None);
+        assert_eq!(
+            config.command(HookEvent::TaskDone),
+            Some("notify-send done")
+        );
+        assert!(config.validate().is_ok());
+        let forever = HookConfig {
+            timeout_seconds: 0,
+            ..HookConfig::default()
+        };
+        assert!(forever.validate().is_err());
+    }
+}
@@ -0,0 +1,432 @@
+use crate::orgmode::markup::{
+    parse_text_runs, split_blocks, ContentBlock, RunKind, RunStyle, TextRun,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// What rendered HTML may contain, so untrusted org files can be shown safely
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct HtmlSanitizeConfig {
+    /// Schemes allowed in link targets; links with other schemes are shown as text
+    pub allowed_link_schemes: Vec<String>,
+    /// Include `#+BEGIN_EXPORT html` blocks, filtered through `allowed_tags`
+    pub allow_raw_html: bool,
+    /// Tags kept in raw HTML; all others are dropped
+    pub allowed_tags: Vec<String>,
+}
+
+impl Default for HtmlSanitizeConfig {
+    fn default() -> Self {
+        Self {
+            allowed_link_schemes: ["http", "https", "mailto", "file", "id"]
+                .map(String::from)
+                .to_vec(),
+            allow_raw_html: false,
+            allowed_tags: [
+                "a",
+                "b",
+                "blockquote",
+                "br",
+                "code",
+                "del",
+                "em",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "hr",
+                "i",
+                "img",
+                "li",
+                "ol",
+                "p",
+                "pre",
+                "span",
+                "strong",
+                "sub",
+                "sup",
+                "table",
+                "tbody",
+                "td",
+                "th",
+                "thead",
+                "tr",
+                "u",
+                "ul",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+// Elements whose content is dropped along with the tags
+const DROPPED_ELEMENTS: &[&str] = &["script", "style", "iframe", "object", "embed", "template"];
+
+// Attributes kept on allowed tags; URLs are checked against the link schemes
+const ALLOWED_ATTRIBUTES: &[&str] = &["href", "src", "alt", "title", "class", "colspan", "rowspan"];
+
+// Named character references decoded in attribute values; others are kept as written
+const NAMED_REFERENCES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("colon", ':'),
+    ("sol", '/'),
+    ("quest", '?'),
+    ("num", '#'),
+    ("period", '.'),
+    ("Tab", '\t'),
+    ("NewLine", '\n'),
+];
+
+// Decode the character references in a raw attribute value, so URLs are checked as the
+// browser will read them; numeric references may omit the `;`, as browsers allow
+fn decode_references(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let reference = &rest[1..];
+        let numeric =
+            reference
+                .strip_prefix('#')
+                .map(|number| match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => (hex, 16, 2),
+                    None => (number, 10, 1),
+                });
+        let found = match numeric {
+            Some((digits, radix, prefix)) => {
+                let length = digits
+                    .find(|c: char| !c.is_digit(radix))
+                    .unwrap_or(digits.len());
+                u32::from_str_radix(&digits[..length], radix)
+                    .ok()
+                    .map(|code| char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .map(|c| {
+                        let semicolon = digits[length..].starts_with(';') as usize;
+                        (c, 1 + prefix + length + semicolon)
+                    })
+            }
+            None => NAMED_REFERENCES.iter().find_map(|(name, c)| {
+                reference
+                    .strip_prefix(name)
+                    .filter(|after| after.starts_with(';'))
+                    .map(|_| (*c, name.len() + 2))
+            }),
+        };
+        match found {
+            Some((c, length)) => {
+                decoded.push(c);
+                rest = &rest[length..];
+            }
+            None => {
+                decoded.push('&');
+                rest = reference;
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Escape text for use in HTML content or attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl HtmlSanitizeConfig {
+    /// Whether a link target may be used as a URL
+    /// Targets without a scheme (relative paths, `#anchors`) are allowed;
+    /// anything that looks like a scheme must be on the allowlist
+    /// Character references are decoded and whitespace and control characters removed first,
+    /// as browsers do, so `javascript&colon;` or `java\tscript:` are not mistaken for paths.
+    pub fn is_link_allowed(&self, target: &str) -> bool {
+        let target: String = decode_references(target)
+            .chars()
+            .filter(|c| !c.is_whitespace() && !c.is_control())
+            .collect();
+        let target = target.as_str();
+        match target.find([':', '/', '?']) {
+            Some(end) if target[end..].starts_with(':') => {
+                let scheme = &target[..end];
+                self.allowed_link_schemes
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(scheme))
+            }
+            _ => !target.starts_with("//"),
+        }
+    }
+
+    fn is_tag_allowed(&self, tag: &str) -> bool {
+        self.allowed_tags
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Render styled runs as HTML
+pub fn render_runs_html(runs: &[TextRun], config: &HtmlSanitizeConfig) -> String {
+    let mut html = String::new();
+
+    for run in runs {
+        let (open, close): (Vec<&str>, Vec<&str>) = run
+            .styles
+            .iter()
+            .map(|style| match style {
+                RunStyle::Bold => ("<strong>", "</strong>"),
+                RunStyle::Italic => ("<em>", "</em>"),
+                RunStyle::Underline => ("<u>", "</u>"),
+                RunStyle::Verbatim | RunStyle::Code => ("<code>", "</code>"),
+                RunStyle::Strike => ("<del>", "</del>"),
+            })
+            .unzip();
+        open.iter().for_each(|tag| html.push_str(tag));
+
+        let text = escape_html(&run.text);
+        match (run.kind, run.target.as_deref()) {
+            (RunKind::Link, Some(target)) if config.is_link_allowed(target) => {
+                html.push_str(&format!("<a href=\"{}\">{}</a>", escape_html(target), text));
+            }
+            (RunKind::Latex, Some(latex)) => {
+                html.push_str(&format!(
+                    "<span class=\"latex\" data-latex=\"{}\">{}</span>",
+                    escape_html(latex),
+                    text
+                ));
+            }
+            _ => html.push_str(&text.replace('\n', "<br>\n")),
+        }
+
+        close.iter().rev().for_each(|tag| html.push_str(tag));
+    }
+
+    html
+}
+
+/// Filter raw HTML down to allowed tags and attributes
+/// Scripts, styles, and embedded frames are removed with their content,
+/// event handler attributes are dropped, and URLs must use an allowed scheme
+pub fn sanitize_html(html: &str, config: &HtmlSanitizeConfig) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('>') else {
+            output.push_str(&escape_html(&rest[start..]));
+            return output;
+        };
+        let tag = &after[..end];
+        rest = &after[end + 1..];
+
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue; // Comments, doctypes, and processing instructions
+        }
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if name.is_empty() {
+            // A stray `<` in text, not a tag
+            output.push_str("&lt;");
+            rest = after;
+            continue;
+        }
+
+        if !closing && DROPPED_ELEMENTS.contains(&name.as_str()) {
+            let end_tag = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&end_tag) {
+                Some(position) => {
+                    let after_end = &rest[position..];
+                    after_end
+                        .find('>')
+                        .map_or("", |close| &after_end[close + 1..])
+                }
+                None => "",
+            };
+            continue;
+        }
+        if !config.is_tag_allowed(&name) {
+            continue;
+        }
+
+        if closing {
+            output.push_str(&format!("</{}>", name));
+        } else {
+            output.push('<');
+            output.push_str(&name);
+            for (attribute, value) in parse_attributes(&tag[name.len()..]) {
+                // Written back fully escaped, so the browser reads exactly the checked value
+                let value = decode_references(&value);
+                let is_url = matches!(attribute.as_str(), "href" | "src");
+                if ALLOWED_ATTRIBUTES.contains(&attribute.as_str())
+                    && (!is_url || config.is_link_allowed(&value))
+                {
+                    output.push_str(&format!(" {}=\"{}\"", attribute, escape_html(&value)));
+                }
+            }
+            output.push('>');
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+// Parse `name="value"`, `name='value'`, `name=value`, and bare `name` attributes
+fn parse_attributes(text: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut chars = text.trim_end_matches('/').chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == '/').is_some() {}
+        let name: String =
+            std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace() && *c != '=' && *c != '/'))
+                .collect();
+        if name.is_empty() {
+            break;
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            match chars.next_if(|c| *c == '"' || *c == '\'') {
+                Some(quote) => value.extend(std::iter::from_fn(|| chars.next_if(|c| *c != quote))),
+                None => value.extend(std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace()))),
+            }
+            chars.next_if(|c| *c == '"' || *c == '\'');
+        }
+        attributes.push((name.to_ascii_lowercase(), value));
+    }
+
+    attributes
+}
+
+/// Render org body text as sanitized HTML
+pub fn render_content_html(content: &str, config: &HtmlSanitizeConfig) -> String {
+    let mut html = String::new();
+
+    for block in split_blocks(content) {
+        match block {
+            ContentBlock::Paragraph(text) => {
+                let runs = parse_text_runs(&text);
+                html.push_str(&format!("<p>{}</p>\n", render_runs_html(&runs, config)));
+            }
+            ContentBlock::Block {
+                kind,
+                parameters,
+                body,
+            } => match kind.as_str() {
+                "export" if parameters.eq_ignore_ascii_case("html") => {
+                    if config.allow_raw_html {
+                        html.push_str(&sanitize_html(&body, config));
+                        html.push('\n');
+                    }
+                }
+                "export" => {} // Other export backends have nothing to show
+                "quote" => {
+                    let runs = parse_text_runs(&body);
+                    html.push_str(&format!(
+                        "<blockquote>{}</blockquote>\n",
+                        render_runs_html(&runs, config)
+                    ));
+                }
+                _ => {
+                    let language = parameters.split_whitespace().next().unwrap_or_default();
+                    html.push_str(&format!(
+                        "<pre><code class=\"{}\">{}</code></pre>\n",
+                        escape_html(language),
+                        escape_html(&body)
+                    ));
+                }
+            },
+        }
+    }
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_content_html() {
+        let config = HtmlSanitizeConfig::default();
+        let html = render_content_html(
+            "Hello *<world>* [[javascript:alert(1)][click]] [[https://orgmode.org][org]]\n\n#+BEGIN_SRC rust\nlet x = \"<b>\";\n#+END_SRC\n#+BEGIN_EXPORT html\n<script>alert(1)</script>\n#+END_EXPORT",
+            &config,
+        );
+        assert_eq!(
+            html,
+            "<p>Hello <strong>&lt;world&gt;</strong> click <a href=\"https://orgmode.org\">org</a></p>\n\
+             <pre><code class=\"rust\">let x = &quot;&lt;b&gt;&quot;;</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_html() {
+        let config = HtmlSanitizeConfig::default();
+        assert_eq!(
+            sanitize_html(
+                "<p onclick=\"steal()\" class='note'>Hi <SCRIPT>evil()</script><a href=\"javascript:x\">x</a><img src=pic.png alt=\"A &amp; B\"></p><iframe src=x></iframe>",
+                &config
+            ),
+            "<p class=\"note\">Hi <a>x</a><img src=\"pic.png\" alt=\"A &amp; B\"></p>"
+        );
+        assert_eq!(
+            sanitize_html("<blink>text</blink><!-- c -->", &config),
+            "text"
+        );
+        assert_eq!(sanitize_html("1 < 2 > 0", &config), "1 &lt; 2 > 0");
+
+        assert!(!config.is_link_allowed("java&#115;cript:alert(1)"));
+        for href in [
+            "javascript&colon;alert(1)",
+            "javascript&#58;alert(1)",
+            "javascript&#x3a;alert(1)",
+            "javascript&#0058alert(1)",
+            "java\tscript:alert(1)",
+            "java&Tab;script:alert(1)",
+            " \u{1}javascript:alert(1)",
+        ] {
+            assert!(!config.is_link_allowed(href), "{}", href);
+            assert_eq!(
+                sanitize_html(&format!("<a href=\"{}\">x</a>", href), &config),
+                "<a>x</a>",
+                "{}",
+                href
+            );
+        }
+        assert_eq!(
+            sanitize_html(
+                "<a href=\"https&#58;//orgmode.org/?a=1&amp;b=2\">x</a>",
+                &config
+            ),
+            "<a href=\"https://orgmode.org/?a=1&amp;b=2\">x</a>"
+        );
+        assert!(!config.is_link_allowed("//evil.example"));
+        assert!(config.is_link_allowed("notes/today.org#top"));
+        assert!(config.is_link_allowed("MAILTO:me@example.com"));
+    }
+}
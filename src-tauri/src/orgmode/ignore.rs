@@ -0,0 +1,159 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::tags::is_valid_tag;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+
+/// Views that leave out headlines with ignored tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum IgnoreView {
+    Agenda, // The agenda, its exports and the digest
+    NextActions,
+    Search, // Full-text searches
+}
+
+/// Tags whose headlines, and everything below them, are left out of views
+/// A view with its own list uses that instead of `tags`; an empty list shows everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct IgnoredTags {
+    pub tags: Vec<String>,
+    pub agenda: Option<Vec<String>>,
+    pub next_actions: Option<Vec<String>>,
+    pub search: Option<Vec<String>>,
+}
+
+impl IgnoredTags {
+    pub fn validate(&self) -> Result<(), String> {
+        let lists = [&self.agenda, &self.next_actions, &self.search];
+        let tags = std::iter::once(&self.tags).chain(lists.into_iter().flatten());
+        match tags.flatten().find(|tag| !is_valid_tag(tag)) {
+            Some(tag) => Err(format!("Invalid tag: '{}'", tag)),
+            None => Ok(()),
+        }
+    }
+
+    /// The tags ignored in a view
+    pub fn for_view(&self, view: IgnoreView) -> &[String] {
+        let own = match view {
+            IgnoreView::Agenda => &self.agenda,
+            IgnoreView::NextActions => &self.next_actions,
+            IgnoreView::Search => &self.search,
+        };
+        own.as_deref().unwrap_or(&self.tags)
+    }
+}
+
+/// Whether a headline or one of its ancestors has one of `tags`, or the file does
+/// through `#+FILETAGS:`
+pub fn is_ignored(document: &OrgDocument, headline_id: &str, tags: &[String]) -> bool {
+    if tags.is_empty() {
+        return false;
+    }
+    let ignored = |tag: &str| tags.iter().any(|ignored| ignored == tag);
+    document.filetags.iter().any(|tag| ignored(tag))
+        || document.headline_path(headline_id).is_some_and(|path| {
+            path.iter()
+                .any(|headline| headline.title.tags.iter().any(|tag| ignored(tag)))
+        })
+}
+
+// Walk the tree carrying whether an ancestor is ignored down to children
+fn collect_ignored(
+    headlines: &[OrgHeadline],
+    inherited: bool,
+    ignored: &impl Fn(&str) -> bool,
+    ids: &mut HashSet<String>,
+) {
+    for headline in headlines {
+        let is_ignored = inherited || headline.title.tags.iter().any(|tag| ignored(tag));
+        if is_ignored {
+            ids.insert(headline.id.clone());
+        }
+        collect_ignored(&headline.children, is_ignored, ignored, ids);
+    }
+}
+
+/// The ids of a document's headlines that are ignored for `tags`, in one pass over the tree
+pub fn ignored_ids(document: &OrgDocument, tags: &[String]) -> HashSet<String> {
+    let mut ids = HashSet::new();
+    if tags.is_empty() {
+        return ids;
+    }
+    let ignored = |tag: &str| tags.iter().any(|ignored| ignored == tag);
+    let file_ignored = document.filetags.iter().any(|tag| ignored(tag));
+    collect_ignored(&document.headlines, file_ignored, &ignored, &mut ids);
+    ids
+}
+
+/// Drop the items whose headline is ignored, looking documents up with `document` and
+/// reading each item's document and headline ids with `headline`
+/// Each document's ignored headlines are collected once, on its first item.
+pub fn retain_unignored<'a, T>(
+    items: &mut Vec<T>,
+    tags: &[String],
+    document: impl Fn(&str) -> Option<&'a OrgDocument>,
+    headline: impl Fn(&T) -> (&str, &str),
+) {
+    if tags.is_empty() {
+        return;
+    }
+    let mut ignored: HashMap<String, HashSet<String>> = HashMap::new();
+    items.retain(|item| {
+        let (document_id, headline_id) = headline(item);
+        if !ignored.contains_key(document_id) {
+            let ids = document(document_id)
+                .map(|document| ignored_ids(document, tags))
+                .unwrap_or_default();
+            ignored.insert(document_id.to_string(), ids);
+        }
+        !ignored[document_id].contains(headline_id)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_ignored_headlines() {
+        let doc = parse_org_document(
+            "* Projects\n** TODO Write report\n* Later :someday:\n** TODO Learn piano\n* TODO Taxes\n",
+            Some("todo.org"),
+        )
+        .unwrap();
+        let config = IgnoredTags {
+            tags: vec!["someday".to_string()],
+            search: Some(Vec::new()),
+            ..IgnoredTags::default()
+        };
+
+        // Tags are inherited, so everything below an ignored headline is ignored too
+        let mut ids = vec!["1", "1.1", "2", "2.1", "3"];
+        retain_unignored(
+            &mut ids,
+            config.for_view(IgnoreView::Agenda),
+            |id| (id == "todo.org").then_some(&doc),
+            |id| ("todo.org", id),
+        );
+        assert_eq!(ids, vec!["1", "1.1", "3"]);
+
+        // A view's own list replaces the global one
+        let (search, next_actions) = (
+            config.for_view(IgnoreView::Search),
+            config.for_view(IgnoreView::NextActions),
+        );
+        assert!(!is_ignored(&doc, "2.1", search));
+        assert!(is_ignored(&doc, "2.1", next_actions));
+        let ids = ignored_ids(&doc, next_actions);
+        assert_eq!(ids, HashSet::from(["2".to_string(), "2.1".to_string()]));
+
+        assert!(IgnoredTags {
+            agenda: Some(vec!["not a tag".to_string()]),
+            ..IgnoredTags::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::links::{extract_links, resolve_file};
+use crate::orgmode::source::source_lines;
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// URI scheme serving images to the frontend, e.g. `org-image://localhost/<encoded path>`,
+/// with `?size=<pixels>` for a thumbnail
+pub const IMAGE_PROTOCOL: &str = "org-image";
+
+/// A `file:` link to an image, to be shown inline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ImageLink {
+    pub document_id: String,
+    pub headline_id: Option<String>,
+    pub line: u32,    // 1-based line number
+    pub path: String, // Absolute path, resolved against the document's directory
+    pub description: Option<String>,
+    pub mime_type: String,
+    pub exists: bool,
+}
+
+/// MIME type of a supported image file, by extension
+pub fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "svg" => Some("image/svg+xml"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Find links to image files in a document
+pub fn find_image_links(document: &OrgDocument) -> Vec<ImageLink> {
+    let mut images = Vec::new();
+
+    for line in source_lines(document) {
+        for link in extract_links(line.text) {
+            let Some(file_path) = link.file_path() else {
+                continue;
+            };
+            let path = resolve_file(document, file_path);
+            let Some(mime_type) = image_mime_type(&path) else {
+                continue;
+            };
+
+            images.push(ImageLink {
+                document_id: document.id.clone(),
+                headline_id: line.headline_id.map(|id| id.to_string()),
+                line: line.number as u32,
+                exists: path.is_file(),
+                path: path.to_string_lossy().to_string(),
+                description: link.description.clone(),
+                mime_type: mime_type.to_string(),
+            });
+        }
+    }
+
+    images
+}
+
+/// Whether an image may be served: it must be a supported image type inside one of `roots`
+/// Paths are canonicalized first, so `..` and symlinks cannot escape the roots
+pub fn is_image_allowed(path: &Path, roots: &[PathBuf]) -> bool {
+    if image_mime_type(path).is_none() {
+        return false;
+    }
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| path.starts_with(root))
+}
+
+/// Largest thumbnail side that may be asked for, in pixels
+pub const MAX_THUMBNAIL_SIZE: u32 = 2048;
+
+/// A PNG thumbnail that fits in `size` by `size` pixels, keeping the aspect ratio
+/// Returns None for images already that small and for ones that don't decode, like SVG,
+/// which is served as is.
+pub fn thumbnail(bytes: &[u8], size: u32) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(bytes).ok()?;
+    if image.width() <= size && image.height() <= size {
+        return None;
+    }
+    let mut png = Vec::new();
+    image
+        .thumbnail(size, size)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// Decode a percent-encoded URI path, as produced by `encodeURIComponent`
+pub fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = input.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_image_links() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("chart.png"), b"png").unwrap();
+        let file_path = dir.path().join("notes.org");
+
+        let doc = parse_org_document(
+            "* Results\n[[file:chart.png]]\n[[./missing.SVG][Diagram]] [[file:notes.txt]]\n",
+            file_path.to_str(),
+        )
+        .unwrap();
+
+        let images = find_image_links(&doc);
+        assert_eq!(images.len(), 2);
+        assert!(images[0].exists);
+        assert_eq!(images[0].headline_id.as_deref(), Some("1"));
+        assert_eq!(images[0].mime_type, "image/png");
+        assert!(!images[1].exists);
+        assert_eq!(images[1].description.as_deref(), Some("Diagram"));
+
+        let roots = vec![dir.path().to_path_buf()];
+        assert!(is_image_allowed(&dir.path().join("chart.png"), &roots));
+        assert!(!is_image_allowed(&dir.path().join("notes.org"), &roots));
+        assert!(!is_image_allowed(&dir.path().join("../chart.png"), &roots));
+    }
+
+    #[test]
+    fn test_thumbnail() {
+        let mut png = Vec::new();
+        image::RgbImage::new(400, 200)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let small = image::load_from_memory(&thumbnail(&png, 100).unwrap()).unwrap();
+        assert_eq!((small.width(), small.height()), (100, 50));
+        assert_eq!(thumbnail(&png, 400), None);
+        assert_eq!(
+            thumbnail(b"<svg xmlns='http://www.w3.org/2000/svg'/>", 100),
+            None
+        );
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(
+            percent_decode("%2Fhome%2Fme%2Fmy%20notes%2F%E5%9B%B3.png").as_deref(),
+            Some("/home/me/my notes/図.png")
+        );
+        assert_eq!(percent_decode("%zz"), None);
+    }
+}
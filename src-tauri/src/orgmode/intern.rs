@@ -0,0 +1,102 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Pool of shared strings, so tags, TODO keywords, and property keys repeated
+/// across many headlines are stored once
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl StringInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the pooled copy of a string, adding it if needed
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(value) {
+            return existing.clone();
+        }
+        let value: Arc<str> = Arc::from(value);
+        self.strings.insert(value.clone());
+        value
+    }
+
+    /// Replace a document's tags, TODO keywords, and property keys with pooled copies
+    pub fn intern_document(&mut self, document: &mut OrgDocument) {
+        for headline in &mut document.headlines {
+            self.intern_headline(headline);
+        }
+    }
+
+    fn intern_headline(&mut self, headline: &mut OrgHeadline) {
+        let title = &mut headline.title;
+        for tag in &mut title.tags {
+            *tag = self.intern(tag);
+        }
+        if let Some(keyword) = &mut title.todo_keyword {
+            *keyword = self.intern(keyword);
+        }
+        title.properties = std::mem::take(&mut title.properties)
+            .into_iter()
+            .map(|(key, value)| (self.intern(&key), value))
+            .collect();
+
+        for child in &mut headline.children {
+            self.intern_headline(child);
+        }
+    }
+
+    /// Drop strings that no document uses any more
+    pub fn prune(&mut self) {
+        self.strings.retain(|value| Arc::strong_count(value) > 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_intern_document() {
+        let mut strings = StringInterner::new();
+        let mut first = parse_org_document(
+            "* TODO Write :work:\n:PROPERTIES:\n:EFFORT: 1:00\n:END:\n",
+            Some("a.org"),
+        )
+        .unwrap();
+        let mut second = parse_org_document(
+            "* TODO Review :work:\n** TODO Nested :work:\n",
+            Some("b.org"),
+        )
+        .unwrap();
+
+        strings.intern_document(&mut first);
+        strings.intern_document(&mut second);
+        // TODO, work, EFFORT
+        assert_eq!(strings.len(), 3);
+
+        let first_tag = &first.headlines[0].title.tags[0];
+        let nested_tag = &second.headlines[0].children[0].title.tags[0];
+        assert!(Arc::ptr_eq(first_tag, nested_tag));
+
+        drop(first);
+        strings.prune();
+        assert_eq!(strings.len(), 2);
+        drop(second);
+        strings.prune();
+        assert!(strings.is_empty());
+    }
+}
@@ -0,0 +1,538 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::flatten_headlines;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Event emitted with the fetched issues after a refresh
+pub const ISSUES_UPDATED_EVENT: &str = "issues-updated";
+
+/// File the last fetched issue states are kept in, in the app data directory
+pub const ISSUES_FILE_NAME: &str = "issues.json";
+
+/// Virtual properties filled from a headline's first issue link
+pub const ISSUE_PROPERTIES: [&str; 3] = ["ISSUE", "ISSUE_STATUS", "ISSUE_TITLE"];
+
+// Shortest time between automatic refreshes, to stay within API rate limits
+const MIN_REFRESH_MINUTES: u32 = 5;
+
+// Longest wait between retries while refreshes keep failing
+const MAX_RETRY_MINUTES: u64 = 6 * 60;
+
+// Longest a single issue request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+static GITHUB_ISSUE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"https://github\.com/([\w.-]+)/([\w.-]+)/(?:issues|pull)/(\d+)").unwrap()
+});
+
+static JIRA_ISSUE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"https?://([\w.-]+(?::\d+)?)/browse/([A-Z][A-Z0-9_]*-\d+)").unwrap());
+
+// Last fetched state of each issue, keyed by its canonical URL
+static ISSUE_CACHE: Lazy<RwLock<HashMap<String, IssueInfo>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Where an issue is tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+pub enum IssueProvider {
+    GitHub,
+    Jira,
+}
+
+/// An issue or pull request URL found in a headline
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IssueLink {
+    pub provider: IssueProvider,
+    pub key: String, // `owner/repo#12` or `PROJ-42`
+    pub url: String, // Canonical URL, without query or fragment
+}
+
+/// The fetched state of an issue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct IssueInfo {
+    pub provider: IssueProvider,
+    pub key: String,
+    pub url: String,
+    pub title: String,
+    pub status: String, // `open`, `closed` or `merged` on GitHub; the workflow status on Jira
+}
+
+/// Access to the issue trackers whose links are enriched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct IssueConfig {
+    pub enabled: bool,
+    pub github_token: Option<String>, // Needed for private repositories and higher rate limits
+    pub jira_base_url: Option<String>, // e.g. https://example.atlassian.net
+    pub jira_email: Option<String>,   // Jira Cloud login; None sends jira_token as a bearer token
+    pub jira_token: Option<String>,
+    pub refresh_minutes: u32,
+}
+
+impl Default for IssueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            github_token: None,
+            jira_base_url: None,
+            jira_email: None,
+            jira_token: None,
+            refresh_minutes: 30,
+        }
+    }
+}
+
+// Non-empty, trimmed setting
+fn setting(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+impl IssueConfig {
+    /// Check the refresh interval and the Jira site, which must use https since the token
+    /// is sent to it
+    pub fn validate(&self) -> Result<(), String> {
+        if self.refresh_minutes < MIN_REFRESH_MINUTES {
+            return Err(format!(
+                "Issues can be refreshed at most every {} minutes",
+                MIN_REFRESH_MINUTES
+            ));
+        }
+        if let Some(base) = setting(&self.jira_base_url) {
+            let host = base.strip_prefix("https://").unwrap_or_default();
+            if host.is_empty() || host.contains(char::is_whitespace) {
+                return Err(format!("Jira URL must start with https://: {}", base));
+            }
+        }
+        Ok(())
+    }
+
+    // The GitHub token, if one is set
+    pub(crate) fn github_token(&self) -> Option<&str> {
+        setting(&self.github_token)
+    }
+
+    // The configured Jira site, without a trailing slash
+    fn jira_base(&self) -> Option<&str> {
+        setting(&self.jira_base_url).map(|base| base.trim_end_matches('/'))
+    }
+}
+
+/// Find GitHub issue and pull request links and Jira issue links in text, in order
+pub fn find_issue_links(text: &str) -> Vec<IssueLink> {
+    let github = GITHUB_ISSUE.captures_iter(text).map(|captures| {
+        let start = captures.get(0).map_or(0, |whole| whole.start());
+        let link = IssueLink {
+            provider: IssueProvider::GitHub,
+            key: format!("{}/{}#{}", &captures[1], &captures[2], &captures[3]),
+            url: captures[0].to_string(),
+        };
+        (start, link)
+    });
+    let jira = JIRA_ISSUE.captures_iter(text).map(|captures| {
+        let start = captures.get(0).map_or(0, |whole| whole.start());
+        let link = IssueLink {
+            provider: IssueProvider::Jira,
+            key: captures[2].to_string(),
+            url: captures[0].to_string(),
+        };
+        (start, link)
+    });
+    let mut links: Vec<(usize, IssueLink)> = github.chain(jira).collect();
+    links.sort_by_key(|(start, _)| *start);
+    links.into_iter().map(|(_, link)| link).collect()
+}
+
+/// The first issue link in a headline's title, or else in its body
+pub fn headline_issue(headline: &OrgHeadline) -> Option<IssueLink> {
+    find_issue_links(&headline.title.raw)
+        .into_iter()
+        .chain(find_issue_links(&headline.content))
+        .next()
+}
+
+/// Every issue linked from a headline title or body, without duplicates
+/// Body links need the documents' content, so load evicted ones first.
+pub fn collect_issue_links(documents: &[&OrgDocument]) -> Vec<IssueLink> {
+    let mut links: Vec<IssueLink> = Vec::new();
+    for document in documents {
+        for headline in flatten_headlines(&document.headlines) {
+            for link in find_issue_links(&headline.title.raw)
+                .into_iter()
+                .chain(find_issue_links(&headline.content))
+            {
+                if !links.iter().any(|known| known.url == link.url) {
+                    links.push(link);
+                }
+            }
+        }
+    }
+    links
+}
+
+/// Value of an `ISSUE`, `ISSUE_STATUS` or `ISSUE_TITLE` virtual property
+/// Status and title come from the last refresh and are None until the issue is fetched
+pub fn issue_property(headline: &OrgHeadline, property: &str) -> Option<String> {
+    let link = headline_issue(headline)?;
+    if property == "ISSUE" {
+        return Some(link.key);
+    }
+    let cache = match ISSUE_CACHE.read() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let info = cache.get(&link.url)?;
+    match property {
+        "ISSUE_STATUS" => Some(info.status.clone()),
+        "ISSUE_TITLE" => Some(info.title.clone()),
+        _ => None,
+    }
+}
+
+/// Remember fetched issues for the virtual properties
+pub fn cache_issues(issues: &[IssueInfo]) {
+    let mut cache = match ISSUE_CACHE.write() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    for issue in issues {
+        cache.insert(issue.url.clone(), issue.clone());
+    }
+}
+
+/// Every fetched issue, for saving across restarts
+pub fn cached_issues() -> Vec<IssueInfo> {
+    let cache = match ISSUE_CACHE.read() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    cache.values().cloned().collect()
+}
+
+/// Load saved issue states, or none if the file does not exist yet
+pub fn load_issues(path: &Path) -> Result<Vec<IssueInfo>, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to read issues {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read issues {}: {}", path.display(), e)),
+    }
+}
+
+/// Save issue states, creating the parent directory if needed
+pub fn save_issues(path: &Path, issues: &[IssueInfo]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(issues)
+        .map_err(|e| format!("Failed to serialize issues: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write issues {}: {}", path.display(), e))
+}
+
+/// How long to wait before retrying after `failures` refreshes in a row failed
+/// The wait doubles from the shortest refresh interval, up to six hours.
+pub fn retry_delay(failures: u32) -> Duration {
+    let minutes = u64::from(MIN_REFRESH_MINUTES)
+        .saturating_mul(1u64 << failures.saturating_sub(1).min(16))
+        .min(MAX_RETRY_MINUTES);
+    Duration::from_secs(minutes * 60)
+}
+
+// Title and status of a GitHub issue or pull request from the REST API
+fn parse_github_issue(link: &IssueLink, body: &serde_json::Value) -> Result<IssueInfo, String> {
+    let title = body["title"]
+        .as_str()
+        .ok_or_else(|| format!("Unexpected response for {}", link.key))?;
+    let merged = !body["pull_request"]["merged_at"].is_null();
+    let status = if merged {
+        "merged"
+    } else {
+        body["state"].as_str().unwrap_or("unknown")
+    };
+    Ok(IssueInfo {
+        provider: link.provider,
+        key: link.key.clone(),
+        url: link.url.clone(),
+        title: title.to_string(),
+        status: status.to_string(),
+    })
+}
+
+// Summary and workflow status of a Jira issue from the REST API
+fn parse_jira_issue(link: &IssueLink, body: &serde_json::Value) -> Result<IssueInfo, String> {
+    let fields = &body["fields"];
+    let title = fields["summary"]
+        .as_str()
+        .ok_or_else(|| format!("Unexpected response for {}", link.key))?;
+    Ok(IssueInfo {
+        provider: link.provider,
+        key: link.key.clone(),
+        url: link.url.clone(),
+        title: title.to_string(),
+        status: fields["status"]["name"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+    })
+}
+
+// Standard base64 with padding, for Jira's basic authentication
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                output.push(ALPHABET[(bits >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+// Agent for tracker API requests
+pub(crate) fn api_agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("org-x")
+        .build()
+}
+
+// A GitHub REST API request for `path`, authorized when there is a token
+pub(crate) fn github_request(
+    agent: &ureq::Agent,
+    method: &str,
+    path: &str,
+    token: Option<&str>,
+) -> ureq::Request {
+    let request = agent
+        .request(method, &format!("https://api.github.com{}", path))
+        .set("Accept", "application/vnd.github+json");
+    match token {
+        Some(token) => request.set("Authorization", &format!("Bearer {}", token)),
+        None => request,
+    }
+}
+
+// The API request for an issue, or None if no tracker is configured for it
+fn issue_request(
+    agent: &ureq::Agent,
+    link: &IssueLink,
+    config: &IssueConfig,
+) -> Option<ureq::Request> {
+    match link.provider {
+        IssueProvider::GitHub => {
+            let (repository, number) = link.key.split_once('#')?;
+            Some(github_request(
+                agent,
+                "GET",
+                &format!("/repos/{}/issues/{}", repository, number),
+                config.github_token(),
+            ))
+        }
+        IssueProvider::Jira => {
+            // Tokens are only sent to the configured site
+            let base = config.jira_base()?;
+            if !link.url.starts_with(&format!("{}/", base)) {
+                return None;
+            }
+            let request = agent.get(&format!(
+                "{}/rest/api/2/issue/{}?fields=summary,status",
+                base, link.key
+            ));
+            let token = setting(&config.jira_token);
+            Some(match (setting(&config.jira_email), token) {
+                (Some(email), Some(token)) => request.set(
+                    "Authorization",
+                    &format!(
+                        "Basic {}",
+                        base64(format!("{}:{}", email, token).as_bytes())
+                    ),
+                ),
+                (None, Some(token)) => request.set("Authorization", &format!("Bearer {}", token)),
+                _ => request,
+            })
+        }
+    }
+}
+
+/// Fetch the title and status of issues from their trackers
+/// Blocks the calling thread. Issues that fail, or whose tracker is not configured, are
+/// logged and left out; if every request fails, the last error is returned.
+pub fn fetch_issues(links: &[IssueLink], config: &IssueConfig) -> Result<Vec<IssueInfo>, String> {
+    let agent = api_agent();
+    let mut issues = Vec::new();
+    let mut last_error = None;
+    for link in links {
+        let Some(request) = issue_request(&agent, link, config) else {
+            continue;
+        };
+        let fetched = request
+            .call()
+            .map_err(|e| e.to_string())
+            .and_then(|response| {
+                response
+                    .into_json::<serde_json::Value>()
+                    .map_err(|e| e.to_string())
+            })
+            .and_then(|body| match link.provider {
+                IssueProvider::GitHub => parse_github_issue(link, &body),
+                IssueProvider::Jira => parse_jira_issue(link, &body),
+            });
+        match fetched {
+            Ok(issue) => issues.push(issue),
+            Err(e) => {
+                eprintln!("Failed to fetch issue {}: {}", link.key, e);
+                last_error = Some(format!("Failed to fetch issue {}: {}", link.key, e));
+            }
+        }
+    }
+    match last_error {
+        Some(e) if issues.is_empty() => Err(e),
+        _ => Ok(issues),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_find_issue_links() {
+        let links = find_issue_links(
+            "Fix [[https://acme.atlassian.net/browse/OPS-7][OPS-7]] after https://github.com/dr-yst/org-x/pull/12#discussion",
+        );
+        let keys: Vec<(&str, &str)> = links
+            .iter()
+            .map(|link| (link.key.as_str(), link.url.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("OPS-7", "https://acme.atlassian.net/browse/OPS-7"),
+                ("dr-yst/org-x#12", "https://github.com/dr-yst/org-x/pull/12"),
+            ]
+        );
+        assert!(find_issue_links("https://github.com/dr-yst/org-x/blob/main/README.md").is_empty());
+    }
+
+    #[test]
+    fn test_issue_properties() {
+        let doc = parse_org_document(
+            "* TODO Ship release\nTracked in https://github.com/acme/app/issues/99\n* Notes\n",
+            Some("work.org"),
+        )
+        .unwrap();
+        let ship = &doc.headlines[0];
+        assert_eq!(
+            issue_property(ship, "ISSUE").as_deref(),
+            Some("acme/app#99")
+        );
+        assert_eq!(issue_property(&doc.headlines[1], "ISSUE"), None);
+
+        let link = headline_issue(ship).unwrap();
+        let body = serde_json::json!({
+            "title": "Release 2.0",
+            "state": "closed",
+            "pull_request": null
+        });
+        cache_issues(&[parse_github_issue(&link, &body).unwrap()]);
+        assert_eq!(
+            issue_property(ship, "ISSUE_STATUS").as_deref(),
+            Some("closed")
+        );
+        assert_eq!(
+            issue_property(ship, "ISSUE_TITLE").as_deref(),
+            Some("Release 2.0")
+        );
+    }
+
+    #[test]
+    fn test_parse_issues() {
+        let pull = IssueLink {
+            provider: IssueProvider::GitHub,
+            key: "acme/app#5".to_string(),
+            url: "https://github.com/acme/app/pull/5".to_string(),
+        };
+        let body = serde_json::json!({
+            "title": "Add export",
+            "state": "closed",
+            "pull_request": { "merged_at": "2024-06-01T10:00:00Z" }
+        });
+        assert_eq!(parse_github_issue(&pull, &body).unwrap().status, "merged");
+
+        let jira = IssueLink {
+            provider: IssueProvider::Jira,
+            key: "OPS-7".to_string(),
+            url: "https://acme.atlassian.net/browse/OPS-7".to_string(),
+        };
+        let body = serde_json::json!({
+            "fields": { "summary": "Rotate keys", "status": { "name": "In Review" } }
+        });
+        let issue = parse_jira_issue(&jira, &body).unwrap();
+        assert_eq!(
+            (issue.title.as_str(), issue.status.as_str()),
+            ("Rotate keys", "In Review")
+        );
+        assert!(parse_jira_issue(&jira, &serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_issue_config() {
+        let mut config = IssueConfig {
+            jira_base_url: Some("http://jira.example.com".to_string()),
+            ..IssueConfig::default()
+        };
+        assert!(config.validate().is_err());
+        config.jira_base_url = Some("https://jira.example.com/".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.jira_base(), Some("https://jira.example.com"));
+
+        assert_eq!(retry_delay(1), Duration::from_secs(5 * 60));
+        assert_eq!(retry_delay(3), Duration::from_secs(20 * 60));
+        assert_eq!(retry_delay(40), Duration::from_secs(6 * 60 * 60));
+    }
+
+    #[test]
+    fn test_saved_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join(ISSUES_FILE_NAME);
+        assert!(load_issues(&path).unwrap().is_empty());
+
+        let issue = IssueInfo {
+            provider: IssueProvider::Jira,
+            key: "OPS-7".to_string(),
+            url: "https://acme.atlassian.net/browse/OPS-7".to_string(),
+            title: "Rotate keys".to_string(),
+            status: "Done".to_string(),
+        };
+        save_issues(&path, std::slice::from_ref(&issue)).unwrap();
+        assert_eq!(load_issues(&path).unwrap(), vec![issue]);
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b"a@b.c:tok"), "YUBiLmM6dG9r");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"a"), "YQ==");
+    }
+}
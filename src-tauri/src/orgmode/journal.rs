@@ -0,0 +1,180 @@
+use crate::orgmode::source::headline_level;
+use crate::orgmode::subtree::insert_subtree;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fmt::Write;
+
+/// How much time one journal file covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum JournalPeriod {
+    Daily,
+    Weekly, // One file per week, named after its first day
+}
+
+/// Where journal entries are kept, in the style of org-journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct JournalConfig {
+    pub directory: Option<String>, // None until the user picks a journal directory
+    pub period: JournalPeriod,
+    pub file_format: String, // chrono format for file names, e.g. "%Y%m%d.org"
+    pub heading_format: String, // chrono format for the entry headline of each day
+}
+
+/// A located or newly created journal entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct JournalEntry {
+    pub document_id: String,
+    pub headline_id: String,
+    pub file_path: String,
+    pub created: bool, // The entry headline did not exist before
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            period: JournalPeriod::Daily,
+            file_format: "%Y%m%d.org".to_string(),
+            heading_format: "%A, %Y-%m-%d".to_string(),
+        }
+    }
+}
+
+// Whether a chrono format string only has items a date can be formatted with
+fn is_date_format(format: &str) -> bool {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return false;
+    }
+    let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap_or_default();
+    let mut formatted = String::new();
+    write!(formatted, "{}", date.format_with_items(items.into_iter())).is_ok()
+}
+
+impl JournalConfig {
+    /// Check that the file name and headline formats are date formats chrono accepts
+    pub fn validate(&self) -> Result<(), String> {
+        if self.file_format.trim().is_empty() || !is_date_format(&self.file_format) {
+            return Err(format!(
+                "Invalid journal file format: '{}'",
+                self.file_format
+            ));
+        }
+        if !is_date_format(&self.heading_format) {
+            return Err(format!(
+                "Invalid journal headline format: '{}'",
+                self.heading_format
+            ));
+        }
+        Ok(())
+    }
+
+    /// File name of the journal file holding `date`, with weeks starting on `week_start`
+    pub fn file_name(&self, date: NaiveDate, week_start: Weekday) -> String {
+        let start = match self.period {
+            JournalPeriod::Daily => date,
+            JournalPeriod::Weekly => date.week(week_start).first_day(),
+        };
+        start.format(&self.file_format).to_string()
+    }
+
+    /// Title of the entry headline for `date`
+    pub fn heading(&self, date: NaiveDate) -> String {
+        date.format(&self.heading_format).to_string()
+    }
+}
+
+/// Line index (0-based) of the top-level headline titled `heading`
+pub fn find_entry_line(content: &str, heading: &str) -> Option<usize> {
+    content.lines().position(|line| {
+        headline_level(line) == Some(1) && line.trim_start_matches('*').trim() == heading
+    })
+}
+
+/// Append a top-level entry headline unless one with the same title exists
+/// Returns the new content, or None if the entry is already there
+pub fn ensure_entry(content: &str, heading: &str) -> Option<String> {
+    if find_entry_line(content, heading).is_some() {
+        return None;
+    }
+
+    let mut output = content.to_string();
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(&format!("* {}\n", heading));
+    Some(output)
+}
+
+/// File a subtree as the last child of the entry titled `heading`
+/// Returns None if the file has no such entry
+pub fn append_to_entry(content: &str, heading: &str, subtree: &str) -> Option<String> {
+    let entry = find_entry_line(content, heading)?;
+    let end = content
+        .lines()
+        .enumerate()
+        .skip(entry + 1)
+        .find(|(_, line)| headline_level(line) == Some(1))
+        .map_or(content.lines().count(), |(index, _)| index);
+    Some(insert_subtree(content, end, subtree))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_file_names() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 16).unwrap(); // Thursday
+        let mut config = JournalConfig::default();
+        assert_eq!(config.file_name(date, Weekday::Mon), "20240516.org");
+        assert_eq!(config.heading(date), "Thursday, 2024-05-16");
+
+        config.period = JournalPeriod::Weekly;
+        assert_eq!(config.file_name(date, Weekday::Mon), "20240513.org");
+        assert_eq!(config.file_name(date, Weekday::Sun), "20240512.org");
+    }
+
+    #[test]
+    fn test_validate_formats() {
+        assert!(JournalConfig::default().validate().is_ok());
+        for format in ["%Y%m%d%.org", "%Q.org", "%H%M.org", ""] {
+            let config = JournalConfig {
+                file_format: format.to_string(),
+                ..JournalConfig::default()
+            };
+            assert!(config.validate().is_err(), "{}", format);
+        }
+        let config = JournalConfig {
+            heading_format: "%A %".to_string(),
+            ..JournalConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_ensure_entry() {
+        let content = ensure_entry("#+TITLE: Week", "Monday").unwrap();
+        assert_eq!(content, "#+TITLE: Week\n* Monday\n");
+        assert_eq!(find_entry_line(&content, "Monday"), Some(1));
+        assert!(ensure_entry(&content, "Monday").is_none());
+        // Nested headlines with the same title are not entries
+        assert!(ensure_entry("* Week\n** Monday\n", "Monday").is_some());
+    }
+
+    #[test]
+    fn test_append_to_entry() {
+        let content = "* Monday\n** Standup\n* Tuesday\n";
+        assert_eq!(
+            append_to_entry(content, "Monday", "** Review\n").unwrap(),
+            "* Monday\n** Standup\n** Review\n* Tuesday\n"
+        );
+        assert_eq!(
+            append_to_entry(content, "Tuesday", "** Review\n").unwrap(),
+            "* Monday\n** Standup\n* Tuesday\n** Review\n"
+        );
+        assert!(append_to_entry(content, "Friday", "** Review\n").is_none());
+    }
+}
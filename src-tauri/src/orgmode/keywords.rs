@@ -0,0 +1,382 @@
+use crate::orgmode::dependencies::{completion_blockers, DependencyGraph};
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::logbook::set_closed;
+use crate::orgmode::source::{
+    headline_level, headline_line_numbers, headline_sections, source_lines,
+};
+use crate::orgmode::todo::TodoConfiguration;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A headline line that starts with a given TODO keyword
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct KeywordOccurrence {
+    pub document_id: String,
+    pub file_path: String,
+    pub headline_id: Option<String>,
+    pub line: u32, // 1-based line number
+    pub text: String,
+}
+
+/// Find headline lines using `keyword` as their TODO keyword
+///
+/// Works on the raw source, so it also finds keywords the parser no longer
+/// recognises (e.g. right after the keyword was renamed in settings).
+/// Missing and read-only archived documents are skipped.
+pub fn find_keyword_occurrences(
+    documents: &[&OrgDocument],
+    keyword: &str,
+) -> Vec<KeywordOccurrence> {
+    let mut documents: Vec<&OrgDocument> = documents
+        .iter()
+        .copied()
+        .filter(|document| !document.missing && !document.archived)
+        .collect();
+    documents.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    let mut occurrences = Vec::new();
+    for document in documents {
+        for line in source_lines(document) {
+            if keyword_span(line.text, keyword).is_some() {
+                occurrences.push(KeywordOccurrence {
+                    document_id: document.id.clone(),
+                    file_path: document.file_path.clone(),
+                    headline_id: line.headline_id.map(|id| id.to_string()),
+                    line: line.number as u32,
+                    text: line.text.to_string(),
+                });
+            }
+        }
+    }
+
+    occurrences
+}
+
+/// Replace `old` with `new` as the TODO keyword of every headline line and where the file's
+/// `#+TODO:` lines declare it
+/// Returns None if neither a headline nor the file's keyword lines use the keyword
+pub fn replace_headline_keyword(content: &str, old: &str, new: &str) -> Option<String> {
+    let mut replaced = false;
+    let mut output = String::with_capacity(content.len());
+
+    for line in content.split_inclusive('\n') {
+        let starts: Vec<usize> = match keyword_span(line, old) {
+            Some(start) => vec![start],
+            None => declared_keyword_spans(line, old),
+        };
+        let mut next = 0;
+        for start in starts {
+            output.push_str(&line[next..start]);
+            output.push_str(new);
+            next = start + old.len();
+            replaced = true;
+        }
+        output.push_str(&line[next..]);
+    }
+
+    replaced.then_some(output)
+}
+
+/// Set the TODO keyword of the headline on a 1-based line, replacing `old` if given
+/// A `new` of None clears the keyword. Returns None if the line is not a headline
+/// starting with `old`
+pub fn set_headline_keyword(
+    content: &str,
+    line_number: usize,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> Option<String> {
+    let mut output = String::with_capacity(content.len() + 16);
+    let mut replaced = false;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if index + 1 != line_number {
+            output.push_str(line);
+            continue;
+        }
+
+        let stars = headline_level(line)?;
+        let rest = match old {
+            Some(old) => &line[keyword_span(line, old)? + old.len()..],
+            None => &line[stars..],
+        };
+        let rest = rest.trim_start_matches([' ', '\t']);
+
+        output.push_str(&line[..stars]);
+        if new.is_some() || !rest.trim_end().is_empty() {
+            output.push(' ');
+        }
+        if let Some(new) = new {
+            output.push_str(new);
+            if !rest.trim_end().is_empty() {
+                output.push(' ');
+            }
+        }
+        output.push_str(rest);
+        replaced = true;
+    }
+
+    replaced.then_some(output)
+}
+
+/// Set the TODO keyword of a headline in `document`, checking it against `config`, the
+/// keywords valid for the document
+/// Closing a task logs it as the file's startup options ask and, unless `force` is set, is
+/// refused while subtasks, blockers in `graph` or checkboxes keep it open; reopening it
+/// removes CLOSED. Returns the new content and whether this change closed the task.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_todo_keyword(
+    document: &OrgDocument,
+    graph: &DependencyGraph,
+    config: &TodoConfiguration,
+    headline_id: &str,
+    keyword: Option<&str>,
+    force: bool,
+    note: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<(String, bool), String> {
+    let not_found = || format!("Headline not found: {}", headline_id);
+    let headline = document.find_headline(headline_id).ok_or_else(not_found)?;
+    let status = match keyword {
+        Some(keyword) => Some(
+            config
+                .find_status(keyword)
+                .ok_or_else(|| format!("Unknown TODO keyword: {}", keyword))?,
+        ),
+        None => None,
+    };
+    let closing = status.is_some_and(|status| status.is_closed());
+
+    if !force && closing {
+        let section = headline_sections(document)
+            .remove(headline_id)
+            .unwrap_or_default();
+        let reasons = completion_blockers(graph, document, headline, &section, config);
+        if !reasons.is_empty() {
+            return Err(format!(
+                "Cannot close '{}': {}",
+                headline.title.raw,
+                reasons.join("; ")
+            ));
+        }
+    }
+
+    let line = headline_line_numbers(document)
+        .get(headline_id)
+        .copied()
+        .ok_or_else(not_found)?;
+    let previous = headline.title.todo_keyword.as_deref();
+    let updated =
+        set_headline_keyword(&document.content, line, previous, keyword).ok_or_else(not_found)?;
+
+    let was_closed = previous
+        .and_then(|keyword| config.find_status(keyword))
+        .is_some_and(|status| status.is_closed());
+    let updated = match (was_closed, closing) {
+        (false, true) => document.startup.log_closing(&updated, line, now, note),
+        (true, false) => set_closed(&updated, line, None),
+        _ => Some(updated),
+    }
+    .ok_or_else(not_found)?;
+    Ok((updated, !was_closed && closing))
+}
+
+/// Set the priority cookie, like `[#A]`, of the headline on a 1-based line whose TODO
+/// keyword is `keyword`
+/// A `priority` of None removes the cookie. Returns None if the line is not a headline
+/// starting with `keyword`
+pub fn set_headline_priority(
+    content: &str,
+    line_number: usize,
+    keyword: Option<&str>,
+    priority: Option<char>,
+) -> Option<String> {
+    let mut output = String::with_capacity(content.len() + 8);
+    let mut replaced = false;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if index + 1 != line_number {
+            output.push_str(line);
+            continue;
+        }
+
+        let stars = headline_level(line)?;
+        let start = match keyword {
+            Some(keyword) => keyword_span(line, keyword)? + keyword.len(),
+            None => stars,
+        };
+        let mut rest = line[start..].trim_start_matches([' ', '\t']);
+        if rest.len() >= 4 && rest.starts_with("[#") && rest.as_bytes()[3] == b']' {
+            rest = rest[4..].trim_start_matches([' ', '\t']);
+        }
+
+        output.push_str(&line[..start]);
+        if let Some(priority) = priority {
+            output.push_str(&format!(" [#{}]", priority));
+        }
+        if !rest.trim_end().is_empty() {
+            output.push(' ');
+        }
+        output.push_str(rest);
+        replaced = true;
+    }
+
+    replaced.then_some(output)
+}
+
+// Byte offset of the keyword if it is the first word after the headline stars
+fn keyword_span(line: &str, keyword: &str) -> Option<usize> {
+    let stars = headline_level(line)?;
+    let rest = &line[stars..];
+    let start = stars + (rest.len() - rest.trim_start_matches([' ', '\t']).len());
+
+    let after = line[start..].strip_prefix(keyword)?;
+    match after.chars().next() {
+        None | Some(' ') | Some('\t') | Some('\r') | Some('\n') => Some(start),
+        _ => None,
+    }
+}
+
+// Byte offsets of `keyword` where a `#+TODO:`, `#+SEQ_TODO:` or `#+TYP_TODO:` line declares
+// it, with or without a fast access key like `WAIT(w)`
+fn declared_keyword_spans(line: &str, keyword: &str) -> Vec<usize> {
+    let Some((key, value)) = line
+        .trim_start()
+        .strip_prefix("#+")
+        .and_then(|rest| rest.split_once(':'))
+    else {
+        return Vec::new();
+    };
+    if !["TODO", "SEQ_TODO", "TYP_TODO"]
+        .iter()
+        .any(|todo_key| key.eq_ignore_ascii_case(todo_key))
+    {
+        return Vec::new();
+    }
+
+    let offset = line.len() - value.len();
+    let mut spans = Vec::new();
+    let mut word_start = None;
+    for (index, c) in value.char_indices().chain([(value.len(), ' ')]) {
+        if !c.is_whitespace() {
+            word_start.get_or_insert(index);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            let word = &value[start..index];
+            if word == keyword
+                || word
+                    .strip_prefix(keyword)
+                    .is_some_and(|rest| rest.starts_with('('))
+            {
+                spans.push(offset + start);
+            }
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const SAMPLE: &str = "* WAIT Call back\n** WAITING Not this one\n- WAIT in a list\n*** WAIT\n";
+
+    #[test]
+    fn test_replace_headline_keyword() {
+        let replaced = replace_headline_keyword(SAMPLE, "WAIT", "HOLD").unwrap();
+        assert_eq!(
+            replaced,
+            "* HOLD Call back\n** WAITING Not this one\n- WAIT in a list\n*** HOLD\n"
+        );
+        assert_eq!(replace_headline_keyword(SAMPLE, "NEXT", "TODO"), None);
+
+        // The file's own declarations follow, fast access keys included
+        assert_eq!(
+            replace_headline_keyword(
+                "#+TODO: TODO WAIT(w@) | DONE\n#+seq_todo: WAITING WAIT\n#+TITLE: WAIT\n",
+                "WAIT",
+                "HOLD"
+            )
+            .unwrap(),
+            "#+TODO: TODO HOLD(w@) | DONE\n#+seq_todo: WAITING HOLD\n#+TITLE: WAIT\n"
+        );
+    }
+
+    #[test]
+    fn test_set_headline_keyword() {
+        assert_eq!(
+            set_headline_keyword(SAMPLE, 1, Some("WAIT"), Some("DONE")).unwrap(),
+            "* DONE Call back\n** WAITING Not this one\n- WAIT in a list\n*** WAIT\n"
+        );
+        assert_eq!(
+            set_headline_keyword(SAMPLE, 4, Some("WAIT"), None).unwrap(),
+            "* WAIT Call back\n** WAITING Not this one\n- WAIT in a list\n***\n"
+        );
+        assert!(set_headline_keyword(SAMPLE, 2, None, Some("TODO"))
+            .unwrap()
+            .contains("** TODO WAITING Not this one\n"));
+        assert_eq!(set_headline_keyword(SAMPLE, 2, Some("WAIT"), None), None);
+        assert_eq!(set_headline_keyword(SAMPLE, 3, None, Some("TODO")), None);
+    }
+
+    #[test]
+    fn test_apply_todo_keyword() {
+        let doc = parse_org_document(
+            "#+STARTUP: logdone\n#+TODO: NEXT | DONE\n* NEXT Write report\n",
+            Some("/notes/own.org"),
+        )
+        .unwrap();
+        let graph = DependencyGraph::build(&[&doc]);
+        let settings = TodoConfiguration::default();
+        let config = doc.effective_todo_config(&settings);
+        let now = NaiveDateTime::parse_from_str("2024-03-04 09:30", "%Y-%m-%d %H:%M").unwrap();
+
+        // Keywords from the settings are valid next to the file's own
+        let (updated, closed) =
+            apply_todo_keyword(&doc, &graph, &config, "1", Some("TODO"), false, None, now).unwrap();
+        assert!(updated.ends_with("#+TODO: NEXT | DONE\n* TODO Write report\n"));
+        assert!(!closed);
+
+        let (updated, closed) =
+            apply_todo_keyword(&doc, &graph, &config, "1", Some("DONE"), false, None, now).unwrap();
+        assert!(updated.contains("* DONE Write report\nCLOSED: [2024-03-04"));
+        assert!(closed);
+
+        assert_eq!(
+            apply_todo_keyword(&doc, &graph, &config, "1", Some("LATER"), false, None, now),
+            Err("Unknown TODO keyword: LATER".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_headline_priority() {
+        let content = "* TODO [#C] Call back\n** Plan trip\n";
+        assert_eq!(
+            set_headline_priority(content, 1, Some("TODO"), Some('A')).unwrap(),
+            "* TODO [#A] Call back\n** Plan trip\n"
+        );
+        assert_eq!(
+            set_headline_priority(content, 1, Some("TODO"), None).unwrap(),
+            "* TODO Call back\n** Plan trip\n"
+        );
+        assert_eq!(
+            set_headline_priority(content, 2, None, Some('B')).unwrap(),
+            "* TODO [#C] Call back\n** [#B] Plan trip\n"
+        );
+        assert_eq!(set_headline_priority(content, 1, Some("DONE"), None), None);
+    }
+
+    #[test]
+    fn test_find_keyword_occurrences() {
+        let doc = parse_org_document(SAMPLE, Some("wait.org")).unwrap();
+        let occurrences = find_keyword_occurrences(&[&doc], "WAIT");
+
+        let lines: Vec<u32> = occurrences.iter().map(|o| o.line).collect();
+        assert_eq!(lines, vec![1, 4]);
+        assert_eq!(occurrences[0].headline_id.as_deref(), Some("1"));
+        assert_eq!(occurrences[1].headline_id.as_deref(), Some("1.1.1"));
+    }
+}
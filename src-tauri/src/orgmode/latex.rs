@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Kind of a LaTeX span
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum LatexKind {
+    InlineMath,  // `\( ... \)` or `$...$`
+    DisplayMath, // `\[ ... \]`, `$$...$$`, or `\begin{env} ... \end{env}`
+    Entity,      // `\alpha`, `\to`, ...
+}
+
+/// LaTeX markup found in org text, to be rendered instead of shown raw
+/// Offsets are in UTF-16 code units so they can be used on JavaScript strings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct LatexSpan {
+    pub start: u32,
+    pub end: u32, // Exclusive
+    pub kind: LatexKind,
+    pub latex: String,           // Source to render, without `\(`/`$` delimiters
+    pub unicode: Option<String>, // Plain-text rendering of an entity
+}
+
+// Org entities as (name, LaTeX, Unicode); a subset of `org-entities`
+const ENTITIES: &[(&str, &str, &str)] = &[
+    ("alpha", "\\alpha", "α"),
+    ("beta", "\\beta", "β"),
+    ("gamma", "\\gamma", "γ"),
+    ("delta", "\\delta", "δ"),
+    ("epsilon", "\\epsilon", "ε"),
+    ("zeta", "\\zeta", "ζ"),
+    ("eta", "\\eta", "η"),
+    ("theta", "\\theta", "θ"),
+    ("iota", "\\iota", "ι"),
+    ("kappa", "\\kappa", "κ"),
+    ("lambda", "\\lambda", "λ"),
+    ("mu", "\\mu", "μ"),
+    ("nu", "\\nu", "ν"),
+    ("xi", "\\xi", "ξ"),
+    ("pi", "\\pi", "π"),
+    ("rho", "\\rho", "ρ"),
+    ("sigma", "\\sigma", "σ"),
+    ("tau", "\\tau", "τ"),
+    ("upsilon", "\\upsilon", "υ"),
+    ("phi", "\\phi", "φ"),
+    ("chi", "\\chi", "χ"),
+    ("psi", "\\psi", "ψ"),
+    ("omega", "\\omega", "ω"),
+    ("Gamma", "\\Gamma", "Γ"),
+    ("Delta", "\\Delta", "Δ"),
+    ("Theta", "\\Theta", "Θ"),
+    ("Lambda", "\\Lambda", "Λ"),
+    ("Xi", "\\Xi", "Ξ"),
+    ("Pi", "\\Pi", "Π"),
+    ("Sigma", "\\Sigma", "Σ"),
+    ("Phi", "\\Phi", "Φ"),
+    ("Psi", "\\Psi", "Ψ"),
+    ("Omega", "\\Omega", "Ω"),
+    ("infin", "\\infty", "∞"),
+    ("infty", "\\infty", "∞"),
+    ("pm", "\\pm", "±"),
+    ("times", "\\times", "×"),
+    ("div", "\\div", "÷"),
+    ("le", "\\le", "≤"),
+    ("leq", "\\le", "≤"),
+    ("ge", "\\ge", "≥"),
+    ("geq", "\\ge", "≥"),
+    ("ne", "\\ne", "≠"),
+    ("neq", "\\ne", "≠"),
+    ("approx", "\\approx", "≈"),
+    ("sum", "\\sum", "∑"),
+    ("prod", "\\prod", "∏"),
+    ("int", "\\int", "∫"),
+    ("partial", "\\partial", "∂"),
+    ("nabla", "\\nabla", "∇"),
+    ("sqrt", "\\sqrt{\\,}", "√"),
+    ("in", "\\in", "∈"),
+    ("notin", "\\notin", "∉"),
+    ("forall", "\\forall", "∀"),
+    ("exists", "\\exists", "∃"),
+    ("empty", "\\emptyset", "∅"),
+    ("cap", "\\cap", "∩"),
+    ("cup", "\\cup", "∪"),
+    ("to", "\\to", "→"),
+    ("rarr", "\\to", "→"),
+    ("rightarrow", "\\rightarrow", "→"),
+    ("larr", "\\leftarrow", "←"),
+    ("leftarrow", "\\leftarrow", "←"),
+    ("Rightarrow", "\\Rightarrow", "⇒"),
+    ("Leftrightarrow", "\\Leftrightarrow", "⇔"),
+    ("deg", "^{\\circ}", "°"),
+    ("cdot", "\\cdot", "⋅"),
+    ("hellip", "\\dots", "…"),
+    ("dots", "\\dots", "…"),
+    ("mdash", "\\textemdash{}", "—"),
+    ("ndash", "\\textendash{}", "–"),
+    ("nbsp", "~", "\u{a0}"),
+    ("copy", "\\textcopyright{}", "©"),
+    ("reg", "\\textregistered{}", "®"),
+    ("trade", "\\texttrademark{}", "™"),
+    ("euro", "\\texteuro{}", "€"),
+    ("pound", "\\pounds{}", "£"),
+    ("yen", "\\textyen{}", "¥"),
+    ("sect", "\\S", "§"),
+    ("para", "\\P{}", "¶"),
+];
+
+/// Look up an org entity by name, returning its LaTeX and Unicode forms
+pub fn lookup_entity(name: &str) -> Option<(&'static str, &'static str)> {
+    ENTITIES
+        .iter()
+        .find(|(entity, _, _)| *entity == name)
+        .map(|(_, latex, unicode)| (*latex, *unicode))
+}
+
+// Whether a `$` at `start` opens an inline fragment, following org's rules
+fn dollar_opens(text: &str, start: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[start + 1..].chars().next();
+    !before.is_some_and(|c| c.is_alphanumeric() || c == '$')
+        && after.is_some_and(|c| !c.is_whitespace() && !matches!(c, '.' | ',' | ';' | '$'))
+}
+
+// Whether a `$` at `end` closes an inline fragment
+fn dollar_closes(text: &str, end: usize) -> bool {
+    let before = text[..end].chars().next_back();
+    let after = text[end + 1..].chars().next();
+    before.is_some_and(|c| !c.is_whitespace() && !matches!(c, '.' | ',' | '$'))
+        && !after.is_some_and(|c| c.is_alphanumeric() || c == '$')
+}
+
+// Find the end of a `$...$` fragment opened at `start`, within the same paragraph
+fn inline_dollar_end(text: &str, start: usize) -> Option<usize> {
+    let body = &text[start + 1..];
+    let close = body.find('$')?;
+    let inner = &body[..close];
+    if inner.contains("\n\n") || !dollar_closes(text, start + 1 + close) {
+        return None;
+    }
+    Some(start + 1 + close)
+}
+
+/// Match a fragment or entity at byte `start`, returning its byte end, kind, LaTeX source,
+/// and the Unicode form of an entity
+pub fn latex_at(text: &str, start: usize) -> Option<(usize, LatexKind, String, Option<String>)> {
+    let rest = &text[start..];
+
+    for (open, close, kind) in [
+        ("\\(", "\\)", LatexKind::InlineMath),
+        ("\\[", "\\]", LatexKind::DisplayMath),
+        ("$$", "$$", LatexKind::DisplayMath),
+    ] {
+        if let Some(body) = rest.strip_prefix(open) {
+            let end = body.find(close)?;
+            let latex = body[..end].trim().to_string();
+            return Some((start + open.len() + end + close.len(), kind, latex, None));
+        }
+    }
+
+    if let Some(body) = rest.strip_prefix("\\begin{") {
+        let name = &body[..body.find('}')?];
+        let end_marker = format!("\\end{{{}}}", name);
+        let end = rest.find(&end_marker)? + end_marker.len();
+        return Some((
+            start + end,
+            LatexKind::DisplayMath,
+            rest[..end].to_string(),
+            None,
+        ));
+    }
+
+    if rest.starts_with('$') && dollar_opens(text, start) {
+        let end = inline_dollar_end(text, start)?;
+        let latex = text[start + 1..end].to_string();
+        return Some((end + 1, LatexKind::InlineMath, latex, None));
+    }
+
+    let name_len = rest
+        .strip_prefix('\\')?
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len() - 1);
+    let name = &rest[1..1 + name_len];
+    let (latex, unicode) = lookup_entity(name)?;
+    // `\alpha{}` ends an entity explicitly
+    let terminator = rest[1 + name_len..].starts_with("{}");
+    let end = start + 1 + name_len + if terminator { 2 } else { 0 };
+    Some((
+        end,
+        LatexKind::Entity,
+        latex.to_string(),
+        Some(unicode.to_string()),
+    ))
+}
+
+/// Find LaTeX fragments and org entities in text, in order and without overlaps
+pub fn find_latex_spans(text: &str) -> Vec<LatexSpan> {
+    let mut spans = Vec::new();
+    let mut index = 0;
+    let mut utf16_index = 0;
+
+    while index < text.len() {
+        let c = text[index..].chars().next().unwrap_or_default();
+        if matches!(c, '\\' | '$') {
+            if let Some((end, kind, latex, unicode)) = latex_at(text, index) {
+                let length = text[index..end].encode_utf16().count();
+                spans.push(LatexSpan {
+                    start: utf16_index as u32,
+                    end: (utf16_index + length) as u32,
+                    kind,
+                    latex,
+                    unicode,
+                });
+                index = end;
+                utf16_index += length;
+                continue;
+            }
+        }
+        index += c.len_utf8();
+        utf16_index += c.len_utf16();
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<(LatexKind, String)> {
+        find_latex_spans(text)
+            .into_iter()
+            .map(|span| (span.kind, span.latex))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_latex_fragments() {
+        assert_eq!(
+            kinds("Energy \\(E = mc^2\\) and $x_1$, then \\[\\sum_i x_i\\]"),
+            vec![
+                (LatexKind::InlineMath, "E = mc^2".to_string()),
+                (LatexKind::InlineMath, "x_1".to_string()),
+                (LatexKind::DisplayMath, "\\sum_i x_i".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds("\\begin{equation}\na = b\n\\end{equation}"),
+            vec![(
+                LatexKind::DisplayMath,
+                "\\begin{equation}\na = b\n\\end{equation}".to_string()
+            )]
+        );
+        // Prices are not math
+        assert!(kinds("It costs $5 to $10.").is_empty());
+    }
+
+    #[test]
+    fn test_find_entities() {
+        let spans = find_latex_spans("é \\alpha{}-decay \\to \\unknown");
+        assert_eq!(spans.len(), 2);
+        assert_eq!((spans[0].start, spans[0].end), (2, 10));
+        assert_eq!(spans[0].unicode.as_deref(), Some("α"));
+        assert_eq!(spans[1].latex, "\\to");
+    }
+}
@@ -0,0 +1,298 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::{flatten_headlines, source_lines};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Kind of an org link, derived from its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum LinkKind {
+    File,     // [[file:notes.org]] or [[./notes.org]]
+    Id,       // [[id:...]]
+    CustomId, // [[#custom-id]]
+    Heading,  // [[*Heading title]]
+    Target,   // [[target]], resolved against <<targets>> and headline titles
+    External, // Any other scheme (https:, mailto:, ...)
+}
+
+/// A bracket link found in org text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct OrgLink {
+    pub target: String,
+    pub description: Option<String>,
+    pub kind: LinkKind,
+}
+
+/// Why a link could not be resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum LinkProblem {
+    MissingFile,
+    UnresolvedId,
+    DanglingTarget,
+}
+
+/// A link that does not resolve, with its location
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BrokenLink {
+    pub document_id: String,
+    pub headline_id: Option<String>,
+    pub line: u32, // 1-based line number
+    pub link: OrgLink,
+    pub problem: LinkProblem,
+}
+
+impl OrgLink {
+    /// Classify a raw link target
+    pub fn new(target: &str, description: Option<&str>) -> Self {
+        Self {
+            target: target.to_string(),
+            description: description.map(|d| d.to_string()),
+            kind: classify_target(target),
+        }
+    }
+
+    /// The file path of a file link, without the `file:` prefix or `::search` option
+    pub fn file_path(&self) -> Option<&str> {
+        if self.kind != LinkKind::File {
+            return None;
+        }
+
+        let path = match self.target.split_once(':') {
+            Some((scheme, rest)) if scheme == "file" || scheme.starts_with("file+") => rest,
+            _ => self.target.as_str(),
+        };
+        Some(path.split("::").next().unwrap_or(path))
+    }
+}
+
+fn classify_target(target: &str) -> LinkKind {
+    if target.starts_with('#') {
+        return LinkKind::CustomId;
+    }
+    if target.starts_with('*') {
+        return LinkKind::Heading;
+    }
+    if target.starts_with('/')
+        || target.starts_with("./")
+        || target.starts_with("../")
+        || target.starts_with("~/")
+    {
+        return LinkKind::File;
+    }
+
+    match target.split_once(':') {
+        Some((scheme, _)) if scheme == "file" || scheme.starts_with("file+") => LinkKind::File,
+        Some(("id", _)) => LinkKind::Id,
+        Some((scheme, _))
+            if !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-') =>
+        {
+            LinkKind::External
+        }
+        _ => LinkKind::Target,
+    }
+}
+
+/// Extract all bracket links (`[[target]]` / `[[target][description]]`) from text
+pub fn extract_links(text: &str) -> Vec<OrgLink> {
+    let mut links = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else {
+            break;
+        };
+
+        let inner = &after[..end];
+        let (target, description) = match inner.split_once("][") {
+            Some((target, description)) => (target, Some(description)),
+            None => (inner, None),
+        };
+
+        if !target.trim().is_empty() {
+            links.push(OrgLink::new(target.trim(), description));
+        }
+
+        rest = &after[end + 2..];
+    }
+
+    links
+}
+
+/// Check every link in the given documents and report those that do not resolve
+pub fn check_links(documents: &[&OrgDocument]) -> Vec<BrokenLink> {
+    let mut documents = documents.to_vec();
+    documents.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    // IDs are global across all documents
+    let known_ids: HashSet<&str> = documents
+        .iter()
+        .flat_map(|document| flatten_headlines(&document.headlines))
+        .filter_map(|headline| headline.title.get_property_ignore_case("ID"))
+        .map(str::trim)
+        .collect();
+
+    let mut broken = Vec::new();
+
+    for document in documents {
+        let headlines = flatten_headlines(&document.headlines);
+        let custom_ids: HashSet<&str> = headlines
+            .iter()
+            .filter_map(|headline| headline.title.get_property_ignore_case("CUSTOM_ID"))
+            .map(str::trim)
+            .collect();
+        let titles: HashSet<&str> = headlines
+            .iter()
+            .map(|headline| headline.title.raw.trim())
+            .collect();
+        let targets = dedicated_targets(&document.content);
+
+        for line in source_lines(document) {
+            for link in extract_links(line.text) {
+                let problem = match link.kind {
+                    LinkKind::File => link
+                        .file_path()
+                        .filter(|path| !resolve_file(document, path).exists())
+                        .map(|_| LinkProblem::MissingFile),
+                    LinkKind::Id => {
+                        let id = link.target["id:".len()..].trim();
+                        (!known_ids.contains(id)).then_some(LinkProblem::UnresolvedId)
+                    }
+                    LinkKind::CustomId => {
+                        let id = link.target[1..].trim();
+                        (!custom_ids.contains(id)).then_some(LinkProblem::DanglingTarget)
+                    }
+                    LinkKind::Heading => {
+                        let title = link.target[1..].trim();
+                        (!titles.contains(title)).then_some(LinkProblem::DanglingTarget)
+                    }
+                    LinkKind::Target => {
+                        let name = link.target.to_lowercase();
+                        (!targets.contains(&name) && !titles.contains(link.target.as_str()))
+                            .then_some(LinkProblem::DanglingTarget)
+                    }
+                    LinkKind::External => None,
+                };
+
+                if let Some(problem) = problem {
+                    broken.push(BrokenLink {
+                        document_id: document.id.clone(),
+                        headline_id: line.headline_id.map(|id| id.to_string()),
+                        line: line.number as u32,
+                        link,
+                        problem,
+                    });
+                }
+            }
+        }
+    }
+
+    broken
+}
+
+// Collect `<<target>>` and `<<<radio target>>>` names, lowercased
+fn dedicated_targets(content: &str) -> HashSet<String> {
+    let mut targets = HashSet::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("<<") {
+        let after = rest[start + 2..].trim_start_matches('<');
+        let Some(end) = after.find(">>") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if !name.is_empty() && !name.contains('\n') {
+            targets.insert(name.to_lowercase());
+        }
+        rest = &after[end + 2..];
+    }
+
+    targets
+}
+
+// Resolve a link path relative to the document's directory
+pub(crate) fn resolve_file(document: &OrgDocument, path: &str) -> PathBuf {
+    if let Some(home_relative) = path.strip_prefix("~/") {
+        if let Ok(home) = std::env::var("HOME") {
+            return Path::new(&home).join(home_relative);
+        }
+    }
+
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    Path::new(&document.file_path)
+        .parent()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_extract_links() {
+        let links = extract_links(
+            "See [[https://orgmode.org][Org]] and [[file:notes.org::*Intro]] or [[#setup]].",
+        );
+
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].kind, LinkKind::External);
+        assert_eq!(links[0].description.as_deref(), Some("Org"));
+        assert_eq!(links[1].kind, LinkKind::File);
+        assert_eq!(links[1].file_path(), Some("notes.org"));
+        assert_eq!(links[2].kind, LinkKind::CustomId);
+    }
+
+    #[test]
+    fn test_check_links() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("exists.org"), "* Present\n").unwrap();
+        let doc_path = dir.path().join("main.org");
+
+        let content = r#"#+TITLE: Links
+Intro [[file:exists.org]] and [[file:missing.org]]
+
+* Setup
+:PROPERTIES:
+:ID: abc-123
+:CUSTOM_ID: setup
+:END:
+<<anchor>>
+* Links
+- [[id:abc-123]] [[id:nope]]
+- [[#setup]] [[#gone]]
+- [[*Setup]] [[*Nowhere]]
+- [[Anchor]] [[dangling]]
+"#;
+        let doc = parse_org_document(content, Some(doc_path.to_str().unwrap())).unwrap();
+        let broken = check_links(&[&doc]);
+
+        let summary: Vec<(&str, u32, LinkProblem)> = broken
+            .iter()
+            .map(|b| (b.link.target.as_str(), b.line, b.problem))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("file:missing.org", 2, LinkProblem::MissingFile),
+                ("id:nope", 11, LinkProblem::UnresolvedId),
+                ("#gone", 12, LinkProblem::DanglingTarget),
+                ("*Nowhere", 13, LinkProblem::DanglingTarget),
+                ("dangling", 14, LinkProblem::DanglingTarget),
+            ]
+        );
+        assert_eq!(broken[0].headline_id, None);
+        assert_eq!(broken[1].headline_id.as_deref(), Some("2"));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::orgmode::datetime::DateRange;
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Language of day names when none is configured, as Emacs writes them by default
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// Abbreviated day names, Monday first, as Emacs writes them in each language's locale
+const DAY_NAMES: [(&str, [&str; 7]); 8] = [
+    ("en", ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]),
+    ("de", ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"]),
+    ("es", ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"]),
+    ("fr", ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"]),
+    ("it", ["lun", "mar", "mer", "gio", "ven", "sab", "dom"]),
+    ("nl", ["ma", "di", "wo", "do", "vr", "za", "zo"]),
+    ("pt", ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"]),
+    ("sv", ["mån", "tis", "ons", "tor", "fre", "lör", "sön"]),
+];
+
+// Full day names, Monday first, for agenda headings, in the languages of DAY_NAMES
+const LONG_DAY_NAMES: [&str; 8] = [
+    "Monday Tuesday Wednesday Thursday Friday Saturday Sunday",
+    "Montag Dienstag Mittwoch Donnerstag Freitag Samstag Sonntag",
+    "lunes martes miércoles jueves viernes sábado domingo",
+    "lundi mardi mercredi jeudi vendredi samedi dimanche",
+    "lunedì martedì mercoledì giovedì venerdì sabato domenica",
+    "maandag dinsdag woensdag donderdag vrijdag zaterdag zondag",
+    "segunda-feira terça-feira quarta-feira quinta-feira sexta-feira sábado domingo",
+    "måndag tisdag onsdag torsdag fredag lördag söndag",
+];
+
+// Month names, January first, for agenda headings, in the languages of DAY_NAMES
+const MONTH_NAMES: [&str; 8] = [
+    "January February March April May June July August September October November December",
+    "Januar Februar März April Mai Juni Juli August September Oktober November Dezember",
+    "enero febrero marzo abril mayo junio julio agosto septiembre octubre noviembre diciembre",
+    "janvier février mars avril mai juin juillet août septembre octobre novembre décembre",
+    "gennaio febbraio marzo aprile maggio giugno luglio agosto settembre ottobre novembre dicembre",
+    "januari februari maart april mei juni juli augustus september oktober november december",
+    "janeiro fevereiro março abril maio junho julho agosto setembro outubro novembro dezembro",
+    "januari februari mars april maj juni juli augusti september oktober november december",
+];
+
+/// Languages day names are available in
+pub fn supported_languages() -> Vec<&'static str> {
+    DAY_NAMES.iter().map(|(language, _)| *language).collect()
+}
+
+/// Abbreviated name of a weekday, in English if the language is not supported
+pub fn day_name(weekday: Weekday, language: &str) -> &'static str {
+    let names = DAY_NAMES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(language))
+        .map_or(&DAY_NAMES[0].1, |(_, names)| names);
+    names[weekday.num_days_from_monday() as usize]
+}
+
+// The `index`th of a language's space-separated names, English if it is not supported
+fn nth_name(table: &[&'static str; 8], language: &str, index: usize) -> &'static str {
+    let language = DAY_NAMES
+        .iter()
+        .position(|(code, _)| code.eq_ignore_ascii_case(language))
+        .unwrap_or(0);
+    table[language].split(' ').nth(index).unwrap_or_default()
+}
+
+/// Full name of a weekday, in English if the language is not supported
+pub fn long_day_name(weekday: Weekday, language: &str) -> &'static str {
+    nth_name(
+        &LONG_DAY_NAMES,
+        language,
+        weekday.num_days_from_monday() as usize,
+    )
+}
+
+/// Name of a month from 1 to 12, in English if the language is not supported
+pub fn month_name(month: u32, language: &str) -> &'static str {
+    nth_name(&MONTH_NAMES, language, month.saturating_sub(1) as usize)
+}
+
+/// Where weeks start and which language new timestamps name their days in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct DateLocale {
+    pub week_start: u32,  // ISO weekday, 1 for Monday to 7 for Sunday
+    pub language: String, // Two-letter code, e.g. "de"
+}
+
+impl Default for DateLocale {
+    fn default() -> Self {
+        Self {
+            week_start: 1,
+            language: DEFAULT_LANGUAGE.to_string(),
+        }
+    }
+}
+
+impl DateLocale {
+    /// Check the week start and language
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=7).contains(&self.week_start) {
+            return Err(format!(
+                "Invalid week start: {} (use 1 for Monday to 7 for Sunday)",
+                self.week_start
+            ));
+        }
+        if !supported_languages()
+            .iter()
+            .any(|language| language.eq_ignore_ascii_case(&self.language))
+        {
+            return Err(format!(
+                "Unsupported language: '{}' (use one of {})",
+                self.language,
+                supported_languages().join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// First day of the week, Monday if the setting is out of range
+    pub fn first_weekday(&self) -> Weekday {
+        match self.week_start {
+            2 => Weekday::Tue,
+            3 => Weekday::Wed,
+            4 => Weekday::Thu,
+            5 => Weekday::Fri,
+            6 => Weekday::Sat,
+            7 => Weekday::Sun,
+            _ => Weekday::Mon,
+        }
+    }
+
+    /// Day names in week order, starting with the first day of the week
+    pub fn day_names(&self) -> Vec<String> {
+        let mut weekday = self.first_weekday();
+        let mut names = Vec::with_capacity(7);
+        for _ in 0..7 {
+            names.push(day_name(weekday, &self.language).to_string());
+            weekday = weekday.succ();
+        }
+        names
+    }
+
+    /// The week containing `date`, first to last day
+    pub fn week_of(&self, date: NaiveDate) -> DateRange {
+        let start = date.week(self.first_weekday());
+        DateRange::new(
+            &start.first_day().format("%Y-%m-%d").to_string(),
+            &start.last_day().format("%Y-%m-%d").to_string(),
+        )
+    }
+}
+
+/// Dates of a month laid out in calendar weeks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct CalendarMonth {
+    pub year: i32,
+    pub month: u32,
+    pub day_names: Vec<String>, // Column headers, first day of the week first
+    pub weeks: Vec<Vec<String>>, // Seven YYYY-MM-DD dates each, padded from the months around
+}
+
+/// Lay out a month as full weeks starting on the locale's first day of the week
+/// Returns None if the month does not exist
+pub fn month_calendar(year: i32, month: u32, locale: &DateLocale) -> Option<CalendarMonth> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let mut day = first.week(locale.first_weekday()).first_day();
+
+    let mut weeks = Vec::new();
+    while day.year() < year || (day.year() == year && day.month() <= month) {
+        let mut week = Vec::with_capacity(7);
+        for _ in 0..7 {
+            week.push(day.format("%Y-%m-%d").to_string());
+            day = day.checked_add_days(Days::new(1))?;
+        }
+        weeks.push(week);
+    }
+
+    Some(CalendarMonth {
+        year,
+        month,
+        day_names: locale.day_names(),
+        weeks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_names_and_weeks() {
+        assert_eq!(day_name(Weekday::Wed, "de"), "Mi");
+        assert_eq!(day_name(Weekday::Wed, "xx"), "Wed");
+        assert_eq!(long_day_name(Weekday::Wed, "de"), "Mittwoch");
+        assert_eq!(long_day_name(Weekday::Sun, "xx"), "Sunday");
+        assert_eq!(month_name(3, "fr"), "mars");
+        assert_eq!(month_name(12, "en"), "December");
+
+        let locale = DateLocale {
+            week_start: 7,
+            language: "fr".to_string(),
+        };
+        assert!(locale.validate().is_ok());
+        assert_eq!(locale.day_names()[..2], ["dim", "lun"]);
+        let thursday = NaiveDate::from_ymd_opt(2024, 5, 16).unwrap();
+        assert_eq!(
+            locale.week_of(thursday),
+            DateRange::new("2024-05-12", "2024-05-18")
+        );
+        assert_eq!(
+            DateLocale::default().week_of(thursday),
+            DateRange::new("2024-05-13", "2024-05-19")
+        );
+
+        let unknown = DateLocale {
+            language: "tlh".to_string(),
+            ..DateLocale::default()
+        };
+        assert!(unknown.validate().is_err());
+    }
+
+    #[test]
+    fn test_month_calendar() {
+        // February 2026 starts on a Sunday and ends on a Saturday
+        let sunday = DateLocale {
+            week_start: 7,
+            ..DateLocale::default()
+        };
+        let february = month_calendar(2026, 2, &sunday).unwrap();
+        assert_eq!(february.weeks.len(), 4);
+        assert_eq!(february.weeks[0][0], "2026-02-01");
+        assert_eq!(february.weeks[3][6], "2026-02-28");
+        assert_eq!(february.day_names[0], "Sun");
+
+        let monday = month_calendar(2026, 2, &DateLocale::default()).unwrap();
+        assert_eq!(monday.weeks.len(), 5);
+        assert_eq!(monday.weeks[0][0], "2026-01-26");
+        assert_eq!(monday.weeks[4][6], "2026-03-01");
+
+        assert!(month_calendar(2026, 13, &sunday).is_none());
+    }
+}
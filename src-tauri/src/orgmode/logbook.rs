@@ -0,0 +1,347 @@
+use crate::orgmode::datetime::OrgDatetime;
+use crate::orgmode::properties::is_planning_line;
+use crate::orgmode::source::headline_level;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A TODO state change recorded in a headline's logbook
+/// e.g. `- State "DONE"       from "TODO"       [2024-01-15 Mon 10:30]`
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+pub struct StateChange {
+    pub to: String,
+    pub from: Option<String>,
+    pub timestamp: OrgDatetime,
+}
+
+/// Extract all state change entries from headline content, in file order
+pub fn parse_state_changes(content: &str) -> Vec<StateChange> {
+    content.lines().filter_map(parse_state_change).collect()
+}
+
+/// Parse a single state change log line
+pub fn parse_state_change(line: &str) -> Option<StateChange> {
+    let rest = line.trim_start().strip_prefix("- State ")?;
+
+    let (to, rest) = take_quoted(rest)?;
+    let rest = rest.trim_start();
+
+    let (from, rest) = match rest.strip_prefix("from ") {
+        Some(after) => {
+            let (from, after) = take_quoted(after.trim_start())?;
+            (Some(from), after)
+        }
+        None => (None, rest),
+    };
+
+    let open = rest.find('[')?;
+    let close = rest[open..].find(']')? + open;
+    let timestamp = parse_timestamp_inner(&rest[open + 1..close])?;
+
+    Some(StateChange {
+        to,
+        from: from.filter(|from| !from.is_empty()),
+        timestamp,
+    })
+}
+
+// Read a "quoted" word, returning it and the remaining input
+fn take_quoted(input: &str) -> Option<(String, &str)> {
+    let input = input.strip_prefix('"')?;
+    let end = input.find('"')?;
+    Some((input[..end].to_string(), &input[end + 1..]))
+}
+
+/// A finished clock entry in a logbook
+/// e.g. `CLOCK: [2024-01-15 Mon 09:00]--[2024-01-15 Mon 10:30] =>  1:30`
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+pub struct ClockEntry {
+    pub start: OrgDatetime,
+    pub end: OrgDatetime,
+}
+
+impl ClockEntry {
+    /// Clocked time in minutes, computed from the timestamps rather than the `=>` total
+    pub fn minutes(&self) -> u32 {
+        (self.end.to_naive_datetime() - self.start.to_naive_datetime())
+            .num_minutes()
+            .max(0) as u32
+    }
+}
+
+/// Extract all finished clock entries from content, in file order
+/// Running clocks (no end timestamp) are skipped
+pub fn parse_clock_entries(content: &str) -> Vec<ClockEntry> {
+    content.lines().filter_map(parse_clock_entry).collect()
+}
+
+/// Parse a single CLOCK line
+pub fn parse_clock_entry(line: &str) -> Option<ClockEntry> {
+    let rest = line.trim_start().strip_prefix("CLOCK:")?.trim_start();
+    let (start, rest) = rest.strip_prefix('[')?.split_once(']')?;
+    let (end, _) = rest.strip_prefix("--[")?.split_once(']')?;
+
+    Some(ClockEntry {
+        start: parse_timestamp_inner(start)?,
+        end: parse_timestamp_inner(end)?,
+    })
+}
+
+/// Parse the inside of a timestamp, without its brackets: `2024-01-15 Mon 10:30`
+pub fn parse_timestamp_inner(inner: &str) -> Option<OrgDatetime> {
+    let mut parts = inner.split_whitespace();
+    let mut datetime = OrgDatetime::from_date_string(parts.next()?)?;
+
+    if let Some(time) = parts.find(|part| part.contains(':')) {
+        let (hour, minute) = time.split_once(':')?;
+        datetime.hour = Some(hour.parse().ok()?);
+        datetime.minute = Some(minute.get(..2)?.parse().ok()?);
+    }
+
+    Some(datetime)
+}
+
+// Lines of `content`, checking that a 1-based line is a headline
+// Returns the lines and the headline's 0-based index
+pub(crate) fn headline_lines(content: &str, headline_line: usize) -> Option<(Vec<String>, usize)> {
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let headline = headline_line.checked_sub(1)?;
+    headline_level(lines.get(headline)?)?;
+    Some((lines, headline))
+}
+
+// Join edited lines, ending with a newline if `content` did
+pub(crate) fn join_lines(lines: Vec<String>, content: &str) -> String {
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+// A planning line without its CLOSED timestamp
+fn without_closed(line: &str) -> String {
+    match line.find("CLOSED:") {
+        Some(start) => {
+            let end = line[start..]
+                .find(']')
+                .map_or(line.len(), |offset| start + offset + 1);
+            let rest = format!("{}{}", &line[..start], line[end..].trim_start());
+            rest.trim_end().to_string()
+        }
+        None => line.to_string(),
+    }
+}
+
+/// Set or clear the CLOSED timestamp of the headline on a 1-based line
+/// CLOSED goes first on the planning line, as org-mode writes it, and a planning line
+/// left empty is removed. Returns None if the line is not a headline
+pub fn set_closed(
+    content: &str,
+    headline_line: usize,
+    closed: Option<NaiveDateTime>,
+) -> Option<String> {
+    let (mut lines, headline) = headline_lines(content, headline_line)?;
+    let planning = headline + 1;
+    let existing = lines
+        .get(planning)
+        .filter(|line| is_planning_line(line))
+        .map(|line| without_closed(line));
+    let closed = closed.map(|closed| closed.format("CLOSED: [%Y-%m-%d %a %H:%M]").to_string());
+
+    match (existing, closed) {
+        (Some(rest), Some(closed)) => {
+            let indent = &rest[..rest.len() - rest.trim_start().len()];
+            lines[planning] = format!("{}{} {}", indent, closed, rest.trim_start())
+                .trim_end()
+                .to_string();
+        }
+        (Some(rest), None) if rest.trim().is_empty() => {
+            lines.remove(planning);
+        }
+        (Some(rest), None) => lines[planning] = rest,
+        (None, Some(closed)) => lines.insert(planning, closed),
+        (None, None) => {}
+    }
+    Some(join_lines(lines, content))
+}
+
+// Index of the line after a headline's planning line and property drawer, where its
+// log starts
+fn log_position(lines: &[String], headline: usize) -> Option<usize> {
+    let mut position = headline + 1;
+    if lines
+        .get(position)
+        .is_some_and(|line| is_planning_line(line))
+    {
+        position += 1;
+    }
+    if lines
+        .get(position)
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case(":PROPERTIES:"))
+    {
+        position += lines[position..]
+            .iter()
+            .position(|line| line.trim().eq_ignore_ascii_case(":END:"))?
+            + 1;
+    }
+    Some(position)
+}
+
+// Insert `entry` at the top of the LOGBOOK drawer at `position`, creating the drawer there
+// if there is none
+fn insert_into_logbook(lines: &mut Vec<String>, mut position: usize, mut entry: Vec<String>) {
+    if lines
+        .get(position)
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case(":LOGBOOK:"))
+    {
+        position += 1;
+    } else {
+        entry.insert(0, ":LOGBOOK:".to_string());
+        entry.push(":END:".to_string());
+    }
+    lines.splice(position..position, entry);
+}
+
+/// Add a note such as `- CLOSING NOTE [2024-01-15 Mon 10:30] \\` to the headline on a
+/// 1-based line, after its planning line and property drawer
+/// With `into_drawer` the note goes at the top of the LOGBOOK drawer, which is created if
+/// needed. Returns None if the line is not a headline
+pub fn add_log_note(
+    content: &str,
+    headline_line: usize,
+    heading: &str,
+    text: &str,
+    into_drawer: bool,
+) -> Option<String> {
+    let (mut lines, headline) = headline_lines(content, headline_line)?;
+    let position = log_position(&lines, headline)?;
+
+    let entry = if text.trim().is_empty() {
+        vec![format!("- {}", heading)]
+    } else {
+        let mut entry = vec![format!("- {} \\\\", heading)];
+        entry.extend(text.lines().map(|line| format!("  {}", line.trim_end())));
+        entry
+    };
+    if into_drawer {
+        insert_into_logbook(&mut lines, position, entry);
+    } else {
+        lines.splice(position..position, entry);
+    }
+    Some(join_lines(lines, content))
+}
+
+/// Whether a headline's own text has a running clock, a CLOCK line without an end
+pub fn has_running_clock(section: &str) -> bool {
+    section.lines().any(|line| {
+        line.trim_start()
+            .strip_prefix("CLOCK:")
+            .is_some_and(|rest| !rest.contains("]--["))
+    })
+}
+
+/// Start a clock on the headline on a 1-based line, adding `CLOCK: [2024-01-15 Mon 10:30]`
+/// at the top of its LOGBOOK drawer, which is created if needed
+/// Returns None if the line is not a headline
+pub fn clock_in(content: &str, headline_line: usize, now: NaiveDateTime) -> Option<String> {
+    let (mut lines, headline) = headline_lines(content, headline_line)?;
+    let position = log_position(&lines, headline)?;
+    let clock = now.format("CLOCK: [%Y-%m-%d %a %H:%M]").to_string();
+    insert_into_logbook(&mut lines, position, vec![clock]);
+    Some(join_lines(lines, content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state_change() {
+        let change = parse_state_change(
+            r#"  - State "DONE"       from "TODO"       [2024-01-15 Mon 10:30]"#,
+        )
+        .unwrap();
+
+        assert_eq!(change.to, "DONE");
+        assert_eq!(change.from.as_deref(), Some("TODO"));
+        assert_eq!(
+            change.timestamp,
+            OrgDatetime::with_time(2024, 1, 15, "Mon", 10, 30)
+        );
+    }
+
+    #[test]
+    fn test_parse_state_changes_skips_other_lines() {
+        let content = r#":LOGBOOK:
+- State "DONE"       from ""           [2024-02-01 Thu]
+- Note taken on [2024-01-30 Tue 09:00] \\
+  Waiting for review
+- State "WAITING"    from "TODO"       [2024-01-29 Mon 17:05]
+:END:"#;
+
+        let changes = parse_state_changes(content);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].to, "DONE");
+        assert_eq!(changes[0].from, None);
+        assert_eq!(changes[0].timestamp.hour, None);
+        assert_eq!(changes[1].to, "WAITING");
+    }
+
+    #[test]
+    fn test_parse_clock_entries() {
+        let content = ":LOGBOOK:\nCLOCK: [2024-01-15 Mon 09:00]--[2024-01-15 Mon 10:30] =>  1:30\nCLOCK: [2024-01-16 Tue 23:30]--[2024-01-17 Wed 00:15] =>  0:45\nCLOCK: [2024-01-18 Thu 08:00]\n:END:";
+
+        let entries = parse_clock_entries(content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].minutes(), 90);
+        assert_eq!(entries[1].start.day, 16);
+        assert_eq!(entries[1].minutes(), 45);
+    }
+
+    #[test]
+    fn test_set_closed() {
+        let closed = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 0)
+            .unwrap();
+        let content = "* DONE Task\n  CLOSED: [2024-01-01 Mon 09:00] DEADLINE: <2024-01-02 Tue>\n* DONE Other\nCLOSED: [2024-01-01 Mon 09:00]\n";
+
+        let updated = set_closed(content, 1, Some(closed)).unwrap();
+        assert!(updated.starts_with(
+            "* DONE Task\n  CLOSED: [2024-01-15 Mon 10:30] DEADLINE: <2024-01-02 Tue>\n"
+        ));
+
+        // Reopening drops the timestamp, and the planning line if nothing else is on it
+        let reopened = set_closed(content, 1, None).unwrap();
+        assert!(reopened.starts_with("* DONE Task\n  DEADLINE: <2024-01-02 Tue>\n"));
+        assert_eq!(
+            set_closed(content, 3, None).unwrap(),
+            "* DONE Task\n  CLOSED: [2024-01-01 Mon 09:00] DEADLINE: <2024-01-02 Tue>\n* DONE Other\n"
+        );
+        assert!(set_closed(content, 2, None).is_none());
+    }
+
+    #[test]
+    fn test_clock_in() {
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 0)
+            .unwrap();
+        let content = "* TODO Task\nSCHEDULED: <2024-01-15 Mon>\n:PROPERTIES:\n:ID: task\n:END:\nNotes\n* TODO Other\n:LOGBOOK:\nCLOCK: [2024-01-14 Sun 09:00]--[2024-01-14 Sun 10:00] =>  1:00\n:END:\n";
+
+        let clocked = clock_in(content, 1, now).unwrap();
+        assert_eq!(
+            clocked,
+            "* TODO Task\nSCHEDULED: <2024-01-15 Mon>\n:PROPERTIES:\n:ID: task\n:END:\n:LOGBOOK:\nCLOCK: [2024-01-15 Mon 10:30]\n:END:\nNotes\n* TODO Other\n:LOGBOOK:\nCLOCK: [2024-01-14 Sun 09:00]--[2024-01-14 Sun 10:00] =>  1:00\n:END:\n"
+        );
+        assert!(has_running_clock(&clocked));
+        assert!(!has_running_clock(content));
+
+        // An existing drawer gets the new clock at the top
+        assert!(clock_in(content, 7, now)
+            .unwrap()
+            .ends_with(":LOGBOOK:\nCLOCK: [2024-01-15 Mon 10:30]\nCLOCK: [2024-01-14 Sun 09:00]--[2024-01-14 Sun 10:00] =>  1:00\n:END:\n"));
+        assert!(clock_in(content, 2, now).is_none());
+    }
+}
@@ -0,0 +1,312 @@
+use crate::orgmode::latex::latex_at;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Emphasis applied to a run of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum RunStyle {
+    Bold,      // *bold*
+    Italic,    // /italic/
+    Underline, // _underline_
+    Verbatim,  // =verbatim=
+    Code,      // ~code~
+    Strike,    // +strike+
+}
+
+/// What a run of text represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum RunKind {
+    Text,
+    Link,  // `target` holds the link target, `text` its description
+    Latex, // `target` holds the LaTeX source, `text` the raw markup
+}
+
+/// A piece of text with uniform styling
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct TextRun {
+    pub text: String,
+    pub styles: Vec<RunStyle>,
+    pub kind: RunKind,
+    pub target: Option<String>,
+}
+
+/// Headline title and body as styled runs, with the body split into paragraphs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct RichText {
+    pub title: Vec<TextRun>,
+    pub paragraphs: Vec<Vec<TextRun>>,
+}
+
+fn marker_style(marker: char) -> Option<RunStyle> {
+    match marker {
+        '*' => Some(RunStyle::Bold),
+        '/' => Some(RunStyle::Italic),
+        '_' => Some(RunStyle::Underline),
+        '=' => Some(RunStyle::Verbatim),
+        '~' => Some(RunStyle::Code),
+        '+' => Some(RunStyle::Strike),
+        _ => None,
+    }
+}
+
+// Org's emphasis borders: what may come before an opening and after a closing marker
+fn is_pre_border(c: Option<char>) -> bool {
+    c.is_none_or(|c| c.is_whitespace() || "-('\"{".contains(c))
+}
+
+fn is_post_border(c: Option<char>) -> bool {
+    c.is_none_or(|c| c.is_whitespace() || "-.,;:!?')}\"[\\".contains(c))
+}
+
+// Byte index of the marker closing an emphasis opened at `start`, if any
+fn emphasis_end(text: &str, start: usize, marker: char) -> Option<usize> {
+    let body_start = start + marker.len_utf8();
+    let first = text[body_start..].chars().next()?;
+    if first.is_whitespace() || !is_pre_border(text[..start].chars().next_back()) {
+        return None;
+    }
+
+    let mut search = body_start + first.len_utf8();
+    while let Some(offset) = text[search..].find(marker) {
+        let end = search + offset;
+        let body = &text[body_start..end];
+        if body.contains("\n\n") {
+            return None;
+        }
+        let before = text[..end].chars().next_back();
+        let after = text[end + marker.len_utf8()..].chars().next();
+        if before.is_some_and(|c| !c.is_whitespace()) && is_post_border(after) {
+            return Some(end);
+        }
+        search = end + marker.len_utf8();
+    }
+    None
+}
+
+fn push_text(runs: &mut Vec<TextRun>, text: &str, styles: &[RunStyle]) {
+    if text.is_empty() {
+        return;
+    }
+    match runs.last_mut() {
+        Some(last) if last.kind == RunKind::Text && last.styles == styles => {
+            last.text.push_str(text)
+        }
+        _ => runs.push(TextRun {
+            text: text.to_string(),
+            styles: styles.to_vec(),
+            kind: RunKind::Text,
+            target: None,
+        }),
+    }
+}
+
+fn parse_runs(text: &str, styles: &[RunStyle], runs: &mut Vec<TextRun>) {
+    let mut plain_start = 0;
+    let mut index = 0;
+
+    while index < text.len() {
+        let rest = &text[index..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        // Links: [[target]] or [[target][description]]
+        if rest.starts_with("[[") {
+            if let Some(end) = rest.find("]]") {
+                let inner = &rest[2..end];
+                let (target, description) = inner.split_once("][").unwrap_or((inner, inner));
+                push_text(runs, &text[plain_start..index], styles);
+                runs.push(TextRun {
+                    text: description.to_string(),
+                    styles: styles.to_vec(),
+                    kind: RunKind::Link,
+                    target: Some(target.to_string()),
+                });
+                index += end + 2;
+                plain_start = index;
+                continue;
+            }
+        }
+
+        if matches!(c, '\\' | '$') {
+            if let Some((end, _, latex, _)) = latex_at(text, index) {
+                push_text(runs, &text[plain_start..index], styles);
+                runs.push(TextRun {
+                    text: text[index..end].to_string(),
+                    styles: styles.to_vec(),
+                    kind: RunKind::Latex,
+                    target: Some(latex),
+                });
+                index = end;
+                plain_start = index;
+                continue;
+            }
+        }
+
+        if let Some(style) = marker_style(c).filter(|style| !styles.contains(style)) {
+            if let Some(end) = emphasis_end(text, index, c) {
+                push_text(runs, &text[plain_start..index], styles);
+                let body = &text[index + c.len_utf8()..end];
+                let mut inner_styles = styles.to_vec();
+                inner_styles.push(style);
+                if matches!(style, RunStyle::Verbatim | RunStyle::Code) {
+                    // Verbatim text is not parsed further
+                    push_text(runs, body, &inner_styles);
+                } else {
+                    parse_runs(body, &inner_styles, runs);
+                }
+                index = end + c.len_utf8();
+                plain_start = index;
+                continue;
+            }
+        }
+
+        index += c.len_utf8();
+    }
+
+    push_text(runs, &text[plain_start..], styles);
+}
+
+/// Split org text into styled runs, resolving emphasis markers, links, and LaTeX
+pub fn parse_text_runs(text: &str) -> Vec<TextRun> {
+    let mut runs = Vec::new();
+    parse_runs(text, &[], &mut runs);
+    runs
+}
+
+/// A paragraph of body text or a `#+BEGIN_...`/`#+END_...` block
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentBlock {
+    Paragraph(String),
+    Block {
+        kind: String,       // Lowercased block type, e.g. "src" or "export"
+        parameters: String, // Rest of the `#+BEGIN_` line, e.g. "rust" or "html"
+        body: String,
+    },
+}
+
+/// Split body text into paragraphs and blocks
+/// An unterminated block is treated as paragraph text
+pub fn split_blocks(content: &str) -> Vec<ContentBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut open: Option<(String, String, Vec<&str>)> = None;
+
+    let flush = |lines: &mut Vec<&str>, blocks: &mut Vec<ContentBlock>| {
+        if !lines.is_empty() {
+            blocks.push(ContentBlock::Paragraph(lines.join("\n")));
+            lines.clear();
+        }
+    };
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let upper = trimmed.to_ascii_uppercase();
+        if let Some((kind, parameters, lines)) = open.as_mut() {
+            if upper.starts_with("#+END_") {
+                blocks.push(ContentBlock::Block {
+                    kind: std::mem::take(kind),
+                    parameters: std::mem::take(parameters),
+                    body: std::mem::take(lines).join("\n"),
+                });
+                open = None;
+            } else {
+                lines.push(line);
+            }
+        } else if upper.starts_with("#+BEGIN_") {
+            flush(&mut current, &mut blocks);
+            let header = &trimmed["#+BEGIN_".len()..];
+            let (kind, parameters) = header
+                .split_once(char::is_whitespace)
+                .unwrap_or((header, ""));
+            open = Some((
+                kind.to_lowercase(),
+                parameters.trim().to_string(),
+                Vec::new(),
+            ));
+        } else if line.trim().is_empty() {
+            flush(&mut current, &mut blocks);
+        } else {
+            current.push(line);
+        }
+    }
+    if let Some((_, _, lines)) = open {
+        current.extend(lines);
+    }
+    flush(&mut current, &mut blocks);
+
+    blocks
+}
+
+/// Split body text into paragraphs of styled runs
+/// `#+BEGIN_...`/`#+END_...` blocks are kept verbatim as a single code run
+pub fn parse_paragraphs(content: &str) -> Vec<Vec<TextRun>> {
+    split_blocks(content)
+        .into_iter()
+        .map(|block| match block {
+            ContentBlock::Paragraph(text) => parse_text_runs(&text),
+            ContentBlock::Block { body, .. } => vec![TextRun {
+                text: body,
+                styles: vec![RunStyle::Code],
+                kind: RunKind::Text,
+                target: None,
+            }],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn styled(runs: &[TextRun]) -> Vec<(&str, Vec<RunStyle>)> {
+        runs.iter()
+            .map(|run| (run.text.as_str(), run.styles.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_text_runs() {
+        let runs = parse_text_runs("Some *bold /and italic/* text, =a*b*c= and +gone+.");
+        assert_eq!(
+            styled(&runs),
+            vec![
+                ("Some ", vec![]),
+                ("bold ", vec![RunStyle::Bold]),
+                ("and italic", vec![RunStyle::Bold, RunStyle::Italic]),
+                (" text, ", vec![]),
+                ("a*b*c", vec![RunStyle::Verbatim]),
+                (" and ", vec![]),
+                ("gone", vec![RunStyle::Strike]),
+                (".", vec![]),
+            ]
+        );
+
+        // Markers inside words or next to spaces are literal
+        assert_eq!(styled(&parse_text_runs("a*b*c and * not bold *")).len(), 1);
+        assert_eq!(styled(&parse_text_runs("snake_case_name")).len(), 1);
+    }
+
+    #[test]
+    fn test_links_and_latex_runs() {
+        let runs = parse_text_runs("See [[https://orgmode.org][the /manual/]] for \\alpha");
+        assert_eq!(runs[1].kind, RunKind::Link);
+        assert_eq!(runs[1].target.as_deref(), Some("https://orgmode.org"));
+        assert_eq!(runs[1].text, "the /manual/");
+        assert_eq!(runs[3].kind, RunKind::Latex);
+        assert_eq!(runs[3].target.as_deref(), Some("\\alpha"));
+    }
+
+    #[test]
+    fn test_parse_paragraphs() {
+        let paragraphs = parse_paragraphs(
+            "First *para*\ncontinues\n\n#+BEGIN_SRC rust\nlet *x* = 1;\n#+END_SRC\nLast",
+        );
+        assert_eq!(paragraphs.len(), 3);
+        assert_eq!(paragraphs[0][0].text, "First ");
+        assert_eq!(paragraphs[0][2].text, "\ncontinues");
+        assert_eq!(
+            styled(&paragraphs[1]),
+            vec![("let *x* = 1;", vec![RunStyle::Code])]
+        );
+        assert_eq!(paragraphs[2][0].text, "Last");
+    }
+}
@@ -0,0 +1,106 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::flatten_headlines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::mem::size_of;
+
+/// Approximate memory held by the repository
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct MemoryStats {
+    pub documents: u32,
+    pub headlines: u32,
+    pub evicted_documents: u32, // Documents whose body content is unloaded
+    pub interned_strings: u32,
+    pub estimated_bytes: u32,
+}
+
+/// Rough heap and inline size of a document, counting string contents
+pub fn estimate_document_bytes(document: &OrgDocument) -> usize {
+    let strings = document.id.len()
+        + document.title.len()
+        + document.content.len()
+        + document.file_path.len()
+        + document.etag.len()
+        + document.category.len()
+        + document.filetags.iter().map(String::len).sum::<usize>()
+        + document
+            .properties
+            .iter()
+            .map(|(key, value)| key.len() + value.len())
+            .sum::<usize>();
+
+    let headlines: usize = flatten_headlines(&document.headlines)
+        .into_iter()
+        .map(estimate_headline_bytes)
+        .sum();
+
+    size_of::<OrgDocument>() + strings + headlines
+}
+
+// Size of a single headline, without its children
+fn estimate_headline_bytes(headline: &OrgHeadline) -> usize {
+    // Tags, keywords, and property keys are interned and shared, so only the pointers count
+    let title = &headline.title;
+    size_of::<OrgHeadline>()
+        + headline.id.len()
+        + headline.document_id.len()
+        + headline.content.len()
+        + headline.etag.len()
+        + title.raw.len()
+        + title.tags.len() * size_of::<usize>() * 2
+        + title
+            .properties
+            .values()
+            .map(|value| size_of::<usize>() * 2 + value.len())
+            .sum::<usize>()
+}
+
+/// Drop the body text of a document and its headlines, keeping the outline
+/// `#+KEYWORD:` lines such as `#+TODO:` are kept so file settings still apply
+/// Returns the number of bytes released
+pub fn strip_body_content(document: &mut OrgDocument) -> usize {
+    fn strip(headlines: &mut [OrgHeadline]) -> usize {
+        headlines
+            .iter_mut()
+            .map(|headline| {
+                let released = std::mem::take(&mut headline.content).len();
+                released + strip(&mut headline.children)
+            })
+            .sum()
+    }
+
+    let keywords: String = document
+        .content
+        .lines()
+        .filter(|line| line.trim_start().starts_with("#+"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let released = document.content.len().saturating_sub(keywords.len());
+    document.content = keywords;
+
+    released + strip(&mut document.headlines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_strip_body_content() {
+        let mut doc = parse_org_document(
+            "#+TODO: NEXT | DONE\n* Outline\nSome body text.\n** Child\nMore text.\n",
+            Some("body.org"),
+        )
+        .unwrap();
+        let before = estimate_document_bytes(&doc);
+
+        let released = strip_body_content(&mut doc);
+        assert!(released > 0);
+        assert_eq!(estimate_document_bytes(&doc), before - released);
+        assert_eq!(doc.headlines[0].title.raw, "Outline");
+        assert!(doc.headlines[0].children[0].content.is_empty());
+        assert_eq!(doc.content, "#+TODO: NEXT | DONE\n");
+    }
+}
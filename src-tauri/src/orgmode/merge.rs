@@ -0,0 +1,275 @@
+use crate::orgmode::conflicts::{entries, parse};
+use crate::orgmode::source::{headline_sections, source_lines};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+
+/// Event emitted with a `WriteConflict` when an edit could not be merged automatically
+pub const WRITE_CONFLICT_EVENT: &str = "write-conflict";
+
+/// Which version wins where both changed a headline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum MergeSide {
+    Local, // The edit made in the app
+    Disk,  // The file as changed outside the app
+}
+
+/// A headline that the edit and the file on disk both changed differently
+/// Sections are None where the headline does not exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct MergeConflict {
+    pub outline: Vec<String>, // Titles from the top-level headline down; empty for the preamble
+    pub base: Option<String>, // The section when the file was loaded
+    pub local: Option<String>,
+    pub disk: Option<String>,
+}
+
+/// An edit to a file that changed on disk, waiting for the conflicts to be resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct WriteConflict {
+    pub document_id: String,
+    pub file_path: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// The result of merging an edit with the file on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Merged(String),
+    Conflicts(Vec<MergeConflict>),
+}
+
+// The preamble and each headline's own section, keyed like `entries`
+struct Sections {
+    preamble: String,
+    order: Vec<String>,
+    sections: HashMap<String, (Vec<String>, String)>, // Key => (outline, section)
+}
+
+fn split_sections(
+    content: &str,
+    keywords: &(Vec<String>, Vec<String>),
+) -> Result<Sections, String> {
+    let document = parse(content, keywords)?;
+    let preamble: String = source_lines(&document)
+        .into_iter()
+        .filter(|line| line.headline_id.is_none())
+        .map(|line| format!("{}\n", line.text))
+        .collect();
+    let mut own = headline_sections(&document);
+    let mut order = Vec::new();
+    let mut sections = HashMap::new();
+    for entry in entries(&document) {
+        let section = own.remove(&entry.headline.id).unwrap_or_default();
+        order.push(entry.key.clone());
+        sections.insert(entry.key, (entry.outline, section));
+    }
+    Ok(Sections {
+        preamble,
+        order,
+        sections,
+    })
+}
+
+impl Sections {
+    fn section(&self, key: &str) -> Option<&str> {
+        self.sections.get(key).map(|(_, section)| section.as_str())
+    }
+}
+
+// Merge one section: a side that kept the base takes the other side's change
+fn merge_section<'a>(
+    base: Option<&'a str>,
+    local: Option<&'a str>,
+    disk: Option<&'a str>,
+) -> Result<Option<&'a str>, ()> {
+    if local == disk || local == base {
+        Ok(disk)
+    } else if disk == base {
+        Ok(local)
+    } else {
+        Err(())
+    }
+}
+
+fn merge(
+    base: &str,
+    local: &str,
+    disk: &str,
+    keywords: &(Vec<String>, Vec<String>),
+    prefer: Option<MergeSide>,
+) -> Result<MergeOutcome, String> {
+    let (base_sections, local_sections, disk_sections) = (
+        split_sections(base, keywords)?,
+        split_sections(local, keywords)?,
+        split_sections(disk, keywords)?,
+    );
+    let mut conflicts = Vec::new();
+    let mut resolve = |outline: Vec<String>,
+                       base: Option<&str>,
+                       local: Option<&str>,
+                       disk: Option<&str>|
+     -> Option<String> {
+        match (merge_section(base, local, disk), prefer) {
+            (Ok(merged), _) => merged.map(str::to_string),
+            (Err(()), Some(MergeSide::Local)) => local.map(str::to_string),
+            (Err(()), Some(MergeSide::Disk)) => disk.map(str::to_string),
+            (Err(()), None) => {
+                conflicts.push(MergeConflict {
+                    outline,
+                    base: base.map(str::to_string),
+                    local: local.map(str::to_string),
+                    disk: disk.map(str::to_string),
+                });
+                None
+            }
+        }
+    };
+
+    // Headlines follow the file on disk, with those only the edit has placed after the
+    // headline they follow in the edit
+    let mut order = disk_sections.order.clone();
+    let on_disk: HashSet<&String> = disk_sections.order.iter().collect();
+    let mut previous: Option<&String> = None;
+    for key in &local_sections.order {
+        if !on_disk.contains(key) {
+            let at = previous
+                .and_then(|previous| order.iter().position(|key| key == previous))
+                .map_or(0, |index| index + 1);
+            order.insert(at, key.clone());
+        }
+        previous = Some(key);
+    }
+    // Headlines the edit deleted that the file changed still need a decision
+    for key in &base_sections.order {
+        if !order.contains(key) {
+            order.push(key.clone());
+        }
+    }
+
+    let mut merged = resolve(
+        Vec::new(),
+        Some(&base_sections.preamble),
+        Some(&local_sections.preamble),
+        Some(&disk_sections.preamble),
+    )
+    .unwrap_or_default();
+    for key in &order {
+        let outline = [&disk_sections, &local_sections, &base_sections]
+            .into_iter()
+            .find_map(|sections| sections.sections.get(key))
+            .map(|(outline, _)| outline.clone())
+            .unwrap_or_default();
+        if let Some(text) = resolve(
+            outline,
+            base_sections.section(key),
+            local_sections.section(key),
+            disk_sections.section(key),
+        ) {
+            merged.push_str(&text);
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Ok(MergeOutcome::Conflicts(conflicts));
+    }
+    if !disk.ends_with('\n') && !local.ends_with('\n') {
+        merged.pop();
+    }
+    if disk.contains("\r\n") {
+        merged = merged.replace('\n', "\r\n");
+    }
+    Ok(MergeOutcome::Merged(merged))
+}
+
+/// Merge an edit of `base` with the file as it is now on `disk`, headline by headline
+/// A headline changed on one side only takes that change, so edits to different headlines
+/// combine. Headlines changed differently on both sides are returned as conflicts.
+pub fn merge_edits(
+    base: &str,
+    local: &str,
+    disk: &str,
+    keywords: &(Vec<String>, Vec<String>),
+) -> Result<MergeOutcome, String> {
+    merge(base, local, disk, keywords, None)
+}
+
+/// Merge like `merge_edits`, settling conflicts in favor of `side`
+pub fn merge_edits_preferring(
+    base: &str,
+    local: &str,
+    disk: &str,
+    keywords: &(Vec<String>, Vec<String>),
+    side: MergeSide,
+) -> Result<String, String> {
+    match merge(base, local, disk, keywords, Some(side))? {
+        MergeOutcome::Merged(merged) => Ok(merged),
+        MergeOutcome::Conflicts(_) => Err("Conflicts were left unresolved".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords() -> (Vec<String>, Vec<String>) {
+        (vec!["TODO".to_string()], vec!["DONE".to_string()])
+    }
+
+    const BASE: &str = "#+TITLE: Plan\n* TODO Report\n* TODO Review\n* Notes\n";
+
+    #[test]
+    fn test_merge_different_headlines() {
+        // The app closed Report while another editor added a note and a headline
+        let local = "#+TITLE: Plan\n* DONE Report\n* TODO Review\n* Notes\n";
+        let disk = "#+TITLE: Plan\n* TODO Report\n* TODO Review\n* Notes\nCall Ann\n* Ideas\n";
+        assert_eq!(
+            merge_edits(BASE, local, disk, &keywords()).unwrap(),
+            MergeOutcome::Merged(
+                "#+TITLE: Plan\n* DONE Report\n* TODO Review\n* Notes\nCall Ann\n* Ideas\n"
+                    .to_string()
+            )
+        );
+
+        // Headlines added by the edit stay after the headline they followed
+        let local = "#+TITLE: Plan\n* TODO Report\n** TODO Outline\n* TODO Review\n* Notes\n";
+        let disk = "#+TITLE: Plan\n* TODO Review\n* TODO Report\n* Notes\n";
+        assert_eq!(
+            merge_edits(BASE, local, disk, &keywords()).unwrap(),
+            MergeOutcome::Merged(
+                "#+TITLE: Plan\n* TODO Review\n* TODO Report\n** TODO Outline\n* Notes\n"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let local = "#+TITLE: Plan\n* DONE Report\n* TODO Review\n* Notes\n";
+        let disk = "#+TITLE: Plan\n* TODO Report\nDue Friday\n* Notes\n";
+        let MergeOutcome::Conflicts(conflicts) =
+            merge_edits(BASE, local, disk, &keywords()).unwrap()
+        else {
+            panic!("expected a conflict");
+        };
+        assert_eq!(
+            conflicts,
+            vec![MergeConflict {
+                outline: vec!["Report".to_string()],
+                base: Some("* TODO Report\n".to_string()),
+                local: Some("* DONE Report\n".to_string()),
+                disk: Some("* TODO Report\nDue Friday\n".to_string()),
+            }]
+        );
+
+        // Review was deleted on disk and left alone by the edit, so it stays deleted
+        assert_eq!(
+            merge_edits_preferring(BASE, local, disk, &keywords(), MergeSide::Local).unwrap(),
+            "#+TITLE: Plan\n* DONE Report\n* Notes\n"
+        );
+        assert_eq!(
+            merge_edits_preferring(BASE, local, disk, &keywords(), MergeSide::Disk).unwrap(),
+            disk
+        );
+    }
+}
@@ -1,5 +1,7 @@
+use crate::orgmode::colors::{tag_style, StatusStyle};
 use crate::orgmode::document::OrgDocument;
 use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::time_zone;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::HashMap;
@@ -12,6 +14,7 @@ pub struct TagInfo {
     pub count: usize,           // Number of occurrences
     pub documents: Vec<String>, // Document IDs where this tag appears
     pub headlines: Vec<String>, // Headline IDs where this tag appears
+    pub style: StatusStyle,     // Style chosen for the tag in the settings
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -35,7 +38,7 @@ impl GlobalMetadata {
         Self {
             tags: HashMap::new(),
             categories: HashMap::new(),
-            last_updated: chrono::Utc::now().to_rfc3339(),
+            last_updated: time_zone::now_rfc3339(),
         }
     }
 
@@ -46,6 +49,7 @@ impl GlobalMetadata {
             count: 0,
             documents: Vec::new(),
             headlines: Vec::new(),
+            style: tag_style(tag),
         });
 
         tag_info.count += 1;
@@ -58,7 +62,7 @@ impl GlobalMetadata {
             tag_info.headlines.push(headline_id.to_string());
         }
 
-        self.last_updated = chrono::Utc::now().to_rfc3339();
+        self.last_updated = time_zone::now_rfc3339();
     }
 
     // Register a category from a headline or document
@@ -90,7 +94,7 @@ impl GlobalMetadata {
             }
         }
 
-        self.last_updated = chrono::Utc::now().to_rfc3339();
+        self.last_updated = time_zone::now_rfc3339();
     }
 
     // Get all tags sorted by occurrence count
@@ -156,8 +160,8 @@ impl MetadataManager {
             metadata.register_category(&document.category, &document.id, None);
         }
 
-        // Register document properties
-        for (key, value) in &document.properties {
+        // Register document keywords
+        for (key, value) in &document.keywords {
             if key.starts_with("CATEGORY_") {
                 metadata.register_category(value, &document.id, None);
             }
@@ -0,0 +1,171 @@
+use crate::orgmode::checklist::parse_list_item;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::{headline_level, source_lines};
+use crate::orgmode::subtree::subtree_lines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+// Line prefixes, before a colon, that mark a line of notes as an action item
+const ACTION_PHRASES: &[&str] = &[
+    "TODO",
+    "ACTION",
+    "ACTION ITEM",
+    "AI",
+    "FOLLOW UP",
+    "FOLLOW-UP",
+];
+
+/// How an action item was spotted in meeting notes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ActionSource {
+    Checkbox, // An unchecked `- [ ]` list item
+    Phrase,   // A line like `TODO: ...`, `Action: ...` or `Follow up: ...`
+}
+
+/// A task proposed from the notes of a meeting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ActionItem {
+    pub text: String,
+    pub line: u32, // 1-based line number in the notes file
+    pub source: ActionSource,
+}
+
+// The text after an action phrase, like "Send slides" in "- Action: Send slides"
+fn action_phrase(line: &str) -> Option<&str> {
+    let text = parse_list_item(line).map_or(line.trim(), |item| item.text);
+    let (phrase, rest) = text.split_once(':')?;
+    let rest = rest.trim();
+    (!rest.is_empty()
+        && ACTION_PHRASES
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(phrase.trim())))
+    .then_some(rest)
+}
+
+/// Find the action items in the notes under a headline and its descendants: unchecked
+/// checkboxes and lines starting with an action phrase, in file order
+/// Headline lines and blocks are skipped. Returns None if the headline is not found.
+pub fn extract_action_items(document: &OrgDocument, headline_id: &str) -> Option<Vec<ActionItem>> {
+    let range = subtree_lines(document, headline_id)?;
+    let mut items = Vec::new();
+    let mut in_block = false;
+
+    for line in &source_lines(document)[range] {
+        let upper = line.text.trim_start().to_ascii_uppercase();
+        if upper.starts_with("#+BEGIN_") {
+            in_block = true;
+        } else if upper.starts_with("#+END_") {
+            in_block = false;
+        }
+        if in_block || headline_level(line.text).is_some() {
+            continue;
+        }
+
+        let (text, source) = match parse_list_item(line.text) {
+            Some(item) if item.checkbox == Some(' ') && !item.text.trim().is_empty() => {
+                (item.text.trim(), ActionSource::Checkbox)
+            }
+            _ => match action_phrase(line.text) {
+                Some(text) => (text, ActionSource::Phrase),
+                None => continue,
+            },
+        };
+        items.push(ActionItem {
+            text: text.to_string(),
+            line: line.number as u32,
+            source,
+        });
+    }
+    Some(items)
+}
+
+/// A link to a meeting headline: by its ID property if it has one, else by file and title
+/// Brackets in the title are escaped with backslashes in the search target, as Org does, so
+/// the link still finds the exact title; the description shows them as braces.
+pub fn meeting_link(document: &OrgDocument, headline: &OrgHeadline) -> String {
+    let title = &headline.title.raw;
+    let description = title.replace('[', "{").replace(']', "}");
+    match headline.title.get_property_ignore_case("ID") {
+        Some(id) => format!("[[id:{}][{}]]", id.trim(), description),
+        None => format!(
+            "[[file:{}::*{}][{}]]",
+            document.file_path,
+            escape_link_target(title),
+            description
+        ),
+    }
+}
+
+// Org's link escaping: a backslash before each bracket, and before backslashes that precede
+// a bracket or end the target
+fn escape_link_target(target: &str) -> String {
+    let chars: Vec<char> = target.chars().collect();
+    let mut escaped = String::with_capacity(target.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '\\' {
+            let run_end = chars[i..].iter().position(|&c| c != '\\').map(|n| i + n);
+            if run_end.is_none_or(|end| matches!(chars[end], '[' | ']')) {
+                escaped.push('\\');
+            }
+        } else if matches!(c, '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Task headlines for accepted action items, each linking back to the meeting
+pub fn action_item_entries(items: &[String], meeting_link: &str) -> String {
+    items
+        .iter()
+        .map(|item| format!("* TODO {}\nFrom {}\n", item.trim(), meeting_link))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_extract_action_items() {
+        let doc = parse_org_document(
+            "* Weekly sync\n- [ ] Book the venue\n- [X] Send agenda\n- Action: Share the slides\nDecided: keep Fridays\n** Budget\nTODO: ask finance for numbers\n#+BEGIN_SRC text\nAI: not a task\n#+END_SRC\n* TODO Follow up: after the sync\n",
+            Some("/notes/meetings.org"),
+        )
+        .unwrap();
+
+        let items = extract_action_items(&doc, "1").unwrap();
+        let found: Vec<(&str, u32, ActionSource)> = items
+            .iter()
+            .map(|item| (item.text.as_str(), item.line, item.source))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Book the venue", 2, ActionSource::Checkbox),
+                ("Share the slides", 4, ActionSource::Phrase),
+                ("ask finance for numbers", 7, ActionSource::Phrase),
+            ]
+        );
+        assert!(extract_action_items(&doc, "9").is_none());
+
+        let headline = doc.find_headline("1").unwrap();
+        let link = meeting_link(&doc, headline);
+        assert_eq!(
+            link,
+            "[[file:/notes/meetings.org::*Weekly sync][Weekly sync]]"
+        );
+        let doc = parse_org_document("* Sync [2024-Q1] notes\n", Some("/notes/q1.org")).unwrap();
+        assert_eq!(
+            meeting_link(&doc, &doc.headlines[0]),
+            "[[file:/notes/q1.org::*Sync \\[2024-Q1\\] notes][Sync {2024-Q1} notes]]"
+        );
+        assert_eq!(
+            action_item_entries(&["Book the venue".to_string()], &link),
+            format!("* TODO Book the venue\nFrom {}\n", link)
+        );
+    }
+}
@@ -1,30 +1,256 @@
 // Re-export public items from submodules
+pub mod agenda;
+pub mod app_state;
+pub mod archive;
+pub mod audit;
+pub mod babel;
+pub mod bookmarks;
+pub mod capture;
+pub mod categories;
+pub mod checklist;
+pub mod citations;
+pub mod colors;
+pub mod columns;
+pub mod completed;
+pub mod conflicts;
+pub mod contacts;
+pub mod conventions;
 pub mod datetime;
+pub mod datetree;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod digest;
 pub mod document;
+pub mod drill;
+pub mod effort;
+pub mod favorites;
+pub mod file_keywords;
+pub mod flags;
+pub mod fuzzy;
+pub mod github_sync;
+pub mod grouping;
 pub mod headline;
+pub mod heatmap;
+pub mod highlights;
+pub mod hooks;
+pub mod html;
+pub mod ignore;
+pub mod images;
+pub mod intern;
+pub mod issues;
+pub mod journal;
+pub mod keywords;
+pub mod latex;
+pub mod links;
+pub mod locale;
+pub mod logbook;
+pub mod markup;
+pub mod memory;
+pub mod merge;
 pub mod metadata;
+pub mod minutes;
 pub mod monitor;
+pub mod narrow;
+pub mod next_actions;
+pub mod normalize;
+pub mod outline;
 pub mod parser;
+pub mod perf;
+pub mod planner;
 pub mod planning;
+pub mod plugins;
+pub mod projects;
+pub mod properties;
+pub mod recent;
+pub mod reminders;
+pub mod replace;
 pub mod repository;
+pub mod reschedule;
+pub mod restructure;
+pub mod rollup;
+pub mod search;
+pub mod share;
+pub mod snapshot;
+pub mod sort;
+pub mod source;
+pub mod speed;
+pub mod startup;
+pub mod subtree;
+pub mod tags;
+pub mod tickler;
+pub mod time_zone;
+pub mod timeline;
 pub mod timestamp;
 pub mod title;
 pub mod todo;
+pub mod trash;
 pub mod update;
+pub mod urgency;
 mod utils;
+pub mod verify;
+pub mod wordcount;
+pub mod work_schedule;
+pub mod writer;
 
 // Re-export commonly used types for convenience
-pub use datetime::OrgDatetime;
+pub use agenda::{
+    carry_overdue, collect_agenda, export_agenda, AgendaDay, AgendaEntry, AgendaFormat,
+    MAX_AGENDA_DAYS,
+};
+pub use app_state::{
+    read_bundle, write_bundle, AppStateBundle, AppStateSummary, APP_STATE_VERSION,
+};
+pub use archive::{append_to_archive, archive_path, archive_properties};
+pub use audit::{find_duplicates, DuplicateEntry, DuplicateKind, HeadlineLocation};
+pub use babel::{
+    collect_tangle_targets, find_source_blocks, parse_header_args, BlockResults, SourceBlock,
+    TangleTarget,
+};
+pub use bookmarks::{bookmark_entry, Bookmark, BookmarkConfig, BookmarkServer, READING_TAG};
+pub use capture::{capture_entry, file_entry, CaptureTarget, EntryPlacement};
+pub use categories::{
+    find_category_occurrences, rewrite_category, uses_category, CategoryOccurrence,
+};
+pub use checklist::{add_checklist_item, parse_list_item, remove_checklist_item, ListItem};
+pub use citations::{
+    collect_cited_keys, document_citations, extract_citations, Citation, CitationRef, CitedKey,
+    DocumentCitations,
+};
+pub use colors::{
+    document_tag_styles, keyword_color, keyword_style, set_style_overrides, tag_color, tag_style,
+    StatusStyle, StatusStyles, StyleOverride, TagColor,
+};
+pub use columns::{column_values, property_columns, property_value, PROPERTY_COLUMN_PREFIX};
+pub use completed::{collect_completed_tasks, CompletedCategory, CompletedDay, CompletedTask};
+pub use conflicts::{
+    conflict_original, diff_headlines, merge_conflict, ConflictResolution, HeadlineChange,
+    HeadlineDiff, SyncConflict,
+};
+pub use contacts::{collect_contacts, is_contact_link, Contact};
+pub use conventions::{conform_headline, FormatConventions, MetadataIndent};
+pub use datetime::{DateRange, OrgDatetime};
+pub use datetree::{ensure_datetree_day, file_under_datetree, find_datetree_day};
+pub use dependencies::{
+    completion_blockers, DependencyEdge, DependencyGraph, DependencyKind, DependencyNode,
+    UnresolvedDependency,
+};
+pub use diagnostics::{catch_parse_panic, ParseDiagnostic};
+pub use digest::{
+    collect_overdue, render_digest, DigestConfig, DigestPeriod, OverdueTask, DIGEST_READY_EVENT,
+};
 pub use document::OrgDocument;
+pub use drill::{collect_due_cards, Card, CardKind};
+pub use effort::{
+    effort_presets, is_valid_effort, DEFAULT_EFFORT_PRESETS, EFFORT_ALL_PROPERTY, EFFORT_PROPERTY,
+};
+pub use favorites::{Favorite, FavoriteHeadline};
+pub use flags::{DocumentFlags, FLAGS_KEYWORD};
+pub use fuzzy::{quick_find, QuickFindResult};
+pub use github_sync::{
+    apply_remote_issues, issues_to_close, sync_github_issues, GitHubSyncConfig,
+    GitHubSyncRepository, GitHubSyncSummary, RemoteIssue, GITHUB_ISSUE_PROPERTY,
+    GITHUB_STATE_PROPERTY,
+};
+pub use grouping::{group_tasks, GroupingMode, HeadlineGroup, HeadlineRef};
 pub use headline::OrgHeadline;
+pub use heatmap::{activity_heatmap, ActivityHeatmap, HeatmapDay};
+pub use highlights::{
+    file_highlights, parse_highlights, Highlight, HighlightImportSummary, HIGHLIGHT_ID_PROPERTY,
+};
+pub use hooks::{run_hook, spawn_hook, HookConfig, HookEvent, MAX_HOOK_TIMEOUT_SECONDS};
+pub use html::{escape_html, render_content_html, sanitize_html, HtmlSanitizeConfig};
+pub use ignore::{is_ignored, retain_unignored, IgnoreView, IgnoredTags};
+pub use images::{find_image_links, ImageLink, IMAGE_PROTOCOL};
+pub use intern::StringInterner;
+pub use issues::{
+    cache_issues, cached_issues, collect_issue_links, fetch_issues, find_issue_links, load_issues,
+    retry_delay, save_issues, IssueConfig, IssueInfo, IssueLink, IssueProvider, ISSUES_FILE_NAME,
+    ISSUES_UPDATED_EVENT, ISSUE_PROPERTIES,
+};
+pub use journal::{JournalConfig, JournalEntry, JournalPeriod};
+pub use keywords::{
+    apply_todo_keyword, find_keyword_occurrences, replace_headline_keyword, set_headline_keyword,
+    set_headline_priority, KeywordOccurrence,
+};
+pub use latex::{find_latex_spans, lookup_entity, LatexKind, LatexSpan};
+pub use links::{check_links, extract_links, BrokenLink, LinkKind, LinkProblem, OrgLink};
+pub use locale::{
+    day_name, month_calendar, supported_languages, CalendarMonth, DateLocale, DEFAULT_LANGUAGE,
+};
+pub use logbook::{
+    add_log_note, clock_in, has_running_clock, parse_state_changes, set_closed, StateChange,
+};
+pub use markup::{
+    parse_paragraphs, parse_text_runs, split_blocks, ContentBlock, RichText, RunKind, RunStyle,
+    TextRun,
+};
+pub use memory::MemoryStats;
+pub use merge::{
+    merge_edits, merge_edits_preferring, MergeConflict, MergeOutcome, MergeSide, WriteConflict,
+    WRITE_CONFLICT_EVENT,
+};
 pub use metadata::{CategoryInfo, GlobalMetadata, MetadataManager, TagInfo};
+pub use minutes::{
+    action_item_entries, extract_action_items, meeting_link, ActionItem, ActionSource,
+};
 pub use monitor::FileMonitor;
+pub use narrow::narrow_to_subtree;
+pub use next_actions::{collect_next_actions, WAITING_KEYWORDS};
+pub use outline::{headline_children, DocumentSummary, HeadlinePage, HeadlineSummary};
 pub use parser::{
     parse_org_document, parse_org_document_with_settings, parse_sample_org, OrgError,
 };
+pub use perf::{ParseTiming, ParseTimings, PerfStats};
+pub use planner::{suggest_schedule, PlannerConfig, ScheduleSuggestion};
 pub use planning::OrgPlanning;
-pub use repository::OrgDocumentRepository;
+pub use plugins::{
+    PluginDocument, PluginFunction, PluginFunctionKind, PluginHeadline, PluginHost, PluginInfo,
+    PLUGIN_API_VERSION, PLUGIN_DIR_NAME,
+};
+pub use projects::{collect_projects, Project, ProjectStatus};
+pub use properties::{
+    is_valid_property_key, matches_filters, remove_headline_property, set_headline_properties,
+    sort_by_property, sum_values, PropertyFilter, PropertyIndex, PropertyType, PropertyValue,
+    TypedValue,
+};
+pub use recent::{RecentAccess, RecentItem};
+pub use reminders::{Reminder, REMINDER_DUE_EVENT};
+pub use replace::{FileReplacePreview, ReplaceMatch, ReplaceQuery, ReplaceSummary};
+pub use repository::{
+    parse_into, reconcile_into, OrgDocumentRepository, ParsedFile, ReconcileOutcome,
+    ReconcileSummary,
+};
+pub use reschedule::{
+    plan_date_shifts, set_planning_date, shift_planning_date, DateDelta, DateShift, PlanningField,
+    ShiftFilter,
+};
+pub use restructure::{merge_headlines, split_headline, SplitMode};
+pub use rollup::Rollup;
+pub use search::{
+    find_in_document, split_date_terms, DateField, DateTerm, HeadlineMatches, MatchSpan, SearchHit,
+    SearchIndex, SearchResults, SEARCH_RESULTS_EVENT,
+};
+pub use share::{shared_page, ShareServer, ShareSession, SharedPage};
+pub use snapshot::{
+    create_snapshot, delete_snapshot, list_snapshots, snapshot_files, Snapshot, SNAPSHOT_DIR_NAME,
+};
+pub use sort::{sort_children, sort_headlines, SortKey, SortRule};
+pub use speed::{apply_section_action, priority_range, HeadlineAction};
+pub use startup::{LogDone, StartupOptions, StartupVisibility};
+pub use tags::{is_valid_tag, rewrite_tags, TagRewriteSummary};
+pub use tickler::{
+    collect_tickler, deferred_value, is_deferred, TicklerItem, DEFERRED_PROPERTY, SOMEDAY_TAG,
+};
+pub use timeline::{document_timeline, TimelineDay, TimelineEntry, TimelineKind};
 pub use timestamp::OrgTimestamp;
 pub use title::OrgTitle;
-pub use todo::{StateType, TodoConfiguration, TodoSequence, TodoStatus};
-pub use update::{OrgUpdateInfo, UpdateTracker};
+pub use todo::{CycleDirection, StateType, TodoConfiguration, TodoSequence, TodoStatus};
+pub use trash::TrashEntry;
+pub use update::{ChangeFeed, ChangeKind, OrgUpdateInfo, UpdateTracker};
+pub use urgency::{collect_urgent_tasks, task_urgency, UrgencyWeights, UrgentTask, URGENCY_COLUMN};
+pub use verify::{
+    verify_documents, DriftKind, DriftedDocument, StoredDocument, VerificationReport,
+};
+pub use wordcount::{DocumentStats, SubtreeStats, TextStats};
+pub use work_schedule::{parse_ics_holidays, Holiday, WorkSchedule};
+pub use writer::{format_document, headline_line, planning_line};
@@ -1,18 +1,25 @@
 use std::collections::HashMap;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use notify::event::{ModifyKind, RenameMode};
 use notify::{Event, EventKind, RecommendedWatcher, Watcher};
+use tauri::Emitter;
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 
-use crate::orgmode::repository::OrgDocumentRepository;
+use crate::orgmode::conventions::{conform_edited, FormatConventions};
+use crate::orgmode::document::is_archive_path;
+use crate::orgmode::hooks::{spawn_hook, HookEvent};
+use crate::orgmode::repository::{parse_into, OrgDocumentRepository};
+use crate::orgmode::utils::generate_document_etag;
 use crate::settings::{MonitoredPath, SettingsManager};
 
 #[cfg(test)]
 mod tests {
-    use super::FileMonitor;
+    use super::{FileMonitor, SelfWriteTracker};
     use crate::orgmode::OrgDocumentRepository;
     use crate::settings::{MonitoredPath, PathType};
     use notify::RecursiveMode;
@@ -77,15 +84,85 @@ mod tests {
     fn test_is_relevant_file() {
         // Test .org file
         let org_file = PathBuf::from("test.org");
-        assert!(FileMonitor::is_relevant_file(&org_file));
+        assert!(FileMonitor::is_relevant_file(&org_file, false));
 
         // Test non-org file
         let txt_file = PathBuf::from("test.txt");
-        assert!(!FileMonitor::is_relevant_file(&txt_file));
+        assert!(!FileMonitor::is_relevant_file(&txt_file, false));
 
         // Test hidden file
         let hidden_file = PathBuf::from(".hidden.org");
-        assert!(!FileMonitor::is_relevant_file(&hidden_file));
+        assert!(!FileMonitor::is_relevant_file(&hidden_file, false));
+
+        // Test archive file, only relevant when archives are included
+        let archive_file = PathBuf::from("test.org_archive");
+        assert!(!FileMonitor::is_relevant_file(&archive_file, false));
+        assert!(FileMonitor::is_relevant_file(&archive_file, true));
+    }
+
+    #[test]
+    fn test_self_write_tracker() {
+        let tracker = SelfWriteTracker::new();
+        let path = PathBuf::from("/notes/todo.org");
+
+        assert!(!tracker.is_self_write(&path, "abc"));
+
+        tracker.record(&path, "abc".to_string());
+        assert!(tracker.is_self_write(&path, "abc"));
+        assert!(tracker.is_self_write(&path, "abc"));
+
+        // External edits change the etag and are not suppressed
+        assert!(!tracker.is_self_write(&path, "def"));
+    }
+
+    #[test]
+    fn test_write_file_updates_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("written.org");
+
+        let repository = Arc::new(Mutex::new(OrgDocumentRepository::new()));
+        let monitor = FileMonitor::new(repository.clone());
+
+        let content = "#+TITLE: Written\n* TODO Task\n";
+        monitor.write_file(&path, content).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        let repo = repository.lock().unwrap();
+        let doc = repo.get(path.to_str().unwrap()).unwrap();
+        assert_eq!(doc.title, "Written");
+        assert!(monitor.self_writes().is_self_write(&path, &doc.etag));
+    }
+
+    #[test]
+    fn test_write_file_replaces_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.org");
+        fs::write(&path, "* Old\n").unwrap();
+
+        let repository = Arc::new(Mutex::new(OrgDocumentRepository::new()));
+        let monitor = FileMonitor::new(repository);
+        monitor.write_file(&path, "* New\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "* New\n");
+        // The temporary file was renamed into place
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec!["notes.org"]);
+    }
+
+    #[test]
+    fn test_write_file_refuses_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("locked.org");
+        let content = "#+ORG_X: readonly\n* Task\n";
+
+        let repository = Arc::new(Mutex::new(OrgDocumentRepository::new()));
+        let monitor = FileMonitor::new(repository);
+        monitor.write_file(&path, content).unwrap();
+        assert!(monitor.write_file(&path, "* Task\n").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
     }
 
     #[test]
@@ -157,6 +234,10 @@ pub struct FileMonitor {
     change_tx: Option<mpsc::Sender<PathBuf>>,
     /// App handle for settings access
     app_handle: Option<tauri::AppHandle>,
+    /// Whether *.org_archive files are parsed too
+    include_archives: bool,
+    /// Files recently written by the app itself
+    self_writes: SelfWriteTracker,
 }
 
 impl FileMonitor {
@@ -168,6 +249,8 @@ impl FileMonitor {
             repository,
             change_tx: None,
             app_handle: None,
+            include_archives: false,
+            self_writes: SelfWriteTracker::new(),
         }
     }
 
@@ -182,6 +265,8 @@ impl FileMonitor {
             repository,
             change_tx: None,
             app_handle: Some(app_handle),
+            include_archives: false,
+            self_writes: SelfWriteTracker::new(),
         }
     }
 
@@ -190,6 +275,76 @@ impl FileMonitor {
         self.app_handle = Some(app_handle);
     }
 
+    /// Get the app handle used for settings access
+    pub fn app_handle(&self) -> Option<tauri::AppHandle> {
+        self.app_handle.clone()
+    }
+
+    /// Set whether *.org_archive files should be watched
+    /// Takes effect the next time monitoring starts
+    pub fn set_include_archives(&mut self, include_archives: bool) {
+        self.include_archives = include_archives;
+    }
+
+    /// Get the tracker for writes performed by the app itself
+    pub fn self_writes(&self) -> SelfWriteTracker {
+        self.self_writes.clone()
+    }
+
+    /// Write a file on behalf of the app and update the repository right away
+    /// Headlines the write changes are laid out by the format conventions.
+    /// The file is replaced atomically, so an interrupted write never truncates it.
+    /// The resulting watcher events are recognised as our own and skipped.
+    /// Files marked `#+ORG_X: readonly` are refused
+    pub fn write_file(&self, path: &Path, content: &str) -> Result<(), String> {
+        let conventions = self
+            .app_handle
+            .as_ref()
+            .map(Self::load_format_conventions_sync)
+            .unwrap_or_default();
+        if conventions == FormatConventions::default() {
+            return self.restore_file(path, content);
+        }
+        let previous = fs::read_to_string(path).unwrap_or_default();
+        self.restore_file(path, &conform_edited(&previous, content, &conventions))
+    }
+
+    /// Write a file exactly as given, such as when restoring a snapshot, and update the
+    /// repository right away
+    /// Atomic, skipped by the watcher and refused for read-only files like `write_file`
+    pub fn restore_file(&self, path: &Path, content: &str) -> Result<(), String> {
+        let read_only = self
+            .repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))?
+            .get(&path.to_string_lossy())
+            .is_some_and(|document| document.flags.read_only);
+        if read_only {
+            return Err(format!("{} is marked read-only", path.display()));
+        }
+
+        self.self_writes
+            .record(path, generate_document_etag(content));
+
+        write_atomically(path, content)
+            .map_err(|e| format!("Failed to write file {}: {}", path.display(), e))?;
+
+        self.reparse_file(path)
+    }
+
+    /// Parse a file again with the user's TODO keywords and update the repository
+    pub fn reparse_file(&self, path: &Path) -> Result<(), String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
+        let todo_keywords = self
+            .app_handle
+            .as_ref()
+            .map(Self::load_user_todo_keywords_sync);
+
+        parse_into(&self.repository, path, &content, todo_keywords)?;
+        Ok(())
+    }
+
     /// Add a path to be monitored
     pub fn add_path(&mut self, path: MonitoredPath) -> Result<(), String> {
         // Don't add duplicates
@@ -252,6 +407,8 @@ impl FileMonitor {
         // Clone repository and app_handle for the task
         let repository = self.repository.clone();
         let app_handle = self.app_handle.clone();
+        let include_archives = self.include_archives;
+        let self_writes = self.self_writes.clone();
 
         // Spawn a task to handle file system events
         tokio::spawn(async move {
@@ -259,10 +416,23 @@ impl FileMonitor {
             let debounce_duration = Duration::from_millis(300);
 
             while let Some(event) = rx.recv().await {
+                // Renames within monitored paths keep the document's identity; others are
+                // a change of the destination
+                let rename = Self::get_rename_from_event(&event);
+                if let Some((from, to)) = &rename {
+                    if Self::handle_rename(&repository, from, to, include_archives) {
+                        continue;
+                    }
+                }
+                let changed = match rename {
+                    Some((_, to)) => Some(to),
+                    None => Self::get_relevant_path_from_event(&event),
+                };
+
                 // Handle the event
-                if let Some(path) = Self::get_relevant_path_from_event(&event) {
+                if let Some(path) = changed {
                     // Skip hidden files and non-org files
-                    if Self::is_relevant_file(&path) {
+                    if Self::is_relevant_file(&path, include_archives) {
                         // Update the debounce map
                         debounce_map.insert(path.clone(), Instant::now());
 
@@ -271,6 +441,7 @@ impl FileMonitor {
                         let change_tx_clone = change_tx.clone();
                         let repo_clone = repository.clone();
                         let app_handle_clone = app_handle.clone();
+                        let self_writes_clone = self_writes.clone();
 
                         // Spawn a task to handle this specific file change after debounce
                         tokio::spawn(async move {
@@ -282,6 +453,7 @@ impl FileMonitor {
                                 repo_clone,
                                 path_clone.clone(),
                                 app_handle_clone,
+                                self_writes_clone,
                             )
                             .await;
 
@@ -321,8 +493,52 @@ impl FileMonitor {
         }
     }
 
+    /// Get the source and destination paths of a rename event
+    fn get_rename_from_event(event: &Event) -> Option<(PathBuf, PathBuf)> {
+        match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                Some((event.paths[0].clone(), event.paths[1].clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Move a renamed document in the repository instead of reparsing it
+    /// Returns false when the rename could not be applied and the event
+    /// should be handled as a regular change of the destination path
+    fn handle_rename(
+        repository: &Arc<Mutex<OrgDocumentRepository>>,
+        from: &Path,
+        to: &Path,
+        include_archives: bool,
+    ) -> bool {
+        let mut repository_lock = match repository.lock() {
+            Ok(lock) => lock,
+            Err(e) => {
+                eprintln!("Failed to lock repository: {}", e);
+                return false;
+            }
+        };
+
+        if !Self::is_relevant_file(to, include_archives) {
+            // Renamed to something we don't parse, e.g. a backup file
+            if let Some(old_id) = from.to_str() {
+                repository_lock.remove(old_id);
+            }
+            return true;
+        }
+
+        match repository_lock.rename_document(from, to) {
+            Some(new_id) => {
+                println!("Document renamed: {} -> {}", from.display(), new_id);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Check if a file is relevant for monitoring
-    fn is_relevant_file(path: &Path) -> bool {
+    fn is_relevant_file(path: &Path, include_archives: bool) -> bool {
         // Skip hidden files
         if let Some(file_name) = path.file_name() {
             if let Some(file_name_str) = file_name.to_str() {
@@ -335,6 +551,11 @@ impl FileMonitor {
                     if extension == "org" {
                         return true;
                     }
+
+                    // Archive files are opt-in
+                    if include_archives && is_archive_path(path) {
+                        return true;
+                    }
                 }
             }
         }
@@ -342,43 +563,85 @@ impl FileMonitor {
         false
     }
 
+    /// Load the format conventions synchronously, falling back to the defaults
+    fn load_format_conventions_sync(app_handle: &tauri::AppHandle) -> FormatConventions {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                SettingsManager::new()
+                    .load_settings(app_handle)
+                    .await
+                    .map(|settings| settings.format_conventions)
+                    .unwrap_or_default()
+            })
+        })
+    }
+
     /// Load user TODO keywords synchronously
     fn load_user_todo_keywords_sync(app_handle: &tauri::AppHandle) -> (Vec<String>, Vec<String>) {
         // Use tokio's block_in_place to run async code in sync context
         tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                let settings_manager = SettingsManager::new();
-                match settings_manager.load_settings(app_handle).await {
-                    Ok(settings) => {
-                        let active = if settings.todo_keywords.active.is_empty() {
-                            vec!["TODO".to_string()]
-                        } else {
-                            settings.todo_keywords.active
-                        };
-
-                        let closed = if settings.todo_keywords.closed.is_empty() {
-                            vec!["DONE".to_string()]
-                        } else {
-                            settings.todo_keywords.closed
-                        };
-
-                        (active, closed)
-                    }
-                    Err(_) => {
-                        // Fallback to defaults
-                        (vec!["TODO".to_string()], vec!["DONE".to_string()])
-                    }
-                }
-            })
+            tokio::runtime::Handle::current().block_on(Self::load_user_todo_keywords(app_handle))
         })
     }
 
+    /// Load user TODO keywords, falling back to TODO/DONE
+    pub async fn load_user_todo_keywords(app_handle: &tauri::AppHandle) -> (Vec<String>, Vec<String>) {
+        let settings_manager = SettingsManager::new();
+        match settings_manager.load_settings(app_handle).await {
+            Ok(settings) => {
+                let active = if settings.todo_keywords.active.is_empty() {
+                    vec!["TODO".to_string()]
+                } else {
+                    settings.todo_keywords.active
+                };
+
+                let closed = if settings.todo_keywords.closed.is_empty() {
+                    vec!["DONE".to_string()]
+                } else {
+                    settings.todo_keywords.closed
+                };
+
+                (active, closed)
+            }
+            Err(_) => {
+                // Fallback to defaults
+                (vec!["TODO".to_string()], vec!["DONE".to_string()])
+            }
+        }
+    }
+
     /// Handle a file change by re-parsing it
+    ///
+    /// File IO and settings loading happen before the repository is locked,
+    /// so a slow disk does not hold up other commands.
     async fn handle_file_change(
         repository: Arc<Mutex<OrgDocumentRepository>>,
         path: PathBuf,
         app_handle: Option<tauri::AppHandle>,
+        self_writes: SelfWriteTracker,
     ) {
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                eprintln!("Failed to read file {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        // Load user TODO keywords to use for parsing
+        let todo_keywords = match app_handle.as_ref() {
+            Some(handle) => {
+                let todo_keywords = Self::load_user_todo_keywords(handle).await;
+                println!(
+                    "Loaded user TODO keywords for file change: {:?} | {:?}",
+                    todo_keywords.0, todo_keywords.1
+                );
+                Some(todo_keywords)
+            }
+            None => None,
+        };
+
         // Get a lock on the repository
         let mut repository_lock = match repository.lock() {
             Ok(lock) => lock,
@@ -388,20 +651,132 @@ impl FileMonitor {
             }
         };
 
-        // Load user TODO keywords and use them for parsing
-        let result = if let Some(handle) = app_handle {
-            let todo_keywords = Self::load_user_todo_keywords_sync(&handle);
-            println!(
-                "Loaded user TODO keywords for file change: {:?} | {:?}",
-                todo_keywords.0, todo_keywords.1
-            );
-            repository_lock.parse_file_with_keywords(&path, todo_keywords)
-        } else {
-            repository_lock.parse_file(&path)
+        // A file that no longer exists is kept but marked as missing
+        let Some(content) = content else {
+            if let Some(id) = path.to_str() {
+                if repository_lock.mark_missing(id) {
+                    println!("Document file is missing: {}", id);
+                    if let Some(handle) = app_handle.as_ref() {
+                        if let Err(e) = handle.emit(DOCUMENT_MISSING_EVENT, id) {
+                            eprintln!("Failed to emit missing document event: {}", e);
+                        }
+                    }
+                }
+            }
+            return;
+        };
+
+        // Skip files whose content is already in the repository, e.g. our own writes
+        let etag = generate_document_etag(&content);
+        let unchanged = path
+            .to_str()
+            .and_then(|id| repository_lock.get(id))
+            .is_some_and(|document| !document.missing && document.etag == etag);
+
+        if unchanged || self_writes.is_self_write(&path, &etag) {
+            println!("Skipping reparse of unchanged file: {}", path.display());
+            return;
+        }
+
+        // Parse without holding the lock so reads aren't blocked by large files
+        drop(repository_lock);
+        match parse_into(&repository, &path, &content, todo_keywords) {
+            Ok(document_id) => {
+                if let Some(handle) = app_handle {
+                    tokio::spawn(Self::run_file_changed_hook(
+                        handle,
+                        path.display().to_string(),
+                        document_id,
+                    ));
+                }
+            }
+            Err(e) => eprintln!("Failed to parse file {}: {}", path.display(), e),
+        }
+    }
+
+    // Run the user's file-changed hook, if any, for a file changed outside the app
+    async fn run_file_changed_hook(
+        app_handle: tauri::AppHandle,
+        path: String,
+        document_id: String,
+    ) {
+        let settings = match SettingsManager::new().load_settings(&app_handle).await {
+            Ok(settings) => settings,
+            Err(e) => {
+                eprintln!("Failed to load settings for file hook: {}", e);
+                return;
+            }
+        };
+        let variables = vec![
+            ("ORGX_FILE".to_string(), path),
+            ("ORGX_DOCUMENT_ID".to_string(), document_id),
+        ];
+        spawn_hook(&settings.hooks, HookEvent::FileChanged, variables);
+    }
+}
+
+/// Replace a file by writing a hidden temporary file next to it and renaming it over the
+/// original. Symlinks are followed so the link itself survives, and the original
+/// permissions are kept.
+fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let file_name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = target.with_file_name(format!(".{}.tmp", file_name));
+
+    fs::write(&temp, content)?;
+    if let Ok(metadata) = fs::metadata(&target) {
+        fs::set_permissions(&temp, metadata.permissions())?;
+    }
+    fs::rename(&temp, &target).inspect_err(|_| {
+        let _ = fs::remove_file(&temp);
+    })
+}
+
+/// Event emitted with the document ID when a monitored file disappears
+pub const DOCUMENT_MISSING_EVENT: &str = "document-missing";
+
+/// How long a self-write stays recognisable after the app wrote a file
+const SELF_WRITE_TTL: Duration = Duration::from_secs(5);
+
+/// Tracks files recently written by the app so the watcher can skip them
+#[derive(Debug, Clone, Default)]
+pub struct SelfWriteTracker {
+    writes: Arc<Mutex<HashMap<PathBuf, (String, Instant)>>>,
+}
+
+impl SelfWriteTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that the app is about to write content with the given etag
+    pub fn record(&self, path: &Path, etag: String) {
+        if let Ok(mut writes) = self.writes.lock() {
+            writes.retain(|_, (_, written_at)| written_at.elapsed() < SELF_WRITE_TTL);
+            writes.insert(path.to_path_buf(), (etag, Instant::now()));
+        }
+    }
+
+    /// Check whether the file's current etag matches a recent write by the app
+    /// A write can trigger several events, so matches are kept until they expire
+    pub fn is_self_write(&self, path: &Path, etag: &str) -> bool {
+        let Ok(mut writes) = self.writes.lock() else {
+            return false;
         };
 
-        if let Err(e) = result {
-            eprintln!("Failed to parse file {}: {}", path.display(), e);
+        match writes.get(path) {
+            Some((expected, written_at)) if written_at.elapsed() < SELF_WRITE_TTL => {
+                expected == etag
+            }
+            Some(_) => {
+                writes.remove(path);
+                false
+            }
+            None => false,
         }
     }
 }
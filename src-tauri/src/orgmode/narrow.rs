@@ -0,0 +1,124 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::file_keywords::set_file_keyword;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::headline_level;
+use crate::orgmode::subtree::subtree_lines;
+
+fn promote(headline: &mut OrgHeadline, levels: u8) {
+    headline.title.level = headline.title.level.saturating_sub(levels);
+    for child in &mut headline.children {
+        promote(child, levels);
+    }
+}
+
+// Whether a line sets a file keyword like `#+TODO:`, rather than opening a block
+fn is_keyword_line(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix("#+")
+        .and_then(|rest| rest.split_once(':'))
+        .is_some_and(|(key, _)| !key.is_empty() && !key.contains(char::is_whitespace))
+}
+
+/// A virtual document holding only a headline's subtree, like narrowing in Emacs
+///
+/// The subtree keeps its headline ids and is promoted to start at level 1. The document is
+/// titled by the headline, takes the tags and category the headline inherits, and keeps
+/// the file's other keyword lines such as `#+TODO:`. `document.content` must be the text
+/// the headlines were parsed from. Returns None if the headline is not found.
+pub fn narrow_to_subtree(document: &OrgDocument, headline_id: &str) -> Option<OrgDocument> {
+    let path = document.headline_path(headline_id)?;
+    let (root, ancestors) = path.split_last()?;
+    let range = subtree_lines(document, headline_id)?;
+    let levels = root.title.level.saturating_sub(1);
+
+    let mut filetags = document.filetags.clone();
+    for tag in ancestors.iter().flat_map(|headline| &headline.title.tags) {
+        if !filetags
+            .iter()
+            .any(|existing| existing.as_str() == tag.as_ref())
+        {
+            filetags.push(tag.to_string());
+        }
+    }
+    let category = document
+        .get_inherited_property(headline_id, "CATEGORY")
+        .map_or_else(|| document.category.clone(), str::to_string);
+
+    let lines: Vec<&str> = document.content.lines().collect();
+    let mut content: String = lines
+        .iter()
+        .take_while(|line| headline_level(line).is_none())
+        .filter(|line| is_keyword_line(line))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let tags = format!(":{}:", filetags.join(":"));
+    content = set_file_keyword(&content, "TITLE", Some(&root.title.raw));
+    content = set_file_keyword(
+        &content,
+        "FILETAGS",
+        (!filetags.is_empty()).then_some(&tags),
+    );
+    content = set_file_keyword(
+        &content,
+        "CATEGORY",
+        (!category.is_empty()).then_some(&category),
+    );
+    for line in &lines[range] {
+        match headline_level(line) {
+            Some(_) => content.push_str(&line[levels as usize..]),
+            None => content.push_str(line),
+        }
+        content.push('\n');
+    }
+
+    let mut headline = (*root).clone();
+    promote(&mut headline, levels);
+    Some(OrgDocument {
+        title: root.title.raw.clone(),
+        content,
+        headlines: vec![headline],
+        filetags,
+        category,
+        ..document.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::todo::TodoConfiguration;
+
+    #[test]
+    fn test_narrow_to_subtree() {
+        let doc = parse_org_document(
+            "#+TITLE: Work\n#+TODO: TODO REVIEW | DONE\n#+FILETAGS: :work:\nIntro\n* Clients :billable:\n:PROPERTIES:\n:CATEGORY: acme\n:END:\n** Launch\n*** REVIEW Press release\nDraft ready\n** Other\n",
+            Some("work.org"),
+        )
+        .unwrap();
+
+        let narrowed = narrow_to_subtree(&doc, "1.1").unwrap();
+        assert_eq!(
+            narrowed.content,
+            "#+TITLE: Launch\n#+TODO: TODO REVIEW | DONE\n#+FILETAGS: :work:billable:\n#+CATEGORY: acme\n* Launch\n** REVIEW Press release\nDraft ready\n"
+        );
+        assert_eq!(
+            (narrowed.title.as_str(), narrowed.category.as_str()),
+            ("Launch", "acme")
+        );
+        assert_eq!(narrowed.filetags, vec!["work", "billable"]);
+
+        // Headlines keep their ids, and the file's TODO keywords still apply
+        let root = &narrowed.headlines[0];
+        assert_eq!((root.id.as_str(), root.title.level), ("1.1", 1));
+        assert_eq!(
+            (root.children[0].id.as_str(), root.children[0].title.level),
+            ("1.1.1", 2)
+        );
+        let config = narrowed.effective_todo_config(&TodoConfiguration::default());
+        assert!(config.keywords().contains(&"REVIEW"));
+        assert_eq!(subtree_lines(&narrowed, "1.1.1"), Some(5..7));
+
+        assert!(narrow_to_subtree(&doc, "3").is_none());
+    }
+}
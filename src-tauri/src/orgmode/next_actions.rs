@@ -0,0 +1,214 @@
+use crate::orgmode::dependencies::DependencyGraph;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::tickler::deferred_until;
+use crate::orgmode::todo::TodoConfiguration;
+use crate::orgmode::urgency::{urgency, UrgencyWeights, UrgentTask};
+use chrono::NaiveDate;
+
+/// TODO keywords for tasks parked on someone else, never suggested as next actions
+pub const WAITING_KEYWORDS: &[&str] = &["WAITING", "WAIT", "HOLD"];
+
+/// Prefix marking a tag as a GTD context, e.g. `@home` or `@phone`
+pub const CONTEXT_PREFIX: char = '@';
+
+/// Normalize a context name to its tag form, adding the `@` prefix if missing
+pub fn context_tag(context: &str) -> String {
+    let context = context.trim();
+    if context.starts_with(CONTEXT_PREFIX) {
+        context.to_string()
+    } else {
+        format!("{}{}", CONTEXT_PREFIX, context)
+    }
+}
+
+// Whether a task with these (inherited) tags can be done in one of the contexts
+// Tasks without any context tag can be done anywhere
+fn in_context(tags: &[String], contexts: &[String]) -> bool {
+    if contexts.is_empty() {
+        return true;
+    }
+    let mut task_contexts = tags
+        .iter()
+        .filter(|tag| tag.starts_with(CONTEXT_PREFIX))
+        .peekable();
+    task_contexts.peek().is_none()
+        || task_contexts.any(|tag| {
+            contexts
+                .iter()
+                .any(|context| context.eq_ignore_ascii_case(tag))
+        })
+}
+
+// Whether any descendant is still an open task, as with `org-enforce-todo-dependencies`
+fn has_open_subtasks(headline: &OrgHeadline, config: &TodoConfiguration) -> bool {
+    headline.children.iter().any(|child| {
+        child
+            .get_todo_status(config)
+            .is_some_and(|status| status.is_active())
+            || has_open_subtasks(child, config)
+    })
+}
+
+/// Whether a headline can be worked on now: an open task that is not waiting, has no
+/// open subtasks and is not blocked through BLOCKER/DEPENDS or an ordered parent
+pub fn is_next_action(
+    document: &OrgDocument,
+    headline: &OrgHeadline,
+    config: &TodoConfiguration,
+    graph: &DependencyGraph,
+) -> bool {
+    let Some(status) = headline.get_todo_status(config) else {
+        return false;
+    };
+    let waiting = WAITING_KEYWORDS
+        .iter()
+        .any(|keyword| status.keyword.eq_ignore_ascii_case(keyword));
+    status.is_active()
+        && !waiting
+        && !has_open_subtasks(headline, config)
+        && !graph.is_blocked(&document.id, &headline.id)
+}
+
+// Walk the tree carrying inherited tags and the ancestors' latest deferral down to children
+#[allow(clippy::too_many_arguments)]
+fn collect_actions(
+    document: &OrgDocument,
+    headlines: &[OrgHeadline],
+    config: &TodoConfiguration,
+    graph: &DependencyGraph,
+    inherited_tags: &[String],
+    inherited_until: Option<NaiveDate>,
+    contexts: &[String],
+    weights: &UrgencyWeights,
+    today: NaiveDate,
+    actions: &mut Vec<UrgentTask>,
+) {
+    for headline in headlines {
+        let mut tags = inherited_tags.to_vec();
+        for tag in &headline.title.tags {
+            if !tags
+                .iter()
+                .any(|existing| existing.as_str() == tag.as_ref())
+            {
+                tags.push(tag.to_string());
+            }
+        }
+
+        let until = deferred_until(headline).max(inherited_until);
+        if is_next_action(document, headline, config, graph)
+            && in_context(&tags, contexts)
+            && until.is_none_or(|until| until <= today)
+        {
+            actions.push(UrgentTask {
+                document_id: document.id.clone(),
+                headline_id: headline.id.clone(),
+                title: headline.title.raw.clone(),
+                todo_keyword: headline
+                    .title
+                    .todo_keyword
+                    .as_deref()
+                    .unwrap_or_default()
+                    .to_string(),
+                tags: tags.clone(),
+                urgency: urgency(headline, weights, today),
+            });
+        }
+
+        collect_actions(
+            document,
+            &headline.children,
+            config,
+            graph,
+            &tags,
+            until,
+            contexts,
+            weights,
+            today,
+            actions,
+        );
+    }
+}
+
+/// Suggest next actions: open tasks that are not waiting, blocked by open subtasks or
+/// blocked through BLOCKER/DEPENDS, doable in one of `contexts` (all contexts when
+/// empty), most urgent first
+/// Tags are inherited from parent headlines and `#+FILETAGS:`. Tasks deferred past
+/// `today` and files marked `no-agenda` are left out.
+pub fn collect_next_actions(
+    documents: &[&OrgDocument],
+    contexts: &[String],
+    weights: &UrgencyWeights,
+    today: NaiveDate,
+) -> Vec<UrgentTask> {
+    let contexts: Vec<String> = contexts
+        .iter()
+        .map(|context| context_tag(context))
+        .collect();
+    let graph = DependencyGraph::build(documents);
+    let mut actions = Vec::new();
+
+    for document in documents {
+        if document.missing || document.archived || document.flags.no_agenda {
+            continue;
+        }
+        let config = document
+            .todo_config
+            .clone()
+            .unwrap_or_else(TodoConfiguration::default);
+        collect_actions(
+            document,
+            &document.headlines,
+            &config,
+            &graph,
+            &document.filetags,
+            None,
+            &contexts,
+            weights,
+            today,
+            &mut actions,
+        );
+    }
+
+    // Most urgent first, then in file order
+    actions.sort_by(|a, b| {
+        b.urgency
+            .total_cmp(&a.urgency)
+            .then_with(|| a.document_id.cmp(&b.document_id))
+    });
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_collect_next_actions() {
+        let doc = parse_org_document(
+            "* Errands :@town:\n** TODO [#A] Buy stamps\n** WAITING Parcel from shop\n* TODO Plan trip\n** TODO Book hotel :@phone:\n* TODO Water plants :@home:\n* TODO Read paper\n* TODO File receipts\n:PROPERTIES:\n:BLOCKER: previous-sibling\n:END:\n",
+            Some("tasks.org"),
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let weights = UrgencyWeights::default();
+        let titles = |contexts: &[String]| -> Vec<String> {
+            collect_next_actions(&[&doc], contexts, &weights, today)
+                .into_iter()
+                .map(|task| task.title)
+                .collect()
+        };
+
+        // Plan trip is blocked by its open subtask, receipts by the paper; the parcel is waiting
+        assert_eq!(
+            titles(&[]),
+            vec!["Buy stamps", "Book hotel", "Water plants", "Read paper"]
+        );
+        // Context tags are inherited; untagged tasks fit any context
+        assert_eq!(
+            titles(&["town".to_string(), "@HOME".to_string()]),
+            vec!["Buy stamps", "Water plants", "Read paper"]
+        );
+    }
+}
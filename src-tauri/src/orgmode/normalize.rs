@@ -0,0 +1,65 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+// Kana voicing marks are combining marks, but dropping them would turn が into か
+fn is_kana_voicing_mark(c: char) -> bool {
+    matches!(c, '\u{3099}' | '\u{309A}')
+}
+
+/// Normalize text for matching: compatibility forms (NFKC), case folding and no diacritics
+/// So full-width "ＣＡＦÉ", "Café" and "cafe" all normalize to "cafe"
+pub fn normalize(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_ascii_lowercase();
+    }
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c) || is_kana_voicing_mark(*c))
+        .nfc()
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .replace('ß', "ss")
+}
+
+/// Normalize a single character, for matching that has to keep character positions
+pub fn normalize_char(c: char) -> String {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase().to_string();
+    }
+    let mut buffer = [0; 4];
+    normalize(c.encode_utf8(&mut buffer))
+}
+
+/// Whether a character belongs to a script written without spaces between words:
+/// Han ideographs, Hiragana, Katakana and Hangul
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11FF}'     // Hangul Jamo
+        | '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{3130}'..='\u{318F}'   // Hangul Compatibility Jamo
+        | '\u{31F0}'..='\u{31FF}'   // Katakana Phonetic Extensions
+        | '\u{3400}'..='\u{4DBF}'   // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}'   // Hangul Syllables
+        | '\u{F900}'..='\u{FAFF}'   // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2FFFF}' // CJK Extensions B and later
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("ＣＡＦÉ"), "cafe");
+        assert_eq!(normalize("Crème Brûlée"), "creme brulee");
+        assert_eq!(normalize("Straße"), "strasse");
+        // Half-width katakana widen, voiced kana keep their marks
+        assert_eq!(normalize("ｶﾞｲﾄﾞ"), "ガイド");
+        assert_eq!(normalize("がか"), "がか");
+        assert_eq!(normalize_char('Ｅ'), "e");
+
+        assert!(is_cjk('東') && is_cjk('ガ') && is_cjk('한'));
+        assert!(!is_cjk('a') && !is_cjk('。'));
+    }
+}
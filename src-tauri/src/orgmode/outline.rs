@@ -0,0 +1,190 @@
+use crate::orgmode::colors::StatusStyle;
+use crate::orgmode::columns::column_values;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::flags::DocumentFlags;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::properties::PropertyType;
+use crate::orgmode::rollup::{rollup, Rollup};
+use crate::orgmode::sort::{sort_headlines, SortRule};
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::startup::StartupOptions;
+use crate::orgmode::title::OrgTitle;
+use crate::orgmode::todo::TodoConfiguration;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+
+/// Document metadata without its content or headlines
+/// Lets the frontend show a large document before loading its outline
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DocumentSummary {
+    pub id: String,
+    pub title: String,
+    pub file_path: String,
+    pub filetags: Vec<String>,
+    pub properties: HashMap<String, String>,
+    pub keywords: HashMap<String, String>,
+    pub startup: StartupOptions,
+    pub flags: DocumentFlags,
+    pub category: String,
+    pub etag: String,
+    pub todo_config: Option<TodoConfiguration>,
+    pub tag_styles: HashMap<String, StatusStyle>,
+    pub archived: bool,
+    pub missing: bool,
+    pub headline_count: u32,
+    pub top_level_count: u32,
+}
+
+/// A headline without its content or children, for lazy outline loading
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HeadlineSummary {
+    pub id: String,
+    pub document_id: String,
+    pub title: OrgTitle,
+    pub etag: String,
+    pub child_count: u32,
+    pub content_length: u32, // Bytes of body text, 0 if the headline has none
+    pub rollup: Option<Rollup>, // Progress of the tasks below, None if there are none
+    pub columns: BTreeMap<String, String>, // Property column values, with inheritance
+}
+
+/// One page of a headline's children
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HeadlinePage {
+    pub items: Vec<HeadlineSummary>,
+    pub total: u32,
+}
+
+impl DocumentSummary {
+    pub fn from_document(document: &OrgDocument) -> Self {
+        Self {
+            id: document.id.clone(),
+            title: document.title.clone(),
+            file_path: document.file_path.clone(),
+            filetags: document.filetags.clone(),
+            properties: document.properties.clone(),
+            keywords: document.keywords.clone(),
+            startup: document.startup.clone(),
+            flags: document.flags.clone(),
+            category: document.category.clone(),
+            etag: document.etag.clone(),
+            todo_config: document.todo_config.clone(),
+            tag_styles: document.tag_styles.clone(),
+            archived: document.archived,
+            missing: document.missing,
+            headline_count: flatten_headlines(&document.headlines).len() as u32,
+            top_level_count: document.headlines.len() as u32,
+        }
+    }
+}
+
+impl HeadlineSummary {
+    pub fn from_headline(
+        document: &OrgDocument,
+        headline: &OrgHeadline,
+        config: &TodoConfiguration,
+        columns: &[String],
+    ) -> Self {
+        Self {
+            id: headline.id.clone(),
+            document_id: headline.document_id.clone(),
+            title: headline.title.clone(),
+            etag: headline.etag.clone(),
+            child_count: headline.children.len() as u32,
+            content_length: headline.content.len() as u32,
+            rollup: rollup(headline, config),
+            columns: column_values(document, headline, columns),
+        }
+    }
+}
+
+/// Page through the children of a headline, or the top-level headlines if `parent_id` is None
+/// `columns` names the properties to compute for the table's property columns. Children are
+/// ordered by `sort`, typing property values with `property_type`, or kept in file order
+/// without rules. Files' own `#+TODO:` keywords come before `settings_config`. Returns None
+/// if the parent headline does not exist.
+#[allow(clippy::too_many_arguments)]
+pub fn headline_children(
+    document: &OrgDocument,
+    parent_id: Option<&str>,
+    offset: usize,
+    limit: usize,
+    columns: &[String],
+    sort: &[SortRule],
+    property_type: impl Fn(&str) -> PropertyType,
+    settings_config: &TodoConfiguration,
+) -> Option<HeadlinePage> {
+    let children = match parent_id {
+        Some(parent_id) => &document.find_headline(parent_id)?.children,
+        None => &document.headlines,
+    };
+
+    let config = document.effective_todo_config(settings_config);
+    let mut children: Vec<&OrgHeadline> = children.iter().collect();
+    sort_headlines(&mut children, sort, &config, property_type);
+
+    Some(HeadlinePage {
+        items: children
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|headline| HeadlineSummary::from_headline(document, headline, &config, columns))
+            .collect(),
+        total: children.len() as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::sort::SortKey;
+
+    #[test]
+    fn test_headline_children() {
+        let doc = parse_org_document(
+            "* One\nBody\n** A\n** DONE B\n** TODO C\n* Two\n",
+            Some("outline.org"),
+        )
+        .unwrap();
+
+        let summary = DocumentSummary::from_document(&doc);
+        assert_eq!((summary.headline_count, summary.top_level_count), (5, 2));
+
+        let file_order = |_: &str| PropertyType::String;
+        let keywords = TodoConfiguration::default();
+        let columns = ["CATEGORY".to_string()];
+        let top =
+            headline_children(&doc, None, 0, 10, &columns, &[], file_order, &keywords).unwrap();
+        assert_eq!(top.total, 2);
+        assert_eq!(top.items[0].child_count, 3);
+        assert!(top.items[0].content_length > 0);
+        assert_eq!(top.items[0].rollup.as_ref().unwrap().percent_done, 50);
+        assert!(top.items[1].rollup.is_none());
+        assert_eq!(top.items[0].columns["CATEGORY"], "outline");
+
+        let page =
+            headline_children(&doc, Some("1"), 1, 1, &[], &[], file_order, &keywords).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].title.raw, "B");
+
+        let by_todo = [SortRule {
+            key: SortKey::Todo,
+            descending: false,
+        }];
+        let page =
+            headline_children(&doc, Some("1"), 0, 3, &[], &by_todo, file_order, &keywords).unwrap();
+        let titles: Vec<&str> = page
+            .items
+            .iter()
+            .map(|item| item.title.raw.as_str())
+            .collect();
+        assert_eq!(titles, vec!["C", "B", "A"]);
+
+        assert!(
+            headline_children(&doc, Some("9"), 0, 10, &[], &[], file_order, &keywords).is_none()
+        );
+    }
+}
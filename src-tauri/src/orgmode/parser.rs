@@ -1,16 +1,20 @@
-use crate::orgmode::document::OrgDocument;
+use crate::orgmode::colors::document_tag_styles;
+use crate::orgmode::document::{is_archive_path, OrgDocument};
+use crate::orgmode::flags::DocumentFlags;
 use crate::orgmode::headline::OrgHeadline;
 use crate::orgmode::planning::OrgPlanning;
+use crate::orgmode::source::headline_sections;
+use crate::orgmode::startup::StartupOptions;
+use crate::orgmode::time_zone;
 use crate::orgmode::title::OrgTitle;
-use crate::orgmode::todo::StateType;
 use crate::orgmode::todo::TodoConfiguration;
-use crate::orgmode::todo::TodoSequence;
-use crate::orgmode::todo::TodoStatus;
-use crate::orgmode::utils::{generate_document_etag, generate_headline_etag};
+use crate::orgmode::utils::{generate_document_etag, generate_section_etag};
 use crate::settings::SettingsManager;
 use chrono::Utc;
 use orgize::{Element, Org};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -28,7 +32,7 @@ pub enum OrgError {
 /// #+SEQ_TODO: TODO | DONE
 ///
 /// Returns a tuple of (active_keywords, closed_keywords)
-fn extract_todo_keywords_from_content(content: &str) -> (Vec<String>, Vec<String>) {
+pub fn extract_todo_keywords_from_content(content: &str) -> (Vec<String>, Vec<String>) {
     // Default keywords if no custom ones are found
     let mut active_keywords = vec!["TODO".to_string()];
     let mut closed_keywords = vec!["DONE".to_string()];
@@ -142,13 +146,33 @@ pub async fn parse_org_document_with_settings(
     let filetags = extract_filetags(&org);
     println!("Filetags extracted: {:?}", filetags);
 
-    // Extract category
-    let category = extract_category(&org).unwrap_or_else(String::new);
+    // Extract document properties, from #+PROPERTY: lines and the drawer before the first headline
+    let properties = extract_file_properties(content);
+    println!("Properties extracted");
+
+    // Extract category, which the property drawer can also set
+    let category = extract_category(&org)
+        .or_else(|| {
+            properties
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("CATEGORY"))
+                .map(|(_, value)| value.clone())
+        })
+        .unwrap_or_else(String::new);
     println!("Category extracted: {}", category);
 
-    // Extract document properties
-    let properties = extract_document_properties(&org);
-    println!("Properties extracted");
+    // Extract the remaining #+KEY: lines
+    let keywords = extract_document_keywords(&org);
+    println!("Keywords extracted");
+
+    // Extract #+STARTUP: options
+    let startup = StartupOptions::from_content(content);
+
+    // Extract #+ORG_X: flags
+    let flags = DocumentFlags::from_content(content);
+
+    // Extract #+CONSTANTS: definitions
+    let constants = extract_constants(content);
 
     // Extract TODO configuration
     let todo_config = extract_todo_configuration(&org, &config);
@@ -166,6 +190,9 @@ pub async fn parse_org_document_with_settings(
     // Generate document ID based on file path
     let id = file_path.unwrap_or("").to_string();
 
+    // Style the file and headline tags
+    let tag_styles = document_tag_styles(&filetags, &headlines);
+
     // Create document with all extracted information
     let document = OrgDocument {
         id: id.clone(),
@@ -176,14 +203,22 @@ pub async fn parse_org_document_with_settings(
         parsed_at: Utc::now(),
         file_path: file_path.unwrap_or("").to_string(),
         properties,
+        keywords,
+        startup,
+        flags,
+        constants,
         category,
         etag: generate_document_etag(content),
         todo_config,
+        tag_styles,
+        archived: file_path.is_some_and(|path| is_archive_path(Path::new(path))),
+        missing: false,
     };
 
     // Update document_id in all headlines
     let mut updated_document = document.clone();
     update_headline_document_ids(&mut updated_document.headlines, &id);
+    refresh_headline_etags(&mut updated_document);
 
     Ok(updated_document)
 }
@@ -251,13 +286,33 @@ pub fn parse_org_document_with_keywords(
     let filetags = extract_filetags(&org);
     println!("Filetags extracted: {:?}", filetags);
 
-    // Extract category
-    let category = extract_category(&org).unwrap_or_else(String::new);
+    // Extract document properties, from #+PROPERTY: lines and the drawer before the first headline
+    let properties = extract_file_properties(content);
+    println!("Properties extracted");
+
+    // Extract category, which the property drawer can also set
+    let category = extract_category(&org)
+        .or_else(|| {
+            properties
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("CATEGORY"))
+                .map(|(_, value)| value.clone())
+        })
+        .unwrap_or_else(String::new);
     println!("Category extracted: {}", category);
 
-    // Extract document properties
-    let properties = extract_document_properties(&org);
-    println!("Properties extracted");
+    // Extract the remaining #+KEY: lines
+    let keywords = extract_document_keywords(&org);
+    println!("Keywords extracted");
+
+    // Extract #+STARTUP: options
+    let startup = StartupOptions::from_content(content);
+
+    // Extract #+ORG_X: flags
+    let flags = DocumentFlags::from_content(content);
+
+    // Extract #+CONSTANTS: definitions
+    let constants = extract_constants(content);
 
     // Extract TODO configuration
     let todo_config = extract_todo_configuration(&org, &config);
@@ -275,6 +330,9 @@ pub fn parse_org_document_with_keywords(
     // Generate document ID based on file path
     let id = file_path.unwrap_or("").to_string();
 
+    // Style the file and headline tags
+    let tag_styles = document_tag_styles(&filetags, &headlines);
+
     // Create document with all extracted information
     let document = OrgDocument {
         id: id.clone(),
@@ -285,14 +343,22 @@ pub fn parse_org_document_with_keywords(
         parsed_at: Utc::now(),
         file_path: file_path.unwrap_or("").to_string(),
         properties,
+        keywords,
+        startup,
+        flags,
+        constants,
         category,
         etag: generate_document_etag(content),
         todo_config,
+        tag_styles,
+        archived: file_path.is_some_and(|path| is_archive_path(Path::new(path))),
+        missing: false,
     };
 
     // Update document_id in all headlines
     let mut updated_document = document.clone();
     update_headline_document_ids(&mut updated_document.headlines, &id);
+    refresh_headline_etags(&mut updated_document);
 
     Ok(updated_document)
 }
@@ -320,16 +386,21 @@ fn extract_document_title(org: &Org) -> Option<String> {
 
 /// Extract filetags from an Org document
 fn extract_filetags(org: &Org) -> Vec<String> {
-    let mut filetags = Vec::new();
+    let mut filetags: Vec<String> = Vec::new();
 
     for event in org.iter() {
         if let orgize::Event::Start(Element::Keyword(keyword)) = event {
             if keyword.key.eq_ignore_ascii_case("FILETAGS") {
-                // Parse filetags - they are typically in format :tag1:tag2:tag3:
-                let tags_str = keyword.value.trim();
-                if tags_str.starts_with(':') && tags_str.ends_with(':') {
-                    let tags = tags_str.trim_matches(':').split(':');
-                    filetags.extend(tags.map(|s| s.to_string()));
+                // Tags are usually written :tag1:tag2:, but whitespace-separated lists are
+                // accepted too. Repeated lines add to the tags, without duplicates
+                let tags = keyword
+                    .value
+                    .split([':', ' ', '\t'])
+                    .filter(|tag| !tag.is_empty());
+                for tag in tags {
+                    if !filetags.iter().any(|existing| existing == tag) {
+                        filetags.push(tag.to_string());
+                    }
                 }
             }
         }
@@ -339,69 +410,123 @@ fn extract_filetags(org: &Org) -> Vec<String> {
 }
 
 /// Extract category from an Org document
+/// With several #+CATEGORY: lines the last one wins
 fn extract_category(org: &Org) -> Option<String> {
+    let mut category = None;
     for event in org.iter() {
         if let orgize::Event::Start(Element::Keyword(keyword)) = event {
             if keyword.key.eq_ignore_ascii_case("CATEGORY") {
-                return Some(keyword.value.to_string());
+                category = Some(keyword.value.trim().to_string());
             }
         }
     }
-    None
+    category
 }
 
-/// Extract document properties from an Org document
-fn extract_document_properties(org: &Org) -> HashMap<String, String> {
-    let mut properties = HashMap::new();
+// Values of the `#+KEY:` lines of a file, wherever they appear
+fn keyword_values<'a>(content: &'a str, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    content.lines().filter_map(move |line| {
+        let rest = line.trim_start().strip_prefix("#+")?;
+        let (name, value) = rest.split_once(':')?;
+        name.eq_ignore_ascii_case(key).then(|| value.trim())
+    })
+}
 
-    for event in org.iter() {
-        if let orgize::Event::Start(Element::Keyword(keyword)) = event {
-            // Skip special keywords that are handled separately
-            if !["TITLE", "FILETAGS", "CATEGORY", "TODO"]
-                .contains(&keyword.key.to_uppercase().as_str())
-            {
-                properties.insert(keyword.key.to_string(), keyword.value.to_string());
+// Set a file-level property, where a `KEY+` key appends to an earlier value
+fn set_file_property(properties: &mut HashMap<String, String>, key: &str, value: &str) {
+    match key.strip_suffix('+') {
+        Some(key) => {
+            let entry = properties.entry(key.to_string()).or_default();
+            if !entry.is_empty() {
+                entry.push(' ');
             }
+            entry.push_str(value);
+        }
+        None => {
+            properties.insert(key.to_string(), value.to_string());
         }
     }
+}
 
-    properties
+// Key and value lines of the property drawer before the first headline
+// As in org-mode, only blank lines and comments or keywords may come before it
+fn file_property_drawer(content: &str) -> Vec<(&str, &str)> {
+    let mut lines = content
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with('#'));
+    if !lines
+        .next()
+        .is_some_and(|line| line.eq_ignore_ascii_case(":PROPERTIES:"))
+    {
+        return Vec::new();
+    }
+
+    let mut entries = Vec::new();
+    for line in lines {
+        if line.eq_ignore_ascii_case(":END:") {
+            return entries;
+        }
+        let Some((key, value)) = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(key, _)| !key.is_empty() && !key.contains(char::is_whitespace))
+        else {
+            break;
+        };
+        entries.push((key, value.trim()));
+    }
+
+    // Without an :END: line it isn't a drawer
+    Vec::new()
 }
 
-/// Helper function to get a color for an active TODO status
-fn get_color_for_active_status(index: usize) -> String {
-    // Color palette for active statuses
-    let colors = [
-        "#ff0000", // Red for TODO
-        "#ff9900", // Orange for IN-PROGRESS
-        "#ffff00", // Yellow for WAITING
-        "#0099ff", // Blue for other active statuses
-        "#9966cc", // Purple
-    ];
-
-    if index < colors.len() {
-        colors[index].to_string()
-    } else {
-        // Fallback color for additional active statuses
-        "#0099ff".to_string()
+/// Extract the file-level properties that headlines inherit: `#+PROPERTY:` lines, then
+/// the property drawer before the first headline, which overrides them
+fn extract_file_properties(content: &str) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    for value in keyword_values(content, "PROPERTY") {
+        let (key, value) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+        set_file_property(&mut properties, key, value.trim());
+    }
+    for (key, value) in file_property_drawer(content) {
+        set_file_property(&mut properties, key, value);
     }
+    properties
 }
 
-/// Helper function to get a color for a closed TODO status
-fn get_color_for_closed_status(index: usize) -> String {
-    // Color palette for closed statuses
-    let colors = [
-        "#00ff00", // Green for DONE
-        "#999999", // Gray for CANCELLED
-        "#666666", // Dark Gray for other closed statuses
-    ];
-
-    if index < colors.len() {
-        colors[index].to_string()
-    } else {
-        // Fallback color for additional closed statuses
-        "#666666".to_string()
+/// Extract `#+CONSTANTS: name=value ...` definitions for table formulas
+fn extract_constants(content: &str) -> HashMap<String, String> {
+    keyword_values(content, "CONSTANTS")
+        .flat_map(str::split_whitespace)
+        .filter_map(|constant| constant.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Extract `#+KEY:` lines from an Org document, other than those handled separately
+fn extract_document_keywords(org: &Org) -> HashMap<String, String> {
+    let mut keywords = HashMap::new();
+
+    for event in org.iter() {
+        if let orgize::Event::Start(Element::Keyword(keyword)) = event {
+            // Skip special keywords that are handled separately
+            if ![
+                "TITLE",
+                "FILETAGS",
+                "CATEGORY",
+                "TODO",
+                "PROPERTY",
+                "CONSTANTS",
+            ]
+            .contains(&keyword.key.to_uppercase().as_str())
+            {
+                keywords.insert(keyword.key.to_string(), keyword.value.to_string());
+            }
+        }
     }
+
+    keywords
 }
 
 /// Extract TODO configuration from an Org document
@@ -414,7 +539,10 @@ fn extract_todo_configuration(
     // First check for TODO keywords in the org file content
     for event in org.iter() {
         if let orgize::Event::Start(Element::Keyword(keyword)) = event {
-            if keyword.key.eq_ignore_ascii_case("TODO") {
+            if ["TODO", "SEQ_TODO", "TYP_TODO"]
+                .iter()
+                .any(|key| keyword.key.eq_ignore_ascii_case(key))
+            {
                 todo_lines.push(keyword.value.to_string());
             }
         }
@@ -432,39 +560,10 @@ fn extract_todo_configuration(
         return None;
     }
 
-    // Create statuses from the keywords
-    let mut statuses = Vec::new();
-
-    // Add active keywords
-    for (i, keyword) in active_keywords.iter().enumerate() {
-        statuses.push(TodoStatus {
-            keyword: keyword.clone(),
-            state_type: StateType::Active,
-            order: i as u32,
-            color: Some(get_color_for_active_status(i)), // Assign color based on index
-        });
-    }
-
-    // Add closed keywords
-    for (i, keyword) in closed_keywords.iter().enumerate() {
-        statuses.push(TodoStatus {
-            keyword: keyword.clone(),
-            state_type: StateType::Closed,
-            order: (active_keywords.len() + i) as u32,
-            color: Some(get_color_for_closed_status(i)), // Assign color based on index
-        });
-    }
-
-    // Create a sequence with the statuses
-    let sequence = TodoSequence {
-        name: "default".to_string(),
-        statuses,
-    };
-
-    Some(TodoConfiguration {
-        sequences: vec![sequence],
-        default_sequence: "default".to_string(),
-    })
+    Some(TodoConfiguration::from_keywords(
+        active_keywords,
+        closed_keywords,
+    ))
 }
 
 /// Function to extract headlines with proper hierarchy and content
@@ -662,26 +761,32 @@ fn build_headline_hierarchy(flat_headlines: Vec<OrgHeadline>) -> Vec<OrgHeadline
         }
     }
 
-    // Generate etags for all headlines now that hierarchy is complete
-    for headline in &mut root_headlines {
-        generate_etags_recursively(headline);
-    }
-
     // Assign hierarchical position-based IDs
     assign_hierarchical_ids(&mut root_headlines);
 
     root_headlines
 }
 
-// Generate etags recursively for a headline and its children
-fn generate_etags_recursively(headline: &mut OrgHeadline) {
-    // Generate etags for all children first
-    for child in &mut headline.children {
-        generate_etags_recursively(child);
+// Regenerate headline etags from each headline's section source, once IDs are final
+// Planning timestamps orgize cannot parse, such as those with a repeater, are first filled
+// in from the planning line below the headline.
+fn refresh_headline_etags(document: &mut OrgDocument) {
+    fn visit(headlines: &mut [OrgHeadline], sections: &HashMap<String, String>) {
+        for headline in headlines {
+            visit(&mut headline.children, sections);
+            let section = sections.get(&headline.id).map(String::as_str).unwrap_or("");
+            if let Some(parsed) = section.lines().nth(1).and_then(OrgPlanning::from_line) {
+                let planning = headline.title.planning.get_or_insert_with(Default::default);
+                planning.deadline = planning.deadline.take().or(parsed.deadline);
+                planning.scheduled = planning.scheduled.take().or(parsed.scheduled);
+                planning.closed = planning.closed.take().or(parsed.closed);
+            }
+            headline.etag = generate_section_etag(headline, section);
+        }
     }
 
-    // Now generate etag for this headline (children already have their etags)
-    headline.etag = generate_headline_etag(headline);
+    let sections = headline_sections(document);
+    visit(&mut document.headlines, &sections);
 }
 
 // Assign hierarchical position-based IDs to headlines
@@ -727,7 +832,7 @@ fn post_process_headlines_recursive(headlines: &mut Vec<OrgHeadline>, custom_key
                 detect_custom_todo_keyword(&headline.title.raw, custom_keywords)
             {
                 // Update the headline with the detected TODO keyword
-                headline.title.todo_keyword = Some(detected_keyword.clone());
+                headline.title.todo_keyword = Some(Arc::from(detected_keyword.as_str()));
 
                 // Also update the raw title to remove the keyword from the beginning
                 let new_raw = headline.title.raw[detected_keyword.len()..]
@@ -770,14 +875,14 @@ fn extract_headline(org: &Org, headline: orgize::Headline) -> OrgHeadline {
     let level = headline.level() as u32;
 
     // Extract tags
-    let tags: Vec<String> = title_element
+    let tags: Vec<Arc<str>> = title_element
         .tags
         .iter()
-        .map(|tag| tag.to_string())
+        .map(|tag| Arc::from(tag.as_ref()))
         .collect();
 
     // Extract TODO keyword (from keyword field)
-    let todo_keyword = title_element.keyword.clone().map(|kw| kw.to_string());
+    let todo_keyword = title_element.keyword.as_deref().map(Arc::from);
 
     // Extract priority and convert to string
     let _priority = title_element.priority.map(|p| p.to_string());
@@ -790,8 +895,8 @@ fn extract_headline(org: &Org, headline: orgize::Headline) -> OrgHeadline {
         raw: raw_title,
         level: level as u8,
         priority: title_element.priority,
-        tags: tags.clone(),                 // Clone for backward compatibility
-        todo_keyword: todo_keyword.clone(), // Clone for backward compatibility
+        tags,
+        todo_keyword,
         properties: extract_properties_from_title(&title_element),
         planning,
     };
@@ -816,12 +921,12 @@ fn extract_headline(org: &Org, headline: orgize::Headline) -> OrgHeadline {
 }
 
 /// Extract properties from a title element
-fn extract_properties_from_title(title: &orgize::elements::Title) -> HashMap<String, String> {
+fn extract_properties_from_title(title: &orgize::elements::Title) -> HashMap<Arc<str>, String> {
     let mut properties = HashMap::new();
 
     if !title.properties.is_empty() {
         for (key, value) in title.properties.iter() {
-            properties.insert(key.to_string(), value.to_string());
+            properties.insert(Arc::from(key.as_ref()), value.to_string());
         }
     }
 
@@ -867,7 +972,7 @@ fn extract_headline_properties(org: &Org, headline: &orgize::Headline) -> HashMa
 
     // 作成タイムスタンプを追加（テスト用）
     if !properties.contains_key("CREATED") {
-        properties.insert("CREATED".to_string(), Utc::now().to_rfc3339());
+        properties.insert("CREATED".to_string(), time_zone::now_rfc3339());
     }
 
     println!("Extracted {} properties", properties.len());
@@ -923,9 +1028,16 @@ To-do list
                 parsed_at: Utc::now(),
                 file_path: "error.org".to_string(),
                 properties: HashMap::new(),
+                keywords: HashMap::new(),
+                startup: StartupOptions::default(),
+                flags: DocumentFlags::default(),
+                constants: HashMap::new(),
                 category: "".to_string(),
                 etag: "".to_string(),
                 todo_config: None,
+                tag_styles: HashMap::new(),
+                archived: false,
+                missing: false,
             }
         }
     }
@@ -1033,8 +1145,8 @@ Content 2
         let h2 = &doc.headlines[1];
         assert_eq!(h2.title, "Heading 2");
         assert_eq!(h2.title.level, 1);
-        assert_eq!(h2.title.todo_keyword, Some("TODO".to_string()));
-        assert_eq!(h2.title.tags, vec!["tag1".to_string()]);
+        assert_eq!(h2.title.todo_keyword.as_deref(), Some("TODO"));
+        assert_eq!(h2.title.tags, vec![Arc::from("tag1")]);
         assert!(h2.is_task());
     }
 
@@ -1051,10 +1163,10 @@ Content 2
         // Check first headline
         let h1 = &doc.headlines[0];
         assert_eq!(h1.title, "Shopping Lists [0/3]");
-        assert_eq!(h1.title.todo_keyword, Some("TODO".to_string()));
+        assert_eq!(h1.title.todo_keyword.as_deref(), Some("TODO"));
         assert_eq!(h1.title.tags.len(), 2);
-        assert!(h1.title.tags.contains(&"shopping".to_string()));
-        assert!(h1.title.tags.contains(&"chores".to_string()));
+        assert!(h1.title.tags.contains(&Arc::from("shopping")));
+        assert!(h1.title.tags.contains(&Arc::from("chores")));
         assert!(h1.is_task());
 
         // Check that h1 has the correct category from properties
@@ -1063,7 +1175,7 @@ Content 2
         // Check second headline
         let h2 = &doc.headlines[1];
         assert_eq!(h2.title, "Meeting Notes");
-        assert_eq!(h2.title.tags, vec!["work".to_string()]);
+        assert_eq!(h2.title.tags, vec![Arc::from("work")]);
         assert!(h2.is_note());
 
         // Check that h2 inherits the document category
@@ -1076,22 +1188,22 @@ Content 2
         let h2_1 = &h2.children[0];
         assert_eq!(h2_1.title, "Progress Report");
         assert_eq!(h2_1.title.level, 2);
-        assert_eq!(h2_1.title.todo_keyword, Some("DONE".to_string()));
-        assert_eq!(h2_1.title.tags, vec!["important".to_string()]);
+        assert_eq!(h2_1.title.todo_keyword.as_deref(), Some("DONE"));
+        assert_eq!(h2_1.title.tags, vec![Arc::from("important")]);
         assert!(h2_1.is_task());
 
         // Check second child of Meeting Notes
         let h2_2 = &h2.children[1];
         assert_eq!(h2_2.title, "Next Steps Planning");
         assert_eq!(h2_2.title.level, 2);
-        assert_eq!(h2_2.title.todo_keyword, Some("TODO".to_string()));
+        assert_eq!(h2_2.title.todo_keyword.as_deref(), Some("TODO"));
         assert!(h2_2.title.tags.is_empty());
         assert!(h2_2.is_task());
 
         // Check third headline
         let h3 = &doc.headlines[2];
         assert_eq!(h3.title, "Follow-up Tasks");
-        assert_eq!(h3.title.todo_keyword, Some("TODO".to_string()));
+        assert_eq!(h3.title.todo_keyword.as_deref(), Some("TODO"));
         assert!(h3.title.tags.is_empty());
         assert!(h3.is_task());
         assert_eq!(h3.children.len(), 0);
@@ -1203,7 +1315,7 @@ More content here.
 
         let task_under_note = &note.children[0];
         assert_eq!(task_under_note.title.raw, "Task under note");
-        assert_eq!(task_under_note.title.todo_keyword, Some("TODO".to_string()));
+        assert_eq!(task_under_note.title.todo_keyword.as_deref(), Some("TODO"));
         assert!(
             task_under_note.content.contains("This task should be shown"),
             "Expected content to contain 'This task should be shown', but got: {}",
@@ -1217,7 +1329,7 @@ More content here.
 
         let top_level_task = &doc.headlines[1];
         assert_eq!(top_level_task.title.raw, "Top-level task");
-        assert_eq!(top_level_task.title.todo_keyword, Some("TODO".to_string()));
+        assert_eq!(top_level_task.title.todo_keyword.as_deref(), Some("TODO"));
         assert!(
             top_level_task.content.contains("top level"),
             "Expected content to contain 'top level', but got: {}",
@@ -1302,22 +1414,22 @@ Content for WIP task
 
         // First headline should have [ ] as TODO keyword
         let h1 = &doc.headlines[0];
-        assert_eq!(h1.title.todo_keyword, Some("[ ]".to_string()));
+        assert_eq!(h1.title.todo_keyword.as_deref(), Some("[ ]"));
         assert_eq!(h1.title.raw, "Task with checkbox");
 
         // Second headline should have [X] as TODO keyword (done)
         let h2 = &doc.headlines[1];
-        assert_eq!(h2.title.todo_keyword, Some("[X]".to_string()));
+        assert_eq!(h2.title.todo_keyword.as_deref(), Some("[X]"));
         assert_eq!(h2.title.raw, "Completed checkbox task");
 
         // Third headline should have regular TODO keyword (detected by orgize)
         let h3 = &doc.headlines[2];
-        assert_eq!(h3.title.todo_keyword, Some("TODO".to_string()));
+        assert_eq!(h3.title.todo_keyword.as_deref(), Some("TODO"));
         assert_eq!(h3.title.raw, "Regular keyword");
 
         // Fourth headline should have [WIP] as TODO keyword
         let h4 = &doc.headlines[3];
-        assert_eq!(h4.title.todo_keyword, Some("[WIP]".to_string()));
+        assert_eq!(h4.title.todo_keyword.as_deref(), Some("[WIP]"));
         assert_eq!(h4.title.raw, "Work in progress");
     }
 
@@ -1388,4 +1500,91 @@ Content for WIP task
         println!("H2 content: {:?}", h2.content);
         assert!(h2.content.contains("This task has no planning"), "H2 should have content");
     }
+
+    #[test]
+    fn test_archive_document_flag() {
+        let content = "* DONE Old task\n";
+
+        let doc = parse_org_document(content, Some("/notes/todo.org_archive")).unwrap();
+        assert!(doc.archived);
+
+        let doc = parse_org_document(content, Some("/notes/todo.org")).unwrap();
+        assert!(!doc.archived);
+    }
+
+    #[test]
+    fn test_file_property_drawer() {
+        let content = "# roam note\n:PROPERTIES:\n:ID:       5a1e-42\n:ROAM_ALIASES: Garden\n:ROAM_ALIASES+: Yard\n:CATEGORY: home\n:END:\n#+TITLE: Garden\n#+STARTUP: overview\n* Weeding\n";
+        let doc = parse_org_document(content, Some("garden.org")).unwrap();
+
+        assert_eq!(doc.properties["ID"], "5a1e-42");
+        assert_eq!(doc.properties["ROAM_ALIASES"], "Garden Yard");
+        assert_eq!(doc.category, "home");
+        assert_eq!(doc.keywords["STARTUP"], "overview");
+        assert!(!doc.keywords.contains_key("ID"));
+        assert!(!doc.properties.contains_key("STARTUP"));
+        // Headlines inherit file-level properties
+        assert_eq!(
+            doc.get_inherited_property(&doc.headlines[0].id, "id"),
+            Some("5a1e-42")
+        );
+
+        // A drawer after other content, or without :END:, is not file-level
+        let late = parse_org_document("Intro\n:PROPERTIES:\n:ID: x\n:END:\n", None).unwrap();
+        assert!(late.properties.is_empty());
+        let open = parse_org_document(":PROPERTIES:\n:ID: x\n* Task\n", None).unwrap();
+        assert!(open.properties.is_empty());
+    }
+
+    #[test]
+    fn test_property_keywords() {
+        let content = "#+PROPERTY: header-args :results silent\n#+PROPERTY: header-args+ :exports both\n#+PROPERTY: Effort_ALL 0:30 1:00\n#+PROPERTY: Owner alice\n#+CONSTANTS: c=299792458. pi=3.14\n:PROPERTIES:\n:Owner: bob\n:END:\n* Project\n:PROPERTIES:\n:Effort_ALL: 2:00\n:END:\n** Task\n";
+        let doc = parse_org_document(content, Some("defaults.org")).unwrap();
+        let task = &doc.headlines[0].children[0].id;
+
+        assert_eq!(
+            doc.properties["header-args"],
+            ":results silent :exports both"
+        );
+        // The top property drawer overrides #+PROPERTY: lines
+        assert_eq!(doc.get_inherited_property(task, "owner"), Some("bob"));
+        assert_eq!(doc.get_inherited_property(task, "Effort_ALL"), Some("2:00"));
+        assert_eq!(doc.get_file_property("effort_all"), Some("0:30 1:00"));
+        assert_eq!(doc.constants["pi"], "3.14");
+        assert!(!doc.keywords.contains_key("PROPERTY"));
+    }
+
+    #[test]
+    fn test_repeated_file_keywords() {
+        let content = include_str!("../../../test_files/keywords-test.org");
+        let doc = parse_org_document(content, Some("keywords-test.org")).unwrap();
+        assert_eq!(doc.filetags, vec!["work", "reports", "quarterly", "review"]);
+        assert_eq!(doc.category, "Reporting");
+
+        // Whitespace-separated FILETAGS, as written in the other test files
+        let notes = include_str!("../../../test_files/notes.org");
+        let doc = parse_org_document(notes, Some("notes.org")).unwrap();
+        assert_eq!(
+            doc.filetags,
+            vec!["meetings", "notes", "ideas", "brainstorm"]
+        );
+        assert_eq!(doc.category, "Notes");
+    }
+
+    #[test]
+    fn test_headline_etags_follow_section_source() {
+        let before = parse_org_document(
+            "* Task\n:LOGBOOK:\nCLOCK: [2024-01-01 Mon 09:00]--[2024-01-01 Mon 10:00] =>  1:00\n:END:\n* Other\n",
+            Some("etag.org"),
+        )
+        .unwrap();
+        let after = parse_org_document(
+            "* Task\n:LOGBOOK:\nCLOCK: [2024-01-01 Mon 09:00]--[2024-01-01 Mon 11:00] =>  2:00\n:END:\n* Other\n",
+            Some("etag.org"),
+        )
+        .unwrap();
+
+        assert_ne!(before.headlines[0].etag, after.headlines[0].etag);
+        assert_eq!(before.headlines[1].etag, after.headlines[1].etag);
+    }
 }
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// Number of recent parses kept for perf telemetry
+const MAX_PARSE_TIMINGS: usize = 200;
+
+/// How long parsing one file took
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ParseTiming {
+    pub file_path: String,
+    pub micros: u32,
+    pub bytes: u32,
+    pub headlines: u32,
+}
+
+/// Recent parse durations and repository sizes, for spotting slow files and regressions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct PerfStats {
+    pub documents: u32,
+    pub headlines: u32,
+    pub content_bytes: u32,
+    pub indexed_documents: u32,
+    pub average_parse_micros: u32,
+    pub recent_parses: Vec<ParseTiming>, // Newest first
+}
+
+/// Bounded history of parse timings
+#[derive(Debug, Default)]
+pub struct ParseTimings {
+    timings: VecDeque<ParseTiming>,
+}
+
+impl ParseTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, file_path: &str, elapsed: Duration, bytes: usize, headlines: usize) {
+        if self.timings.len() == MAX_PARSE_TIMINGS {
+            self.timings.pop_front();
+        }
+        self.timings.push_back(ParseTiming {
+            file_path: file_path.to_string(),
+            micros: u32::try_from(elapsed.as_micros()).unwrap_or(u32::MAX),
+            bytes: bytes as u32,
+            headlines: headlines as u32,
+        });
+    }
+
+    /// Recorded timings, newest first
+    pub fn recent(&self) -> Vec<ParseTiming> {
+        self.timings.iter().rev().cloned().collect()
+    }
+
+    pub fn average_micros(&self) -> u32 {
+        if self.timings.is_empty() {
+            return 0;
+        }
+        let total: u64 = self.timings.iter().map(|t| u64::from(t.micros)).sum();
+        (total / self.timings.len() as u64) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timings() {
+        let mut timings = ParseTimings::new();
+        assert_eq!(timings.average_micros(), 0);
+
+        for i in 0..MAX_PARSE_TIMINGS + 1 {
+            timings.record(&format!("{}.org", i), Duration::from_micros(10), 100, 2);
+        }
+        timings.record("slow.org", Duration::from_micros(2010), 100, 2);
+
+        let recent = timings.recent();
+        assert_eq!(recent.len(), MAX_PARSE_TIMINGS);
+        assert_eq!(recent[0].file_path, "slow.org");
+        assert_eq!(timings.average_micros(), 20);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::effort::EFFORT_PROPERTY;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::properties::parse_duration_minutes;
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::timestamp::OrgTimestamp;
+use crate::orgmode::todo::TodoConfiguration;
+use crate::orgmode::urgency::{urgency, UrgencyWeights};
+use crate::orgmode::work_schedule::WorkSchedule;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+/// How much unscheduled work the planner spreads over each working day, and how far ahead
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PlannerConfig {
+    pub enabled: bool,
+    pub daily_minutes: u32, // Task work planned per working day, at most the working hours
+    pub horizon_days: u32,  // How many days ahead suggestions may go
+    pub default_effort_minutes: u32, // Assumed effort of tasks without an Effort property
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            daily_minutes: 360,
+            horizon_days: 14,
+            default_effort_minutes: 30,
+        }
+    }
+}
+
+/// A proposed SCHEDULED date for an unscheduled task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ScheduleSuggestion {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub date: String, // YYYY-MM-DD
+    pub effort_minutes: u32,
+}
+
+// An open task with the facts the planner needs
+struct Task<'a> {
+    document: &'a OrgDocument,
+    headline: &'a OrgHeadline,
+    effort: u32,
+    scheduled: Option<NaiveDate>,
+    deadline: Option<NaiveDate>,
+}
+
+fn open_tasks<'a>(
+    documents: &[&'a OrgDocument],
+    config: &PlannerConfig,
+    settings_config: &TodoConfiguration,
+) -> Vec<Task<'a>> {
+    let mut tasks = Vec::new();
+    for document in documents {
+        if document.missing || document.archived || document.flags.no_agenda {
+            continue;
+        }
+        let todo_config = document.effective_todo_config(settings_config);
+
+        for headline in flatten_headlines(&document.headlines) {
+            let is_open = headline
+                .get_todo_status(&todo_config)
+                .is_some_and(|status| status.is_active());
+            if !is_open {
+                continue;
+            }
+            let planning = headline.title.planning.as_deref();
+            let date = |timestamp: Option<&OrgTimestamp>| {
+                timestamp
+                    .and_then(|timestamp| timestamp.start_date())
+                    .map(|date| date.to_naive_date())
+            };
+            tasks.push(Task {
+                document,
+                headline,
+                effort: headline
+                    .title
+                    .get_property_ignore_case(EFFORT_PROPERTY)
+                    .and_then(parse_duration_minutes)
+                    .unwrap_or(config.default_effort_minutes),
+                scheduled: date(planning.and_then(|planning| planning.scheduled.as_ref())),
+                deadline: date(planning.and_then(|planning| planning.deadline.as_ref())),
+            });
+        }
+    }
+    tasks
+}
+
+/// Propose SCHEDULED dates for open tasks that have none
+///
+/// Tasks are placed most urgent first on the earliest working day within the horizon
+/// (skipping weekends and holidays of the work schedule) whose planned effort still fits
+/// in `daily_minutes` and the working hours, counting the effort of tasks
+/// already scheduled there (overdue ones count against today). A task longer than a day
+/// gets a day of its own. Tasks with a deadline are only placed on or before it, on the
+/// least loaded day if none has room. Tasks that fit nowhere get no suggestion. Files' own
+/// `#+TODO:` keywords come before `settings_config`.
+pub fn suggest_schedule(
+    documents: &[&OrgDocument],
+    config: &PlannerConfig,
+    schedule: &WorkSchedule,
+    weights: &UrgencyWeights,
+    today: NaiveDate,
+    settings_config: &TodoConfiguration,
+) -> Vec<ScheduleSuggestion> {
+    let days: Vec<NaiveDate> = (0..u64::from(config.horizon_days))
+        .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+        .filter(|day| schedule.is_working_day(*day))
+        .collect();
+    let capacity = config.daily_minutes.min(schedule.working_minutes());
+    let tasks = open_tasks(documents, config, settings_config);
+
+    let mut load: HashMap<NaiveDate, u32> = HashMap::new();
+    for task in &tasks {
+        if let Some(scheduled) = task.scheduled {
+            *load.entry(scheduled.max(today)).or_default() += task.effort;
+        }
+    }
+
+    let mut unscheduled: Vec<(f64, &Task)> = tasks
+        .iter()
+        .filter(|task| task.scheduled.is_none())
+        .map(|task| (urgency(task.headline, weights, today), task))
+        .collect();
+    unscheduled.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    let mut suggestions = Vec::new();
+    for (_, task) in unscheduled {
+        let candidates: Vec<NaiveDate> = days
+            .iter()
+            .copied()
+            .filter(|day| task.deadline.is_none_or(|deadline| *day <= deadline))
+            .collect();
+        let day_load = |day: &NaiveDate| load.get(day).copied().unwrap_or(0);
+        let fits = candidates.iter().copied().find(|day| {
+            let planned = day_load(day);
+            planned == 0 || planned + task.effort <= capacity
+        });
+        let chosen = match (fits, task.deadline) {
+            (Some(day), _) => Some(day),
+            (None, Some(_)) => candidates.iter().copied().min_by_key(day_load),
+            (None, None) => None,
+        };
+        let Some(day) = chosen else {
+            continue;
+        };
+
+        *load.entry(day).or_default() += task.effort;
+        suggestions.push(ScheduleSuggestion {
+            document_id: task.document.id.clone(),
+            headline_id: task.headline.id.clone(),
+            title: task.headline.title.raw.clone(),
+            date: day.format("%Y-%m-%d").to_string(),
+            effort_minutes: task.effort,
+        });
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::work_schedule::Holiday;
+
+    #[test]
+    fn test_suggest_schedule() {
+        let doc = parse_org_document(
+            "* TODO Meeting prep\nSCHEDULED: <2024-03-04 Mon>\n:PROPERTIES:\n:Effort: 5:00\n:END:\n* TODO [#A] Report\n:PROPERTIES:\n:Effort: 2:00\n:END:\n* TODO Invoice\nDEADLINE: <2024-03-04 Mon>\n:PROPERTIES:\n:Effort: 3:00\n:END:\n* TODO Backlog\n* DONE Old\n",
+            Some("work.org"),
+        )
+        .unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let config = PlannerConfig {
+            enabled: true,
+            horizon_days: 7,
+            ..PlannerConfig::default()
+        };
+
+        let schedule = WorkSchedule::default();
+        let weights = UrgencyWeights::default();
+        let keywords = TodoConfiguration::default();
+
+        let suggestions =
+            suggest_schedule(&[&doc], &config, &schedule, &weights, monday, &keywords);
+        let dates: Vec<(&str, &str)> = suggestions
+            .iter()
+            .map(|suggestion| (suggestion.title.as_str(), suggestion.date.as_str()))
+            .collect();
+        // The invoice is due today, so it goes there even though Monday is full
+        assert_eq!(
+            dates,
+            vec![
+                ("Invoice", "2024-03-04"),
+                ("Report", "2024-03-05"),
+                ("Backlog", "2024-03-05"),
+            ]
+        );
+        assert_eq!(suggestions[2].effort_minutes, 30);
+
+        // Weekends and holidays are skipped
+        let holiday = WorkSchedule {
+            holidays: vec![Holiday {
+                date: "2024-03-05".to_string(),
+                name: "Carnival".to_string(),
+            }],
+            ..schedule.clone()
+        };
+        let suggestions = suggest_schedule(&[&doc], &config, &holiday, &weights, monday, &keywords);
+        assert_eq!(suggestions[1].date, "2024-03-06");
+        let saturday = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let weekend = PlannerConfig {
+            horizon_days: 2,
+            ..config
+        };
+        assert!(
+            suggest_schedule(&[&doc], &weekend, &schedule, &weights, saturday, &keywords)
+                .is_empty()
+        );
+    }
+}
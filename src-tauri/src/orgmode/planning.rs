@@ -1,3 +1,4 @@
+use crate::orgmode::logbook::parse_timestamp_inner;
 use crate::orgmode::timestamp::OrgTimestamp;
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -25,6 +26,48 @@ impl OrgPlanning {
         self.deadline.is_none() && self.scheduled.is_none() && self.closed.is_none()
     }
     
+    /// Parse a planning line like `SCHEDULED: <2024-06-03 Mon +1w> DEADLINE: <2024-06-07 Fri>`
+    /// Returns None if the line sets none of the three timestamps
+    pub fn from_line(line: &str) -> Option<Self> {
+        let timestamp = |keyword: &str| {
+            let rest = line[line.find(keyword)? + keyword.len()..].trim_start();
+            let (active, close) = match rest.chars().next()? {
+                '<' => (true, '>'),
+                '[' => (false, ']'),
+                _ => return None,
+            };
+            let inner = &rest[1..rest.find(close)?];
+            let start = parse_timestamp_inner(inner)?;
+            let part = |prefixes: &[&str]| {
+                inner
+                    .split_whitespace()
+                    .skip(1)
+                    .find(|part| prefixes.iter().any(|prefix| part.starts_with(prefix)))
+                    .map(str::to_string)
+            };
+            let (repeater, delay) = (part(&["+", ".+"]), part(&["-"]));
+            Some(if active {
+                OrgTimestamp::Active {
+                    start,
+                    repeater,
+                    delay,
+                }
+            } else {
+                OrgTimestamp::Inactive {
+                    start,
+                    repeater,
+                    delay,
+                }
+            })
+        };
+        let planning = Self {
+            deadline: timestamp("DEADLINE:"),
+            scheduled: timestamp("SCHEDULED:"),
+            closed: timestamp("CLOSED:"),
+        };
+        (!planning.is_empty()).then_some(planning)
+    }
+
     /// Get formatted deadline timestamp string if it exists
     pub fn formatted_deadline(&self) -> Option<String> {
         self.deadline.as_ref().map(|ts| ts.format())
@@ -0,0 +1,569 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::flatten_headlines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use wasmi::{
+    Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+/// Version of the host interface plugins are built against
+///
+/// A plugin is a WebAssembly module that exports:
+/// - `memory`
+/// - `orgx_alloc(len: i32) -> i32`, returning a buffer the host writes input into
+/// - `orgx_manifest() -> i64`, returning its JSON manifest
+/// - a `(ptr: i32, len: i32) -> i64` function for each function in the manifest
+///
+/// Outputs are returned as `ptr << 32 | len`. Plugins may import `orgx.log(ptr, len)` to
+/// write a line to the app log; nothing else is available to them.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Directory in the app data directory that plugins are loaded from
+pub const PLUGIN_DIR_NAME: &str = "plugins";
+
+// Instructions a plugin may run per call before it is stopped
+const PLUGIN_FUEL: u64 = 100_000_000;
+
+// Largest linear memory a plugin may grow to
+const PLUGIN_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// What a plugin function is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PluginFunctionKind {
+    Query,            // Gets `{ argument, headlines }`, returns a JSON bool per headline
+    Exporter,         // Gets a document as JSON, returns the exported text
+    CaptureProcessor, // Gets captured text before it is filed, returns the text to file
+}
+
+/// A function a plugin registers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PluginFunction {
+    pub kind: PluginFunctionKind,
+    pub name: String, // Name of the exported function
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A plugin found in the plugin directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PluginInfo {
+    pub id: String, // File name without the .wasm extension
+    pub name: String,
+    pub version: String,
+    pub file_path: String,
+    pub functions: Vec<PluginFunction>,
+    pub error: Option<String>, // Why the plugin could not be loaded
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    api_version: u32,
+    name: String,
+    version: String,
+    functions: Vec<PluginFunction>,
+}
+
+/// A headline as plugins see it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginHeadline {
+    pub document_id: String,
+    pub headline_id: String,
+    pub level: u8,
+    pub title: String,
+    pub todo_keyword: Option<String>,
+    pub priority: Option<char>,
+    pub tags: Vec<String>,
+    pub properties: BTreeMap<String, String>,
+    pub scheduled: Option<String>, // YYYY-MM-DD
+    pub deadline: Option<String>,  // YYYY-MM-DD
+    pub content: String,
+}
+
+impl PluginHeadline {
+    pub fn new(document: &OrgDocument, headline: &OrgHeadline) -> Self {
+        let planning = headline.title.planning.as_deref();
+        let date = |timestamp: Option<&crate::orgmode::timestamp::OrgTimestamp>| {
+            timestamp
+                .and_then(|timestamp| timestamp.start_date())
+                .map(|date| date.to_naive_date().format("%Y-%m-%d").to_string())
+        };
+        Self {
+            document_id: document.id.clone(),
+            headline_id: headline.id.clone(),
+            level: headline.title.level,
+            title: headline.title.raw.clone(),
+            todo_keyword: headline.title.todo_keyword.as_deref().map(str::to_string),
+            priority: headline.title.priority,
+            tags: headline
+                .title
+                .tags
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
+            properties: headline
+                .title
+                .properties
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+            scheduled: date(planning.and_then(|planning| planning.scheduled.as_ref())),
+            deadline: date(planning.and_then(|planning| planning.deadline.as_ref())),
+            content: headline.content.clone(),
+        }
+    }
+}
+
+/// A document as exporters see it, with its headlines in file order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginDocument {
+    pub id: String,
+    pub title: String,
+    pub file_path: String,
+    pub content: String,
+    pub headlines: Vec<PluginHeadline>,
+}
+
+impl PluginDocument {
+    pub fn new(document: &OrgDocument) -> Self {
+        Self {
+            id: document.id.clone(),
+            title: document.title.clone(),
+            file_path: document.file_path.clone(),
+            content: document.content.clone(),
+            headlines: flatten_headlines(&document.headlines)
+                .into_iter()
+                .map(|headline| PluginHeadline::new(document, headline))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct QueryInput<'a> {
+    argument: &'a str,
+    headlines: &'a [PluginHeadline],
+}
+
+struct HostState {
+    plugin: String,
+    limits: StoreLimits,
+}
+
+// An instantiated plugin with its own store, fuel and memory limit
+struct Session {
+    store: Store<HostState>,
+    instance: Instance,
+    memory: Memory,
+}
+
+// Bytes of a `ptr << 32 | len` output, if they lie within the memory
+fn packed_bytes(data: &[u8], packed: i64) -> Option<Vec<u8>> {
+    let packed = packed as u64;
+    let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    data.get(ptr..ptr.checked_add(len)?).map(<[u8]>::to_vec)
+}
+
+impl Session {
+    fn start(engine: &Engine, module: &Module, plugin: &str) -> Result<Self, String> {
+        let state = HostState {
+            plugin: plugin.to_string(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(PLUGIN_MEMORY_BYTES)
+                .build(),
+        };
+        let mut store = Store::new(engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(PLUGIN_FUEL).map_err(|e| e.to_string())?;
+
+        let mut linker = Linker::<HostState>::new(engine);
+        linker
+            .func_wrap(
+                "orgx",
+                "log",
+                |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                    let packed = (i64::from(ptr) << 32) | i64::from(len as u32);
+                    let text = caller
+                        .get_export("memory")
+                        .and_then(Extern::into_memory)
+                        .and_then(|memory| packed_bytes(memory.data(&caller), packed));
+                    if let Some(text) = text {
+                        println!(
+                            "Plugin {}: {}",
+                            caller.data().plugin,
+                            String::from_utf8_lossy(&text)
+                        );
+                    }
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        let instance = linker
+            .instantiate(&mut store, module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|e| format!("Failed to start plugin {}: {}", plugin, e))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| format!("Plugin {} does not export its memory", plugin))?;
+
+        Ok(Self {
+            store,
+            instance,
+            memory,
+        })
+    }
+
+    fn read_output(&self, function: &str, packed: i64) -> Result<Vec<u8>, String> {
+        packed_bytes(self.memory.data(&self.store), packed).ok_or_else(|| {
+            format!(
+                "Plugin {} returned output outside its memory from {}",
+                self.store.data().plugin,
+                function
+            )
+        })
+    }
+
+    fn manifest(&mut self) -> Result<Vec<u8>, String> {
+        let manifest = self
+            .instance
+            .get_typed_func::<(), i64>(&self.store, "orgx_manifest")
+            .map_err(|e| format!("Missing orgx_manifest: {}", e))?;
+        let packed = manifest
+            .call(&mut self.store, ())
+            .map_err(|e| format!("orgx_manifest failed: {}", e))?;
+        self.read_output("orgx_manifest", packed)
+    }
+
+    // Copy the input into the plugin's memory, call the function and copy its output out
+    fn call(&mut self, function: &str, input: &[u8]) -> Result<Vec<u8>, String> {
+        let plugin = self.store.data().plugin.clone();
+        let failed = |e: wasmi::Error| format!("Plugin {} failed in {}: {}", plugin, function, e);
+        let len = i32::try_from(input.len())
+            .map_err(|_| format!("Input to plugin {} is too large", plugin))?;
+
+        let alloc = self
+            .instance
+            .get_typed_func::<i32, i32>(&self.store, "orgx_alloc")
+            .map_err(failed)?;
+        let ptr = alloc.call(&mut self.store, len).map_err(failed)?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, input)
+            .map_err(|e| format!("Plugin {} gave an invalid buffer: {}", plugin, e))?;
+
+        let func = self
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&self.store, function)
+            .map_err(failed)?;
+        let packed = func.call(&mut self.store, (ptr, len)).map_err(failed)?;
+        self.read_output(function, packed)
+    }
+}
+
+// A plugin and its compiled module, None if it failed to load
+struct Plugin {
+    info: PluginInfo,
+    module: Option<Module>,
+}
+
+/// Loaded WebAssembly plugins
+///
+/// Plugins run in an interpreter with no access to files, the network or the app beyond
+/// their inputs. Each call gets a fresh instance with a fuel and memory budget.
+pub struct PluginHost {
+    engine: Engine,
+    plugins: Vec<Plugin>,
+}
+
+impl Default for PluginHost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluginHost {
+    pub fn new() -> Self {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        Self {
+            engine: Engine::new(&config),
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Load every `.wasm` file in a directory, in file name order
+    /// Plugins that fail to load are listed with their error
+    pub fn load_dir(dir: &Path) -> Self {
+        let mut host = Self::new();
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        for path in paths {
+            match fs::read(&path) {
+                Ok(bytes) => host.add(&path, &bytes),
+                Err(e) => eprintln!("Failed to read plugin {}: {}", path.display(), e),
+            }
+        }
+        host
+    }
+
+    /// Compile a plugin and read its manifest
+    pub fn add(&mut self, path: &Path, bytes: &[u8]) {
+        let id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut info = PluginInfo {
+            id: id.clone(),
+            name: id.clone(),
+            version: String::new(),
+            file_path: path.display().to_string(),
+            functions: Vec::new(),
+            error: None,
+        };
+
+        let loaded = Module::new(&self.engine, bytes)
+            .map_err(|e| format!("Invalid WebAssembly module: {}", e))
+            .and_then(|module| {
+                let manifest = Session::start(&self.engine, &module, &id)?.manifest()?;
+                let manifest: Manifest = serde_json::from_slice(&manifest)
+                    .map_err(|e| format!("Invalid plugin manifest: {}", e))?;
+                if manifest.api_version != PLUGIN_API_VERSION {
+                    return Err(format!(
+                        "Plugin is built for API version {}, but this app provides version {}",
+                        manifest.api_version, PLUGIN_API_VERSION
+                    ));
+                }
+                Ok((module, manifest))
+            });
+        let module = match loaded {
+            Ok((module, manifest)) => {
+                info.name = manifest.name;
+                info.version = manifest.version;
+                info.functions = manifest.functions;
+                Some(module)
+            }
+            Err(e) => {
+                info.error = Some(e);
+                None
+            }
+        };
+        self.plugins.push(Plugin { info, module });
+    }
+
+    /// The loaded plugins, including ones that failed to load
+    pub fn plugins(&self) -> Vec<PluginInfo> {
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.info.clone())
+            .collect()
+    }
+
+    // Start a session for a plugin function, checking the function is registered as `kind`
+    fn session(
+        &self,
+        plugin_id: &str,
+        function: &str,
+        kind: PluginFunctionKind,
+    ) -> Result<Session, String> {
+        let plugin = self
+            .plugins
+            .iter()
+            .find(|plugin| plugin.info.id == plugin_id)
+            .ok_or_else(|| format!("Plugin not found: {}", plugin_id))?;
+        let module = plugin.module.as_ref().ok_or_else(|| {
+            format!(
+                "Plugin {} is not loaded: {}",
+                plugin_id,
+                plugin.info.error.as_deref().unwrap_or_default()
+            )
+        })?;
+        if !plugin
+            .info
+            .functions
+            .iter()
+            .any(|registered| registered.name == function && registered.kind == kind)
+        {
+            return Err(format!(
+                "Plugin {} has no {:?} function named {}",
+                plugin_id, kind, function
+            ));
+        }
+        Session::start(&self.engine, module, plugin_id)
+    }
+
+    /// Run a query function over headlines, returning whether each one matches
+    pub fn query(
+        &self,
+        plugin_id: &str,
+        function: &str,
+        argument: &str,
+        headlines: &[PluginHeadline],
+    ) -> Result<Vec<bool>, String> {
+        let input = serde_json::to_vec(&QueryInput {
+            argument,
+            headlines,
+        })
+        .map_err(|e| e.to_string())?;
+        let output = self
+            .session(plugin_id, function, PluginFunctionKind::Query)?
+            .call(function, &input)?;
+        let matches: Vec<bool> = serde_json::from_slice(&output).map_err(|e| {
+            format!(
+                "Plugin {} returned an invalid query result: {}",
+                plugin_id, e
+            )
+        })?;
+        if matches.len() != headlines.len() {
+            return Err(format!(
+                "Plugin {} returned {} query results for {} headlines",
+                plugin_id,
+                matches.len(),
+                headlines.len()
+            ));
+        }
+        Ok(matches)
+    }
+
+    /// Export a document with an exporter function
+    pub fn export(
+        &self,
+        plugin_id: &str,
+        function: &str,
+        document: &PluginDocument,
+    ) -> Result<String, String> {
+        let input = serde_json::to_vec(document).map_err(|e| e.to_string())?;
+        let output = self
+            .session(plugin_id, function, PluginFunctionKind::Exporter)?
+            .call(function, &input)?;
+        String::from_utf8(output)
+            .map_err(|_| format!("Plugin {} exported invalid UTF-8", plugin_id))
+    }
+
+    /// Pass captured text through every capture processor, in plugin order
+    pub fn process_capture(&self, text: &str) -> Result<String, String> {
+        let mut text = text.to_string();
+        for plugin in &self.plugins {
+            for function in &plugin.info.functions {
+                if function.kind != PluginFunctionKind::CaptureProcessor {
+                    continue;
+                }
+                let output = self
+                    .session(&plugin.info.id, &function.name, function.kind)?
+                    .call(&function.name, text.as_bytes())?;
+                text = String::from_utf8(output)
+                    .map_err(|_| format!("Plugin {} returned invalid UTF-8", plugin.info.id))?;
+            }
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    // A plugin with a query matching headlines whose JSON contains "urgent", an exporter
+    // returning its input and a capture processor that upper-cases ASCII letters
+    const PLUGIN: &str = r#"
+(module
+  (import "orgx" "log" (func $log (param i32 i32)))
+  (memory (export "memory") 2)
+  (global $next (mut i32) (i32.const 4096))
+  (data (i32.const 0) "{\"api_version\":1,\"name\":\"Sample\",\"version\":\"1.0\",\"functions\":[{\"kind\":\"Query\",\"name\":\"has_tags\"},{\"kind\":\"Exporter\",\"name\":\"echo\"},{\"kind\":\"CaptureProcessor\",\"name\":\"shout\"}]}")
+  (data (i32.const 1024) "[true,false]")
+  (func (export "orgx_alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+  (func $pack (param $ptr i32) (param $len i32) (result i64)
+    (i64.or (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+            (i64.extend_i32_u (local.get $len))))
+  (func (export "orgx_manifest") (result i64)
+    (call $pack (i32.const 0) (i32.const 175)))
+  (func (export "has_tags") (param $ptr i32) (param $len i32) (result i64)
+    (call $log (local.get $ptr) (i32.const 12))
+    (call $pack (i32.const 1024) (i32.const 12)))
+  (func (export "echo") (param $ptr i32) (param $len i32) (result i64)
+    (call $pack (local.get $ptr) (local.get $len)))
+  (func (export "shout") (param $ptr i32) (param $len i32) (result i64)
+    (local $i i32) (local $c i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+        (if (i32.and (i32.ge_u (local.get $c) (i32.const 97)) (i32.le_u (local.get $c) (i32.const 122)))
+          (then (i32.store8 (i32.add (local.get $ptr) (local.get $i)) (i32.sub (local.get $c) (i32.const 32)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (call $pack (local.get $ptr) (local.get $len)))
+  (func (export "spin") (param $ptr i32) (param $len i32) (result i64)
+    (loop $forever (br $forever))
+    (i64.const 0)))
+"#;
+
+    fn host() -> PluginHost {
+        let mut host = PluginHost::new();
+        host.add(
+            Path::new("/plugins/sample.wasm"),
+            &wat::parse_str(PLUGIN).unwrap(),
+        );
+        host
+    }
+
+    #[test]
+    fn test_load_plugin() {
+        let host = host();
+        let info = &host.plugins()[0];
+        assert_eq!(info.error, None);
+        assert_eq!((info.id.as_str(), info.name.as_str()), ("sample", "Sample"));
+        assert_eq!(info.functions.len(), 3);
+
+        let mut broken = PluginHost::new();
+        broken.add(Path::new("broken.wasm"), b"not wasm");
+        assert!(broken.plugins()[0].error.is_some());
+    }
+
+    #[test]
+    fn test_plugin_functions() {
+        let host = host();
+        let doc = parse_org_document("* TODO Pay rent\n* Notes\n", Some("home.org")).unwrap();
+        let document = PluginDocument::new(&doc);
+        assert_eq!(
+            host.query("sample", "has_tags", "", &document.headlines),
+            Ok(vec![true, false])
+        );
+        let exported = host.export("sample", "echo", &document).unwrap();
+        assert!(exported.contains("\"title\":\"Pay rent\""));
+        assert_eq!(host.process_capture("buy milk").unwrap(), "BUY MILK");
+
+        // Functions must be registered under the kind they are called as
+        assert!(host.export("sample", "shout", &document).is_err());
+        assert!(host.query("missing", "has_tags", "", &[]).is_err());
+    }
+
+    #[test]
+    fn test_plugin_runaway_loop() {
+        let mut host = host();
+        host.plugins[0].info.functions.push(PluginFunction {
+            kind: PluginFunctionKind::Exporter,
+            name: "spin".to_string(),
+            description: String::new(),
+        });
+        let doc = parse_org_document("* Notes\n", Some("notes.org")).unwrap();
+        // The loop runs out of fuel instead of hanging the app
+        assert!(host
+            .export("sample", "spin", &PluginDocument::new(&doc))
+            .is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::orgmode::dependencies::DependencyGraph;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::next_actions::is_next_action;
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::todo::TodoConfiguration;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Where a project stands in a GTD weekly review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ProjectStatus {
+    Active, // Has at least one next action
+    Stuck,  // Has open tasks, but none can be worked on now
+    Done,   // Closed, or no open tasks left
+}
+
+/// A headline with child tasks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Project {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub todo_keyword: Option<String>,
+    pub status: ProjectStatus,
+    pub open_tasks: u32,   // Open tasks anywhere below the project
+    pub done_tasks: u32,   // Closed tasks anywhere below the project
+    pub next_actions: u32, // Open tasks below the project that can be worked on now
+}
+
+// Projects have at least one direct child task
+fn is_project(headline: &OrgHeadline) -> bool {
+    headline.children.iter().any(|child| child.is_task())
+}
+
+fn classify(
+    document: &OrgDocument,
+    headline: &OrgHeadline,
+    config: &TodoConfiguration,
+    graph: &DependencyGraph,
+) -> Project {
+    let mut open_tasks = 0;
+    let mut done_tasks = 0;
+    let mut next_actions = 0;
+    for task in flatten_headlines(&headline.children) {
+        match task.get_todo_status(config) {
+            Some(status) if status.is_active() => {
+                open_tasks += 1;
+                if is_next_action(document, task, config, graph) {
+                    next_actions += 1;
+                }
+            }
+            Some(status) if status.is_closed() => done_tasks += 1,
+            _ => {}
+        }
+    }
+
+    let closed = headline
+        .get_todo_status(config)
+        .is_some_and(|status| status.is_closed());
+    let status = if closed || open_tasks == 0 {
+        ProjectStatus::Done
+    } else if next_actions > 0 {
+        ProjectStatus::Active
+    } else {
+        ProjectStatus::Stuck
+    };
+
+    Project {
+        document_id: document.id.clone(),
+        headline_id: headline.id.clone(),
+        title: headline.title.raw.clone(),
+        todo_keyword: headline.title.todo_keyword.as_deref().map(str::to_string),
+        status,
+        open_tasks,
+        done_tasks,
+        next_actions,
+    }
+}
+
+/// Find projects in file order; subprojects are listed as projects of their own
+/// Missing and read-only archived documents are skipped
+pub fn collect_projects(documents: &[&OrgDocument]) -> Vec<Project> {
+    let graph = DependencyGraph::build(documents);
+    let mut projects = Vec::new();
+
+    for document in documents {
+        if document.missing || document.archived {
+            continue;
+        }
+        let config = document
+            .todo_config
+            .clone()
+            .unwrap_or_else(TodoConfiguration::default);
+        projects.extend(
+            flatten_headlines(&document.headlines)
+                .into_iter()
+                .filter(|headline| is_project(headline))
+                .map(|headline| classify(document, headline, &config, &graph)),
+        );
+    }
+
+    projects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_collect_projects() {
+        let doc = parse_org_document(
+            "#+TODO: TODO WAITING | DONE\n* Website\n** DONE Pick theme\n** TODO Write copy\n* TODO Tax return\n** WAITING Forms from bank\n* DONE Old project\n** TODO Leftover\n* Reading list\n** Some book\n",
+            Some("projects.org"),
+        )
+        .unwrap();
+        let projects = collect_projects(&[&doc]);
+
+        let summary: Vec<(&str, ProjectStatus, u32)> = projects
+            .iter()
+            .map(|project| (project.title.as_str(), project.status, project.next_actions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Website", ProjectStatus::Active, 1),
+                ("Tax return", ProjectStatus::Stuck, 0),
+                ("Old project", ProjectStatus::Done, 1),
+            ]
+        );
+        assert_eq!(projects[0].done_tasks, 1);
+        assert_eq!(projects[0].open_tasks, 1);
+    }
+}
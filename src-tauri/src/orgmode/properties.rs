@@ -0,0 +1,633 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::{flatten_headlines, headline_level};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+// Minutes per org duration unit, as in `org-duration-units`
+const DURATION_UNITS: [(&str, f64); 6] = [
+    ("min", 1.0),
+    ("h", 60.0),
+    ("d", 1440.0),
+    ("w", 10080.0),
+    ("m", 43200.0),
+    ("y", 525960.0),
+];
+
+/// A distinct property value and the number of headlines that set it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PropertyValue {
+    pub value: String,
+    pub count: u32,
+}
+
+/// Restrict a listing to headlines whose (inherited) property has one of the values
+/// An empty value list only requires the property to be set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct PropertyFilter {
+    pub property: String,
+    pub values: Vec<String>,
+}
+
+impl PropertyFilter {
+    /// Check a headline against the filter, with org-style property inheritance
+    pub fn matches(&self, document: &OrgDocument, headline: &OrgHeadline) -> bool {
+        let Some(value) = document
+            .get_inherited_property(&headline.id, &self.property)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        else {
+            return false;
+        };
+
+        self.values.is_empty() || self.values.iter().any(|wanted| wanted.trim() == value)
+    }
+}
+
+/// Check a headline against every filter
+pub fn matches_filters(
+    document: &OrgDocument,
+    headline: &OrgHeadline,
+    filters: &[PropertyFilter],
+) -> bool {
+    filters
+        .iter()
+        .all(|filter| filter.matches(document, headline))
+}
+
+/// Declared type of a custom property, controlling parsing, sorting and totals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PropertyType {
+    #[default]
+    String,
+    Number,
+    Duration, // Org durations such as `1:30`, `2h`, `1d 4h`
+    Date,     // `2024-03-05` or an org timestamp
+}
+
+/// A property value parsed according to its declared type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub enum TypedValue {
+    Text(String),
+    Number(f64),
+    Duration(u32), // Minutes
+    Date(String),  // YYYY-MM-DD
+}
+
+impl PropertyType {
+    /// Parse a raw property value, returning an error message if it does not fit the type
+    pub fn parse(self, raw: &str) -> Result<TypedValue, String> {
+        let raw = raw.trim();
+        match self {
+            PropertyType::String => Ok(TypedValue::Text(raw.to_string())),
+            PropertyType::Number => raw
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(TypedValue::Number)
+                .ok_or_else(|| format!("'{}' is not a number", raw)),
+            PropertyType::Duration => parse_duration_minutes(raw)
+                .map(TypedValue::Duration)
+                .ok_or_else(|| format!("'{}' is not a duration", raw)),
+            PropertyType::Date => parse_date(raw)
+                .map(|date| TypedValue::Date(date.format("%Y-%m-%d").to_string()))
+                .ok_or_else(|| format!("'{}' is not a date", raw)),
+        }
+    }
+}
+
+impl TypedValue {
+    /// Order two values; values of different types order by type
+    pub fn compare(&self, other: &TypedValue) -> Ordering {
+        match (self, other) {
+            (TypedValue::Text(a), TypedValue::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (TypedValue::Number(a), TypedValue::Number(b)) => a.total_cmp(b),
+            (TypedValue::Duration(a), TypedValue::Duration(b)) => a.cmp(b),
+            (TypedValue::Date(a), TypedValue::Date(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+
+    /// Format the value the way org writes it
+    pub fn display(&self) -> String {
+        match self {
+            TypedValue::Text(text) | TypedValue::Date(text) => text.clone(),
+            TypedValue::Number(number) => number.to_string(),
+            TypedValue::Duration(minutes) => format_duration(*minutes),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            TypedValue::Text(_) => 0,
+            TypedValue::Number(_) => 1,
+            TypedValue::Duration(_) => 2,
+            TypedValue::Date(_) => 3,
+        }
+    }
+}
+
+/// Parse an org duration (`1:30`, `90`, `1.5h`, `1d 2h 30min`) into minutes
+/// A bare number counts as minutes, like org effort values. Durations past `u32::MAX`
+/// minutes don't parse.
+pub fn parse_duration_minutes(raw: &str) -> Option<u32> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    if let Some((hours, rest)) = raw.split_once(':') {
+        let hours: u32 = hours.trim().parse().ok()?;
+        let minutes: u32 = rest.split(':').next()?.trim().parse().ok()?;
+        if minutes >= 60 {
+            return None;
+        }
+        return hours.checked_mul(60)?.checked_add(minutes);
+    }
+
+    if let Ok(minutes) = raw.parse::<f64>() {
+        return whole_minutes(minutes);
+    }
+
+    let mut total = 0.0;
+    let mut rest = raw;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let amount: f64 = rest[..number_len].parse().ok()?;
+        rest = rest[number_len..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (_, minutes) = DURATION_UNITS
+            .iter()
+            .find(|(unit, _)| *unit == &rest[..unit_len])?;
+        total += amount * minutes;
+        rest = rest[unit_len..].trim_start();
+    }
+
+    whole_minutes(total)
+}
+
+// Round to whole minutes, if they fit a u32
+fn whole_minutes(minutes: f64) -> Option<u32> {
+    let minutes = minutes.round();
+    (0.0..=u32::MAX as f64)
+        .contains(&minutes)
+        .then_some(minutes as u32)
+}
+
+/// Format minutes as `H:MM`
+pub fn format_duration(minutes: u32) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+// Accept `YYYY-MM-DD` or an org timestamp like `<2024-03-05 Tue 10:00>`
+fn parse_date(raw: &str) -> Option<NaiveDate> {
+    let inner = raw.trim_start_matches(['<', '[']);
+    NaiveDate::parse_from_str(inner.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Sort items by a typed property value; items without a valid value go last
+pub fn sort_by_property<T>(
+    items: Vec<T>,
+    property_type: PropertyType,
+    descending: bool,
+    value_of: impl Fn(&T) -> Option<String>,
+) -> Vec<T> {
+    let mut keyed: Vec<(Option<TypedValue>, T)> = items
+        .into_iter()
+        .map(|item| {
+            let value = value_of(&item).and_then(|raw| property_type.parse(&raw).ok());
+            (value, item)
+        })
+        .collect();
+
+    keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) if descending => b.compare(a),
+        (Some(a), Some(b)) => a.compare(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+
+    keyed.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Sum numeric or duration values, skipping values that do not parse
+/// Returns None for types that cannot be summed and durations whose total overflows
+pub fn sum_values<'a>(
+    property_type: PropertyType,
+    values: impl IntoIterator<Item = &'a str>,
+) -> Option<TypedValue> {
+    let parsed = values
+        .into_iter()
+        .filter_map(|raw| property_type.parse(raw).ok());
+
+    match property_type {
+        PropertyType::Number => Some(TypedValue::Number(
+            parsed
+                .filter_map(|value| match value {
+                    TypedValue::Number(number) => Some(number),
+                    _ => None,
+                })
+                .sum(),
+        )),
+        PropertyType::Duration => parsed
+            .filter_map(|value| match value {
+                TypedValue::Duration(minutes) => Some(minutes),
+                _ => None,
+            })
+            .try_fold(0u32, u32::checked_add)
+            .map(TypedValue::Duration),
+        PropertyType::String | PropertyType::Date => None,
+    }
+}
+
+/// Distinct values of headline properties across documents
+/// Property names are stored uppercased, since org matches them case-insensitively
+#[derive(Debug, Default)]
+pub struct PropertyIndex {
+    // document ID -> property -> value -> number of headlines
+    documents: HashMap<String, HashMap<String, HashMap<String, u32>>>,
+}
+
+impl PropertyIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the property values of a document
+    pub fn index_document(&mut self, document: &OrgDocument) {
+        let mut properties: HashMap<String, HashMap<String, u32>> = HashMap::new();
+
+        for headline in flatten_headlines(&document.headlines) {
+            for (key, value) in &headline.title.properties {
+                let value = value.trim();
+                if value.is_empty() {
+                    continue;
+                }
+                *properties
+                    .entry(key.to_uppercase())
+                    .or_default()
+                    .entry(value.to_string())
+                    .or_default() += 1;
+            }
+        }
+
+        self.documents.insert(document.id.clone(), properties);
+    }
+
+    /// Remove a document's property values
+    pub fn remove_document(&mut self, document_id: &str) {
+        self.documents.remove(document_id);
+    }
+
+    /// All distinct values of a property, sorted by value
+    pub fn values(&self, property: &str) -> Vec<PropertyValue> {
+        let property = property.to_uppercase();
+        let mut values: BTreeMap<&str, u32> = BTreeMap::new();
+
+        for properties in self.documents.values() {
+            if let Some(counts) = properties.get(&property) {
+                for (value, count) in counts {
+                    *values.entry(value).or_default() += count;
+                }
+            }
+        }
+
+        values
+            .into_iter()
+            .map(|(value, count)| PropertyValue {
+                value: value.to_string(),
+                count,
+            })
+            .collect()
+    }
+}
+
+/// Whether a string can be used as a property key
+pub fn is_valid_property_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(|c: char| c.is_whitespace() || c == ':')
+}
+
+/// Key of a property drawer line (`:KEY: value`)
+pub fn property_key(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix(':')?;
+    let end = rest.find(':')?;
+    Some(&rest[..end])
+}
+
+/// Whether a line is a SCHEDULED, DEADLINE or CLOSED planning line
+pub fn is_planning_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+        .iter()
+        .any(|keyword| trimmed.starts_with(keyword))
+}
+
+// Index where the headline's property drawer is or would go, after any planning line
+fn drawer_line(lines: &[String], headline: usize) -> usize {
+    if lines
+        .get(headline + 1)
+        .is_some_and(|line| is_planning_line(line))
+    {
+        headline + 2
+    } else {
+        headline + 1
+    }
+}
+
+// Index of the `:END:` line closing the drawer that opens at `drawer`
+fn drawer_end(lines: &[String], drawer: usize) -> Option<usize> {
+    Some(
+        lines[drawer + 1..]
+            .iter()
+            .take_while(|line| headline_level(line).is_none())
+            .position(|line| line.trim().eq_ignore_ascii_case(":END:"))?
+            + drawer
+            + 1,
+    )
+}
+
+fn leading_whitespace(line: &str) -> String {
+    line.chars().take_while(|c| c.is_whitespace()).collect()
+}
+
+/// Set properties in the drawer of the headline at index `headline` of `lines`,
+/// replacing existing keys (case-insensitively) and creating the drawer after the
+/// planning line if the headline has none, indented like the planning line
+/// Returns None if the drawer has no `:END:`
+pub fn set_drawer_properties(
+    lines: &mut Vec<String>,
+    headline: usize,
+    properties: &[(&str, String)],
+) -> Option<()> {
+    let drawer = drawer_line(lines, headline);
+
+    let has_drawer = lines
+        .get(drawer)
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case(":PROPERTIES:"));
+    if !has_drawer {
+        let indent = if drawer > headline + 1 {
+            leading_whitespace(&lines[headline + 1])
+        } else {
+            String::new()
+        };
+        let mut new_lines = vec![format!("{}:PROPERTIES:", indent)];
+        new_lines.extend(
+            properties
+                .iter()
+                .map(|(key, value)| format!("{}:{}: {}", indent, key, value)),
+        );
+        new_lines.push(format!("{}:END:", indent));
+        lines.splice(drawer..drawer, new_lines);
+        return Some(());
+    }
+
+    let indent = leading_whitespace(&lines[drawer]);
+    let mut end = drawer_end(lines, drawer)?;
+    for (key, value) in properties {
+        let existing = (drawer + 1..end)
+            .find(|&i| property_key(&lines[i]).is_some_and(|k| k.eq_ignore_ascii_case(key)));
+        match existing {
+            Some(i) => {
+                let indent = leading_whitespace(&lines[i]);
+                lines[i] = format!("{}:{}: {}", indent, key, value);
+            }
+            None => {
+                lines.insert(end, format!("{}:{}: {}", indent, key, value));
+                end += 1;
+            }
+        }
+    }
+    Some(())
+}
+
+/// Remove a property (matched case-insensitively) from the drawer of the headline at
+/// index `headline` of `lines`, dropping the drawer if nothing is left in it
+/// Returns None if the headline has no such property or the drawer has no `:END:`
+pub fn remove_drawer_property(lines: &mut Vec<String>, headline: usize, key: &str) -> Option<()> {
+    let drawer = drawer_line(lines, headline);
+    if !lines
+        .get(drawer)
+        .is_some_and(|line| line.trim().eq_ignore_ascii_case(":PROPERTIES:"))
+    {
+        return None;
+    }
+    let end = drawer_end(lines, drawer)?;
+    let index = (drawer + 1..end)
+        .find(|&i| property_key(&lines[i]).is_some_and(|k| k.eq_ignore_ascii_case(key)))?;
+    lines.remove(index);
+    if end - 1 == drawer + 1 {
+        lines.drain(drawer..=drawer + 1);
+    }
+    Some(())
+}
+
+// Apply a drawer edit to the headline starting at `headline_line` (1-based)
+fn edit_headline_drawer(
+    content: &str,
+    headline_line: usize,
+    edit: impl FnOnce(&mut Vec<String>, usize) -> Option<()>,
+) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let headline = headline_line.checked_sub(1)?;
+    headline_level(lines.get(headline)?)?;
+    edit(&mut lines, headline)?;
+
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    Some(output)
+}
+
+/// Set properties on the headline starting at `headline_line` (1-based)
+/// Returns None if `headline_line` is not a headline line
+pub fn set_headline_properties(
+    content: &str,
+    headline_line: usize,
+    properties: &[(&str, String)],
+) -> Option<String> {
+    edit_headline_drawer(content, headline_line, |lines, headline| {
+        set_drawer_properties(lines, headline, properties)
+    })
+}
+
+/// Remove a property from the headline starting at `headline_line` (1-based)
+/// Returns None if `headline_line` is not a headline line or has no such property
+pub fn remove_headline_property(content: &str, headline_line: usize, key: &str) -> Option<String> {
+    edit_headline_drawer(content, headline_line, |lines, headline| {
+        remove_drawer_property(lines, headline, key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const SAMPLE: &str = r#"* Work
+:PROPERTIES:
+:agenda-group: Office
+:END:
+** TODO Write report
+:PROPERTIES:
+:Effort: 1:00
+:END:
+** TODO Review budget
+:PROPERTIES:
+:EFFORT: 0:30
+:END:
+* TODO Water plants
+:PROPERTIES:
+:Effort: 0:30
+:END:
+"#;
+
+    #[test]
+    fn test_parse_typed_values() {
+        assert_eq!(parse_duration_minutes("1:30"), Some(90));
+        assert_eq!(parse_duration_minutes("45"), Some(45));
+        assert_eq!(parse_duration_minutes("1.5h"), Some(90));
+        assert_eq!(parse_duration_minutes("1d 2h 30min"), Some(1590));
+        assert_eq!(parse_duration_minutes("soon"), None);
+        assert_eq!(parse_duration_minutes("99999999:00"), None);
+        assert_eq!(parse_duration_minutes("1e12"), None);
+        assert_eq!(parse_duration_minutes("9999999d"), None);
+        assert_eq!(format_duration(1590), "26:30");
+
+        assert_eq!(
+            PropertyType::Date.parse("<2024-03-05 Tue 10:00>"),
+            Ok(TypedValue::Date("2024-03-05".to_string()))
+        );
+        assert!(PropertyType::Number.parse("12x").is_err());
+        assert_eq!(
+            PropertyType::Number.parse(" 2.5 "),
+            Ok(TypedValue::Number(2.5))
+        );
+    }
+
+    #[test]
+    fn test_sort_and_sum_typed_values() {
+        let efforts = vec!["0:30", "2h", "bad", "10"];
+        let sorted = sort_by_property(efforts.clone(), PropertyType::Duration, false, |raw| {
+            Some(raw.to_string())
+        });
+        assert_eq!(sorted, vec!["10", "0:30", "2h", "bad"]);
+
+        // Plain strings sort case-insensitively
+        let sorted = sort_by_property(efforts.clone(), PropertyType::String, true, |raw| {
+            Some(raw.to_string())
+        });
+        assert_eq!(sorted, vec!["bad", "2h", "10", "0:30"]);
+
+        let total = sum_values(PropertyType::Duration, efforts.iter().copied()).unwrap();
+        assert_eq!(total.display(), "2:40");
+        assert_eq!(
+            sum_values(PropertyType::Duration, ["4000000000", "4000000000"]),
+            None
+        );
+        assert_eq!(
+            sum_values(PropertyType::Date, efforts.iter().copied()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_property_values() {
+        let doc = parse_org_document(SAMPLE, Some("props.org")).unwrap();
+        let mut index = PropertyIndex::new();
+        index.index_document(&doc);
+
+        let values = index.values("effort");
+        let summary: Vec<(&str, u32)> =
+            values.iter().map(|v| (v.value.as_str(), v.count)).collect();
+        assert_eq!(summary, vec![("0:30", 2), ("1:00", 1)]);
+
+        index.remove_document("props.org");
+        assert!(index.values("Effort").is_empty());
+    }
+
+    #[test]
+    fn test_property_filter_inherits() {
+        let doc = parse_org_document(SAMPLE, Some("props.org")).unwrap();
+        let office = PropertyFilter {
+            property: "AGENDA-GROUP".to_string(),
+            values: vec!["Office".to_string()],
+        };
+        let has_effort = PropertyFilter {
+            property: "Effort".to_string(),
+            values: Vec::new(),
+        };
+
+        let report = doc.find_headline("1.1").unwrap();
+        let plants = doc.find_headline("2").unwrap();
+        assert!(office.matches(&doc, report));
+        assert!(!office.matches(&doc, plants));
+        assert!(matches_filters(
+            &doc,
+            plants,
+            std::slice::from_ref(&has_effort)
+        ));
+        assert!(!matches_filters(&doc, plants, &[has_effort, office]));
+    }
+
+    #[test]
+    fn test_set_headline_properties() {
+        let content = "* Project\nSCHEDULED: <2024-01-05 Fri>\n** Task\n  :PROPERTIES:\n  :EFFORT: 1:00\n  :ID: task\n  :END:\nBody\n";
+        let group = [("agenda-group", "Home".to_string())];
+
+        // A new drawer goes after the planning line
+        let updated = set_headline_properties(content, 1, &group).unwrap();
+        assert!(updated.starts_with(
+            "* Project\nSCHEDULED: <2024-01-05 Fri>\n:PROPERTIES:\n:agenda-group: Home\n:END:\n** Task\n"
+        ));
+
+        // Existing keys are replaced case-insensitively, new keys keep the drawer indent
+        let effort = [
+            ("Effort", "2:00".to_string()),
+            ("agenda-group", "Home".to_string()),
+        ];
+        let updated = set_headline_properties(content, 3, &effort).unwrap();
+        assert!(updated.ends_with(
+            "  :PROPERTIES:\n  :Effort: 2:00\n  :ID: task\n  :agenda-group: Home\n  :END:\nBody\n"
+        ));
+
+        assert!(set_headline_properties(content, 2, &group).is_none());
+        assert!(is_valid_property_key("agenda-group"));
+        assert!(!is_valid_property_key("two words") && !is_valid_property_key("a:b"));
+    }
+
+    #[test]
+    fn test_remove_headline_property() {
+        let content = "* Task\n  SCHEDULED: <2024-01-05 Fri>\n  :PROPERTIES:\n  :Effort: 1:00\n  :ID: task\n  :END:\nBody\n";
+
+        let updated = remove_headline_property(content, 1, "effort").unwrap();
+        assert_eq!(
+            updated,
+            "* Task\n  SCHEDULED: <2024-01-05 Fri>\n  :PROPERTIES:\n  :ID: task\n  :END:\nBody\n"
+        );
+        // Removing the last property drops the drawer
+        let empty = remove_headline_property(&updated, 1, "ID").unwrap();
+        assert_eq!(empty, "* Task\n  SCHEDULED: <2024-01-05 Fri>\nBody\n");
+        assert!(remove_headline_property(&empty, 1, "ID").is_none());
+
+        // A new drawer takes the planning line's indentation
+        let added = set_headline_properties(&empty, 1, &[("ID", "task".to_string())]).unwrap();
+        assert_eq!(
+            added,
+            "* Task\n  SCHEDULED: <2024-01-05 Fri>\n  :PROPERTIES:\n  :ID: task\n  :END:\nBody\n"
+        );
+        // A drawer without :END: is left alone
+        assert!(
+            remove_headline_property("* Task\n:PROPERTIES:\n:ID: x\n* Next\n", 1, "ID").is_none()
+        );
+    }
+}
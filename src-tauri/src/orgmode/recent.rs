@@ -0,0 +1,193 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::favorites::locate_headline;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// File name of the recent items list, inside the app data directory
+pub const RECENT_FILE_NAME: &str = "recent.json";
+
+/// Number of recent items kept; older ones are dropped
+pub const MAX_RECENT_ITEMS: usize = 100;
+
+/// How a recent item was last accessed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum RecentAccess {
+    Opened,
+    Edited,
+}
+
+/// A document or headline the user opened or edited, most recent first
+/// Positional headline IDs shift when headlines are added above, so a headline is found
+/// again by its `:ID:` property or outline path, as favorites are
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct RecentItem {
+    pub document_id: String,
+    pub headline_id: Option<String>, // None for the document itself
+    pub title: String,
+    pub access: RecentAccess,
+    pub access_count: u32,   // Times accessed, for quick-switcher ranking
+    pub accessed_at: String, // RFC 3339
+    #[serde(default)]
+    pub org_id: Option<String>, // Value of the headline's :ID: property
+    #[serde(default)]
+    pub outline_path: Vec<String>, // Titles from the top-level headline down
+}
+
+impl RecentItem {
+    // Whether two items are the same document or the same headline, however it moved
+    fn is_same(&self, other: &RecentItem) -> bool {
+        if self.document_id != other.document_id {
+            return false;
+        }
+        match (&self.org_id, &other.org_id) {
+            (Some(id), Some(other_id)) => id == other_id,
+            _ if self.outline_path.is_empty() || other.outline_path.is_empty() => {
+                self.headline_id == other.headline_id
+            }
+            _ => self.outline_path == other.outline_path,
+        }
+    }
+
+    /// Point a headline item at where its headline is now, returning false if it is gone
+    /// Items stored before outline paths were kept only match if the headline at their
+    /// position still has the same title. Document items always match.
+    pub fn relocate(&mut self, document: &OrgDocument) -> bool {
+        let Some(headline_id) = &self.headline_id else {
+            return true;
+        };
+        let found = if self.org_id.is_none() && self.outline_path.is_empty() {
+            document
+                .find_headline(headline_id)
+                .filter(|headline| headline.title.raw == self.title)
+        } else {
+            locate_headline(document, self.org_id.as_deref(), &self.outline_path)
+        };
+        let Some(headline) = found else {
+            return false;
+        };
+        self.headline_id = Some(headline.id.clone());
+        self.title = headline.title.raw.clone();
+        true
+    }
+}
+
+/// Move an item to the front of the list, counting the access
+/// The item's own access count is replaced by the running count.
+pub fn record_access(items: &mut Vec<RecentItem>, mut item: RecentItem) {
+    item.access_count = match items.iter().position(|existing| existing.is_same(&item)) {
+        Some(index) => items.remove(index).access_count + 1,
+        None => 1,
+    };
+    items.insert(0, item);
+    items.truncate(MAX_RECENT_ITEMS);
+}
+
+/// Load recent items, treating a missing file as empty
+pub fn load_recent(path: &Path) -> Result<Vec<RecentItem>, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to read recent items {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!(
+            "Failed to read recent items {}: {}",
+            path.display(),
+            e
+        )),
+    }
+}
+
+/// Save recent items, creating the parent directory if needed
+pub fn save_recent(path: &Path, items: &[RecentItem]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(items)
+        .map_err(|e| format!("Failed to serialize recent items: {}", e))?;
+    fs::write(path, json)
+        .map_err(|e| format!("Failed to write recent items {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use tempfile::tempdir;
+
+    fn item(
+        document_id: &str,
+        outline_path: &[&str],
+        access: RecentAccess,
+        at: &str,
+    ) -> RecentItem {
+        RecentItem {
+            document_id: document_id.to_string(),
+            headline_id: (!outline_path.is_empty()).then(|| "1".to_string()),
+            title: outline_path.last().unwrap_or(&"").to_string(),
+            access,
+            access_count: 0,
+            accessed_at: at.to_string(),
+            org_id: None,
+            outline_path: outline_path.iter().map(|title| title.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_record_access_moves_item_to_front() {
+        let mut items = Vec::new();
+        record_access(&mut items, item("/a.org", &[], RecentAccess::Opened, "t1"));
+        record_access(
+            &mut items,
+            item("/b.org", &["Task"], RecentAccess::Opened, "t2"),
+        );
+        record_access(&mut items, item("/a.org", &[], RecentAccess::Edited, "t3"));
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].document_id, "/a.org");
+        assert_eq!(items[0].access, RecentAccess::Edited);
+        assert_eq!(items[0].access_count, 2);
+        assert_eq!(items[0].accessed_at, "t3");
+        assert_eq!(items[1].headline_id.as_deref(), Some("1"));
+
+        // The same headline at another position is still the same item
+        let mut moved = item("/b.org", &["Task"], RecentAccess::Edited, "t4");
+        moved.headline_id = Some("2".to_string());
+        record_access(&mut items, moved);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].access_count, 2);
+
+        for i in 0..MAX_RECENT_ITEMS {
+            let id = format!("/{}.org", i);
+            record_access(&mut items, item(&id, &[], RecentAccess::Opened, "t"));
+        }
+        assert_eq!(items.len(), MAX_RECENT_ITEMS);
+    }
+
+    #[test]
+    fn test_relocate() {
+        let mut task = item("/a.org", &["Projects", "Task"], RecentAccess::Edited, "t1");
+        let edited = parse_org_document("* Inbox\n* Projects\n** Task\n", Some("/a.org")).unwrap();
+        assert!(task.relocate(&edited));
+        assert_eq!(task.headline_id.as_deref(), Some("2.1"));
+        let removed = parse_org_document("* Projects\n", Some("/a.org")).unwrap();
+        assert!(!task.relocate(&removed));
+        assert!(item("/a.org", &[], RecentAccess::Opened, "t1").relocate(&removed));
+    }
+
+    #[test]
+    fn test_recent_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("data").join(RECENT_FILE_NAME);
+        assert!(load_recent(&path).unwrap().is_empty());
+
+        let mut items = Vec::new();
+        record_access(
+            &mut items,
+            item("/a.org", &["Idea"], RecentAccess::Opened, "t1"),
+        );
+        save_recent(&path, &items).unwrap();
+        assert_eq!(load_recent(&path).unwrap(), items);
+    }
+}
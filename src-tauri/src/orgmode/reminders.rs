@@ -0,0 +1,246 @@
+use crate::orgmode::datetime::OrgDatetime;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::favorites::{locate_headline, Favorite};
+use crate::orgmode::headline::OrgHeadline;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// File name of the reminder store, inside the app data directory
+pub const REMINDERS_FILE_NAME: &str = "reminders.json";
+
+/// Event emitted with a `Reminder` when it becomes due
+pub const REMINDER_DUE_EVENT: &str = "reminder-due";
+
+/// A nudge attached to a headline, kept outside the org file
+/// Positional headline IDs shift when headlines are added above, so the headline is found
+/// again by its `:ID:` property or outline path, as favorites are
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Reminder {
+    pub id: String,
+    pub document_id: String,
+    pub headline_id: Option<String>, // Where the headline was last found, None once it is gone
+    pub title: String,               // Headline title when the reminder was last resolved
+    pub remind_at: String,           // RFC 3339
+    pub message: String,
+    pub fired: bool,
+    #[serde(default)]
+    pub org_id: Option<String>, // Value of the headline's :ID: property
+    #[serde(default)]
+    pub outline_path: Vec<String>, // Titles from the top-level headline down
+}
+
+impl Reminder {
+    /// Build a reminder for a headline, or None if the headline is not in the document
+    pub fn for_headline(
+        id: String,
+        document: &OrgDocument,
+        headline_id: &str,
+        remind_at: String,
+        message: String,
+    ) -> Option<Self> {
+        let target = Favorite::for_headline(document, headline_id)?;
+        Some(Self {
+            id,
+            document_id: document.id.clone(),
+            headline_id: Some(headline_id.to_string()),
+            org_id: target.org_id,
+            title: target.outline_path.last()?.clone(),
+            outline_path: target.outline_path,
+            remind_at,
+            message,
+            fired: false,
+        })
+    }
+
+    /// Find the reminder's headline again in the current version of its document
+    /// Reminders stored before outline paths were kept only match if the headline at
+    /// their position still has the same title.
+    pub fn find<'a>(&self, document: &'a OrgDocument) -> Option<&'a OrgHeadline> {
+        if self.org_id.is_none() && self.outline_path.is_empty() {
+            return document
+                .find_headline(self.headline_id.as_deref()?)
+                .filter(|headline| headline.title.raw == self.title);
+        }
+        locate_headline(document, self.org_id.as_deref(), &self.outline_path)
+    }
+
+    /// Point the reminder at where its headline is now, or at none if it is gone
+    pub fn relocate(&mut self, document: &OrgDocument) {
+        let Some(headline) = self.find(document) else {
+            self.headline_id = None;
+            return;
+        };
+        if let Some(path) = document.headline_path(&headline.id) {
+            self.outline_path = path.iter().map(|h| h.title.raw.clone()).collect();
+        }
+        self.headline_id = Some(headline.id.clone());
+        self.title = headline.title.raw.clone();
+    }
+
+    /// When the reminder is due, or None if the stored time is unreadable
+    pub fn due_time(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.remind_at).ok()
+    }
+}
+
+/// Check a reminder time, returning it normalized to RFC 3339
+/// Times without an offset (`YYYY-MM-DDThh:mm:ss`) are in the assumed time zone.
+pub fn parse_remind_at(remind_at: &str) -> Result<String, String> {
+    if let Some(local) = OrgDatetime::from_datetime_string(remind_at) {
+        return Ok(local.to_utc().to_rfc3339());
+    }
+    DateTime::parse_from_rfc3339(remind_at)
+        .map(|time| time.to_rfc3339())
+        .map_err(|e| format!("Invalid reminder time '{}': {}", remind_at, e))
+}
+
+/// Mark reminders due at `now` as fired, returning them in due order
+pub fn take_due(reminders: &mut [Reminder], now: DateTime<FixedOffset>) -> Vec<Reminder> {
+    let mut due: Vec<Reminder> = reminders
+        .iter_mut()
+        .filter(|reminder| !reminder.fired && reminder.due_time().is_some_and(|time| time <= now))
+        .map(|reminder| {
+            reminder.fired = true;
+            reminder.clone()
+        })
+        .collect();
+    due.sort_by_key(|reminder| reminder.due_time());
+    due
+}
+
+/// Reminders that have not fired yet, soonest first
+pub fn upcoming(reminders: &[Reminder]) -> Vec<Reminder> {
+    let mut upcoming: Vec<Reminder> = reminders
+        .iter()
+        .filter(|reminder| !reminder.fired)
+        .cloned()
+        .collect();
+    upcoming.sort_by_key(|reminder| reminder.due_time());
+    upcoming
+}
+
+/// Load reminders, treating a missing store as empty
+pub fn load_reminders(path: &Path) -> Result<Vec<Reminder>, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to read reminders {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!(
+            "Failed to read reminders {}: {}",
+            path.display(),
+            e
+        )),
+    }
+}
+
+/// Save reminders, creating the parent directory if needed
+pub fn save_reminders(path: &Path, reminders: &[Reminder]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(reminders)
+        .map_err(|e| format!("Failed to serialize reminders: {}", e))?;
+    fs::write(path, json)
+        .map_err(|e| format!("Failed to write reminders {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use tempfile::tempdir;
+
+    fn reminder(id: &str, remind_at: &str) -> Reminder {
+        Reminder {
+            id: id.to_string(),
+            document_id: "/notes/a.org".to_string(),
+            headline_id: Some("1".to_string()),
+            org_id: None,
+            outline_path: vec!["Call back".to_string()],
+            title: "Call back".to_string(),
+            remind_at: remind_at.to_string(),
+            message: "Follow up".to_string(),
+            fired: false,
+        }
+    }
+
+    #[test]
+    fn test_take_due_fires_once() {
+        let mut reminders = vec![
+            reminder("late", "2024-01-02T09:00:00+00:00"),
+            reminder("second", "2024-01-01T10:00:00+00:00"),
+            reminder("first", "2024-01-01T09:00:00+00:00"),
+        ];
+        let now = DateTime::parse_from_rfc3339("2024-01-01T12:00:00+00:00").unwrap();
+
+        let due: Vec<String> = take_due(&mut reminders, now)
+            .into_iter()
+            .map(|reminder| reminder.id)
+            .collect();
+        assert_eq!(due, vec!["first", "second"]);
+        assert!(take_due(&mut reminders, now).is_empty());
+
+        let upcoming: Vec<String> = upcoming(&reminders)
+            .into_iter()
+            .map(|reminder| reminder.id)
+            .collect();
+        assert_eq!(upcoming, vec!["late"]);
+        assert!(parse_remind_at("tomorrow").is_err());
+        // Times without an offset are taken in the assumed time zone
+        let local = parse_remind_at("2024-01-01T09:00:00").unwrap();
+        assert!(DateTime::parse_from_rfc3339(&local).is_ok());
+    }
+
+    #[test]
+    fn test_reminders_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("data").join(REMINDERS_FILE_NAME);
+        assert!(load_reminders(&path).unwrap().is_empty());
+
+        let reminders = vec![reminder("r1", "2024-01-01T09:00:00+00:00")];
+        save_reminders(&path, &reminders).unwrap();
+        assert_eq!(load_reminders(&path).unwrap(), reminders);
+    }
+
+    #[test]
+    fn test_reminder_follows_headline() {
+        let document = parse_org_document(
+            "* Projects\n** TODO Call back\n* Areas\n",
+            Some("/notes/a.org"),
+        )
+        .unwrap();
+        let mut call = Reminder::for_headline(
+            "r1".to_string(),
+            &document,
+            "1.1",
+            "2024-01-01T09:00:00+00:00".to_string(),
+            String::new(),
+        )
+        .unwrap();
+
+        // A headline added above shifts the positional ID
+        let edited = parse_org_document(
+            "* Inbox\n* Projects\n** TODO Call back\n* Areas\n",
+            Some("/notes/a.org"),
+        )
+        .unwrap();
+        call.relocate(&edited);
+        assert_eq!(call.headline_id.as_deref(), Some("2.1"));
+
+        let removed = parse_org_document("* Projects\n* Areas\n", Some("/notes/a.org")).unwrap();
+        call.relocate(&removed);
+        assert_eq!(call.headline_id, None);
+
+        // Without an outline path, the headline at the old position must keep its title
+        let mut legacy = reminder("r2", "2024-01-01T09:00:00+00:00");
+        legacy.outline_path.clear();
+        legacy.relocate(&parse_org_document("* Call back\n", Some("/notes/a.org")).unwrap());
+        assert_eq!(legacy.headline_id.as_deref(), Some("1"));
+        legacy.relocate(&parse_org_document("* Other\n", Some("/notes/a.org")).unwrap());
+        assert_eq!(legacy.headline_id, None);
+    }
+}
@@ -0,0 +1,160 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// What to find and what to replace it with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ReplaceQuery {
+    pub pattern: String,
+    pub replacement: String, // In regex mode, `$1` and `${name}` refer to capture groups
+    pub regex: bool,         // Otherwise the pattern is matched literally
+    pub case_sensitive: bool,
+}
+
+/// One match and what it would be replaced with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ReplaceMatch {
+    pub line: u32,         // 1-based line where the match starts
+    pub line_text: String, // The whole line, for context
+    pub matched: String,
+    pub replacement: String,
+}
+
+/// Matches in one file, before replacing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct FileReplacePreview {
+    pub document_id: String,
+    pub file_path: String,
+    pub matches: Vec<ReplaceMatch>,
+}
+
+/// Outcome of a find-and-replace across files
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ReplaceSummary {
+    pub files_changed: u32,
+    pub replacements: u32,
+}
+
+impl ReplaceQuery {
+    /// Compile the pattern, escaping it unless it is a regex
+    pub fn compile(&self) -> Result<Regex, String> {
+        if self.pattern.is_empty() {
+            return Err("Search pattern is empty".to_string());
+        }
+        let pattern = if self.regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .multi_line(true)
+            .build()
+            .map_err(|e| format!("Invalid pattern: {}", e))?;
+
+        // A pattern that can match nothing would replace at every position
+        let hir = regex_syntax::ParserBuilder::new()
+            .multi_line(true)
+            .build()
+            .parse(&pattern)
+            .map_err(|e| format!("Invalid pattern: {}", e))?;
+        if hir.properties().minimum_len() == Some(0) {
+            return Err("Search pattern matches empty text".to_string());
+        }
+        Ok(regex)
+    }
+
+    fn expand(&self, captures: &regex::Captures) -> String {
+        if !self.regex {
+            return self.replacement.clone();
+        }
+        let mut expanded = String::new();
+        captures.expand(&self.replacement, &mut expanded);
+        expanded
+    }
+
+    /// List every match in `content` with its replacement
+    pub fn preview(&self, regex: &Regex, content: &str) -> Vec<ReplaceMatch> {
+        regex
+            .captures_iter(content)
+            .filter_map(|captures| {
+                let found = captures.get(0)?;
+                let line_start = content[..found.start()].rfind('\n').map_or(0, |i| i + 1);
+                let line_end = content[found.start()..]
+                    .find('\n')
+                    .map_or(content.len(), |i| found.start() + i);
+                Some(ReplaceMatch {
+                    line: content[..found.start()].matches('\n').count() as u32 + 1,
+                    line_text: content[line_start..line_end].to_string(),
+                    matched: found.as_str().to_string(),
+                    replacement: self.expand(&captures),
+                })
+            })
+            .collect()
+    }
+
+    /// Replace every match in `content`
+    /// Returns the new content and the number of replacements, or None if nothing matched
+    pub fn replace_all(&self, regex: &Regex, content: &str) -> Option<(String, u32)> {
+        let mut count = 0;
+        let replaced = regex.replace_all(content, |captures: &regex::Captures| {
+            count += 1;
+            self.expand(captures)
+        });
+        (count > 0).then(|| (replaced.into_owned(), count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(pattern: &str, replacement: &str, regex: bool) -> ReplaceQuery {
+        ReplaceQuery {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            regex,
+            case_sensitive: false,
+        }
+    }
+
+    #[test]
+    fn test_literal_replace() {
+        let content = "* Call Bob ($5)\nBob owes $5.\n";
+        let literal = query("$5", "$10", false);
+        let regex = literal.compile().unwrap();
+
+        let matches = literal.preview(&regex, content);
+        let lines: Vec<u32> = matches.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![1, 2]);
+        assert_eq!(matches[1].line_text, "Bob owes $5.");
+
+        // Literal replacements don't expand `$` references
+        let (updated, count) = literal.replace_all(&regex, content).unwrap();
+        assert_eq!(updated, "* Call Bob ($10)\nBob owes $10.\n");
+        assert_eq!(count, 2);
+        assert!(literal.replace_all(&regex, "nothing here").is_none());
+    }
+
+    #[test]
+    fn test_regex_replace() {
+        let content = "SCHEDULED: <2024-01-05 Fri>\nsee ISSUE-12 and issue-7\n";
+        let issues = query(r"issue-(\d+)", "[[issue:$1]]", true);
+        let regex = issues.compile().unwrap();
+
+        let matches = issues.preview(&regex, content);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].matched, "ISSUE-12");
+        assert_eq!(matches[0].replacement, "[[issue:12]]");
+
+        let (updated, count) = issues.replace_all(&regex, content).unwrap();
+        assert_eq!(count, 2);
+        assert!(updated.ends_with("see [[issue:12]] and [[issue:7]]\n"));
+
+        assert!(query("(unclosed", "", true).compile().is_err());
+        assert!(query("", "x", false).compile().is_err());
+        for empty in ["a*", "^", "\\b", "(x|)"] {
+            assert!(query(empty, "", true).compile().is_err(), "{}", empty);
+        }
+    }
+}
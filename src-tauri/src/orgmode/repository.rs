@@ -1,17 +1,162 @@
-use crate::orgmode::document::OrgDocument;
+use crate::jobs::CancellationToken;
+use crate::orgmode::diagnostics::{catch_parse_panic, ParseDiagnostic};
+use crate::orgmode::document::{is_archive_path, OrgDocument};
 use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::intern::StringInterner;
+use crate::orgmode::memory::{estimate_document_bytes, strip_body_content, MemoryStats};
 use crate::orgmode::parser::{
     parse_org_document, parse_org_document_with_keywords, parse_org_document_with_settings,
 };
+use crate::orgmode::perf::{ParseTimings, PerfStats};
+use crate::orgmode::properties::{matches_filters, PropertyFilter, PropertyIndex, PropertyValue};
+use crate::orgmode::search::{DateTerm, SearchHit, SearchIndex};
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::update::{ChangeFeed, ChangeKind, OrgUpdateInfo, UpdateTracker};
+use crate::orgmode::utils::generate_document_etag;
+use crate::orgmode::wordcount::{document_stats, DocumentStats};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// TODO keywords as (active, closed) lists
+type TodoKeywordLists = (Vec<String>, Vec<String>);
+
+// Number of changes kept for the change feed
+const MAX_UPDATE_HISTORY: usize = 1000;
 
 // Document repository
 pub struct OrgDocumentRepository {
     documents: HashMap<String, OrgDocument>,
     last_updated: HashMap<String, DateTime<Utc>>,
+    renamed_ids: HashMap<String, String>, // Old document ID -> current document ID
+    updates: UpdateTracker,               // Change feed for incremental sync
+    search_index: SearchIndex,            // Full-text index, kept in step with documents
+    property_index: PropertyIndex,        // Distinct property values, for filter dropdowns
+    parsed_keywords: HashMap<String, TodoKeywordLists>, // Keywords each document was parsed with
+    parse_timings: ParseTimings,          // Recent parse durations, for perf stats
+    strings: StringInterner,              // Shared tags, TODO keywords, and property keys
+    last_opened: HashMap<String, Instant>, // When each document was last opened in the UI
+    evicted: HashSet<String>,             // Documents whose body content is unloaded
+    diagnostics: HashMap<String, ParseDiagnostic>, // Files whose last parse failed, by path
+    text_stats: HashMap<String, DocumentStats>, // Word counts, kept when content is evicted
+}
+
+/// Whether a file was reused from the repository or parsed again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconcileOutcome {
+    Reused,
+    Reparsed,
+}
+
+/// Counts from reconciling the repository with the files on disk
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconcileSummary {
+    pub reused: u32,
+    pub reparsed: u32,
+    pub missing: u32,
+    pub failed: u32,
+}
+
+/// A file parsed without holding the repository lock, ready to be inserted
+pub struct ParsedFile {
+    path: PathBuf,
+    document: OrgDocument,
+    todo_keywords: Option<TodoKeywordLists>,
+    bytes: usize,
+    elapsed: Duration,
+}
+
+impl ParsedFile {
+    /// Parse file content, with custom TODO keywords if given
+    pub fn parse(
+        path: &Path,
+        content: &str,
+        todo_keywords: Option<TodoKeywordLists>,
+    ) -> Result<Self, String> {
+        // A panic in the parser becomes an error for this file only
+        let started = Instant::now();
+        let document = catch_parse_panic(|| {
+            match &todo_keywords {
+                Some(keywords) => {
+                    parse_org_document_with_keywords(content, path.to_str(), keywords.clone())
+                }
+                None => parse_org_document(content, path.to_str()),
+            }
+            .map_err(|e| format!("Failed to parse document: {}", e))
+        })?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            document,
+            todo_keywords,
+            bytes: content.len(),
+            elapsed: started.elapsed(),
+        })
+    }
+
+    /// The parsed document, for callers that do not add it to a repository
+    pub fn into_document(self) -> OrgDocument {
+        self.document
+    }
+}
+
+/// Parse content without the repository lock, then insert it under a short lock
+/// Keeps the repository readable while large files are parsed. If another parse of the
+/// file was stored in the meantime, it is newer and kept, and its document ID returned.
+pub fn parse_into(
+    repository: &Mutex<OrgDocumentRepository>,
+    path: &Path,
+    content: &str,
+    todo_keywords: Option<TodoKeywordLists>,
+) -> Result<String, String> {
+    let lock = || {
+        repository
+            .lock()
+            .map_err(|e| format!("Failed to lock repository: {}", e))
+    };
+    let etag = lock()?.stored_etag(path);
+    let parsed = ParsedFile::parse(path, content, todo_keywords);
+
+    let mut repository_lock = lock()?;
+    if repository_lock.stored_etag(path) != etag {
+        return path
+            .to_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("Invalid file name: {}", path.display()));
+    }
+    repository_lock.insert_parse_result(path, parsed)
+}
+
+/// Reconcile file content with the repository, parsing outside the lock when needed
+pub fn reconcile_into(
+    repository: &Mutex<OrgDocumentRepository>,
+    path: &Path,
+    content: &str,
+    todo_keywords: TodoKeywordLists,
+) -> Result<ReconcileOutcome, String> {
+    let current = repository
+        .lock()
+        .map_err(|e| format!("Failed to lock repository: {}", e))?
+        .is_current(path, content, &todo_keywords);
+    if current {
+        return Ok(ReconcileOutcome::Reused);
+    }
+
+    parse_into(repository, path, content, Some(todo_keywords))?;
+    Ok(ReconcileOutcome::Reparsed)
+}
+
+impl ReconcileSummary {
+    pub fn record(&mut self, outcome: Result<ReconcileOutcome, String>) {
+        match outcome {
+            Ok(ReconcileOutcome::Reused) => self.reused += 1,
+            Ok(ReconcileOutcome::Reparsed) => self.reparsed += 1,
+            Err(_) => self.failed += 1,
+        }
+    }
 }
 
 impl OrgDocumentRepository {
@@ -19,13 +164,35 @@ impl OrgDocumentRepository {
         Self {
             documents: HashMap::new(),
             last_updated: HashMap::new(),
+            renamed_ids: HashMap::new(),
+            updates: UpdateTracker::new(MAX_UPDATE_HISTORY),
+            search_index: SearchIndex::new(),
+            property_index: PropertyIndex::new(),
+            parsed_keywords: HashMap::new(),
+            parse_timings: ParseTimings::new(),
+            strings: StringInterner::new(),
+            last_opened: HashMap::new(),
+            evicted: HashSet::new(),
+            diagnostics: HashMap::new(),
+            text_stats: HashMap::new(),
         }
     }
 
     // Add or update a document
-    pub fn upsert(&mut self, document: OrgDocument) {
+    pub fn upsert(&mut self, mut document: OrgDocument) {
+        self.strings.intern_document(&mut document);
         let id = document.id.clone();
-        self.documents.insert(id.clone(), document);
+        self.evicted.remove(&id);
+        if let Some(update) = OrgUpdateInfo::between(self.documents.get(&id), &document) {
+            self.updates.add_update(update);
+        }
+        self.search_index.index_document(&document);
+        self.property_index.index_document(&document);
+        self.text_stats
+            .insert(id.clone(), document_stats(&document));
+        if self.documents.insert(id.clone(), document).is_some() {
+            self.strings.prune();
+        }
         self.last_updated.insert(id, Utc::now());
     }
 
@@ -42,35 +209,233 @@ impl OrgDocumentRepository {
     // Remove document
     pub fn remove(&mut self, id: &str) -> Option<OrgDocument> {
         self.last_updated.remove(id);
-        self.documents.remove(id)
+        let removed = self.documents.remove(id);
+        self.search_index.remove_document(id);
+        self.property_index.remove_document(id);
+        self.parsed_keywords.remove(id);
+        self.diagnostics.remove(id);
+        self.text_stats.remove(id);
+        self.last_opened.remove(id);
+        self.evicted.remove(id);
+        if removed.is_some() {
+            self.strings.prune();
+            self.updates
+                .add_update(OrgUpdateInfo::new(ChangeKind::Removed, id));
+        }
+        removed
     }
 
-    // Parse a file and add it to the repository
-    pub fn parse_file(&mut self, path: &Path) -> Result<String, String> {
-        // Read the file
-        let content = fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
+    // Get document and headline changes after a change feed cursor
+    pub fn changes_since(&self, cursor: u32) -> ChangeFeed {
+        self.updates.changes_since(cursor)
+    }
 
-        // Get file name for document ID
-        let file_name = path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .ok_or_else(|| format!("Invalid file name: {}", path.display()))?;
+    // Find headlines matching a full-text query, all property filters and all date terms
+    pub fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: &[PropertyFilter],
+        dates: &[DateTerm],
+    ) -> Vec<SearchHit> {
+        self.search_cancellable(query, limit, filters, dates, &CancellationToken::new())
+            .unwrap_or_default()
+    }
 
-        // Parse the document (fallback to content-based parsing)
-        let mut document = parse_org_document(&content, path.to_str())
-            .map_err(|e| format!("Failed to parse document: {}", e))?;
+    // Search that stops with None once the token is cancelled
+    // A query of date terms alone matches every headline with a date in their ranges
+    pub fn search_cancellable(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: &[PropertyFilter],
+        dates: &[DateTerm],
+        token: &CancellationToken,
+    ) -> Option<Vec<SearchHit>> {
+        if filters.is_empty() && dates.is_empty() {
+            return self.search_index.search_cancellable(query, limit, token);
+        }
 
-        // Use file name as document ID if not set
-        if document.id.is_empty() {
-            document.id = file_name.to_string();
+        let mut hits = if query.trim().is_empty() && !dates.is_empty() {
+            self.dated_headlines(dates)
+        } else {
+            self.search_index
+                .search_cancellable(query, usize::MAX, token)?
+        };
+        hits.retain(|hit| {
+            self.documents
+                .get(&hit.document_id)
+                .is_some_and(|document| {
+                    document
+                        .find_headline(&hit.headline_id)
+                        .is_some_and(|headline| {
+                            matches_filters(document, headline, filters)
+                                && dates.iter().all(|date| date.matches(headline))
+                        })
+                })
+        });
+        hits.truncate(limit);
+        (!token.is_cancelled()).then_some(hits)
+    }
+
+    // Every headline with a date in the ranges of all date terms, in file order
+    fn dated_headlines(&self, dates: &[DateTerm]) -> Vec<SearchHit> {
+        let mut documents: Vec<&OrgDocument> = self
+            .documents
+            .values()
+            .filter(|document| !document.missing)
+            .collect();
+        documents.sort_by(|a, b| a.id.cmp(&b.id));
+        documents
+            .into_iter()
+            .flat_map(|document| {
+                flatten_headlines(&document.headlines)
+                    .into_iter()
+                    .filter(|headline| dates.iter().all(|date| date.matches(headline)))
+                    .map(|headline| SearchHit {
+                        document_id: document.id.clone(),
+                        headline_id: headline.id.clone(),
+                        score: 0,
+                    })
+            })
+            .collect()
+    }
+
+    // Get all distinct values of a headline property
+    pub fn get_property_values(&self, property: &str) -> Vec<PropertyValue> {
+        self.property_index.values(property)
+    }
+
+    // Rebuild the full-text index from scratch, reporting (indexed, total) after each document
+    // On cancellation the previous index is kept and None is returned
+    pub fn rebuild_search_index(
+        &mut self,
+        token: &CancellationToken,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Option<usize> {
+        let mut search_index = SearchIndex::new();
+        let documents: Vec<&OrgDocument> = self
+            .documents
+            .values()
+            .filter(|document| !document.missing)
+            .collect();
+
+        let total = documents.len();
+        for (index, document) in documents.into_iter().enumerate() {
+            if token.is_cancelled() {
+                return None;
+            }
+            search_index.index_document(document);
+            progress(index + 1, total);
         }
 
-        // Add to repository
-        let doc_id = document.id.clone();
-        self.upsert(document);
+        self.search_index = search_index;
+        Some(total)
+    }
 
-        Ok(doc_id)
+    // Move a document to a new path, keeping its content and headline IDs
+    // Returns the new document ID, or None if the old path is not known
+    pub fn rename_document(&mut self, old_path: &Path, new_path: &Path) -> Option<String> {
+        let old_id = old_path.to_str()?;
+        let new_id = new_path.to_str()?.to_string();
+
+        let mut document = self.documents.remove(old_id)?;
+        self.last_updated.remove(old_id);
+        document.id = new_id.clone();
+        document.file_path = new_id.clone();
+        document.archived = is_archive_path(new_path);
+        update_headline_document_ids(&mut document.headlines, &new_id);
+        self.search_index.remove_document(old_id);
+        self.search_index.index_document(&document);
+        self.property_index.remove_document(old_id);
+        self.property_index.index_document(&document);
+        if let Some(keywords) = self.parsed_keywords.remove(old_id) {
+            self.parsed_keywords.insert(new_id.clone(), keywords);
+        }
+        if let Some(mut stats) = self.text_stats.remove(old_id) {
+            stats.document_id = new_id.clone();
+            self.text_stats.insert(new_id.clone(), stats);
+        }
+        if let Some(opened) = self.last_opened.remove(old_id) {
+            self.last_opened.insert(new_id.clone(), opened);
+        }
+        if self.evicted.remove(old_id) {
+            self.evicted.insert(new_id.clone());
+        }
+        self.documents.insert(new_id.clone(), document);
+        self.last_updated.insert(new_id.clone(), Utc::now());
+        self.updates.add_update(OrgUpdateInfo::new(
+            ChangeKind::Renamed {
+                from: old_id.to_string(),
+            },
+            &new_id,
+        ));
+
+        // Keep earlier renames pointing at the current ID
+        for current_id in self.renamed_ids.values_mut() {
+            if current_id == old_id {
+                *current_id = new_id.clone();
+            }
+        }
+        self.renamed_ids.remove(&new_id);
+        self.renamed_ids.insert(old_id.to_string(), new_id.clone());
+
+        Some(new_id)
+    }
+
+    // Mark a document whose file disappeared, returning true if it was known
+    pub fn mark_missing(&mut self, id: &str) -> bool {
+        match self.documents.get_mut(id) {
+            Some(document) => {
+                if !document.missing {
+                    document.missing = true;
+                    self.search_index.remove_document(id);
+                    self.last_updated.insert(id.to_string(), Utc::now());
+                    self.updates
+                        .add_update(OrgUpdateInfo::new(ChangeKind::Missing, id));
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Remove a document only if it is marked as missing
+    pub fn forget_missing(&mut self, id: &str) -> Option<OrgDocument> {
+        if self
+            .documents
+            .get(id)
+            .is_some_and(|document| document.missing)
+        {
+            self.remove(id)
+        } else {
+            None
+        }
+    }
+
+    // Resolve a possibly outdated document ID to the current one
+    pub fn resolve_id(&self, id: &str) -> Option<String> {
+        if self.documents.contains_key(id) {
+            return Some(id.to_string());
+        }
+
+        self.renamed_ids
+            .get(id)
+            .filter(|current_id| self.documents.contains_key(current_id.as_str()))
+            .cloned()
+    }
+
+    // Parse a file and add it to the repository
+    pub fn parse_file(&mut self, path: &Path) -> Result<String, String> {
+        let content = read_file(path)?;
+        self.parse_content(path, &content)
+    }
+
+    // Parse already-read file content and add it to the repository
+    // Lets callers read files before taking the repository lock
+    pub fn parse_content(&mut self, path: &Path, content: &str) -> Result<String, String> {
+        let parsed = ParsedFile::parse(path, content, None);
+        self.insert_parse_result(path, parsed)
     }
 
     // Parse a file with user settings and add it to the repository
@@ -79,18 +444,14 @@ impl OrgDocumentRepository {
         path: &Path,
         app_handle: Option<&tauri::AppHandle>,
     ) -> Result<String, String> {
-        // Read the file
-        let content = fs::read_to_string(path)
+        // Read the file without blocking the async runtime
+        let content = tokio::fs::read_to_string(path)
+            .await
             .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
 
-        // Get file name for document ID
-        let file_name = path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .ok_or_else(|| format!("Invalid file name: {}", path.display()))?;
-
         // Parse the document with user settings
-        let mut document = if let Some(handle) = app_handle {
+        let started = Instant::now();
+        let document = if let Some(handle) = app_handle {
             parse_org_document_with_settings(&content, path.to_str(), Some(handle))
                 .await
                 .map_err(|e| format!("Failed to parse document: {}", e))?
@@ -98,42 +459,236 @@ impl OrgDocumentRepository {
             parse_org_document(&content, path.to_str())
                 .map_err(|e| format!("Failed to parse document: {}", e))?
         };
+        self.insert_parsed_file(ParsedFile {
+            path: path.to_path_buf(),
+            document,
+            todo_keywords: None,
+            bytes: content.len(),
+            elapsed: started.elapsed(),
+        })
+    }
 
-        // Use file name as document ID if not set
-        if document.id.is_empty() {
-            document.id = file_name.to_string();
+    // Parse a file with custom TODO keywords and add it to the repository
+    pub fn parse_file_with_keywords(
+        &mut self,
+        path: &Path,
+        todo_keywords: TodoKeywordLists,
+    ) -> Result<String, String> {
+        let content = read_file(path)?;
+        self.parse_content_with_keywords(path, &content, todo_keywords)
+    }
+
+    // Parse already-read file content with custom TODO keywords
+    pub fn parse_content_with_keywords(
+        &mut self,
+        path: &Path,
+        content: &str,
+        todo_keywords: TodoKeywordLists,
+    ) -> Result<String, String> {
+        let parsed = ParsedFile::parse(path, content, Some(todo_keywords));
+        self.insert_parse_result(path, parsed)
+    }
+
+    // Keep a file's document if its content and keywords are unchanged, otherwise parse it
+    pub fn reconcile_file(
+        &mut self,
+        path: &Path,
+        todo_keywords: TodoKeywordLists,
+    ) -> Result<ReconcileOutcome, String> {
+        let content = read_file(path)?;
+        self.reconcile_content(path, &content, todo_keywords)
+    }
+
+    // Reconcile already-read file content with the stored document
+    pub fn reconcile_content(
+        &mut self,
+        path: &Path,
+        content: &str,
+        todo_keywords: TodoKeywordLists,
+    ) -> Result<ReconcileOutcome, String> {
+        if self.is_current(path, content, &todo_keywords) {
+            return Ok(ReconcileOutcome::Reused);
         }
 
-        // Add to repository
-        let doc_id = document.id.clone();
-        self.upsert(document);
+        self.parse_content_with_keywords(path, content, todo_keywords)?;
+        Ok(ReconcileOutcome::Reparsed)
+    }
 
-        Ok(doc_id)
+    // Whether the stored document was parsed from this content with these keywords
+    pub fn is_current(&self, path: &Path, content: &str, todo_keywords: &TodoKeywordLists) -> bool {
+        path.to_str().is_some_and(|id| {
+            self.documents.get(id).is_some_and(|document| {
+                !document.missing && document.etag == generate_document_etag(content)
+            }) && self.parsed_keywords.get(id) == Some(todo_keywords)
+        })
     }
 
-    // Parse a file with custom TODO keywords and add it to the repository
-    pub fn parse_file_with_keywords(
+    // The etag of the document stored for a file, if it was parsed
+    fn stored_etag(&self, path: &Path) -> Option<String> {
+        let document = self.documents.get(path.to_str()?)?;
+        Some(document.etag.clone())
+    }
+
+    // Whether a document's stored etag still matches this content
+    pub fn matches_content(&self, id: &str, content: &str) -> bool {
+        self.documents.get(id).is_some_and(|document| {
+            !document.missing && document.etag == generate_document_etag(content)
+        })
+    }
+
+    // Insert a parsed file, or record why parsing it failed
+    pub fn insert_parse_result(
         &mut self,
         path: &Path,
-        todo_keywords: (Vec<String>, Vec<String>),
+        parsed: Result<ParsedFile, String>,
     ) -> Result<String, String> {
-        // Read the file
-        let content = fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
+        match parsed {
+            Ok(parsed) => self.insert_parsed_file(parsed),
+            Err(message) => {
+                let file_path = path.to_string_lossy();
+                self.diagnostics.insert(
+                    file_path.to_string(),
+                    ParseDiagnostic::new(&file_path, &message),
+                );
+                Err(message)
+            }
+        }
+    }
+
+    // Word counts and reading time of a document, computed when it was parsed
+    pub fn document_stats(&self, id: &str) -> Option<&DocumentStats> {
+        self.text_stats.get(id)
+    }
 
-        // Get file name for document ID
-        let file_name = path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .ok_or_else(|| format!("Invalid file name: {}", path.display()))?;
+    // Files whose most recent parse failed, sorted by path
+    pub fn parse_diagnostics(&self) -> Vec<ParseDiagnostic> {
+        let mut diagnostics: Vec<ParseDiagnostic> = self.diagnostics.values().cloned().collect();
+        diagnostics.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        diagnostics
+    }
+
+    // Add a file parsed outside the repository lock, returning its document ID
+    pub fn insert_parsed_file(&mut self, parsed: ParsedFile) -> Result<String, String> {
+        self.diagnostics
+            .remove(parsed.path.to_string_lossy().as_ref());
+        let headlines = flatten_headlines(&parsed.document.headlines).len();
+        self.parse_timings.record(
+            &parsed.path.to_string_lossy(),
+            parsed.elapsed,
+            parsed.bytes,
+            headlines,
+        );
 
-        // Parse the document with custom TODO keywords
-        let mut document = parse_org_document_with_keywords(&content, path.to_str(), todo_keywords)
-            .map_err(|e| format!("Failed to parse document: {}", e))?;
+        let doc_id = self.insert_parsed(&parsed.path, parsed.document)?;
+        if let Some(keywords) = parsed.todo_keywords {
+            self.parsed_keywords.insert(doc_id.clone(), keywords);
+        }
+        Ok(doc_id)
+    }
 
+    // Record that a document was opened, for least-recently-used eviction
+    pub fn mark_opened(&mut self, id: &str) {
+        if self.documents.contains_key(id) {
+            self.last_opened.insert(id.to_string(), Instant::now());
+        }
+    }
+
+    // Whether a document's body content has been unloaded
+    pub fn is_evicted(&self, id: &str) -> bool {
+        self.evicted.contains(id)
+    }
+
+    // Unload body content of all but the `keep` most recently opened documents
+    // Outlines stay loaded, and the search index keeps body terms until it is rebuilt
+    // Returns the IDs of newly evicted documents
+    pub fn evict_least_recent(&mut self, keep: usize) -> Vec<String> {
+        let mut candidates: Vec<(&String, Option<&Instant>)> = self
+            .documents
+            .values()
+            .filter(|document| !document.missing)
+            .map(|document| (&document.id, self.last_opened.get(&document.id)))
+            .collect();
+        // Most recently opened first; never-opened documents go last
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let stale: Vec<String> = candidates
+            .into_iter()
+            .skip(keep)
+            .map(|(id, _)| id.clone())
+            .filter(|id| !self.evicted.contains(id))
+            .collect();
+        for id in &stale {
+            if let Some(document) = self.documents.get_mut(id) {
+                strip_body_content(document);
+                self.evicted.insert(id.clone());
+            }
+        }
+        stale
+    }
+
+    // TODO keywords a document was parsed with, if they differ from the built-in ones
+    pub fn parsed_keywords(&self, id: &str) -> Option<&TodoKeywordLists> {
+        self.parsed_keywords.get(id)
+    }
+
+    // Reload an evicted document from its current file content
+    pub fn rehydrate_content(&mut self, id: &str, content: &str) -> Result<String, String> {
+        let path = self
+            .get_path_by_id(id)
+            .ok_or_else(|| format!("Document not found: {}", id))?;
+        match self.parsed_keywords.get(id).cloned() {
+            Some(keywords) => self.parse_content_with_keywords(Path::new(&path), content, keywords),
+            None => self.parse_content(Path::new(&path), content),
+        }
+    }
+
+    // Approximate memory held by documents
+    pub fn memory_stats(&self) -> MemoryStats {
+        let documents = self.list();
+        MemoryStats {
+            documents: documents.len() as u32,
+            headlines: documents
+                .iter()
+                .map(|document| flatten_headlines(&document.headlines).len() as u32)
+                .sum(),
+            evicted_documents: self.evicted.len() as u32,
+            interned_strings: self.strings.len() as u32,
+            estimated_bytes: documents
+                .iter()
+                .map(|document| estimate_document_bytes(document))
+                .sum::<usize>()
+                .min(u32::MAX as usize) as u32,
+        }
+    }
+
+    // Recent parse durations and repository sizes
+    pub fn perf_stats(&self) -> PerfStats {
+        let documents = self.list();
+        PerfStats {
+            documents: documents.len() as u32,
+            headlines: documents
+                .iter()
+                .map(|document| flatten_headlines(&document.headlines).len() as u32)
+                .sum(),
+            content_bytes: documents
+                .iter()
+                .map(|document| document.content.len() as u32)
+                .sum(),
+            indexed_documents: self.search_index.document_count() as u32,
+            average_parse_micros: self.parse_timings.average_micros(),
+            recent_parses: self.parse_timings.recent(),
+        }
+    }
+
+    // Add a freshly parsed document, returning its ID
+    fn insert_parsed(&mut self, path: &Path, mut document: OrgDocument) -> Result<String, String> {
         // Use file name as document ID if not set
         if document.id.is_empty() {
-            document.id = file_name.to_string();
+            document.id = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| format!("Invalid file name: {}", path.display()))?
+                .to_string();
         }
 
         // Add to repository
@@ -143,6 +698,21 @@ impl OrgDocumentRepository {
         Ok(doc_id)
     }
 
+    // Mark documents missing whose files are gone, returning their IDs
+    pub fn mark_deleted_files_missing(&mut self) -> Vec<String> {
+        let deleted: Vec<String> = self
+            .documents
+            .values()
+            .filter(|document| !document.missing && !Path::new(&document.file_path).exists())
+            .map(|document| document.id.clone())
+            .collect();
+
+        for id in &deleted {
+            self.mark_missing(id);
+        }
+        deleted
+    }
+
     // Get document for headline
     pub fn get_document_for_headline(&self, headline_id: &str) -> Option<&OrgDocument> {
         for document in self.documents.values() {
@@ -231,11 +801,27 @@ impl OrgDocumentRepository {
     }
 }
 
+// Read a whole file, with the path in the error message
+fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Failed to read file {}: {}", path.display(), e))
+}
+
+// Point all headlines at a new document ID
+fn update_headline_document_ids(headlines: &mut [OrgHeadline], document_id: &str) {
+    for headline in headlines.iter_mut() {
+        headline.document_id = document_id.to_string();
+        update_headline_document_ids(&mut headline.children, document_id);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::orgmode::OrgTitle;
+    use crate::orgmode::search::split_date_terms;
+    use crate::orgmode::{DocumentFlags, OrgTitle, StartupOptions};
+    use chrono::{NaiveDate, Weekday};
     use std::collections::HashMap;
+    use tempfile::tempdir;
 
     #[test]
     fn test_repository_basic_operations() {
@@ -251,9 +837,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "test1.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         let doc2 = OrgDocument {
@@ -265,9 +858,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "test2.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag2".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Test upsert
@@ -343,9 +943,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "test.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag4".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         repo.upsert(doc);
@@ -375,9 +982,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/path/to/test1.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Document with empty title (should fall back to filename)
@@ -390,9 +1004,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/path/to/test2.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag2".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Document with invalid path that has no filename (should fall back to "Untitled")
@@ -405,9 +1026,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag3".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         repo.upsert(doc1);
@@ -454,9 +1082,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/monitored/file1.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         let doc2 = OrgDocument {
@@ -468,9 +1103,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/unmonitored/file2.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag2".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         let doc3 = OrgDocument {
@@ -482,9 +1124,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/monitored/subdir/file3.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag3".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Add documents to repository
@@ -538,9 +1187,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/path/file1.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         repo.upsert(doc1);
@@ -571,9 +1227,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/monitored/path/file.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag1".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         let unmonitored_doc = OrgDocument {
@@ -585,9 +1248,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/unmonitored/path/file.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag2".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         let disabled_doc = OrgDocument {
@@ -599,9 +1269,16 @@ mod tests {
             parsed_at: Utc::now(),
             file_path: "/disabled/path/file.org".to_string(),
             properties: HashMap::new(),
+            keywords: HashMap::new(),
+            startup: StartupOptions::default(),
+            flags: DocumentFlags::default(),
+            constants: HashMap::new(),
             category: "Test".to_string(),
             etag: "etag3".to_string(),
             todo_config: None,
+            tag_styles: HashMap::new(),
+            archived: false,
+            missing: false,
         };
 
         // Initially, all documents are in the repository
@@ -677,9 +1354,16 @@ mod tests {
                 parsed_at: Utc::now(),
                 file_path: test_file_path.to_string(),
                 properties: HashMap::new(),
+                keywords: HashMap::new(),
+                startup: StartupOptions::default(),
+                flags: DocumentFlags::default(),
+                constants: HashMap::new(),
                 category: "Test".to_string(),
                 etag: "etag1".to_string(),
                 todo_config: None,
+                tag_styles: HashMap::new(),
+                archived: false,
+                missing: false,
             }
         };
 
@@ -743,4 +1427,259 @@ mod tests {
 
         // This test confirms that using file path as document ID eliminates the duplicate issue
     }
+
+    #[test]
+    fn test_rename_document_keeps_identity() {
+        let mut repo = OrgDocumentRepository::new();
+        let document = parse_org_document(
+            "#+TITLE: Notes\n* TODO Task\n** Child\n",
+            Some("/notes/a.org"),
+        )
+        .unwrap();
+        repo.upsert(document);
+
+        let new_id = repo
+            .rename_document(Path::new("/notes/a.org"), Path::new("/notes/b.org"))
+            .unwrap();
+        assert_eq!(new_id, "/notes/b.org");
+        assert!(repo.get("/notes/a.org").is_none());
+
+        let renamed = repo.get("/notes/b.org").unwrap();
+        assert_eq!(renamed.file_path, "/notes/b.org");
+        assert_eq!(renamed.headlines[0].id, "1");
+        assert_eq!(renamed.headlines[0].children[0].document_id, "/notes/b.org");
+
+        // Old IDs resolve to the current one, across several renames
+        repo.rename_document(Path::new("/notes/b.org"), Path::new("/notes/c.org"));
+        assert_eq!(
+            repo.resolve_id("/notes/a.org").as_deref(),
+            Some("/notes/c.org")
+        );
+        assert_eq!(
+            repo.resolve_id("/notes/c.org").as_deref(),
+            Some("/notes/c.org")
+        );
+        assert_eq!(repo.resolve_id("/notes/unknown.org"), None);
+
+        // Unknown sources are not renamed
+        assert!(repo
+            .rename_document(Path::new("/notes/missing.org"), Path::new("/notes/d.org"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_missing_documents() {
+        let mut repo = OrgDocumentRepository::new();
+        let document = parse_org_document("* TODO Task\n", Some("/notes/gone.org")).unwrap();
+        repo.upsert(document);
+
+        // Present documents cannot be forgotten
+        assert!(repo.forget_missing("/notes/gone.org").is_none());
+
+        assert!(repo.mark_missing("/notes/gone.org"));
+        assert!(!repo.mark_missing("/notes/unknown.org"));
+        assert!(repo.get("/notes/gone.org").unwrap().missing);
+
+        assert!(repo.forget_missing("/notes/gone.org").is_some());
+        assert!(repo.get("/notes/gone.org").is_none());
+    }
+
+    #[test]
+    fn test_repository_change_feed() {
+        let mut repo = OrgDocumentRepository::new();
+        let cursor = repo.changes_since(0).cursor;
+
+        repo.upsert(parse_org_document("* One\n", Some("/notes/a.org")).unwrap());
+        repo.upsert(parse_org_document("* One\n", Some("/notes/a.org")).unwrap());
+        repo.upsert(parse_org_document("* One\n* Two\n", Some("/notes/a.org")).unwrap());
+        repo.rename_document(Path::new("/notes/a.org"), Path::new("/notes/b.org"));
+        repo.remove("/notes/b.org");
+
+        let feed = repo.changes_since(cursor);
+        assert!(feed.complete);
+        let kinds: Vec<ChangeKind> = feed.changes.iter().map(|c| c.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::Added,
+                ChangeKind::Updated,
+                ChangeKind::Renamed {
+                    from: "/notes/a.org".to_string()
+                },
+                ChangeKind::Removed,
+            ]
+        );
+        assert_eq!(feed.changes[1].new_headlines, vec!["2"]);
+        assert!(repo.changes_since(feed.cursor).changes.is_empty());
+    }
+
+    #[test]
+    fn test_search_index_follows_repository() {
+        let mut repo = OrgDocumentRepository::new();
+        repo.upsert(
+            parse_org_document(
+                "* Quarterly report
+",
+                Some("/notes/a.org"),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            repo.search("quarterly", 10, &[], &[])[0].document_id,
+            "/notes/a.org"
+        );
+
+        repo.rename_document(Path::new("/notes/a.org"), Path::new("/notes/b.org"));
+        assert_eq!(
+            repo.search("quarterly", 10, &[], &[])[0].document_id,
+            "/notes/b.org"
+        );
+
+        repo.mark_missing("/notes/b.org");
+        assert!(repo.search("quarterly", 10, &[], &[]).is_empty());
+
+        let mut steps = Vec::new();
+        repo.upsert(
+            parse_org_document(
+                "* Budget
+",
+                Some("/notes/c.org"),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            repo.rebuild_search_index(&CancellationToken::new(), |done, total| steps
+                .push((done, total))),
+            Some(1)
+        );
+        assert_eq!(steps, vec![(1, 1)]);
+        assert_eq!(repo.search("budget", 10, &[], &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_search_date_terms() {
+        let mut repo = OrgDocumentRepository::new();
+        repo.upsert(
+            parse_org_document(
+                "* TODO Quarterly report\nDEADLINE: <2024-06-28 Fri>\n* TODO Quarterly plan\nSCHEDULED: <2024-07-01 Mon>\n* Notes\n",
+                Some("/notes/a.org"),
+            )
+            .unwrap(),
+        );
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let titles = |query: &str| {
+            let (text, dates) = split_date_terms(query, today, Weekday::Mon).unwrap();
+            repo.search(&text, 10, &[], &dates)
+                .into_iter()
+                .map(|hit| hit.headline_id)
+                .collect::<Vec<_>>()
+        };
+        // Date terms on their own list every headline in the range
+        assert_eq!(titles("due:this-quarter"), vec!["1"]);
+        assert_eq!(titles("due:2024-Q3"), vec!["2"]);
+        assert_eq!(titles("quarterly scheduled:W27"), vec!["2"]);
+        assert!(titles("quarterly deadline:W27").is_empty());
+        assert!(split_date_terms("due:someday", today, Weekday::Mon).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notes.org");
+        fs::write(&path, "* TODO Task\n").unwrap();
+        let keywords = (vec!["TODO".to_string()], vec!["DONE".to_string()]);
+
+        let mut repo = OrgDocumentRepository::new();
+        let mut summary = ReconcileSummary::default();
+        summary.record(repo.reconcile_file(&path, keywords.clone()));
+        summary.record(repo.reconcile_file(&path, keywords.clone()));
+
+        // Changed keywords force a reparse even if the file is the same
+        let other_keywords = (vec!["NEXT".to_string()], vec!["DONE".to_string()]);
+        summary.record(repo.reconcile_file(&path, other_keywords));
+
+        fs::write(&path, "* TODO Edited\n").unwrap();
+        summary.record(repo.reconcile_file(&path, keywords.clone()));
+        summary.record(repo.reconcile_file(&dir.path().join("absent.org"), keywords));
+        assert_eq!(
+            summary,
+            ReconcileSummary {
+                reused: 1,
+                reparsed: 3,
+                missing: 0,
+                failed: 1,
+            }
+        );
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            repo.mark_deleted_files_missing(),
+            vec![path.to_str().unwrap()]
+        );
+        assert!(repo.get(path.to_str().unwrap()).unwrap().missing);
+    }
+
+    #[test]
+    fn test_evict_least_recent() {
+        let mut repo = OrgDocumentRepository::new();
+        let content = "* Heading\nBody text\n";
+        for name in ["/notes/a.org", "/notes/b.org", "/notes/c.org"] {
+            repo.parse_content(Path::new(name), content).unwrap();
+        }
+        let before = repo.memory_stats();
+
+        repo.mark_opened("/notes/c.org");
+        repo.mark_opened("/notes/a.org");
+        assert_eq!(repo.evict_least_recent(2), vec!["/notes/b.org"]);
+        assert!(repo.evict_least_recent(2).is_empty());
+        assert!(repo.get("/notes/b.org").unwrap().headlines[0]
+            .content
+            .is_empty());
+
+        let stats = repo.memory_stats();
+        assert_eq!(stats.evicted_documents, 1);
+        assert!(stats.estimated_bytes < before.estimated_bytes);
+
+        repo.rehydrate_content("/notes/b.org", content).unwrap();
+        assert!(!repo.is_evicted("/notes/b.org"));
+        assert_eq!(repo.memory_stats(), before);
+    }
+
+    #[test]
+    fn test_reconcile_into() {
+        let repository = Mutex::new(OrgDocumentRepository::new());
+        let path = Path::new("/notes/locked.org");
+        let keywords = (vec!["TODO".to_string()], vec!["DONE".to_string()]);
+
+        let first = reconcile_into(&repository, path, "* TODO Task\n", keywords.clone());
+        let second = reconcile_into(&repository, path, "* TODO Task\n", keywords.clone());
+        assert_eq!(first, Ok(ReconcileOutcome::Reparsed));
+        assert_eq!(second, Ok(ReconcileOutcome::Reused));
+
+        parse_into(&repository, path, "* DONE Task\n", Some(keywords)).unwrap();
+        let repository = repository.lock().unwrap();
+        let document = repository.get("/notes/locked.org").unwrap();
+        assert_eq!(
+            document.headlines[0].title.todo_keyword.as_deref(),
+            Some("DONE")
+        );
+        assert_eq!(repository.perf_stats().recent_parses.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let mut repo = OrgDocumentRepository::new();
+        let path = Path::new("/notes/broken.org");
+
+        let crashed = catch_parse_panic::<ParsedFile>(|| panic!("unbalanced drawer"));
+        assert!(repo.insert_parse_result(path, crashed).is_err());
+        let diagnostics = repo.parse_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].panicked);
+        assert_eq!(diagnostics[0].file_path, "/notes/broken.org");
+
+        // A later successful parse clears the diagnostic
+        repo.parse_content(path, "* Fixed\n").unwrap();
+        assert!(repo.parse_diagnostics().is_empty());
+    }
 }
@@ -0,0 +1,426 @@
+use crate::orgmode::datetime::OrgDatetime;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::properties::{is_planning_line, matches_filters, PropertyFilter};
+use crate::orgmode::source::{flatten_headlines, headline_level};
+use crate::orgmode::todo::TodoConfiguration;
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A relative date change such as `+1d`, `-2w`, `+1m` or `+1y`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateDelta {
+    amount: i64,
+    unit: char, // d, w, m or y
+}
+
+impl DateDelta {
+    /// Parse a delta in org's `+Nd` form; the sign defaults to `+`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid date shift: '{}'", text);
+        let text = text.trim();
+        let unit = text
+            .chars()
+            .last()
+            .ok_or_else(invalid)?
+            .to_ascii_lowercase();
+        if !matches!(unit, 'd' | 'w' | 'm' | 'y') {
+            return Err(invalid());
+        }
+        let number = &text[..text.len() - 1];
+        let (sign, digits) = match number.strip_prefix('-') {
+            Some(digits) => (-1, digits),
+            None => (1, number.strip_prefix('+').unwrap_or(number)),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let amount: i64 = digits.parse().map_err(|_| invalid())?;
+        Ok(Self {
+            amount: sign * amount,
+            unit,
+        })
+    }
+
+    /// Move a date by the delta, or None if it leaves chrono's range
+    pub fn apply(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let magnitude = self.amount.unsigned_abs();
+        match self.unit {
+            'd' | 'w' => {
+                let days = Days::new(if self.unit == 'w' {
+                    magnitude * 7
+                } else {
+                    magnitude
+                });
+                if self.amount < 0 {
+                    date.checked_sub_days(days)
+                } else {
+                    date.checked_add_days(days)
+                }
+            }
+            _ => {
+                let months = if self.unit == 'y' {
+                    magnitude * 12
+                } else {
+                    magnitude
+                };
+                let months = Months::new(u32::try_from(months).ok()?);
+                if self.amount < 0 {
+                    date.checked_sub_months(months)
+                } else {
+                    date.checked_add_months(months)
+                }
+            }
+        }
+    }
+}
+
+/// A planning line timestamp that a bulk shift can move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PlanningField {
+    Scheduled,
+    Deadline,
+}
+
+impl PlanningField {
+    fn keyword(self) -> &'static str {
+        match self {
+            PlanningField::Scheduled => "SCHEDULED:",
+            PlanningField::Deadline => "DEADLINE:",
+        }
+    }
+}
+
+/// Which open tasks and dates a bulk shift applies to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ShiftFilter {
+    pub properties: Vec<PropertyFilter>, // Every filter must match, with inheritance
+    pub fields: Vec<PlanningField>,
+    pub overdue_only: bool, // Only shift dates before today
+}
+
+/// One date a bulk shift will move, for previewing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct DateShift {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub field: PlanningField,
+    pub from: String, // The timestamp as it is now
+    pub to: String,   // The timestamp after the shift
+}
+
+// Shift the date of an org timestamp such as `<2024-03-05 Tue 10:00 +1w>`, renaming the
+// day in `language` and keeping the time, repeater and delay
+fn shift_timestamp(timestamp: &str, delta: &DateDelta, language: &str) -> Option<String> {
+    let close = match timestamp.chars().next()? {
+        '<' => '>',
+        '[' => ']',
+        _ => return None,
+    };
+    let end = timestamp.find(close)?;
+    let inner = &timestamp[1..end];
+    let date = NaiveDate::parse_from_str(inner.get(..10)?, "%Y-%m-%d").ok()?;
+    let shifted = delta.apply(date)?;
+
+    // Keep the time, repeater and delay, dropping the old day name after the date
+    let mut words: Vec<&str> = inner[10..].split_whitespace().collect();
+    if words
+        .first()
+        .is_some_and(|word| word.starts_with(char::is_alphabetic))
+    {
+        words.remove(0);
+    }
+    let shifted = OrgDatetime::from_naive_date(shifted, language).format_org_date();
+    let mut shifted = format!("{}{}", &timestamp[..1], shifted);
+    for word in words {
+        shifted.push(' ');
+        shifted.push_str(word);
+    }
+    shifted.push_str(&timestamp[end..]);
+    Some(shifted)
+}
+
+/// Shift one planning timestamp of the headline on a 1-based line, naming the new day in
+/// `language`
+/// Returns None if the headline has no such timestamp
+pub fn shift_planning_date(
+    content: &str,
+    headline_line: usize,
+    field: PlanningField,
+    delta: &DateDelta,
+    language: &str,
+) -> Option<String> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    headline_level(lines.get(headline_line.checked_sub(1)?)?)?;
+    let planning = *lines
+        .get(headline_line)
+        .filter(|line| is_planning_line(line))?;
+
+    let keyword = planning.find(field.keyword())?;
+    let after = keyword + field.keyword().len();
+    let start = after + (planning[after..].len() - planning[after..].trim_start().len());
+    let close = if planning[start..].starts_with('[') {
+        ']'
+    } else {
+        '>'
+    };
+    let end = start + planning[start..].find(close)? + 1;
+    let shifted = shift_timestamp(&planning[start..end], delta, language)?;
+
+    let line = format!("{}{}{}", &planning[..start], shifted, &planning[end..]);
+    lines[headline_line] = &line;
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    Some(output)
+}
+
+/// Set one planning date of the headline on a 1-based line to an active day timestamp
+///
+/// An existing timestamp for the field is replaced, otherwise the field is added to the
+/// planning line in org's order, and the line is created if needed. The day is named in
+/// `language`. Returns None if the line is not a headline
+pub fn set_planning_date(
+    content: &str,
+    headline_line: usize,
+    field: PlanningField,
+    date: NaiveDate,
+    language: &str,
+) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    headline_level(lines.get(headline_line.checked_sub(1)?)?)?;
+    let date = OrgDatetime::from_naive_date(date, language).format_org_date();
+    let item = format!("{} <{}>", field.keyword(), date);
+
+    match lines
+        .get(headline_line)
+        .filter(|line| is_planning_line(line))
+    {
+        Some(planning) => {
+            let planning = planning.clone();
+            lines[headline_line] = match planning.find(field.keyword()) {
+                Some(keyword) => {
+                    let after = keyword + field.keyword().len();
+                    let rest = planning[after..].trim_start();
+                    let close = if rest.starts_with('[') { ']' } else { '>' };
+                    let end = rest.find(close).map_or(rest.len(), |end| end + 1);
+                    format!("{}{}{}", &planning[..keyword], item, &rest[end..])
+                }
+                // Keep org's CLOSED, DEADLINE, SCHEDULED order, which mobile apps write too
+                None => match planning.find(PlanningField::Scheduled.keyword()) {
+                    Some(scheduled) if field == PlanningField::Deadline => format!(
+                        "{}{} {}",
+                        &planning[..scheduled],
+                        item,
+                        &planning[scheduled..]
+                    ),
+                    _ => format!("{} {}", planning.trim_end(), item),
+                },
+            };
+        }
+        None => {
+            // A new planning line is indented like the property drawer it goes above
+            let indent = lines
+                .get(headline_line)
+                .filter(|line| line.trim().eq_ignore_ascii_case(":PROPERTIES:"))
+                .map_or(String::new(), |line| {
+                    line[..line.len() - line.trim_start().len()].to_string()
+                });
+            lines.insert(headline_line, format!("{}{}", indent, item));
+        }
+    }
+
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    Some(output)
+}
+
+/// List the dates a bulk shift would move in the given documents
+///
+/// Only open tasks (in an active TODO state) matching the property filters are shifted,
+/// and with `overdue_only` only their dates before `today`. Archives are skipped. Shifted
+/// days are named in `language`. Files' own `#+TODO:` keywords come before
+/// `settings_config`.
+pub fn plan_date_shifts(
+    documents: &[&OrgDocument],
+    filter: &ShiftFilter,
+    delta: &DateDelta,
+    today: NaiveDate,
+    language: &str,
+    settings_config: &TodoConfiguration,
+) -> Vec<DateShift> {
+    let mut shifts = Vec::new();
+
+    for document in documents {
+        if document.missing || document.archived || document.flags.read_only {
+            continue;
+        }
+        let config = document.effective_todo_config(settings_config);
+
+        for headline in flatten_headlines(&document.headlines) {
+            let is_open = headline
+                .get_todo_status(&config)
+                .is_some_and(|status| status.is_active());
+            if !is_open || !matches_filters(document, headline, &filter.properties) {
+                continue;
+            }
+            let Some(planning) = headline.title.planning.as_deref() else {
+                continue;
+            };
+
+            for field in &filter.fields {
+                let timestamp = match field {
+                    PlanningField::Scheduled => planning.scheduled.as_ref(),
+                    PlanningField::Deadline => planning.deadline.as_ref(),
+                };
+                // Diary sexps have no date to shift
+                let Some((timestamp, date)) = timestamp.and_then(|timestamp| {
+                    Some((timestamp, timestamp.start_date()?.to_naive_date()))
+                }) else {
+                    continue;
+                };
+                if filter.overdue_only && date >= today {
+                    continue;
+                }
+                let from = timestamp.format();
+                let Some(to) = shift_timestamp(&from, delta, language) else {
+                    continue;
+                };
+                shifts.push(DateShift {
+                    document_id: document.id.clone(),
+                    headline_id: headline.id.clone(),
+                    title: headline.title.raw.clone(),
+                    field: *field,
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+    shifts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_date_delta() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        let shift = |delta: &str| DateDelta::parse(delta).unwrap().apply(date).unwrap();
+        assert_eq!(shift("+1d"), NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        assert_eq!(shift("-2w"), NaiveDate::from_ymd_opt(2024, 1, 17).unwrap());
+        // Month ends are clamped
+        assert_eq!(shift("1m"), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(shift("+1y"), NaiveDate::from_ymd_opt(2025, 1, 31).unwrap());
+        assert!(DateDelta::parse("+d").is_err() && DateDelta::parse("tomorrow").is_err());
+    }
+
+    #[test]
+    fn test_shift_planning_date() {
+        let delta = DateDelta::parse("+1d").unwrap();
+        let content = "* TODO Call\n  DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon 10:00 +1w>\nBody\n";
+        assert_eq!(
+            shift_planning_date(content, 1, PlanningField::Scheduled, &delta, "en").unwrap(),
+            "* TODO Call\n  DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-05 Tue 10:00 +1w>\nBody\n"
+        );
+        assert_eq!(
+            shift_planning_date(content, 1, PlanningField::Deadline, &delta, "en").unwrap(),
+            "* TODO Call\n  DEADLINE: <2024-03-09 Sat> SCHEDULED: <2024-03-04 Mon 10:00 +1w>\nBody\n"
+        );
+        // The new day is named in the configured language
+        assert_eq!(
+            shift_planning_date(content, 1, PlanningField::Deadline, &delta, "de").unwrap(),
+            "* TODO Call\n  DEADLINE: <2024-03-09 Sa> SCHEDULED: <2024-03-04 Mon 10:00 +1w>\nBody\n"
+        );
+        assert_eq!(
+            shift_planning_date(
+                "* TODO Call\nBody\n",
+                1,
+                PlanningField::Deadline,
+                &delta,
+                "en"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_set_planning_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+        assert_eq!(
+            set_planning_date(
+                "* TODO Call\nBody\n",
+                1,
+                PlanningField::Scheduled,
+                date,
+                "en"
+            )
+            .unwrap(),
+            "* TODO Call\nSCHEDULED: <2024-03-06 Wed>\nBody\n"
+        );
+        assert_eq!(
+            set_planning_date(
+                "* TODO Call\n  DEADLINE: <2024-03-08 Fri>\n",
+                1,
+                PlanningField::Scheduled,
+                date,
+                "en"
+            )
+            .unwrap(),
+            "* TODO Call\n  DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-06 Wed>\n"
+        );
+        assert_eq!(
+            set_planning_date(
+                "* TODO Call\nSCHEDULED: <2024-03-01 Fri> DEADLINE: <2024-03-08 Fri>",
+                1,
+                PlanningField::Scheduled,
+                date,
+                "en"
+            )
+            .unwrap(),
+            "* TODO Call\nSCHEDULED: <2024-03-06 Wed> DEADLINE: <2024-03-08 Fri>"
+        );
+    }
+
+    #[test]
+    fn test_plan_date_shifts() {
+        let doc = parse_org_document(
+            "* TODO Overdue\nSCHEDULED: <2024-03-01 Fri> DEADLINE: <2024-03-20 Wed>\n* DONE Finished\nSCHEDULED: <2024-03-01 Fri>\n* TODO Later\nSCHEDULED: <2024-03-10 Sun>\n",
+            Some("tasks.org"),
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        let filter = ShiftFilter {
+            properties: Vec::new(),
+            fields: vec![PlanningField::Scheduled, PlanningField::Deadline],
+            overdue_only: true,
+        };
+        let delta = DateDelta::parse("+1w").unwrap();
+        let settings_config = TodoConfiguration::default();
+
+        // Only the overdue date of an open task moves
+        let shifts = plan_date_shifts(&[&doc], &filter, &delta, today, "en", &settings_config);
+        assert_eq!(shifts.len(), 1);
+        assert_eq!(shifts[0].title, "Overdue");
+        assert_eq!(shifts[0].field, PlanningField::Scheduled);
+        assert_eq!(
+            (shifts[0].from.as_str(), shifts[0].to.as_str()),
+            ("<2024-03-01 Fri>", "<2024-03-08 Fri>")
+        );
+
+        let everything = ShiftFilter {
+            overdue_only: false,
+            ..filter
+        };
+        assert_eq!(
+            plan_date_shifts(&[&doc], &everything, &delta, today, "en", &settings_config).len(),
+            3
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::orgmode::checklist::{checklist_items, parse_list_item};
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::properties::is_planning_line;
+use crate::orgmode::source::headline_level;
+use crate::orgmode::subtree::subtree_lines;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::ops::Range;
+
+/// Where a headline's body is split into new headlines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum SplitMode {
+    ListItems,  // At each top-level item of the first plain list
+    Paragraphs, // At each paragraph after the first
+}
+
+// Line indices (0-based) of a headline's subtree
+struct Section {
+    headline: usize,
+    children: usize, // First line of the first child, or `end` without children
+    end: usize,
+}
+
+fn find_section(document: &OrgDocument, lines: &[String], headline_id: &str) -> Option<Section> {
+    let range = subtree_lines(document, headline_id)?;
+    let children = (range.start + 1..range.end)
+        .find(|index| headline_level(&lines[*index]).is_some())
+        .unwrap_or(range.end);
+    Some(Section {
+        headline: range.start,
+        children,
+        end: range.end,
+    })
+}
+
+fn is_drawer_start(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() > 2
+        && trimmed.starts_with(':')
+        && trimmed.ends_with(':')
+        && !trimmed[1..trimmed.len() - 1].contains([':', ' '])
+        && !trimmed.eq_ignore_ascii_case(":END:")
+}
+
+// Index of the first body line of a section, after its planning line and drawers
+fn body_start(lines: &[String], section: &Section) -> usize {
+    let mut index = section.headline + 1;
+    if index < section.children && is_planning_line(&lines[index]) {
+        index += 1;
+    }
+    while index < section.children && is_drawer_start(&lines[index]) {
+        match lines[index + 1..section.children]
+            .iter()
+            .position(|line| line.trim().eq_ignore_ascii_case(":END:"))
+        {
+            Some(offset) => index += offset + 2,
+            None => break,
+        }
+    }
+    index
+}
+
+// Ranges of the paragraphs between `start` and `end`: runs of lines without a blank line,
+// keeping blocks whole
+fn paragraphs(lines: &[String], start: usize, end: usize) -> Vec<Range<usize>> {
+    let mut paragraphs = Vec::new();
+    let mut open = None;
+    let mut in_block = false;
+    for (index, line) in lines.iter().enumerate().take(end).skip(start) {
+        let upper = line.trim().to_ascii_uppercase();
+        let blank = upper.is_empty() && !in_block;
+        if upper.starts_with("#+BEGIN") {
+            in_block = true;
+        } else if upper.starts_with("#+END") {
+            in_block = false;
+        }
+        match (blank, open) {
+            (false, None) => open = Some(index),
+            (true, Some(first)) => {
+                paragraphs.push(first..index);
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(first) = open {
+        paragraphs.push(first..end);
+    }
+    paragraphs
+}
+
+// Lines with their common indentation removed
+fn dedent(lines: &[String]) -> Vec<String> {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default().to_string())
+        .collect()
+}
+
+// A new headline for the lines of a list item or paragraph, titled by its first line
+// Checkboxes become TODO or DONE keywords.
+fn split_entry(lines: &[String], stars: &str) -> Vec<String> {
+    let (keyword, title) = match parse_list_item(&lines[0]) {
+        Some(item) => match item.checkbox {
+            Some('X' | 'x') => ("DONE ", item.text),
+            Some(_) => ("TODO ", item.text),
+            None => ("", item.text),
+        },
+        None => ("", lines[0].as_str()),
+    };
+    let mut entry = vec![format!("{} {}{}", stars, keyword, title.trim())];
+    entry.extend(dedent(&lines[1..]));
+    entry
+}
+
+fn join_lines(lines: Vec<String>, document: &OrgDocument) -> String {
+    let mut content = lines.join("\n");
+    if document.content.ends_with('\n') {
+        content.push('\n');
+    }
+    content
+}
+
+/// Split a headline into siblings at the items of its first plain list or at its
+/// paragraphs, each new headline titled by the first line of its text
+///
+/// The new headlines follow the headline's subtree; its title, planning, drawers and the
+/// rest of its body stay. `document.content` must be the text the headlines were parsed
+/// from.
+pub fn split_headline(
+    document: &OrgDocument,
+    headline_id: &str,
+    mode: SplitMode,
+) -> Result<String, String> {
+    let mut lines: Vec<String> = document.content.lines().map(str::to_string).collect();
+    let section = find_section(document, &lines, headline_id)
+        .ok_or_else(|| format!("Headline not found: {}", headline_id))?;
+    let stars = "*".repeat(headline_level(&lines[section.headline]).unwrap_or(1));
+
+    let parts: Vec<Range<usize>> = match mode {
+        SplitMode::ListItems => checklist_items(&lines, section.headline)
+            .into_iter()
+            .map(|(start, end)| start..end)
+            .collect(),
+        SplitMode::Paragraphs => paragraphs(&lines, body_start(&lines, &section), section.children)
+            .into_iter()
+            .skip(1)
+            .collect(),
+    };
+    if parts.is_empty() {
+        return Err("Nothing to split".to_string());
+    }
+
+    // Add the new headlines below the subtree, then remove their text with the blank lines
+    // after it from the bottom up
+    let entries: Vec<String> = parts
+        .iter()
+        .flat_map(|range| split_entry(&lines[range.clone()], &stars))
+        .collect();
+    lines.splice(section.end..section.end, entries);
+    for range in parts.into_iter().rev() {
+        let end = (range.end..section.children)
+            .find(|index| !lines[*index].trim().is_empty())
+            .unwrap_or(section.children);
+        lines.drain(range.start..end);
+    }
+    Ok(join_lines(lines, document))
+}
+
+/// Merge sibling headlines into the first of them in file order
+///
+/// The others' headline lines without their stars become lines of its body, keeping their
+/// keywords and tags, followed by their bodies, and their children follow its own. Siblings
+/// after the first with a planning line or drawers are refused, so no ID or clock history is
+/// lost. `document.content` must be the text the headlines were parsed from.
+pub fn merge_headlines(document: &OrgDocument, headline_ids: &[String]) -> Result<String, String> {
+    let lines: Vec<String> = document.content.lines().map(str::to_string).collect();
+    let mut sections = Vec::with_capacity(headline_ids.len());
+    let mut parent = None;
+    for (index, headline_id) in headline_ids.iter().enumerate() {
+        let not_found = || format!("Headline not found: {}", headline_id);
+        let path = document.headline_path(headline_id).ok_or_else(not_found)?;
+        let headline_parent = path
+            .len()
+            .checked_sub(2)
+            .map(|index| path[index].id.as_str());
+        if index > 0 && headline_parent != parent {
+            return Err("Only sibling headlines can be merged".to_string());
+        }
+        parent = headline_parent;
+        sections.push(find_section(document, &lines, headline_id).ok_or_else(not_found)?);
+    }
+    sections.sort_by_key(|section| section.headline);
+    sections.dedup_by_key(|section| section.headline);
+    if sections.len() < 2 {
+        return Err("Choose at least two headlines to merge".to_string());
+    }
+
+    if sections[1..]
+        .iter()
+        .any(|section| body_start(&lines, section) > section.headline + 1)
+    {
+        return Err(
+            "Headlines with planning, properties or a logbook can only be merged into".to_string(),
+        );
+    }
+
+    let first = &sections[0];
+    let mut merged: Vec<String> = lines[first.headline..first.children].to_vec();
+    for other in &sections[1..] {
+        while merged.len() > 1 && merged.last().is_some_and(|line| line.trim().is_empty()) {
+            merged.pop();
+        }
+        if merged.len() > 1 {
+            merged.push(String::new());
+        }
+        let headline = lines[other.headline].trim_start_matches('*').trim();
+        merged.push(headline.to_string());
+        merged.extend_from_slice(&lines[other.headline + 1..other.children]);
+    }
+    for section in &sections {
+        merged.extend_from_slice(&lines[section.children..section.end]);
+    }
+
+    let mut output = Vec::with_capacity(lines.len());
+    let mut next = 0;
+    for (index, section) in sections.iter().enumerate() {
+        output.extend_from_slice(&lines[next..section.headline]);
+        if index == 0 {
+            output.append(&mut merged);
+        }
+        next = section.end;
+    }
+    output.extend_from_slice(&lines[next..]);
+    Ok(join_lines(output, document))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_split_headline() {
+        let doc = parse_org_document(
+            "* Inbox\n:PROPERTIES:\n:ID: inbox\n:END:\nCaptured on the train\n- [ ] Call the bank\n  about the card\n- [X] Buy milk\n- Read chapter 3\n** Old\n* Later\n",
+            Some("notes.org"),
+        )
+        .unwrap();
+        assert_eq!(
+            split_headline(&doc, "1", SplitMode::ListItems).unwrap(),
+            "* Inbox\n:PROPERTIES:\n:ID: inbox\n:END:\nCaptured on the train\n** Old\n* TODO Call the bank\nabout the card\n* DONE Buy milk\n* Read chapter 3\n* Later\n"
+        );
+
+        let doc = parse_org_document(
+            "* Notes\nSCHEDULED: <2024-03-01 Fri>\nFirst idea\n\nSecond idea\n#+BEGIN_QUOTE\nA quote\n\nin two parts\n#+END_QUOTE\n\n  Third idea\n",
+            Some("notes.org"),
+        )
+        .unwrap();
+        assert_eq!(
+            split_headline(&doc, "1", SplitMode::Paragraphs).unwrap(),
+            "* Notes\nSCHEDULED: <2024-03-01 Fri>\nFirst idea\n\n* Second idea\n#+BEGIN_QUOTE\nA quote\n\nin two parts\n#+END_QUOTE\n* Third idea\n"
+        );
+        assert!(split_headline(&doc, "1", SplitMode::ListItems).is_err());
+    }
+
+    #[test]
+    fn test_merge_headlines() {
+        let doc = parse_org_document(
+            "* Projects\n** Garden\nBuy seeds\n** Other\n** TODO Garden shed :diy:\nPaint it\n*** Buy paint\n** Pond\n:PROPERTIES:\n:ID: pond\n:END:\n** Fence\n:LOGBOOK:\nCLOCK: [2024-03-01 Fri 10:00]--[2024-03-01 Fri 11:00] =>  1:00\n:END:\n** Hedge\nDEADLINE: <2024-04-01 Mon>\n* Garden\n",
+            Some("notes.org"),
+        )
+        .unwrap();
+        let ids = |ids: &[&str]| -> Vec<String> { ids.iter().map(|id| id.to_string()).collect() };
+        assert_eq!(
+            merge_headlines(&doc, &ids(&["1.3", "1.1"])).unwrap(),
+            "* Projects\n** Garden\nBuy seeds\n\nTODO Garden shed :diy:\nPaint it\n*** Buy paint\n** Other\n** Pond\n:PROPERTIES:\n:ID: pond\n:END:\n** Fence\n:LOGBOOK:\nCLOCK: [2024-03-01 Fri 10:00]--[2024-03-01 Fri 11:00] =>  1:00\n:END:\n** Hedge\nDEADLINE: <2024-04-01 Mon>\n* Garden\n"
+        );
+        assert!(merge_headlines(&doc, &ids(&["1.1", "1.4"])).is_err());
+        assert!(merge_headlines(&doc, &ids(&["1.1", "1.5"])).is_err());
+        assert!(merge_headlines(&doc, &ids(&["1.1", "1.6"])).is_err());
+        assert!(merge_headlines(&doc, &ids(&["1.1", "2"])).is_err());
+        assert!(merge_headlines(&doc, &ids(&["1.1", "1.1"])).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::properties::parse_duration_minutes;
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::todo::TodoConfiguration;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Progress of a headline derived from the tasks below it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Rollup {
+    pub total_tasks: u32, // Tasks anywhere below the headline
+    pub done_tasks: u32,
+    pub percent_done: u32,                 // 0-100, rounded
+    pub remaining_effort_minutes: u32,     // Effort of open tasks, as org's {:} column summary
+    pub earliest_deadline: Option<String>, // YYYY-MM-DD, over the headline and open tasks below
+}
+
+fn is_open(headline: &OrgHeadline, config: &TodoConfiguration) -> bool {
+    headline
+        .get_todo_status(config)
+        .is_some_and(|status| status.is_active())
+}
+
+// Children's efforts replace the headline's own, as org's column view does, so a
+// parent estimate is not counted twice once its subtasks are estimated
+fn remaining_effort(headline: &OrgHeadline, config: &TodoConfiguration) -> Option<u32> {
+    let children: Vec<u32> = headline
+        .children
+        .iter()
+        .filter_map(|child| remaining_effort(child, config))
+        .collect();
+    if !children.is_empty() {
+        return Some(children.iter().sum());
+    }
+    if !is_open(headline, config) {
+        return None;
+    }
+    headline
+        .title
+        .get_property_ignore_case("Effort")
+        .and_then(parse_duration_minutes)
+}
+
+fn deadline(headline: &OrgHeadline) -> Option<NaiveDate> {
+    headline
+        .title
+        .planning
+        .as_ref()?
+        .deadline
+        .as_ref()?
+        .start_date()
+        .map(|date| date.to_naive_date())
+}
+
+/// Roll up the tasks below a headline, or None if there are none
+pub fn rollup(headline: &OrgHeadline, config: &TodoConfiguration) -> Option<Rollup> {
+    let descendants = flatten_headlines(&headline.children);
+    let statuses: Vec<bool> = descendants
+        .iter()
+        .filter_map(|task| task.get_todo_status(config))
+        .map(|status| status.is_closed())
+        .collect();
+    if statuses.is_empty() {
+        return None;
+    }
+
+    let total_tasks = statuses.len() as u32;
+    let done_tasks = statuses.iter().filter(|closed| **closed).count() as u32;
+    let earliest_deadline = std::iter::once(headline)
+        .chain(descendants)
+        .filter(|task| is_open(task, config))
+        .filter_map(deadline)
+        .min();
+
+    Some(Rollup {
+        total_tasks,
+        done_tasks,
+        percent_done: (done_tasks as f64 * 100.0 / total_tasks as f64).round() as u32,
+        remaining_effort_minutes: remaining_effort(headline, config).unwrap_or(0),
+        earliest_deadline: earliest_deadline.map(|date| date.format("%Y-%m-%d").to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_rollup() {
+        let doc = parse_org_document(
+            "* Launch\n:PROPERTIES:\n:Effort: 10:00\n:END:\n** DONE Design\n:PROPERTIES:\n:Effort: 2:00\n:END:\n** TODO Build\nDEADLINE: <2024-03-01 Fri>\n*** TODO Backend\n:PROPERTIES:\n:Effort: 3:00\n:END:\n*** TODO Frontend\nDEADLINE: <2024-02-15 Thu>\n:PROPERTIES:\n:Effort: 1:30\n:END:\n** Notes\n* Someday\n",
+            Some("goals.org"),
+        )
+        .unwrap();
+        let config = TodoConfiguration::default();
+
+        let launch = rollup(&doc.headlines[0], &config).unwrap();
+        assert_eq!((launch.total_tasks, launch.done_tasks), (4, 1));
+        assert_eq!(launch.percent_done, 25);
+        // Build's subtasks replace any estimate of its own; Design is done
+        assert_eq!(launch.remaining_effort_minutes, 270);
+        assert_eq!(launch.earliest_deadline.as_deref(), Some("2024-02-15"));
+
+        assert!(rollup(&doc.headlines[1], &config).is_none());
+    }
+}
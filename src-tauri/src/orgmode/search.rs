@@ -0,0 +1,444 @@
+use crate::jobs::CancellationToken;
+use crate::orgmode::datetime::DateRange;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::normalize::{is_cjk, normalize, normalize_char};
+use crate::orgmode::source::flatten_headlines;
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+
+/// Event emitted with `SearchResults` when a background search finishes
+pub const SEARCH_RESULTS_EVENT: &str = "search-results";
+
+/// Title terms count more than body terms when ranking
+const TITLE_WEIGHT: u32 = 3;
+
+/// A headline matching a full-text query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SearchHit {
+    pub document_id: String,
+    pub headline_id: String,
+    pub score: u32,
+}
+
+/// Hits of a background search job
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SearchResults {
+    pub job_id: u32,
+    pub hits: Vec<SearchHit>,
+}
+
+/// Inverted full-text index over headline titles and content
+/// Updated per document, so the watcher pipeline keeps it current
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    // term -> document ID -> headline ID -> weighted term count
+    postings: HashMap<String, HashMap<String, HashMap<String, u32>>>,
+    // document ID -> terms it contributed, for removal
+    document_terms: HashMap<String, HashSet<String>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a document in the index
+    /// Files marked `no-index` are only removed from it
+    pub fn index_document(&mut self, document: &OrgDocument) {
+        self.remove_document(&document.id);
+        if document.flags.no_index {
+            return;
+        }
+
+        let mut terms = HashSet::new();
+        for headline in flatten_headlines(&document.headlines) {
+            let weighted = tokenize(&headline.title.raw)
+                .into_iter()
+                .map(|term| (term, TITLE_WEIGHT))
+                .chain(
+                    tokenize(&headline.content)
+                        .into_iter()
+                        .map(|term| (term, 1)),
+                );
+
+            for (term, weight) in weighted {
+                *self
+                    .postings
+                    .entry(term.clone())
+                    .or_default()
+                    .entry(document.id.clone())
+                    .or_default()
+                    .entry(headline.id.clone())
+                    .or_default() += weight;
+                terms.insert(term);
+            }
+        }
+
+        self.document_terms.insert(document.id.clone(), terms);
+    }
+
+    /// Remove a document from the index
+    pub fn remove_document(&mut self, document_id: &str) {
+        let Some(terms) = self.document_terms.remove(document_id) else {
+            return;
+        };
+
+        for term in terms {
+            if let Some(documents) = self.postings.get_mut(&term) {
+                documents.remove(document_id);
+                if documents.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Number of indexed documents
+    pub fn document_count(&self) -> usize {
+        self.document_terms.len()
+    }
+
+    /// Find headlines containing all query terms, best matches first
+    /// The last query term also matches as a prefix, for search-as-you-type
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.search_cancellable(query, limit, &CancellationToken::new())
+            .unwrap_or_default()
+    }
+
+    /// Like `search`, but gives up with None once the token is cancelled
+    pub fn search_cancellable(
+        &self,
+        query: &str,
+        limit: usize,
+        token: &CancellationToken,
+    ) -> Option<Vec<SearchHit>> {
+        let terms = tokenize(query);
+        let Some((last, rest)) = terms.split_last() else {
+            return Some(Vec::new());
+        };
+
+        let mut scores: Option<HashMap<(&str, &str), u32>> = None;
+        for (index, term) in rest.iter().chain(std::iter::once(last)).enumerate() {
+            if token.is_cancelled() {
+                return None;
+            }
+            let prefix = index == terms.len() - 1;
+            let matches = self.matches_for(term, prefix);
+
+            scores = Some(match scores {
+                None => matches,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(key, score)| matches.get(&key).map(|s| (key, score + s)))
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|((document_id, headline_id), score)| SearchHit {
+                document_id: document_id.to_string(),
+                headline_id: headline_id.to_string(),
+                score,
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.document_id.cmp(&b.document_id))
+                .then_with(|| a.headline_id.cmp(&b.headline_id))
+        });
+        hits.truncate(limit);
+        Some(hits)
+    }
+
+    // Collect weighted counts per headline for a term (or term prefix)
+    fn matches_for(&self, term: &str, prefix: bool) -> HashMap<(&str, &str), u32> {
+        let mut matches = HashMap::new();
+
+        let postings: Vec<&HashMap<String, HashMap<String, u32>>> = if prefix {
+            self.postings
+                .iter()
+                .filter(|(indexed, _)| indexed.starts_with(term))
+                .map(|(_, documents)| documents)
+                .collect()
+        } else {
+            self.postings.get(term).into_iter().collect()
+        };
+
+        for documents in postings {
+            for (document_id, headlines) in documents {
+                for (headline_id, count) in headlines {
+                    *matches
+                        .entry((document_id.as_str(), headline_id.as_str()))
+                        .or_insert(0) += count;
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+/// A match as byte offsets into the searched text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct MatchSpan {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Matches within one headline's title and content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct HeadlineMatches {
+    pub headline_id: String,
+    pub title: Vec<MatchSpan>,   // Offsets into `title.raw`
+    pub content: Vec<MatchSpan>, // Offsets into `content`
+}
+
+// Non-overlapping occurrences of `needle`, ignoring case, diacritics and width
+fn find_spans(haystack: &str, needle: &str) -> Vec<MatchSpan> {
+    let needle: Vec<String> = needle.chars().map(normalize_char).collect();
+    let chars: Vec<(usize, String)> = haystack
+        .char_indices()
+        .map(|(offset, c)| (offset, normalize_char(c)))
+        .collect();
+    let mut spans = Vec::new();
+
+    let mut index = 0;
+    while index + needle.len() <= chars.len() {
+        let window = &chars[index..index + needle.len()];
+        if window.iter().zip(&needle).all(|((_, c), n)| c == n) {
+            let end = chars
+                .get(index + needle.len())
+                .map_or(haystack.len(), |(offset, _)| *offset);
+            spans.push(MatchSpan {
+                start: chars[index].0 as u32,
+                end: end as u32,
+            });
+            index += needle.len();
+        } else {
+            index += 1;
+        }
+    }
+
+    spans
+}
+
+/// Find a phrase in every headline of a document in file order, ignoring case and diacritics
+/// Needs the document's content, so load it first if it was evicted
+pub fn find_in_document(document: &OrgDocument, query: &str) -> Vec<HeadlineMatches> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    flatten_headlines(&document.headlines)
+        .into_iter()
+        .map(|headline| HeadlineMatches {
+            headline_id: headline.id.clone(),
+            title: find_spans(&headline.title.raw, query),
+            content: find_spans(&headline.content, query),
+        })
+        .filter(|matches| !matches.title.is_empty() || !matches.content.is_empty())
+        .collect()
+}
+
+/// Planning date restricted by a `deadline:`, `scheduled:` or `due:` query term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Deadline,
+    Scheduled,
+    Due, // The deadline or the scheduled date
+}
+
+/// A query term such as `due:this-quarter` or `deadline:2024-W23`, keeping hits whose
+/// planning date falls in the range
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateTerm {
+    pub field: DateField,
+    pub range: DateRange,
+}
+
+impl DateTerm {
+    /// Check a headline's planning dates against the range
+    pub fn matches(&self, headline: &OrgHeadline) -> bool {
+        let Some(planning) = headline.title.planning.as_deref() else {
+            return false;
+        };
+        let timestamps = match self.field {
+            DateField::Deadline => [planning.deadline.as_ref(), None],
+            DateField::Scheduled => [planning.scheduled.as_ref(), None],
+            DateField::Due => [planning.deadline.as_ref(), planning.scheduled.as_ref()],
+        };
+        timestamps
+            .into_iter()
+            .flatten()
+            .filter_map(|timestamp| timestamp.start_date())
+            .any(|date| self.range.contains(date))
+    }
+}
+
+/// Split the date terms off a query, returning the remaining text and the terms
+/// Ranges take any `DateRange::parse` expression, relative to `today`
+pub fn split_date_terms(
+    query: &str,
+    today: NaiveDate,
+    week_start: Weekday,
+) -> Result<(String, Vec<DateTerm>), String> {
+    let mut text = Vec::new();
+    let mut dates = Vec::new();
+    for word in query.split_whitespace() {
+        let term = word.split_once(':').and_then(|(field, range)| {
+            let field = match field.to_ascii_lowercase().as_str() {
+                "deadline" => DateField::Deadline,
+                "scheduled" => DateField::Scheduled,
+                "due" => DateField::Due,
+                _ => return None,
+            };
+            Some((field, range))
+        });
+        match term {
+            Some((field, range)) => dates.push(DateTerm {
+                field,
+                range: DateRange::parse(range, today, week_start)?,
+            }),
+            None => text.push(word),
+        }
+    }
+    Ok((text.join(" "), dates))
+}
+
+// Index runs of CJK text by overlapping character pairs, since words aren't space-separated
+fn push_bigrams(run: &mut Vec<char>, terms: &mut Vec<String>) {
+    if run.len() == 1 {
+        terms.push(run[0].to_string());
+    }
+    terms.extend(run.windows(2).map(|pair| pair.iter().collect::<String>()));
+    run.clear();
+}
+
+/// Split text into normalized alphanumeric terms
+/// CJK text becomes character bigrams, so "東京都" is found by "東京" and "京都"
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run = Vec::new();
+
+    for c in normalize(text).chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                terms.push(std::mem::take(&mut word));
+            }
+            cjk_run.push(c);
+        } else {
+            push_bigrams(&mut cjk_run, &mut terms);
+            if c.is_alphanumeric() {
+                word.push(c);
+            } else if !word.is_empty() {
+                terms.push(std::mem::take(&mut word));
+            }
+        }
+    }
+    push_bigrams(&mut cjk_run, &mut terms);
+    if !word.is_empty() {
+        terms.push(word);
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_search_index_updates() {
+        let mut index = SearchIndex::new();
+        let doc = parse_org_document(
+            "* Garden plans\nPlant tomatoes in spring.\n* Groceries\nBuy tomatoes and basil.\n",
+            Some("a.org"),
+        )
+        .unwrap();
+        index.index_document(&doc);
+
+        let hits = index.search("tomatoes", 10);
+        assert_eq!(hits.len(), 2);
+
+        // Title matches rank higher, last term matches as a prefix
+        let hits = index.search("gard", 10);
+        assert_eq!(hits[0].headline_id, "1");
+        let hits = index.search("buy bas", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].headline_id, "2");
+
+        // Reindexing replaces old terms
+        let doc = parse_org_document("* Garden plans\nPlant peppers.\n", Some("a.org")).unwrap();
+        index.index_document(&doc);
+        assert!(index.search("tomatoes", 10).is_empty());
+
+        let token = CancellationToken::new();
+        token.cancel();
+        assert_eq!(index.search_cancellable("garden", 10, &token), None);
+
+        index.remove_document("a.org");
+        assert!(index.search("garden", 10).is_empty());
+        assert_eq!(index.document_count(), 0);
+
+        // Files marked no-index are left out
+        let doc = parse_org_document("#+ORG_X: no-index\n* Garden plans\n", Some("a.org")).unwrap();
+        index.index_document(&doc);
+        assert_eq!(index.document_count(), 0);
+    }
+
+    #[test]
+    fn test_find_in_document() {
+        let doc = parse_org_document(
+            "* Tomato Plans\nPlant tomatoes; water the TOMATO bed.\n* Groceries\nBasil.\n* Größe\nMaße und GRÖSSE\n",
+            Some("a.org"),
+        )
+        .unwrap();
+
+        let matches = find_in_document(&doc, "tomato");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].title, vec![MatchSpan { start: 0, end: 6 }]);
+        let content = &doc.headlines[0].content;
+        let found: Vec<&str> = matches[0]
+            .content
+            .iter()
+            .map(|span| &content[span.start as usize..span.end as usize])
+            .collect();
+        assert_eq!(found, vec!["tomato", "TOMATO"]);
+
+        // Offsets are byte offsets, so multi-byte characters stay on boundaries
+        let matches = find_in_document(&doc, "öße");
+        assert_eq!(matches[0].title, vec![MatchSpan { start: 2, end: 7 }]);
+        assert!(find_in_document(&doc, "  ").is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_normalizes_and_splits_cjk() {
+        assert_eq!(tokenize("Café ＡＰＩ docs"), vec!["cafe", "api", "docs"]);
+        assert_eq!(
+            tokenize("東京都の会議 at 10時"),
+            vec!["東京", "京都", "都の", "の会", "会議", "at", "10", "時"]
+        );
+
+        let mut index = SearchIndex::new();
+        let doc = parse_org_document(
+            "* 東京都の会議\nRésumé review.\n* 京都旅行\n",
+            Some("notes.org"),
+        )
+        .unwrap();
+        index.index_document(&doc);
+
+        assert_eq!(index.search("東京", 10).len(), 1);
+        assert_eq!(index.search("京都", 10).len(), 2);
+        assert_eq!(index.search("resume", 10).len(), 1);
+        assert_eq!(find_in_document(&doc, "RESUME")[0].content.len(), 1);
+    }
+}
@@ -0,0 +1,406 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::html::{
+    escape_html, render_content_html, render_runs_html, HtmlSanitizeConfig,
+};
+use crate::orgmode::markup::parse_text_runs;
+use crate::orgmode::todo::TodoConfiguration;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// A boxed future, for the request handlers of local servers
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+// How often a shared page checks whether the document changed, in milliseconds
+const RELOAD_INTERVAL_MS: u32 = 2000;
+
+// How long a connection may take to send its request line and headers
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Longest request line and headers accepted, so bookmarks with a long selection still fit
+const MAX_REQUEST_HEAD: usize = 64 * 1024;
+
+/// The request line and headers of a request to a local server
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// The value of a header, whatever the case of its name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Parse the text before the blank line that ends the headers
+    fn parse(head: &str) -> Self {
+        let mut lines = head.lines();
+        let mut parts = lines.next().unwrap_or_default().split_whitespace();
+        Self {
+            method: parts.next().unwrap_or_default().to_string(),
+            target: parts.next().unwrap_or("/").to_string(),
+            headers: lines
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Whether a `Host` header names this machine's loopback address on `port`
+/// Pages that rebind their own domain to 127.0.0.1 send their domain here, so checking it
+/// keeps other sites from reading local servers.
+pub fn is_local_host(host: Option<&str>, port: u16) -> bool {
+    host.and_then(|host| host.rsplit_once(':'))
+        .is_some_and(|(name, host_port)| {
+            (name == "127.0.0.1" || name.eq_ignore_ascii_case("localhost"))
+                && host_port == port.to_string()
+        })
+}
+
+/// Where a shared document can be viewed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ShareSession {
+    pub document_id: String,
+    pub url: String, // http://127.0.0.1:<port>/
+}
+
+/// A rendered document page and the version it was rendered from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedPage {
+    pub html: String,
+    pub version: String, // The document's etag; the page reloads when it changes
+}
+
+const SHARE_STYLE: &str = "body{font-family:sans-serif;max-width:50em;margin:2em auto;padding:0 1em;font-size:1.15em;line-height:1.5;color:#222}\
+h1,h2,h3,h4,h5,h6{margin:1.2em 0 .4em}\
+.keyword{color:#c00;font-weight:bold}\
+.keyword.done{color:#080}\
+.priority{color:#888}\
+.tags{float:right;font-size:.7em;font-weight:normal;color:#666}\
+pre{background:#f4f4f4;padding:.6em;overflow-x:auto}\
+blockquote{border-left:3px solid #ccc;margin-left:0;padding-left:1em;color:#555}";
+
+fn headline_html(
+    headline: &OrgHeadline,
+    todo_config: &TodoConfiguration,
+    config: &HtmlSanitizeConfig,
+    html: &mut String,
+) {
+    // The document title is the only h1
+    let level = (headline.title.level as usize + 1).min(6);
+    html.push_str(&format!("<h{}>", level));
+    if let Some(keyword) = &headline.title.todo_keyword {
+        let closed = headline
+            .get_todo_status(todo_config)
+            .is_some_and(|status| status.is_closed());
+        let class = if closed { "keyword done" } else { "keyword" };
+        html.push_str(&format!(
+            "<span class=\"{}\">{}</span> ",
+            class,
+            escape_html(keyword)
+        ));
+    }
+    if let Some(priority) = headline.title.priority {
+        html.push_str(&format!("<span class=\"priority\">[#{}]</span> ", priority));
+    }
+    html.push_str(&render_runs_html(
+        &parse_text_runs(&headline.title.raw),
+        config,
+    ));
+    if !headline.title.tags.is_empty() {
+        let tags: Vec<String> = headline
+            .title
+            .tags
+            .iter()
+            .map(|tag| escape_html(tag))
+            .collect();
+        html.push_str(&format!(
+            " <span class=\"tags\">:{}:</span>",
+            tags.join(":")
+        ));
+    }
+    html.push_str(&format!("</h{}>\n", level));
+    html.push_str(&render_content_html(&headline.content, config));
+
+    for child in &headline.children {
+        headline_html(child, todo_config, config, html);
+    }
+}
+
+/// Render a document as a standalone, read-only page that reloads itself when the
+/// document changes
+/// Body content goes through the sanitizer allowlist, as in the app
+pub fn shared_page(document: &OrgDocument, config: &HtmlSanitizeConfig) -> SharedPage {
+    let title = escape_html(&document.title);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, SHARE_STYLE, title
+    );
+    let todo_config = document
+        .todo_config
+        .clone()
+        .unwrap_or_else(TodoConfiguration::default);
+    for headline in &document.headlines {
+        headline_html(headline, &todo_config, config, &mut html);
+    }
+    // The version is JSON-encoded so it is a valid script string whatever it contains
+    html.push_str(&format!(
+        "<script>\nconst version = {};\nsetInterval(async () => {{\n  try {{\n    const response = await fetch(\"/version\", {{ cache: \"no-store\" }});\n    if (response.ok && (await response.text()) !== version) location.reload();\n  }} catch (e) {{}}\n}}, {});\n</script>\n</body>\n</html>\n",
+        serde_json::Value::String(document.etag.clone()),
+        RELOAD_INTERVAL_MS
+    ));
+
+    SharedPage {
+        html,
+        version: document.etag.clone(),
+    }
+}
+
+pub(crate) fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Build the HTTP response for a request line's method and path
+/// Only the page (`/`) and its version (`/version`) are served, and only to GET
+pub fn respond(method: &str, path: &str, page: Option<&SharedPage>) -> String {
+    if method != "GET" {
+        return response("405 Method Not Allowed", "text/plain", "Read-only");
+    }
+    let path = path.split('?').next().unwrap_or(path);
+    match (path, page) {
+        (_, None) => response(
+            "404 Not Found",
+            "text/plain",
+            "The document is no longer available",
+        ),
+        ("/", Some(page)) => response("200 OK", "text/html; charset=utf-8", &page.html),
+        ("/version", Some(page)) => response("200 OK", "text/plain", &page.version),
+        _ => response("404 Not Found", "text/plain", "Not found"),
+    }
+}
+
+// The request line and headers, read until the blank line that ends them
+// Returns the status to answer with instead if they are longer than MAX_REQUEST_HEAD.
+// Connections that don't send them within READ_TIMEOUT are dropped, so idle clients don't
+// pile up.
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Result<Request, &'static str>> {
+    let read_head = async {
+        let mut head = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let text = String::from_utf8_lossy(&head);
+            if let Some(end) = text.find("\r\n\r\n").or_else(|| text.find("\n\n")) {
+                head.truncate(end);
+                break;
+            }
+            if head.len() > MAX_REQUEST_HEAD {
+                break;
+            }
+            let read = stream.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            head.extend_from_slice(&buffer[..read]);
+        }
+        Ok::<_, std::io::Error>(head)
+    };
+    let head = tokio::time::timeout(READ_TIMEOUT, read_head)
+        .await
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "No request received"))??;
+    if head.len() > MAX_REQUEST_HEAD {
+        return Ok(Err(if head.contains(&b'\n') {
+            "431 Request Header Fields Too Large"
+        } else {
+            "414 URI Too Long"
+        }));
+    }
+    Ok(Ok(Request::parse(&String::from_utf8_lossy(&head))))
+}
+
+// Accept connections until `stopped` fires, answering each request with `respond`
+// The returned task ends, closing the listener, once the server has stopped
+pub(crate) fn serve(
+    listener: TcpListener,
+    mut stopped: oneshot::Receiver<()>,
+    respond: Arc<dyn Fn(Request) -> BoxFuture<String> + Send + Sync>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut stopped => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((mut stream, _)) => {
+                        let respond = respond.clone();
+                        tokio::spawn(async move {
+                            let answered = async {
+                                let response = match read_request(&mut stream).await? {
+                                    Ok(request) => respond(request).await,
+                                    Err(status) => {
+                                        response(status, "text/plain", "The request is too long")
+                                    }
+                                };
+                                stream.write_all(response.as_bytes()).await?;
+                                stream.shutdown().await
+                            };
+                            if let Err(e) = answered.await {
+                                eprintln!("Failed to answer local request: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => eprintln!("Failed to accept local connection: {}", e),
+                },
+            }
+        }
+    })
+}
+
+/// A running localhost server sharing one document
+pub struct ShareServer {
+    pub session: ShareSession,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl ShareServer {
+    /// Serve the page `render` returns on a free port of 127.0.0.1
+    /// `render` runs for every request, so the page always shows the current document
+    pub async fn start(
+        document_id: &str,
+        render: impl Fn() -> Option<SharedPage> + Send + Sync + 'static,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let port = listener.local_addr()?.port();
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        serve(
+            listener,
+            stopped,
+            Arc::new(move |request: Request| -> BoxFuture<String> {
+                let response = if is_local_host(request.header("Host"), port) {
+                    respond(&request.method, &request.target, render().as_ref())
+                } else {
+                    response("403 Forbidden", "text/plain", "Unknown host")
+                };
+                Box::pin(async move { response })
+            }),
+        );
+
+        Ok(Self {
+            session: ShareSession {
+                document_id: document_id.to_string(),
+                url: format!("http://127.0.0.1:{}/", port),
+            },
+            shutdown,
+        })
+    }
+
+    /// Stop serving; open pages keep their last content
+    pub fn stop(self) {
+        // The server task may already be gone, in which case there is nothing to stop
+        let _ = self.shutdown.send(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    fn page() -> SharedPage {
+        let doc = parse_org_document(
+            "#+TITLE: Standup\n* TODO [#A] Review PR & docs :team:\nNotes with *bold* text\n** DONE Deploy\n",
+            Some("standup.org"),
+        )
+        .unwrap();
+        shared_page(&doc, &HtmlSanitizeConfig::default())
+    }
+
+    #[test]
+    fn test_shared_page() {
+        let page = page();
+        assert!(page.html.contains("<title>Standup</title>"));
+        assert!(page.html.contains(
+            "<h2><span class=\"keyword\">TODO</span> <span class=\"priority\">[#A]</span> Review PR &amp; docs <span class=\"tags\">:team:</span></h2>"
+        ));
+        assert!(page.html.contains("<strong>bold</strong>"));
+        assert!(page
+            .html
+            .contains("<h3><span class=\"keyword done\">DONE</span> Deploy</h3>"));
+        assert!(page
+            .html
+            .contains(&format!("const version = \"{}\"", page.version)));
+    }
+
+    #[test]
+    fn test_is_local_host() {
+        assert!(is_local_host(Some("127.0.0.1:8123"), 8123));
+        assert!(is_local_host(Some("LOCALHOST:8123"), 8123));
+        assert!(!is_local_host(Some("localhost:8124"), 8123));
+        assert!(!is_local_host(Some("evil.example:8123"), 8123));
+        assert!(!is_local_host(Some("127.0.0.1"), 8123));
+        assert!(!is_local_host(None, 8123));
+    }
+
+    #[test]
+    fn test_respond() {
+        let page = page();
+        assert!(respond("GET", "/", Some(&page)).starts_with("HTTP/1.1 200 OK"));
+        assert!(respond("GET", "/version?t=1", Some(&page)).ends_with(&page.version));
+        assert!(respond("GET", "/../secret", Some(&page)).starts_with("HTTP/1.1 404"));
+        assert!(respond("GET", "/", None).starts_with("HTTP/1.1 404"));
+        assert!(respond("POST", "/", Some(&page)).starts_with("HTTP/1.1 405"));
+    }
+
+    #[tokio::test]
+    async fn test_share_server() {
+        let shared = page();
+        let server = ShareServer::start("standup", move || Some(shared.clone()))
+            .await
+            .unwrap();
+        let address = server
+            .session
+            .url
+            .trim_start_matches("http://")
+            .trim_end_matches('/');
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(format!("GET /version HTTP/1.1\r\nHost: {}\r\n\r\n", address).as_bytes())
+            .await
+            .unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).await.unwrap();
+        assert!(reply.starts_with("HTTP/1.1 200 OK"));
+        assert!(reply.ends_with(&page().version));
+
+        // Pages on other domains rebound to 127.0.0.1 send their own host and are refused
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: attacker.example:80\r\n\r\n")
+            .await
+            .unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).await.unwrap();
+        assert!(reply.starts_with("HTTP/1.1 403"));
+
+        server.stop();
+    }
+}
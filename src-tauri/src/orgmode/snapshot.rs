@@ -0,0 +1,160 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory holding snapshots, inside the app data directory
+pub const SNAPSHOT_DIR_NAME: &str = "snapshots";
+
+// Manifest in each snapshot directory; file copies sit next to it as `files/<index>`
+const MANIFEST_FILE_NAME: &str = "snapshot.json";
+
+/// Copies of all monitored files taken at one moment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct Snapshot {
+    pub id: String, // Timestamp the snapshot was taken, like `20240301-120000`
+    pub label: String,
+    pub created_at: String, // RFC 3339
+    pub files: Vec<String>, // Paths of the files copied
+}
+
+// Snapshot ids name directories, so only the characters of generated ids are accepted
+fn snapshot_dir(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err(format!("Invalid snapshot id: {}", id));
+    }
+    Ok(dir.join(id))
+}
+
+fn read_manifest(path: &Path) -> Result<Snapshot, String> {
+    let json = fs::read_to_string(path.join(MANIFEST_FILE_NAME))
+        .map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))
+}
+
+/// Copy `paths` into a new snapshot directory under `dir`
+/// Files that no longer exist are left out.
+pub fn create_snapshot(
+    dir: &Path,
+    label: &str,
+    paths: &[String],
+    now: DateTime<Local>,
+) -> Result<Snapshot, String> {
+    // Snapshots taken in the same second get a counter
+    let stamp = now.format("%Y%m%d-%H%M%S").to_string();
+    let mut id = stamp.clone();
+    let mut count = 1;
+    while dir.join(&id).exists() {
+        count += 1;
+        id = format!("{}-{}", stamp, count);
+    }
+    let path = dir.join(&id);
+    let files_dir = path.join("files");
+    fs::create_dir_all(&files_dir)
+        .map_err(|e| format!("Failed to create {}: {}", files_dir.display(), e))?;
+
+    let mut files = Vec::new();
+    for source in paths {
+        let target = files_dir.join(files.len().to_string());
+        match fs::copy(source, &target) {
+            Ok(_) => files.push(source.clone()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to copy file {}: {}", source, e)),
+        }
+    }
+
+    let snapshot = Snapshot {
+        id,
+        label: label.trim().to_string(),
+        created_at: now.to_rfc3339(),
+        files,
+    };
+    let json = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+    // The manifest is written last, so a snapshot that failed halfway is never listed
+    fs::write(path.join(MANIFEST_FILE_NAME), json)
+        .map_err(|e| format!("Failed to write snapshot {}: {}", path.display(), e))?;
+    Ok(snapshot)
+}
+
+/// List the snapshots under `dir`, most recent first, treating a missing directory as empty
+pub fn list_snapshots(dir: &Path) -> Result<Vec<Snapshot>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .filter_map(|entry| read_manifest(&entry.ok()?.path()).ok())
+        .collect();
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    Ok(snapshots)
+}
+
+/// The files in a snapshot with the content they had when it was taken
+pub fn snapshot_files(dir: &Path, id: &str) -> Result<Vec<(String, String)>, String> {
+    let path = snapshot_dir(dir, id)?;
+    let snapshot = read_manifest(&path)?;
+    snapshot
+        .files
+        .into_iter()
+        .enumerate()
+        .map(|(index, file)| {
+            let copy = path.join("files").join(index.to_string());
+            fs::read_to_string(&copy)
+                .map(|content| (file, content))
+                .map_err(|e| format!("Failed to read {}: {}", copy.display(), e))
+        })
+        .collect()
+}
+
+/// Delete a snapshot and its file copies
+pub fn delete_snapshot(dir: &Path, id: &str) -> Result<(), String> {
+    let path = snapshot_dir(dir, id)?;
+    fs::remove_dir_all(&path).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let notes = tempdir().unwrap();
+        let todo = notes.path().join("todo.org").to_string_lossy().to_string();
+        let gone = notes.path().join("gone.org").to_string_lossy().to_string();
+        fs::write(&todo, "* TODO Report\n").unwrap();
+
+        let dir = tempdir().unwrap();
+        let snapshots = dir.path().join(SNAPSHOT_DIR_NAME);
+        assert!(list_snapshots(&snapshots).unwrap().is_empty());
+
+        let now = Local.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let first =
+            create_snapshot(&snapshots, " Before cleanup ", &[todo.clone(), gone], now).unwrap();
+        assert_eq!(first.id, "20240301-120000");
+        assert_eq!(first.label, "Before cleanup");
+        assert_eq!(first.files, vec![todo.clone()]);
+
+        fs::write(&todo, "* DONE Report\n").unwrap();
+        let second = create_snapshot(&snapshots, "", std::slice::from_ref(&todo), now).unwrap();
+        assert_eq!(second.id, "20240301-120000-2");
+        assert_eq!(
+            list_snapshots(&snapshots).unwrap(),
+            vec![second.clone(), first.clone()]
+        );
+
+        assert_eq!(
+            snapshot_files(&snapshots, &first.id).unwrap(),
+            vec![(todo, "* TODO Report\n".to_string())]
+        );
+        assert!(snapshot_files(&snapshots, "../notes").is_err());
+
+        delete_snapshot(&snapshots, &second.id).unwrap();
+        assert_eq!(list_snapshots(&snapshots).unwrap(), vec![first]);
+    }
+}
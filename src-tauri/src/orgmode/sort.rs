@@ -0,0 +1,271 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::properties::{sort_by_property, PropertyType, TypedValue};
+use crate::orgmode::subtree::subtree_lines;
+use crate::orgmode::todo::TodoConfiguration;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cmp::Ordering;
+
+/// What to sort a headline's children by, like the keys of org-sort
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum SortKey {
+    Title,            // Alphabetically, ignoring case
+    Todo,             // In TODO sequence order, headlines without a keyword last
+    Priority,         // Highest first, headlines without a priority counted as B
+    Deadline,         // Earliest first, headlines without a deadline last
+    Property(String), // By the headline's own value, parsed with the property's type
+}
+
+/// One key of a listing order, like "priority, then deadline"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct SortRule {
+    pub key: SortKey,
+    pub descending: bool, // Reverses the key's order; missing deadlines and values stay last
+}
+
+// Org's default priority, used for headlines that have none
+const DEFAULT_PRIORITY: char = 'B';
+
+fn deadline(headline: &OrgHeadline) -> Option<NaiveDateTime> {
+    headline
+        .title
+        .planning
+        .as_ref()
+        .and_then(|planning| planning.deadline.as_ref())
+        .and_then(|deadline| deadline.start_date())
+        .map(|date| date.to_naive_datetime())
+}
+
+// Order present values by `compare`, reversed if `descending`, with missing values last
+fn present_first<T>(
+    a: Option<T>,
+    b: Option<T>,
+    descending: bool,
+    compare: impl Fn(&T, &T) -> Ordering,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => compare(&b, &a),
+        (Some(a), Some(b)) => compare(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn compare(a: &OrgHeadline, b: &OrgHeadline, key: &SortKey, keywords: &[&str]) -> Ordering {
+    match key {
+        SortKey::Title => a.title.raw.to_lowercase().cmp(&b.title.raw.to_lowercase()),
+        SortKey::Todo => {
+            let position = |headline: &OrgHeadline| {
+                headline
+                    .title
+                    .todo_keyword
+                    .as_deref()
+                    .and_then(|keyword| keywords.iter().position(|other| *other == keyword))
+                    .unwrap_or(keywords.len())
+            };
+            position(a).cmp(&position(b))
+        }
+        SortKey::Priority => {
+            let priority =
+                |headline: &OrgHeadline| headline.title.priority.unwrap_or(DEFAULT_PRIORITY);
+            priority(a).cmp(&priority(b))
+        }
+        SortKey::Deadline => present_first(deadline(a), deadline(b), false, Ord::cmp),
+        // Property values need their declared type, so `sort_children` sorts them
+        SortKey::Property(_) => Ordering::Equal,
+    }
+}
+
+/// Order headlines by `rules`, each settling the ties of the one before
+/// The sort is stable, so headlines equal under every rule keep their order. Property
+/// values are parsed with the type `property_type` gives for the property.
+pub fn sort_headlines(
+    headlines: &mut [&OrgHeadline],
+    rules: &[SortRule],
+    config: &TodoConfiguration,
+    property_type: impl Fn(&str) -> PropertyType,
+) {
+    let keywords = config.keywords();
+    let types: Vec<PropertyType> = rules
+        .iter()
+        .map(|rule| match &rule.key {
+            SortKey::Property(property) => property_type(property),
+            _ => PropertyType::String,
+        })
+        .collect();
+
+    headlines.sort_by(|a, b| {
+        rules
+            .iter()
+            .zip(&types)
+            .map(|(rule, property_type)| match &rule.key {
+                SortKey::Deadline => {
+                    present_first(deadline(a), deadline(b), rule.descending, Ord::cmp)
+                }
+                SortKey::Property(property) => {
+                    let value = |headline: &OrgHeadline| {
+                        let raw = headline.title.get_property_ignore_case(property)?;
+                        property_type.parse(raw).ok()
+                    };
+                    present_first(value(a), value(b), rule.descending, TypedValue::compare)
+                }
+                key if rule.descending => compare(a, b, key, &keywords).reverse(),
+                key => compare(a, b, key, &keywords),
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Reorder the children of a headline, moving each child's whole subtree
+///
+/// `document.content` must be the text the headlines were parsed from. The sort is stable,
+/// the parent's own section stays in place and `property_type` is only used for
+/// `SortKey::Property`. Returns None if the headline is not found.
+pub fn sort_children(
+    document: &OrgDocument,
+    headline_id: &str,
+    key: &SortKey,
+    config: &TodoConfiguration,
+    property_type: PropertyType,
+) -> Option<String> {
+    let parent = document.find_headline(headline_id)?;
+    let parent_lines = subtree_lines(document, headline_id)?;
+    let lines: Vec<&str> = document.content.split_inclusive('\n').collect();
+
+    let mut children = Vec::with_capacity(parent.children.len());
+    for child in &parent.children {
+        let range = subtree_lines(document, &child.id)?;
+        let mut text: String = lines[range.clone()].concat();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        children.push((child, range, text));
+    }
+    let Some(first) = children.first().map(|(_, range, _)| range.start) else {
+        return Some(document.content.clone());
+    };
+
+    let sorted = match key {
+        SortKey::Property(property) => {
+            sort_by_property(children, property_type, false, |(child, _, _)| {
+                child
+                    .title
+                    .get_property_ignore_case(property)
+                    .map(str::to_string)
+            })
+        }
+        _ => {
+            let keywords = config.keywords();
+            children.sort_by(|(a, _, _), (b, _, _)| compare(a, b, key, &keywords));
+            children
+        }
+    };
+
+    let mut output: String = lines[..first].concat();
+    for (_, _, text) in sorted {
+        output.push_str(&text);
+    }
+    output.push_str(&lines[parent_lines.end..].concat());
+    if !document.content.ends_with('\n') && output.ends_with('\n') {
+        output.pop();
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const CONTENT: &str = "#+TODO: TODO WAITING | DONE\n* Tasks\nIntro\n** TODO [#C] write tests\nDEADLINE: <2024-03-09 Sat>\n:PROPERTIES:\n:Size: 10\n:END:\n*** Note\n** DONE Archive\n:PROPERTIES:\n:Size: 9\n:END:\n** WAITING [#A] Budget\nDEADLINE: <2024-03-05 Tue>\n* Other";
+
+    fn titles(content: &str) -> Vec<&str> {
+        content
+            .lines()
+            .filter(|line| line.starts_with("**"))
+            .collect()
+    }
+
+    fn sorted(key: SortKey, property_type: PropertyType) -> String {
+        let doc = parse_org_document(CONTENT, Some("tasks.org")).unwrap();
+        let config = TodoConfiguration::default();
+        let content = sort_children(&doc, "1", &key, &config, property_type).unwrap();
+        assert!(
+            content.starts_with("#+TODO: TODO WAITING | DONE\n* Tasks\nIntro\n")
+                && content.ends_with("\n* Other")
+        );
+        content
+    }
+
+    #[test]
+    fn test_sort_children() {
+        let by_title = sorted(SortKey::Title, PropertyType::String);
+        assert_eq!(
+            titles(&by_title),
+            vec![
+                "** DONE Archive",
+                "** WAITING [#A] Budget",
+                "** TODO [#C] write tests",
+                "*** Note"
+            ]
+        );
+        // Subtrees move whole
+        assert!(by_title.contains("** TODO [#C] write tests\nDEADLINE: <2024-03-09 Sat>\n:PROPERTIES:\n:Size: 10\n:END:\n*** Note\n* Other"));
+
+        let by_todo = sorted(SortKey::Todo, PropertyType::String);
+        assert_eq!(titles(&by_todo)[0], "** TODO [#C] write tests");
+        assert_eq!(titles(&by_todo)[3], "** DONE Archive");
+
+        // Archive has no priority and counts as B
+        let by_priority = sorted(SortKey::Priority, PropertyType::String);
+        assert_eq!(titles(&by_priority)[1], "** DONE Archive");
+
+        let by_deadline = sorted(SortKey::Deadline, PropertyType::String);
+        assert_eq!(titles(&by_deadline)[0], "** WAITING [#A] Budget");
+        assert_eq!(titles(&by_deadline)[3], "** DONE Archive");
+
+        // Numbers sort by value, missing values last
+        let by_size = sorted(SortKey::Property("Size".to_string()), PropertyType::Number);
+        assert_eq!(titles(&by_size)[0], "** DONE Archive");
+        assert_eq!(titles(&by_size)[3], "** WAITING [#A] Budget");
+    }
+
+    #[test]
+    fn test_sort_headlines() {
+        let config = TodoConfiguration::default();
+        let rule = |key: SortKey, descending: bool| SortRule { key, descending };
+        let order = |content: &str, rules: &[SortRule]| -> Vec<String> {
+            let doc = parse_org_document(content, Some("tasks.org")).unwrap();
+            let mut headlines: Vec<&OrgHeadline> = doc.headlines[0].children.iter().collect();
+            sort_headlines(&mut headlines, rules, &config, |_| PropertyType::Number);
+            headlines
+                .iter()
+                .map(|headline| headline.title.raw.clone())
+                .collect()
+        };
+
+        // Descending keeps headlines without a deadline or value last
+        let latest_first = [rule(SortKey::Deadline, true)];
+        assert_eq!(
+            order(CONTENT, &latest_first),
+            vec!["write tests", "Budget", "Archive"]
+        );
+        let largest_first = [rule(SortKey::Property("Size".to_string()), true)];
+        assert_eq!(
+            order(CONTENT, &largest_first),
+            vec!["write tests", "Archive", "Budget"]
+        );
+
+        // Later rules settle ties, and headlines equal under all rules keep file order
+        let content = "* Tasks\n** TODO [#B] Plan\n** DONE [#A] Ship\n** TODO [#A] Test\n** Notes\n** Ideas\n";
+        let rules = [rule(SortKey::Todo, false), rule(SortKey::Priority, false)];
+        assert_eq!(
+            order(content, &rules),
+            vec!["Test", "Plan", "Ship", "Notes", "Ideas"]
+        );
+    }
+}
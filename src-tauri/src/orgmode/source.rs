@@ -0,0 +1,124 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use std::collections::HashMap;
+
+/// A line of document source, attributed to the headline whose section it belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLine<'a> {
+    pub number: usize, // 1-based line number
+    pub text: &'a str,
+    pub headline_id: Option<&'a str>, // None for the preamble before the first headline
+}
+
+/// Get the level of a headline line (`** Title` => 2), or None for other lines
+pub fn headline_level(line: &str) -> Option<usize> {
+    let stars = line.chars().take_while(|&c| c == '*').count();
+    if stars == 0 {
+        return None;
+    }
+
+    match line[stars..].chars().next() {
+        Some(' ') | Some('\t') | None => Some(stars),
+        _ => None,
+    }
+}
+
+/// Flatten headlines in file (pre-order) order
+pub fn flatten_headlines(headlines: &[OrgHeadline]) -> Vec<&OrgHeadline> {
+    fn visit<'a>(headlines: &'a [OrgHeadline], out: &mut Vec<&'a OrgHeadline>) {
+        for headline in headlines {
+            out.push(headline);
+            visit(&headline.children, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(headlines, &mut out);
+    out
+}
+
+/// Split the document source into lines attributed to headlines
+///
+/// Headline lines are matched to parsed headlines in file order, so this
+/// relies on the document content being the text the headlines were parsed from.
+pub fn source_lines(document: &OrgDocument) -> Vec<SourceLine<'_>> {
+    let mut headlines = flatten_headlines(&document.headlines).into_iter();
+    let mut current: Option<&str> = None;
+
+    document
+        .content
+        .lines()
+        .enumerate()
+        .map(|(index, text)| {
+            if headline_level(text).is_some() {
+                current = headlines.next().map(|headline| headline.id.as_str());
+            }
+            SourceLine {
+                number: index + 1,
+                text,
+                headline_id: current,
+            }
+        })
+        .collect()
+}
+
+/// Collect the source text of each headline's own section
+/// The section is the headline line with its planning, drawers, and body, without child headlines
+pub fn headline_sections(document: &OrgDocument) -> HashMap<String, String> {
+    let mut sections: HashMap<String, String> = HashMap::new();
+
+    for line in source_lines(document) {
+        if let Some(id) = line.headline_id {
+            let section = sections.entry(id.to_string()).or_default();
+            section.push_str(line.text);
+            section.push('\n');
+        }
+    }
+
+    sections
+}
+
+/// Map each headline ID to the 1-based line number of its headline line
+pub fn headline_line_numbers(document: &OrgDocument) -> HashMap<String, usize> {
+    let mut numbers = HashMap::new();
+
+    for line in source_lines(document) {
+        if let (Some(_), Some(id)) = (headline_level(line.text), line.headline_id) {
+            numbers.insert(id.to_string(), line.number);
+        }
+    }
+
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_headline_level() {
+        assert_eq!(headline_level("* Top"), Some(1));
+        assert_eq!(headline_level("*** TODO Deep"), Some(3));
+        assert_eq!(headline_level("*"), Some(1));
+        assert_eq!(headline_level("*bold* text"), None);
+        assert_eq!(headline_level("  * list item"), None);
+    }
+
+    #[test]
+    fn test_source_lines_attribution() {
+        let content = "#+TITLE: Lines\n\n* First\nbody\n** Child\n* Second\n";
+        let doc = parse_org_document(content, Some("lines.org")).unwrap();
+        let lines = source_lines(&doc);
+
+        assert_eq!(lines[0].headline_id, None);
+        assert_eq!(lines[3].headline_id, Some("1"));
+        assert_eq!(lines[4].headline_id, Some("1.1"));
+        assert_eq!(lines[5].headline_id, Some("2"));
+
+        let numbers = headline_line_numbers(&doc);
+        assert_eq!(numbers.get("1"), Some(&3));
+        assert_eq!(numbers.get("1.1"), Some(&5));
+        assert_eq!(numbers.get("2"), Some(&6));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::keywords::set_headline_priority;
+use crate::orgmode::logbook::{clock_in, has_running_clock};
+use crate::orgmode::reschedule::{set_planning_date, PlanningField};
+use crate::orgmode::source::{headline_line_numbers, headline_sections};
+use crate::orgmode::todo::CycleDirection;
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::ops::RangeInclusive;
+
+// org-mode's default `org-priority-highest` and `org-priority-lowest`
+const HIGHEST_PRIORITY: char = 'A';
+const LOWEST_PRIORITY: char = 'C';
+
+/// An action on a headline bound to a single key, like org-mode's speed commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum HeadlineAction {
+    CycleState(CycleDirection), // Next or previous TODO keyword in its sequence
+    SetPriority(Option<char>),  // A priority cookie like `[#A]`, removed with None
+    ScheduleToday,
+    ScheduleTomorrow,
+    Archive, // Move the subtree to the file's archive
+    ClockIn,
+}
+
+/// The priorities a file allows, from `#+PRIORITIES: <highest> <lowest> <default>`, or A
+/// to C as in org-mode
+pub fn priority_range(document: &OrgDocument) -> RangeInclusive<char> {
+    let declared = document
+        .keywords
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("PRIORITIES"))
+        .and_then(|(_, value)| {
+            let mut priorities = value.split_whitespace().map(|part| {
+                let mut chars = part.chars();
+                chars.next().filter(|_| chars.next().is_none())
+            });
+            let (highest, lowest) = (priorities.next()??, priorities.next()??);
+            let letters = highest.is_ascii_uppercase() && lowest.is_ascii_uppercase();
+            let digits = highest.is_ascii_digit() && lowest.is_ascii_digit();
+            ((letters || digits) && highest <= lowest).then_some(highest..=lowest)
+        });
+    declared.unwrap_or(HIGHEST_PRIORITY..=LOWEST_PRIORITY)
+}
+
+/// Apply an action that only edits the headline's own section to the document's content
+/// Scheduling uses the day of `now`, named in `language`. Cycling the TODO state and
+/// archiving change more than the section and are refused here.
+pub fn apply_section_action(
+    document: &OrgDocument,
+    headline_id: &str,
+    action: HeadlineAction,
+    now: NaiveDateTime,
+    language: &str,
+) -> Result<String, String> {
+    let not_found = || format!("Headline not found: {}", headline_id);
+    let headline = document.find_headline(headline_id).ok_or_else(not_found)?;
+    let line = headline_line_numbers(document)
+        .get(headline_id)
+        .copied()
+        .ok_or_else(not_found)?;
+    let content = &document.content;
+
+    let edited = match action {
+        HeadlineAction::SetPriority(priority) => {
+            let range = priority_range(document);
+            if let Some(priority) = priority.filter(|priority| !range.contains(priority)) {
+                return Err(format!(
+                    "Invalid priority: {} (expected {} to {})",
+                    priority,
+                    range.start(),
+                    range.end()
+                ));
+            }
+            let keyword = headline.title.todo_keyword.as_deref();
+            set_headline_priority(content, line, keyword, priority)
+        }
+        HeadlineAction::ScheduleToday | HeadlineAction::ScheduleTomorrow => {
+            let mut date = now.date();
+            if action == HeadlineAction::ScheduleTomorrow {
+                date += Duration::days(1);
+            }
+            set_planning_date(content, line, PlanningField::Scheduled, date, language)
+        }
+        HeadlineAction::ClockIn => {
+            if headline_sections(document)
+                .get(headline_id)
+                .is_some_and(|section| has_running_clock(section))
+            {
+                return Err("The clock is already running".to_string());
+            }
+            clock_in(content, line, now)
+        }
+        HeadlineAction::CycleState(_) | HeadlineAction::Archive => {
+            return Err(format!(
+                "{:?} changes more than the headline's section",
+                action
+            ));
+        }
+    };
+    edited.ok_or_else(not_found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use chrono::NaiveDate;
+
+    const CONTENT: &str = "* TODO [#B] Write report\nDraft first\n* Call Bob\n";
+
+    // Apply an action to the top-level headline at `index`
+    fn apply(content: &str, index: usize, action: HeadlineAction) -> Result<String, String> {
+        let doc = parse_org_document(content, Some("tasks.org")).unwrap();
+        let now = NaiveDate::from_ymd_opt(2024, 3, 4)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        apply_section_action(&doc, &doc.headlines[index].id, action, now, "en")
+    }
+
+    #[test]
+    fn test_apply_section_action() {
+        assert_eq!(
+            apply(CONTENT, 0, HeadlineAction::SetPriority(Some('A'))).unwrap(),
+            "* TODO [#A] Write report\nDraft first\n* Call Bob\n"
+        );
+        assert_eq!(
+            apply(CONTENT, 0, HeadlineAction::SetPriority(None)).unwrap(),
+            "* TODO Write report\nDraft first\n* Call Bob\n"
+        );
+        // Only A to C, unless the file declares its own range
+        assert!(apply(CONTENT, 1, HeadlineAction::SetPriority(Some('D'))).is_err());
+        assert!(apply(CONTENT, 1, HeadlineAction::SetPriority(Some('1'))).is_err());
+        let numeric = format!("#+PRIORITIES: 1 5 3\n{}", CONTENT);
+        assert!(apply(&numeric, 1, HeadlineAction::SetPriority(Some('5'))).is_ok());
+        assert!(apply(&numeric, 1, HeadlineAction::SetPriority(Some('A'))).is_err());
+
+        assert_eq!(
+            apply(CONTENT, 1, HeadlineAction::ScheduleTomorrow).unwrap(),
+            "* TODO [#B] Write report\nDraft first\n* Call Bob\nSCHEDULED: <2024-03-05 Tue>\n"
+        );
+
+        let clocked = apply(CONTENT, 1, HeadlineAction::ClockIn).unwrap();
+        assert!(clocked.contains("CLOCK: [2024-03-04 Mon 09:30]"));
+        assert_eq!(
+            apply(&clocked, 1, HeadlineAction::ClockIn),
+            Err("The clock is already running".to_string())
+        );
+
+        assert!(apply(CONTENT, 0, HeadlineAction::Archive).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::orgmode::logbook::{add_log_note, set_closed};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// What is recorded when a task is closed, from `logdone`, `lognotedone` and `nologdone`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum LogDone {
+    #[default]
+    Nothing,
+    Time, // A CLOSED timestamp
+    Note, // A CLOSED timestamp and a closing note
+}
+
+/// Initial outline visibility from `overview`, `content`, `showall` and `showeverything`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum StartupVisibility {
+    Overview,
+    Content,
+    ShowAll,
+    ShowEverything,
+}
+
+/// Options from a file's `#+STARTUP:` lines
+/// Later options override earlier ones and unknown options are ignored, as in org-mode
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct StartupOptions {
+    pub log_done: LogDone,
+    pub log_into_drawer: bool, // `logdrawer`: notes go into the LOGBOOK drawer
+    pub visibility: Option<StartupVisibility>,
+    pub ordered: bool,       // `ordered`: sibling tasks are closed in order
+    pub align: Option<bool>, // `align` / `noalign`: whether tables are aligned on open
+}
+
+impl StartupOptions {
+    /// Collect the options of every `#+STARTUP:` line in a file
+    pub fn from_content(content: &str) -> Self {
+        let mut options = Self::default();
+        let values = content.lines().filter_map(|line| {
+            let rest = line.trim_start().strip_prefix("#+")?;
+            let (key, value) = rest.split_once(':')?;
+            key.eq_ignore_ascii_case("STARTUP").then_some(value)
+        });
+
+        for option in values.flat_map(str::split_whitespace) {
+            match option.to_ascii_lowercase().as_str() {
+                "logdone" => options.log_done = LogDone::Time,
+                "lognotedone" => options.log_done = LogDone::Note,
+                "nologdone" => options.log_done = LogDone::Nothing,
+                "logdrawer" => options.log_into_drawer = true,
+                "nologdrawer" => options.log_into_drawer = false,
+                "overview" | "fold" => options.visibility = Some(StartupVisibility::Overview),
+                "content" => options.visibility = Some(StartupVisibility::Content),
+                "showall" | "nofold" => options.visibility = Some(StartupVisibility::ShowAll),
+                "showeverything" => options.visibility = Some(StartupVisibility::ShowEverything),
+                "ordered" => options.ordered = true,
+                "align" => options.align = Some(true),
+                "noalign" => options.align = Some(false),
+                _ => {}
+            }
+        }
+        options
+    }
+
+    /// Record the closing of the headline on a 1-based line as `log_done` asks
+    /// `note` is only written with `lognotedone`. Returns None if the line is not a headline
+    pub fn log_closing(
+        &self,
+        content: &str,
+        headline_line: usize,
+        now: NaiveDateTime,
+        note: Option<&str>,
+    ) -> Option<String> {
+        match self.log_done {
+            LogDone::Nothing => Some(content.to_string()),
+            LogDone::Time => set_closed(content, headline_line, Some(now)),
+            LogDone::Note => {
+                let closed = set_closed(content, headline_line, Some(now))?;
+                let heading = format!("CLOSING NOTE {}", now.format("[%Y-%m-%d %a %H:%M]"));
+                add_log_note(
+                    &closed,
+                    headline_line,
+                    &heading,
+                    note.unwrap_or(""),
+                    self.log_into_drawer,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_startup_options() {
+        let options = StartupOptions::from_content(
+            "#+STARTUP: overview logdone\n#+startup: lognotedone logdrawer noalign\n* Task\n",
+        );
+        assert_eq!(options.log_done, LogDone::Note);
+        assert!(options.log_into_drawer);
+        assert_eq!(options.visibility, Some(StartupVisibility::Overview));
+        assert_eq!(options.align, Some(false));
+        assert!(!options.ordered);
+        assert_eq!(
+            StartupOptions::from_content("* Task\n"),
+            StartupOptions::default()
+        );
+    }
+
+    #[test]
+    fn test_log_closing() {
+        let now = NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_opt(16, 30, 0)
+            .unwrap();
+        let content =
+            "* DONE Task\nSCHEDULED: <2024-03-05 Tue>\n:PROPERTIES:\n:ID: 1\n:END:\nBody\n";
+
+        let quiet = StartupOptions::default();
+        assert_eq!(quiet.log_closing(content, 1, now, None).unwrap(), content);
+
+        let time = StartupOptions::from_content("#+STARTUP: logdone");
+        assert_eq!(
+            time.log_closing(content, 1, now, Some("ignored")).unwrap(),
+            "* DONE Task\nCLOSED: [2024-03-05 Tue 16:30] SCHEDULED: <2024-03-05 Tue>\n:PROPERTIES:\n:ID: 1\n:END:\nBody\n"
+        );
+
+        let note = StartupOptions::from_content("#+STARTUP: lognotedone logdrawer");
+        assert_eq!(
+            note.log_closing("* DONE Task\nBody\n", 1, now, Some("Shipped")).unwrap(),
+            "* DONE Task\nCLOSED: [2024-03-05 Tue 16:30]\n:LOGBOOK:\n- CLOSING NOTE [2024-03-05 Tue 16:30] \\\\\n  Shipped\n:END:\nBody\n"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::{headline_level, source_lines};
+use std::ops::Range;
+
+/// Line range (0-based, end exclusive) of a headline and all its descendants
+pub fn subtree_lines(document: &OrgDocument, headline_id: &str) -> Option<Range<usize>> {
+    let lines = source_lines(document);
+    let start = lines.iter().position(|line| {
+        line.headline_id == Some(headline_id) && headline_level(line.text).is_some()
+    })?;
+    let level = headline_level(lines[start].text)?;
+
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| headline_level(line.text).is_some_and(|other| other <= level))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    Some(start..end)
+}
+
+/// Split content into the lines outside `range` and the lines inside it
+/// Line endings are kept, and a removed last line without one gets a newline
+pub fn take_lines(content: &str, range: Range<usize>) -> (String, String) {
+    let mut kept = String::with_capacity(content.len());
+    let mut taken = String::new();
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if range.contains(&index) {
+            taken.push_str(line);
+        } else {
+            kept.push_str(line);
+        }
+    }
+    if !taken.is_empty() && !taken.ends_with('\n') {
+        taken.push('\n');
+    }
+
+    (kept, taken)
+}
+
+/// Insert a subtree before the first headline at or after `line` (0-based)
+/// Falls back to appending, so the text never lands inside another section
+pub fn insert_subtree(content: &str, line: usize, subtree: &str) -> String {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let at = lines
+        .iter()
+        .enumerate()
+        .skip(line)
+        .find(|(_, text)| headline_level(text).is_some())
+        .map_or(lines.len(), |(index, _)| index);
+
+    let mut output = String::with_capacity(content.len() + subtree.len() + 1);
+    for text in &lines[..at] {
+        output.push_str(text);
+    }
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(subtree);
+    if !subtree.ends_with('\n') {
+        output.push('\n');
+    }
+    for text in &lines[at..] {
+        output.push_str(text);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const CONTENT: &str = "#+TITLE: Tree\n* One\nBody\n** Child\n*** Grandchild\n* Two\n";
+
+    #[test]
+    fn test_subtree_lines() {
+        let doc = parse_org_document(CONTENT, Some("tree.org")).unwrap();
+        assert_eq!(subtree_lines(&doc, "1"), Some(1..5));
+        assert_eq!(subtree_lines(&doc, "1.1"), Some(3..5));
+        assert_eq!(subtree_lines(&doc, "2"), Some(5..6));
+        assert_eq!(subtree_lines(&doc, "3"), None);
+    }
+
+    #[test]
+    fn test_take_and_insert_subtree() {
+        let (kept, taken) = take_lines(CONTENT, 3..5);
+        assert_eq!(kept, "#+TITLE: Tree\n* One\nBody\n* Two\n");
+        assert_eq!(taken, "** Child\n*** Grandchild\n");
+
+        // Reinserting at the original line restores the content
+        assert_eq!(insert_subtree(&kept, 3, &taken), CONTENT);
+        // Past the end, or with no later headline, the subtree is appended
+        assert_eq!(insert_subtree("* Two", 9, "* One\n"), "* Two\n* One\n");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::orgmode::source::headline_level;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Outcome of renaming or merging tags across monitored files
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct TagRewriteSummary {
+    pub files_changed: u32,
+    pub headlines_changed: u32, // FILETAGS lines are counted in files, not here
+}
+
+/// Whether a string can be used as an org tag: letters, digits, `_`, `@`, `#` and `%`
+pub fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%'))
+}
+
+// Byte offset of a trailing `:tag1:tag2:` group on a line
+fn tag_group_start(line: &str) -> Option<usize> {
+    let start = line.rfind([' ', '\t'])? + 1;
+    let group = &line[start..];
+    let inner = group.strip_prefix(':')?.strip_suffix(':')?;
+    inner.split(':').all(is_valid_tag).then_some(start)
+}
+
+// Replace any of `from` with `into`, keeping the first occurrence of each tag
+fn rewrite_group(group: &str, from: &[String], into: &str) -> Option<String> {
+    let tags: Vec<&str> = group.trim_matches(':').split(':').collect();
+    if !tags.iter().any(|tag| from.iter().any(|old| old == tag)) {
+        return None;
+    }
+
+    let mut rewritten: Vec<&str> = Vec::new();
+    for tag in tags {
+        let tag = if from.iter().any(|old| old == tag) {
+            into
+        } else {
+            tag
+        };
+        if !rewritten.contains(&tag) {
+            rewritten.push(tag);
+        }
+    }
+    Some(format!(":{}:", rewritten.join(":")))
+}
+
+// Rewrite the tag group of a line, keeping right-aligned tags aligned
+fn rewrite_line(line: &str, from: &[String], into: &str) -> Option<String> {
+    let start = tag_group_start(line)?;
+    let group = &line[start..];
+    let new_group = rewrite_group(group, from, into)?;
+
+    let head = line[..start].trim_end_matches([' ', '\t']);
+    let gap = &line[head.len()..start];
+    let gap = if gap.len() > 1 && !gap.contains('\t') {
+        let end_column = head.chars().count() + gap.len() + group.chars().count();
+        let width = end_column.saturating_sub(head.chars().count() + new_group.chars().count());
+        " ".repeat(width.max(1))
+    } else {
+        gap.to_string()
+    };
+    Some(format!("{}{}{}", head, gap, new_group))
+}
+
+fn is_filetags_line(line: &str) -> bool {
+    line.trim_start()
+        .get(..11)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("#+FILETAGS:"))
+}
+
+// Rewrite the tag group of a `#+FILETAGS:` line, whatever whitespace surrounds its value
+fn rewrite_filetags(line: &str, from: &[String], into: &str) -> Option<String> {
+    let keyword_end = line.len() - line.trim_start().len() + 11;
+    let value = line[keyword_end..].trim();
+    let inner = value.strip_prefix(':')?.strip_suffix(':')?;
+    if !inner.split(':').all(is_valid_tag) {
+        return None;
+    }
+    let new_group = rewrite_group(value, from, into)?;
+    Some(format!("{} {}", &line[..keyword_end], new_group))
+}
+
+/// Replace the tags in `from` with `into` on every headline and `#+FILETAGS:` line
+/// A headline that ends up with `into` twice keeps it once. Returns the new content and the
+/// number of headlines changed, or None if nothing used the tags.
+pub fn rewrite_tags(content: &str, from: &[String], into: &str) -> Option<(String, u32)> {
+    let mut output = String::with_capacity(content.len());
+    let mut changed = false;
+    let mut headlines_changed = 0;
+
+    for line in content.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let is_headline = headline_level(body).is_some();
+        let rewritten = if is_headline {
+            rewrite_line(body, from, into)
+        } else if is_filetags_line(body) {
+            rewrite_filetags(body, from, into)
+        } else {
+            None
+        };
+
+        match rewritten {
+            Some(rewritten) => {
+                output.push_str(&rewritten);
+                output.push_str(&line[body.len()..]);
+                changed = true;
+                if is_headline {
+                    headlines_changed += 1;
+                }
+            }
+            None => output.push_str(line),
+        }
+    }
+
+    changed.then_some((output, headlines_changed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_tags() {
+        let content = "#+FILETAGS: :work:misc:\n* TODO Call Bob                                    :phone:work:\n* Notes :Work:\nA line with :work: in it\n** Errands\t:errand:calls:\n";
+        let from = vec!["work".to_string(), "calls".to_string()];
+
+        let (updated, headlines) = rewrite_tags(content, &from, "job").unwrap();
+        assert_eq!(headlines, 2);
+        let lines: Vec<&str> = updated.lines().collect();
+        assert_eq!(lines[0], "#+FILETAGS: :job:misc:");
+        // Aligned tags keep ending at the same column
+        assert!(lines[1].starts_with("* TODO Call Bob ") && lines[1].ends_with(" :phone:job:"));
+        assert_eq!(lines[1].len(), content.lines().nth(1).unwrap().len());
+        // Tags are case-sensitive, and body text is left alone
+        assert_eq!(lines[2], "* Notes :Work:");
+        assert_eq!(lines[3], "A line with :work: in it");
+        assert_eq!(lines[4], "** Errands\t:errand:job:");
+
+        // Merging into a tag the headline already has keeps a single copy
+        let (merged, _) = rewrite_tags("* Task :job:work:\n", &from, "job").unwrap();
+        assert_eq!(merged, "* Task :job:\n");
+
+        // FILETAGS values are trimmed, wherever the keyword starts
+        let (padded, headlines) =
+            rewrite_tags("  #+filetags:   :work:misc:  \n", &from, "job").unwrap();
+        assert_eq!(
+            (padded.as_str(), headlines),
+            ("  #+filetags: :job:misc:\n", 0)
+        );
+
+        assert!(rewrite_tags(content, &["none".to_string()], "job").is_none());
+        assert!(is_valid_tag("@home") && !is_valid_tag("two words") && !is_valid_tag(""));
+    }
+}
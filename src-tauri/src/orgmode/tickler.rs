@@ -0,0 +1,172 @@
+use crate::orgmode::datetime::OrgDatetime;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+/// Property holding the day a deferred task comes back, as an inactive timestamp
+pub const DEFERRED_PROPERTY: &str = "DEFERRED";
+
+/// Tag of someday/maybe headlines, listed in the tickler
+pub const SOMEDAY_TAG: &str = "someday";
+
+/// A headline in the tickler: deferred to a later day or parked as someday/maybe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct TicklerItem {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub todo_keyword: Option<String>,
+    pub deferred_until: Option<String>, // YYYY-MM-DD, None for someday headlines
+    pub someday: bool,
+}
+
+/// The day a headline is deferred until, from its DEFERRED property
+pub fn deferred_until(headline: &OrgHeadline) -> Option<NaiveDate> {
+    let value = headline
+        .title
+        .get_property_ignore_case(DEFERRED_PROPERTY)?
+        .trim()
+        .trim_start_matches(['[', '<']);
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// The DEFERRED property value for a day, named in `language`
+pub fn deferred_value(date: NaiveDate, language: &str) -> String {
+    format!(
+        "[{}]",
+        OrgDatetime::from_naive_date(date, language).format_org_date()
+    )
+}
+
+/// Whether a headline, or one of its ancestors, is deferred past `date`
+/// Deferred tasks stay out of active lists until then and come back on their own. Views
+/// checking every headline of a document look its deferral up in [`deferrals`] instead.
+pub fn is_deferred(document: &OrgDocument, headline_id: &str, date: NaiveDate) -> bool {
+    document.headline_path(headline_id).is_some_and(|path| {
+        path.iter()
+            .any(|headline| deferred_until(headline).is_some_and(|until| until > date))
+    })
+}
+
+// Walk the tree carrying the latest deferral of the ancestors down to children
+fn collect_deferrals(
+    headlines: &[OrgHeadline],
+    inherited: Option<NaiveDate>,
+    deferrals: &mut HashMap<String, NaiveDate>,
+) {
+    for headline in headlines {
+        let until = deferred_until(headline).max(inherited);
+        if let Some(until) = until {
+            deferrals.insert(headline.id.clone(), until);
+        }
+        collect_deferrals(&headline.children, until, deferrals);
+    }
+}
+
+/// The day each deferred headline of a document comes back, by headline id
+/// Subtasks inherit the latest deferral of their ancestors; headlines that are not deferred
+/// are left out.
+pub fn deferrals(document: &OrgDocument) -> HashMap<String, NaiveDate> {
+    let mut deferrals = HashMap::new();
+    collect_deferrals(&document.headlines, None, &mut deferrals);
+    deferrals
+}
+
+fn is_someday(tag: &str) -> bool {
+    tag.eq_ignore_ascii_case(SOMEDAY_TAG)
+}
+
+// Walk the tree carrying an inherited `:someday:` tag down to children
+fn collect_items(
+    document: &OrgDocument,
+    headlines: &[OrgHeadline],
+    inherited_someday: bool,
+    today: NaiveDate,
+    items: &mut Vec<TicklerItem>,
+) {
+    for headline in headlines {
+        let until = deferred_until(headline).filter(|until| *until > today);
+        let someday = inherited_someday || headline.title.tags.iter().any(|tag| is_someday(tag));
+        if until.is_some() || someday {
+            items.push(TicklerItem {
+                document_id: document.id.clone(),
+                headline_id: headline.id.clone(),
+                title: headline.title.raw.clone(),
+                todo_keyword: headline.title.todo_keyword.as_deref().map(str::to_string),
+                deferred_until: until.map(|until| until.format("%Y-%m-%d").to_string()),
+                someday,
+            });
+        }
+        collect_items(document, &headline.children, someday, today, items);
+    }
+}
+
+/// Collect the tickler: headlines deferred past `today`, soonest to come back first, then
+/// those tagged `:someday:` in file order
+/// The tag is inherited from ancestors and `#+FILETAGS:`. Missing documents, read-only
+/// archives and files marked `no-agenda` are left out.
+pub fn collect_tickler(documents: &[&OrgDocument], today: NaiveDate) -> Vec<TicklerItem> {
+    let mut items = Vec::new();
+    for document in documents {
+        if document.missing || document.archived || document.flags.no_agenda {
+            continue;
+        }
+        let someday = document.filetags.iter().any(|tag| is_someday(tag));
+        collect_items(document, &document.headlines, someday, today, &mut items);
+    }
+    items.sort_by(|a, b| {
+        (a.deferred_until.is_none(), &a.deferred_until)
+            .cmp(&(b.deferred_until.is_none(), &b.deferred_until))
+    });
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_tickler() {
+        let doc = parse_org_document(
+            "* TODO Renew passport\n:PROPERTIES:\n:DEFERRED: [2024-06-01 Sat]\n:END:\n** TODO Book photo\n* Learn piano :someday:\n* TODO Call bank\n:PROPERTIES:\n:DEFERRED: [2024-03-04 Mon]\n:END:\n* TODO Pay rent\n",
+            Some("todo.org"),
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        let items = collect_tickler(&[&doc], today);
+        let titles: Vec<&str> = items.iter().map(|item| item.title.as_str()).collect();
+        assert_eq!(titles, vec!["Call bank", "Renew passport", "Learn piano"]);
+        assert_eq!(items[0].deferred_until.as_deref(), Some("2024-03-04"));
+
+        // Subtasks are deferred with their parent; tasks come back on the deferred day
+        assert!(is_deferred(&doc, "1.1", today));
+        assert!(!is_deferred(&doc, "4", today));
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert!(!is_deferred(&doc, "3", monday));
+        assert_eq!(collect_tickler(&[&doc], monday).len(), 2);
+        let deferrals = deferrals(&doc);
+        assert_eq!(deferrals.len(), 3);
+        assert_eq!(deferrals["1.1"], deferrals["1"]);
+
+        // Someday tags are inherited from ancestors and the file; no-agenda files are skipped
+        let doc = parse_org_document(
+            "#+FILETAGS: :someday:\n* Learn piano\n** Buy keyboard\n",
+            Some("someday.org"),
+        )
+        .unwrap();
+        assert_eq!(collect_tickler(&[&doc], today).len(), 2);
+        let doc = parse_org_document(
+            "#+ORG_X: no-agenda\n* Learn piano :someday:\n",
+            Some("hidden.org"),
+        )
+        .unwrap();
+        assert!(collect_tickler(&[&doc], today).is_empty());
+
+        assert_eq!(deferred_value(monday, "en"), "[2024-03-04 Mon]");
+    }
+}
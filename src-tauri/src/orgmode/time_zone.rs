@@ -0,0 +1,128 @@
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+// Zone org timestamps are assumed to be in, None for the system time zone
+static ASSUMED_TIME_ZONE: Lazy<RwLock<Option<Tz>>> = Lazy::new(|| RwLock::new(None));
+
+/// Parse an IANA time zone name such as `Europe/Berlin`
+pub fn parse_time_zone(name: &str) -> Result<Tz, String> {
+    name.trim()
+        .parse::<Tz>()
+        .map_err(|_| format!("Unknown time zone: '{}'", name))
+}
+
+/// Set the zone org timestamps are assumed to be in; None uses the system time zone
+pub fn set_assumed_time_zone(zone: Option<Tz>) {
+    match ASSUMED_TIME_ZONE.write() {
+        Ok(mut assumed) => *assumed = zone,
+        Err(poisoned) => *poisoned.into_inner() = zone,
+    }
+}
+
+/// The zone org timestamps are assumed to be in, None for the system time zone
+pub fn assumed_time_zone() -> Option<Tz> {
+    match ASSUMED_TIME_ZONE.read() {
+        Ok(assumed) => *assumed,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+/// Wall-clock time of an instant in a zone, or in the system time zone if None
+pub fn to_local(instant: DateTime<Utc>, zone: Option<Tz>) -> NaiveDateTime {
+    match zone {
+        Some(zone) => instant.with_timezone(&zone).naive_local(),
+        None => instant.with_timezone(&chrono::Local).naive_local(),
+    }
+}
+
+// The instant a wall-clock time denotes in a zone
+fn resolve<Z: TimeZone>(zone: &Z, local: NaiveDateTime) -> DateTime<Utc> {
+    match zone.from_local_datetime(&local) {
+        LocalResult::Single(instant) | LocalResult::Ambiguous(instant, _) => {
+            instant.with_timezone(&Utc)
+        }
+        // Skipped when the clocks went forward: count from an hour before, so 02:30 on
+        // the night clocks jump from 02:00 to 03:00 becomes 03:30
+        LocalResult::None => zone
+            .from_local_datetime(&(local - Duration::hours(1)))
+            .earliest()
+            .map(|instant| instant.with_timezone(&Utc) + Duration::hours(1))
+            .unwrap_or_else(|| local.and_utc()),
+    }
+}
+
+/// The instant a wall-clock time denotes in a zone, or in the system time zone if None
+///
+/// Times that occur twice when clocks go back resolve to the first occurrence, and times
+/// skipped when clocks go forward are moved forward by the gap.
+pub fn to_utc(local: NaiveDateTime, zone: Option<Tz>) -> DateTime<Utc> {
+    match zone {
+        Some(zone) => resolve(&zone, local),
+        None => resolve(&chrono::Local, local),
+    }
+}
+
+/// An instant as RFC 3339 with the offset of a zone, or of the system time zone if None
+pub fn to_rfc3339(instant: DateTime<Utc>, zone: Option<Tz>) -> String {
+    match zone {
+        Some(zone) => instant.with_timezone(&zone).to_rfc3339(),
+        None => instant.with_timezone(&chrono::Local).to_rfc3339(),
+    }
+}
+
+/// Current time as RFC 3339 with the assumed time zone's offset, for stored timestamps
+pub fn now_rfc3339() -> String {
+    to_rfc3339(Utc::now(), assumed_time_zone())
+}
+
+/// Current wall-clock time in the assumed time zone
+pub fn now() -> NaiveDateTime {
+    to_local(Utc::now(), assumed_time_zone())
+}
+
+/// Current date in the assumed time zone, for "today" and "overdue"
+pub fn today() -> NaiveDate {
+    now().date()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_conversions() {
+        let tokyo = parse_time_zone("Asia/Tokyo").unwrap();
+        let instant = to_utc(datetime("2024-03-05 08:00"), Some(tokyo));
+        assert_eq!(instant.to_rfc3339(), "2024-03-04T23:00:00+00:00");
+        // Already the next day in Tokyo while still the previous one in UTC
+        assert_eq!(
+            to_local(instant, Some(tokyo)).date(),
+            NaiveDate::from_ymd_opt(2024, 3, 5).unwrap()
+        );
+        assert_eq!(
+            to_rfc3339(instant, Some(tokyo)),
+            "2024-03-05T08:00:00+09:00"
+        );
+        assert!(parse_time_zone("Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_daylight_saving_changes() {
+        let berlin = parse_time_zone("Europe/Berlin").unwrap();
+        // Clocks jumped from 02:00 to 03:00 on 2024-03-31
+        let skipped = to_utc(datetime("2024-03-31 02:30"), Some(berlin));
+        assert_eq!(
+            to_local(skipped, Some(berlin)),
+            datetime("2024-03-31 03:30")
+        );
+        // And went back from 03:00 to 02:00 on 2024-10-27
+        let repeated = to_utc(datetime("2024-10-27 02:30"), Some(berlin));
+        assert_eq!(repeated.to_rfc3339(), "2024-10-27T00:30:00+00:00");
+    }
+}
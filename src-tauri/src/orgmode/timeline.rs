@@ -0,0 +1,210 @@
+use crate::orgmode::datetime::OrgDatetime;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::logbook::parse_timestamp_inner;
+use crate::orgmode::source::{flatten_headlines, headline_sections};
+use crate::orgmode::timestamp::OrgTimestamp;
+use crate::orgmode::work_schedule::WorkSchedule;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// What put an entry on the timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum TimelineKind {
+    Scheduled,
+    Deadline,
+    Closed,
+    Timestamp, // Active timestamp in the title or body
+}
+
+/// A headline at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct TimelineEntry {
+    pub headline_id: String,
+    pub title: String,
+    pub todo_keyword: Option<String>,
+    pub kind: TimelineKind,
+    pub time: Option<String>, // HH:MM, None for all-day entries
+}
+
+/// Timeline entries falling on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct TimelineDay {
+    pub date: String, // YYYY-MM-DD
+    pub entries: Vec<TimelineEntry>,
+    pub working_day: bool,       // False on weekends and holidays
+    pub holiday: Option<String>, // Name of the holiday on this day
+}
+
+// Repeater of a timestamp's inner text, like `+1w`, `++2d` or `.+1m`
+fn timestamp_repeater(inner: &str) -> Option<String> {
+    inner
+        .split_whitespace()
+        .skip(1)
+        .find(|part| part.starts_with('+') || part.starts_with(".+"))
+        .map(str::to_string)
+}
+
+// Start and repeater of every active timestamp on a line; ranges contribute their start
+fn active_timestamps(line: &str) -> Vec<(OrgDatetime, Option<String>)> {
+    let mut found = Vec::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('<') {
+        let after = &rest[open + 1..];
+        match after.find('>') {
+            Some(close) => {
+                let inner = &after[..close];
+                if let Some(datetime) = parse_timestamp_inner(inner) {
+                    found.push((datetime, timestamp_repeater(inner)));
+                }
+                rest = &after[close + 1..];
+                // Skip the end of a `<start>--<end>` range
+                if let Some(end) = rest.strip_prefix("--<") {
+                    rest = end.find('>').map_or("", |close| &end[close + 1..]);
+                }
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+fn is_planning_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+        .iter()
+        .any(|keyword| trimmed.starts_with(keyword))
+}
+
+fn planning_entries(headline: &OrgHeadline) -> Vec<(TimelineKind, &OrgTimestamp)> {
+    let Some(planning) = &headline.title.planning else {
+        return Vec::new();
+    };
+    [
+        (TimelineKind::Scheduled, &planning.scheduled),
+        (TimelineKind::Deadline, &planning.deadline),
+        (TimelineKind::Closed, &planning.closed),
+    ]
+    .into_iter()
+    .filter_map(|(kind, timestamp)| timestamp.as_ref().map(|timestamp| (kind, timestamp)))
+    .collect()
+}
+
+/// A timeline entry with the start of its timestamp and the timestamp's repeater
+pub(crate) type StampedEntry = (OrgDatetime, Option<String>, TimelineEntry);
+
+/// Every scheduled, deadline, closed and active timestamp of a document, in file order
+/// Body timestamps need the document's content, so load it first if it was evicted
+pub(crate) fn stamped_entries(document: &OrgDocument) -> Vec<StampedEntry> {
+    let sections = headline_sections(document);
+    let mut stamped: Vec<StampedEntry> = Vec::new();
+
+    for headline in flatten_headlines(&document.headlines) {
+        let entry = |kind, datetime: &OrgDatetime| TimelineEntry {
+            headline_id: headline.id.clone(),
+            title: headline.title.raw.clone(),
+            todo_keyword: headline.title.todo_keyword.as_deref().map(str::to_string),
+            kind,
+            time: datetime
+                .hour
+                .map(|hour| format!("{:02}:{:02}", hour, datetime.minute.unwrap_or(0))),
+        };
+
+        for (kind, timestamp) in planning_entries(headline) {
+            if let Some(datetime) = timestamp.start_date() {
+                let repeater = timestamp.repeater().map(str::to_string);
+                stamped.push((datetime.clone(), repeater, entry(kind, datetime)));
+            }
+        }
+        let section = sections.get(&headline.id).map_or("", String::as_str);
+        for line in section.lines().filter(|line| !is_planning_line(line)) {
+            for (datetime, repeater) in active_timestamps(line) {
+                let timeline_entry = entry(TimelineKind::Timestamp, &datetime);
+                stamped.push((datetime, repeater, timeline_entry));
+            }
+        }
+    }
+    stamped
+}
+
+/// Collect every scheduled, deadline, closed and active timestamp of a document,
+/// grouped by day in chronological order; entries on a day are sorted by time, all-day first
+/// Body timestamps need the document's content, so load it first if it was evicted
+/// Days are marked as working days or not according to the work schedule
+pub fn document_timeline(document: &OrgDocument, schedule: &WorkSchedule) -> Vec<TimelineDay> {
+    let mut stamped = stamped_entries(document);
+
+    // Stable sort keeps file order for entries at the same time
+    stamped.sort_by_key(|(datetime, _, _)| {
+        (
+            datetime.to_naive_date(),
+            datetime
+                .hour
+                .map(|hour| (hour, datetime.minute.unwrap_or(0))),
+        )
+    });
+
+    let mut days: Vec<TimelineDay> = Vec::new();
+    for (datetime, _, entry) in stamped {
+        let naive_date = datetime.to_naive_date();
+        let date = naive_date.format("%Y-%m-%d").to_string();
+        match days.last_mut() {
+            Some(day) if day.date == date => day.entries.push(entry),
+            _ => days.push(TimelineDay {
+                date,
+                entries: vec![entry],
+                working_day: schedule.is_working_day(naive_date),
+                holiday: schedule.holiday(naive_date).map(str::to_string),
+            }),
+        }
+    }
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use crate::orgmode::work_schedule::Holiday;
+
+    #[test]
+    fn test_document_timeline() {
+        let doc = parse_org_document(
+            "* DONE Kickoff <2024-01-08 Mon 10:00>\nCLOSED: [2024-01-08 Mon 11:30]\n* TODO Draft spec\nSCHEDULED: <2024-01-09 Tue> DEADLINE: <2024-01-12 Fri>\nReview call <2024-01-09 Tue 14:00>--<2024-01-09 Tue 15:00>, noted [2024-01-10 Wed].\n* Notes\n",
+            Some("project.org"),
+        )
+        .unwrap();
+        let schedule = WorkSchedule {
+            holidays: vec![Holiday {
+                date: "2024-01-12".to_string(),
+                name: "Founders' Day".to_string(),
+            }],
+            ..WorkSchedule::default()
+        };
+        let days = document_timeline(&doc, &schedule);
+
+        let dates: Vec<&str> = days.iter().map(|day| day.date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-01-08", "2024-01-09", "2024-01-12"]);
+
+        let kickoff: Vec<(TimelineKind, Option<&str>)> = days[0]
+            .entries
+            .iter()
+            .map(|entry| (entry.kind, entry.time.as_deref()))
+            .collect();
+        assert_eq!(
+            kickoff,
+            vec![
+                (TimelineKind::Timestamp, Some("10:00")),
+                (TimelineKind::Closed, Some("11:30")),
+            ]
+        );
+
+        // The all-day scheduled entry comes before the timed call
+        assert_eq!(days[1].entries[0].kind, TimelineKind::Scheduled);
+        assert_eq!(days[1].entries[1].time.as_deref(), Some("14:00"));
+        assert_eq!(days[1].entries.len(), 2);
+        assert_eq!(days[2].entries[0].kind, TimelineKind::Deadline);
+        assert!(days[1].working_day && !days[2].working_day);
+        assert_eq!(days[2].holiday.as_deref(), Some("Founders' Day"));
+    }
+}
@@ -129,6 +129,17 @@ impl OrgTimestamp {
         }
     }
 
+    /// Get the repeater, like `+1w` or `.+1d`, if the timestamp has one
+    pub fn repeater(&self) -> Option<&str> {
+        match self {
+            OrgTimestamp::Active { repeater, .. }
+            | OrgTimestamp::Inactive { repeater, .. }
+            | OrgTimestamp::ActiveRange { repeater, .. }
+            | OrgTimestamp::InactiveRange { repeater, .. } => repeater.as_deref(),
+            OrgTimestamp::Diary { .. } => None,
+        }
+    }
+
     /// Get the end date if this is a range timestamp
     pub fn end_date(&self) -> Option<&OrgDatetime> {
         match self {
@@ -4,17 +4,21 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 /// Represents a headline title in org-mode
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct OrgTitle {
-    pub raw: String,                         // Raw title text
-    pub level: u8,                        // Level of the headline (1, 2, 3, etc)
-    pub priority: Option<char>,              // Priority cookie (A, B, C, etc)
-    pub tags: Vec<String>,                   // Tags associated with the title
-    pub todo_keyword: Option<String>,        // TODO keyword if present
-    pub properties: HashMap<String, String>, // Properties associated with this headline
-    pub planning: Option<Box<OrgPlanning>>,  // Planning information if present
+    pub raw: String,            // Raw title text
+    pub level: u8,              // Level of the headline (1, 2, 3, etc)
+    pub priority: Option<char>, // Priority cookie (A, B, C, etc)
+    #[specta(type = Vec<String>)]
+    pub tags: Vec<Arc<str>>, // Tags associated with the title (interned)
+    #[specta(type = Option<String>)]
+    pub todo_keyword: Option<Arc<str>>, // TODO keyword if present (interned)
+    #[specta(type = HashMap<String, String>)]
+    pub properties: HashMap<Arc<str>, String>, // Properties associated with this headline (keys interned)
+    pub planning: Option<Box<OrgPlanning>>, // Planning information if present
 }
 
 impl OrgTitle {
@@ -30,8 +34,8 @@ impl OrgTitle {
             raw,
             level,
             priority,
-            tags,
-            todo_keyword,
+            tags: tags.into_iter().map(Arc::from).collect(),
+            todo_keyword: todo_keyword.map(Arc::from),
             properties: HashMap::new(),
             planning: None,
         }
@@ -90,9 +94,17 @@ impl OrgTitle {
         self.properties.get(key).map(|s| s.as_str())
     }
 
+    /// Get a property value, matching the key case-insensitively
+    pub fn get_property_ignore_case(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
     /// Set a property value
     pub fn set_property(&mut self, key: String, value: String) {
-        self.properties.insert(key, value);
+        self.properties.insert(key.into(), value);
     }
 }
 
@@ -142,6 +154,7 @@ impl std::fmt::Display for OrgTitle {
 impl Hash for OrgTitle {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.raw.hash(state);
+        self.level.hash(state);
         self.priority.hash(state);
         self.tags.hash(state);
         self.todo_keyword.hash(state);
@@ -176,8 +189,8 @@ mod tests {
         assert_eq!(title.raw, "Test Title");
         assert_eq!(title.level, 1);
         assert_eq!(title.priority, Some('A'));
-        assert_eq!(title.tags, vec!["tag1".to_string(), "tag2".to_string()]);
-        assert_eq!(title.todo_keyword, Some("TODO".to_string()));
+        assert_eq!(title.tags, vec![Arc::from("tag1"), Arc::from("tag2")]);
+        assert_eq!(title.todo_keyword.as_deref(), Some("TODO"));
     }
 
     #[test]
@@ -1,3 +1,4 @@
+use crate::orgmode::colors::{keyword_color, keyword_style, StatusStyle};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
@@ -8,6 +9,7 @@ pub struct TodoStatus {
     pub state_type: StateType, // Whether it's active or closed
     pub order: u32,      // Order in the sequence
     pub color: Option<String>, // Optional color for UI display
+    pub style: StatusStyle, // Light and dark theme colors and an icon hint
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
@@ -31,7 +33,8 @@ impl TodoStatus {
             keyword: "TODO".to_string(),
             state_type: StateType::Active,
             order: 0,
-            color: Some("#ff0000".to_string()), // Red
+            color: Some(keyword_color("TODO", &StateType::Active)),
+            style: keyword_style("TODO", &StateType::Active),
         }
     }
 
@@ -41,11 +44,19 @@ impl TodoStatus {
             keyword: "DONE".to_string(),
             state_type: StateType::Closed,
             order: 100,
-            color: Some("#00ff00".to_string()), // Green
+            color: Some(keyword_color("DONE", &StateType::Closed)),
+            style: keyword_style("DONE", &StateType::Closed),
         }
     }
 }
 
+/// Which way to cycle a headline's TODO keyword, like Shift-right and Shift-left in Emacs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum CycleDirection {
+    Next,
+    Previous,
+}
+
 // Configuration for TODO sequences
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct TodoConfiguration {
@@ -69,31 +80,36 @@ impl TodoConfiguration {
                     keyword: "TODO".to_string(),
                     state_type: StateType::Active,
                     order: 0,
-                    color: Some("#ff0000".to_string()),
+                    color: Some(keyword_color("TODO", &StateType::Active)),
+                    style: keyword_style("TODO", &StateType::Active),
                 },
                 TodoStatus {
                     keyword: "IN-PROGRESS".to_string(),
                     state_type: StateType::Active,
                     order: 10,
-                    color: Some("#ff9900".to_string()),
+                    color: Some(keyword_color("IN-PROGRESS", &StateType::Active)),
+                    style: keyword_style("IN-PROGRESS", &StateType::Active),
                 },
                 TodoStatus {
                     keyword: "WAITING".to_string(),
                     state_type: StateType::Active,
                     order: 20,
-                    color: Some("#ffff00".to_string()),
+                    color: Some(keyword_color("WAITING", &StateType::Active)),
+                    style: keyword_style("WAITING", &StateType::Active),
                 },
                 TodoStatus {
                     keyword: "DONE".to_string(),
                     state_type: StateType::Closed,
                     order: 100,
-                    color: Some("#00ff00".to_string()),
+                    color: Some(keyword_color("DONE", &StateType::Closed)),
+                    style: keyword_style("DONE", &StateType::Closed),
                 },
                 TodoStatus {
                     keyword: "CANCELLED".to_string(),
                     state_type: StateType::Closed,
                     order: 110,
-                    color: Some("#999999".to_string()),
+                    color: Some(keyword_color("CANCELLED", &StateType::Closed)),
+                    style: keyword_style("CANCELLED", &StateType::Closed),
                 },
             ],
         };
@@ -112,12 +128,194 @@ impl TodoConfiguration {
             .find(|status| status.keyword == keyword)
     }
 
-    // Parse org-mode TODO configuration
-    pub fn from_org_config(_config_lines: &[String]) -> Self {
-        // This is a placeholder for now
-        // In a real implementation, this would parse #+TODO: lines from org files
-        // Example: #+TODO: TODO IN-PROGRESS WAITING | DONE CANCELLED
-        Self::default()
+    // Build a single-sequence configuration from active and closed keywords
+    pub fn from_keywords(active: &[String], closed: &[String]) -> Self {
+        let sequence = TodoSequence::from_keywords("default", active, closed);
+        Self {
+            sequences: vec![sequence],
+            default_sequence: "default".to_string(),
+        }
+    }
+
+    // Parse org-mode TODO configuration, one sequence per #+TODO: line
+    // Example: #+TODO: TODO(t) IN-PROGRESS WAITING(w@/!) | DONE CANCELLED
+    pub fn from_org_config(config_lines: &[String]) -> Self {
+        let sequences: Vec<TodoSequence> = config_lines
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let (active, closed) = parse_todo_line(line)?;
+                let name = format!("file-{}", index + 1);
+                Some(TodoSequence::from_keywords(&name, &active, &closed))
+            })
+            .collect();
+
+        match sequences.first() {
+            Some(first) => Self {
+                default_sequence: first.name.clone(),
+                sequences,
+            },
+            None => Self::default(),
+        }
+    }
+
+    // Append another configuration's sequences, skipping keywords already defined
+    pub fn merged_with(mut self, other: &TodoConfiguration) -> Self {
+        for sequence in &other.sequences {
+            let statuses: Vec<TodoStatus> = sequence
+                .statuses
+                .iter()
+                .filter(|status| self.find_status(&status.keyword).is_none())
+                .cloned()
+                .collect();
+
+            if !statuses.is_empty() {
+                self.sequences.push(TodoSequence {
+                    name: sequence.name.clone(),
+                    statuses,
+                });
+            }
+        }
+        self
+    }
+
+    // All keywords in sequence order
+    pub fn keywords(&self) -> Vec<&str> {
+        self.sequences
+            .iter()
+            .flat_map(|sequence| &sequence.statuses)
+            .map(|status| status.keyword.as_str())
+            .collect()
+    }
+
+    /// The keyword after or before `keyword` in its sequence, wrapping through no keyword
+    /// Headlines without a keyword, or with an unknown one, enter the default sequence.
+    pub fn cycle(&self, keyword: Option<&str>, direction: CycleDirection) -> Option<String> {
+        let sequence = keyword
+            .and_then(|keyword| {
+                self.sequences.iter().find(|sequence| {
+                    sequence
+                        .statuses
+                        .iter()
+                        .any(|status| status.keyword == keyword)
+                })
+            })
+            .or_else(|| {
+                self.sequences
+                    .iter()
+                    .find(|sequence| sequence.name == self.default_sequence)
+            })
+            .or_else(|| self.sequences.first())?;
+        let statuses = &sequence.statuses;
+        let position = keyword
+            .and_then(|keyword| statuses.iter().position(|status| status.keyword == keyword));
+
+        let next = match (direction, position) {
+            (CycleDirection::Next, None) => statuses.first(),
+            (CycleDirection::Next, Some(index)) => statuses.get(index + 1),
+            (CycleDirection::Previous, None) => statuses.last(),
+            (CycleDirection::Previous, Some(index)) => {
+                index.checked_sub(1).and_then(|index| statuses.get(index))
+            }
+        };
+        next.map(|status| status.keyword.clone())
+    }
+
+    // Active and closed keywords, in the form the parser takes them
+    pub fn keyword_lists(&self) -> (Vec<String>, Vec<String>) {
+        let (active, closed): (Vec<&TodoStatus>, Vec<&TodoStatus>) = self
+            .sequences
+            .iter()
+            .flat_map(|sequence| &sequence.statuses)
+            .partition(|status| status.is_active());
+        let keywords = |statuses: Vec<&TodoStatus>| -> Vec<String> {
+            statuses
+                .into_iter()
+                .map(|status| status.keyword.clone())
+                .collect()
+        };
+        (keywords(active), keywords(closed))
+    }
+}
+
+impl TodoSequence {
+    // Build a sequence with palette colors, active keywords first
+    pub fn from_keywords(name: &str, active: &[String], closed: &[String]) -> Self {
+        let active_statuses = active.iter().enumerate().map(|(i, keyword)| TodoStatus {
+            keyword: keyword.clone(),
+            state_type: StateType::Active,
+            order: i as u32,
+            color: Some(keyword_color(keyword, &StateType::Active)),
+            style: keyword_style(keyword, &StateType::Active),
+        });
+        let closed_statuses = closed.iter().enumerate().map(|(i, keyword)| TodoStatus {
+            keyword: keyword.clone(),
+            state_type: StateType::Closed,
+            order: (active.len() + i) as u32,
+            color: Some(keyword_color(keyword, &StateType::Closed)),
+            style: keyword_style(keyword, &StateType::Closed),
+        });
+
+        Self {
+            name: name.to_string(),
+            statuses: active_statuses.chain(closed_statuses).collect(),
+        }
+    }
+}
+
+/// Collect the values of `#+TODO:`, `#+SEQ_TODO:` and `#+TYP_TODO:` lines in a file
+/// Lines inside blocks such as `#+BEGIN_SRC` or `#+BEGIN_EXAMPLE` are skipped, as they are
+/// when the file is parsed
+pub fn file_todo_lines(content: &str) -> Vec<String> {
+    let mut in_block = false;
+    content
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim_start().strip_prefix("#+")?;
+            let upper = rest.to_ascii_uppercase();
+            if upper.starts_with("BEGIN_") {
+                in_block = true;
+            } else if upper.starts_with("END_") {
+                in_block = false;
+                return None;
+            }
+            if in_block {
+                return None;
+            }
+            let (key, value) = rest.split_once(':')?;
+            ["TODO", "SEQ_TODO", "TYP_TODO"]
+                .iter()
+                .any(|todo_key| key.eq_ignore_ascii_case(todo_key))
+                .then(|| value.trim().to_string())
+        })
+        .collect()
+}
+
+// Split a #+TODO: value into active and closed keywords, dropping fast-access keys
+// Without a `|`, the last keyword is the closed state, as in org-mode
+fn parse_todo_line(line: &str) -> Option<(Vec<String>, Vec<String>)> {
+    let keywords = |words: &str| -> Vec<String> {
+        words
+            .split_whitespace()
+            .filter_map(|word| word.split('(').next())
+            .filter(|keyword| !keyword.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let (active, closed) = match line.split_once('|') {
+        Some((active, closed)) => (keywords(active), keywords(closed)),
+        None => {
+            let mut active = keywords(line);
+            let closed: Vec<String> = active.pop().into_iter().collect();
+            (active, closed)
+        }
+    };
+
+    if active.is_empty() && closed.is_empty() {
+        None
+    } else {
+        Some((active, closed))
     }
 }
 
@@ -182,4 +380,67 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_from_org_config() {
+        let lines = file_todo_lines(
+            "#+TITLE: Local\n#+TODO: TODO(t) NEXT(n) | DONE(d!) CANCELLED(c@)\n#+seq_todo: REPORT BUG FIXED\n",
+        );
+        assert_eq!(lines.len(), 2);
+        let quoted = "#+BEGIN_SRC org\n#+TODO: A | B\n#+END_SRC\n#+begin_example\n#+TODO: C | D\n#+end_example\n";
+        assert!(file_todo_lines(quoted).is_empty());
+
+        let config = TodoConfiguration::from_org_config(&lines);
+        assert_eq!(config.default_sequence, "file-1");
+        assert_eq!(
+            config.keywords(),
+            vec![
+                "TODO",
+                "NEXT",
+                "DONE",
+                "CANCELLED",
+                "REPORT",
+                "BUG",
+                "FIXED"
+            ]
+        );
+        assert!(config.find_status("CANCELLED").unwrap().is_closed());
+        assert!(config.find_status("FIXED").unwrap().is_closed());
+        assert!(config.find_status("BUG").unwrap().is_active());
+    }
+
+    #[test]
+    fn test_merged_with_settings() {
+        let file = TodoConfiguration::from_org_config(&["NEXT | DONE".to_string()]);
+        let settings = TodoConfiguration::from_keywords(
+            &["TODO".to_string(), "NEXT".to_string()],
+            &["DONE".to_string()],
+        );
+
+        let merged = file.merged_with(&settings);
+        assert_eq!(merged.default_sequence, "file-1");
+        assert_eq!(merged.keywords(), vec!["NEXT", "DONE", "TODO"]);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let config = TodoConfiguration::from_org_config(&[
+            "TODO NEXT | DONE".to_string(),
+            "BUG | FIXED".to_string(),
+        ]);
+        let next = |keyword| config.cycle(keyword, CycleDirection::Next);
+        let previous = |keyword| config.cycle(keyword, CycleDirection::Previous);
+
+        assert_eq!(next(None).as_deref(), Some("TODO"));
+        assert_eq!(next(Some("NEXT")).as_deref(), Some("DONE"));
+        assert_eq!(next(Some("DONE")), None);
+        assert_eq!(previous(None).as_deref(), Some("DONE"));
+        assert_eq!(previous(Some("TODO")), None);
+
+        // Keywords stay within their own sequence
+        assert_eq!(next(Some("BUG")).as_deref(), Some("FIXED"));
+        assert_eq!(next(Some("FIXED")), None);
+        assert_eq!(previous(Some("FIXED")).as_deref(), Some("BUG"));
+        assert_eq!(next(Some("UNKNOWN")).as_deref(), Some("TODO"));
+    }
 }
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// File name of the trash, inside the app data directory
+pub const TRASH_FILE_NAME: &str = "trash.json";
+
+/// Text removed from an org file, kept so it can be restored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct TrashEntry {
+    pub id: String,
+    pub file_path: String, // File the text was removed from
+    pub title: String,     // Title of the removed headline
+    pub line: u32,         // 1-based line where the text started
+    pub text: String,
+    pub deleted_at: String, // RFC 3339
+}
+
+/// Load trash entries, treating a missing trash file as empty
+pub fn load_trash(path: &Path) -> Result<Vec<TrashEntry>, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to read trash {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read trash {}: {}", path.display(), e)),
+    }
+}
+
+/// Save trash entries, creating the parent directory if needed
+pub fn save_trash(path: &Path, entries: &[TrashEntry]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize trash: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write trash {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_trash_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("data").join(TRASH_FILE_NAME);
+        assert!(load_trash(&path).unwrap().is_empty());
+
+        let entry = TrashEntry {
+            id: "entry-1".to_string(),
+            file_path: "/notes/a.org".to_string(),
+            title: "Old idea".to_string(),
+            line: 3,
+            text: "* Old idea\n".to_string(),
+            deleted_at: "2024-01-01T00:00:00+00:00".to_string(),
+        };
+        save_trash(&path, std::slice::from_ref(&entry)).unwrap();
+        assert_eq!(load_trash(&path).unwrap(), vec![entry]);
+    }
+}
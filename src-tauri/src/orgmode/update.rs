@@ -1,9 +1,25 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::time_zone;
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::HashMap;
+
+// What happened to a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub enum ChangeKind {
+    Added,
+    Updated,
+    Removed,
+    Missing,                  // File disappeared, document kept
+    Renamed { from: String }, // Previous document ID
+}
 
 // Model representing update information
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct OrgUpdateInfo {
+    pub sequence: u32, // Position in the change feed, assigned by the tracker
+    pub kind: ChangeKind,
     pub document_id: String,
     pub updated_headlines: Vec<String>, // IDs of updated headlines
     pub deleted_headlines: Vec<String>, // IDs of deleted headlines
@@ -11,10 +27,77 @@ pub struct OrgUpdateInfo {
     pub timestamp: String,
 }
 
+// Changes since a cursor, returned by the change feed
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ChangeFeed {
+    pub cursor: u32, // Pass this back to get the next batch
+    pub changes: Vec<OrgUpdateInfo>,
+    pub complete: bool, // False if changes were dropped from history; refetch everything
+}
+
+impl OrgUpdateInfo {
+    // Create an update without headline details
+    pub fn new(kind: ChangeKind, document_id: &str) -> Self {
+        Self {
+            sequence: 0,
+            kind,
+            document_id: document_id.to_string(),
+            updated_headlines: Vec::new(),
+            deleted_headlines: Vec::new(),
+            new_headlines: Vec::new(),
+            timestamp: time_zone::now_rfc3339(),
+        }
+    }
+
+    // Describe the change from an old version of a document to a new one
+    // Returns None when nothing observable changed
+    pub fn between(old: Option<&OrgDocument>, new: &OrgDocument) -> Option<Self> {
+        let Some(old) = old else {
+            let mut update = Self::new(ChangeKind::Added, &new.id);
+            update.new_headlines = flatten_headlines(&new.headlines)
+                .into_iter()
+                .map(|headline| headline.id.clone())
+                .collect();
+            return Some(update);
+        };
+
+        let old_etags: HashMap<&str, &str> = flatten_headlines(&old.headlines)
+            .into_iter()
+            .map(|headline| (headline.id.as_str(), headline.etag.as_str()))
+            .collect();
+        let new_headlines = flatten_headlines(&new.headlines);
+
+        let mut update = Self::new(ChangeKind::Updated, &new.id);
+        for headline in &new_headlines {
+            match old_etags.get(headline.id.as_str()) {
+                None => update.new_headlines.push(headline.id.clone()),
+                Some(etag) if *etag != headline.etag => {
+                    update.updated_headlines.push(headline.id.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        update.deleted_headlines = flatten_headlines(&old.headlines)
+            .into_iter()
+            .filter(|headline| !new_headlines.iter().any(|h| h.id == headline.id))
+            .map(|headline| headline.id.clone())
+            .collect();
+
+        let unchanged = old.etag == new.etag
+            && old.missing == new.missing
+            && update.new_headlines.is_empty()
+            && update.updated_headlines.is_empty()
+            && update.deleted_headlines.is_empty();
+
+        (!unchanged).then_some(update)
+    }
+}
+
 // Update tracker - tracks changes to documents
 pub struct UpdateTracker {
     updates: Vec<OrgUpdateInfo>,
     max_history: usize,
+    last_sequence: u32,
 }
 
 impl UpdateTracker {
@@ -22,15 +105,46 @@ impl UpdateTracker {
         Self {
             updates: Vec::new(),
             max_history,
+            last_sequence: 0,
         }
     }
 
-    // Add a new update
-    pub fn add_update(&mut self, update: OrgUpdateInfo) {
+    // Add a new update, assigning it the next sequence number
+    pub fn add_update(&mut self, mut update: OrgUpdateInfo) -> u32 {
+        self.last_sequence += 1;
+        update.sequence = self.last_sequence;
         self.updates.push(update);
         if self.updates.len() > self.max_history {
             self.updates.remove(0);
         }
+        self.last_sequence
+    }
+
+    // Current position of the change feed
+    pub fn cursor(&self) -> u32 {
+        self.last_sequence
+    }
+
+    // Get all changes after the given cursor, oldest first
+    pub fn changes_since(&self, cursor: u32) -> ChangeFeed {
+        let oldest_retained = self
+            .updates
+            .first()
+            .map_or(self.last_sequence + 1, |update| update.sequence);
+
+        // A cursor from the future belongs to an earlier session
+        let complete = cursor <= self.last_sequence && cursor + 1 >= oldest_retained;
+
+        ChangeFeed {
+            cursor: self.last_sequence,
+            changes: self
+                .updates
+                .iter()
+                .filter(|update| update.sequence > cursor)
+                .cloned()
+                .collect(),
+            complete,
+        }
     }
 
     // Get recent updates for a document
@@ -53,6 +167,8 @@ mod tests {
 
         // Create some test updates
         let update1 = OrgUpdateInfo {
+            sequence: 0,
+            kind: ChangeKind::Updated,
             document_id: "doc1".to_string(),
             updated_headlines: vec!["h1".to_string()],
             deleted_headlines: Vec::new(),
@@ -61,6 +177,8 @@ mod tests {
         };
 
         let update2 = OrgUpdateInfo {
+            sequence: 0,
+            kind: ChangeKind::Updated,
             document_id: "doc2".to_string(),
             updated_headlines: vec!["h2".to_string()],
             deleted_headlines: Vec::new(),
@@ -69,6 +187,8 @@ mod tests {
         };
 
         let update3 = OrgUpdateInfo {
+            sequence: 0,
+            kind: ChangeKind::Updated,
             document_id: "doc1".to_string(),
             updated_headlines: vec!["h3".to_string()],
             deleted_headlines: vec!["h4".to_string()],
@@ -77,6 +197,8 @@ mod tests {
         };
 
         let update4 = OrgUpdateInfo {
+            sequence: 0,
+            kind: ChangeKind::Added,
             document_id: "doc3".to_string(),
             updated_headlines: Vec::new(),
             deleted_headlines: Vec::new(),
@@ -106,4 +228,50 @@ mod tests {
         let doc1_updates = tracker.get_updates_for_document("doc1");
         assert_eq!(doc1_updates.len(), 1);
     }
+
+    #[test]
+    fn test_changes_since_cursor() {
+        let mut tracker = UpdateTracker::new(2);
+        assert_eq!(tracker.cursor(), 0);
+
+        tracker.add_update(OrgUpdateInfo::new(ChangeKind::Added, "doc1"));
+        let feed = tracker.changes_since(0);
+        assert_eq!(feed.cursor, 1);
+        assert_eq!(feed.changes.len(), 1);
+        assert!(feed.complete);
+
+        tracker.add_update(OrgUpdateInfo::new(ChangeKind::Updated, "doc1"));
+        tracker.add_update(OrgUpdateInfo::new(ChangeKind::Removed, "doc1"));
+
+        // Only changes after the cursor are returned
+        let feed = tracker.changes_since(1);
+        let kinds: Vec<&ChangeKind> = feed.changes.iter().map(|c| &c.kind).collect();
+        assert_eq!(kinds, vec![&ChangeKind::Updated, &ChangeKind::Removed]);
+        assert!(feed.complete);
+
+        // The first change fell out of history
+        assert!(!tracker.changes_since(0).complete);
+        // Cursors from another session are not trusted
+        assert!(!tracker.changes_since(10).complete);
+        assert!(tracker.changes_since(3).changes.is_empty());
+    }
+
+    #[test]
+    fn test_update_between_documents() {
+        use crate::orgmode::parser::parse_org_document;
+
+        let old = parse_org_document("* One\n* Two\n* Three\n", Some("a.org")).unwrap();
+        let new = parse_org_document("* One\n* Two changed\n", Some("a.org")).unwrap();
+
+        let added = OrgUpdateInfo::between(None, &old).unwrap();
+        assert_eq!(added.kind, ChangeKind::Added);
+        assert_eq!(added.new_headlines, vec!["1", "2", "3"]);
+
+        let update = OrgUpdateInfo::between(Some(&old), &new).unwrap();
+        assert_eq!(update.kind, ChangeKind::Updated);
+        assert_eq!(update.updated_headlines, vec!["2"]);
+        assert_eq!(update.deleted_headlines, vec!["3"]);
+
+        assert!(OrgUpdateInfo::between(Some(&new), &new).is_none());
+    }
 }
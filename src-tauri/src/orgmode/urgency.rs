@@ -0,0 +1,242 @@
+use crate::orgmode::datetime::OrgDatetime;
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::properties::parse_duration_minutes;
+use crate::orgmode::source::flatten_headlines;
+use crate::orgmode::tickler::deferrals;
+use crate::orgmode::todo::TodoConfiguration;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Table column showing the computed urgency of a task
+pub const URGENCY_COLUMN: &str = "urgency";
+
+/// Deadlines this many days overdue or more count as fully urgent
+const OVERDUE_DAYS: i64 = 7;
+
+/// Deadlines this many days away or more count as barely urgent
+const DISTANT_DAYS: i64 = 14;
+
+/// Tasks this old or older get the full age bonus
+const MAX_AGE_DAYS: i64 = 365;
+
+/// Efforts this long or longer get no quick-win bonus
+const MAX_EFFORT_MINUTES: u32 = 480;
+
+/// How much each factor adds to a task's urgency
+/// Each factor is scaled to 0..1 before weighting, so a weight is the most that factor can add
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct UrgencyWeights {
+    pub deadline: f64, // Closeness of the DEADLINE, full when a week overdue
+    pub priority: f64, // Priority cookie; no cookie counts as org's default [#B]
+    pub age: f64,      // Days since the CREATED property, full after a year
+    pub effort: f64,   // Quick wins: shorter Effort scores higher
+}
+
+impl Default for UrgencyWeights {
+    fn default() -> Self {
+        Self {
+            deadline: 12.0,
+            priority: 6.0,
+            age: 2.0,
+            effort: 1.0,
+        }
+    }
+}
+
+/// An open task with its urgency score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct UrgentTask {
+    pub document_id: String,
+    pub headline_id: String,
+    pub title: String,
+    pub todo_keyword: String,
+    pub tags: Vec<String>,
+    pub urgency: f64,
+}
+
+fn deadline_factor(headline: &OrgHeadline, today: NaiveDate) -> f64 {
+    let Some(deadline) = headline
+        .title
+        .planning
+        .as_ref()
+        .and_then(|planning| planning.deadline.as_ref())
+        .and_then(|deadline| deadline.start_date())
+    else {
+        return 0.0;
+    };
+
+    // Linear from 0.2 at DISTANT_DAYS away to 1.0 at OVERDUE_DAYS overdue
+    let days = (deadline.to_naive_date() - today)
+        .num_days()
+        .clamp(-OVERDUE_DAYS, DISTANT_DAYS);
+    0.2 + 0.8 * (DISTANT_DAYS - days) as f64 / (DISTANT_DAYS + OVERDUE_DAYS) as f64
+}
+
+fn priority_factor(headline: &OrgHeadline) -> f64 {
+    let priority = headline.title.priority.unwrap_or('B').to_ascii_uppercase();
+    if !priority.is_ascii_uppercase() {
+        return 0.0;
+    }
+    let rank = (priority as u8 - b'A') as f64;
+    (1.0 - 0.35 * rank).max(0.0)
+}
+
+// Date of a CREATED value, an org timestamp or an RFC 3339 instant; instants count in the
+// assumed time zone, so one stored just after midnight there is not a day older
+fn created_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    if let Ok(instant) = DateTime::parse_from_rfc3339(value) {
+        return Some(OrgDatetime::from_utc(instant.with_timezone(&Utc)).to_naive_date());
+    }
+    let date = value.trim_start_matches(['[', '<']).get(..10)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+fn age_factor(headline: &OrgHeadline, today: NaiveDate) -> f64 {
+    let created = headline
+        .title
+        .get_property_ignore_case("CREATED")
+        .and_then(created_date);
+    match created {
+        Some(created) => {
+            let days = (today - created).num_days().clamp(0, MAX_AGE_DAYS);
+            days as f64 / MAX_AGE_DAYS as f64
+        }
+        None => 0.0,
+    }
+}
+
+fn effort_factor(headline: &OrgHeadline) -> f64 {
+    match headline
+        .title
+        .get_property_ignore_case("Effort")
+        .and_then(parse_duration_minutes)
+    {
+        Some(minutes) => 1.0 - minutes.min(MAX_EFFORT_MINUTES) as f64 / MAX_EFFORT_MINUTES as f64,
+        None => 0.0,
+    }
+}
+
+/// Urgency of a headline on `today`, rounded to two decimals
+pub fn urgency(headline: &OrgHeadline, weights: &UrgencyWeights, today: NaiveDate) -> f64 {
+    let score = weights.deadline * deadline_factor(headline, today)
+        + weights.priority * priority_factor(headline)
+        + weights.age * age_factor(headline, today)
+        + weights.effort * effort_factor(headline);
+    (score * 100.0).round() / 100.0
+}
+
+// TODO keywords of a document, falling back to the defaults
+fn todo_config(document: &OrgDocument) -> TodoConfiguration {
+    document
+        .todo_config
+        .clone()
+        .unwrap_or_else(TodoConfiguration::default)
+}
+
+/// Urgency of a headline, or None if it is not a task in an active TODO state
+pub fn task_urgency(
+    document: &OrgDocument,
+    headline: &OrgHeadline,
+    weights: &UrgencyWeights,
+    today: NaiveDate,
+) -> Option<f64> {
+    headline
+        .get_todo_status(&todo_config(document))
+        .filter(|status| status.is_active())
+        .map(|_| urgency(headline, weights, today))
+}
+
+/// Collect tasks in an active TODO state, most urgent first
+/// Tasks deferred past `today` and missing, read-only archived and `no-agenda` documents
+/// are skipped
+pub fn collect_urgent_tasks(
+    documents: &[&OrgDocument],
+    weights: &UrgencyWeights,
+    today: NaiveDate,
+) -> Vec<UrgentTask> {
+    let mut tasks = Vec::new();
+
+    for document in documents {
+        if document.missing || document.archived || document.flags.no_agenda {
+            continue;
+        }
+        let config = todo_config(document);
+        let deferrals = deferrals(document);
+
+        for headline in flatten_headlines(&document.headlines) {
+            let Some(status) = headline.get_todo_status(&config) else {
+                continue;
+            };
+            let deferred = deferrals
+                .get(&headline.id)
+                .is_some_and(|until| *until > today);
+            if !status.is_active() || deferred {
+                continue;
+            }
+            tasks.push(UrgentTask {
+                document_id: document.id.clone(),
+                headline_id: headline.id.clone(),
+                title: headline.title.raw.clone(),
+                todo_keyword: status.keyword.clone(),
+                tags: headline
+                    .title
+                    .tags
+                    .iter()
+                    .map(|tag| tag.to_string())
+                    .collect(),
+                urgency: urgency(headline, weights, today),
+            });
+        }
+    }
+
+    // Most urgent first, then in file order
+    tasks.sort_by(|a, b| {
+        b.urgency
+            .total_cmp(&a.urgency)
+            .then_with(|| a.document_id.cmp(&b.document_id))
+    });
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const TASKS: &str = "* TODO [#A] Ship release\nDEADLINE: <2024-01-08 Mon>\n* TODO Tidy desk\n:PROPERTIES:\n:Effort: 0:15\n:CREATED: [2023-07-03 Mon 09:00]\n:END:\n* TODO [#C] Someday\n* DONE [#A] Finished\nDEADLINE: <2024-01-01 Mon>\n* Notes\n";
+
+    #[test]
+    fn test_collect_urgent_tasks() {
+        let doc = parse_org_document(TASKS, Some("tasks.org")).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let tasks = collect_urgent_tasks(&[&doc], &UrgencyWeights::default(), today);
+
+        let titles: Vec<&str> = tasks.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles, vec!["Ship release", "Tidy desk", "Someday"]);
+
+        // Deadline a week away: 12 * (0.2 + 0.8 * 7 / 21), plus full priority
+        assert_eq!(tasks[0].urgency, 11.6);
+        // Default [#B] priority, half a year old, a 15 minute effort
+        assert_eq!(tasks[1].urgency, 5.87);
+        assert_eq!(tasks[2].urgency, 1.8);
+    }
+
+    #[test]
+    fn test_deadline_factor_is_clamped() {
+        let doc = parse_org_document(TASKS, Some("tasks.org")).unwrap();
+        let weights = UrgencyWeights {
+            deadline: 1.0,
+            priority: 0.0,
+            age: 0.0,
+            effort: 0.0,
+        };
+        let headline = &doc.headlines[0];
+        let far = NaiveDate::from_ymd_opt(2023, 6, 1).unwrap();
+        let late = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(urgency(headline, &weights, far), 0.2);
+        assert_eq!(urgency(headline, &weights, late), 1.0);
+    }
+}
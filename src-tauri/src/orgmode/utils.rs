@@ -25,6 +25,16 @@ pub fn generate_headline_etag(headline: &OrgHeadline) -> String {
     format!("{:x}", hasher.finish())
 }
 
+/// Generate etag for a headline from its parsed fields and its raw section source
+/// Including the source catches changes the parsed fields don't capture, such as
+/// LOGBOOK drawers or reformatted planning lines
+pub fn generate_section_etag(headline: &OrgHeadline, section: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    generate_headline_etag(headline).hash(&mut hasher);
+    section.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,130 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::utils::generate_document_etag;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// How a document differs from its file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DriftKind {
+    Changed,    // File content no longer matches the stored etag
+    Missing,    // File is gone but the document is not marked missing
+    Reappeared, // Document is marked missing but the file exists again
+    Unreadable, // File exists but could not be read
+}
+
+/// A document whose stored state has drifted from disk
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DriftedDocument {
+    pub document_id: String,
+    pub file_path: String,
+    pub kind: DriftKind,
+    pub repaired: bool, // Whether the document was reparsed or marked missing
+}
+
+/// Result of comparing the repository against the files on disk
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct VerificationReport {
+    pub checked: u32,
+    pub drifted: Vec<DriftedDocument>,
+}
+
+/// The stored state of a document that verification compares with disk
+/// It is copied out of the repository so the files can be read without holding its lock.
+#[derive(Debug, Clone)]
+pub struct StoredDocument {
+    pub id: String,
+    pub file_path: String,
+    pub etag: String,
+    pub missing: bool,
+}
+
+impl From<&OrgDocument> for StoredDocument {
+    fn from(document: &OrgDocument) -> Self {
+        Self {
+            id: document.id.clone(),
+            file_path: document.file_path.clone(),
+            etag: document.etag.clone(),
+            missing: document.missing,
+        }
+    }
+}
+
+/// Re-hash a document's file and compare it with the stored etag
+pub fn check_document(document: &StoredDocument) -> Option<DriftKind> {
+    let path = Path::new(&document.file_path);
+    if !path.exists() {
+        return (!document.missing).then_some(DriftKind::Missing);
+    }
+    if document.missing {
+        return Some(DriftKind::Reappeared);
+    }
+
+    match fs::read_to_string(path) {
+        Ok(content) if generate_document_etag(&content) == document.etag => None,
+        Ok(_) => Some(DriftKind::Changed),
+        Err(_) => Some(DriftKind::Unreadable),
+    }
+}
+
+/// Check every document, sorted by file path
+pub fn verify_documents(mut documents: Vec<StoredDocument>) -> VerificationReport {
+    documents.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    let drifted = documents
+        .iter()
+        .filter_map(|document| {
+            check_document(document).map(|kind| DriftedDocument {
+                document_id: document.id.clone(),
+                file_path: document.file_path.clone(),
+                kind,
+                repaired: false,
+            })
+        })
+        .collect();
+
+    VerificationReport {
+        checked: documents.len() as u32,
+        drifted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_verify_documents() {
+        let dir = tempdir().unwrap();
+        let same = dir.path().join("same.org");
+        let changed = dir.path().join("changed.org");
+        let gone = dir.path().join("gone.org");
+
+        fs::write(&same, "* Same\n").unwrap();
+        fs::write(&changed, "* Edited while asleep\n").unwrap();
+
+        let same_doc = parse_org_document("* Same\n", same.to_str()).unwrap();
+        let changed_doc = parse_org_document("* Before\n", changed.to_str()).unwrap();
+        let gone_doc = parse_org_document("* Gone\n", gone.to_str()).unwrap();
+
+        let report = verify_documents(
+            [&same_doc, &gone_doc, &changed_doc]
+                .into_iter()
+                .map(StoredDocument::from)
+                .collect(),
+        );
+        assert_eq!(report.checked, 3);
+
+        let kinds: Vec<DriftKind> = report.drifted.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, vec![DriftKind::Changed, DriftKind::Missing]);
+
+        let mut missing_doc = StoredDocument::from(&gone_doc);
+        missing_doc.missing = true;
+        assert_eq!(check_document(&missing_doc), None);
+        fs::write(&gone, "* Back\n").unwrap();
+        assert_eq!(check_document(&missing_doc), Some(DriftKind::Reappeared));
+    }
+}
@@ -0,0 +1,167 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::source::{flatten_headlines, headline_level, source_lines};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+/// Average silent reading speed used for reading time estimates
+const WORDS_PER_MINUTE: u32 = 200;
+
+/// Word, character and reading time counts for a span of prose
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct TextStats {
+    pub words: u32,
+    pub characters: u32, // Including spaces, excluding line breaks
+    pub reading_minutes: u32,
+}
+
+impl TextStats {
+    fn add_text(&mut self, text: &str) {
+        self.words += text.split_whitespace().count() as u32;
+        self.characters += text.trim().chars().count() as u32;
+        self.reading_minutes = self.words.div_ceil(WORDS_PER_MINUTE);
+    }
+
+    fn add(&mut self, other: &TextStats) {
+        self.words += other.words;
+        self.characters += other.characters;
+        self.reading_minutes = self.words.div_ceil(WORDS_PER_MINUTE);
+    }
+}
+
+/// Counts for a headline and everything below it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SubtreeStats {
+    pub headline_id: String,
+    pub title: String,
+    pub level: u32,
+    pub stats: TextStats,
+}
+
+/// Counts for a whole document and each of its subtrees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DocumentStats {
+    pub document_id: String,
+    pub total: TextStats,
+    pub subtrees: Vec<SubtreeStats>, // In file order
+}
+
+// Lines that are markup rather than prose: keywords, comments, planning and drawers
+fn is_markup(line: &str, in_drawer: &mut bool) -> bool {
+    let trimmed = line.trim();
+    if *in_drawer {
+        if trimmed.eq_ignore_ascii_case(":END:") {
+            *in_drawer = false;
+        }
+        return true;
+    }
+    if trimmed.len() > 2
+        && trimmed.starts_with(':')
+        && trimmed.ends_with(':')
+        && trimmed[1..trimmed.len() - 1]
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        *in_drawer = true;
+        return true;
+    }
+    trimmed.starts_with("#+")
+        || trimmed == "#"
+        || trimmed.starts_with("# ")
+        || ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+            .iter()
+            .any(|keyword| trimmed.starts_with(keyword))
+}
+
+/// Count the prose of a document and each subtree
+/// Headline titles and body text count; keywords, comments, planning lines and drawers don't.
+/// Needs the document's full content, so compute it before the content is evicted.
+pub fn document_stats(document: &OrgDocument) -> DocumentStats {
+    let headlines: HashMap<&str, &OrgHeadline> = flatten_headlines(&document.headlines)
+        .into_iter()
+        .map(|headline| (headline.id.as_str(), headline))
+        .collect();
+
+    let mut own: HashMap<&str, TextStats> = HashMap::new();
+    let mut preamble = TextStats::default();
+    let mut in_drawer = false;
+    for line in source_lines(document) {
+        let text = if headline_level(line.text).is_some() {
+            in_drawer = false;
+            line.headline_id
+                .and_then(|id| headlines.get(id))
+                .map_or("", |headline| headline.title.raw.as_str())
+        } else if is_markup(line.text, &mut in_drawer) {
+            continue;
+        } else {
+            line.text
+        };
+        match line.headline_id {
+            Some(id) => own.entry(id).or_default().add_text(text),
+            None => preamble.add_text(text),
+        }
+    }
+
+    let mut subtrees = Vec::new();
+    let mut total = preamble;
+    for headline in &document.headlines {
+        total.add(&collect_subtrees(headline, 1, &own, &mut subtrees));
+    }
+
+    DocumentStats {
+        document_id: document.id.clone(),
+        total,
+        subtrees,
+    }
+}
+
+// Add a headline's subtree and its descendants in pre-order, returning the subtree total
+fn collect_subtrees(
+    headline: &OrgHeadline,
+    level: u32,
+    own: &HashMap<&str, TextStats>,
+    subtrees: &mut Vec<SubtreeStats>,
+) -> TextStats {
+    let index = subtrees.len();
+    subtrees.push(SubtreeStats {
+        headline_id: headline.id.clone(),
+        title: headline.title.raw.clone(),
+        level,
+        stats: TextStats::default(),
+    });
+
+    let mut stats = own.get(headline.id.as_str()).copied().unwrap_or_default();
+    for child in &headline.children {
+        stats.add(&collect_subtrees(child, level + 1, own, subtrees));
+    }
+    subtrees[index].stats = stats;
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    #[test]
+    fn test_document_stats() {
+        let doc = parse_org_document(
+            "#+TITLE: Novel\nA short foreword.\n* TODO Chapter one :draft:\nSCHEDULED: <2024-01-01 Mon>\n:PROPERTIES:\n:WORDS: 5000\n:END:\nIt was a dark night.\n# Note to self\n** Scene\nRain fell.\n* Chapter two\n",
+            Some("novel.org"),
+        )
+        .unwrap();
+        let stats = document_stats(&doc);
+
+        // Foreword (3) + "Chapter one" (2) + body (5) + "Scene" (1) + body (2) + "Chapter two" (2)
+        assert_eq!(stats.total.words, 15);
+        assert_eq!(stats.total.reading_minutes, 1);
+
+        let chapter = &stats.subtrees[0];
+        assert_eq!((chapter.title.as_str(), chapter.level), ("Chapter one", 1));
+        assert_eq!(chapter.stats.words, 10);
+        assert_eq!(stats.subtrees[1].stats.words, 3);
+        assert_eq!(stats.subtrees[1].stats.characters, 15);
+        assert_eq!(stats.subtrees[2].level, 1);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::orgmode::datetime::OrgDatetime;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+
+/// A day off
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct Holiday {
+    pub date: String, // YYYY-MM-DD
+    pub name: String,
+}
+
+/// When the user works, for the planner and day views
+/// Weekend days and holidays are not working days
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct WorkSchedule {
+    pub workdays: Vec<u32>, // ISO weekdays, 1 for Monday to 7 for Sunday
+    pub day_start: String,  // HH:MM
+    pub day_end: String,    // HH:MM
+    pub holidays: Vec<Holiday>,
+    pub holiday_feed: Option<String>, // Path of an .ics calendar whose events are holidays
+}
+
+impl Default for WorkSchedule {
+    fn default() -> Self {
+        Self {
+            workdays: vec![1, 2, 3, 4, 5],
+            day_start: "09:00".to_string(),
+            day_end: "17:00".to_string(),
+            holidays: Vec::new(),
+            holiday_feed: None,
+        }
+    }
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+impl WorkSchedule {
+    /// Check the weekdays, hours and holiday dates, returning the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(day) = self.workdays.iter().find(|day| !(1..=7).contains(*day)) {
+            return Err(format!(
+                "Invalid weekday: {} (use 1 for Monday to 7 for Sunday)",
+                day
+            ));
+        }
+        let (Some(start), Some(end)) = (parse_time(&self.day_start), parse_time(&self.day_end))
+        else {
+            return Err("Working hours must be given as HH:MM".to_string());
+        };
+        if start >= end {
+            return Err("The working day must end after it starts".to_string());
+        }
+        if let Some(holiday) = self
+            .holidays
+            .iter()
+            .find(|holiday| NaiveDate::parse_from_str(&holiday.date, "%Y-%m-%d").is_err())
+        {
+            return Err(format!("Invalid holiday date: '{}'", holiday.date));
+        }
+        Ok(())
+    }
+
+    /// Length of a working day in minutes, or 0 if the hours don't parse
+    pub fn working_minutes(&self) -> u32 {
+        match (parse_time(&self.day_start), parse_time(&self.day_end)) {
+            (Some(start), Some(end)) if start < end => (end - start).num_minutes() as u32,
+            _ => 0,
+        }
+    }
+
+    /// Name of the holiday on a date, if any
+    pub fn holiday(&self, date: NaiveDate) -> Option<&str> {
+        let date = date.format("%Y-%m-%d").to_string();
+        self.holidays
+            .iter()
+            .find(|holiday| holiday.date == date)
+            .map(|holiday| holiday.name.as_str())
+    }
+
+    /// Whether a date is a workday that is not a holiday
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.workdays.contains(&date.weekday().number_from_monday()) && self.holiday(date).is_none()
+    }
+
+    /// Add the holidays of the `.ics` feed, if one is set
+    /// An unreadable feed is logged and skipped, so the configured holidays still apply
+    pub fn with_feed_holidays(mut self) -> Self {
+        if let Some(path) = &self.holiday_feed {
+            match fs::read_to_string(path) {
+                Ok(calendar) => self.holidays.extend(parse_ics_holidays(&calendar)),
+                Err(e) => eprintln!("Failed to read holiday feed {}: {}", path, e),
+            }
+        }
+        self
+    }
+}
+
+// Date of an iCalendar DTSTART/DTEND value such as `20241225` or `20241225T000000Z`
+// UTC times fall on their date in the assumed time zone; floating times keep their own
+fn ics_date(value: &str) -> Option<NaiveDate> {
+    if let Some(utc) = value.strip_suffix(['Z', 'z']) {
+        let instant = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(OrgDatetime::from_utc(instant.and_utc()).to_naive_date());
+    }
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Read the events of an iCalendar file as holidays, one per day they cover
+/// DTEND is exclusive as in RFC 5545; events without it last one day
+pub fn parse_ics_holidays(calendar: &str) -> Vec<Holiday> {
+    // Unfold continuation lines, which start with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in calendar.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+
+    let mut holidays = Vec::new();
+    let (mut start, mut end, mut name) = (None, None, String::new());
+    for line in &lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters such as `;VALUE=DATE` follow the property name
+        let key = key.split(';').next().unwrap_or(key).to_ascii_uppercase();
+        match key.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VEVENT") => {
+                (start, end, name) = (None, None, String::new());
+            }
+            "DTSTART" => start = ics_date(value),
+            "DTEND" => end = ics_date(value),
+            "SUMMARY" => name = value.replace("\\,", ",").replace("\\;", ";"),
+            "END" if value.eq_ignore_ascii_case("VEVENT") => {
+                let Some(first) = start else {
+                    continue;
+                };
+                let last = end
+                    .and_then(|end| end.pred_opt())
+                    .filter(|last| *last >= first);
+                let mut day = first;
+                while day <= last.unwrap_or(first) {
+                    holidays.push(Holiday {
+                        date: day.format("%Y-%m-%d").to_string(),
+                        name: name.clone(),
+                    });
+                    match day.succ_opt() {
+                        Some(next) => day = next,
+                        None => break,
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    holidays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_working_days() {
+        let schedule = WorkSchedule {
+            holidays: vec![Holiday {
+                date: "2024-12-25".to_string(),
+                name: "Christmas".to_string(),
+            }],
+            ..WorkSchedule::default()
+        };
+        let date = |day| NaiveDate::from_ymd_opt(2024, 12, day).unwrap();
+        assert!(schedule.is_working_day(date(24)));
+        assert!(!schedule.is_working_day(date(25)));
+        assert!(!schedule.is_working_day(date(28))); // Saturday
+        assert_eq!(schedule.holiday(date(25)), Some("Christmas"));
+        assert_eq!(schedule.working_minutes(), 480);
+        assert!(schedule.validate().is_ok());
+
+        let backwards = WorkSchedule {
+            day_start: "18:00".to_string(),
+            ..WorkSchedule::default()
+        };
+        assert!(backwards.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_ics_holidays() {
+        let calendar = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20241225\r\nDTEND;VALUE=DATE:20241227\r\nSUMMARY:Christmas\\, Boxing\r\n  Day\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250101\r\nSUMMARY:New Year\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let holidays = parse_ics_holidays(calendar);
+        let dates: Vec<(&str, &str)> = holidays
+            .iter()
+            .map(|holiday| (holiday.date.as_str(), holiday.name.as_str()))
+            .collect();
+        assert_eq!(
+            dates,
+            vec![
+                ("2024-12-25", "Christmas, Boxing Day"),
+                ("2024-12-26", "Christmas, Boxing Day"),
+                ("2025-01-01", "New Year"),
+            ]
+        );
+    }
+}
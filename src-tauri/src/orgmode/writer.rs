@@ -0,0 +1,327 @@
+use crate::orgmode::document::OrgDocument;
+use crate::orgmode::headline::OrgHeadline;
+use crate::orgmode::parser::{
+    extract_todo_keywords_from_content, parse_org_document_with_keywords,
+};
+use crate::orgmode::planning::OrgPlanning;
+use crate::orgmode::properties::{is_planning_line, property_key};
+use crate::orgmode::source::{flatten_headlines, headline_level};
+use crate::orgmode::title::OrgTitle;
+use crate::orgmode::utils::generate_section_etag;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// The parts of a headline's own section in the source, with their line endings
+struct SectionParts<'a> {
+    headline: &'a str,
+    planning: &'a str, // Empty if there is no planning line
+    drawer: &'a str,   // The whole :PROPERTIES: drawer, or empty
+    body: &'a str,
+}
+
+impl<'a> SectionParts<'a> {
+    fn split(section: &'a str) -> Self {
+        let first_line = |text: &'a str| text.split_inclusive('\n').next().unwrap_or("");
+
+        let headline = first_line(section);
+        let rest = &section[headline.len()..];
+        let planning = Some(first_line(rest))
+            .filter(|line| is_planning_line(line))
+            .unwrap_or("");
+        let rest = &rest[planning.len()..];
+
+        let mut drawer_len = 0;
+        if first_line(rest).trim().eq_ignore_ascii_case(":PROPERTIES:") {
+            let mut len = 0;
+            for line in rest.split_inclusive('\n') {
+                len += line.len();
+                if line.trim().eq_ignore_ascii_case(":END:") {
+                    drawer_len = len;
+                    break;
+                }
+            }
+        }
+
+        Self {
+            headline,
+            planning,
+            drawer: &rest[..drawer_len],
+            body: &rest[drawer_len..],
+        }
+    }
+
+    fn line_ending(&self) -> &'static str {
+        if self.headline.ends_with("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+}
+
+// Split the source into the preamble before the first headline and each headline's
+// own section, matched to headlines in file order like `source_lines`
+fn split_source(document: &OrgDocument) -> (&str, HashMap<&str, &str>) {
+    let content = document.content.as_str();
+    let mut headlines = flatten_headlines(&document.headlines).into_iter();
+    let mut sections = HashMap::new();
+    let mut current: Option<(&str, usize)> = None;
+    let mut preamble_end = content.len();
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        if headline_level(line.trim_end_matches(['\r', '\n'])).is_some() {
+            match current.take() {
+                Some((id, start)) => {
+                    sections.insert(id, &content[start..offset]);
+                }
+                None => preamble_end = offset,
+            }
+            current = headlines
+                .next()
+                .map(|headline| (headline.id.as_str(), offset));
+        }
+        offset += line.len();
+    }
+    if let Some((id, start)) = current {
+        sections.insert(id, &content[start..]);
+    }
+
+    (&content[..preamble_end], sections)
+}
+
+/// Render a headline line from its title, without a line ending
+/// Tags are separated from the title by a single space rather than aligned
+pub fn headline_line(title: &OrgTitle) -> String {
+    let mut line = "*".repeat(title.level.max(1) as usize);
+    if let Some(keyword) = &title.todo_keyword {
+        line.push(' ');
+        line.push_str(keyword);
+    }
+    if let Some(priority) = title.priority {
+        line.push_str(&format!(" [#{}]", priority));
+    }
+    if !title.raw.is_empty() {
+        line.push(' ');
+        line.push_str(&title.raw);
+    }
+    if !title.tags.is_empty() {
+        let tags: Vec<&str> = title.tags.iter().map(|tag| tag.as_ref()).collect();
+        line.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    line
+}
+
+/// Render a planning line, or None if nothing is planned
+pub fn planning_line(planning: Option<&OrgPlanning>) -> Option<String> {
+    let planning = planning?;
+    let items: Vec<String> = [
+        ("CLOSED:", planning.formatted_closed()),
+        ("DEADLINE:", planning.formatted_deadline()),
+        ("SCHEDULED:", planning.formatted_scheduled()),
+    ]
+    .into_iter()
+    .filter_map(|(keyword, timestamp)| Some(format!("{} {}", keyword, timestamp?)))
+    .collect();
+    (!items.is_empty()).then(|| items.join(" "))
+}
+
+// Render a property drawer, keeping the indentation and key order of the original drawer
+// and adding new keys in sorted order
+fn property_drawer(properties: &HashMap<Arc<str>, String>, original: &str, eol: &str) -> String {
+    if properties.is_empty() {
+        return String::new();
+    }
+    let indent = original
+        .lines()
+        .next()
+        .map_or("", |line| &line[..line.len() - line.trim_start().len()]);
+
+    let mut keys: Vec<&str> = original
+        .lines()
+        .filter_map(property_key)
+        .filter(|key| properties.contains_key(*key))
+        .collect();
+    let mut added: Vec<&str> = properties
+        .keys()
+        .map(|key| key.as_ref())
+        .filter(|key| !keys.contains(key))
+        .collect();
+    added.sort_unstable();
+    keys.extend(added);
+
+    let mut drawer = format!("{}:PROPERTIES:{}", indent, eol);
+    for key in keys {
+        drawer.push_str(&format!("{}:{}: {}{}", indent, key, properties[key], eol));
+    }
+    drawer.push_str(&format!("{}:END:{}", indent, eol));
+    drawer
+}
+
+// Write one headline's section, reusing each part of the original whose parsed value is
+// unchanged in the model
+fn format_section(
+    headline: &OrgHeadline,
+    original: Option<&str>,
+    keywords: &(Vec<String>, Vec<String>),
+) -> String {
+    let Some(original) = original else {
+        return format_new_section(headline);
+    };
+    // The etag covers the parsed fields and the source, so a match means nothing changed
+    let source: String = original.lines().flat_map(|line| [line, "\n"]).collect();
+    if generate_section_etag(headline, &source) == headline.etag {
+        return original.to_string();
+    }
+    let parsed = parse_org_document_with_keywords(original, None, keywords.clone())
+        .ok()
+        .and_then(|document| document.headlines.into_iter().next());
+    let Some(parsed) = parsed else {
+        return format_new_section(headline);
+    };
+
+    let parts = SectionParts::split(original);
+    let eol = parts.line_ending();
+    let mut output = String::with_capacity(original.len());
+
+    if headline_line(&parsed.title) == headline_line(&headline.title) {
+        output.push_str(parts.headline);
+    } else {
+        output.push_str(&headline_line(&headline.title));
+        output.push_str(eol);
+    }
+
+    let planning = planning_line(headline.title.planning.as_deref());
+    if planning_line(parsed.title.planning.as_deref()) == planning {
+        output.push_str(parts.planning);
+    } else if let Some(planning) = planning {
+        let indent = &parts.planning[..parts.planning.len() - parts.planning.trim_start().len()];
+        output.push_str(indent);
+        output.push_str(&planning);
+        output.push_str(eol);
+    }
+
+    if parsed.title.properties == headline.title.properties {
+        output.push_str(parts.drawer);
+    } else {
+        output.push_str(&property_drawer(
+            &headline.title.properties,
+            parts.drawer,
+            eol,
+        ));
+    }
+
+    if parsed.content == headline.content {
+        output.push_str(parts.body);
+    } else {
+        if !headline.content.is_empty() {
+            output.push_str(&headline.content.replace('\n', eol));
+            output.push_str(eol);
+        }
+        // Keep the blank lines that separated the section from the next headline
+        let text = parts.body.trim_end_matches(['\r', '\n']);
+        let trailing = &parts.body[text.len()..];
+        if text.is_empty() {
+            output.push_str(trailing);
+        } else {
+            output.push_str(trailing.get(eol.len()..).unwrap_or(""));
+        }
+    }
+
+    output
+}
+
+// Write a section for a headline that has no source yet
+fn format_new_section(headline: &OrgHeadline) -> String {
+    let mut output = headline_line(&headline.title);
+    output.push('\n');
+    if let Some(planning) = planning_line(headline.title.planning.as_deref()) {
+        output.push_str(&planning);
+        output.push('\n');
+    }
+    output.push_str(&property_drawer(&headline.title.properties, "", "\n"));
+    if !headline.content.is_empty() {
+        output.push_str(&headline.content);
+        output.push('\n');
+    }
+    output
+}
+
+/// Regenerate the org text of a document from its parsed model
+///
+/// `document.content` must be the text the headlines were parsed from. Each part of a
+/// headline's section (headline line, planning line, property drawer and body) is copied
+/// from it byte for byte while the model still matches it, and written from the model
+/// otherwise, so edits to the model only touch the lines they change. Headlines missing
+/// from the model are dropped, and headlines without source are written from scratch.
+pub fn format_document(document: &OrgDocument) -> String {
+    let (preamble, sections) = split_source(document);
+    let keywords = match &document.todo_config {
+        Some(config) => config.keyword_lists(),
+        None => extract_todo_keywords_from_content(&document.content),
+    };
+
+    let mut output = String::with_capacity(document.content.len());
+    output.push_str(preamble);
+    for headline in flatten_headlines(&document.headlines) {
+        let original = sections.get(headline.id.as_str()).copied();
+        output.push_str(&format_section(headline, original, &keywords));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orgmode::parser::parse_org_document;
+
+    const CONTENT: &str = "#+TITLE: Round trip\n\n* TODO [#A] Write report                        :work:\n  DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>\n:PROPERTIES:\n:Effort:   2:00\n:ID:       report\n:END:\n  Draft   the *summary* first.\n\n** Collect numbers\n:LOGBOOK:\nCLOCK: [2024-03-04 Mon 09:00]--[2024-03-04 Mon 10:00] =>  1:00\n:END:\n* Notes\nLoose   ends";
+
+    #[test]
+    fn test_unmodified_round_trip() {
+        let doc = parse_org_document(CONTENT, Some("round.org")).unwrap();
+        assert_eq!(format_document(&doc), CONTENT);
+
+        let crlf = CONTENT.replace('\n', "\r\n");
+        let doc = parse_org_document(&crlf, Some("round.org")).unwrap();
+        assert_eq!(format_document(&doc), crlf);
+    }
+
+    #[test]
+    fn test_modified_parts() {
+        let mut doc = parse_org_document(CONTENT, Some("round.org")).unwrap();
+        let report = &mut doc.headlines[0];
+        report.title.todo_keyword = Some(Arc::from("DONE"));
+        report
+            .title
+            .properties
+            .insert(Arc::from("Effort"), "3:00".to_string());
+        report.children[0].content = "Numbers are in.".to_string();
+        doc.headlines.remove(1);
+
+        let output = format_document(&doc);
+        // Changed parts are rewritten, everything else is kept as it was
+        assert_eq!(
+            output,
+            "#+TITLE: Round trip\n\n* DONE [#A] Write report :work:\n  DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>\n:PROPERTIES:\n:Effort: 3:00\n:ID: report\n:END:\n  Draft   the *summary* first.\n\n** Collect numbers\nNumbers are in.\n"
+        );
+    }
+
+    #[test]
+    fn test_new_headline() {
+        let mut doc = parse_org_document("* Inbox\n", Some("inbox.org")).unwrap();
+        let mut child = doc.headlines[0].clone();
+        child.id = String::new();
+        child.title.raw = "Call Bob".to_string();
+        child.title.level = 2;
+        child.title.todo_keyword = Some(Arc::from("TODO"));
+        child.title.tags = vec![Arc::from("phone")];
+        child.content = "About the invoice".to_string();
+        doc.headlines[0].children.push(child);
+
+        assert_eq!(
+            format_document(&doc),
+            "* Inbox\n** TODO Call Bob :phone:\nAbout the invoice\n"
+        );
+    }
+}
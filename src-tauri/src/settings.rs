@@ -1,7 +1,28 @@
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
+use crate::orgmode::bookmarks::BookmarkConfig;
+use crate::orgmode::colors::StatusStyles;
+use crate::orgmode::conventions::FormatConventions;
+use crate::orgmode::digest::DigestConfig;
+use crate::orgmode::document::is_archive_path;
+use crate::orgmode::effort::{is_valid_effort, DEFAULT_EFFORT_PRESETS};
+use crate::orgmode::favorites::Favorite;
+use crate::orgmode::github_sync::GitHubSyncConfig;
+use crate::orgmode::hooks::HookConfig;
+use crate::orgmode::html::HtmlSanitizeConfig;
+use crate::orgmode::ignore::IgnoredTags;
+use crate::orgmode::issues::{IssueConfig, ISSUE_PROPERTIES};
+use crate::orgmode::journal::JournalConfig;
+use crate::orgmode::locale::DateLocale;
+use crate::orgmode::planner::PlannerConfig;
+use crate::orgmode::properties::{is_valid_property_key, PropertyType};
+use crate::orgmode::sort::{SortKey, SortRule};
+use crate::orgmode::todo::TodoConfiguration;
+use crate::orgmode::urgency::{UrgencyWeights, URGENCY_COLUMN};
+use crate::orgmode::work_schedule::WorkSchedule;
 use notify::RecursiveMode;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tauri_plugin_store::StoreExt;
 use thiserror::Error;
@@ -23,6 +44,45 @@ impl TableColumnConfig {
     }
 }
 
+/// Template used when creating a new org file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+pub struct FileTemplate {
+    /// Unique template name
+    pub name: String,
+    /// Value for #+TITLE; None uses the file name
+    pub title: Option<String>,
+    /// Tags for #+FILETAGS
+    pub filetags: Vec<String>,
+    /// Value for #+CATEGORY
+    pub category: Option<String>,
+    /// Starter headlines, written as given (e.g. "* Tasks")
+    pub headlines: Vec<String>,
+}
+
+impl FileTemplate {
+    /// Render the file content, using `default_title` when the template has no title
+    pub fn render(&self, default_title: &str) -> String {
+        let mut content = format!(
+            "#+TITLE: {}\n",
+            self.title.as_deref().unwrap_or(default_title)
+        );
+        if !self.filetags.is_empty() {
+            content.push_str(&format!("#+FILETAGS: :{}:\n", self.filetags.join(":")));
+        }
+        if let Some(category) = &self.category {
+            content.push_str(&format!("#+CATEGORY: {}\n", category));
+        }
+        if !self.headlines.is_empty() {
+            content.push('\n');
+            for headline in &self.headlines {
+                content.push_str(headline);
+                content.push('\n');
+            }
+        }
+        content
+    }
+}
+
 /// Configuration for TODO keywords
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
 pub struct TodoKeywords {
@@ -51,6 +111,21 @@ impl TodoKeywords {
         Self::default()
     }
 
+    /// Build a TODO configuration, falling back to TODO/DONE for empty lists
+    pub fn to_todo_configuration(&self) -> TodoConfiguration {
+        let or_default = |keywords: &Vec<String>, default: &str| {
+            if keywords.is_empty() {
+                vec![default.to_string()]
+            } else {
+                keywords.clone()
+            }
+        };
+        TodoConfiguration::from_keywords(
+            &or_default(&self.active, "TODO"),
+            &or_default(&self.closed, "DONE"),
+        )
+    }
+
     /// Get all keywords (active + closed)
     pub fn all_keywords(&self) -> Vec<String> {
         let mut all = self.active.clone();
@@ -326,10 +401,56 @@ pub struct UserSettings {
     pub todo_keywords: TodoKeywords,
     /// Custom headline properties
     pub custom_properties: Vec<String>,
+    /// Declared types of custom properties; untyped properties are strings
+    pub custom_property_types: BTreeMap<String, PropertyType>,
     /// Command to open files in an external editor
     pub external_editor_command: String,
     /// Table column configuration
     pub table_columns: Vec<TableColumnConfig>,
+    /// Whether to parse *.org_archive files as read-only archived documents
+    pub parse_archive_files: bool,
+    /// Keep body content for at most this many recently opened documents; None keeps all
+    pub max_loaded_documents: Option<u32>,
+    /// Templates offered when creating a new org file
+    pub file_templates: Vec<FileTemplate>,
+    /// Journal directory and file layout
+    pub journal: JournalConfig,
+    /// Files holding org-contacts entries; empty searches all documents
+    pub contacts_files: Vec<String>,
+    /// What org content rendered as HTML may contain
+    pub html_sanitize: HtmlSanitizeConfig,
+    /// Headlines pinned to the top of the UI
+    pub favorites: Vec<Favorite>,
+    /// How deadline, priority, age and effort add up to a task's urgency
+    pub urgency_weights: UrgencyWeights,
+    /// Effort values offered for one-click assignment, unless a file sets `Effort_ALL`
+    pub effort_presets: Vec<String>,
+    /// Daily load and horizon of the optional auto-scheduling planner
+    pub planner: PlannerConfig,
+    /// Workdays, working hours and holidays, for the planner and the timeline
+    pub work_schedule: WorkSchedule,
+    /// First day of the week and the language of day names in new timestamps
+    pub date_locale: DateLocale,
+    /// IANA time zone org timestamps are in, e.g. `Europe/Berlin`; None for the system's
+    pub time_zone: Option<String>,
+    /// Period, format and morning auto-copy of the deadline digest
+    pub digest: DigestConfig,
+    /// Shell commands run when a task is done, text is captured or a file changes
+    pub hooks: HookConfig,
+    /// Local endpoint the browser bookmarklet files read-later entries through
+    pub bookmarks: BookmarkConfig,
+    /// Tokens and refresh interval for fetching the state of linked GitHub and Jira issues
+    pub issues: IssueConfig,
+    /// Repositories whose assigned GitHub issues are mirrored to an org file, and how often
+    pub github_sync: GitHubSyncConfig,
+    /// How edited headlines lay out planning lines, drawers and tags
+    pub format_conventions: FormatConventions,
+    /// Tags whose headlines are left out of the agenda, next actions and searches
+    pub ignored_tags: IgnoredTags,
+    /// Order of listed headlines when the caller gives none; empty keeps file order
+    pub default_sort: Vec<SortRule>,
+    /// Light and dark colors and icons chosen for TODO keywords and tags
+    pub status_styles: StatusStyles,
 }
 
 impl Default for UserSettings {
@@ -338,8 +459,31 @@ impl Default for UserSettings {
             monitored_paths: Vec::new(),
             todo_keywords: TodoKeywords::default(),
             custom_properties: Vec::new(),
+            custom_property_types: BTreeMap::new(),
             external_editor_command: "emacsclient --no-wait +{line}:{column} {file}".to_string(),
             table_columns: Self::default_table_columns(),
+            parse_archive_files: false,
+            max_loaded_documents: None,
+            file_templates: Vec::new(),
+            journal: JournalConfig::default(),
+            contacts_files: Vec::new(),
+            html_sanitize: HtmlSanitizeConfig::default(),
+            favorites: Vec::new(),
+            urgency_weights: UrgencyWeights::default(),
+            effort_presets: DEFAULT_EFFORT_PRESETS.map(String::from).to_vec(),
+            planner: PlannerConfig::default(),
+            work_schedule: WorkSchedule::default(),
+            date_locale: DateLocale::default(),
+            time_zone: None,
+            digest: DigestConfig::default(),
+            hooks: HookConfig::default(),
+            bookmarks: BookmarkConfig::default(),
+            issues: IssueConfig::default(),
+            github_sync: GitHubSyncConfig::default(),
+            format_conventions: FormatConventions::default(),
+            ignored_tags: IgnoredTags::default(),
+            default_sort: Vec::new(),
+            status_styles: StatusStyles::default(),
         }
     }
 }
@@ -394,7 +538,11 @@ impl UserSettings {
         if all_except_current.contains(&new_property) {
             return Err(SettingsError::DuplicateKeyword(new_property));
         }
-        self.custom_properties[index] = new_property;
+        let old_property = std::mem::replace(&mut self.custom_properties[index], new_property);
+        if let Some(property_type) = self.custom_property_types.remove(&old_property) {
+            self.custom_property_types
+                .insert(self.custom_properties[index].clone(), property_type);
+        }
         Ok(())
     }
 
@@ -406,7 +554,8 @@ impl UserSettings {
                 self.custom_properties.len(),
             ));
         }
-        self.custom_properties.remove(index);
+        let property = self.custom_properties.remove(index);
+        self.custom_property_types.remove(&property);
         Ok(())
     }
 
@@ -440,6 +589,49 @@ impl UserSettings {
     /// Reset custom properties to empty (or defaults if desired)
     pub fn reset_custom_properties_to_defaults(&mut self) {
         self.custom_properties.clear();
+        self.custom_property_types.clear();
+    }
+
+    /// Get the declared type of a property, matched case-insensitively
+    pub fn get_custom_property_type(&self, property: &str) -> PropertyType {
+        self.custom_property_types
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(property))
+            .map(|(_, property_type)| *property_type)
+            .unwrap_or_default()
+    }
+
+    /// Declare the type of an existing custom property
+    pub fn set_custom_property_type(
+        &mut self,
+        property: &str,
+        property_type: PropertyType,
+    ) -> Result<(), SettingsError> {
+        if !self.custom_properties.iter().any(|name| name == property) {
+            return Err(SettingsError::InvalidKeyword(format!(
+                "Unknown custom property: {}",
+                property
+            )));
+        }
+        if property_type == PropertyType::String {
+            self.custom_property_types.remove(property);
+        } else {
+            self.custom_property_types
+                .insert(property.to_string(), property_type);
+        }
+        Ok(())
+    }
+
+    /// Check that a value fits the declared type of a property before writing it
+    pub fn validate_property_value(
+        &self,
+        property: &str,
+        value: &str,
+    ) -> Result<(), SettingsError> {
+        self.get_custom_property_type(property)
+            .parse(value)
+            .map(|_| ())
+            .map_err(|message| SettingsError::InvalidPropertyValue(property.to_string(), message))
     }
 
     /// Add a monitored path, preventing duplicates
@@ -509,10 +701,222 @@ impl UserSettings {
             .unwrap_or(false) // Default to false if path not found
     }
 
+    /// Enable or disable parsing of *.org_archive files
+    pub fn set_parse_archive_files(&mut self, enabled: bool) {
+        self.parse_archive_files = enabled;
+    }
+
+    /// Limit how many documents keep their body content loaded
+    pub fn set_max_loaded_documents(&mut self, limit: Option<u32>) {
+        self.max_loaded_documents = limit;
+    }
+
+    /// Replace the journal configuration
+    pub fn set_journal_config(&mut self, journal: JournalConfig) {
+        self.journal = journal;
+    }
+
+    /// Replace the list of contacts files
+    pub fn set_contacts_files(&mut self, files: Vec<String>) {
+        self.contacts_files = files;
+    }
+
+    /// Replace the HTML sanitization allowlist
+    pub fn set_html_sanitize_config(&mut self, config: HtmlSanitizeConfig) {
+        self.html_sanitize = config;
+    }
+
+    /// Replace the urgency score weights
+    pub fn set_urgency_weights(&mut self, weights: UrgencyWeights) {
+        self.urgency_weights = weights;
+    }
+
+    /// Replace the effort presets
+    pub fn set_effort_presets(&mut self, presets: Vec<String>) {
+        self.effort_presets = presets;
+    }
+
+    /// Replace the planner's settings
+    pub fn set_planner_config(&mut self, config: PlannerConfig) {
+        self.planner = config;
+    }
+
+    /// Replace the workdays, working hours and holidays
+    pub fn set_work_schedule(&mut self, schedule: WorkSchedule) {
+        self.work_schedule = schedule;
+    }
+
+    /// Replace the week start and day name language
+    pub fn set_date_locale(&mut self, locale: DateLocale) {
+        self.date_locale = locale;
+    }
+
+    /// Set the time zone org timestamps are in, or None for the system's
+    pub fn set_time_zone(&mut self, time_zone: Option<String>) {
+        self.time_zone = time_zone;
+    }
+
+    /// Replace the digest period, format and auto-copy toggle
+    pub fn set_digest_config(&mut self, config: DigestConfig) {
+        self.digest = config;
+    }
+
+    /// Replace the hook commands and their timeout
+    pub fn set_hook_config(&mut self, config: HookConfig) {
+        self.hooks = config;
+    }
+
+    /// Replace the bookmark endpoint's port, token and inbox
+    pub fn set_bookmark_config(&mut self, config: BookmarkConfig) {
+        self.bookmarks = config;
+    }
+
+    /// Replace the issue tracker tokens and refresh interval
+    pub fn set_issue_config(&mut self, config: IssueConfig) {
+        self.issues = config;
+    }
+
+    /// Replace the GitHub issue sync file, repositories and interval
+    pub fn set_github_sync_config(&mut self, config: GitHubSyncConfig) {
+        self.github_sync = config;
+    }
+
+    /// Replace the layout rules for edited headlines
+    pub fn set_format_conventions(&mut self, conventions: FormatConventions) {
+        self.format_conventions = conventions;
+    }
+
+    /// Replace the ignored tags
+    pub fn set_ignored_tags(&mut self, ignored_tags: IgnoredTags) {
+        self.ignored_tags = ignored_tags;
+    }
+
+    /// Replace the default order of listed headlines
+    pub fn set_default_sort(&mut self, rules: Vec<SortRule>) {
+        self.default_sort = rules;
+    }
+
+    /// Replace the styles chosen for TODO keywords and tags
+    pub fn set_status_styles(&mut self, styles: StatusStyles) {
+        self.status_styles = styles;
+    }
+
+    /// Check each section the way its setter does, for settings replaced as a whole
+    pub fn validate_sections(&self) -> Result<(), String> {
+        self.hooks.validate()?;
+        self.bookmarks.validate()?;
+        self.journal.validate()?;
+        self.issues.validate()?;
+        self.github_sync.validate()?;
+        self.date_locale.validate()?;
+        self.work_schedule.validate()?;
+        self.ignored_tags.validate()?;
+        self.status_styles.validate()?;
+        for rule in &self.default_sort {
+            if let SortKey::Property(property) = &rule.key {
+                if !is_valid_property_key(property) {
+                    return Err(format!("Invalid property name: '{}'", property));
+                }
+            }
+        }
+        if let Some(invalid) = self
+            .effort_presets
+            .iter()
+            .find(|preset| !is_valid_effort(preset))
+        {
+            return Err(format!("Invalid effort: '{}'", invalid));
+        }
+        Ok(())
+    }
+
+    /// A copy without the issue tracker tokens and the bookmark token, for exporting
+    pub fn without_secrets(&self) -> Self {
+        let mut settings = self.clone();
+        settings.issues.github_token = None;
+        settings.issues.jira_token = None;
+        settings.bookmarks.token = String::new();
+        settings
+    }
+
+    /// Take the tokens missing from these settings from `current`, so importing settings
+    /// exported without them keeps this machine's
+    pub fn keep_secrets(&mut self, current: &UserSettings) {
+        if self.issues.github_token.is_none() {
+            self.issues.github_token = current.issues.github_token.clone();
+        }
+        if self.issues.jira_token.is_none() {
+            self.issues.jira_token = current.issues.jira_token.clone();
+        }
+        if self.bookmarks.token.trim().is_empty() {
+            self.bookmarks.token = current.bookmarks.token.clone();
+        }
+    }
+
+    /// Pin a headline, returning false if it is already a favorite
+    pub fn add_favorite(&mut self, favorite: Favorite) -> bool {
+        if self
+            .favorites
+            .iter()
+            .any(|existing| existing.same_target(&favorite))
+        {
+            return false;
+        }
+        self.favorites.push(favorite);
+        true
+    }
+
+    /// Unpin a headline
+    pub fn remove_favorite(&mut self, favorite: &Favorite) -> bool {
+        let initial_len = self.favorites.len();
+        self.favorites
+            .retain(|existing| !existing.same_target(favorite));
+
+        self.favorites.len() < initial_len
+    }
+
+    /// Find a file template by name
+    pub fn get_file_template(&self, name: &str) -> Option<&FileTemplate> {
+        self.file_templates
+            .iter()
+            .find(|template| template.name == name)
+    }
+
+    /// Add a file template, or replace the template with the same name
+    pub fn save_file_template(&mut self, template: FileTemplate) -> Result<(), SettingsError> {
+        if template.name.trim().is_empty() {
+            return Err(SettingsError::InvalidTemplate(
+                "Template name cannot be empty".to_string(),
+            ));
+        }
+
+        match self
+            .file_templates
+            .iter_mut()
+            .find(|existing| existing.name == template.name)
+        {
+            Some(existing) => *existing = template,
+            None => self.file_templates.push(template),
+        }
+        Ok(())
+    }
+
+    /// Remove a file template by name
+    pub fn remove_file_template(&mut self, name: &str) -> bool {
+        let initial_len = self.file_templates.len();
+        self.file_templates.retain(|template| template.name != name);
+
+        self.file_templates.len() < initial_len
+    }
+
     /// Check if a file is covered by any monitored path with parsing enabled
     pub fn is_file_covered(&self, file_path: &str) -> bool {
         let file_path_buf = PathBuf::from(file_path);
 
+        // Archive files are only covered when explicitly enabled
+        if !self.parse_archive_files && is_archive_path(&file_path_buf) {
+            return false;
+        }
+
         for monitored_path in &self.monitored_paths {
             if !monitored_path.parse_enabled {
                 continue;
@@ -673,8 +1077,18 @@ impl UserSettings {
             "document".to_string(),
             "tags".to_string(),
             "date".to_string(),
+            URGENCY_COLUMN.to_string(),
         ];
 
+        // Virtual issue properties, once there are issue states to show
+        if self.issues.enabled {
+            columns.extend(
+                ISSUE_PROPERTIES
+                    .iter()
+                    .map(|property| format!("property:{}", property)),
+            );
+        }
+
         println!(
             "get_available_columns: custom_properties = {:?}",
             self.custom_properties
@@ -718,6 +1132,12 @@ pub enum SettingsError {
 
     #[error("Invalid index {0}, max: {1}")]
     InvalidIndex(usize, usize),
+
+    #[error("Invalid value for property {0}: {1}")]
+    InvalidPropertyValue(String, String),
+
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
 }
 
 /// Settings manager using Tauri Store plugin
@@ -764,6 +1184,15 @@ impl SettingsManager {
         }
     }
 
+    /// Read settings saved by this or an older version, filling in the fields they lack
+    pub fn settings_from_value(
+        &self,
+        value: serde_json::Value,
+    ) -> Result<UserSettings, SettingsError> {
+        serde_json::from_value::<UserSettings>(value.clone())
+            .or_else(|_| self.migrate_settings(value))
+    }
+
     /// Migrate settings from older format that might be missing new fields
     fn migrate_settings(&self, value: serde_json::Value) -> Result<UserSettings, SettingsError> {
         // Try to extract monitored_paths from the old format
@@ -780,6 +1209,12 @@ impl SettingsManager {
             Vec::new()
         };
 
+        // Property types are optional; unreadable types fall back to strings
+        let custom_property_types = value
+            .get("custom_property_types")
+            .and_then(|types| serde_json::from_value(types.clone()).ok())
+            .unwrap_or_default();
+
         // Try to extract external_editor_command from the old format, or use default
         let external_editor_command = if let Some(cmd) = value.get("external_editor_command") {
             serde_json::from_value(cmd.clone())
@@ -796,13 +1231,174 @@ impl SettingsManager {
             UserSettings::default_table_columns()
         };
 
-        // Create settings with default todo_keywords and migrated custom_properties
+        // Keep configured TODO keywords if they can still be read
+        let todo_keywords = if let Some(keywords) = value.get("todo_keywords") {
+            serde_json::from_value(keywords.clone()).unwrap_or_else(|_| TodoKeywords::default())
+        } else {
+            TodoKeywords::default()
+        };
+
+        // Archive parsing is opt-in
+        let parse_archive_files = value
+            .get("parse_archive_files")
+            .and_then(|enabled| enabled.as_bool())
+            .unwrap_or(false);
+
+        // Document eviction is opt-in
+        let max_loaded_documents = value
+            .get("max_loaded_documents")
+            .and_then(|limit| limit.as_u64())
+            .map(|limit| limit.min(u32::MAX as u64) as u32);
+
+        // File templates are optional; unreadable templates are dropped
+        let file_templates = value
+            .get("file_templates")
+            .and_then(|templates| serde_json::from_value(templates.clone()).ok())
+            .unwrap_or_default();
+
+        // Journal settings fall back to defaults when unreadable
+        let journal = value
+            .get("journal")
+            .and_then(|journal| serde_json::from_value(journal.clone()).ok())
+            .unwrap_or_default();
+
+        // Contacts files are optional
+        let contacts_files = value
+            .get("contacts_files")
+            .and_then(|files| serde_json::from_value(files.clone()).ok())
+            .unwrap_or_default();
+
+        // Sanitization settings fall back to the safe defaults
+        let html_sanitize = value
+            .get("html_sanitize")
+            .and_then(|config| serde_json::from_value(config.clone()).ok())
+            .unwrap_or_default();
+
+        // Favorites are optional; unreadable favorites are dropped
+        let favorites = value
+            .get("favorites")
+            .and_then(|favorites| serde_json::from_value(favorites.clone()).ok())
+            .unwrap_or_default();
+
+        // Urgency weights fall back to the defaults when unreadable
+        let urgency_weights = value
+            .get("urgency_weights")
+            .and_then(|weights| serde_json::from_value(weights.clone()).ok())
+            .unwrap_or_default();
+
+        // Effort presets fall back to the defaults when unreadable
+        let effort_presets = value
+            .get("effort_presets")
+            .and_then(|presets| serde_json::from_value(presets.clone()).ok())
+            .unwrap_or_else(|| DEFAULT_EFFORT_PRESETS.map(String::from).to_vec());
+
+        // The planner stays off unless its settings can be read
+        let planner = value
+            .get("planner")
+            .and_then(|planner| serde_json::from_value(planner.clone()).ok())
+            .unwrap_or_default();
+
+        // Settings from before the work schedule get a Monday to Friday, 9 to 5 week
+        let work_schedule = value
+            .get("work_schedule")
+            .and_then(|schedule| serde_json::from_value(schedule.clone()).ok())
+            .unwrap_or_default();
+
+        // Weeks start on Monday with English day names, as before the setting existed
+        let date_locale = value
+            .get("date_locale")
+            .and_then(|locale| serde_json::from_value(locale.clone()).ok())
+            .unwrap_or_default();
+
+        let time_zone = value
+            .get("time_zone")
+            .and_then(|zone| zone.as_str())
+            .map(|zone| zone.to_string());
+
+        // The digest is not copied automatically unless it has been turned on
+        let digest = value
+            .get("digest")
+            .and_then(|digest| serde_json::from_value(digest.clone()).ok())
+            .unwrap_or_default();
+
+        // No hooks run until the user sets some up
+        let hooks = value
+            .get("hooks")
+            .and_then(|hooks| serde_json::from_value(hooks.clone()).ok())
+            .unwrap_or_default();
+
+        // The bookmark endpoint stays closed until it is turned on
+        let bookmarks = value
+            .get("bookmarks")
+            .and_then(|bookmarks| serde_json::from_value(bookmarks.clone()).ok())
+            .unwrap_or_default();
+
+        // Issue links are not fetched until enrichment is turned on
+        let issues = value
+            .get("issues")
+            .and_then(|issues| serde_json::from_value(issues.clone()).ok())
+            .unwrap_or_default();
+
+        // No file is synced with GitHub until a sync is set up
+        let github_sync = value
+            .get("github_sync")
+            .and_then(|github_sync| serde_json::from_value(github_sync.clone()).ok())
+            .unwrap_or_default();
+
+        // Older settings kept the layout of existing lines, which is still the default
+        let format_conventions = value
+            .get("format_conventions")
+            .and_then(|conventions| serde_json::from_value(conventions.clone()).ok())
+            .unwrap_or_default();
+
+        // Nothing was ignored before ignored tags existed
+        let ignored_tags = value
+            .get("ignored_tags")
+            .and_then(|ignored_tags| serde_json::from_value(ignored_tags.clone()).ok())
+            .unwrap_or_default();
+
+        // Listings were in file order before the default sort existed
+        let default_sort = value
+            .get("default_sort")
+            .and_then(|rules| serde_json::from_value(rules.clone()).ok())
+            .unwrap_or_default();
+
+        // Every keyword and tag had generated styles before styles could be chosen
+        let status_styles = value
+            .get("status_styles")
+            .and_then(|styles| serde_json::from_value(styles.clone()).ok())
+            .unwrap_or_default();
+
+        // Create settings from migrated fields, using defaults for anything missing
         let migrated_settings = UserSettings {
             monitored_paths,
-            todo_keywords: TodoKeywords::default(),
+            todo_keywords,
             custom_properties,
+            custom_property_types,
             external_editor_command,
             table_columns,
+            parse_archive_files,
+            max_loaded_documents,
+            file_templates,
+            journal,
+            contacts_files,
+            html_sanitize,
+            favorites,
+            urgency_weights,
+            effort_presets,
+            planner,
+            work_schedule,
+            date_locale,
+            time_zone,
+            digest,
+            hooks,
+            bookmarks,
+            issues,
+            github_sync,
+            format_conventions,
+            ignored_tags,
+            default_sort,
+            status_styles,
         };
 
         Ok(migrated_settings)
@@ -1353,4 +1949,147 @@ mod tests {
             vec!["DONE", "CANCELLED"]
         );
     }
+
+    #[test]
+    fn test_archive_files_coverage() {
+        let mut settings = UserSettings::default();
+        settings
+            .monitored_paths
+            .push(MonitoredPath::directory("/notes".to_string()));
+
+        assert!(settings.is_file_covered("/notes/todo.org"));
+        assert!(!settings.is_file_covered("/notes/todo.org_archive"));
+
+        settings.set_parse_archive_files(true);
+        assert!(settings.is_file_covered("/notes/todo.org_archive"));
+    }
+
+    #[test]
+    fn test_settings_migration_keeps_todo_keywords() {
+        let manager = SettingsManager::new();
+
+        let old_settings_json = serde_json::json!({
+            "monitored_paths": [],
+            "todo_keywords": {
+                "active": ["NEXT"],
+                "closed": ["FINISHED"]
+            }
+        });
+
+        let migrated_settings = manager.migrate_settings(old_settings_json).unwrap();
+
+        assert_eq!(migrated_settings.todo_keywords.active, vec!["NEXT"]);
+        assert_eq!(migrated_settings.todo_keywords.closed, vec!["FINISHED"]);
+        assert!(!migrated_settings.parse_archive_files);
+        assert_eq!(migrated_settings.max_loaded_documents, None);
+    }
+
+    #[test]
+    fn test_custom_property_types() {
+        let mut settings = UserSettings::new();
+        settings.add_custom_property("Effort".to_string()).unwrap();
+
+        assert!(settings
+            .set_custom_property_type("Missing", PropertyType::Number)
+            .is_err());
+        settings
+            .set_custom_property_type("Effort", PropertyType::Duration)
+            .unwrap();
+        assert_eq!(
+            settings.get_custom_property_type("EFFORT"),
+            PropertyType::Duration
+        );
+
+        assert!(settings.validate_property_value("Effort", "1:30").is_ok());
+        assert!(matches!(
+            settings.validate_property_value("Effort", "later"),
+            Err(SettingsError::InvalidPropertyValue(_, _))
+        ));
+        assert!(settings
+            .validate_property_value("Other", "anything")
+            .is_ok());
+
+        // Renaming keeps the type, removing drops it
+        settings
+            .edit_custom_property(0, "Estimate".to_string())
+            .unwrap();
+        assert_eq!(
+            settings.get_custom_property_type("Estimate"),
+            PropertyType::Duration
+        );
+        settings.remove_custom_property(0).unwrap();
+        assert!(settings.custom_property_types.is_empty());
+    }
+
+    #[test]
+    fn test_file_templates() {
+        let mut settings = UserSettings::new();
+        let template = FileTemplate {
+            name: "Project".to_string(),
+            title: None,
+            filetags: vec!["work".to_string(), "project".to_string()],
+            category: Some("Projects".to_string()),
+            headlines: vec!["* Tasks".to_string(), "* Notes".to_string()],
+        };
+        settings.save_file_template(template.clone()).unwrap();
+        assert_eq!(
+            settings.get_file_template("Project").unwrap().render("launch"),
+            "#+TITLE: launch\n#+FILETAGS: :work:project:\n#+CATEGORY: Projects\n\n* Tasks\n* Notes\n"
+        );
+
+        // Saving under the same name replaces the template
+        settings
+            .save_file_template(FileTemplate {
+                title: Some("Plan".to_string()),
+                ..template
+            })
+            .unwrap();
+        assert_eq!(settings.file_templates.len(), 1);
+        assert!(settings
+            .get_file_template("Project")
+            .unwrap()
+            .render("launch")
+            .starts_with("#+TITLE: Plan\n"));
+
+        assert!(settings.remove_file_template("Project"));
+        assert!(settings.get_file_template("Project").is_none());
+    }
+
+    #[test]
+    fn test_favorites() {
+        let mut settings = UserSettings::new();
+        let favorite = Favorite {
+            document_id: "/notes/a.org".to_string(),
+            org_id: None,
+            outline_path: vec!["Projects".to_string(), "Website".to_string()],
+        };
+        assert!(settings.add_favorite(favorite.clone()));
+        assert!(!settings.add_favorite(favorite.clone()));
+        assert_eq!(settings.favorites.len(), 1);
+
+        assert!(settings.remove_favorite(&favorite));
+        assert!(!settings.remove_favorite(&favorite));
+        assert!(settings.favorites.is_empty());
+    }
+
+    #[test]
+    fn test_secrets_and_sections() {
+        let mut settings = UserSettings::new();
+        settings.issues.github_token = Some("ghp_secret".to_string());
+        settings.bookmarks.token = "bookmark-secret".to_string();
+        let exported = settings.without_secrets();
+        assert_eq!(exported.issues.github_token, None);
+        assert!(exported.bookmarks.token.is_empty());
+
+        let mut imported = exported.clone();
+        imported.keep_secrets(&settings);
+        assert_eq!(imported, settings);
+        assert!(imported.validate_sections().is_ok());
+
+        imported.issues.refresh_minutes = 0;
+        assert!(imported.validate_sections().is_err());
+        imported.issues.refresh_minutes = 30;
+        imported.effort_presets.push("soon".to_string());
+        assert!(imported.validate_sections().is_err());
+    }
 }
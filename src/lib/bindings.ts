@@ -63,6 +63,8 @@ async getAllDocuments() : Promise<Result<OrgDocument[], string>> {
 },
 /**
  * Get document by ID
+ * Opening a document reloads its body content if it was evicted, and evicts
+ * the least recently opened documents when a limit is configured
  */
 async getOrgDocumentById(documentId: string) : Promise<Result<OrgDocument | null, string>> {
     try {
@@ -73,519 +75,2931 @@ async getOrgDocumentById(documentId: string) : Promise<Result<OrgDocument | null
 }
 },
 /**
- * Get document display title by ID
+ * Get a headline's subtree as a virtual document, like narrowing in Emacs, for views
+ * and exports of part of a file
+ * The subtree is promoted to level 1 and inherits the headline's tags and category. Its
+ * headlines keep their ids, so edits to them apply to the real document.
  */
-async getOrgDocumentDisplayTitleById(documentId: string) : Promise<Result<string, string>> {
+async getSubtreeAsDocument(documentId: string, headlineId: string) : Promise<Result<OrgDocument | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_org_document_display_title_by_id", { documentId }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_subtree_as_document", { documentId, headlineId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get document file path by ID
+ * Get a document's metadata and headline counts without its content or outline
+ * Use `get_headline_children` to load the outline level by level
  */
-async getOrgDocumentPathById(documentId: string) : Promise<Result<string, string>> {
+async getDocumentSummary(documentId: string) : Promise<Result<DocumentSummary | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_org_document_path_by_id", { documentId }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_document_summary", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Load user settings
+ * Get word and character counts and reading time for a document and each of its subtrees
  */
-async loadUserSettings() : Promise<Result<UserSettings, string>> {
+async getDocumentStats(documentId: string) : Promise<Result<DocumentStats | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("load_user_settings") };
+    return { status: "ok", data: await TAURI_INVOKE("get_document_stats", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Save user settings
+ * Get a document's scheduled, deadline, closed and timestamped entries grouped by day,
+ * oldest first, for a per-project timeline
+ * Days are marked as working days or holidays from the work schedule
  */
-async saveUserSettings(settings: UserSettings) : Promise<Result<null, string>> {
+async getDocumentTimeline(documentId: string) : Promise<Result<TimelineDay[] | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("save_user_settings", { settings }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_document_timeline", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Add a monitored path to settings
+ * Lay out a month as calendar weeks starting on the configured first day of the week,
+ * with day names in the configured language
  */
-async addMonitoredPath(path: MonitoredPath) : Promise<Result<UserSettings, string>> {
+async getCalendarMonth(year: number, month: number) : Promise<Result<CalendarMonth, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("add_monitored_path", { path }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_calendar_month", { year, month }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Remove a monitored path from settings
+ * Get a page of a headline's children, or of the top-level headlines if `parent_id` is null
+ * Children are returned without content or nested headlines, with the values of the
+ * visible `property:` table columns. They are ordered by `sort`, or by the default sort
+ * from settings if it is null; an empty order keeps file order.
  */
-async removeMonitoredPath(path: string) : Promise<Result<UserSettings, string>> {
+async getHeadlineChildren(documentId: string, parentId: string | null, offset: number, limit: number, sort: SortRule[] | null) : Promise<Result<HeadlinePage | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("remove_monitored_path", { path }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_headline_children", { documentId, parentId, offset, limit, sort }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Update a monitored path in settings
+ * Get the values of the visible `property:` table columns for each headline, in the order
+ * given
+ * Values are inherited the way the outline's are; missing headlines get no values.
  */
-async updateMonitoredPath(oldPath: string, newPath: MonitoredPath) : Promise<Result<UserSettings, string>> {
+async getColumnValues(headlines: HeadlineRef[]) : Promise<Result<Partial<{ [key in string]: string }>[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_monitored_path", { oldPath, newPath }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_column_values", { headlines }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Set whether parsing is enabled for a monitored path
+ * Get the body text of a single headline
  */
-async setPathParseEnabled(path: string, parseEnabled: boolean) : Promise<Result<UserSettings, string>> {
+async getHeadlineContent(documentId: string, headlineId: string) : Promise<Result<string | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_path_parse_enabled", { path, parseEnabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_headline_content", { documentId, headlineId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Clear user settings
+ * Get recently opened or edited documents and headlines, most recent first
+ * Renamed documents are reported under their current ID and moved headlines under their
+ * current one. Forgotten documents and deleted headlines are left out.
  */
-async clearUserSettings() : Promise<Result<null, string>> {
+async getRecentItems(limit: number) : Promise<Result<RecentItem[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("clear_user_settings") };
+    return { status: "ok", data: await TAURI_INVOKE("get_recent_items", { limit }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Check if a file path is covered by current monitoring configuration
+ * Get the etag of every headline in a document, keyed by headline ID
+ * Lets the frontend re-render only the rows whose etag changed
  */
-async checkPathMonitoringStatus(filePath: string) : Promise<Result<boolean, string>> {
+async getDocumentEtags(documentId: string) : Promise<Result<Partial<{ [key in string]: string }> | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("check_path_monitoring_status", { filePath }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_document_etags", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get TODO keywords as TodoStatus objects for UI display
+ * Get document display title by ID
  */
-async getTodoKeywords() : Promise<Result<TodoStatus[], string>> {
+async getOrgDocumentDisplayTitleById(documentId: string) : Promise<Result<string, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_todo_keywords") };
+    return { status: "ok", data: await TAURI_INVOKE("get_org_document_display_title_by_id", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get current TODO keywords configuration from user settings
+ * Get document file path by ID
  */
-async getUserTodoKeywords() : Promise<Result<TodoKeywords, string>> {
+async getOrgDocumentPathById(documentId: string) : Promise<Result<string, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_user_todo_keywords") };
+    return { status: "ok", data: await TAURI_INVOKE("get_org_document_path_by_id", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Update TODO keywords in user settings
+ * Resolve a document ID that may have changed because its file was renamed
  */
-async updateTodoKeywords(todoKeywords: TodoKeywords) : Promise<Result<UserSettings, string>> {
+async resolveDocumentId(documentId: string) : Promise<Result<string | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_todo_keywords", { todoKeywords }) };
+    return { status: "ok", data: await TAURI_INVOKE("resolve_document_id", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Add active TODO keyword
+ * Drop a document whose file has gone missing from the repository
+ * Returns false if the document is unknown or its file still exists
  */
-async addActiveTodoKeyword(keyword: string) : Promise<Result<UserSettings, string>> {
+async forgetMissingDocument(documentId: string) : Promise<Result<boolean, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("add_active_todo_keyword", { keyword }) };
+    return { status: "ok", data: await TAURI_INVOKE("forget_missing_document", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Add closed TODO keyword
+ * Compare stored documents with the files on disk, e.g. after the machine slept
+ * With `repair`, changed files are reparsed and vanished files marked missing
  */
-async addClosedTodoKeyword(keyword: string) : Promise<Result<UserSettings, string>> {
+async verifyRepository(repair: boolean) : Promise<Result<VerificationReport, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("add_closed_todo_keyword", { keyword }) };
+    return { status: "ok", data: await TAURI_INVOKE("verify_repository", { repair }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Remove active TODO keyword by index
+ * List the conflict copies Dropbox and Syncthing left among the monitored files, each with
+ * a headline diff against the file it conflicts with
  */
-async removeActiveTodoKeyword(index: number) : Promise<Result<UserSettings, string>> {
+async getSyncConflicts() : Promise<Result<SyncConflict[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("remove_active_todo_keyword", { index }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_sync_conflicts") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Remove closed TODO keyword by index
+ * Resolve a sync conflict copy by merging its extra headlines into the original file, or
+ * by discarding it
+ * Returns the headlines that differ between both files after a merge. While there are
+ * any, the copy is kept so its edits are not lost; discarding always deletes it.
  */
-async removeClosedTodoKeyword(index: number) : Promise<Result<UserSettings, string>> {
+async resolveSyncConflict(conflictPath: string, resolution: ConflictResolution) : Promise<Result<HeadlineDiff[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("remove_closed_todo_keyword", { index }) };
+    return { status: "ok", data: await TAURI_INVOKE("resolve_sync_conflict", { conflictPath, resolution }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Edit active TODO keyword by index
+ * Get the headlines an edit of a document conflicts with, if one is waiting to be resolved
  */
-async editActiveTodoKeyword(index: number, newKeyword: string) : Promise<Result<UserSettings, string>> {
+async getWriteConflict(documentId: string) : Promise<Result<WriteConflict | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("edit_active_todo_keyword", { index, newKeyword }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_write_conflict", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Edit closed TODO keyword by index
+ * Drop an edit that conflicted with changes on disk, keeping the file as it is
  */
-async editClosedTodoKeyword(index: number, newKeyword: string) : Promise<Result<UserSettings, string>> {
+async discardWriteConflict(documentId: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("edit_closed_todo_keyword", { index, newKeyword }) };
+    return { status: "ok", data: await TAURI_INVOKE("discard_write_conflict", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Move active TODO keyword
+ * Write an edit that conflicted with changes on disk, merging it with the file as it is
+ * now and settling headlines both changed in favor of `side`
+ * If the file changed again in a way that conflicts with the edit, the new conflicts are
+ * reported instead and the edit stays pending.
  */
-async moveActiveTodoKeyword(index: number, direction: number) : Promise<Result<UserSettings, string>> {
+async resolveWriteConflict(documentId: string, side: MergeSide) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("move_active_todo_keyword", { index, direction }) };
+    return { status: "ok", data: await TAURI_INVOKE("resolve_write_conflict", { documentId, side }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Move closed TODO keyword
+ * Get the TODO keywords valid for a document: its own `#+TODO:` sequences
+ * merged with the keywords from settings
  */
-async moveClosedTodoKeyword(index: number, direction: number) : Promise<Result<UserSettings, string>> {
+async getDocumentTodoConfig(documentId: string) : Promise<Result<TodoConfiguration | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("move_closed_todo_keyword", { index, direction }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_document_todo_config", { documentId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Reset TODO keywords to defaults
+ * Get document and headline changes since a cursor from a previous call
+ * Start with cursor 0; if `complete` is false, refetch all documents
  */
-async resetTodoKeywordsToDefaults() : Promise<Result<UserSettings, string>> {
+async pollChanges(sinceCursor: number) : Promise<Result<ChangeFeed, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("reset_todo_keywords_to_defaults") };
+    return { status: "ok", data: await TAURI_INVOKE("poll_changes", { sinceCursor }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Reload all documents with updated TODO keywords settings
+ * Full-text search over headline titles and content, narrowed by property filters
+ * Terms such as `due:this-quarter`, `deadline:W23` or `scheduled:2024-06` keep headlines
+ * with that planning date in the range, and on their own list every such headline.
+ * Headlines with ignored tags are left out.
  */
-async reloadDocumentsWithSettings() : Promise<Result<string, string>> {
+async searchHeadlines(query: string, limit: number, filters: PropertyFilter[]) : Promise<Result<SearchHit[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("reload_documents_with_settings") };
+    return { status: "ok", data: await TAURI_INVOKE("search_headlines", { query, limit, filters }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get current custom headline properties from user settings
+ * Find a phrase within one document, returning match offsets per headline for highlighting
+ * Returns None if the document doesn't exist
  */
-async getCustomProperties() : Promise<Result<string[], string>> {
+async searchInDocument(documentId: string, query: string) : Promise<Result<HeadlineMatches[] | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_custom_properties") };
+    return { status: "ok", data: await TAURI_INVOKE("search_in_document", { documentId, query }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Add a custom headline property
+ * Rebuild the search index from all loaded documents in the background
+ * Returns the job ID; progress is reported through job-progress events
+ * A cancelled rebuild keeps the previous index
  */
-async addCustomProperty(property: string) : Promise<Result<string[], string>> {
+async rebuildSearchIndex() : Promise<Result<number, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("add_custom_property", { property }) };
+    return { status: "ok", data: await TAURI_INVOKE("rebuild_search_index") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Edit a custom headline property by index
+ * Cancel a running job started by a scan, search, or index rebuild
+ * Returns false if the job is unknown or has already finished
  */
-async editCustomProperty(index: number, newProperty: string) : Promise<Result<string[], string>> {
+async cancelJob(jobId: number) : Promise<Result<boolean, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("edit_custom_property", { index, newProperty }) };
+    return { status: "ok", data: await TAURI_INVOKE("cancel_job", { jobId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Remove a custom headline property by index
+ * Get the current state of a background job
  */
-async removeCustomProperty(index: number) : Promise<Result<string[], string>> {
+async getJob(jobId: number) : Promise<Result<JobInfo | null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("remove_custom_property", { index }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_job", { jobId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Move a custom headline property up/down in the list
+ * Get recent parse durations per file and repository sizes
  */
-async moveCustomProperty(index: number, direction: number) : Promise<Result<string[], string>> {
+async getPerfStats() : Promise<Result<PerfStats, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("move_custom_property", { index, direction }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_perf_stats") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Reset custom headline properties to empty
+ * Get the files that failed to parse, including files that crashed the parser
  */
-async resetCustomPropertiesToDefaults() : Promise<Result<string[], string>> {
+async getParseDiagnostics() : Promise<Result<ParseDiagnostic[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("reset_custom_properties_to_defaults") };
+    return { status: "ok", data: await TAURI_INVOKE("get_parse_diagnostics") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get the external editor command from user settings
+ * Limit how many recently opened documents keep their body content loaded
+ * Other documents keep their outline and reload content from disk when opened
  */
-async getExternalEditorCommand() : Promise<Result<string, string>> {
+async setMaxLoadedDocuments(limit: number | null) : Promise<Result<UserSettings, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_external_editor_command") };
+    return { status: "ok", data: await TAURI_INVOKE("set_max_loaded_documents", { limit }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Set the external editor command in user settings
+ * Get document and headline counts with an estimate of the memory they use
  */
-async setExternalEditorCommand(command: string) : Promise<Result<null, string>> {
+async getMemoryStats() : Promise<Result<MemoryStats, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_external_editor_command", { command }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_memory_stats") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Reset the external editor command to default in user settings
+ * List running and recently finished background jobs, newest first
  */
-async resetExternalEditorCommand() : Promise<Result<null, string>> {
+async listJobs() : Promise<Result<JobInfo[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("reset_external_editor_command") };
+    return { status: "ok", data: await TAURI_INVOKE("list_jobs") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Open a file in external editor using the configured command
+ * Search in the background, emitting the hits as a search-results event
+ * Returns the job ID; a cancelled search emits nothing. Queries take the same date terms
+ * as `search_headlines`. Headlines with ignored tags are left out.
  */
-async openFileInExternalEditor(filePath: string, line: number | null, column: number | null) : Promise<Result<null, string>> {
+async startSearch(query: string, limit: number, filters: PropertyFilter[]) : Promise<Result<number, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("open_file_in_external_editor", { filePath, line, column }) };
+    return { status: "ok", data: await TAURI_INVOKE("start_search", { query, limit, filters }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get table columns configuration
+ * Fuzzy-match document and headline titles for a jump-to dialog
  */
-async getTableColumns() : Promise<Result<TableColumnConfig[], string>> {
+async quickFind(query: string, limit: number) : Promise<Result<QuickFindResult[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_table_columns") };
+    return { status: "ok", data: await TAURI_INVOKE("quick_find", { query, limit }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get available table columns (built-in + custom properties)
+ * Get all tasks bucketed by category or agenda-group property
+ * Only tasks matching every property filter are included
  */
-async getAvailableTableColumns() : Promise<Result<string[], string>> {
+async getGroupedTasks(mode: GroupingMode, filters: PropertyFilter[]) : Promise<Result<HeadlineGroup[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_available_table_columns") };
+    return { status: "ok", data: await TAURI_INVOKE("get_grouped_tasks", { mode, filters }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Update table columns configuration
+ * Get all distinct values of a headline property, e.g. for a column filter dropdown
  */
-async updateTableColumns(tableColumns: TableColumnConfig[]) : Promise<Result<UserSettings, string>> {
+async getPropertyValues(property: string) : Promise<Result<PropertyValue[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_table_columns", { tableColumns }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_property_values", { property }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Add table column
+ * Get tasks closed within a date range, grouped by day and category
  */
-async addTableColumn(column: TableColumnConfig) : Promise<Result<UserSettings, string>> {
+async getCompletedTasks(range: DateRange) : Promise<Result<CompletedDay[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("add_table_column", { column }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_completed_tasks", { range }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Remove table column by index
+ * Turn a range expression such as `2024-W23`, `2024-06`, `Q3` or `this-quarter` into
+ * dates, for the range arguments of other commands
+ * Relative weeks start on the configured first day of the week
  */
-async removeTableColumn(index: number) : Promise<Result<UserSettings, string>> {
+async resolveDateRange(expression: string) : Promise<Result<DateRange, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("remove_table_column", { index }) };
+    return { status: "ok", data: await TAURI_INVOKE("resolve_date_range", { expression }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Set table column visibility
+ * Export the agenda for a date range as org-agenda style plain text or a printable HTML
+ * page, listing each day's scheduled, deadline and timestamped entries
+ * The range is an expression such as `2024-06-01..2024-06-15`, `W23`, `2024-06` or
+ * `this-quarter`, with relative weeks starting on the configured first day of the week.
+ * Repeating timestamps show on every day they repeat on, open tasks past their date are
+ * carried forward to today, and days and months are named in the date locale's language.
+ * Ranges longer than a year are rejected. Headlines with ignored tags are left out.
  */
-async setColumnVisibility(columnId: string, visible: boolean) : Promise<Result<UserSettings, string>> {
+async exportAgenda(range: string, format: AgendaFormat) : Promise<Result<string, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_column_visibility", { columnId, visible }) };
+    return { status: "ok", data: await TAURI_INVOKE("export_agenda", { range, format }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Reset table columns to defaults
+ * Render a digest of today's or this week's agenda with overdue tasks above it, as plain
+ * text or HTML to paste into a mail
  */
-async resetTableColumnsToDefaults() : Promise<Result<UserSettings, string>> {
+async getDigest(period: DigestPeriod, format: AgendaFormat) : Promise<Result<string, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("reset_table_columns_to_defaults") };
+    return { status: "ok", data: await TAURI_INVOKE("get_digest", { period, format }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
-}
-}
-
-/** user-defined events **/
-
-
-
-/** user-defined constants **/
-
-
-
-/** user-defined types **/
-
+},
 /**
- * Structure to represent a monitored path
+ * Update the digest period, format and morning auto-copy toggle
  */
-export type MonitoredPath = { 
+async setDigestConfig(config: DigestConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_digest_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * The path to monitor (file or directory)
+ * Update the shell commands run on task completion, capture and file changes, and how
+ * long they may run
  */
-path: string; 
+async setHookConfig(config: HookConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_hook_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Type of the path (file or directory)
+ * Update the issue tracker tokens and refresh interval
  */
-path_type: PathType; 
+async setIssueConfig(config: IssueConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_issue_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Whether this path should be parsed for org-mode content
+ * Fetch the title and status of every GitHub and Jira issue linked from a headline
+ * They show in the ISSUE_STATUS and ISSUE_TITLE properties and are emitted as an
+ * issues-updated event
  */
-parse_enabled: boolean }
+async refreshIssues() : Promise<Result<IssueInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("refresh_issues") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * OrgDatetime represents a date/time in an org-mode file
- * This is similar to Orgize's Datetime but designed to be owned and serializable
+ * Update the GitHub issue sync file, repositories and interval
+ * The file must be inside the monitored paths, since syncs write to it without asking.
  */
-export type OrgDatetime = { year: number; month: number; day: number; dayname: string; hour: number | null; minute: number | null }
+async setGithubSyncConfig(config: GitHubSyncConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_github_sync_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Basic org-mode document structure
+ * Sync the GitHub issue file: close issues whose headlines were given the closed
+ * keyword, then mirror the open issues assigned to the token's user
  */
-export type OrgDocument = { id: string; title: string; content: string; headlines: OrgHeadline[]; filetags: string[]; file_path: string; properties: Partial<{ [key in string]: string }>; category: string; etag: string; todo_config: TodoConfiguration | null }
+async syncGithubIssues() : Promise<Result<GitHubSyncSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_github_issues") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Basic headline structure
+ * Update how edited headlines lay out planning lines, drawers and tags
  */
-export type OrgHeadline = { id: string; document_id: string; title: OrgTitle; content: string; children: OrgHeadline[]; etag: string }
-export type OrgPlanning = { deadline: OrgTimestamp | null; scheduled: OrgTimestamp | null; closed: OrgTimestamp | null }
+async setFormatConventions(conventions: FormatConventions) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_format_conventions", { conventions }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * OrgTimestamp represents an org-mode timestamp
+ * Set the tags whose headlines are left out of the agenda, next actions and searches,
+ * with optional lists for each of those views
  */
-export type OrgTimestamp = { Active: { start: OrgDatetime; repeater: string | null; delay: string | null } } | { Inactive: { start: OrgDatetime; repeater: string | null; delay: string | null } } | { ActiveRange: { start: OrgDatetime; end: OrgDatetime; repeater: string | null; delay: string | null } } | { InactiveRange: { start: OrgDatetime; end: OrgDatetime; repeater: string | null; delay: string | null } } | { Diary: { value: string } }
+async setIgnoredTags(ignoredTags: IgnoredTags) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_ignored_tags", { ignoredTags }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Represents a headline title in org-mode
+ * Set the default order of listed headlines, used when a listing gives no order of its
+ * own; an empty list keeps file order
  */
-export type OrgTitle = { raw: string; level: number; priority: string | null; tags: string[]; todo_keyword: string | null; properties: Partial<{ [key in string]: string }>; planning: OrgPlanning | null }
+async setDefaultSort(sort: SortRule[]) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_default_sort", { sort }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Type of path being monitored
+ * Get per-day counts of completed tasks and clocked minutes for a year, for an activity heatmap
  */
-export type PathType = "File" | "Directory"
-export type StateType = "Active" | "Closed"
+async getActivityHeatmap(year: number) : Promise<Result<ActivityHeatmap, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_activity_heatmap", { year }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Configuration for table columns
+ * Report broken file links, unresolved id links and dangling internal targets
  */
-export type TableColumnConfig = { 
+async checkLinks() : Promise<Result<BrokenLink[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_links") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Column identifier (e.g. "status", "title", "property:Effort")
+ * Audit the repository for duplicated :ID: properties and same-file duplicate titles
  */
-id: string; 
+async auditDuplicates() : Promise<Result<DuplicateEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("audit_duplicates") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Whether the column is visible
+ * List org-drill and org-fc cards due today or earlier, most overdue first
+ * Cards that were never reviewed come last
  */
-visible: boolean; 
+async getDueCards() : Promise<Result<Card[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_due_cards") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Display order of the column
+ * Record a review of an org-drill card, rated 0 (blackout) to 5 (perfect)
+ * The card's DRILL_* properties and SCHEDULED date are updated as org-drill would
+ * Returns the card with its new due date
  */
-order: number }
-export type TodoConfiguration = { sequences: TodoSequence[]; default_sequence: string }
+async recordCardReview(documentId: string, headlineId: string, quality: number) : Promise<Result<Card, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("record_card_review", { documentId, headlineId, quality }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Configuration for TODO keywords
+ * Change a headline's TODO keyword, or clear it with None
+ * Closing a task that is still blocked by open subtasks, open BLOCKER/DEPENDS targets,
+ * earlier siblings in an ordered subtree or unchecked checkboxes is refused unless `force`
+ * Closing is logged as the file's `#+STARTUP:` options ask, with `note` as the closing
+ * note under `lognotedone`; reopening removes the CLOSED timestamp
+ * Keywords are those of the file's `#+TODO:` lines together with the ones from settings.
+ */
+async setTodoKeyword(documentId: string, headlineId: string, keyword: string | null, force: boolean, note: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_todo_keyword", { documentId, headlineId, keyword, force, note }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move a headline's TODO keyword to the next or previous one in its sequence, wrapping
+ * through no keyword, like Shift-right and Shift-left in Emacs
+ * Closing is checked and logged as by `set_todo_keyword`. Returns the new keyword.
+ */
+async cycleTodoState(documentId: string, headlineId: string, direction: CycleDirection) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cycle_todo_state", { documentId, headlineId, direction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run a single-key action on a headline, like org-mode's speed commands: cycle its TODO
+ * state, set its priority, schedule it for today or tomorrow, archive it or clock in
+ * Priorities must be within the file's `#+PRIORITIES:` range, A to C by default. Archived
+ * subtrees go to the end of `<file>_archive`. Clocking in is refused while the headline's
+ * clock is already running.
+ */
+async executeHeadlineAction(documentId: string, headlineId: string, action: HeadlineAction) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_headline_action", { documentId, headlineId, action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write a headline's Effort property, creating its drawer if needed
+ */
+async setEffort(documentId: string, headlineId: string, effort: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_effort", { documentId, headlineId, effort }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set a property on a headline, editing its `:PROPERTIES:` drawer in place or creating one
+ * The key is matched case-insensitively against existing properties; values of typed
+ * custom properties must parse as their type
+ */
+async setHeadlineProperty(documentId: string, headlineId: string, key: string, value: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_headline_property", { documentId, headlineId, key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a property from a headline, dropping its `:PROPERTIES:` drawer if it becomes empty
+ */
+async deleteHeadlineProperty(documentId: string, headlineId: string, key: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_headline_property", { documentId, headlineId, key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write a document back from an edited copy of its headline tree
+ * Parts of headlines that are unchanged are kept byte for byte and only the parts that
+ * changed are rewritten. Headlines left out are removed, and headlines with an empty id
+ * are written as new ones.
+ */
+async saveDocumentHeadlines(documentId: string, headlines: OrgHeadline[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_document_headlines", { documentId, headlines }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Defer a task until a day after today, hiding it and its subtasks from the agenda, next
+ * actions and urgent tasks until then through its DEFERRED property
+ * Without a day, the task comes back now.
+ */
+async deferTask(documentId: string, headlineId: string, untilDate: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("defer_task", { documentId, headlineId, untilDate }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add an unchecked item to a headline's checklist, before the item at `position` or at
+ * the end, creating the list if needed and updating statistics cookies
+ */
+async addChecklistItem(documentId: string, headlineId: string, text: string, position: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_checklist_item", { documentId, headlineId, text, position }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a top-level item, with anything nested under it, from a headline's checklist
+ */
+async removeChecklistItem(documentId: string, headlineId: string, index: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_checklist_item", { documentId, headlineId, index }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reorder a headline's children by title, TODO state, priority, deadline or a property,
+ * moving each child's whole subtree
+ */
+async sortChildren(documentId: string, headlineId: string, key: SortKey) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sort_children", { documentId, headlineId, key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Split a headline into siblings at the items of its first plain list or at its
+ * paragraphs, each new headline titled by the first line of its text
+ */
+async splitHeadline(documentId: string, headlineId: string, mode: SplitMode) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("split_headline", { documentId, headlineId, mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Merge sibling headlines into the first of them in file order, keeping the others'
+ * titles and bodies as its text and their children as its own
+ */
+async mergeHeadlines(documentId: string, headlineIds: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("merge_headlines", { documentId, headlineIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set a document's `#+TITLE:` line, or remove it if the title is empty
+ */
+async setDocumentTitle(documentId: string, title: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_document_title", { documentId, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace a document's `#+FILETAGS:` with the given tags, or remove it if there are none
+ */
+async setFiletags(documentId: string, tags: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_filetags", { documentId, tags }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set a document's `#+CATEGORY:` line, or remove it if the category is empty
+ */
+async setDocumentCategory(documentId: string, category: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_document_category", { documentId, category }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Load user settings
+ */
+async loadUserSettings() : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_user_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save user settings
+ */
+async saveUserSettings(settings: UserSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_user_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a new org file from a file template and start monitoring it
+ * Without a template name the file only gets a #+TITLE from its file name
+ * Returns the id of the new document
+ */
+async createOrgFile(path: string, templateName: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_org_file", { path, templateName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List pinned headlines in the order they were added
+ * Favorites whose headline no longer exists are removed from the settings once every
+ * monitored file has been loaded
+ */
+async listFavorites() : Promise<Result<FavoriteHeadline[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_favorites") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pin a headline, returning the updated favorites
+ */
+async addFavorite(documentId: string, headlineId: string) : Promise<Result<FavoriteHeadline[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_favorite", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Unpin a headline, returning the updated favorites
+ */
+async removeFavorite(documentId: string, headlineId: string) : Promise<Result<FavoriteHeadline[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_favorite", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add a file template, replacing any template with the same name
+ */
+async saveFileTemplate(template: FileTemplate) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_file_template", { template }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a file template by name
+ */
+async removeFileTemplate(name: string) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_file_template", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Find the journal entry for a date (YYYY-MM-DD), creating its file and headline if needed
+ */
+async openOrCreateJournalEntry(date: string) : Promise<Result<JournalEntry, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_or_create_journal_entry", { date }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set where journal files are kept and how they are named
+ */
+async setJournalConfig(config: JournalConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_journal_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the source blocks of a document with their header arguments and results
+ * Blocks are only read, never executed
+ */
+async getSourceBlocks(documentId: string) : Promise<Result<SourceBlock[] | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_source_blocks", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List every file that source blocks are tangled into, across documents
+ */
+async listTangleTargets() : Promise<Result<TangleTarget[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_tangle_targets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List links to image files in a document, to show them inline via the org-image protocol
+ */
+async getImageLinks(documentId: string) : Promise<Result<ImageLink[] | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_image_links", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a headline's title and body as styled text runs, so markup is rendered instead of shown raw
+ */
+async getHeadlineRichText(documentId: string, headlineId: string) : Promise<Result<RichText | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_headline_rich_text", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get a headline's body as sanitized HTML, filtered through the configured allowlist
+ */
+async getHeadlineHtml(documentId: string, headlineId: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_headline_html", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Share a read-only view of a document on a temporary localhost server, for showing
+ * meeting notes during a call; the page reloads itself when the document changes
+ * Replaces any document already being shared
+ */
+async startSharing(documentId: string) : Promise<Result<ShareSession, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_sharing", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop sharing the shared document, if any
+ */
+async stopSharing() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_sharing") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Load the plugins in the plugin directory, replacing the ones loaded before
+ * Returns every plugin found, with the error for any that failed to load
+ */
+async reloadPlugins() : Promise<Result<PluginInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reload_plugins") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the loaded plugins and the functions they provide
+ */
+async listPlugins() : Promise<Result<PluginInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_plugins") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Find the headlines of all documents that a plugin query function matches
+ */
+async runPluginQuery(pluginId: string, functionName: string, argument: string) : Promise<Result<HeadlineRef[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_plugin_query", { pluginId, functionName, argument }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export a document with a plugin exporter function
+ */
+async exportWithPlugin(pluginId: string, functionName: string, documentId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_with_plugin", { pluginId, functionName, documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Find LaTeX fragments and entities in the body of a headline, or of the whole document
+ * Span offsets are relative to that content
+ */
+async getLatexSpans(documentId: string, headlineId: string | null) : Promise<Result<LatexSpan[] | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_latex_spans", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the bibliographies of a document and its citations, located by headline
+ */
+async getDocumentCitations(documentId: string) : Promise<Result<DocumentCitations | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_document_citations", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List every cited key across documents, with how often and where it is cited
+ */
+async listCitedKeys() : Promise<Result<CitedKey[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_cited_keys") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List contacts from the contacts files, filtered by an optional search query
+ */
+async listContacts(query: string | null) : Promise<Result<Contact[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_contacts", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set which files are searched for contacts
+ */
+async setContactsFiles(files: string[]) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_contacts_files", { files }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update the allowlist used when rendering org content as HTML
+ */
+async setHtmlSanitizeConfig(config: HtmlSanitizeConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_html_sanitize_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Open a mailto: or tel: link with the system handler
+ */
+async openContactLink(url: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_contact_link", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * File captured text at a capture target
+ * Returns the id of the document the text was added to
+ */
+async capture(target: CaptureTarget, text: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("capture", { target, text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import a Readwise JSON or CSV highlights export into a notes file
+ * Each highlight is filed under a headline for its book or article; highlights already
+ * in the file are skipped by id. The notes file is created if it does not exist.
+ */
+async importHighlights(exportPath: string, notesPath: string) : Promise<Result<HighlightImportSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_highlights", { exportPath, notesPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * File a browser bookmark in the bookmark inbox as a `TODO Read:` entry tagged `reading`
+ * Returns the id of the inbox document
+ */
+async captureBookmark(bookmark: Bookmark) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("capture_bookmark", { bookmark }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Propose tasks from the notes under a meeting headline: its unchecked checkboxes and
+ * lines like `TODO: ...` or `Action: ...`
+ */
+async extractActionItems(documentId: string, headlineId: string) : Promise<Result<ActionItem[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("extract_action_items", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * File accepted action items as TODO headlines at a capture target, each linking back to
+ * the meeting headline they came from
+ * Returns the id of the document the tasks were added to
+ */
+async acceptActionItems(documentId: string, headlineId: string, items: string[], target: CaptureTarget) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("accept_action_items", { documentId, headlineId, items, target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update the bookmark endpoint and restart it
+ * A token is generated the first time the endpoint is turned on
+ */
+async setBookmarkConfig(config: BookmarkConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_bookmark_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the `javascript:` bookmarklet that sends the current browser page to the bookmark
+ * endpoint
+ */
+async getBookmarklet() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_bookmarklet") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move a headline and its subtree to a capture target, such as a date tree
+ * Returns the id of the document the headline was moved to
+ */
+async refileHeadline(documentId: string, headlineId: string, target: CaptureTarget) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("refile_headline", { documentId, headlineId, target }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add a monitored path to settings
+ */
+async addMonitoredPath(path: MonitoredPath) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_monitored_path", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a monitored path from settings
+ */
+async removeMonitoredPath(path: string) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_monitored_path", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update a monitored path in settings
+ */
+async updateMonitoredPath(oldPath: string, newPath: MonitoredPath) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_monitored_path", { oldPath, newPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set whether parsing is enabled for a monitored path
+ */
+async setPathParseEnabled(path: string, parseEnabled: boolean) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_path_parse_enabled", { path, parseEnabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Clear user settings
+ */
+async clearUserSettings() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_user_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write settings, favorites, reminders, recent items and the trash to one file, for
+ * moving to another machine
+ * 
+ * The issue tracker and bookmark tokens are left out.
+ */
+async exportAppState(path: string) : Promise<Result<AppStateSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_app_state", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace settings, favorites, reminders, recent items and the trash with those in a file
+ * written by `export_app_state`, then restart monitoring with the imported paths
+ * 
+ * Each section is checked as its setter would; tokens missing from the file are kept.
+ */
+async importAppState(path: string) : Promise<Result<AppStateSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_app_state", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check if a file path is covered by current monitoring configuration
+ */
+async checkPathMonitoringStatus(filePath: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_path_monitoring_status", { filePath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get TODO keywords as TodoStatus objects for UI display
+ */
+async getTodoKeywords() : Promise<Result<TodoStatus[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_todo_keywords") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the color and style of each of the given tags, keyed by tag
+ * Colors come from the same palette as TODO keywords, so they are the same on every run;
+ * the light and dark colors and icons chosen in the settings take precedence in the style.
+ */
+async getTagColors(tags: string[]) : Promise<Result<Partial<{ [key in string]: TagColor }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tag_colors", { tags }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Choose the light and dark colors and icons of TODO keywords and tags, replacing the
+ * generated ones; documents are re-parsed so their statuses carry the new styles
+ */
+async setStatusStyles(styles: StatusStyles) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_status_styles", { styles }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get current TODO keywords configuration from user settings
+ */
+async getUserTodoKeywords() : Promise<Result<TodoKeywords, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_user_todo_keywords") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update TODO keywords in user settings
+ */
+async updateTodoKeywords(todoKeywords: TodoKeywords) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_todo_keywords", { todoKeywords }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add active TODO keyword
+ */
+async addActiveTodoKeyword(keyword: string) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_active_todo_keyword", { keyword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add closed TODO keyword
+ */
+async addClosedTodoKeyword(keyword: string) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_closed_todo_keyword", { keyword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove active TODO keyword by index
+ */
+async removeActiveTodoKeyword(index: number) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_active_todo_keyword", { index }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove closed TODO keyword by index
+ */
+async removeClosedTodoKeyword(index: number) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_closed_todo_keyword", { index }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Edit active TODO keyword by index
+ */
+async editActiveTodoKeyword(index: number, newKeyword: string) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("edit_active_todo_keyword", { index, newKeyword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List headlines in monitored files that use a TODO keyword, before renaming it
+ */
+async previewTodoKeywordRename(oldKeyword: string) : Promise<Result<KeywordOccurrence[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_todo_keyword_rename", { oldKeyword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Rewrite a renamed TODO keyword in all monitored files, on headlines and in the files'
+ * `#+TODO:` lines
+ * Returns the number of files that were changed
+ */
+async applyTodoKeywordRename(oldKeyword: string, newKeyword: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_todo_keyword_rename", { oldKeyword, newKeyword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Rename a tag on every headline and FILETAGS line in monitored files
+ */
+async renameTag(oldTag: string, newTag: string) : Promise<Result<TagRewriteSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rename_tag", { oldTag, newTag }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace several tags with one on every headline and FILETAGS line in monitored files
+ * Headlines that had more than one of the tags keep a single copy
+ */
+async mergeTags(tags: string[], into: string) : Promise<Result<TagRewriteSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("merge_tags", { tags, into }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the category keywords and properties in monitored files that set a category,
+ * before renaming it
+ */
+async previewCategoryRename(oldCategory: string) : Promise<Result<CategoryOccurrence[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_category_rename", { oldCategory }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Rewrite a category in the `#+CATEGORY:` keywords and `:CATEGORY:` properties of all
+ * monitored files
+ * Returns the number of files that were changed
+ */
+async renameCategory(oldCategory: string, newCategory: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rename_category", { oldCategory, newCategory }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Preview a find-and-replace across monitored files, listing every match per file
+ */
+async previewFindReplace(query: ReplaceQuery) : Promise<Result<FileReplacePreview[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_find_replace", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace every match of a find-and-replace in monitored files, except in the excluded
+ * documents, and reparse the changed files
+ */
+async applyFindReplace(query: ReplaceQuery, excludedDocumentIds: string[]) : Promise<Result<ReplaceSummary, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_find_replace", { query, excludedDocumentIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set a property on every headline matching all filters, optionally only within one
+ * headline's subtree, e.g. to set `agenda-group` on a whole project
+ * Drawers are created where missing, and values of typed custom properties must parse as
+ * their type. Returns the number of headlines changed
+ */
+async bulkSetProperty(filters: PropertyFilter[], root: HeadlineRef | null, key: string, value: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("bulk_set_property", { filters, root, key, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the SCHEDULED and DEADLINE dates of open tasks that a bulk shift by `delta`
+ * (e.g. `+1d`) would move
+ */
+async previewShiftDates(filter: ShiftFilter, delta: string) : Promise<Result<DateShift[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_shift_dates", { filter, delta }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move the SCHEDULED and DEADLINE dates of matching open tasks by `delta`, except on the
+ * excluded headlines, e.g. `+1d` for everything overdue
+ * Returns the number of dates moved
+ */
+async shiftDates(filter: ShiftFilter, delta: string, excluded: HeadlineRef[]) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("shift_dates", { filter, delta, excluded }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Propose SCHEDULED dates for unscheduled open tasks from their effort, the load already
+ * scheduled on each working day of the work schedule
+ * Returns nothing while the planner is turned off
+ */
+async getScheduleSuggestions() : Promise<Result<ScheduleSuggestion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_schedule_suggestions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the accepted schedule suggestions as SCHEDULED dates
+ * Returns the number of headlines scheduled
+ */
+async acceptScheduleSuggestions(suggestions: ScheduleSuggestion[]) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("accept_schedule_suggestions", { suggestions }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a headline and its subtree from its file, keeping the text in the trash
+ */
+async deleteHeadline(documentId: string, headlineId: string) : Promise<Result<TrashEntry, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_headline", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List deleted headlines, most recent first
+ */
+async listTrash() : Promise<Result<TrashEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_trash") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Put a deleted headline back into its original file and remove it from the trash
+ */
+async restoreFromTrash(entryId: string) : Promise<Result<TrashEntry, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_from_trash", { entryId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Copy all monitored files into a new snapshot, as a safety net before bulk changes
+ */
+async createSnapshot(label: string) : Promise<Result<Snapshot, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_snapshot", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List snapshots, most recent first
+ */
+async listSnapshots() : Promise<Result<Snapshot[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_snapshots") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the files in a snapshot back as they were when it was taken
+ * The current files are snapshotted first so the restore can be undone. Files created
+ * since the snapshot are left alone. Returns the paths of the files that were rewritten.
+ */
+async restoreSnapshot(snapshotId: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_snapshot", { snapshotId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a snapshot and its file copies
+ */
+async deleteSnapshot(snapshotId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_snapshot", { snapshotId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Attach a reminder to a headline without changing its org file
+ * `remind_at` is an RFC 3339 date and time, or one without an offset in the assumed time zone
+ */
+async setReminder(documentId: string, headlineId: string, remindAt: string, message: string) : Promise<Result<Reminder, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_reminder", { documentId, headlineId, remindAt, message }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a reminder, whether or not it has fired
+ */
+async removeReminder(reminderId: string) : Promise<Result<Reminder, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_reminder", { reminderId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List reminders that have not fired yet, soonest first
+ */
+async getUpcomingReminders() : Promise<Result<Reminder[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_upcoming_reminders") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Edit closed TODO keyword by index
+ */
+async editClosedTodoKeyword(index: number, newKeyword: string) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("edit_closed_todo_keyword", { index, newKeyword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move active TODO keyword
+ */
+async moveActiveTodoKeyword(index: number, direction: number) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_active_todo_keyword", { index, direction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move closed TODO keyword
+ */
+async moveClosedTodoKeyword(index: number, direction: number) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_closed_todo_keyword", { index, direction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset TODO keywords to defaults
+ */
+async resetTodoKeywordsToDefaults() : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_todo_keywords_to_defaults") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reload all documents with updated TODO keywords settings
+ */
+async reloadDocumentsWithSettings() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reload_documents_with_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get current custom headline properties from user settings
+ */
+async getCustomProperties() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_custom_properties") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add a custom headline property
+ */
+async addCustomProperty(property: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_custom_property", { property }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Edit a custom headline property by index
+ */
+async editCustomProperty(index: number, newProperty: string) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("edit_custom_property", { index, newProperty }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a custom headline property by index
+ */
+async removeCustomProperty(index: number) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_custom_property", { index }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move a custom headline property up/down in the list
+ */
+async moveCustomProperty(index: number, direction: number) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_custom_property", { index, direction }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Declare the type of a custom property (string, number, duration or date)
+ */
+async setCustomPropertyType(property: string, propertyType: PropertyType) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_custom_property_type", { property, propertyType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sort headlines by a property according to its declared type
+ * Headlines without a valid value are placed last
+ */
+async sortHeadlinesByProperty(headlines: HeadlineRef[], property: string, descending: boolean) : Promise<Result<HeadlineRef[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sort_headlines_by_property", { headlines, property, descending }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Total a number or duration property over headlines, formatted for display
+ * Returns None for string and date properties
+ */
+async sumProperty(headlines: HeadlineRef[], property: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sum_property", { headlines, property }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the urgency of each headline, in the order given
+ * Headlines that are not open tasks have no urgency
+ */
+async getUrgencyScores(headlines: HeadlineRef[]) : Promise<Result<(number | null)[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_urgency_scores", { headlines }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sort headlines by urgency, for the urgency table column
+ * Headlines that are not open tasks are placed last
+ */
+async sortHeadlinesByUrgency(headlines: HeadlineRef[], descending: boolean) : Promise<Result<HeadlineRef[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sort_headlines_by_urgency", { headlines, descending }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the most urgent open tasks across all documents
+ */
+async getMostUrgentTasks(limit: number) : Promise<Result<UrgentTask[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_most_urgent_tasks", { limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Suggest next actions for a GTD panel: the most urgent open tasks that are not
+ * waiting or blocked, limited to the given `@context` tags (all contexts when empty)
+ * Headlines with ignored tags are left out.
+ */
+async getNextActions(contextTags: string[], limit: number) : Promise<Result<UrgentTask[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_next_actions", { contextTags, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the tickler: tasks deferred past today, soonest to come back first, then
+ * headlines tagged `:someday:`
+ */
+async getTickler() : Promise<Result<TicklerItem[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tickler") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get task dependencies from BLOCKER/DEPENDS properties across all documents
+ */
+async getDependencyGraph() : Promise<Result<DependencyGraph, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dependency_graph") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get all projects (headlines with child tasks) with their status, for a projects review
+ */
+async getProjects() : Promise<Result<Project[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_projects") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get open projects that have no next action
+ */
+async getStuckProjects() : Promise<Result<Project[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_stuck_projects") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set how deadline, priority, age and effort add up to a task's urgency
+ */
+async setUrgencyWeights(weights: UrgencyWeights) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_urgency_weights", { weights }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the effort values to offer for a headline: its file's or an ancestor's
+ * `Effort_ALL`, falling back to the presets in settings
+ */
+async getEffortPresets(documentId: string | null, headlineId: string | null) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_effort_presets", { documentId, headlineId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the effort presets offered when a file doesn't define `Effort_ALL`
+ */
+async setEffortPresets(presets: string[]) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_effort_presets", { presets }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set how much the auto-scheduling planner plans per day and how far ahead, or turn it
+ * on or off
+ */
+async setPlannerConfig(config: PlannerConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_planner_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the workdays, working hours and holidays used by the planner and the timeline
+ * `holiday_feed` is the path of an .ics calendar whose events count as holidays
+ */
+async setWorkSchedule(schedule: WorkSchedule) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_work_schedule", { schedule }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the first day of the week and the language day names are written in, e.g. `de`
+ * for `<2024-05-15 Mi>`
+ */
+async setDateLocale(locale: DateLocale) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_date_locale", { locale }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the IANA time zone names the time zone can be set to
+ */
+async getTimeZones() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_time_zones") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the IANA time zone org timestamps are assumed to be in, e.g. `America/New_York`,
+ * or null to use the system time zone
+ * This decides which day is today for overdue and due-today checks
+ */
+async setTimeZone(timeZone: string | null) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_time_zone", { timeZone }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset custom headline properties to empty
+ */
+async resetCustomPropertiesToDefaults() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_custom_properties_to_defaults") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the external editor command from user settings
+ */
+async getExternalEditorCommand() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_external_editor_command") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the external editor command in user settings
+ */
+async setExternalEditorCommand(command: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_external_editor_command", { command }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable or disable parsing of *.org_archive files and reload documents
+ */
+async setParseArchiveFiles(enabled: boolean) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_parse_archive_files", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset the external editor command to default in user settings
+ */
+async resetExternalEditorCommand() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_external_editor_command") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Open a file in external editor using the configured command
+ */
+async openFileInExternalEditor(filePath: string, line: number | null, column: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_file_in_external_editor", { filePath, line, column }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Show a document's file in Finder, Explorer, or the platform file manager
+ */
+async revealDocumentInFileManager(documentId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reveal_document_in_file_manager", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Open a document's file with the application the OS associates with org files
+ */
+async openWithSystemDefault(documentId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_with_system_default", { documentId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get table columns configuration
+ */
+async getTableColumns() : Promise<Result<TableColumnConfig[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_table_columns") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get available table columns (built-in + custom properties)
+ */
+async getAvailableTableColumns() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_available_table_columns") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Update table columns configuration
+ */
+async updateTableColumns(tableColumns: TableColumnConfig[]) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_table_columns", { tableColumns }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add table column
+ */
+async addTableColumn(column: TableColumnConfig) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_table_column", { column }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove table column by index
+ */
+async removeTableColumn(index: number) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_table_column", { index }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set table column visibility
+ */
+async setColumnVisibility(columnId: string, visible: boolean) : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_column_visibility", { columnId, visible }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reset table columns to defaults
+ */
+async resetTableColumnsToDefaults() : Promise<Result<UserSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_table_columns_to_defaults") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * A task proposed from the notes of a meeting
+ */
+export type ActionItem = { text: string; line: number; source: ActionSource }
+/**
+ * How an action item was spotted in meeting notes
+ */
+export type ActionSource = "Checkbox" | "Phrase"
+/**
+ * Daily activity over a year, for a contribution-graph style heatmap
+ */
+export type ActivityHeatmap = { year: number; days: HeatmapDay[]; max_completed: number; max_clocked_minutes: number; total_completed: number; total_clocked_minutes: number }
+/**
+ * How an exported agenda is laid out
+ */
+export type AgendaFormat = "Text" | "Html"
+/**
+ * What an app state bundle held
+ */
+export type AppStateSummary = { exported_at: string; reminders: number; recent_items: number; trash_entries: number }
+/**
+ * Output stored under a `#+RESULTS:` line
+ */
+export type BlockResults = { line: number; text: string }
+/**
+ * A page sent from the browser to read later
+ */
+export type Bookmark = { url: string; title: string; selection: string | null }
+/**
+ * The local endpoint that a bookmarklet sends pages to
+ */
+export type BookmarkConfig = { enabled: boolean; port: number; token: string; inbox: string | null }
+/**
+ * A link that does not resolve, with its location
+ */
+export type BrokenLink = { document_id: string; headline_id: string | null; line: number; link: OrgLink; problem: LinkProblem }
+/**
+ * Dates of a month laid out in calendar weeks
+ */
+export type CalendarMonth = { year: number; month: number; day_names: string[]; weeks: string[][] }
+/**
+ * Where captured text is filed
+ */
+export type CaptureTarget = 
+/**
+ * Appended as a top-level headline at the end of a file
+ */
+{ File: { path: string } } | 
+/**
+ * Filed under the journal entry of a day (YYYY-MM-DD), today if None
+ */
+{ Journal: { date: string | null } } | 
+/**
+ * A flashcard headline
+ */
+export type Card = { document_id: string; headline_id: string; title: string; kind: CardKind; due: string | null; total_repeats: number; failure_count: number }
+/**
+ * Which flashcard package a card belongs to
+ */
+export type CardKind = "Drill" | "Fc"
+/**
+ * Filed under the Year → Month → Day headlines of a date tree, today if None
+ */
+{ Datetree: { path: string; date: string | null } }
+/**
+ * A `#+CATEGORY:` keyword or `:CATEGORY:` property line setting a given category
+ */
+export type CategoryOccurrence = { document_id: string; file_path: string; headline_id: string | null; line: number; text: string }
+export type ChangeFeed = { cursor: number; changes: OrgUpdateInfo[]; complete: boolean }
+export type ChangeKind = "Added" | "Updated" | "Removed" | "Missing" | { Renamed: { from: string } }
+/**
+ * An org-cite citation such as `[cite/t:@doe2020; @roe2021]`
+ */
+export type Citation = { style: string | null; keys: string[] }
+/**
+ * A citation with its location
+ */
+export type CitationRef = { document_id: string; headline_id: string | null; line: number; citation: Citation }
+/**
+ * A cited key with the documents citing it
+ */
+export type CitedKey = { key: string; count: number; document_ids: string[] }
+/**
+ * Completed tasks sharing a category on a given day
+ */
+export type CompletedCategory = { category: string; tasks: CompletedTask[] }
+/**
+ * All tasks completed on a single day (YYYY-MM-DD)
+ */
+export type CompletedDay = { date: string; categories: CompletedCategory[] }
+/**
+ * A task that was closed, with the time it was closed
+ */
+export type CompletedTask = { document_id: string; headline_id: string; title: string; keyword: string; category: string; closed: OrgDatetime }
+/**
+ * What to do with a conflict copy
+ */
+export type ConflictResolution = "Merge" | "Discard"
+/**
+ * A headline with contact properties, in the style of org-contacts
+ */
+export type Contact = { document_id: string; headline_id: string; name: string; emails: string[]; phones: string[]; address: string | null; birthday: string | null; tags: string[] }
+/**
+ * Which way to cycle a headline's TODO keyword, like Shift-right and Shift-left in Emacs
+ */
+export type CycleDirection = "Next" | "Previous"
+/**
+ * Where weeks start and which language new timestamps name their days in
+ */
+export type DateLocale = { week_start: number; language: string }
+/**
+ * Inclusive date range expressed as ISO8601 dates (YYYY-MM-DD)
+ */
+export type DateRange = { start: string; end: string }
+/**
+ * One date a bulk shift will move, for previewing
+ */
+export type DateShift = { document_id: string; headline_id: string; title: string; field: PlanningField; from: string; to: string }
+/**
+ * `blocked` cannot be done before `blocker`
+ */
+export type DependencyEdge = { blocker: HeadlineRef; blocked: HeadlineRef; kind: DependencyKind }
+/**
+ * Dependencies between headlines across all documents, for visualization
+ */
+export type DependencyGraph = { nodes: DependencyNode[]; edges: DependencyEdge[]; unresolved: UnresolvedDependency[] }
+/**
+ * Where a dependency comes from
+ */
+export type DependencyKind = "Property" | "Ordered"
+/**
+ * A headline taking part in a dependency
+ */
+export type DependencyNode = { document_id: string; headline_id: string; title: string; todo_keyword: string | null; open: boolean; blocked: boolean }
+/**
+ * The morning deadline digest
+ */
+export type DigestConfig = { auto_copy: boolean; period: DigestPeriod; format: AgendaFormat }
+/**
+ * How many days a digest's agenda covers
+ */
+export type DigestPeriod = "Daily" | "Weekly"
+/**
+ * Bibliography files and citations of one document
+ */
+export type DocumentCitations = { bibliographies: string[]; citations: CitationRef[] }
+/**
+ * Per-file opt-outs from a document's `#+ORG_X:` lines
+ * Unknown flags are ignored, so files stay readable by older versions.
+ */
+export type DocumentFlags = { no_index: boolean; no_agenda: boolean; read_only: boolean }
+/**
+ * Counts for a whole document and each of its subtrees
+ */
+export type DocumentStats = { document_id: string; total: TextStats; subtrees: SubtreeStats[] }
+/**
+ * Document metadata without its content or headlines
+ * Lets the frontend show a large document before loading its outline
+ */
+export type DocumentSummary = { id: string; title: string; file_path: string; filetags: string[]; properties: Partial<{ [key in string]: string }>; keywords: Partial<{ [key in string]: string }>; startup: StartupOptions; flags: DocumentFlags; category: string; etag: string; todo_config: TodoConfiguration | null; tag_styles: Partial<{ [key in string]: StatusStyle }>; archived: boolean; missing: boolean; headline_count: number; top_level_count: number }
+/**
+ * How a document differs from its file on disk
+ */
+export type DriftKind = "Changed" | "Missing" | "Reappeared" | "Unreadable"
+/**
+ * A document whose stored state has drifted from disk
+ */
+export type DriftedDocument = { document_id: string; file_path: string; kind: DriftKind; repaired: boolean }
+/**
+ * A value shared by more than one headline
+ */
+export type DuplicateEntry = { kind: DuplicateKind; value: string; locations: HeadlineLocation[] }
+/**
+ * Kind of duplication found by the audit
+ */
+export type DuplicateKind = "Id" | "Title"
+/**
+ * A pinned headline, stored in settings
+ * Positional headline IDs shift when headlines are added above, so a favorite is
+ * identified by the org `:ID:` property when set, otherwise by its outline path
+ */
+export type Favorite = { document_id: string; org_id: string | null; outline_path: string[] }
+/**
+ * A favorite resolved against the current document
+ */
+export type FavoriteHeadline = { document_id: string; headline_id: string; title: string; todo_keyword: string | null; outline_path: string[] }
+/**
+ * Matches in one file, before replacing
+ */
+export type FileReplacePreview = { document_id: string; file_path: string; matches: ReplaceMatch[] }
+/**
+ * Template used when creating a new org file
+ */
+export type FileTemplate = { 
+/**
+ * Unique template name
+ */
+name: string; 
+/**
+ * Value for #+TITLE; None uses the file name
+ */
+title: string | null; 
+/**
+ * Tags for #+FILETAGS
+ */
+filetags: string[]; 
+/**
+ * Value for #+CATEGORY
+ */
+category: string | null; 
+/**
+ * Starter headlines, written as given (e.g. "* Tasks")
+ */
+headlines: string[] }
+/**
+ * Layout rules for headlines written back to files, so that files also edited in
+ * mobile apps such as Orgzly and beorg don't churn in diffs
+ */
+export type FormatConventions = { metadata_indent: MetadataIndent; tags_column: number }
+/**
+ * Two-way sync of the issues assigned to the GitHub user with an org file
+ * Requests use the GitHub token of the issue settings.
+ */
+export type GitHubSyncConfig = { enabled: boolean; file: string | null; repositories: GitHubSyncRepository[]; interval_minutes: number }
+/**
+ * A repository whose assigned issues are synced, and the keywords issue states map to
+ */
+export type GitHubSyncRepository = { repository: string; open_keyword: string; closed_keyword: string; close_on_github: boolean }
+/**
+ * What a sync changed
+ */
+export type GitHubSyncSummary = { added: number; updated: number; closed_on_github: number }
+/**
+ * How tasks are bucketed in a grouped listing
+ */
+export type GroupingMode = 
+/**
+ * Group by effective category (inherited CATEGORY property or document category)
+ */
+"Category" | 
+/**
+ * Group by the inherited `agenda-group` property
+ */
+"AgendaGroup"
+/**
+ * An action on a headline bound to a single key, like org-mode's speed commands
+ */
+export type HeadlineAction = { CycleState: CycleDirection } | { SetPriority: string | null } | "ScheduleToday" | "ScheduleTomorrow" | "Archive" | "ClockIn"
+/**
+ * How a headline differs between a file and its conflict copy
+ */
+export type HeadlineChange = "Added" | "Removed" | "Changed"
+/**
+ * A headline whose section differs, with both sides for a side-by-side view
+ */
+export type HeadlineDiff = { outline: string[]; change: HeadlineChange; original: string | null; conflict: string | null }
+/**
+ * A named bucket of headlines
+ */
+export type HeadlineGroup = { 
+/**
+ * Group name, or None for tasks that have no value for the grouping key
+ */
+name: string | null; headlines: HeadlineRef[] }
+/**
+ * Where a duplicated headline lives
+ */
+export type HeadlineLocation = { document_id: string; file_path: string; headline_id: string; line: number }
+/**
+ * Matches within one headline's title and content
+ */
+export type HeadlineMatches = { headline_id: string; title: MatchSpan[]; content: MatchSpan[] }
+/**
+ * One page of a headline's children
+ */
+export type HeadlinePage = { items: HeadlineSummary[]; total: number }
+/**
+ * Reference to a headline within a document
+ */
+export type HeadlineRef = { document_id: string; headline_id: string }
+/**
+ * A headline without its content or children, for lazy outline loading
+ */
+export type HeadlineSummary = { id: string; document_id: string; title: OrgTitle; etag: string; child_count: number; content_length: number; rollup: Rollup | null; columns: Partial<{ [key in string]: string }> }
+/**
+ * Activity on one day
+ */
+export type HeatmapDay = { date: string; completed: number; clocked_minutes: number }
+/**
+ * What an import filed
+ */
+export type HighlightImportSummary = { imported: number; duplicates: number; sources: number }
+/**
+ * A day off
+ */
+export type Holiday = { date: string; name: string }
+/**
+ * Shell commands to run on app events
+ * Commands get the event's details as `ORGX_*` environment variables, never spliced
+ * into the command line
+ */
+export type HookConfig = { on_task_done: string | null; on_capture: string | null; on_file_changed: string | null; timeout_seconds: number }
+/**
+ * What rendered HTML may contain, so untrusted org files can be shown safely
+ */
+export type HtmlSanitizeConfig = { 
+/**
+ * Schemes allowed in link targets; links with other schemes are shown as text
+ */
+allowed_link_schemes: string[]; 
+/**
+ * Include `#+BEGIN_EXPORT html` blocks, filtered through `allowed_tags`
+ */
+allow_raw_html: boolean; 
+/**
+ * Tags kept in raw HTML; all others are dropped
+ */
+allowed_tags: string[] }
+/**
+ * Tags whose headlines, and everything below them, are left out of views
+ * A view with its own list uses that instead of `tags`; an empty list shows everything.
+ */
+export type IgnoredTags = { tags: string[]; agenda: string[] | null; next_actions: string[] | null; search: string[] | null }
+/**
+ * A `file:` link to an image, to be shown inline
+ */
+export type ImageLink = { document_id: string; headline_id: string | null; line: number; path: string; description: string | null; mime_type: string; exists: boolean }
+/**
+ * Access to the issue trackers whose links are enriched
+ */
+export type IssueConfig = { enabled: boolean; github_token: string | null; jira_base_url: string | null; jira_email: string | null; jira_token: string | null; refresh_minutes: number }
+/**
+ * The fetched state of an issue
+ */
+export type IssueInfo = { provider: IssueProvider; key: string; url: string; title: string; status: string }
+/**
+ * Where an issue is tracked
+ */
+export type IssueProvider = "GitHub" | "Jira"
+/**
+ * Snapshot of a background job, as reported to the frontend
+ */
+export type JobInfo = { id: number; kind: JobKind; status: JobStatus; done: number; total: number; percent: number; result: string | null; error: string | null }
+/**
+ * What a background job is doing
+ */
+export type JobKind = "Scan" | "IndexBuild" | "Search" | "Sync" | "Export"
+/**
+ * Lifecycle state of a background job
+ */
+export type JobStatus = "Running" | "Completed" | "Failed" | "Cancelled"
+/**
+ * Where journal entries are kept, in the style of org-journal
+ */
+export type JournalConfig = { directory: string | null; period: JournalPeriod; file_format: string; heading_format: string }
+/**
+ * A located or newly created journal entry
+ */
+export type JournalEntry = { document_id: string; headline_id: string; file_path: string; created: boolean }
+/**
+ * How much time one journal file covers
+ */
+export type JournalPeriod = "Daily" | "Weekly"
+/**
+ * A headline line that starts with a given TODO keyword
+ */
+export type KeywordOccurrence = { document_id: string; file_path: string; headline_id: string | null; line: number; text: string }
+/**
+ * Kind of a LaTeX span
+ */
+export type LatexKind = "InlineMath" | "DisplayMath" | "Entity"
+/**
+ * LaTeX markup found in org text, to be rendered instead of shown raw
+ * Offsets are in UTF-16 code units so they can be used on JavaScript strings
+ */
+export type LatexSpan = { start: number; end: number; kind: LatexKind; latex: string; unicode: string | null }
+/**
+ * Kind of an org link, derived from its target
+ */
+export type LinkKind = "File" | "Id" | "CustomId" | "Heading" | "Target" | "External"
+/**
+ * Why a link could not be resolved
+ */
+export type LinkProblem = "MissingFile" | "UnresolvedId" | "DanglingTarget"
+/**
+ * What is recorded when a task is closed, from `logdone`, `lognotedone` and `nologdone`
+ */
+export type LogDone = "Nothing" | "Time" | "Note"
+/**
+ * A match as byte offsets into the searched text
+ */
+export type MatchSpan = { start: number; end: number }
+/**
+ * Approximate memory held by the repository
+ */
+export type MemoryStats = { documents: number; headlines: number; evicted_documents: number; interned_strings: number; estimated_bytes: number }
+/**
+ * A headline that the edit and the file on disk both changed differently
+ * Sections are None where the headline does not exist.
+ */
+export type MergeConflict = { outline: string[]; base: string | null; local: string | null; disk: string | null }
+/**
+ * Which version wins where both changed a headline
+ */
+export type MergeSide = "Local" | "Disk"
+/**
+ * Where a headline's planning line and drawers start
+ */
+export type MetadataIndent = "Keep" | "Flush" | "Headline"
+/**
+ * Structure to represent a monitored path
+ */
+export type MonitoredPath = { 
+/**
+ * The path to monitor (file or directory)
+ */
+path: string; 
+/**
+ * Type of the path (file or directory)
+ */
+path_type: PathType; 
+/**
+ * Whether this path should be parsed for org-mode content
+ */
+parse_enabled: boolean }
+/**
+ * OrgDatetime represents a date/time in an org-mode file
+ * This is similar to Orgize's Datetime but designed to be owned and serializable
+ */
+export type OrgDatetime = { year: number; month: number; day: number; dayname: string; hour: number | null; minute: number | null }
+/**
+ * Basic org-mode document structure
+ */
+export type OrgDocument = { id: string; title: string; content: string; headlines: OrgHeadline[]; filetags: string[]; file_path: string; properties: Partial<{ [key in string]: string }>; keywords: Partial<{ [key in string]: string }>; startup: StartupOptions; flags: DocumentFlags; constants: Partial<{ [key in string]: string }>; category: string; etag: string; todo_config: TodoConfiguration | null; tag_styles: Partial<{ [key in string]: StatusStyle }>; archived: boolean; missing: boolean }
+/**
+ * Basic headline structure
+ */
+export type OrgHeadline = { id: string; document_id: string; title: OrgTitle; content: string; children: OrgHeadline[]; etag: string }
+/**
+ * A bracket link found in org text
+ */
+export type OrgLink = { target: string; description: string | null; kind: LinkKind }
+export type OrgPlanning = { deadline: OrgTimestamp | null; scheduled: OrgTimestamp | null; closed: OrgTimestamp | null }
+/**
+ * OrgTimestamp represents an org-mode timestamp
+ */
+export type OrgTimestamp = { Active: { start: OrgDatetime; repeater: string | null; delay: string | null } } | { Inactive: { start: OrgDatetime; repeater: string | null; delay: string | null } } | { ActiveRange: { start: OrgDatetime; end: OrgDatetime; repeater: string | null; delay: string | null } } | { InactiveRange: { start: OrgDatetime; end: OrgDatetime; repeater: string | null; delay: string | null } } | { Diary: { value: string } }
+/**
+ * Represents a headline title in org-mode
+ */
+export type OrgTitle = { raw: string; level: number; priority: string | null; tags: string[]; todo_keyword: string | null; properties: Partial<{ [key in string]: string }>; planning: OrgPlanning | null }
+export type OrgUpdateInfo = { sequence: number; kind: ChangeKind; document_id: string; updated_headlines: string[]; deleted_headlines: string[]; new_headlines: string[]; timestamp: string }
+/**
+ * A file that could not be parsed during the last attempt
+ */
+export type ParseDiagnostic = { file_path: string; message: string; panicked: boolean }
+/**
+ * How long parsing one file took
+ */
+export type ParseTiming = { file_path: string; micros: number; bytes: number; headlines: number }
+/**
+ * Type of path being monitored
+ */
+export type PathType = "File" | "Directory"
+/**
+ * Recent parse durations and repository sizes, for spotting slow files and regressions
+ */
+export type PerfStats = { documents: number; headlines: number; content_bytes: number; indexed_documents: number; average_parse_micros: number; recent_parses: ParseTiming[] }
+/**
+ * How much unscheduled work the planner spreads over each working day, and how far ahead
+ */
+export type PlannerConfig = { enabled: boolean; daily_minutes: number; horizon_days: number; default_effort_minutes: number }
+/**
+ * A planning line timestamp that a bulk shift can move
+ */
+export type PlanningField = "Scheduled" | "Deadline"
+/**
+ * A function a plugin registers
+ */
+export type PluginFunction = { kind: PluginFunctionKind; name: string; description: string }
+/**
+ * What a plugin function is used for
+ */
+export type PluginFunctionKind = "Query" | "Exporter" | "CaptureProcessor"
+/**
+ * A plugin found in the plugin directory
+ */
+export type PluginInfo = { id: string; name: string; version: string; file_path: string; functions: PluginFunction[]; error: string | null }
+/**
+ * A headline with child tasks
+ */
+export type Project = { document_id: string; headline_id: string; title: string; todo_keyword: string | null; status: ProjectStatus; open_tasks: number; done_tasks: number; next_actions: number }
+/**
+ * Where a project stands in a GTD weekly review
+ */
+export type ProjectStatus = "Active" | "Stuck" | "Done"
+/**
+ * Restrict a listing to headlines whose (inherited) property has one of the values
+ * An empty value list only requires the property to be set
+ */
+export type PropertyFilter = { property: string; values: string[] }
+/**
+ * Declared type of a custom property, controlling parsing, sorting and totals
+ */
+export type PropertyType = "String" | "Number" | "Duration" | "Date"
+/**
+ * A distinct property value and the number of headlines that set it
+ */
+export type PropertyValue = { value: string; count: number }
+/**
+ * A fuzzy match of a document or headline title
+ */
+export type QuickFindResult = { document_id: string; headline_id: string | null; title: string; score: number; positions: number[] }
+/**
+ * How a recent item was last accessed
+ */
+export type RecentAccess = "Opened" | "Edited"
+/**
+ * A document or headline the user opened or edited, most recent first
+ * Positional headline IDs shift when headlines are added above, so a headline is found
+ * again by its `:ID:` property or outline path, as favorites are
+ */
+export type RecentItem = { document_id: string; headline_id: string | null; title: string; access: RecentAccess; access_count: number; accessed_at: string; org_id: string | null; outline_path: string[] }
+/**
+ * A nudge attached to a headline, kept outside the org file
+ * Positional headline IDs shift when headlines are added above, so the headline is found
+ * again by its `:ID:` property or outline path, as favorites are
+ */
+export type Reminder = { id: string; document_id: string; headline_id: string | null; title: string; remind_at: string; message: string; fired: boolean; org_id: string | null; outline_path: string[] }
+/**
+ * One match and what it would be replaced with
+ */
+export type ReplaceMatch = { line: number; line_text: string; matched: string; replacement: string }
+/**
+ * What to find and what to replace it with
+ */
+export type ReplaceQuery = { pattern: string; replacement: string; regex: boolean; case_sensitive: boolean }
+/**
+ * Outcome of a find-and-replace across files
+ */
+export type ReplaceSummary = { files_changed: number; replacements: number }
+/**
+ * Headline title and body as styled runs, with the body split into paragraphs
+ */
+export type RichText = { title: TextRun[]; paragraphs: TextRun[][] }
+/**
+ * Progress of a headline derived from the tasks below it
+ */
+export type Rollup = { total_tasks: number; done_tasks: number; percent_done: number; remaining_effort_minutes: number; earliest_deadline: string | null }
+/**
+ * What a run of text represents
+ */
+export type RunKind = "Text" | "Link" | "Latex"
+/**
+ * Emphasis applied to a run of text
+ */
+export type RunStyle = "Bold" | "Italic" | "Underline" | "Verbatim" | "Code" | "Strike"
+/**
+ * A proposed SCHEDULED date for an unscheduled task
+ */
+export type ScheduleSuggestion = { document_id: string; headline_id: string; title: string; date: string; effort_minutes: number }
+/**
+ * A headline matching a full-text query
+ */
+export type SearchHit = { document_id: string; headline_id: string; score: number }
+/**
+ * Where a shared document can be viewed
+ */
+export type ShareSession = { document_id: string; url: string }
+/**
+ * Which open tasks and dates a bulk shift applies to
+ */
+export type ShiftFilter = { properties: PropertyFilter[]; fields: PlanningField[]; overdue_only: boolean }
+/**
+ * Copies of all monitored files taken at one moment
+ */
+export type Snapshot = { id: string; label: string; created_at: string; files: string[] }
+/**
+ * What to sort a headline's children by, like the keys of org-sort
+ */
+export type SortKey = "Title" | "Todo" | "Priority" | "Deadline" | { Property: string }
+/**
+ * One key of a listing order, like "priority, then deadline"
+ */
+export type SortRule = { key: SortKey; descending: boolean }
+/**
+ * A `#+BEGIN_SRC` block with its header arguments, never executed
+ */
+export type SourceBlock = { document_id: string; headline_id: string | null; line: number; name: string | null; language: string | null; header_args: Partial<{ [key in string]: string }>; body: string; tangle: string | null; results: BlockResults | null }
+/**
+ * Where a headline's body is split into new headlines
+ */
+export type SplitMode = "ListItems" | "Paragraphs"
+/**
+ * Options from a file's `#+STARTUP:` lines
+ * Later options override earlier ones and unknown options are ignored, as in org-mode
+ */
+export type StartupOptions = { log_done: LogDone; log_into_drawer: boolean; visibility: StartupVisibility | null; ordered: boolean; align: boolean | null }
+/**
+ * Initial outline visibility from `overview`, `content`, `showall` and `showeverything`
+ */
+export type StartupVisibility = "Overview" | "Content" | "ShowAll" | "ShowEverything"
+export type StateType = "Active" | "Closed"
+/**
+ * How a TODO keyword or tag is shown in the light and the dark theme
+ */
+export type StatusStyle = { light: string; dark: string; icon: string | null }
+/**
+ * Styles chosen for TODO keywords and tags, replacing the generated ones
+ */
+export type StatusStyles = { keywords: StyleOverride[]; tags: StyleOverride[] }
+/**
+ * A style chosen for a TODO keyword or tag; fields left None keep the generated ones
+ */
+export type StyleOverride = { name: string; light: string | null; dark: string | null; icon: string | null }
+/**
+ * Counts for a headline and everything below it
+ */
+export type SubtreeStats = { headline_id: string; title: string; level: number; stats: TextStats }
+/**
+ * A conflict copy left by a file sync service next to the file it conflicts with
+ */
+export type SyncConflict = { conflict_path: string; original_path: string; original_exists: boolean; diff: HeadlineDiff[] }
+/**
+ * Configuration for table columns
+ */
+export type TableColumnConfig = { 
+/**
+ * Column identifier (e.g. "status", "title", "property:Effort")
+ */
+id: string; 
+/**
+ * Whether the column is visible
+ */
+visible: boolean; 
+/**
+ * Display order of the column
+ */
+order: number }
+/**
+ * Outcome of renaming or merging tags across monitored files
+ */
+export type TagColor = { color: string; style: StatusStyle }
+export type TagRewriteSummary = { files_changed: number; headlines_changed: number }
+/**
+ * A file that source blocks are tangled into
+ */
+export type TangleTarget = { path: string; document_ids: string[]; block_count: number; languages: string[] }
+/**
+ * A piece of text with uniform styling
+ */
+export type TextRun = { text: string; styles: RunStyle[]; kind: RunKind; target: string | null }
+/**
+ * Word, character and reading time counts for a span of prose
+ */
+export type TextStats = { words: number; characters: number; reading_minutes: number }
+/**
+ * A headline in the tickler: deferred to a later day or parked as someday/maybe
+ */
+export type TicklerItem = { document_id: string; headline_id: string; title: string; todo_keyword: string | null; deferred_until: string | null; someday: boolean }
+/**
+ * Timeline entries falling on one day
+ */
+export type TimelineDay = { date: string; entries: TimelineEntry[]; working_day: boolean; holiday: string | null }
+/**
+ * A headline at one point in time
+ */
+export type TimelineEntry = { headline_id: string; title: string; todo_keyword: string | null; kind: TimelineKind; time: string | null }
+/**
+ * What put an entry on the timeline
+ */
+export type TimelineKind = "Scheduled" | "Deadline" | "Closed" | "Timestamp"
+export type TodoConfiguration = { sequences: TodoSequence[]; default_sequence: string }
+/**
+ * Configuration for TODO keywords
  */
 export type TodoKeywords = { 
 /**
@@ -597,7 +3011,24 @@ active: string[];
  */
 closed: string[] }
 export type TodoSequence = { name: string; statuses: TodoStatus[] }
-export type TodoStatus = { keyword: string; state_type: StateType; order: number; color: string | null }
+export type TodoStatus = { keyword: string; state_type: StateType; order: number; color: string | null; style: StatusStyle }
+/**
+ * Text removed from an org file, kept so it can be restored
+ */
+export type TrashEntry = { id: string; file_path: string; title: string; line: number; text: string; deleted_at: string }
+/**
+ * A reference that does not match any `:ID:` in the loaded documents
+ */
+export type UnresolvedDependency = { document_id: string; headline_id: string; id: string }
+/**
+ * How much each factor adds to a task's urgency
+ * Each factor is scaled to 0..1 before weighting, so a weight is the most that factor can add
+ */
+export type UrgencyWeights = { deadline: number; priority: number; age: number; effort: number }
+/**
+ * An open task with its urgency score
+ */
+export type UrgentTask = { document_id: string; headline_id: string; title: string; todo_keyword: string; tags: string[]; urgency: number }
 /**
  * Main user settings structure
  */
@@ -614,6 +3045,10 @@ todo_keywords: TodoKeywords;
  * Custom headline properties
  */
 custom_properties: string[]; 
+/**
+ * Declared types of custom properties; untyped properties are strings
+ */
+custom_property_types: Partial<{ [key in string]: PropertyType }>; 
 /**
  * Command to open files in an external editor
  */
@@ -621,7 +3056,108 @@ external_editor_command: string;
 /**
  * Table column configuration
  */
-table_columns: TableColumnConfig[] }
+table_columns: TableColumnConfig[]; 
+/**
+ * Whether to parse *.org_archive files as read-only archived documents
+ */
+parse_archive_files: boolean; 
+/**
+ * Keep body content for at most this many recently opened documents; None keeps all
+ */
+max_loaded_documents: number | null; 
+/**
+ * Templates offered when creating a new org file
+ */
+file_templates: FileTemplate[]; 
+/**
+ * Journal directory and file layout
+ */
+journal: JournalConfig; 
+/**
+ * Files holding org-contacts entries; empty searches all documents
+ */
+contacts_files: string[]; 
+/**
+ * What org content rendered as HTML may contain
+ */
+html_sanitize: HtmlSanitizeConfig; 
+/**
+ * Headlines pinned to the top of the UI
+ */
+favorites: Favorite[]; 
+/**
+ * How deadline, priority, age and effort add up to a task's urgency
+ */
+urgency_weights: UrgencyWeights; 
+/**
+ * Effort values offered for one-click assignment, unless a file sets `Effort_ALL`
+ */
+effort_presets: string[]; 
+/**
+ * Daily load and horizon of the optional auto-scheduling planner
+ */
+planner: PlannerConfig; 
+/**
+ * Workdays, working hours and holidays, for the planner and the timeline
+ */
+work_schedule: WorkSchedule; 
+/**
+ * First day of the week and the language of day names in new timestamps
+ */
+date_locale: DateLocale; 
+/**
+ * IANA time zone org timestamps are in, e.g. `Europe/Berlin`; None for the system's
+ */
+time_zone: string | null; 
+/**
+ * Period, format and morning auto-copy of the deadline digest
+ */
+digest: DigestConfig; 
+/**
+ * Shell commands run when a task is done, text is captured or a file changes
+ */
+hooks: HookConfig; 
+/**
+ * Local endpoint the browser bookmarklet files read-later entries through
+ */
+bookmarks: BookmarkConfig; 
+/**
+ * Tokens and refresh interval for fetching the state of linked GitHub and Jira issues
+ */
+issues: IssueConfig; 
+/**
+ * Repositories whose assigned GitHub issues are mirrored to an org file, and how often
+ */
+github_sync: GitHubSyncConfig; 
+/**
+ * How edited headlines lay out planning lines, drawers and tags
+ */
+format_conventions: FormatConventions; 
+/**
+ * Tags whose headlines are left out of the agenda, next actions and searches
+ */
+ignored_tags: IgnoredTags; 
+/**
+ * Order of listed headlines when the caller gives none; empty keeps file order
+ */
+default_sort: SortRule[]; 
+/**
+ * Light and dark colors and icons chosen for TODO keywords and tags
+ */
+status_styles: StatusStyles }
+/**
+ * Result of comparing the repository against the files on disk
+ */
+export type VerificationReport = { checked: number; drifted: DriftedDocument[] }
+/**
+ * When the user works, for the planner and day views
+ * Weekend days and holidays are not working days
+ */
+export type WorkSchedule = { workdays: number[]; day_start: string; day_end: string; holidays: Holiday[]; holiday_feed: string | null }
+/**
+ * An edit to a file that changed on disk, waiting for the conflicts to be resolved
+ */
+export type WriteConflict = { document_id: string; file_path: string; conflicts: MergeConflict[] }
 
 /** tauri-specta globals **/
 
@@ -14,9 +14,15 @@ vi.mock('$lib/bindings', () => ({
       filetags: [],
       file_path: '/path/to/test.org',
       properties: {},
+      keywords: {},
+      startup: { log_done: 'Nothing', log_into_drawer: false, visibility: null, ordered: false, align: null },
+      flags: { no_index: false, no_agenda: false, read_only: false },
+      constants: {},
       category: 'Test',
       etag: '123',
-      todo_config: null
+      todo_config: null,
+      archived: false,
+      missing: false
     })
   }
 }));
@@ -45,9 +51,15 @@ describe('OrgDocument Component', () => {
       filetags: [],
       file_path: '/path/to/test.org',
       properties: {},
+      keywords: {},
+      startup: { log_done: 'Nothing', log_into_drawer: false, visibility: null, ordered: false, align: null },
+      flags: { no_index: false, no_agenda: false, read_only: false },
+      constants: {},
       category: '',
       etag: '123',
-      todo_config: null
+      todo_config: null,
+      archived: false,
+      missing: false
     };
 
     render(OrgDocument, {
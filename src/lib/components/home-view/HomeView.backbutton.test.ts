@@ -69,9 +69,15 @@ const mockDocument = {
   filetags: [],
   file_path: "/test/path.org",
   properties: {},
+  keywords: {},
+  startup: { log_done: "Nothing", log_into_drawer: false, visibility: null, ordered: false, align: null },
+  flags: { no_index: false, no_agenda: false, read_only: false },
+  constants: {},
   category: "test",
   etag: "doc-etag",
   todo_config: null,
+  archived: false,
+  missing: false,
 };
 
 describe("HomeView Breadcrumb Home Navigation", () => {
@@ -76,9 +76,15 @@ describe("HomeView Component", () => {
       filetags: ["test", "doc"],
       file_path: "/path/to/test.org",
       properties: { AUTHOR: "Test User", CREATED: "2023-01-01" },
+      keywords: {},
+      startup: { log_done: "Nothing", log_into_drawer: false, visibility: null, ordered: false, align: null },
+      flags: { no_index: false, no_agenda: false, read_only: false },
+      constants: {},
       category: "Test",
       etag: "test-etag",
       todo_config: null,
+      archived: false,
+      missing: false,
     };
 
     // Mock headlines
@@ -130,9 +130,15 @@ const mockDocument: OrgDocument = {
   filetags: [],
   file_path: "/test/path.org",
   properties: {},
+  keywords: {},
+  startup: { log_done: "Nothing", log_into_drawer: false, visibility: null, ordered: false, align: null },
+  flags: { no_index: false, no_agenda: false, read_only: false },
+  constants: {},
   category: "test",
   etag: "doc-etag",
   todo_config: null,
+  archived: false,
+  missing: false,
 };
 
 // Mock document with hierarchical structure to test Issue #36 fix
@@ -227,9 +233,15 @@ const mockHierarchicalDocument: OrgDocument = {
   filetags: [],
   file_path: "/test/hierarchical.org",
   properties: {},
+  keywords: {},
+  startup: { log_done: "Nothing", log_into_drawer: false, visibility: null, ordered: false, align: null },
+  flags: { no_index: false, no_agenda: false, read_only: false },
+  constants: {},
   category: "test",
   etag: "doc-hierarchical-etag",
   todo_config: null,
+  archived: false,
+  missing: false,
 };
 
 describe("ListView Store", () => {
@@ -850,9 +862,15 @@ describe("ListView Store", () => {
       filetags: [],
       file_path: "/test/tags.org",
       properties: {},
+      keywords: {},
+      startup: { log_done: "Nothing", log_into_drawer: false, visibility: null, ordered: false, align: null },
+      flags: { no_index: false, no_agenda: false, read_only: false },
+      constants: {},
       category: "test",
       etag: "doc-etag-tags",
       todo_config: null,
+      archived: false,
+      missing: false,
     };
 
     beforeEach(() => {
@@ -132,14 +132,19 @@ export const documentMap = derived(
   ($docs) => new Map($docs.map((doc) => [doc.id, doc])),
 );
 
-export const allHeadlines = derived(documents, ($docs) =>
+// Documents whose files disappeared keep their entry but contribute no headlines
+const presentDocuments = derived(documents, ($docs) =>
+  $docs.filter((doc) => !doc.missing),
+);
+
+export const allHeadlines = derived(presentDocuments, ($docs) =>
   $docs.flatMap((doc) =>
     flattenHeadlinesWithParent(doc.headlines).map(({ headline }) => headline),
   ),
 );
 
 // Derived store for headlines with parent context
-export const headlinesWithParent = derived(documents, ($docs) =>
+export const headlinesWithParent = derived(presentDocuments, ($docs) =>
   $docs.flatMap((doc) => flattenHeadlinesWithParent(doc.headlines)),
 );
 
@@ -291,3 +291,9 @@ vi.mock("$lib/bindings", () => ({
       .mockResolvedValue({ status: "ok", data: null }),
   },
 }));
+
+// Mock Tauri event listeners used by components
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn().mockResolvedValue(() => {}),
+  emit: vi.fn(),
+}));